@@ -91,8 +91,10 @@ pub fn database_attr(attr: TokenStream, input: TokenStream) -> Result<TokenStrea
             /// Retrieves a connection of type `Self` from the `rocket`
             /// instance. Returns `Some` as long as `Self::fairing()` has been
             /// attached.
-            pub async fn get_one(__rocket: &::rocket::Rocket) -> Option<Self> {
-                <#pool>::get_one(&__rocket).await.map(Self)
+            pub async fn get_one<P: ::rocket::Phase>(
+                __rocket: &::rocket::Rocket<P>
+            ) -> Option<Self> {
+                <#pool>::get_one(__rocket).await.map(Self)
             }
 
             /// Runs the provided closure on a thread from a threadpool. The
@@ -0,0 +1,561 @@
+//! Automatic provisioning and renewal of TLS certificates via ACME.
+//!
+//! The [`Acme`] fairing obtains certificates from an ACME certificate
+//! authority, [Let's Encrypt] by default, for the configured domains. Issued
+//! certificates are persisted to a directory, renewed before they expire, and
+//! swapped into the running server without a restart.
+//!
+//! [Let's Encrypt]: https://letsencrypt.org
+//!
+//! # Enabling
+//!
+//! This module is only available when the `acme` feature is enabled. Enable it
+//! in `Cargo.toml` as follows:
+//!
+//! ```toml
+//! [dependencies.rocket_contrib]
+//! version = "0.5.0-dev"
+//! default-features = false
+//! features = ["acme"]
+//! ```
+//!
+//! # Configuration
+//!
+//! The fairing is configured via the `acme` configuration parameter. The TLS
+//! configuration must point to the certificate and key files in the ACME
+//! directory, `cert.pem` and `key.pem`, respectively:
+//!
+//! ```toml
+//! [global.acme]
+//! domains = ["example.com", "www.example.com"]
+//! email = "admin@example.com"
+//! directory = "acme"      # default: "acme"
+//! staging = false         # default: false
+//! http_port = 80          # default: 80
+//! renew_days = 30         # default: 30
+//!
+//! [global.tls]
+//! certs = "acme/cert.pem"
+//! key = "acme/key.pem"
+//! ```
+//!
+//! The parameters have the following meaning:
+//!
+//!   * `domains`: the domains to certify; the first is the primary domain
+//!   * `email`: the contact email address for the ACME account
+//!   * `directory`: the directory to persist the account, keys, and
+//!     certificates to; relative paths are relative to the configuration file
+//!   * `staging`: whether to use the Let's Encrypt staging environment, which
+//!     issues untrusted certificates but has far more generous rate limits
+//!   * `http_port`: the port on which to answer ACME challenges
+//!   * `renew_days`: renew certificates expiring in fewer than this many days
+//!
+//! # Challenges
+//!
+//! Domain ownership is proven via the ACME `HTTP-01` challenge: the certificate
+//! authority requests a token from the reserved route
+//! `/.well-known/acme-challenge/<token>` over plain HTTP on port 80. While an
+//! order is being validated, the fairing answers requests for this route, and
+//! only this route, on `http_port` using a minimal, standalone HTTP server. The
+//! server is independent of the Rocket instance: it doesn't run fairings, use
+//! the inherited socket, or signal readiness. The `TLS-ALPN-01` challenge is
+//! not supported.
+//!
+//! # Lifecycle
+//!
+//! On attach, if no valid certificate has been persisted, a certificate is
+//! ordered and written to the ACME directory before the server launches. The
+//! fairing fails to attach if ordering fails. Once launched, certificates are
+//! checked periodically and renewed `renew_days` before they expire. A renewed
+//! certificate is served to new connections immediately via
+//! [`TlsHandle::set_certificate()`]. A failed renewal is logged and retried.
+//!
+//! [`TlsHandle::set_certificate()`]: rocket::TlsHandle::set_certificate()
+//!
+//! # Example
+//!
+//! ```rust,no_run
+//! # #[macro_use] extern crate rocket;
+//! # extern crate rocket_contrib;
+//! use rocket_contrib::acme::Acme;
+//!
+//! #[launch]
+//! fn rocket() -> rocket::Rocket {
+//!     rocket::ignite().attach(Acme::fairing())
+//! }
+//! ```
+
+use std::collections::HashMap;
+use std::net::{IpAddr, SocketAddr};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, RwLock};
+use std::time::Duration;
+use std::{fs, io};
+
+use acme_lib::persist::FilePersist;
+use acme_lib::{create_p256_key, Certificate, Directory, DirectoryUrl};
+use serde::Deserialize;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::sync::oneshot;
+use tokio::task::JoinHandle;
+
+use rocket::{Rocket, Orbit};
+use rocket::fairing::{Fairing, Info, Kind};
+use rocket::figment::value::magic::RelativePathBuf;
+
+/// The interval at which certificates are checked for renewal.
+const CHECK_INTERVAL: Duration = Duration::from_secs(12 * 60 * 60);
+
+/// The name of the persisted PEM-encoded certificate chain.
+const CERT_FILE: &str = "cert.pem";
+
+/// The name of the persisted PEM-encoded private key.
+const KEY_FILE: &str = "key.pem";
+
+/// The path prefix of `HTTP-01` challenge requests.
+const CHALLENGE_PREFIX: &str = "/.well-known/acme-challenge/";
+
+/// The maximum size of the head of a challenge request.
+const MAX_REQUEST_HEAD: usize = 8 * 1024;
+
+/// How long a challenge request may take to arrive.
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Pending `HTTP-01` challenges: a map from token to key authorization.
+type Challenges = Arc<RwLock<HashMap<String, String>>>;
+
+/// The fairing that provisions and renews TLS certificates via ACME.
+///
+/// See the [module level documentation](self) for details.
+pub struct Acme(());
+
+impl Acme {
+    /// Returns a fairing that provisions and renews TLS certificates for the
+    /// domains in the `acme` configuration.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket_contrib::acme::Acme;
+    ///
+    /// let rocket = rocket::ignite().attach(Acme::fairing());
+    /// ```
+    pub fn fairing() -> Acme {
+        Acme(())
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct AcmeConfig {
+    domains: Vec<String>,
+    email: String,
+    #[serde(default = "AcmeConfig::default_directory")]
+    directory: RelativePathBuf,
+    #[serde(default)]
+    staging: bool,
+    #[serde(default = "AcmeConfig::default_http_port")]
+    http_port: u16,
+    #[serde(default = "AcmeConfig::default_renew_days")]
+    renew_days: i64,
+}
+
+impl AcmeConfig {
+    fn default_directory() -> RelativePathBuf {
+        PathBuf::from("acme").into()
+    }
+
+    fn default_http_port() -> u16 {
+        80
+    }
+
+    fn default_renew_days() -> i64 {
+        30
+    }
+}
+
+/// The state shared between the fairing's callbacks and the renewal task.
+#[derive(Clone)]
+struct AcmeState {
+    config: AcmeConfig,
+    address: IpAddr,
+    challenges: Challenges,
+}
+
+/// Returns `true` if a certificate with `days_left` days of validity, or no
+/// certificate at all if `None`, must be (re)issued.
+fn renewal_due(days_left: Option<i64>, renew_days: i64) -> bool {
+    days_left.map_or(true, |days| days < renew_days)
+}
+
+/// A minimal HTTP/1.x server answering `HTTP-01` challenges.
+///
+/// This is intentionally not a `Rocket` instance: launching one would claim
+/// the socket inherited from a handover and report the application as ready
+/// before the application's own server is listening.
+struct ChallengeServer {
+    address: SocketAddr,
+    shutdown: oneshot::Sender<()>,
+    task: JoinHandle<()>,
+}
+
+impl ChallengeServer {
+    /// Starts answering challenges in `challenges` on `address`.
+    async fn bind(address: SocketAddr, challenges: Challenges) -> io::Result<ChallengeServer> {
+        let mut listener = TcpListener::bind(address).await?;
+        let address = listener.local_addr()?;
+        let (shutdown, mut shutdown_rx) = oneshot::channel::<()>();
+        let task = tokio::spawn(async move {
+            loop {
+                tokio::select! {
+                    _ = &mut shutdown_rx => break,
+                    accepted = listener.accept() => match accepted {
+                        Ok((stream, _)) => {
+                            tokio::spawn(answer_challenge(stream, challenges.clone()));
+                        }
+                        Err(e) => {
+                            warn_!("ACME challenge listener error: {}", e);
+                            tokio::time::delay_for(Duration::from_millis(100)).await;
+                        }
+                    }
+                }
+            }
+        });
+
+        Ok(ChallengeServer { address, shutdown, task })
+    }
+
+    /// Stops accepting connections and waits for the listener to close.
+    async fn shutdown(self) {
+        let _ = self.shutdown.send(());
+        let _ = self.task.await;
+    }
+}
+
+/// Reads one request from `stream` and writes the response to it.
+async fn answer_challenge(mut stream: TcpStream, challenges: Challenges) {
+    let read_head = async {
+        let (mut head, mut buf) = (Vec::new(), [0; 1024]);
+        while !head.windows(4).any(|w| w == b"\r\n\r\n") && head.len() < MAX_REQUEST_HEAD {
+            match stream.read(&mut buf).await? {
+                0 => break,
+                n => head.extend_from_slice(&buf[..n]),
+            }
+        }
+
+        Ok::<_, io::Error>(head)
+    };
+
+    let head = match tokio::time::timeout(REQUEST_TIMEOUT, read_head).await {
+        Ok(Ok(head)) => head,
+        _ => return,
+    };
+
+    let response = challenge_response(&head, &challenges);
+    let _ = stream.write_all(response.as_bytes()).await;
+}
+
+/// Returns the full HTTP response to the request whose head is `head`.
+fn challenge_response(head: &[u8], challenges: &Challenges) -> String {
+    let line = head.split(|&b| b == b'\n').next().unwrap_or(&[]);
+    let line = String::from_utf8_lossy(line);
+    let mut parts = line.trim_end().split(' ');
+    let token = match (parts.next(), parts.next()) {
+        (Some("GET"), Some(path)) => path.strip_prefix(CHALLENGE_PREFIX),
+        _ => None,
+    };
+
+    let proof = token.and_then(|token| challenges.read().unwrap().get(token).cloned());
+    match proof {
+        Some(proof) => format!("HTTP/1.1 200 OK\r\nContent-Type: text/plain\r\n\
+            Content-Length: {}\r\nConnection: close\r\n\r\n{}", proof.len(), proof),
+        None => "HTTP/1.1 404 Not Found\r\nContent-Length: 0\r\n\
+            Connection: close\r\n\r\n".into(),
+    }
+}
+
+impl AcmeState {
+    fn directory(&self) -> PathBuf {
+        self.config.directory.relative()
+    }
+
+    fn acme_directory(&self) -> Result<Directory<FilePersist>, acme_lib::Error> {
+        let url = match self.config.staging {
+            true => DirectoryUrl::LetsEncryptStaging,
+            false => DirectoryUrl::LetsEncrypt,
+        };
+
+        Directory::from_url(FilePersist::new(self.directory()), url)
+    }
+
+    /// Returns the number of days the persisted certificate remains valid, or
+    /// `None` if there is no persisted certificate. Blocks.
+    fn valid_days_left(&self) -> Result<Option<i64>, acme_lib::Error> {
+        // A certificate that was persisted but not written out is unusable.
+        if !self.directory().join(CERT_FILE).exists() {
+            return Ok(None);
+        }
+
+        let account = self.acme_directory()?.account(&self.config.email)?;
+        let cert = account.certificate(&self.config.domains[0])?;
+        Ok(cert.map(|cert| cert.valid_days_left()))
+    }
+
+    /// Orders a new certificate, answering challenges via `self.challenges`.
+    /// Blocks.
+    fn order(&self) -> Result<Certificate, acme_lib::Error> {
+        let account = self.acme_directory()?.account(&self.config.email)?;
+        let primary = &self.config.domains[0];
+        let alt_names: Vec<&str> = self.config.domains[1..].iter()
+            .map(|s| s.as_str())
+            .collect();
+
+        let mut order = account.new_order(primary, &alt_names)?;
+        let csr = loop {
+            if let Some(csr) = order.confirm_validations() {
+                break csr;
+            }
+
+            let auths = order.authorizations()?;
+            for auth in &auths {
+                let challenge = auth.http_challenge();
+                let (token, proof) = (challenge.http_token(), challenge.http_proof());
+                self.challenges.write().unwrap().insert(token.into(), proof);
+            }
+
+            for auth in auths {
+                auth.http_challenge().validate(5000)?;
+            }
+
+            order.refresh()?;
+        };
+
+        csr.finalize_pkey(create_p256_key(), 5000)?.download_and_save_cert()
+    }
+
+    /// Writes `cert` to the ACME directory as `CERT_FILE` and `KEY_FILE`.
+    fn persist(&self, cert: &Certificate) -> io::Result<()> {
+        fn write_atomic(path: &Path, contents: &str) -> io::Result<()> {
+            let tmp = path.with_extension("tmp");
+            fs::write(&tmp, contents)?;
+            fs::rename(&tmp, path)
+        }
+
+        let dir = self.directory();
+        fs::create_dir_all(&dir)?;
+        write_atomic(&dir.join(KEY_FILE), cert.private_key())?;
+        write_atomic(&dir.join(CERT_FILE), cert.certificate())
+    }
+
+    /// Orders a new certificate while serving challenges on `http_port`, then
+    /// persists it. Returns the new certificate.
+    async fn renew(&self) -> Result<Certificate, String> {
+        let address = SocketAddr::new(self.address, self.config.http_port);
+        let server = ChallengeServer::bind(address, self.challenges.clone()).await
+            .map_err(|e| format!("failed to serve ACME challenges on {}: {}", address, e))?;
+
+        let state = self.clone();
+        let result = tokio::task::spawn_blocking(move || {
+            let cert = state.order().map_err(|e| e.to_string())?;
+            state.persist(&cert).map_err(|e| e.to_string())?;
+            Ok(cert)
+        }).await;
+
+        server.shutdown().await;
+        self.challenges.write().unwrap().clear();
+        match result {
+            Ok(result) => result,
+            Err(e) => Err(format!("ACME order task failed: {}", e)),
+        }
+    }
+
+    /// Returns `true` if the certificate must be (re)issued. Blocks.
+    fn needs_renewal(&self) -> Result<bool, String> {
+        self.valid_days_left()
+            .map(|days_left| renewal_due(days_left, self.config.renew_days))
+            .map_err(|e| e.to_string())
+    }
+}
+
+#[rocket::async_trait]
+impl Fairing for Acme {
+    fn info(&self) -> Info {
+        Info { kind: Kind::Attach | Kind::Launch, name: "ACME" }
+    }
+
+    async fn on_attach(&self, rocket: Rocket) -> Result<Rocket, Rocket> {
+        use rocket::{logger::PaintExt, yansi::Paint};
+
+        let config = match rocket.figment().extract_inner::<AcmeConfig>("acme") {
+            Ok(config) => config,
+            Err(e) => {
+                rocket::config::pretty_print_error(e);
+                return Err(rocket);
+            }
+        };
+
+        info!("{}{}", Paint::emoji("🔏 "), Paint::magenta("ACME:"));
+        if config.domains.is_empty() {
+            error_!("At least one domain must be configured in `acme.domains`.");
+            return Err(rocket);
+        }
+
+        if rocket.config().tls.is_none() {
+            error_!("TLS must be configured to use the certificates in `{}`.",
+                Paint::white(config.directory.relative().display()));
+            return Err(rocket);
+        }
+
+        let state = AcmeState {
+            config,
+            address: rocket.config().address,
+            challenges: Arc::new(RwLock::new(HashMap::new())),
+        };
+
+        info_!("domains: {}", Paint::white(state.config.domains.join(", ")));
+        info_!("directory: {}", Paint::white(state.directory().display()));
+
+        let check = state.clone();
+        let needs_renewal = tokio::task::spawn_blocking(move || check.needs_renewal())
+            .await
+            .unwrap_or_else(|e| Err(e.to_string()));
+
+        match needs_renewal {
+            Ok(false) => Ok(rocket.manage(state)),
+            Ok(true) => match state.renew().await {
+                Ok(cert) => {
+                    info_!("certificate issued: valid for {} days", cert.valid_days_left());
+                    Ok(rocket.manage(state))
+                }
+                Err(e) => {
+                    error_!("Failed to obtain a certificate: {}", e);
+                    Err(rocket)
+                }
+            },
+            Err(e) => {
+                error_!("Failed to check the persisted certificate: {}", e);
+                Err(rocket)
+            }
+        }
+    }
+
+    fn on_launch(&self, rocket: &Rocket<Orbit>) {
+        let state = rocket.state::<AcmeState>()
+            .expect("ACME state registered in on_attach")
+            .clone();
+
+        let handle = match rocket.tls_handle() {
+            Some(handle) => handle.clone(),
+            None => {
+                warn!("ACME: server launched without TLS; renewal is disabled.");
+                return;
+            }
+        };
+
+        tokio::spawn(async move {
+            loop {
+                tokio::time::delay_for(CHECK_INTERVAL).await;
+
+                let check = state.clone();
+                let needs_renewal = tokio::task::spawn_blocking(move || check.needs_renewal())
+                    .await
+                    .unwrap_or_else(|e| Err(e.to_string()));
+
+                match needs_renewal {
+                    Ok(true) => {},
+                    Ok(false) => continue,
+                    Err(e) => {
+                        warn!("ACME: failed to check certificate expiry: {}", e);
+                        continue;
+                    }
+                }
+
+                let cert = match state.renew().await {
+                    Ok(cert) => cert,
+                    Err(e) => {
+                        warn!("ACME: failed to renew certificate: {}", e);
+                        continue;
+                    }
+                };
+
+                let (certs, key) = (cert.certificate().as_bytes(), cert.private_key().as_bytes());
+                match handle.set_certificate(certs, key) {
+                    Ok(()) => info!("ACME: renewed certificate is now being served."),
+                    Err(e) => warn!("ACME: renewed certificate is unusable: {}", e),
+                }
+            }
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn challenges(token: &str, proof: &str) -> Challenges {
+        let mut map = HashMap::new();
+        map.insert(token.to_string(), proof.to_string());
+        Arc::new(RwLock::new(map))
+    }
+
+    #[test]
+    fn renewal_is_due_before_expiry() {
+        assert!(renewal_due(None, 30));
+        assert!(renewal_due(Some(0), 30));
+        assert!(renewal_due(Some(29), 30));
+        assert!(!renewal_due(Some(30), 30));
+        assert!(!renewal_due(Some(89), 30));
+        assert!(renewal_due(Some(-1), 0));
+        assert!(!renewal_due(Some(0), 0));
+    }
+
+    #[test]
+    fn challenge_responses() {
+        let challenges = challenges("abc", "abc.xyz");
+        let response = |head: &str| challenge_response(head.as_bytes(), &challenges);
+
+        let ok = response("GET /.well-known/acme-challenge/abc HTTP/1.1\r\nHost: a\r\n\r\n");
+        assert!(ok.starts_with("HTTP/1.1 200 OK\r\n"));
+        assert!(ok.contains("\r\nContent-Length: 7\r\n"));
+        assert!(ok.ends_with("\r\n\r\nabc.xyz"));
+
+        let not_found = "HTTP/1.1 404 Not Found\r\n";
+        assert!(response("GET /.well-known/acme-challenge/abd HTTP/1.1\r\n\r\n")
+            .starts_with(not_found));
+        assert!(response("POST /.well-known/acme-challenge/abc HTTP/1.1\r\n\r\n")
+            .starts_with(not_found));
+        assert!(response("GET /abc HTTP/1.1\r\n\r\n").starts_with(not_found));
+        assert!(response("GET /.well-known/acme-challenge/ HTTP/1.1\r\n\r\n")
+            .starts_with(not_found));
+        assert!(response("").starts_with(not_found));
+    }
+
+    async fn request(address: SocketAddr, head: &str) -> String {
+        let mut stream = TcpStream::connect(address).await.unwrap();
+        stream.write_all(head.as_bytes()).await.unwrap();
+        let mut response = String::new();
+        stream.read_to_string(&mut response).await.unwrap();
+        response
+    }
+
+    #[rocket::async_test]
+    async fn challenge_server_answers_challenges() {
+        let address: SocketAddr = "127.0.0.1:0".parse().unwrap();
+        let server = ChallengeServer::bind(address, challenges("tok", "tok.key")).await.unwrap();
+
+        // An idle connection doesn't hold up other requests.
+        let _idle = TcpStream::connect(server.address).await.unwrap();
+
+        let head = "GET /.well-known/acme-challenge/tok HTTP/1.1\r\n\r\n";
+        let response = request(server.address, head).await;
+        assert!(response.starts_with("HTTP/1.1 200 OK\r\n"));
+        assert!(response.ends_with("tok.key"));
+
+        let response = request(server.address, "GET / HTTP/1.1\r\n\r\n").await;
+        assert!(response.starts_with("HTTP/1.1 404 Not Found\r\n"));
+
+        let address = server.address;
+        server.shutdown().await;
+        assert!(TcpStream::connect(address).await.is_err());
+    }
+}
@@ -0,0 +1,474 @@
+//! Streaming zip and tar.gz archive responses.
+//!
+//! See the [`Archive`](crate::archive::Archive) type for further details.
+//!
+//! # Enabling
+//!
+//! This module is only available when the `archive` feature is enabled. Enable
+//! it in `Cargo.toml` as follows:
+//!
+//! ```toml
+//! [dependencies.rocket_contrib]
+//! version = "0.5.0-dev"
+//! default-features = false
+//! features = ["archive"]
+//! ```
+
+use std::future::Future;
+use std::io::{self, Read, Write};
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+use tokio::io::AsyncRead;
+use tokio::task::JoinHandle;
+
+use flate2::{Crc, Compression};
+use flate2::write::GzEncoder;
+
+use rocket::request::Request;
+use rocket::response::{self, Download, Responder, Response};
+use rocket::http::ContentType;
+
+/// The number of bytes read from an entry at a time.
+const CHUNK_SIZE: usize = 32 * 1024;
+
+/// A responder that streams entries as a zip or tar.gz archive.
+///
+/// An `Archive` wraps any value that can be turned into an iterator of entries,
+/// each a pair of a path within the archive and a reader of the entry's
+/// contents. The archive is generated as the response is written: entries are
+/// retrieved from the iterator and read one at a time.
+///
+///   * **zip** archives, created via [`Archive::zip()`], store entries
+///     uncompressed and are streamed in chunks: no entry is ever buffered in
+///     full. Entries and the archive as a whole are limited to 4GiB, and the
+///     archive to 65535 entries.
+///   * **tar.gz** archives, created via [`Archive::tar_gz()`], are compressed.
+///     Since a tar entry's size precedes its contents, each entry is read into
+///     memory in full before it is written. Paths are limited to 255 bytes.
+///
+/// Entries are retrieved, read, and encoded on a thread dedicated to blocking
+/// work, so readers may perform blocking I/O, such as reading from local files,
+/// without stalling other requests.
+///
+/// The Content-Type of the response is set to `application/zip` or
+/// `application/gzip`, and the response is marked as an attachment via
+/// [`Download`]. The file name is `archive.zip` or `archive.tar.gz` unless set
+/// via [`Archive::filename()`].
+///
+/// Errors reading an entry or exceeding the limits above, which occur only
+/// after the response has begun, abort the response.
+///
+/// # Example
+///
+/// ```rust
+/// # #[macro_use] extern crate rocket;
+/// # extern crate rocket_contrib;
+/// use std::io::Cursor;
+/// use rocket_contrib::archive::Archive;
+///
+/// #[get("/export")]
+/// fn export() -> Archive<Vec<(String, Cursor<String>)>> {
+///     let entries = (1..=3)
+///         .map(|i| (format!("reports/{}.txt", i), Cursor::new(format!("report {}", i))))
+///         .collect();
+///
+///     Archive::zip(entries).filename("reports.zip")
+/// }
+/// ```
+#[derive(Debug, Clone)]
+pub struct Archive<I> {
+    entries: I,
+    format: Format,
+    filename: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Format {
+    Zip,
+    TarGz,
+}
+
+impl<I> Archive<I> {
+    /// Constructs an `Archive` responder that streams `entries` as a zip
+    /// archive.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket_contrib::archive::Archive;
+    ///
+    /// let archive = Archive::zip(vec![("hello.txt", &b"Hello, world!"[..])]);
+    /// ```
+    #[inline(always)]
+    pub fn zip(entries: I) -> Archive<I> {
+        Archive { entries, format: Format::Zip, filename: None }
+    }
+
+    /// Constructs an `Archive` responder that streams `entries` as a gzipped
+    /// tar archive.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket_contrib::archive::Archive;
+    ///
+    /// let archive = Archive::tar_gz(vec![("hello.txt", &b"Hello, world!"[..])]);
+    /// ```
+    #[inline(always)]
+    pub fn tar_gz(entries: I) -> Archive<I> {
+        Archive { entries, format: Format::TarGz, filename: None }
+    }
+
+    /// Sets the name of the file the response should be downloaded as.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket_contrib::archive::Archive;
+    ///
+    /// let entries = vec![("hello.txt", &b"Hello, world!"[..])];
+    /// let archive = Archive::tar_gz(entries).filename("hello.tar.gz");
+    /// ```
+    #[inline(always)]
+    pub fn filename<S: Into<String>>(mut self, filename: S) -> Archive<I> {
+        self.filename = Some(filename.into());
+        self
+    }
+}
+
+fn limit_error(what: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::Other, format!("archive {} exceeds format limit", what))
+}
+
+/// A zip entry whose data has been written, for the central directory.
+struct ZipRecord {
+    name: String,
+    crc: u32,
+    size: u32,
+    offset: u32,
+}
+
+/// Writes a zip archive of uncompressed entries, each followed by a data
+/// descriptor so that sizes and checksums needn't be known in advance.
+struct ZipWriter<R> {
+    current: Option<(R, String, Crc, u64, u64)>,
+    records: Vec<ZipRecord>,
+    written: u64,
+    finished: bool,
+}
+
+// General purpose flags: sizes in data descriptor (3) and UTF-8 names (11).
+const ZIP_FLAGS: u16 = 0x0808;
+const ZIP_VERSION: u16 = 20;
+// 1980-01-01 00:00:00 in MS-DOS date format, the earliest representable.
+const ZIP_DATE: u16 = (1 << 5) | 1;
+
+impl<R: Read> ZipWriter<R> {
+    fn new() -> Self {
+        ZipWriter { current: None, records: vec![], written: 0, finished: false }
+    }
+
+    fn emit(&mut self, out: &mut Vec<u8>, bytes: &[u8]) {
+        out.extend_from_slice(bytes);
+        self.written += bytes.len() as u64;
+    }
+
+    fn offset(&self) -> io::Result<u32> {
+        if self.written > u32::MAX as u64 {
+            return Err(limit_error("size"));
+        }
+
+        Ok(self.written as u32)
+    }
+
+    fn fill<E>(&mut self, entries: &mut E, out: &mut Vec<u8>) -> io::Result<bool>
+        where E: Iterator<Item = (String, R)>
+    {
+        if let Some((mut reader, name, mut crc, size, offset)) = self.current.take() {
+            let mut chunk = vec![0; CHUNK_SIZE];
+            let n = reader.read(&mut chunk)?;
+            if n > 0 {
+                crc.update(&chunk[..n]);
+                self.emit(out, &chunk[..n]);
+                self.current = Some((reader, name, crc, size + n as u64, offset));
+                return Ok(true);
+            }
+
+            if size > u32::MAX as u64 {
+                return Err(limit_error("entry size"));
+            }
+
+            let (crc, size, offset) = (crc.sum(), size as u32, offset as u32);
+            let record = ZipRecord { name, crc, size, offset };
+            let mut descriptor = Vec::with_capacity(16);
+            descriptor.extend_from_slice(&0x08074b50u32.to_le_bytes());
+            descriptor.extend_from_slice(&record.crc.to_le_bytes());
+            descriptor.extend_from_slice(&record.size.to_le_bytes());
+            descriptor.extend_from_slice(&record.size.to_le_bytes());
+            self.emit(out, &descriptor);
+            self.records.push(record);
+            return Ok(true);
+        }
+
+        if let Some((name, reader)) = entries.next() {
+            if self.records.len() >= u16::MAX as usize || name.len() > u16::MAX as usize {
+                return Err(limit_error("entry count or path"));
+            }
+
+            let offset = self.offset()?;
+            let mut header = Vec::with_capacity(30 + name.len());
+            header.extend_from_slice(&0x04034b50u32.to_le_bytes());
+            header.extend_from_slice(&ZIP_VERSION.to_le_bytes());
+            header.extend_from_slice(&ZIP_FLAGS.to_le_bytes());
+            header.extend_from_slice(&0u16.to_le_bytes()); // stored
+            header.extend_from_slice(&0u16.to_le_bytes()); // time
+            header.extend_from_slice(&ZIP_DATE.to_le_bytes());
+            header.extend_from_slice(&[0; 12]); // crc and sizes, in descriptor
+            header.extend_from_slice(&(name.len() as u16).to_le_bytes());
+            header.extend_from_slice(&0u16.to_le_bytes()); // extra field length
+            header.extend_from_slice(name.as_bytes());
+            self.emit(out, &header);
+            self.current = Some((reader, name, Crc::new(), 0, offset as u64));
+            return Ok(true);
+        }
+
+        if self.finished {
+            return Ok(false);
+        }
+
+        let directory_offset = self.offset()?;
+        let mut directory = vec![];
+        for record in &self.records {
+            directory.extend_from_slice(&0x02014b50u32.to_le_bytes());
+            directory.extend_from_slice(&ZIP_VERSION.to_le_bytes()); // made by
+            directory.extend_from_slice(&ZIP_VERSION.to_le_bytes()); // needed
+            directory.extend_from_slice(&ZIP_FLAGS.to_le_bytes());
+            directory.extend_from_slice(&0u16.to_le_bytes()); // stored
+            directory.extend_from_slice(&0u16.to_le_bytes()); // time
+            directory.extend_from_slice(&ZIP_DATE.to_le_bytes());
+            directory.extend_from_slice(&record.crc.to_le_bytes());
+            directory.extend_from_slice(&record.size.to_le_bytes());
+            directory.extend_from_slice(&record.size.to_le_bytes());
+            directory.extend_from_slice(&(record.name.len() as u16).to_le_bytes());
+            directory.extend_from_slice(&[0; 12]); // extra, comment, disk, attrs
+            directory.extend_from_slice(&record.offset.to_le_bytes());
+            directory.extend_from_slice(record.name.as_bytes());
+        }
+
+        let count = self.records.len() as u16;
+        let directory_len = directory.len() as u32;
+        directory.extend_from_slice(&0x06054b50u32.to_le_bytes());
+        directory.extend_from_slice(&[0; 4]); // disk numbers
+        directory.extend_from_slice(&count.to_le_bytes());
+        directory.extend_from_slice(&count.to_le_bytes());
+        directory.extend_from_slice(&directory_len.to_le_bytes());
+        directory.extend_from_slice(&directory_offset.to_le_bytes());
+        directory.extend_from_slice(&0u16.to_le_bytes()); // comment length
+        self.emit(out, &directory);
+        self.finished = true;
+        Ok(true)
+    }
+}
+
+/// Writes a gzipped tar archive, one entry at a time.
+struct TarGzWriter {
+    encoder: Option<GzEncoder<Vec<u8>>>,
+}
+
+/// Writes `value` into `field` as a NUL-terminated, zero-padded octal number.
+fn tar_octal(field: &mut [u8], value: u64) -> io::Result<()> {
+    let digits = format!("{:0width$o}", value, width = field.len() - 1);
+    if digits.len() >= field.len() {
+        return Err(limit_error("entry size"));
+    }
+
+    field[..digits.len()].copy_from_slice(digits.as_bytes());
+    field[digits.len()] = 0;
+    Ok(())
+}
+
+/// Returns a ustar header for a regular file at `path` of `size` bytes.
+fn tar_header(path: &str, size: u64) -> io::Result<[u8; 512]> {
+    // Paths longer than 100 bytes are split into a prefix and a name at a `/`.
+    let (prefix, name) = match path.len() {
+        0..=100 => ("", path),
+        _ => path.char_indices()
+            .filter(|&(i, c)| c == '/' && i <= 155 && path.len() - i - 1 <= 100 && i > 0)
+            .map(|(i, _)| (&path[..i], &path[i + 1..]))
+            .next()
+            .ok_or_else(|| limit_error("path"))?,
+    };
+
+    let mut header = [0u8; 512];
+    header[..name.len()].copy_from_slice(name.as_bytes());
+    tar_octal(&mut header[100..108], 0o644)?;
+    tar_octal(&mut header[108..116], 0)?;
+    tar_octal(&mut header[116..124], 0)?;
+    tar_octal(&mut header[124..136], size)?;
+    tar_octal(&mut header[136..148], 0)?;
+    header[156] = b'0';
+    header[257..263].copy_from_slice(b"ustar\0");
+    header[263..265].copy_from_slice(b"00");
+    header[345..345 + prefix.len()].copy_from_slice(prefix.as_bytes());
+
+    // The checksum is computed with the checksum field set to spaces.
+    header[148..156].copy_from_slice(b"        ");
+    let checksum: u64 = header.iter().map(|&b| b as u64).sum();
+    tar_octal(&mut header[148..155], checksum)?;
+    Ok(header)
+}
+
+impl TarGzWriter {
+    fn new() -> Self {
+        TarGzWriter { encoder: Some(GzEncoder::new(vec![], Compression::default())) }
+    }
+
+    fn fill<R: Read, E>(&mut self, entries: &mut E, out: &mut Vec<u8>) -> io::Result<bool>
+        where E: Iterator<Item = (String, R)>
+    {
+        let mut encoder = match self.encoder.take() {
+            Some(encoder) => encoder,
+            None => return Ok(false),
+        };
+
+        match entries.next() {
+            Some((name, mut reader)) => {
+                let mut data = vec![];
+                reader.read_to_end(&mut data)?;
+                encoder.write_all(&tar_header(&name, data.len() as u64)?)?;
+                encoder.write_all(&data)?;
+                encoder.write_all(&vec![0; (512 - data.len() % 512) % 512])?;
+                out.append(encoder.get_mut());
+                self.encoder = Some(encoder);
+            }
+            None => {
+                encoder.write_all(&[0; 1024])?;
+                out.append(&mut encoder.finish()?);
+            }
+        }
+
+        Ok(true)
+    }
+}
+
+enum Writer<R> {
+    Zip(ZipWriter<R>),
+    TarGz(TarGzWriter),
+}
+
+/// The entries and writer of an archive, moved to the blocking thread pool and
+/// back each time more of the archive is needed.
+struct ArchiveState<R> {
+    entries: Box<dyn Iterator<Item = (String, R)> + Send>,
+    writer: Writer<R>,
+    buf: Vec<u8>,
+    pos: usize,
+}
+
+impl<R: Read> ArchiveState<R> {
+    /// Refills the empty buffer. Returns `false` when the archive is complete.
+    fn refill(&mut self) -> io::Result<bool> {
+        self.buf.clear();
+        self.pos = 0;
+        loop {
+            let filled = match self.writer {
+                Writer::Zip(ref mut zip) => zip.fill(&mut self.entries, &mut self.buf)?,
+                Writer::TarGz(ref mut tar) => tar.fill(&mut self.entries, &mut self.buf)?,
+            };
+
+            if !filled || !self.buf.is_empty() {
+                return Ok(filled);
+            }
+        }
+    }
+}
+
+/// An `AsyncRead` that writes the archive on demand.
+enum ArchiveReader<R> {
+    Idle(Box<ArchiveState<R>>),
+    Filling(JoinHandle<(Box<ArchiveState<R>>, io::Result<bool>)>),
+    Done,
+}
+
+impl<R: Read + Send + 'static> AsyncRead for ArchiveReader<R> {
+    fn poll_read(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut [u8],
+    ) -> Poll<io::Result<usize>> {
+        let this = self.get_mut();
+        loop {
+            match this {
+                ArchiveReader::Idle(state) if state.pos < state.buf.len() => {
+                    let n = std::cmp::min(buf.len(), state.buf.len() - state.pos);
+                    buf[..n].copy_from_slice(&state.buf[state.pos..state.pos + n]);
+                    state.pos += n;
+                    return Poll::Ready(Ok(n));
+                }
+                ArchiveReader::Idle(_) => {
+                    let mut state = match std::mem::replace(this, ArchiveReader::Done) {
+                        ArchiveReader::Idle(state) => state,
+                        _ => unreachable!("the reader is idle"),
+                    };
+
+                    *this = ArchiveReader::Filling(tokio::task::spawn_blocking(move || {
+                        let filled = state.refill();
+                        (state, filled)
+                    }));
+                }
+                ArchiveReader::Filling(handle) => {
+                    let result = match Pin::new(handle).poll(cx) {
+                        Poll::Pending => return Poll::Pending,
+                        Poll::Ready(result) => result,
+                    };
+
+                    *this = ArchiveReader::Done;
+                    match result {
+                        Ok((state, Ok(true))) => *this = ArchiveReader::Idle(state),
+                        Ok((_, Ok(false))) => return Poll::Ready(Ok(0)),
+                        Ok((_, Err(e))) => return Poll::Ready(Err(e)),
+                        Err(e) => {
+                            let error = io::Error::new(io::ErrorKind::Other, e.to_string());
+                            return Poll::Ready(Err(error));
+                        }
+                    }
+                }
+                ArchiveReader::Done => return Poll::Ready(Ok(0)),
+            }
+        }
+    }
+}
+
+/// Streams the entries as an archive with a Content-Type of `application/zip`
+/// or `application/gzip` and an attachment Content-Disposition.
+impl<'r, I, P, R> Responder<'r, 'static> for Archive<I>
+    where I: IntoIterator<Item = (P, R)>, I::IntoIter: Send + 'static,
+          P: Into<String>, R: Read + Send + 'static
+{
+    fn respond_to(self, req: &'r Request<'_>) -> response::Result<'static> {
+        let entries = self.entries.into_iter().map(|(path, reader)| {
+            let path: String = path.into();
+            (path.trim_start_matches('/').to_string(), reader)
+        });
+
+        let (writer, content_type, default_name) = match self.format {
+            Format::Zip => {
+                (Writer::Zip(ZipWriter::new()), ContentType::ZIP, "archive.zip")
+            }
+            Format::TarGz => {
+                (Writer::TarGz(TarGzWriter::new()), ContentType::GZIP, "archive.tar.gz")
+            }
+        };
+
+        let state = ArchiveState { entries: Box::new(entries), writer, buf: vec![], pos: 0 };
+        let reader = ArchiveReader::Idle(Box::new(state));
+        let response = Response::build()
+            .header(content_type)
+            .streamed_body(reader)
+            .finalize();
+
+        let filename = self.filename.unwrap_or_else(|| default_name.to_string());
+        Download::attachment(response, filename).respond_to(req)
+    }
+}
@@ -0,0 +1,255 @@
+//! Auditing of outgoing responses for common header misconfigurations.
+//!
+//! See the [`Audit`] fairing for further details.
+//!
+//! # Enabling
+//!
+//! This module is only available when the `audit` feature is enabled. Enable
+//! it in `Cargo.toml` as follows:
+//!
+//! ```toml
+//! [dependencies.rocket_contrib]
+//! version = "0.5.0-dev"
+//! default-features = false
+//! features = ["audit"]
+//! ```
+
+use std::fmt;
+
+use rocket::{Request, Response};
+use rocket::fairing::{Fairing, Info, Kind};
+use rocket::http::{Header, Status};
+
+/// A misconfiguration found by an [`Audit`].
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum Issue {
+    /// The response has a body but no `Content-Type`.
+    MissingContentType,
+    /// The response sets a cookie but may be stored by shared caches, which
+    /// would then serve the cookie to other clients.
+    CacheableWithCookie,
+    /// The response was negotiated on the named request header, but its
+    /// `Vary` header doesn't list it, so caches may serve it to clients that
+    /// would have been sent a different response.
+    MissingVary(&'static str),
+    /// The response has a status, `204` or `304`, that forbids a body but has
+    /// a body anyway.
+    BodyNotAllowed(Status),
+}
+
+impl Issue {
+    /// Returns a short, stable identifier for `self`, as used in the
+    /// [`Audit::HEADER`] header.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket_contrib::audit::Issue;
+    ///
+    /// assert_eq!(Issue::MissingVary("Accept").code(), "missing-vary");
+    /// ```
+    pub fn code(&self) -> &'static str {
+        match self {
+            Issue::MissingContentType => "missing-content-type",
+            Issue::CacheableWithCookie => "cacheable-with-cookie",
+            Issue::MissingVary(_) => "missing-vary",
+            Issue::BodyNotAllowed(_) => "body-not-allowed",
+        }
+    }
+}
+
+impl fmt::Display for Issue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Issue::MissingContentType => write!(f, "response has a body but no Content-Type"),
+            Issue::CacheableWithCookie => {
+                write!(f, "response sets a cookie but is cacheable by shared caches")
+            }
+            Issue::MissingVary(name) => {
+                write!(f, "response negotiated on {} but Vary doesn't list it", name)
+            }
+            Issue::BodyNotAllowed(status) => {
+                write!(f, "response has a body but its status, {}, forbids one", status)
+            }
+        }
+    }
+}
+
+/// A fairing that warns about common misconfigurations in outgoing responses.
+///
+/// An `Audit` inspects every response and logs a warning for each [`Issue`]
+/// it finds. The following are checked:
+///
+///   * **Missing `Content-Type`:** A response with a non-empty body has no
+///     `Content-Type` header, leaving clients to guess.
+///   * **Cacheable cookies:** A response with a `Set-Cookie` header is
+///     cacheable by shared caches: its `Cache-Control` is `public` or has a
+///     positive `max-age` or `s-maxage`, and isn't `private` or `no-store`,
+///     or it has an `Expires` header but no `Cache-Control`.
+///   * **Missing `Vary`:** A response was negotiated but doesn't say so in
+///     its `Vary` header. A response is considered negotiated on `Accept` if
+///     it's from a non-payload route with a `format`, and on
+///     `Accept-Encoding` if it has a `Content-Encoding`.
+///   * **Forbidden bodies:** A `204 No Content` or `304 Not Modified`
+///     response has a non-empty body.
+///
+/// The checks are heuristics meant to catch bugs before they reach
+/// production. An `Audit` is intended to be attached in development and
+/// staging; it does nothing to fix the issues it reports. When enabled via
+/// [`Audit::header()`], each issue is also reported to the client in an
+/// [`Audit::HEADER`] header so that it shows up in browser tools and in
+/// integration tests. Since the audit runs as a response fairing, responses
+/// are audited as modified by the fairings attached before it.
+///
+/// # Example
+///
+/// ```rust,no_run
+/// # #[macro_use] extern crate rocket;
+/// # extern crate rocket_contrib;
+/// use rocket_contrib::audit::Audit;
+///
+/// #[launch]
+/// fn rocket() -> rocket::Rocket {
+///     let rocket = rocket::ignite();
+///     if cfg!(debug_assertions) {
+///         return rocket.attach(Audit::new().header(true));
+///     }
+///
+///     rocket
+/// }
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct Audit {
+    header: bool,
+}
+
+impl Audit {
+    /// The name of the response header listing the [`Issue::code()`] of each
+    /// issue found, when enabled via [`Audit::header()`]: `X-Audit`.
+    pub const HEADER: &'static str = "X-Audit";
+
+    /// Creates a new audit that logs the issues it finds.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket_contrib::audit::Audit;
+    ///
+    /// let audit = Audit::new();
+    /// ```
+    pub fn new() -> Audit {
+        Audit::default()
+    }
+
+    /// Sets whether the issues found in a response are also listed in its
+    /// [`Audit::HEADER`] header. Defaults to `false`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket_contrib::audit::Audit;
+    ///
+    /// let audit = Audit::new().header(true);
+    /// ```
+    pub fn header(mut self, enabled: bool) -> Self {
+        self.header = enabled;
+        self
+    }
+
+    /// Returns the issues found in the response `res` to `req`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # extern crate rocket_contrib;
+    /// use rocket::Response;
+    /// use rocket::http::Status;
+    /// use rocket::local::blocking::Client;
+    /// use rocket_contrib::audit::{Audit, Issue};
+    ///
+    /// let client = Client::tracked(rocket::ignite()).unwrap();
+    /// let request = client.get("/");
+    ///
+    /// let response = Response::build()
+    ///     .status(Status::NoContent)
+    ///     .sized_body(None, std::io::Cursor::new("oops"))
+    ///     .finalize();
+    ///
+    /// let issues = Audit::check(request.inner(), &response);
+    /// assert_eq!(issues, vec![Issue::BodyNotAllowed(Status::NoContent)]);
+    /// ```
+    pub fn check(req: &Request<'_>, res: &Response<'_>) -> Vec<Issue> {
+        let mut issues = vec![];
+        let has_body = res.body().map_or(false, |body| body.known_size() != Some(0));
+        let status = res.status();
+        if has_body && (status == Status::NoContent || status == Status::NotModified) {
+            issues.push(Issue::BodyNotAllowed(status));
+        } else if has_body && !res.headers().contains("Content-Type") {
+            issues.push(Issue::MissingContentType);
+        }
+
+        if res.headers().contains("Set-Cookie") && is_shared_cacheable(res) {
+            issues.push(Issue::CacheableWithCookie);
+        }
+
+        let negotiated_format = req.route()
+            .map_or(false, |route| route.format.is_some() && !route.method.supports_payload());
+
+        if negotiated_format && !varies_on(res, "Accept") {
+            issues.push(Issue::MissingVary("Accept"));
+        }
+
+        if res.headers().contains("Content-Encoding") && !varies_on(res, "Accept-Encoding") {
+            issues.push(Issue::MissingVary("Accept-Encoding"));
+        }
+
+        issues
+    }
+}
+
+/// Returns `true` if shared caches may store `res`.
+fn is_shared_cacheable(res: &Response<'_>) -> bool {
+    let directives: Vec<String> = res.headers().get("Cache-Control")
+        .flat_map(|value| value.split(','))
+        .map(|directive| directive.trim().to_ascii_lowercase())
+        .collect();
+
+    if directives.is_empty() {
+        return res.headers().contains("Expires");
+    }
+
+    let positive_age = |directive: &str, name: &str| directive.strip_prefix(name)
+        .and_then(|rest| rest.strip_prefix('='))
+        .and_then(|age| age.trim_matches('"').parse::<u64>().ok())
+        .map_or(false, |age| age > 0);
+
+    !directives.iter().any(|d| d == "private" || d == "no-store")
+        && directives.iter().any(|d| {
+            d == "public" || positive_age(d, "max-age") || positive_age(d, "s-maxage")
+        })
+}
+
+/// Returns `true` if the `Vary` header of `res` lists `name` or `*`.
+fn varies_on(res: &Response<'_>, name: &str) -> bool {
+    res.headers().get("Vary")
+        .flat_map(|value| value.split(','))
+        .map(|field| field.trim())
+        .any(|field| field == "*" || field.eq_ignore_ascii_case(name))
+}
+
+#[rocket::async_trait]
+impl Fairing for Audit {
+    fn info(&self) -> Info {
+        Info { name: "Response Audit", kind: Kind::Response }
+    }
+
+    async fn on_response<'r>(&self, req: &'r Request<'_>, res: &mut Response<'r>) {
+        for issue in Audit::check(req, res) {
+            warn!("Audit: {} {}: {}.", req.method(), req.uri(), issue);
+            if self.header {
+                res.adjoin_header(Header::new(Audit::HEADER, issue.code()));
+            }
+        }
+    }
+}
@@ -0,0 +1,275 @@
+//! iCalendar (`.ics`) responses.
+//!
+//! See the [`Calendar`](crate::calendar::Calendar) type for further details.
+//!
+//! # Enabling
+//!
+//! This module is only available when the `calendar` feature is enabled.
+//! Enable it in `Cargo.toml` as follows:
+//!
+//! ```toml
+//! [dependencies.rocket_contrib]
+//! version = "0.5.0-dev"
+//! default-features = false
+//! features = ["calendar"]
+//! ```
+
+use std::time::SystemTime;
+
+use time::{OffsetDateTime, UtcOffset};
+
+use rocket::request::Request;
+use rocket::response::{self, Download, Responder, content::Content};
+use rocket::http::ContentType;
+
+/// A responder for an iCalendar file of events.
+///
+/// A `Calendar` is built from any number of [`Event`] values. Text is escaped
+/// and long lines are folded as RFC 5545 requires, and times are written in
+/// UTC. The Content-Type of the response is set to `text/calendar`. If a
+/// filename is set via [`Calendar::filename()`], the response is marked as an
+/// attachment with that name via [`Download`].
+///
+/// Times are [`OffsetDateTime`] values from the `time` crate.
+///
+/// # Example
+///
+/// ```rust
+/// # #[macro_use] extern crate rocket;
+/// # extern crate rocket_contrib;
+/// use rocket_contrib::calendar::{Calendar, Event};
+/// use time::{Duration, OffsetDateTime};
+///
+/// #[get("/meetup.ics")]
+/// fn meetup() -> Calendar {
+///     let start = OffsetDateTime::from_unix_timestamp(1593864000);
+///
+///     Calendar::new()
+///         .event(Event::new("meetup-2020-07@example.com", "Rocket Meetup", start)
+///             .end(start + Duration::hours(2))
+///             .location("Room 101, Main St.")
+///             .description("Talks, then questions; all welcome."))
+///         .filename("meetup.ics")
+/// }
+/// ```
+#[derive(Debug, Clone)]
+pub struct Calendar {
+    product: String,
+    name: Option<String>,
+    events: Vec<Event>,
+    filename: Option<String>,
+}
+
+/// An event in a [`Calendar`].
+///
+/// Every event has a globally unique ID, which clients use to recognize the
+/// event when it is later updated, a summary, and a start time.
+///
+/// # Example
+///
+/// ```rust
+/// use rocket_contrib::calendar::Event;
+/// use time::OffsetDateTime;
+///
+/// let start = OffsetDateTime::from_unix_timestamp(1593864000);
+/// let event = Event::new("launch@example.com", "Launch", start)
+///     .url("https://example.com/launch");
+/// ```
+#[derive(Debug, Clone)]
+pub struct Event {
+    uid: String,
+    summary: String,
+    start: OffsetDateTime,
+    end: Option<OffsetDateTime>,
+    description: Option<String>,
+    location: Option<String>,
+    url: Option<String>,
+}
+
+impl Calendar {
+    /// Returns an empty calendar.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket_contrib::calendar::Calendar;
+    ///
+    /// let calendar = Calendar::new();
+    /// ```
+    pub fn new() -> Calendar {
+        Calendar {
+            product: "-//Rocket//rocket_contrib//EN".into(),
+            name: None,
+            events: vec![],
+            filename: None,
+        }
+    }
+
+    /// Sets the identifier of the product that generated the calendar, the
+    /// `PRODID` property. Defaults to `-//Rocket//rocket_contrib//EN`.
+    pub fn product<S: Into<String>>(mut self, product: S) -> Self {
+        self.product = product.into();
+        self
+    }
+
+    /// Sets the name clients display for the calendar.
+    pub fn name<S: Into<String>>(mut self, name: S) -> Self {
+        self.name = Some(name.into());
+        self
+    }
+
+    /// Appends `event` to the calendar.
+    pub fn event(mut self, event: Event) -> Self {
+        self.events.push(event);
+        self
+    }
+
+    /// Appends every event in `events` to the calendar.
+    pub fn events<I: IntoIterator<Item = Event>>(mut self, events: I) -> Self {
+        self.events.extend(events);
+        self
+    }
+
+    /// Sets the name of the file the response should be downloaded as.
+    pub fn filename<S: Into<String>>(mut self, filename: S) -> Self {
+        self.filename = Some(filename.into());
+        self
+    }
+
+    fn to_ics(&self) -> String {
+        let stamp = timestamp(OffsetDateTime::from(SystemTime::now()));
+
+        let mut ics = String::new();
+        line(&mut ics, "BEGIN:VCALENDAR");
+        line(&mut ics, "VERSION:2.0");
+        line(&mut ics, &format!("PRODID:{}", escape(&self.product)));
+        if let Some(ref name) = self.name {
+            line(&mut ics, &format!("X-WR-CALNAME:{}", escape(name)));
+        }
+
+        for event in &self.events {
+            line(&mut ics, "BEGIN:VEVENT");
+            line(&mut ics, &format!("UID:{}", escape(&event.uid)));
+            line(&mut ics, &format!("DTSTAMP:{}", stamp));
+            line(&mut ics, &format!("DTSTART:{}", timestamp(event.start)));
+            if let Some(end) = event.end {
+                line(&mut ics, &format!("DTEND:{}", timestamp(end)));
+            }
+
+            line(&mut ics, &format!("SUMMARY:{}", escape(&event.summary)));
+            if let Some(ref description) = event.description {
+                line(&mut ics, &format!("DESCRIPTION:{}", escape(description)));
+            }
+
+            if let Some(ref location) = event.location {
+                line(&mut ics, &format!("LOCATION:{}", escape(location)));
+            }
+
+            if let Some(ref url) = event.url {
+                line(&mut ics, &format!("URL:{}", url));
+            }
+
+            line(&mut ics, "END:VEVENT");
+        }
+
+        line(&mut ics, "END:VCALENDAR");
+        ics
+    }
+}
+
+impl Default for Calendar {
+    fn default() -> Self {
+        Calendar::new()
+    }
+}
+
+impl Event {
+    /// Returns an event with the unique ID `uid`, summarized as `summary`,
+    /// starting at `start`.
+    pub fn new<U, S>(uid: U, summary: S, start: OffsetDateTime) -> Event
+        where U: Into<String>, S: Into<String>
+    {
+        Event {
+            uid: uid.into(), summary: summary.into(), start,
+            end: None, description: None, location: None, url: None,
+        }
+    }
+
+    /// Sets the time the event ends.
+    pub fn end(mut self, end: OffsetDateTime) -> Self {
+        self.end = Some(end);
+        self
+    }
+
+    /// Sets the event's plain-text description.
+    pub fn description<S: Into<String>>(mut self, description: S) -> Self {
+        self.description = Some(description.into());
+        self
+    }
+
+    /// Sets the event's location.
+    pub fn location<S: Into<String>>(mut self, location: S) -> Self {
+        self.location = Some(location.into());
+        self
+    }
+
+    /// Sets the URL of a page describing the event.
+    pub fn url<S: Into<String>>(mut self, url: S) -> Self {
+        self.url = Some(url.into());
+        self
+    }
+}
+
+/// Escapes `text` for use as a property value.
+fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.replace("\r\n", "\n").chars() {
+        match c {
+            '\\' => escaped.push_str("\\\\"),
+            ';' => escaped.push_str("\\;"),
+            ',' => escaped.push_str("\\,"),
+            '\n' | '\r' => escaped.push_str("\\n"),
+            c => escaped.push(c),
+        }
+    }
+
+    escaped
+}
+
+/// Appends `content` to `ics` as a CRLF-terminated line, folded so that no
+/// line exceeds 75 octets. Lines are never folded within a character.
+fn line(ics: &mut String, content: &str) {
+    let mut limit = 75;
+    let mut start = 0;
+    for (i, c) in content.char_indices() {
+        if i + c.len_utf8() - start > limit {
+            ics.push_str(&content[start..i]);
+            ics.push_str("\r\n ");
+            start = i;
+            limit = 74;
+        }
+    }
+
+    ics.push_str(&content[start..]);
+    ics.push_str("\r\n");
+}
+
+/// Formats `date` as a UTC iCalendar date-time: `20200704T120000Z`.
+fn timestamp(date: OffsetDateTime) -> String {
+    let date = date.to_offset(UtcOffset::UTC);
+    format!("{:04}{:02}{:02}T{:02}{:02}{:02}Z",
+        date.year(), date.month(), date.day(),
+        date.hour(), date.minute(), date.second())
+}
+
+/// Renders the calendar with a Content-Type of `text/calendar`, as an
+/// attachment if a filename is set.
+impl<'r> Responder<'r, 'static> for Calendar {
+    fn respond_to(self, req: &'r Request<'_>) -> response::Result<'static> {
+        let ics = Content(ContentType::Calendar, self.to_ics());
+        match self.filename {
+            Some(filename) => Download::attachment(ics, filename).respond_to(req),
+            None => ics.respond_to(req),
+        }
+    }
+}
@@ -0,0 +1,388 @@
+//! Traffic splitting between two variants of routes, for A/B tests and canary
+//! releases.
+//!
+//! See the [`Split`] fairing for further details.
+//!
+//! # Enabling
+//!
+//! This module is only available when the `canary` feature is enabled. Enable
+//! it in `Cargo.toml` as follows:
+//!
+//! ```toml
+//! [dependencies.rocket_contrib]
+//! version = "0.5.0-dev"
+//! default-features = false
+//! features = ["canary"]
+//! ```
+
+use std::fmt;
+use std::borrow::Cow;
+use std::sync::{Arc, Mutex};
+use std::sync::atomic::{AtomicU8, AtomicUsize, Ordering};
+
+use rand::Rng;
+
+use rocket::{Request, Response, Data, Route};
+use rocket::fairing::{Fairing, Info, Kind};
+use rocket::handler::{Handler, Outcome};
+use rocket::http::{Cookie, Header};
+
+/// One of the two variants of a [`Split`].
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum Variant {
+    /// The control variant, served to requests not assigned to `B`.
+    A,
+    /// The experimental or canary variant.
+    B,
+}
+
+impl Variant {
+    /// Returns the name of `self` as it appears in the [`Split::HEADER`]
+    /// header and the sticky cookie: `a` or `b`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket_contrib::canary::Variant;
+    ///
+    /// assert_eq!(Variant::B.as_str(), "b");
+    /// ```
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Variant::A => "a",
+            Variant::B => "b",
+        }
+    }
+
+    fn parse(value: &str) -> Option<Variant> {
+        match value {
+            "a" => Some(Variant::A),
+            "b" => Some(Variant::B),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for Variant {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// The state shared by all clones of a `Split`.
+#[derive(Debug)]
+struct Shared {
+    percent: AtomicU8,
+    served: [AtomicUsize; 2],
+}
+
+/// A fairing that splits traffic between two variants of a set of routes.
+///
+/// A `Split` assigns each request to [`Variant::A`] or [`Variant::B`], sending
+/// the configured percentage of requests to `B`. Routes for both variants are
+/// created with [`Split::routes()`], which takes the routes of each variant
+/// and returns routes to mount. A request is routed to the `B` routes only if
+/// it is assigned to `B`; otherwise, or if the `B` route forwards, it's routed
+/// to the `A` routes.
+///
+/// Requests are assigned randomly unless the split is made _sticky_ via
+/// [`Split::sticky()`]. A sticky split stores each client's assignment in a
+/// cookie, so that a client sees the same variant across requests. Sticky
+/// assignments are ignored when the split sends all or no requests to `B`,
+/// so a canary can be rolled back for everyone by setting its percentage to
+/// `0`.
+///
+/// The variant that served a request is recorded in two ways: the response
+/// carries a [`Split::HEADER`] header with the name of the variant, and
+/// [`Split::served()`] counts the requests each variant has served.
+///
+/// Clones of a `Split` share their percentage and counts, so the split can be
+/// reconfigured at runtime via [`Split::set_percent()`] by managing a clone
+/// of it as state.
+///
+/// The fairing must be attached for responses to carry the header and for the
+/// sticky cookie to be set. The routes work without it.
+///
+/// # Example
+///
+/// ```rust,no_run
+/// # #[macro_use] extern crate rocket;
+/// # extern crate rocket_contrib;
+/// use rocket::State;
+/// use rocket_contrib::canary::{Split, Variant};
+///
+/// #[get("/checkout")]
+/// fn checkout() -> &'static str { "current checkout" }
+///
+/// #[get("/checkout")]
+/// fn new_checkout() -> &'static str { "redesigned checkout" }
+///
+/// #[post("/admin/canary/<percent>")]
+/// fn set_canary(split: State<'_, Split>, percent: u8) -> String {
+///     split.set_percent(percent);
+///     format!("B has served {} requests", split.served(Variant::B))
+/// }
+///
+/// #[launch]
+/// fn rocket() -> rocket::Rocket {
+///     let split = Split::new(10).sticky("checkout_variant");
+///     rocket::ignite()
+///         .mount("/", split.routes(routes![checkout], routes![new_checkout]))
+///         .mount("/", routes![set_canary])
+///         .manage(split.clone())
+///         .attach(split)
+/// }
+/// ```
+#[derive(Debug, Clone)]
+pub struct Split {
+    shared: Arc<Shared>,
+    cookie: Option<Cow<'static, str>>,
+}
+
+/// The assignments of a request to the variants of every split.
+#[derive(Default)]
+struct Assignments(Mutex<Vec<Assignment>>);
+
+struct Assignment {
+    split: usize,
+    variant: Variant,
+    served: Option<Variant>,
+}
+
+impl Split {
+    /// The name of the response header naming the variant that served the
+    /// request: `X-Variant`.
+    pub const HEADER: &'static str = "X-Variant";
+
+    /// Creates a new, non-sticky split sending `percent` percent of requests
+    /// to variant `B`. Percentages above `100` are treated as `100`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket_contrib::canary::Split;
+    ///
+    /// let split = Split::new(5);
+    /// assert_eq!(split.percent(), 5);
+    /// ```
+    pub fn new(percent: u8) -> Split {
+        let shared = Shared {
+            percent: AtomicU8::new(std::cmp::min(percent, 100)),
+            served: [AtomicUsize::new(0), AtomicUsize::new(0)],
+        };
+
+        Split { shared: Arc::new(shared), cookie: None }
+    }
+
+    /// Makes the split sticky, storing a client's assignment in the cookie
+    /// named `cookie`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket_contrib::canary::Split;
+    ///
+    /// let split = Split::new(50).sticky("homepage_variant");
+    /// ```
+    pub fn sticky<N: Into<Cow<'static, str>>>(mut self, cookie: N) -> Self {
+        self.cookie = Some(cookie.into());
+        self
+    }
+
+    /// Returns the percentage of requests sent to variant `B`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket_contrib::canary::Split;
+    ///
+    /// let split = Split::new(150);
+    /// assert_eq!(split.percent(), 100);
+    /// ```
+    pub fn percent(&self) -> u8 {
+        self.shared.percent.load(Ordering::Relaxed)
+    }
+
+    /// Sets the percentage of requests sent to variant `B` to `percent`, for
+    /// `self` and all of its clones. Percentages above `100` are treated as
+    /// `100`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket_contrib::canary::Split;
+    ///
+    /// let split = Split::new(5);
+    /// let managed = split.clone();
+    /// managed.set_percent(25);
+    /// assert_eq!(split.percent(), 25);
+    /// ```
+    pub fn set_percent(&self, percent: u8) {
+        self.shared.percent.store(std::cmp::min(percent, 100), Ordering::Relaxed);
+    }
+
+    /// Returns the number of requests served by the routes of `variant`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket_contrib::canary::{Split, Variant};
+    ///
+    /// let split = Split::new(5);
+    /// assert_eq!(split.served(Variant::A), 0);
+    /// ```
+    pub fn served(&self, variant: Variant) -> usize {
+        self.shared.served[variant as usize].load(Ordering::Relaxed)
+    }
+
+    /// Returns the routes to mount for the variant `A` routes `a` and the
+    /// variant `B` routes `b`.
+    ///
+    /// The `B` routes only match requests assigned to `B`, in addition to any
+    /// matcher they already have, and are ranked one less than their rank so
+    /// that they're tried first and don't collide with `A` routes of the same
+    /// path.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # #[macro_use] extern crate rocket;
+    /// # extern crate rocket_contrib;
+    /// use rocket_contrib::canary::Split;
+    ///
+    /// #[get("/")]
+    /// fn index() -> &'static str { "A" }
+    ///
+    /// #[get("/")]
+    /// fn new_index() -> &'static str { "B" }
+    ///
+    /// let routes = Split::new(10).routes(routes![index], routes![new_index]);
+    /// assert_eq!(routes[0].rank - 1, routes[1].rank);
+    /// ```
+    pub fn routes<A, B>(&self, a: A, b: B) -> Vec<Route>
+        where A: Into<Vec<Route>>, B: Into<Vec<Route>>
+    {
+        let a = a.into().into_iter().map(|route| self.record(route, Variant::A));
+        let b = b.into().into_iter().map(|mut route| {
+            let (split, matcher) = (self.clone(), route.matcher.take());
+            route.rank -= 1;
+            route = route.with_matcher(move |req| {
+                split.variant(req) == Variant::B && matcher.as_ref().map_or(true, |m| m(req))
+            });
+
+            self.record(route, Variant::B)
+        });
+
+        a.chain(b).collect()
+    }
+
+    /// Returns the variant `req` is assigned to, assigning it if necessary.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # extern crate rocket_contrib;
+    /// use rocket::local::blocking::Client;
+    /// use rocket_contrib::canary::{Split, Variant};
+    ///
+    /// let split = Split::new(100);
+    /// let client = Client::tracked(rocket::ignite()).unwrap();
+    /// let request = client.get("/");
+    /// assert_eq!(split.variant(request.inner()), Variant::B);
+    /// ```
+    pub fn variant(&self, req: &Request<'_>) -> Variant {
+        let mut assignments = req.local_cache(Assignments::default).0.lock()
+            .expect("assignments lock");
+
+        if let Some(assignment) = assignments.iter().find(|a| a.split == self.key()) {
+            return assignment.variant;
+        }
+
+        let variant = match self.percent() {
+            0 => Variant::A,
+            100 => Variant::B,
+            percent => self.cookie.as_ref()
+                .and_then(|name| req.cookies().get(name).map(|c| c.value().to_string()))
+                .and_then(|value| Variant::parse(&value))
+                .unwrap_or_else(|| match rand::thread_rng().gen_range(0, 100) < percent {
+                    true => Variant::B,
+                    false => Variant::A,
+                }),
+        };
+
+        assignments.push(Assignment { split: self.key(), variant, served: None });
+        variant
+    }
+
+    /// Identifies `self` and its clones among the splits of a request.
+    fn key(&self) -> usize {
+        Arc::as_ptr(&self.shared) as usize
+    }
+
+    /// Returns the variant of `self` that served `req`, if any.
+    fn served_by(&self, req: &Request<'_>) -> Option<Variant> {
+        let assignments = req.local_cache(Assignments::default).0.lock()
+            .expect("assignments lock");
+
+        assignments.iter().find(|a| a.split == self.key()).and_then(|a| a.served)
+    }
+
+    /// Wraps the handler of `route` to record that `variant` served a request.
+    fn record(&self, mut route: Route, variant: Variant) -> Route {
+        let handler = route.handler.clone();
+        route.handler = Box::new(Recorded { handler, split: self.clone(), variant });
+        route
+    }
+}
+
+/// A handler recording that its variant served a request it didn't forward.
+#[derive(Clone)]
+struct Recorded {
+    handler: Box<dyn Handler>,
+    split: Split,
+    variant: Variant,
+}
+
+#[rocket::async_trait]
+impl Handler for Recorded {
+    async fn handle<'r, 's: 'r>(&'s self, req: &'r Request<'_>, data: Data) -> Outcome<'r> {
+        let outcome = self.handler.handle(req, data).await;
+        if !outcome.is_forward() {
+            // Ensure the request has an assignment to record into.
+            self.split.variant(req);
+            let mut assignments = req.local_cache(Assignments::default).0.lock()
+                .expect("assignments lock");
+
+            if let Some(a) = assignments.iter_mut().find(|a| a.split == self.split.key()) {
+                a.served = Some(self.variant);
+            }
+
+            self.split.shared.served[self.variant as usize].fetch_add(1, Ordering::Relaxed);
+        }
+
+        outcome
+    }
+}
+
+#[rocket::async_trait]
+impl Fairing for Split {
+    fn info(&self) -> Info {
+        Info { name: "A/B Split", kind: Kind::Request | Kind::Response }
+    }
+
+    async fn on_request(&self, req: &mut Request<'_>, _: &mut Data) {
+        let variant = self.variant(req);
+        if let Some(ref name) = self.cookie {
+            let current = req.cookies().get(name).map(|c| c.value() == variant.as_str());
+            if current != Some(true) {
+                req.cookies().add(Cookie::new(name.clone(), variant.as_str()));
+            }
+        }
+    }
+
+    async fn on_response<'r>(&self, req: &'r Request<'_>, res: &mut Response<'r>) {
+        if let Some(variant) = self.served_by(req) {
+            res.adjoin_header(Header::new(Split::HEADER, variant.as_str()));
+        }
+    }
+}
@@ -1,4 +1,3 @@
-use rocket::config::{ConfigError, Value};
 use rocket::fairing::{Fairing, Info, Kind};
 use rocket::http::MediaType;
 use rocket::Rocket;
@@ -6,6 +5,7 @@ use rocket::{Request, Response};
 
 struct Context {
     exclusions: Vec<MediaType>,
+    min_size: usize,
 }
 
 impl Default for Context {
@@ -16,36 +16,56 @@ impl Default for Context {
                 MediaType::parse_flexible("application/zip").unwrap(),
                 MediaType::parse_flexible("image/*").unwrap(),
                 MediaType::parse_flexible("video/*").unwrap(),
+                MediaType::parse_flexible("audio/*").unwrap(),
+                MediaType::parse_flexible("font/woff").unwrap(),
+                MediaType::parse_flexible("font/woff2").unwrap(),
+                MediaType::parse_flexible("application/font-woff").unwrap(),
                 MediaType::parse_flexible("application/wasm").unwrap(),
                 MediaType::parse_flexible("application/octet-stream").unwrap(),
             ],
+            min_size: Compression::DEFAULT_MIN_SIZE,
         }
     }
 }
 
-/// Compresses all responses with Brotli or Gzip compression.
+/// Compresses all eligible responses with Brotli or Gzip compression.
 ///
 /// Compression is done in the same manner as the [`Compress`](super::Compress)
-/// responder.
+/// responder, but only for responses that benefit from it. A response isn't
+/// compressed if any of the following hold:
 ///
-/// By default, the fairing does not compress responses with a `Content-Type`
-/// matching any of the following:
+///   * It has no body, a `Content-Encoding` header, or a status of `206
+///     Partial Content`.
+///   * Its body is smaller than the minimum size, by default
+///     [`Compression::DEFAULT_MIN_SIZE`] bytes. Compressing small bodies
+///     wastes CPU time for no gain. Streamed bodies, whose size isn't known
+///     ahead of time, are always compressed.
+///   * Its `Content-Type` matches an exclusion, by default any of the
+///     following, whose formats are already compressed:
 ///
-/// - `application/gzip`
-/// - `application/zip`
-/// - `image/*`
-/// - `video/*`
-/// - `application/wasm`
-/// - `application/octet-stream`
+///     - `application/gzip`
+///     - `application/zip`
+///     - `image/*`
+///     - `video/*`
+///     - `audio/*`
+///     - `font/woff`
+///     - `font/woff2`
+///     - `application/font-woff`
+///     - `application/wasm`
+///     - `application/octet-stream`
 ///
-/// The excluded types can be changed changing the `compress.exclude` Rocket
-/// configuration property in Rocket.toml. The default `Content-Type` exclusions
-/// will be ignored if this is set, and must be added back in one by one if
-/// desired.
+/// Eligible responses list `Accept-Encoding` in their `Vary` header, whether
+/// or not the client accepted a supported encoding.
+///
+/// The excluded types can be changed by setting the `compress.exclude` Rocket
+/// configuration parameter, and the minimum size by setting `compress.min_size`.
+/// The default `Content-Type` exclusions will be ignored if `exclude` is set
+/// and must be added back in one by one if desired.
 ///
 /// ```toml
-/// [global.compress]
+/// [default.compress]
 /// exclude = ["video/*", "application/x-xz"]
+/// min_size = 512
 /// ```
 ///
 /// # Usage
@@ -70,6 +90,9 @@ impl Default for Context {
 pub struct Compression(());
 
 impl Compression {
+    /// The minimum size, in bytes, of a body compressed by default: `1024`.
+    pub const DEFAULT_MIN_SIZE: usize = 1024;
+
     /// Returns a fairing that compresses outgoing requests.
     ///
     /// ## Example
@@ -95,6 +118,7 @@ impl Compression {
     }
 }
 
+#[rocket::async_trait]
 impl Fairing for Compression {
     fn info(&self) -> Info {
         Info {
@@ -103,52 +127,52 @@ impl Fairing for Compression {
         }
     }
 
-    fn on_attach(&self, rocket: Rocket) -> Result<Rocket, Rocket> {
+    async fn on_attach(&self, rocket: Rocket) -> Result<Rocket, Rocket> {
         let mut ctxt = Context::default();
+        let figment = rocket.figment();
 
-        match rocket.config().get_table("compress").and_then(|t| {
-            t.get("exclude").ok_or_else(|| ConfigError::Missing(String::from("exclude")))
-        }) {
-            Ok(excls) => match excls.as_array() {
-                Some(excls) => {
-                    ctxt.exclusions = excls.iter().flat_map(|ex| {
-                        if let Value::String(s) = ex {
-                            let mt = MediaType::parse_flexible(s);
-                            if mt.is_none() {
-                                warn_!("Ignoring invalid media type '{:?}'", s);
-                            }
-                            mt
-                        } else {
-                            warn_!("Ignoring non-string media type '{:?}'", ex);
-                            None
+        if figment.find_value("compress.exclude").is_ok() {
+            match figment.extract_inner::<Vec<String>>("compress.exclude") {
+                Ok(exclusions) => {
+                    ctxt.exclusions = exclusions.iter().filter_map(|ex| {
+                        let mt = MediaType::parse_flexible(ex);
+                        if mt.is_none() {
+                            warn_!("Ignoring invalid media type '{}'", ex);
                         }
+
+                        mt
                     }).collect();
                 }
-                None => {
-                    warn_!(
-                        "Exclusions is not an array; using default compression exclusions '{:?}'",
-                        ctxt.exclusions
-                    );
+                Err(e) => {
+                    rocket::config::pretty_print_error(e);
+                    return Err(rocket);
                 }
-            },
-            Err(ConfigError::Missing(_)) => { /* ignore missing */ }
-            Err(e) => {
-                e.pretty_print();
-                warn_!(
-                    "Using default compression exclusions '{:?}'",
-                    ctxt.exclusions
-                );
             }
-        };
+        }
+
+        if figment.find_value("compress.min_size").is_ok() {
+            match figment.extract_inner::<usize>("compress.min_size") {
+                Ok(min_size) => ctxt.min_size = min_size,
+                Err(e) => {
+                    rocket::config::pretty_print_error(e);
+                    return Err(rocket);
+                }
+            }
+        }
 
         Ok(rocket.manage(ctxt))
     }
 
-    fn on_response<'r>(&self, request: &'r Request<'_>, response: &mut Response<'r>) {
-        let context = request
-            .guard::<rocket::State<'_, Context>>()
+    async fn on_response<'r>(&self, request: &'r Request<'_>, response: &mut Response<'r>) {
+        let context = request.managed_state::<Context>()
             .expect("Compression Context registered in on_attach");
 
+        if let Some(body) = response.body_mut() {
+            if body.size().await.map_or(false, |size| size < context.min_size) {
+                return;
+            }
+        }
+
         super::CompressionUtils::compress_response(request, response, &context.exclusions);
     }
 }
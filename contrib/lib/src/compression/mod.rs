@@ -28,45 +28,53 @@ mod responder;
 pub use self::fairing::Compression;
 pub use self::responder::Compress;
 
-use std::io::Read;
+use std::io::{self, Write};
+use std::pin::Pin;
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll};
 
-use rocket::http::MediaType;
-use rocket::http::hyper::header::{ContentEncoding, Encoding};
+use rocket::tokio::io::AsyncRead;
+use rocket::http::{ContentType, Header, MediaType, Status};
+use rocket::response::Body;
 use rocket::{Request, Response};
 
 #[cfg(feature = "brotli_compression")]
-use brotli::enc::backward_references::BrotliEncoderMode;
+use brotli::{CompressorWriter, enc::backward_references::BrotliEncoderMode};
 
 #[cfg(feature = "gzip_compression")]
-use flate2::read::GzEncoder;
+use flate2::write::GzEncoder;
+
+/// The number of bytes read from a body at a time.
+const CHUNK_SIZE: usize = 4096;
 
 struct CompressionUtils;
 
 impl CompressionUtils {
+    /// Returns `true` if `request` accepts `encoding`, that is, lists it or
+    /// `*` in its `Accept-Encoding` header without a `q` of `0`.
     fn accepts_encoding(request: &Request<'_>, encoding: &str) -> bool {
         request
             .headers()
             .get("Accept-Encoding")
             .flat_map(|accept| accept.split(','))
-            .map(|accept| accept.trim())
-            .any(|accept| accept == encoding)
+            .any(|accept| {
+                let mut parts = accept.split(';').map(|part| part.trim());
+                let name = parts.next().unwrap_or("");
+                let q = parts.filter_map(|param| param.strip_prefix("q="))
+                    .filter_map(|q| q.parse::<f32>().ok())
+                    .next()
+                    .unwrap_or(1.0);
+
+                (name == "*" || name.eq_ignore_ascii_case(encoding)) && q > 0.0
+            })
     }
 
     fn already_encoded(response: &Response<'_>) -> bool {
         response.headers().get("Content-Encoding").next().is_some()
     }
 
-    fn set_body_and_encoding<'r, B: Read + 'r>(
-        response: &mut Response<'r>,
-        body: B,
-        encoding: Encoding,
-    ) {
-        response.set_header(ContentEncoding(vec![encoding]));
-        response.set_streamed_body(body);
-    }
-
     fn skip_encoding(
-        content_type: &Option<rocket::http::ContentType>,
+        content_type: &Option<ContentType>,
         exclusions: &[MediaType],
     ) -> bool {
         match content_type {
@@ -81,8 +89,31 @@ impl CompressionUtils {
         }
     }
 
-    fn compress_response(request: &Request<'_>, response: &mut Response<'_>, exclusions: &[MediaType]) {
-        if CompressionUtils::already_encoded(response) {
+    /// Adds `Accept-Encoding` to the `Vary` header of `response` unless it's
+    /// already listed.
+    fn vary_on_encoding(response: &mut Response<'_>) {
+        let listed = response.headers()
+            .get("Vary")
+            .flat_map(|vary| vary.split(','))
+            .map(|field| field.trim())
+            .any(|field| field == "*" || field.eq_ignore_ascii_case("Accept-Encoding"));
+
+        if !listed {
+            response.adjoin_header(Header::new("Vary", "Accept-Encoding"));
+        }
+    }
+
+    fn compress_response(
+        request: &Request<'_>,
+        response: &mut Response<'_>,
+        exclusions: &[MediaType],
+    ) {
+        if response.body().is_none() || CompressionUtils::already_encoded(response) {
+            return;
+        }
+
+        // Compressing part of a representation would corrupt it.
+        if response.status() == Status::PartialContent {
             return;
         }
 
@@ -92,43 +123,187 @@ impl CompressionUtils {
             return;
         }
 
-        // Compression is done when the request accepts brotli or gzip encoding
-        // and the corresponding feature is enabled
-        if cfg!(feature = "brotli_compression") && CompressionUtils::accepts_encoding(request, "br")
+        // The response is eligible: whether it's compressed now depends on the
+        // request's `Accept-Encoding`, so caches must take it into account.
+        CompressionUtils::vary_on_encoding(response);
+
+        let output = Output::default();
+        let (codec, encoding) = match CompressionUtils::codec(request, &content_type, &output) {
+            Some(codec) => codec,
+            None => return,
+        };
+
+        if let Some(plain) = response.take_body() {
+            response.set_header(Header::new("Content-Encoding", encoding));
+            match plain {
+                Body::Sized(body, _) => {
+                    response.set_streamed_body(Encoder::new(body, codec, output))
+                }
+                Body::Chunked(body, n) => {
+                    response.set_chunked_body(Encoder::new(body, codec, output), n)
+                }
+            }
+        }
+    }
+
+    /// Returns a compressor writing to `output` and the name of its encoding.
+    ///
+    /// Compression is done when the request accepts brotli or gzip encoding
+    /// and the corresponding feature is enabled. Brotli is preferred.
+    #[cfg_attr(not(feature = "brotli_compression"), allow(unused_variables))]
+    fn codec(
+        request: &Request<'_>,
+        content_type: &Option<ContentType>,
+        output: &Output,
+    ) -> Option<(Codec, &'static str)> {
+        #[cfg(feature = "brotli_compression")]
         {
-            #[cfg(feature = "brotli_compression")]
-            {
-                if let Some(plain) = response.take_body() {
-                    let content_type_top = content_type.as_ref().map(|ct| ct.top());
-                    let mut params = brotli::enc::BrotliEncoderInitParams();
-                    params.quality = 2;
-                    if content_type_top == Some("text".into()) {
-                        params.mode = BrotliEncoderMode::BROTLI_MODE_TEXT;
-                    } else if content_type_top == Some("font".into()) {
-                        params.mode = BrotliEncoderMode::BROTLI_MODE_FONT;
-                    }
-
-                    let compressor =
-                        brotli::CompressorReader::with_params(plain.into_inner(), 4096, &params);
-
-                    CompressionUtils::set_body_and_encoding(
-                        response,
-                        compressor,
-                        Encoding::EncodingExt("br".into()),
-                    );
+            if CompressionUtils::accepts_encoding(request, "br") {
+                let content_type_top = content_type.as_ref().map(|ct| ct.top());
+                let mut params = brotli::enc::BrotliEncoderInitParams();
+                params.quality = 2;
+                if content_type_top.map_or(false, |top| top == "text") {
+                    params.mode = BrotliEncoderMode::BROTLI_MODE_TEXT;
+                } else if content_type_top.map_or(false, |top| top == "font") {
+                    params.mode = BrotliEncoderMode::BROTLI_MODE_FONT;
                 }
+
+                let writer = CompressorWriter::with_params(output.clone(), CHUNK_SIZE, &params);
+                return Some((Codec::Brotli(Box::new(writer)), "br"));
             }
-        } else if cfg!(feature = "gzip_compression")
-            && CompressionUtils::accepts_encoding(request, "gzip")
+        }
+
+        #[cfg(feature = "gzip_compression")]
         {
+            if CompressionUtils::accepts_encoding(request, "gzip") {
+                let writer = GzEncoder::new(output.clone(), flate2::Compression::default());
+                return Some((Codec::Gzip(writer), "gzip"));
+            }
+        }
+
+        None
+    }
+}
+
+/// A writer appending to a buffer shared with the [`Encoder`] reading from it.
+#[derive(Clone, Default)]
+struct Output(Arc<Mutex<Vec<u8>>>);
+
+impl Output {
+    fn take(&self) -> Vec<u8> {
+        std::mem::take(&mut *self.0.lock().expect("unpoisoned output"))
+    }
+}
+
+impl Write for Output {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.0.lock().expect("unpoisoned output").extend_from_slice(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+/// A compressor writing to an [`Output`].
+enum Codec {
+    #[cfg(feature = "brotli_compression")]
+    Brotli(Box<CompressorWriter<Output>>),
+    #[cfg(feature = "gzip_compression")]
+    Gzip(GzEncoder<Output>),
+}
+
+impl Codec {
+    fn writer(&mut self) -> &mut dyn Write {
+        match self {
+            #[cfg(feature = "brotli_compression")]
+            Codec::Brotli(writer) => &mut **writer,
+            #[cfg(feature = "gzip_compression")]
+            Codec::Gzip(writer) => writer,
+        }
+    }
+
+    fn finish(self) -> io::Result<()> {
+        match self {
+            #[cfg(feature = "brotli_compression")]
+            Codec::Brotli(writer) => {
+                // Dropping the writer finishes the stream.
+                drop(writer);
+                Ok(())
+            }
             #[cfg(feature = "gzip_compression")]
-            {
-                if let Some(plain) = response.take_body() {
-                    let compressor = GzEncoder::new(plain.into_inner(), flate2::Compression::default());
+            Codec::Gzip(writer) => writer.finish().map(|_| ()),
+        }
+    }
+}
+
+/// Compresses the body read from `reader` as it's read.
+///
+/// Input is compressed as it becomes available. When `reader` has no more
+/// input available for the time being, the compressor is flushed so that
+/// streamed bodies, such as server-sent events, aren't held back.
+struct Encoder<R> {
+    reader: R,
+    codec: Option<Codec>,
+    output: Output,
+    flushed: bool,
+    buf: Vec<u8>,
+    pos: usize,
+}
+
+impl<R> Encoder<R> {
+    fn new(reader: R, codec: Codec, output: Output) -> Self {
+        Encoder { reader, codec: Some(codec), output, flushed: true, buf: vec![], pos: 0 }
+    }
+}
 
-                    CompressionUtils::set_body_and_encoding(response, compressor, Encoding::Gzip);
+impl<R: AsyncRead + Unpin> AsyncRead for Encoder<R> {
+    fn poll_read(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut [u8],
+    ) -> Poll<io::Result<usize>> {
+        let this = self.get_mut();
+        let mut chunk = [0; CHUNK_SIZE];
+        while this.pos == this.buf.len() {
+            let mut codec = match this.codec.take() {
+                Some(codec) => codec,
+                None => return Poll::Ready(Ok(0)),
+            };
+
+            let written = match Pin::new(&mut this.reader).poll_read(cx, &mut chunk) {
+                Poll::Ready(Ok(0)) => codec.finish(),
+                Poll::Ready(Ok(n)) => {
+                    this.flushed = false;
+                    let result = codec.writer().write_all(&chunk[..n]);
+                    this.codec = Some(codec);
+                    result
+                }
+                Poll::Ready(Err(e)) => return Poll::Ready(Err(e)),
+                Poll::Pending if this.flushed => {
+                    this.codec = Some(codec);
+                    return Poll::Pending;
                 }
+                Poll::Pending => {
+                    this.flushed = true;
+                    let result = codec.writer().flush();
+                    this.codec = Some(codec);
+                    result
+                }
+            };
+
+            if let Err(e) = written {
+                return Poll::Ready(Err(e));
             }
+
+            this.buf = this.output.take();
+            this.pos = 0;
         }
+
+        let n = std::cmp::min(buf.len(), this.buf.len() - this.pos);
+        buf[..n].copy_from_slice(&this.buf[this.pos..this.pos + n]);
+        this.pos += n;
+        Poll::Ready(Ok(n))
     }
 }
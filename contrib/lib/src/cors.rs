@@ -0,0 +1,357 @@
+//! Cross-Origin Resource Sharing (CORS) headers and preflight handling.
+//!
+//! See the [`Cors`] fairing for further details.
+//!
+//! # Enabling
+//!
+//! This module is only available when the `cors` feature is enabled. Enable it
+//! in `Cargo.toml` as follows:
+//!
+//! ```toml
+//! [dependencies.rocket_contrib]
+//! version = "0.5.0-dev"
+//! default-features = false
+//! features = ["cors"]
+//! ```
+
+use std::str::FromStr;
+use std::time::Duration;
+
+use rocket::{Request, Response, Rocket};
+use rocket::http::{Header, Method, Status, StatusClass};
+use rocket::fairing::{Fairing, Info, Kind};
+
+/// A [`Fairing`](../../rocket/fairing/trait.Fairing.html) that implements
+/// Cross-Origin Resource Sharing (CORS).
+///
+/// # Usage
+///
+/// To use `Cors`, construct an instance with [`Cors::default()`] or
+/// [`Cors::new()`], configure it, and attach it to your application's instance
+/// of `Rocket`:
+///
+/// ```rust
+/// # extern crate rocket;
+/// # extern crate rocket_contrib;
+/// use std::time::Duration;
+///
+/// use rocket::http::Method;
+/// use rocket_contrib::cors::Cors;
+///
+/// let cors = Cors::new()
+///     .allow_origin("https://rocket.rs")
+///     .allow_origin("https://api.rocket.rs")
+///     .allow_methods(vec![Method::Get, Method::Post])
+///     .allow_headers(vec!["Authorization", "Content-Type"])
+///     .allow_credentials(true)
+///     .max_age(Duration::from_secs(3600));
+///
+/// rocket::ignite()
+///     // ...
+///     .attach(cors)
+/// # ;
+/// ```
+///
+/// # Behavior
+///
+/// Requests without an `Origin` header, or with an `Origin` that isn't allowed,
+/// are left untouched; browsers reject the response of such a cross-origin
+/// request since it lacks CORS headers.
+///
+/// A preflight request is an `OPTIONS` request with an allowed `Origin` and an
+/// `Access-Control-Request-Method` header. If the requested method and all of
+/// the headers in `Access-Control-Request-Headers` are allowed, the fairing
+/// adds the `Access-Control-Allow-Methods`, `Access-Control-Allow-Headers`, and
+/// `Access-Control-Max-Age` headers to the response. Unless a route handled the
+/// preflight request successfully, the response is replaced with an empty
+/// `204 No Content` response.
+///
+/// Every response to a request from an allowed origin, including preflight
+/// requests, receives an `Access-Control-Allow-Origin` header and, when enabled,
+/// an `Access-Control-Allow-Credentials` header. The allowed origin is `*` if
+/// any origin is allowed. Otherwise, it's the request's origin, and `Origin` is
+/// added to the response's `Vary` header.
+///
+/// # Credentials
+///
+/// Credentials can only be allowed for an explicit list of origins: allowing
+/// credentialed requests from any origin would let every site act on behalf of
+/// a user. Attaching a `Cors` fairing that allows both any origin and
+/// credentials fails.
+pub struct Cors {
+    origins: Option<Vec<String>>,
+    methods: Vec<Method>,
+    headers: Option<Vec<String>>,
+    credentials: bool,
+    max_age: Option<Duration>,
+}
+
+impl Default for Cors {
+    /// Returns a `Cors` fairing that allows any origin to make `GET`, `HEAD`,
+    /// `POST`, `PUT`, `PATCH`, and `DELETE` requests without credentials or
+    /// additional headers.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket_contrib::cors::Cors;
+    ///
+    /// let cors = Cors::default();
+    /// ```
+    fn default() -> Self {
+        Cors::new()
+            .allow_any_origin()
+            .allow_methods(vec![
+                Method::Get, Method::Head, Method::Post,
+                Method::Put, Method::Patch, Method::Delete,
+            ])
+    }
+}
+
+impl Cors {
+    /// Returns a `Cors` fairing that allows nothing: no origins, no methods,
+    /// and no additional headers.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket_contrib::cors::Cors;
+    ///
+    /// let cors = Cors::new().allow_origin("https://rocket.rs");
+    /// ```
+    pub fn new() -> Self {
+        Cors {
+            origins: Some(vec![]),
+            methods: vec![],
+            headers: Some(vec![]),
+            credentials: false,
+            max_age: None,
+        }
+    }
+
+    /// Allows requests from `origin`, a scheme, host, and optional port such
+    /// as `https://rocket.rs:8000`. Origins are compared case-insensitively.
+    ///
+    /// This has no effect if any origin is allowed.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket_contrib::cors::Cors;
+    ///
+    /// let cors = Cors::new()
+    ///     .allow_origin("https://rocket.rs")
+    ///     .allow_origin("http://localhost:8000");
+    /// ```
+    pub fn allow_origin<S: Into<String>>(mut self, origin: S) -> Self {
+        if let Some(ref mut origins) = self.origins {
+            origins.push(origin.into());
+        }
+
+        self
+    }
+
+    /// Allows requests from any origin.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket_contrib::cors::Cors;
+    ///
+    /// let cors = Cors::new().allow_any_origin();
+    /// ```
+    pub fn allow_any_origin(mut self) -> Self {
+        self.origins = None;
+        self
+    }
+
+    /// Sets the methods cross-origin requests may use to `methods`, replacing
+    /// any previously allowed methods.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::http::Method;
+    /// use rocket_contrib::cors::Cors;
+    ///
+    /// let cors = Cors::default().allow_methods(vec![Method::Get, Method::Post]);
+    /// ```
+    pub fn allow_methods<I: IntoIterator<Item = Method>>(mut self, methods: I) -> Self {
+        self.methods = methods.into_iter().collect();
+        self
+    }
+
+    /// Sets the headers, beyond those always allowed by browsers, that
+    /// cross-origin requests may include to `headers`, replacing any
+    /// previously allowed headers. Headers are compared case-insensitively.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket_contrib::cors::Cors;
+    ///
+    /// let cors = Cors::default().allow_headers(vec!["Authorization", "X-Api-Key"]);
+    /// ```
+    pub fn allow_headers<I, S>(mut self, headers: I) -> Self
+        where I: IntoIterator<Item = S>, S: Into<String>
+    {
+        self.headers = Some(headers.into_iter().map(|h| h.into()).collect());
+        self
+    }
+
+    /// Allows cross-origin requests to include any headers.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket_contrib::cors::Cors;
+    ///
+    /// let cors = Cors::default().allow_any_header();
+    /// ```
+    pub fn allow_any_header(mut self) -> Self {
+        self.headers = None;
+        self
+    }
+
+    /// Sets whether cross-origin requests may include credentials such as
+    /// cookies. Disabled by default.
+    ///
+    /// Credentials require an explicit list of allowed origins. If any origin
+    /// is allowed, attaching the fairing fails.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket_contrib::cors::Cors;
+    ///
+    /// let cors = Cors::new()
+    ///     .allow_origin("https://rocket.rs")
+    ///     .allow_credentials(true);
+    /// ```
+    pub fn allow_credentials(mut self, enabled: bool) -> Self {
+        self.credentials = enabled;
+        self
+    }
+
+    /// Sets how long browsers may cache the result of a preflight request.
+    /// Browsers may cap the duration. When unset, browsers use their default.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use std::time::Duration;
+    /// use rocket_contrib::cors::Cors;
+    ///
+    /// let cors = Cors::default().max_age(Duration::from_secs(600));
+    /// ```
+    pub fn max_age(mut self, max_age: Duration) -> Self {
+        self.max_age = Some(max_age);
+        self
+    }
+
+    /// Returns `true` if `origin` is allowed.
+    fn allows_origin(&self, origin: &str) -> bool {
+        match self.origins {
+            Some(ref origins) => origins.iter().any(|o| o.eq_ignore_ascii_case(origin)),
+            None => true
+        }
+    }
+
+    /// Returns `true` if every header in the comma-separated `headers` is
+    /// allowed.
+    fn allows_headers(&self, headers: &str) -> bool {
+        let allowed = match self.headers {
+            Some(ref allowed) => allowed,
+            None => return true
+        };
+
+        headers.split(',')
+            .map(|header| header.trim())
+            .filter(|header| !header.is_empty())
+            .all(|header| allowed.iter().any(|h| h.eq_ignore_ascii_case(header)))
+    }
+
+    /// Adds the preflight headers to `response` if the preflight request `req`
+    /// requests an allowed method and allowed headers. Returns `false` if it
+    /// doesn't, in which case `response` is untouched.
+    fn preflight(&self, req: &Request<'_>, method: &str, response: &mut Response<'_>) -> bool {
+        let method_allowed = Method::from_str(method.trim())
+            .map_or(false, |method| self.methods.contains(&method));
+
+        let requested_headers = req.headers().get_one("Access-Control-Request-Headers");
+        if !method_allowed || !requested_headers.map_or(true, |h| self.allows_headers(h)) {
+            return false;
+        }
+
+        if response.status().class() != StatusClass::Success {
+            response.set_status(Status::NoContent);
+            response.remove_header("Content-Type");
+            response.take_body();
+        }
+
+        let methods = self.methods.iter()
+            .map(|method| method.as_str())
+            .collect::<Vec<_>>()
+            .join(", ");
+
+        response.set_raw_header("Access-Control-Allow-Methods", methods);
+
+        // When any header is allowed, echo the requested headers back.
+        let headers = match self.headers {
+            Some(ref headers) => headers.join(", "),
+            None => requested_headers.unwrap_or("").to_string(),
+        };
+
+        if !headers.is_empty() {
+            response.set_raw_header("Access-Control-Allow-Headers", headers);
+        }
+
+        if let Some(max_age) = self.max_age {
+            response.set_raw_header("Access-Control-Max-Age", max_age.as_secs().to_string());
+        }
+
+        true
+    }
+}
+
+#[rocket::async_trait]
+impl Fairing for Cors {
+    fn info(&self) -> Info {
+        Info { name: "CORS", kind: Kind::Attach | Kind::Response }
+    }
+
+    async fn on_attach(&self, rocket: Rocket) -> Result<Rocket, Rocket> {
+        if self.origins.is_none() && self.credentials {
+            error!("CORS: credentials can't be allowed for any origin.");
+            info_!("Allow specific origins with `Cors::allow_origin()`.");
+            return Err(rocket);
+        }
+
+        Ok(rocket)
+    }
+
+    async fn on_response<'r>(&self, req: &'r Request<'_>, res: &mut Response<'r>) {
+        let origin = match req.headers().get_one("Origin") {
+            Some(origin) if self.allows_origin(origin) => origin.to_string(),
+            _ => return
+        };
+
+        let requested_method = req.headers().get_one("Access-Control-Request-Method");
+        if let (Method::Options, Some(method)) = (req.method(), requested_method) {
+            if !self.preflight(req, method, res) {
+                return;
+            }
+        }
+
+        if self.origins.is_none() {
+            res.set_raw_header("Access-Control-Allow-Origin", "*");
+            return;
+        }
+
+        res.set_raw_header("Access-Control-Allow-Origin", origin);
+        res.adjoin_header(Header::new("Vary", "Origin"));
+        if self.credentials {
+            res.set_raw_header("Access-Control-Allow-Credentials", "true");
+        }
+    }
+}
@@ -0,0 +1,387 @@
+//! Content Security Policy headers with per-request script nonces.
+//!
+//! See the [`Csp`] fairing and the [`CspNonce`] request guard for further
+//! details.
+//!
+//! # Enabling
+//!
+//! This module is only available when the `csp` feature is enabled. Enable it
+//! in `Cargo.toml` as follows:
+//!
+//! ```toml
+//! [dependencies.rocket_contrib]
+//! version = "0.5.0-dev"
+//! default-features = false
+//! features = ["csp"]
+//! ```
+
+use std::fmt;
+
+use rocket::{Request, Response, Data};
+use rocket::fairing::{Fairing, Info, Kind};
+use rocket::request::{self, FromRequest};
+use rocket::http::Status;
+
+/// A fairing that sets a [Content Security Policy] header on every response.
+///
+/// A `Csp` is built from directives, each a name and a list of [`Source`]s.
+/// On every request, the fairing generates a random nonce, retrieved via the
+/// [`CspNonce`] request guard. Every [`Source::Nonce`] in the policy is
+/// rendered as `'nonce-<nonce>'` using that request's nonce, so scripts and
+/// styles carrying the nonce in their `nonce` attribute are allowed to run
+/// while injected ones are not. When the `handlebars_templates` or
+/// `tera_templates` features are also enabled, the nonce is added to the
+/// context of every [`Template`](crate::templates::Template) whose context is a
+/// map or struct without a `csp_nonce` field.
+///
+/// The header is not set if the response already contains it; a warning is
+/// emitted instead.
+///
+/// [Content Security Policy]: https://developer.mozilla.org/en-US/docs/Web/HTTP/CSP
+///
+/// # Example
+///
+/// ```rust,no_run
+/// # #[macro_use] extern crate rocket;
+/// # extern crate rocket_contrib;
+/// use rocket::response::content::Html;
+/// use rocket_contrib::csp::{Csp, CspNonce, Source};
+///
+/// #[get("/")]
+/// fn index(nonce: CspNonce) -> Html<String> {
+///     Html(format!(r#"<script nonce="{}">console.log("allowed")</script>"#, nonce))
+/// }
+///
+/// #[launch]
+/// fn rocket() -> rocket::Rocket {
+///     let csp = Csp::default()
+///         .img_src(vec![Source::SelfOrigin, "https://images.example.com".into()]);
+///
+///     rocket::ignite()
+///         .mount("/", routes![index])
+///         .attach(csp)
+/// }
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Csp {
+    directives: Vec<(String, Vec<Source>)>,
+    report_only: bool,
+}
+
+/// A source in a [`Csp`] directive.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum Source {
+    /// `'none'`: nothing is allowed.
+    None,
+    /// `'self'`: the document's own origin.
+    SelfOrigin,
+    /// `'unsafe-inline'`: any inline script or style.
+    UnsafeInline,
+    /// `'unsafe-eval'`: `eval()` and similar.
+    UnsafeEval,
+    /// `'strict-dynamic'`: scripts loaded by an already trusted script.
+    StrictDynamic,
+    /// `'nonce-<nonce>'`, with the nonce of the request being responded to.
+    Nonce,
+    /// A host or scheme source, such as `https://cdn.example.com` or `data:`.
+    Host(String),
+}
+
+/// A request guard for the Content Security Policy nonce of a request.
+///
+/// The nonce's `Display` implementation writes the nonce, ready to be used as
+/// the value of a `nonce` attribute. The guard fails with a status of `500` if
+/// the [`Csp`] fairing isn't attached.
+///
+/// # Example
+///
+/// ```rust
+/// # #[macro_use] extern crate rocket;
+/// # extern crate rocket_contrib;
+/// use rocket_contrib::csp::CspNonce;
+///
+/// #[get("/script")]
+/// fn script(nonce: CspNonce) -> String {
+///     format!(r#"<script nonce="{}" src="/app.js"></script>"#, nonce)
+/// }
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CspNonce(String);
+
+/// The nonce generated for a request, stored in request-local state.
+struct Issued(Option<String>);
+
+impl Default for Csp {
+    /// Returns a strict, nonce-based policy:
+    ///
+    /// ```text
+    /// default-src 'self'; script-src 'nonce-<nonce>' 'strict-dynamic';
+    /// object-src 'none'; base-uri 'none'
+    /// ```
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket_contrib::csp::Csp;
+    ///
+    /// let csp = Csp::default();
+    /// ```
+    fn default() -> Self {
+        Csp::new()
+            .default_src(vec![Source::SelfOrigin])
+            .script_src(vec![Source::Nonce, Source::StrictDynamic])
+            .object_src(vec![Source::None])
+            .base_uri(vec![Source::None])
+    }
+}
+
+impl Csp {
+    /// The name of the header set by the fairing.
+    pub const HEADER: &'static str = "Content-Security-Policy";
+
+    /// The name of the header set by the fairing in report-only mode.
+    pub const REPORT_ONLY_HEADER: &'static str = "Content-Security-Policy-Report-Only";
+
+    /// Returns a `Csp` without any directives. See [`Csp::default()`] for a
+    /// strict, nonce-based policy.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket_contrib::csp::{Csp, Source};
+    ///
+    /// let csp = Csp::new().default_src(vec![Source::SelfOrigin]);
+    /// ```
+    pub fn new() -> Self {
+        Csp { directives: vec![], report_only: false }
+    }
+
+    /// Sets the directive `name` to `sources`, replacing any existing
+    /// directive of the same name. Directives without sources, such as
+    /// `upgrade-insecure-requests`, are set with an empty `sources`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket_contrib::csp::{Csp, Source};
+    ///
+    /// let csp = Csp::default()
+    ///     .directive("worker-src", vec![Source::SelfOrigin])
+    ///     .directive("upgrade-insecure-requests", vec![]);
+    /// ```
+    pub fn directive<N: Into<String>>(mut self, name: N, sources: Vec<Source>) -> Self {
+        let name = name.into();
+        match self.directives.iter_mut().find(|(n, _)| n.eq_ignore_ascii_case(&name)) {
+            Some(directive) => directive.1 = sources,
+            None => self.directives.push((name, sources)),
+        }
+
+        self
+    }
+
+    /// Sets the `default-src` directive to `sources`.
+    pub fn default_src(self, sources: Vec<Source>) -> Self {
+        self.directive("default-src", sources)
+    }
+
+    /// Sets the `script-src` directive to `sources`.
+    pub fn script_src(self, sources: Vec<Source>) -> Self {
+        self.directive("script-src", sources)
+    }
+
+    /// Sets the `style-src` directive to `sources`.
+    pub fn style_src(self, sources: Vec<Source>) -> Self {
+        self.directive("style-src", sources)
+    }
+
+    /// Sets the `img-src` directive to `sources`.
+    pub fn img_src(self, sources: Vec<Source>) -> Self {
+        self.directive("img-src", sources)
+    }
+
+    /// Sets the `connect-src` directive to `sources`.
+    pub fn connect_src(self, sources: Vec<Source>) -> Self {
+        self.directive("connect-src", sources)
+    }
+
+    /// Sets the `font-src` directive to `sources`.
+    pub fn font_src(self, sources: Vec<Source>) -> Self {
+        self.directive("font-src", sources)
+    }
+
+    /// Sets the `object-src` directive to `sources`.
+    pub fn object_src(self, sources: Vec<Source>) -> Self {
+        self.directive("object-src", sources)
+    }
+
+    /// Sets the `base-uri` directive to `sources`.
+    pub fn base_uri(self, sources: Vec<Source>) -> Self {
+        self.directive("base-uri", sources)
+    }
+
+    /// Sets the `form-action` directive to `sources`.
+    pub fn form_action(self, sources: Vec<Source>) -> Self {
+        self.directive("form-action", sources)
+    }
+
+    /// Sets the `frame-ancestors` directive to `sources`.
+    pub fn frame_ancestors(self, sources: Vec<Source>) -> Self {
+        self.directive("frame-ancestors", sources)
+    }
+
+    /// Sets the `report-uri` directive, asking browsers to report violations
+    /// to `uri`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket_contrib::csp::Csp;
+    ///
+    /// let csp = Csp::default().report_uri("/csp-reports");
+    /// ```
+    pub fn report_uri<U: Into<String>>(self, uri: U) -> Self {
+        self.directive("report-uri", vec![Source::Host(uri.into())])
+    }
+
+    /// Sets whether the policy is only reported on, via the
+    /// [`Csp::REPORT_ONLY_HEADER`] header, instead of enforced. Defaults to
+    /// `false`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket_contrib::csp::Csp;
+    ///
+    /// let csp = Csp::default().report_uri("/csp-reports").report_only(true);
+    /// ```
+    pub fn report_only(mut self, report_only: bool) -> Self {
+        self.report_only = report_only;
+        self
+    }
+
+    /// Returns the value of the header for a request with nonce `nonce`.
+    fn render(&self, nonce: &str) -> String {
+        let directives = self.directives.iter().map(|(name, sources)| {
+            let mut directive = name.clone();
+            for source in sources {
+                directive.push(' ');
+                match source {
+                    Source::Nonce => directive.push_str(&format!("'nonce-{}'", nonce)),
+                    source => directive.push_str(&source.to_string()),
+                }
+            }
+
+            directive
+        });
+
+        directives.collect::<Vec<_>>().join("; ")
+    }
+}
+
+impl fmt::Display for Source {
+    /// Writes the source as it appears in a policy. `Source::Nonce` is written
+    /// as `'nonce'`; the fairing replaces it with the request's nonce.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Source::None => f.write_str("'none'"),
+            Source::SelfOrigin => f.write_str("'self'"),
+            Source::UnsafeInline => f.write_str("'unsafe-inline'"),
+            Source::UnsafeEval => f.write_str("'unsafe-eval'"),
+            Source::StrictDynamic => f.write_str("'strict-dynamic'"),
+            Source::Nonce => f.write_str("'nonce'"),
+            Source::Host(host) => f.write_str(host),
+        }
+    }
+}
+
+impl From<&str> for Source {
+    fn from(host: &str) -> Self {
+        Source::Host(host.to_string())
+    }
+}
+
+impl From<String> for Source {
+    fn from(host: String) -> Self {
+        Source::Host(host)
+    }
+}
+
+impl CspNonce {
+    /// Returns the nonce as a string.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # #[macro_use] extern crate rocket;
+    /// # extern crate rocket_contrib;
+    /// use rocket_contrib::csp::CspNonce;
+    ///
+    /// #[get("/style")]
+    /// fn style(nonce: CspNonce) -> String {
+    ///     format!(r#"<style nonce="{}">body {{ margin: 0 }}</style>"#, nonce.as_str())
+    /// }
+    /// ```
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    /// Returns the nonce generated for `req`, if the fairing is attached.
+    pub(crate) fn of<'a>(req: &'a Request<'_>) -> Option<&'a str> {
+        req.local_cache(|| Issued(None)).0.as_deref()
+    }
+}
+
+impl fmt::Display for CspNonce {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+#[rocket::async_trait]
+impl<'a, 'r> FromRequest<'a, 'r> for CspNonce {
+    type Error = ();
+
+    async fn from_request(req: &'a Request<'r>) -> request::Outcome<Self, ()> {
+        match CspNonce::of(req) {
+            Some(nonce) => request::Outcome::Success(CspNonce(nonce.to_string())),
+            None => {
+                error_!("Attempted to retrieve a CSP nonce without the `Csp` fairing.");
+                request::Outcome::Failure((Status::InternalServerError, ()))
+            }
+        }
+    }
+}
+
+/// Generates a new random nonce.
+fn generate_nonce() -> String {
+    rand::random::<[u8; 16]>().iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+#[rocket::async_trait]
+impl Fairing for Csp {
+    fn info(&self) -> Info {
+        Info { name: "Content Security Policy", kind: Kind::Request | Kind::Response }
+    }
+
+    async fn on_request(&self, req: &mut Request<'_>, _: &mut Data) {
+        req.local_cache(|| Issued(Some(generate_nonce())));
+    }
+
+    async fn on_response<'r>(&self, req: &'r Request<'_>, res: &mut Response<'r>) {
+        let name = match self.report_only {
+            true => Csp::REPORT_ONLY_HEADER,
+            false => Csp::HEADER,
+        };
+
+        if res.headers().contains(name) {
+            warn!("Content Security Policy: response contains a '{}' header.", name);
+            warn_!("Refusing to overwrite existing header.");
+            return;
+        }
+
+        // Requests that failed before the request fairing ran have no nonce.
+        let nonce = CspNonce::of(req).map(String::from).unwrap_or_else(generate_nonce);
+        res.set_raw_header(name, self.render(&nonce));
+    }
+}
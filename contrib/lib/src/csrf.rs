@@ -0,0 +1,288 @@
+//! Cross-Site Request Forgery (CSRF) protection.
+//!
+//! See the [`Csrf`] fairing and the [`CsrfToken`] request guard for further
+//! details.
+//!
+//! # Enabling
+//!
+//! This module is only available when the `csrf` feature is enabled. Enable it
+//! in `Cargo.toml` as follows:
+//!
+//! ```toml
+//! [dependencies.rocket_contrib]
+//! version = "0.5.0-dev"
+//! default-features = false
+//! features = ["csrf"]
+//! ```
+//!
+//! The feature enables Rocket's `secrets` feature: tokens are stored in private
+//! cookies, encrypted and authenticated with the configured `secret_key`.
+
+use std::fmt;
+
+use rocket::{Request, Data};
+use rocket::data::ToByteUnit;
+use rocket::fairing::{Fairing, Info, Kind};
+use rocket::request::{self, FromRequest, FormItems};
+use rocket::http::{Cookie, Method, Status};
+
+/// The methods of requests that must carry a valid token.
+const MUTATING_METHODS: &[Method] = &[Method::Post, Method::Put, Method::Patch, Method::Delete];
+
+/// A fairing that protects against cross-site request forgery.
+///
+/// On every request without one, the fairing issues a random token in a private
+/// cookie named [`Csrf::COOKIE`]. The token is retrieved via the [`CsrfToken`]
+/// request guard and embedded into pages, typically as a hidden form field or a
+/// `<meta>` tag read by JavaScript. Every `POST`, `PUT`, `PATCH`, and `DELETE`
+/// request must then submit the token back in one of two ways:
+///
+///   * As the value of the [`Csrf::HEADER`] header, or
+///   * As the value of the [`Csrf::FORM_FIELD`] field of a form. Forms are read
+///     up to the `forms` limit, as they are by [`Form`](rocket::request::Form).
+///
+/// Requests that don't submit a token matching the cookie's are failed with a
+/// `403 Forbidden` via [`Request::set_failure()`], so no route is invoked.
+/// Requests to the path prefixes registered via [`Csrf::exempt()`], such as
+/// webhooks, are never rejected.
+///
+/// # Example
+///
+/// ```rust,no_run
+/// # #[macro_use] extern crate rocket;
+/// # extern crate rocket_contrib;
+/// use rocket::request::LenientForm;
+/// use rocket::response::content::Html;
+/// use rocket_contrib::csrf::{Csrf, CsrfToken};
+///
+/// #[get("/comment")]
+/// fn comment_form(token: CsrfToken) -> Html<String> {
+///     Html(format!(r#"<form method="post" action="/comment">
+///         <input type="hidden" name="csrf_token" value="{}">
+///         <input type="text" name="body">
+///     </form>"#, token))
+/// }
+///
+/// #[derive(FromForm)]
+/// struct Comment {
+///     body: String,
+/// }
+///
+/// #[post("/comment", data = "<comment>")]
+/// fn comment(comment: LenientForm<Comment>) { /* .. */ }
+///
+/// #[post("/webhooks/deploy")]
+/// fn deploy() { /* .. */ }
+///
+/// #[launch]
+/// fn rocket() -> rocket::Rocket {
+///     rocket::ignite()
+///         .mount("/", routes![comment_form, comment, deploy])
+///         .attach(Csrf::new().exempt("/webhooks"))
+/// }
+/// ```
+///
+/// Since forms embedding the token contain a [`Csrf::FORM_FIELD`] field, they
+/// must either be parsed leniently, as above, or declare the field, for
+/// instance as `csrf_token: String`.
+#[derive(Debug, Clone, Default)]
+pub struct Csrf {
+    exempt: Vec<String>,
+}
+
+/// A request guard for the CSRF token issued to the client by the [`Csrf`]
+/// fairing.
+///
+/// The token's `Display` implementation writes the token, ready to be embedded
+/// into a page. The guard fails with a status of `500` if the [`Csrf`] fairing
+/// isn't attached.
+///
+/// # Example
+///
+/// ```rust
+/// # #[macro_use] extern crate rocket;
+/// # extern crate rocket_contrib;
+/// use rocket_contrib::csrf::CsrfToken;
+///
+/// #[get("/token")]
+/// fn token(token: CsrfToken) -> String {
+///     token.to_string()
+/// }
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CsrfToken(String);
+
+/// The token issued for a request, stored in request-local state.
+struct Issued(Option<String>);
+
+impl Csrf {
+    /// The name of the private cookie holding the token.
+    pub const COOKIE: &'static str = "csrf_token";
+
+    /// The name of the header a token may be submitted in.
+    pub const HEADER: &'static str = "X-CSRF-Token";
+
+    /// The name of the form field a token may be submitted in.
+    pub const FORM_FIELD: &'static str = "csrf_token";
+
+    /// Returns a `Csrf` fairing that exempts no paths.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket_contrib::csrf::Csrf;
+    ///
+    /// let csrf = Csrf::new();
+    /// ```
+    pub fn new() -> Self {
+        Csrf::default()
+    }
+
+    /// Exempts requests to `prefix`, or to any path below it, from token
+    /// validation.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket_contrib::csrf::Csrf;
+    ///
+    /// // Exempt `/webhooks`, `/webhooks/deploy`, and so on, but not `/webhooks-new`.
+    /// let csrf = Csrf::new().exempt("/webhooks");
+    /// ```
+    pub fn exempt<P: Into<String>>(mut self, prefix: P) -> Self {
+        let prefix = prefix.into();
+        let prefix = match prefix.trim_end_matches('/') {
+            "" => "/".to_string(),
+            trimmed => trimmed.to_string(),
+        };
+
+        self.exempt.push(prefix);
+        self
+    }
+
+    fn is_exempt(&self, path: &str) -> bool {
+        self.exempt.iter().any(|prefix| {
+            prefix == "/" || path == prefix
+                || (path.starts_with(&**prefix) && path[prefix.len()..].starts_with('/'))
+        })
+    }
+}
+
+impl CsrfToken {
+    /// Returns the token as a string.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # #[macro_use] extern crate rocket;
+    /// # extern crate rocket_contrib;
+    /// use rocket_contrib::csrf::CsrfToken;
+    ///
+    /// #[get("/token")]
+    /// fn token(token: CsrfToken) -> String {
+    ///     format!("<meta name=\"csrf-token\" content=\"{}\">", token.as_str())
+    /// }
+    /// ```
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+impl fmt::Display for CsrfToken {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+#[rocket::async_trait]
+impl<'a, 'r> FromRequest<'a, 'r> for CsrfToken {
+    type Error = ();
+
+    async fn from_request(req: &'a Request<'r>) -> request::Outcome<Self, ()> {
+        match req.local_cache(|| Issued(None)) {
+            Issued(Some(token)) => request::Outcome::Success(CsrfToken(token.clone())),
+            Issued(None) => {
+                error_!("Attempted to retrieve a CSRF token without the `Csrf` fairing.");
+                request::Outcome::Failure((Status::InternalServerError, ()))
+            }
+        }
+    }
+}
+
+/// Generates a new random token.
+fn generate_token() -> String {
+    rand::random::<[u8; 32]>().iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+/// Compares `a` and `b` in time independent of the position of any difference.
+fn tokens_match(a: &str, b: &str) -> bool {
+    a.len() == b.len() && a.bytes().zip(b.bytes()).fold(0, |acc, (x, y)| acc | (x ^ y)) == 0
+}
+
+/// Returns the token submitted in the form field of the form in `data`, if any.
+///
+/// The form is read up to the `forms` limit and `data` is replaced with what
+/// was read, so that the form remains available to the route.
+async fn form_token(req: &Request<'_>, data: &mut Data) -> Option<String> {
+    if !req.content_type().map_or(false, |ct| ct.is_form()) {
+        return None;
+    }
+
+    let limit = req.limits().get("forms").unwrap_or(32.kibibytes());
+    let body = std::mem::replace(data, Data::from(vec![]));
+    let bytes = match body.open(limit).stream_to_vec().await {
+        Ok(bytes) => bytes,
+        Err(e) => {
+            warn_!("Failed to read form for CSRF token: {}.", e);
+            return None;
+        }
+    };
+
+    let token = std::str::from_utf8(&bytes).ok().and_then(|form| {
+        FormItems::from(form)
+            .find(|item| item.key.as_str() == Csrf::FORM_FIELD)
+            .and_then(|item| item.value.url_decode().ok())
+    });
+
+    *data = Data::from(bytes);
+    token
+}
+
+#[rocket::async_trait]
+impl Fairing for Csrf {
+    fn info(&self) -> Info {
+        Info { name: "CSRF", kind: Kind::Request }
+    }
+
+    async fn on_request(&self, req: &mut Request<'_>, data: &mut Data) {
+        let existing = req.cookies().get_private(Csrf::COOKIE).map(|c| c.value().to_string());
+        let token = match existing {
+            Some(ref token) => token.clone(),
+            None => {
+                let token = generate_token();
+                req.cookies().add_private(Cookie::new(Csrf::COOKIE, token.clone()));
+                token
+            }
+        };
+
+        req.local_cache(|| Issued(Some(token)));
+        if !MUTATING_METHODS.contains(&req.method()) || self.is_exempt(req.uri().path()) {
+            return;
+        }
+
+        let submitted = match req.headers().get_one(Csrf::HEADER) {
+            Some(token) => Some(token.to_string()),
+            None => form_token(req, data).await,
+        };
+
+        let valid = match (existing, submitted) {
+            (Some(expected), Some(submitted)) => tokens_match(&expected, &submitted),
+            _ => false
+        };
+
+        if !valid {
+            warn_!("Rejecting request with a missing or invalid CSRF token.");
+            req.set_failure(Status::Forbidden);
+        }
+    }
+}
@@ -0,0 +1,181 @@
+//! Streaming CSV responses.
+//!
+//! See the [`Csv`](crate::csv::Csv) type for further details.
+//!
+//! # Enabling
+//!
+//! This module is only available when the `csv_export` feature is enabled.
+//! Enable it in `Cargo.toml` as follows:
+//!
+//! ```toml
+//! [dependencies.rocket_contrib]
+//! version = "0.5.0-dev"
+//! default-features = false
+//! features = ["csv_export"]
+//! ```
+
+use std::io;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+use tokio::io::AsyncRead;
+
+use rocket::request::Request;
+use rocket::response::{self, Download, Responder, Response};
+use rocket::http::ContentType;
+
+use serde::Serialize;
+
+/// A responder that streams rows as CSV.
+///
+/// A `Csv` wraps any value that can be turned into an iterator of rows, where
+/// each row implements [`Serialize`]. Rows are serialized one at a time as the
+/// response is written, so exports of large datasets need not be buffered in
+/// memory. Fields are quoted as necessary. When rows are structs, a header row
+/// with the field names is written first.
+///
+/// The Content-Type of the response is set to `text/csv`. If a filename is set
+/// via [`Csv::filename()`], the response is marked as an attachment with that
+/// name via [`Download`].
+///
+/// Serialization errors, which occur only after the response has begun, abort
+/// the response.
+///
+/// # Example
+///
+/// ```rust
+/// # #[macro_use] extern crate rocket;
+/// # extern crate rocket_contrib;
+/// use serde::Serialize;
+/// use rocket_contrib::csv::Csv;
+///
+/// #[derive(Serialize)]
+/// struct Record {
+///     id: usize,
+///     name: String,
+/// }
+///
+/// #[get("/export")]
+/// fn export() -> Csv<Vec<Record>> {
+///     let records = vec![
+///         Record { id: 1, name: "Bob".into() },
+///         Record { id: 2, name: "Alice, Jr.".into() },
+///     ];
+///
+///     Csv::new(records).filename("records.csv")
+/// }
+/// ```
+#[derive(Debug, Clone)]
+pub struct Csv<I> {
+    rows: I,
+    filename: Option<String>,
+}
+
+impl<I> Csv<I> {
+    /// Constructs a `Csv` responder that streams `rows`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket_contrib::csv::Csv;
+    ///
+    /// let csv = Csv::new(vec![(1, "one"), (2, "two")]);
+    /// ```
+    #[inline(always)]
+    pub fn new(rows: I) -> Csv<I> {
+        Csv { rows, filename: None }
+    }
+
+    /// Sets the name of the file the response should be downloaded as.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket_contrib::csv::Csv;
+    ///
+    /// let csv = Csv::new(vec![(1, "one"), (2, "two")]).filename("numbers.csv");
+    /// ```
+    #[inline(always)]
+    pub fn filename<S: Into<String>>(mut self, filename: S) -> Csv<I> {
+        self.filename = Some(filename.into());
+        self
+    }
+}
+
+/// An `AsyncRead` that serializes rows on demand.
+struct CsvReader<T> {
+    rows: Box<dyn Iterator<Item = T> + Send>,
+    buf: Vec<u8>,
+    pos: usize,
+    headers: bool,
+}
+
+impl<T: Serialize> CsvReader<T> {
+    /// Serializes the next row into `buf`. Returns `false` if there are no
+    /// rows remaining.
+    fn fill(&mut self) -> io::Result<bool> {
+        let row = match self.rows.next() {
+            Some(row) => row,
+            None => return Ok(false),
+        };
+
+        self.buf.clear();
+        self.pos = 0;
+
+        let mut writer = ::csv::WriterBuilder::new()
+            .has_headers(self.headers)
+            .from_writer(&mut self.buf);
+
+        writer.serialize(row).map_err(|e| io::Error::new(io::ErrorKind::Other, e))?;
+        writer.flush()?;
+        self.headers = false;
+        Ok(true)
+    }
+}
+
+impl<T: Serialize> AsyncRead for CsvReader<T> {
+    fn poll_read(
+        self: Pin<&mut Self>,
+        _: &mut Context<'_>,
+        buf: &mut [u8],
+    ) -> Poll<io::Result<usize>> {
+        let this = self.get_mut();
+        while this.pos == this.buf.len() {
+            match this.fill() {
+                Ok(true) => continue,
+                Ok(false) => return Poll::Ready(Ok(0)),
+                Err(e) => return Poll::Ready(Err(e)),
+            }
+        }
+
+        let n = std::cmp::min(buf.len(), this.buf.len() - this.pos);
+        buf[..n].copy_from_slice(&this.buf[this.pos..this.pos + n]);
+        this.pos += n;
+        Poll::Ready(Ok(n))
+    }
+}
+
+/// Streams the rows as CSV with a Content-Type of `text/csv` and, if a filename
+/// was set, an attachment Content-Disposition.
+impl<'r, I> Responder<'r, 'static> for Csv<I>
+    where I: IntoIterator, I::IntoIter: Send + 'static, I::Item: Serialize + 'static
+{
+    fn respond_to(self, req: &'r Request<'_>) -> response::Result<'static> {
+        let reader = CsvReader {
+            rows: Box::new(self.rows.into_iter()),
+            buf: Vec::new(),
+            pos: 0,
+            headers: true,
+        };
+
+        let response = Response::build()
+            .header(ContentType::CSV)
+            .streamed_body(reader)
+            .finalize();
+
+        match self.filename {
+            Some(filename) => Download::attachment(response, filename).respond_to(req),
+            None => Ok(response),
+        }
+    }
+}
@@ -806,7 +806,9 @@ impl<K: 'static, C: Poolable> ConnectionPool<K, C> {
     }
 
     #[inline]
-    pub async fn get_one(rocket: &rocket::Rocket) -> Option<Connection<K, C>> {
+    pub async fn get_one<P>(rocket: &rocket::Rocket<P>) -> Option<Connection<K, C>>
+        where P: rocket::Phase
+    {
         match rocket.state::<Self>() {
             Some(pool) => pool.get().await.ok(),
             None => None
@@ -814,7 +816,7 @@ impl<K: 'static, C: Poolable> ConnectionPool<K, C> {
     }
 
     #[inline]
-    pub async fn get_pool(rocket: &rocket::Rocket) -> Option<Self> {
+    pub async fn get_pool<P: rocket::Phase>(rocket: &rocket::Rocket<P>) -> Option<Self> {
         rocket.state::<Self>().map(|pool| pool.clone())
     }
 }
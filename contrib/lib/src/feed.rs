@@ -0,0 +1,474 @@
+//! RSS and Atom feed responses.
+//!
+//! See the [`Feed`](crate::feed::Feed) type for further details.
+//!
+//! # Enabling
+//!
+//! This module is only available when the `feed` feature is enabled. Enable it
+//! in `Cargo.toml` as follows:
+//!
+//! ```toml
+//! [dependencies.rocket_contrib]
+//! version = "0.5.0-dev"
+//! default-features = false
+//! features = ["feed"]
+//! ```
+
+use std::fmt::Write;
+
+use time::{OffsetDateTime, UtcOffset};
+
+use rocket::request::Request;
+use rocket::response::{self, Responder, content::Content};
+use rocket::http::ContentType;
+
+/// A responder for an RSS 2.0 or Atom 1.0 feed.
+///
+/// A `Feed` is built from a title, a link to the site it describes, and any
+/// number of [`Entry`] values, each an item in the feed. All text is escaped as
+/// necessary and dates are written in the format each standard requires.
+///
+///   * **RSS** feeds, created via [`Feed::rss()`], respond with a Content-Type
+///     of `application/rss+xml`.
+///   * **Atom** feeds, created via [`Feed::atom()`], respond with a
+///     Content-Type of `application/atom+xml`.
+///
+/// Dates are [`OffsetDateTime`] values from the `time` crate.
+///
+/// # Example
+///
+/// ```rust
+/// # #[macro_use] extern crate rocket;
+/// # extern crate rocket_contrib;
+/// use rocket_contrib::feed::{Feed, Entry};
+/// use time::OffsetDateTime;
+///
+/// #[get("/feed.xml")]
+/// fn feed() -> Feed {
+///     let published = OffsetDateTime::from_unix_timestamp(1593864000);
+///
+///     Feed::atom("Rocket Blog", "https://rocket.rs/blog")
+///         .description("News about Rocket.")
+///         .entry(Entry::new("Rocket 0.5", "https://rocket.rs/blog/0.5")
+///             .summary("Rocket 0.5 is async & stable.")
+///             .author("Sergio")
+///             .published(published))
+/// }
+/// ```
+#[derive(Debug, Clone)]
+pub struct Feed {
+    format: Format,
+    title: String,
+    link: String,
+    id: Option<String>,
+    description: Option<String>,
+    updated: Option<OffsetDateTime>,
+    entries: Vec<Entry>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Format {
+    Rss,
+    Atom,
+}
+
+/// An entry, or item, in a [`Feed`].
+///
+/// An entry's ID, used by clients to identify the entry, defaults to its link.
+/// When both a summary and content are set, RSS feeds include only the content.
+///
+/// # Example
+///
+/// ```rust
+/// use rocket_contrib::feed::Entry;
+///
+/// let entry = Entry::new("Hello", "https://example.com/hello")
+///     .id("urn:uuid:a5b2b9c0-3c9e-4c9e-9b2c-2b1f4c6e3d21")
+///     .content("<p>Hello, world!</p>");
+/// ```
+#[derive(Debug, Clone)]
+pub struct Entry {
+    title: String,
+    link: String,
+    id: Option<String>,
+    summary: Option<String>,
+    content: Option<String>,
+    author: Option<String>,
+    published: Option<OffsetDateTime>,
+    updated: Option<OffsetDateTime>,
+}
+
+impl Feed {
+    /// Returns an empty RSS 2.0 feed titled `title` for the site at `link`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket_contrib::feed::Feed;
+    ///
+    /// let feed = Feed::rss("Rocket Blog", "https://rocket.rs/blog");
+    /// ```
+    pub fn rss<T: Into<String>, L: Into<String>>(title: T, link: L) -> Feed {
+        Feed::new(Format::Rss, title.into(), link.into())
+    }
+
+    /// Returns an empty Atom 1.0 feed titled `title` for the site at `link`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket_contrib::feed::Feed;
+    ///
+    /// let feed = Feed::atom("Rocket Blog", "https://rocket.rs/blog");
+    /// ```
+    pub fn atom<T: Into<String>, L: Into<String>>(title: T, link: L) -> Feed {
+        Feed::new(Format::Atom, title.into(), link.into())
+    }
+
+    fn new(format: Format, title: String, link: String) -> Feed {
+        Feed {
+            format, title, link,
+            id: None, description: None, updated: None, entries: vec![]
+        }
+    }
+
+    /// Sets the feed's ID, which defaults to its link. Only used by Atom feeds.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket_contrib::feed::Feed;
+    ///
+    /// let feed = Feed::atom("Rocket Blog", "https://rocket.rs/blog")
+    ///     .id("urn:uuid:60a76c80-d399-11d9-b93c-0003939e0af6");
+    /// ```
+    pub fn id<S: Into<String>>(mut self, id: S) -> Self {
+        self.id = Some(id.into());
+        self
+    }
+
+    /// Sets the description, or subtitle, of the feed.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket_contrib::feed::Feed;
+    ///
+    /// let feed = Feed::rss("Rocket Blog", "https://rocket.rs/blog")
+    ///     .description("News about Rocket.");
+    /// ```
+    pub fn description<S: Into<String>>(mut self, description: S) -> Self {
+        self.description = Some(description.into());
+        self
+    }
+
+    /// Sets the time the feed was last updated. Defaults to the latest time
+    /// any entry was published or updated.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket_contrib::feed::Feed;
+    /// use time::OffsetDateTime;
+    ///
+    /// let feed = Feed::atom("Rocket Blog", "https://rocket.rs/blog")
+    ///     .updated(OffsetDateTime::from_unix_timestamp(1593864000));
+    /// ```
+    pub fn updated(mut self, updated: OffsetDateTime) -> Self {
+        self.updated = Some(updated);
+        self
+    }
+
+    /// Appends `entry` to the feed.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket_contrib::feed::{Feed, Entry};
+    ///
+    /// let feed = Feed::rss("Rocket Blog", "https://rocket.rs/blog")
+    ///     .entry(Entry::new("Rocket 0.5", "https://rocket.rs/blog/0.5"));
+    /// ```
+    pub fn entry(mut self, entry: Entry) -> Self {
+        self.entries.push(entry);
+        self
+    }
+
+    /// Appends every entry in `entries` to the feed.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket_contrib::feed::{Feed, Entry};
+    ///
+    /// let posts = vec![("First", "/first"), ("Second", "/second")];
+    /// let feed = Feed::rss("Posts", "https://example.com")
+    ///     .entries(posts.into_iter().map(|(title, link)| Entry::new(title, link)));
+    /// ```
+    pub fn entries<I: IntoIterator<Item = Entry>>(mut self, entries: I) -> Self {
+        self.entries.extend(entries);
+        self
+    }
+
+    fn last_updated(&self) -> Option<OffsetDateTime> {
+        self.updated.or_else(|| self.entries.iter().filter_map(|e| e.last_updated()).max())
+    }
+
+    fn to_rss(&self) -> String {
+        let mut xml = String::from("<?xml version=\"1.0\" encoding=\"utf-8\"?>\n");
+        xml.push_str("<rss version=\"2.0\"><channel>");
+        element(&mut xml, "title", &self.title);
+        element(&mut xml, "link", &self.link);
+        element(&mut xml, "description", self.description.as_deref().unwrap_or(""));
+        if let Some(updated) = self.last_updated() {
+            element(&mut xml, "lastBuildDate", &rfc2822(updated));
+        }
+
+        for entry in &self.entries {
+            xml.push_str("<item>");
+            element(&mut xml, "title", &entry.title);
+            element(&mut xml, "link", &entry.link);
+            let permalink = if entry.id.is_some() { "false" } else { "true" };
+            let guid = escape(entry.id());
+            let _ = write!(xml, "<guid isPermaLink=\"{}\">{}</guid>", permalink, guid);
+            if let Some(description) = entry.content.as_ref().or(entry.summary.as_ref()) {
+                element(&mut xml, "description", description);
+            }
+
+            if let Some(ref author) = entry.author {
+                element(&mut xml, "author", author);
+            }
+
+            if let Some(published) = entry.published.or(entry.updated) {
+                element(&mut xml, "pubDate", &rfc2822(published));
+            }
+
+            xml.push_str("</item>");
+        }
+
+        xml.push_str("</channel></rss>\n");
+        xml
+    }
+
+    fn to_atom(&self) -> String {
+        let updated = self.last_updated().unwrap_or_else(OffsetDateTime::unix_epoch);
+
+        let mut xml = String::from("<?xml version=\"1.0\" encoding=\"utf-8\"?>\n");
+        xml.push_str("<feed xmlns=\"http://www.w3.org/2005/Atom\">");
+        element(&mut xml, "title", &self.title);
+        if let Some(ref description) = self.description {
+            element(&mut xml, "subtitle", description);
+        }
+
+        let _ = write!(xml, "<link href=\"{}\"/>", escape(&self.link));
+        element(&mut xml, "id", self.id.as_ref().unwrap_or(&self.link));
+        element(&mut xml, "updated", &rfc3339(updated));
+        for entry in &self.entries {
+            xml.push_str("<entry>");
+            element(&mut xml, "title", &entry.title);
+            let _ = write!(xml, "<link href=\"{}\"/>", escape(&entry.link));
+            element(&mut xml, "id", entry.id());
+            element(&mut xml, "updated", &rfc3339(entry.last_updated().unwrap_or(updated)));
+            if let Some(published) = entry.published {
+                element(&mut xml, "published", &rfc3339(published));
+            }
+
+            if let Some(ref author) = entry.author {
+                xml.push_str("<author>");
+                element(&mut xml, "name", author);
+                xml.push_str("</author>");
+            }
+
+            if let Some(ref summary) = entry.summary {
+                element(&mut xml, "summary", summary);
+            }
+
+            if let Some(ref content) = entry.content {
+                let _ = write!(xml, "<content type=\"html\">{}</content>", escape(content));
+            }
+
+            xml.push_str("</entry>");
+        }
+
+        xml.push_str("</feed>\n");
+        xml
+    }
+}
+
+impl Entry {
+    /// Returns an entry titled `title` linking to `link`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket_contrib::feed::Entry;
+    ///
+    /// let entry = Entry::new("Hello", "https://example.com/hello");
+    /// ```
+    pub fn new<T: Into<String>, L: Into<String>>(title: T, link: L) -> Entry {
+        Entry {
+            title: title.into(), link: link.into(),
+            id: None, summary: None, content: None, author: None,
+            published: None, updated: None,
+        }
+    }
+
+    /// Sets the entry's ID, which defaults to its link.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket_contrib::feed::Entry;
+    ///
+    /// let entry = Entry::new("Hello", "https://example.com/hello")
+    ///     .id("urn:uuid:a5b2b9c0-3c9e-4c9e-9b2c-2b1f4c6e3d21");
+    /// ```
+    pub fn id<S: Into<String>>(mut self, id: S) -> Self {
+        self.id = Some(id.into());
+        self
+    }
+
+    /// Sets the entry's plain-text summary.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket_contrib::feed::Entry;
+    ///
+    /// let entry = Entry::new("Hello", "https://example.com/hello")
+    ///     .summary("A friendly greeting.");
+    /// ```
+    pub fn summary<S: Into<String>>(mut self, summary: S) -> Self {
+        self.summary = Some(summary.into());
+        self
+    }
+
+    /// Sets the entry's content, as HTML.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket_contrib::feed::Entry;
+    ///
+    /// let entry = Entry::new("Hello", "https://example.com/hello")
+    ///     .content("<p>Hello, world!</p>");
+    /// ```
+    pub fn content<S: Into<String>>(mut self, html: S) -> Self {
+        self.content = Some(html.into());
+        self
+    }
+
+    /// Sets the name of the entry's author.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket_contrib::feed::Entry;
+    ///
+    /// let entry = Entry::new("Hello", "https://example.com/hello")
+    ///     .author("Sergio");
+    /// ```
+    pub fn author<S: Into<String>>(mut self, author: S) -> Self {
+        self.author = Some(author.into());
+        self
+    }
+
+    /// Sets the time the entry was published.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket_contrib::feed::Entry;
+    /// use time::OffsetDateTime;
+    ///
+    /// let entry = Entry::new("Hello", "https://example.com/hello")
+    ///     .published(OffsetDateTime::from_unix_timestamp(1593864000));
+    /// ```
+    pub fn published(mut self, published: OffsetDateTime) -> Self {
+        self.published = Some(published);
+        self
+    }
+
+    /// Sets the time the entry was last updated. Defaults to the time it was
+    /// published.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket_contrib::feed::Entry;
+    /// use time::OffsetDateTime;
+    ///
+    /// let entry = Entry::new("Hello", "https://example.com/hello")
+    ///     .published(OffsetDateTime::from_unix_timestamp(1593864000))
+    ///     .updated(OffsetDateTime::from_unix_timestamp(1593950400));
+    /// ```
+    pub fn updated(mut self, updated: OffsetDateTime) -> Self {
+        self.updated = Some(updated);
+        self
+    }
+
+    fn id(&self) -> &str {
+        self.id.as_ref().unwrap_or(&self.link)
+    }
+
+    fn last_updated(&self) -> Option<OffsetDateTime> {
+        self.updated.or(self.published)
+    }
+}
+
+/// Escapes the XML special characters in `string`.
+fn escape(string: &str) -> String {
+    let mut escaped = String::with_capacity(string.len());
+    for c in string.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            c => escaped.push(c),
+        }
+    }
+
+    escaped
+}
+
+/// Writes `<name>text</name>` to `xml`, escaping `text`.
+fn element(xml: &mut String, name: &str, text: &str) {
+    let _ = write!(xml, "<{}>{}</{}>", name, escape(text), name);
+}
+
+/// Formats `date` per RFC 2822, as RSS requires: `Sat, 04 Jul 2020 12:00:00 +0000`.
+fn rfc2822(date: OffsetDateTime) -> String {
+    const DAYS: [&str; 7] = ["Mon", "Tue", "Wed", "Thu", "Fri", "Sat", "Sun"];
+    const MONTHS: [&str; 12] = [
+        "Jan", "Feb", "Mar", "Apr", "May", "Jun", "Jul", "Aug", "Sep", "Oct", "Nov", "Dec"
+    ];
+
+    let date = date.to_offset(UtcOffset::UTC);
+    format!("{}, {:02} {} {:04} {:02}:{:02}:{:02} +0000",
+        DAYS[date.weekday().number_days_from_monday() as usize], date.day(),
+        MONTHS[date.month() as usize - 1], date.year(),
+        date.hour(), date.minute(), date.second())
+}
+
+/// Formats `date` per RFC 3339, as Atom requires: `2020-07-04T12:00:00Z`.
+fn rfc3339(date: OffsetDateTime) -> String {
+    let date = date.to_offset(UtcOffset::UTC);
+    format!("{:04}-{:02}-{:02}T{:02}:{:02}:{:02}Z",
+        date.year(), date.month(), date.day(),
+        date.hour(), date.minute(), date.second())
+}
+
+/// Renders the feed with a Content-Type of `application/rss+xml` or
+/// `application/atom+xml`.
+impl<'r> Responder<'r, 'static> for Feed {
+    fn respond_to(self, req: &'r Request<'_>) -> response::Result<'static> {
+        let (content_type, xml) = match self.format {
+            Format::Rss => (ContentType::new("application", "rss+xml"), self.to_rss()),
+            Format::Atom => (ContentType::new("application", "atom+xml"), self.to_atom()),
+        };
+
+        Content(content_type, xml).respond_to(req)
+    }
+}
@@ -3,7 +3,7 @@ use std::sync::atomic::{AtomicBool, Ordering};
 
 use rocket::http::uncased::UncasedStr;
 use rocket::fairing::{Fairing, Info, Kind};
-use rocket::{Rocket, Request, Response};
+use rocket::{Rocket, Orbit, Request, Response};
 
 use crate::helmet::*;
 
@@ -201,7 +201,7 @@ impl Fairing for SpaceHelmet {
         self.apply(res);
     }
 
-    fn on_launch(&self, rocket: &Rocket) {
+    fn on_launch(&self, rocket: &Rocket<Orbit>) {
         if rocket.config().tls_enabled()
             && rocket.figment().profile() != rocket::Config::DEBUG_PROFILE
             && !self.is_enabled::<Hsts>()
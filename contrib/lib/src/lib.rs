@@ -19,11 +19,30 @@
 //! * [json*](type@json) - JSON (de)serialization
 //! * [serve*](serve) - Static File Serving
 //! * [msgpack](msgpack) - MessagePack (de)serialization
+//! * [protobuf](protobuf) - Protocol Buffers (de)serialization
+//! * [csv_export](csv) - Streaming CSV Responses
+//! * [process](process) - Streaming External Process Output
+//! * [archive](archive) - Streaming Zip and Tar Archives
+//! * [feed](feed) - RSS and Atom Feeds
+//! * [calendar](calendar) - iCalendar Files
+//! * [well_known](well_known) - Favicon and Well-Known Resource Handlers
+//! * [webdav](webdav) - WebDAV Methods and File-Backed Handler
 //! * [handlebars_templates](templates) - Handlebars Templating
 //! * [tera_templates](templates) - Tera Templating
 //! * [uuid](uuid) - UUID (de)serialization
 //! * [${database}_pool](databases) - Database Configuration and Pooling
 //! * [helmet](helmet) - Fairing for Security and Privacy Headers
+//! * [response_cache](response_cache) - Fairing for Caching Rendered Responses
+//! * [cors](cors) - Fairing for Cross-Origin Resource Sharing
+//! * [csrf](csrf) - Fairing for Cross-Site Request Forgery Protection
+//! * [csp](csp) - Fairing for Content Security Policies with Nonces
+//! * [canary](canary) - Fairing for A/B Tests and Canary Routing
+//! * [audit](audit) - Fairing for Auditing Response Headers
+//! * [timing](timing) - Fairing for Response Time Headers
+//! * [schema](schema) - Fairing for Validating JSON Bodies Against Schemas
+//! * [locale](locale) - Per-Request Locale Negotiation
+//! * [acme](acme) - Automatic TLS Certificates via ACME
+//! * [compression](compression) - Gzip and Brotli Response Compression
 //!
 //! The recommend way to include features from this crate via Rocket in your
 //! project is by adding a `[dependencies.rocket_contrib]` section to your
@@ -46,11 +65,28 @@
 #[cfg(feature="json")] #[macro_use] pub mod json;
 #[cfg(feature="serve")] pub mod serve;
 #[cfg(feature="msgpack")] pub mod msgpack;
+#[cfg(feature="protobuf")] pub mod protobuf;
+#[cfg(feature="csv_export")] pub mod csv;
+#[cfg(feature="process")] pub mod process;
+#[cfg(feature="archive")] pub mod archive;
+#[cfg(feature="feed")] pub mod feed;
+#[cfg(feature="calendar")] pub mod calendar;
+#[cfg(feature="well_known")] pub mod well_known;
+#[cfg(feature="webdav")] pub mod webdav;
 #[cfg(feature="templates")] pub mod templates;
 #[cfg(feature="uuid")] pub mod uuid;
 #[cfg(feature="databases")] pub mod databases;
 #[cfg(feature = "helmet")] pub mod helmet;
-// TODO.async: Migrate compression, reenable this, tests, and add to docs.
-//#[cfg(any(feature="brotli_compression", feature="gzip_compression"))] pub mod compression;
+#[cfg(feature = "response_cache")] pub mod response_cache;
+#[cfg(feature = "cors")] pub mod cors;
+#[cfg(feature = "csrf")] pub mod csrf;
+#[cfg(feature = "csp")] pub mod csp;
+#[cfg(feature = "canary")] pub mod canary;
+#[cfg(feature = "audit")] pub mod audit;
+#[cfg(feature = "timing")] pub mod timing;
+#[cfg(feature = "schema")] pub mod schema;
+#[cfg(feature = "locale")] pub mod locale;
+#[cfg(feature = "acme")] pub mod acme;
+#[cfg(any(feature="brotli_compression", feature="gzip_compression"))] pub mod compression;
 
 #[cfg(feature="databases")] #[doc(hidden)] pub use rocket_contrib_codegen::*;
@@ -0,0 +1,299 @@
+//! Per-request locale negotiation.
+//!
+//! See the [`Locale`] request guard and [`Locale::fairing()`] for further
+//! details.
+//!
+//! # Enabling
+//!
+//! This module is only available when the `locale` feature is enabled. Enable
+//! it in `Cargo.toml` as follows:
+//!
+//! ```toml
+//! [dependencies.rocket_contrib]
+//! version = "0.5.0-dev"
+//! default-features = false
+//! features = ["locale"]
+//! ```
+//!
+//! # Configuration
+//!
+//! The default locale and the supported locales are read from the `locale`
+//! configuration parameter, for instance, in `Rocket.toml`:
+//!
+//! ```toml
+//! [default.locale]
+//! default = "en"
+//! supported = ["en", "fr", "de-CH"]
+//! ```
+//!
+//! When `default` isn't set, it is `en`. When `supported` isn't set or is
+//! empty, any well-formed language tag is accepted.
+
+use std::cmp::Ordering;
+use std::fmt;
+
+use serde::{Serialize, Serializer, Deserialize};
+
+use rocket::{Rocket, Request, Response, Data};
+use rocket::fairing::{Fairing, Info, Kind};
+use rocket::request::{self, FromRequest};
+use rocket::http::{Cookie, Status};
+
+/// The locale of a request, as negotiated by the [`Locale::fairing()`].
+///
+/// The locale of a request is the first of the following that names a
+/// supported locale:
+///
+///   1. The [`Locale::QUERY_PARAM`] query parameter, as in `?lang=fr`.
+///   2. The [`Locale::COOKIE`] cookie.
+///   3. The tags in the `Accept-Language` header, in order of preference. A tag
+///      matches a supported locale exactly, ignoring case, or by its primary
+///      language: `fr-CH` matches `fr`, and `fr` matches `fr-FR`.
+///   4. The configured default locale.
+///
+/// A locale chosen explicitly via the query parameter is persisted in the
+/// [`Locale::COOKIE`] cookie, so it applies to subsequent requests. Responses
+/// to requests whose locale was used, via the guard or a template, carry a
+/// `Vary: Accept-Language` header.
+///
+/// As a request guard, `Locale` fails with a status of `500` if the fairing
+/// isn't attached. When the `handlebars_templates` or `tera_templates` features
+/// are also enabled, the locale is added to the context of every
+/// [`Template`](crate::templates::Template) whose context is a map or struct
+/// without a `locale` field.
+///
+/// # Example
+///
+/// ```rust
+/// # #[macro_use] extern crate rocket;
+/// # extern crate rocket_contrib;
+/// use rocket_contrib::locale::Locale;
+///
+/// #[get("/")]
+/// fn greet(locale: Locale) -> &'static str {
+///     match locale.language() {
+///         "fr" => "Bonjour !",
+///         "de" => "Hallo!",
+///         _ => "Hello!",
+///     }
+/// }
+///
+/// #[launch]
+/// fn rocket() -> rocket::Rocket {
+///     rocket::ignite()
+///         .mount("/", routes![greet])
+///         .attach(Locale::fairing())
+/// }
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Locale(String);
+
+/// The `locale` configuration parameter.
+#[derive(Debug, Clone, Deserialize)]
+struct LocaleConfig {
+    #[serde(default = "default_locale")]
+    default: String,
+    #[serde(default)]
+    supported: Vec<String>,
+}
+
+fn default_locale() -> String {
+    "en".into()
+}
+
+impl Default for LocaleConfig {
+    fn default() -> Self {
+        LocaleConfig { default: default_locale(), supported: vec![] }
+    }
+}
+
+/// The locale negotiated for a request, stored in request-local state.
+struct Negotiated(Option<Locale>);
+
+impl Locale {
+    /// The name of the query parameter that explicitly selects a locale.
+    pub const QUERY_PARAM: &'static str = "lang";
+
+    /// The name of the cookie persisting an explicitly selected locale.
+    pub const COOKIE: &'static str = "lang";
+
+    /// Returns the fairing that negotiates the locale of every request.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket_contrib::locale::Locale;
+    ///
+    /// let rocket = rocket::ignite().attach(Locale::fairing());
+    /// ```
+    pub fn fairing() -> impl Fairing {
+        LocaleFairing
+    }
+
+    /// Returns the locale's language tag, such as `en` or `de-CH`.
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    /// Returns the locale's primary language subtag: `de` for `de-CH`.
+    pub fn language(&self) -> &str {
+        primary(&self.0)
+    }
+
+    /// Returns the locale negotiated for `req`, if the fairing is attached.
+    pub(crate) fn of<'a>(req: &'a Request<'_>) -> Option<&'a Locale> {
+        req.local_cache(|| Negotiated(negotiate(req))).0.as_ref()
+    }
+}
+
+impl fmt::Display for Locale {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+impl Serialize for Locale {
+    fn serialize<S: Serializer>(&self, ser: S) -> Result<S::Ok, S::Error> {
+        ser.serialize_str(&self.0)
+    }
+}
+
+#[rocket::async_trait]
+impl<'a, 'r> FromRequest<'a, 'r> for Locale {
+    type Error = ();
+
+    async fn from_request(req: &'a Request<'r>) -> request::Outcome<Self, ()> {
+        match Locale::of(req) {
+            Some(locale) => request::Outcome::Success(locale.clone()),
+            None => {
+                error_!("Attempted to retrieve a locale without the locale fairing.");
+                info_!("To use `Locale`, you must attach `Locale::fairing()`.");
+                request::Outcome::Failure((Status::InternalServerError, ()))
+            }
+        }
+    }
+}
+
+/// Returns the primary language subtag of `tag`.
+fn primary(tag: &str) -> &str {
+    tag.split('-').next().unwrap_or(tag)
+}
+
+/// Returns whether `tag` is a well-formed language tag.
+fn is_well_formed(tag: &str) -> bool {
+    !tag.is_empty() && tag.len() <= 35
+        && tag.split('-').all(|sub| !sub.is_empty() && sub.len() <= 8)
+        && tag.bytes().all(|b| b.is_ascii_alphanumeric() || b == b'-')
+}
+
+impl LocaleConfig {
+    /// Returns the supported locale matching `tag` exactly or by language.
+    fn find(&self, tag: &str) -> Option<Locale> {
+        if !is_well_formed(tag) {
+            return None;
+        }
+
+        if self.supported.is_empty() {
+            return Some(Locale(tag.to_string()));
+        }
+
+        self.supported.iter()
+            .find(|s| s.eq_ignore_ascii_case(tag))
+            .or_else(|| self.supported.iter()
+                .find(|s| primary(s).eq_ignore_ascii_case(primary(tag))))
+            .map(|s| Locale(s.clone()))
+    }
+}
+
+/// Returns the tags in an `Accept-Language` header in order of preference.
+fn accepted(header: &str) -> Vec<&str> {
+    let mut tags: Vec<(&str, f32)> = header.split(',')
+        .filter_map(|item| {
+            let mut parts = item.split(';');
+            let tag = parts.next()?.trim();
+            let quality = match parts.find_map(|p| p.trim().strip_prefix("q=")) {
+                Some(q) => q.trim().parse().ok()?,
+                None => 1.0,
+            };
+
+            if tag.is_empty() || tag == "*" || quality <= 0.0 {
+                return None;
+            }
+
+            Some((tag, quality))
+        })
+        .collect();
+
+    // The sort is stable: tags of equal quality keep their order.
+    tags.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(Ordering::Equal));
+    tags.into_iter().map(|(tag, _)| tag).collect()
+}
+
+/// Negotiates the locale of `req`. Returns `None` without the fairing.
+fn negotiate(req: &Request<'_>) -> Option<Locale> {
+    let config = req.managed_state::<LocaleConfig>()?;
+    let explicit = req.get_query_value::<String>(Locale::QUERY_PARAM)
+        .and_then(|value| value.ok())
+        .and_then(|tag| config.find(&tag));
+
+    let locale = explicit
+        .or_else(|| req.cookies().get(Locale::COOKIE).and_then(|c| config.find(c.value())))
+        .or_else(|| req.headers().get_one("Accept-Language")
+            .and_then(|header| accepted(header).into_iter().find_map(|tag| config.find(tag))))
+        .unwrap_or_else(|| Locale(config.default.clone()));
+
+    Some(locale)
+}
+
+struct LocaleFairing;
+
+#[rocket::async_trait]
+impl Fairing for LocaleFairing {
+    fn info(&self) -> Info {
+        Info { name: "Locale", kind: Kind::Attach | Kind::Request | Kind::Response }
+    }
+
+    async fn on_attach(&self, rocket: Rocket) -> Result<Rocket, Rocket> {
+        let config = match rocket.figment().find_value("locale") {
+            Ok(_) => match rocket.figment().extract_inner::<LocaleConfig>("locale") {
+                Ok(config) => config,
+                Err(e) => {
+                    rocket::config::pretty_print_error(e);
+                    return Err(rocket);
+                }
+            },
+            Err(_) => LocaleConfig::default(),
+        };
+
+        if !config.supported.is_empty() && !config.supported.contains(&config.default) {
+            warn!("The default locale `{}` isn't a supported locale.", config.default);
+        }
+
+        Ok(rocket.manage(config))
+    }
+
+    async fn on_request(&self, req: &mut Request<'_>, _: &mut Data) {
+        let config = match req.managed_state::<LocaleConfig>() {
+            Some(config) => config,
+            None => return,
+        };
+
+        let explicit = req.get_query_value::<String>(Locale::QUERY_PARAM)
+            .and_then(|value| value.ok())
+            .and_then(|tag| config.find(&tag));
+
+        if let Some(locale) = explicit {
+            let current = req.cookies().get(Locale::COOKIE).map(|c| c.value().to_string());
+            if current.as_deref() != Some(locale.as_str()) {
+                req.cookies().add(Cookie::build(Locale::COOKIE, locale.0).permanent().finish());
+            }
+        }
+    }
+
+    async fn on_response<'r>(&self, req: &'r Request<'_>, res: &mut Response<'r>) {
+        // Only responses that used the locale depend on `Accept-Language`.
+        if req.local_cache(|| Negotiated(None)).0.is_some() {
+            res.adjoin_raw_header("Vary", "Accept-Language");
+        }
+    }
+}
@@ -0,0 +1,153 @@
+//! Streaming the output of external processes.
+//!
+//! See the [`CommandStream`](crate::process::CommandStream) type for further
+//! details.
+//!
+//! # Enabling
+//!
+//! This module is only available when the `process` feature is enabled. Enable
+//! it in `Cargo.toml` as follows:
+//!
+//! ```toml
+//! [dependencies.rocket_contrib]
+//! version = "0.5.0-dev"
+//! default-features = false
+//! features = ["process"]
+//! ```
+
+use std::io;
+use std::pin::Pin;
+use std::process::Stdio;
+use std::task::{Context, Poll};
+
+use tokio::io::AsyncRead;
+use tokio::process::{Child, ChildStdout, Command};
+
+use rocket::request::Request;
+use rocket::response::{self, Responder, Response};
+use rocket::http::Status;
+
+/// A responder that spawns a process and streams its standard output.
+///
+/// The process is spawned when the response is generated. Its standard output
+/// is streamed to the client as a chunked response as it is produced, so the
+/// output is never buffered in full, in memory or in a temporary file. The
+/// process's standard input is closed, and its standard error is inherited
+/// from the application.
+///
+/// By default, the process is killed if the response is dropped before the
+/// process's output ends, as happens when the client disconnects. To let the
+/// process run to completion instead, use
+/// [`CommandStream::kill_on_disconnect()`].
+///
+/// The response has no Content-Type. To set one, wrap the `CommandStream` in a
+/// [`Content`](rocket::response::content::Content) responder.
+///
+/// # Failure
+///
+/// If the process can't be spawned, an error is logged and the request fails
+/// with a status of `500`. The response is abandoned if reading from the
+/// process's standard output fails once the response has begun.
+///
+/// # Example
+///
+/// ```rust
+/// # #[macro_use] extern crate rocket;
+/// # extern crate rocket_contrib;
+/// use std::process::Command;
+/// use rocket::response::content::Plain;
+/// use rocket_contrib::process::CommandStream;
+///
+/// #[get("/logs")]
+/// fn logs() -> Plain<CommandStream> {
+///     let mut command = Command::new("tail");
+///     command.args(&["-n", "100", "/var/log/app.log"]);
+///     Plain(CommandStream::new(command))
+/// }
+/// ```
+#[derive(Debug)]
+pub struct CommandStream {
+    command: Command,
+    kill_on_disconnect: bool,
+}
+
+impl CommandStream {
+    /// Constructs a `CommandStream` responder that spawns `command` and
+    /// streams its standard output.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use std::process::Command;
+    /// use rocket_contrib::process::CommandStream;
+    ///
+    /// let mut command = Command::new("echo");
+    /// command.arg("Hello, world!");
+    /// let stream = CommandStream::new(command);
+    /// ```
+    #[inline(always)]
+    pub fn new(command: std::process::Command) -> CommandStream {
+        CommandStream { command: command.into(), kill_on_disconnect: true }
+    }
+
+    /// Sets whether the process is killed if the response is dropped before
+    /// the process's output ends, as happens when the client disconnects.
+    /// Enabled by default.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use std::process::Command;
+    /// use rocket_contrib::process::CommandStream;
+    ///
+    /// let command = Command::new("./generate-report");
+    /// let stream = CommandStream::new(command).kill_on_disconnect(false);
+    /// ```
+    #[inline(always)]
+    pub fn kill_on_disconnect(mut self, enabled: bool) -> CommandStream {
+        self.kill_on_disconnect = enabled;
+        self
+    }
+}
+
+/// An `AsyncRead` over the standard output of a process that owns the process,
+/// so that dropping the reader drops, and possibly kills, the process.
+struct ProcessReader {
+    _child: Child,
+    stdout: ChildStdout,
+}
+
+impl AsyncRead for ProcessReader {
+    fn poll_read(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut [u8],
+    ) -> Poll<io::Result<usize>> {
+        Pin::new(&mut self.stdout).poll_read(cx, buf)
+    }
+}
+
+/// Spawns the process and streams its standard output as a chunked response.
+impl<'r> Responder<'r, 'static> for CommandStream {
+    fn respond_to(mut self, _: &'r Request<'_>) -> response::Result<'static> {
+        let spawned = self.command
+            .stdin(Stdio::null())
+            .stdout(Stdio::piped())
+            .kill_on_drop(self.kill_on_disconnect)
+            .spawn();
+
+        let mut child = spawned.map_err(|e| {
+            error_!("Failed to spawn process {:?}: {}", self.command, e);
+            Status::InternalServerError
+        })?;
+
+        let stdout = child.stdout.take().ok_or_else(|| {
+            error_!("Process {:?} has no standard output.", self.command);
+            Status::InternalServerError
+        })?;
+
+        Response::build()
+            .streamed_body(ProcessReader { _child: child, stdout })
+            .ok()
+    }
+}
@@ -0,0 +1,192 @@
+//! Automatic Protocol Buffers (de)serialization support.
+//!
+//! See the [`Protobuf`](crate::protobuf::Protobuf) type for further details.
+//!
+//! # Enabling
+//!
+//! This module is only available when the `protobuf` feature is enabled. Enable
+//! it in `Cargo.toml` as follows:
+//!
+//! ```toml
+//! [dependencies.rocket_contrib]
+//! version = "0.5.0-dev"
+//! default-features = false
+//! features = ["protobuf"]
+//! ```
+
+use std::io;
+use std::ops::{Deref, DerefMut};
+
+use tokio::io::AsyncReadExt;
+
+use rocket::request::Request;
+use rocket::outcome::Outcome::*;
+use rocket::data::{Data, ByteUnit, FromData, Outcome};
+use rocket::response::{self, content, Responder};
+use rocket::http::{ContentType, Status};
+
+pub use prost::{Message, DecodeError};
+
+/// The `Protobuf` type: implements [`FromData`] and [`Responder`], allowing you
+/// to easily consume and respond with Protocol Buffers messages.
+///
+/// ## Receiving Protobuf
+///
+/// If you're receiving Protocol Buffers data, simply add a `data` parameter to
+/// your route arguments and ensure the type of the parameter is a
+/// `Protobuf<T>`, where `T` is a [`prost`] [`Message`]. The message is decoded
+/// from the HTTP request body.
+///
+/// ```rust
+/// # #[macro_use] extern crate rocket;
+/// # extern crate rocket_contrib;
+/// # type User = ();
+/// use rocket_contrib::protobuf::Protobuf;
+///
+/// #[post("/users", format = "application/x-protobuf", data = "<user>")]
+/// fn new_user(user: Protobuf<User>) {
+///     /* ... */
+/// }
+/// ```
+///
+/// You don't _need_ to use `format = "application/x-protobuf"`, but it _may_
+/// be what you want. Using the format means that any request that doesn't
+/// specify "application/x-protobuf" as its `Content-Type` will not be routed to
+/// this handler.
+///
+/// If the body exceeds the size limit, the request fails with a status of
+/// `413 Payload Too Large`. If the body is not a valid encoding of `T`, the
+/// request fails with a status of `422 Unprocessable Entity`.
+///
+/// ## Sending Protobuf
+///
+/// If you're responding with Protocol Buffers data, return a `Protobuf<T>`
+/// type, where `T` is a [`Message`]. The content type of the response is set to
+/// `application/x-protobuf` automatically.
+///
+/// ```rust
+/// # #[macro_use] extern crate rocket;
+/// # extern crate rocket_contrib;
+/// # type User = ();
+/// use rocket_contrib::protobuf::Protobuf;
+///
+/// #[get("/users/<id>")]
+/// fn user(id: usize) -> Protobuf<User> {
+///     let user_from_id = User::default();
+///     /* ... */
+///     Protobuf(user_from_id)
+/// }
+/// ```
+///
+/// ## Incoming Data Limits
+///
+/// The default size limit for incoming Protocol Buffers data is 1MiB. Setting a
+/// limit protects your application from denial of service (DOS) attacks and
+/// from resource exhaustion through high memory consumption. The limit can be
+/// increased by setting the `limits.protobuf` configuration parameter. For
+/// instance, to increase the limit to 5MiB for all environments, you may add
+/// the following to your `Rocket.toml`:
+///
+/// ```toml
+/// [global.limits]
+/// protobuf = 5242880
+/// ```
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Protobuf<T>(pub T);
+
+impl<T> Protobuf<T> {
+    /// Consumes the `Protobuf` wrapper and returns the wrapped item.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use rocket_contrib::protobuf::Protobuf;
+    /// let string = "Hello".to_string();
+    /// let my_protobuf = Protobuf(string);
+    /// assert_eq!(my_protobuf.into_inner(), "Hello".to_string());
+    /// ```
+    #[inline(always)]
+    pub fn into_inner(self) -> T {
+        self.0
+    }
+}
+
+/// Error returned by the [`Protobuf`] guard when incoming data fails to be
+/// read or decoded.
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum Error {
+    /// An I/O error occurred while reading the incoming request data.
+    Io(io::Error),
+
+    /// The incoming request data exceeded the configured size limit.
+    TooLarge,
+
+    /// The client's data was received successfully but failed to decode as a
+    /// valid message.
+    Decode(DecodeError),
+}
+
+const DEFAULT_LIMIT: ByteUnit = ByteUnit::Mebibyte(1);
+
+#[rocket::async_trait]
+impl<T: Message + Default> FromData for Protobuf<T> {
+    type Error = Error;
+
+    async fn from_data(r: &Request<'_>, d: Data) -> Outcome<Self, Self::Error> {
+        let size_limit = r.limits().get("protobuf").unwrap_or(DEFAULT_LIMIT);
+
+        // Read one byte past the limit to detect bodies exceeding it.
+        let mut buf = Vec::new();
+        let mut reader = d.open(ByteUnit::from(size_limit.as_u64() + 1));
+        if let Err(e) = reader.read_to_end(&mut buf).await {
+            return Failure((Status::BadRequest, Error::Io(e)));
+        }
+
+        if buf.len() as u64 > size_limit.as_u64() {
+            error_!("Protobuf body exceeds the limit of {}.", size_limit);
+            return Failure((Status::PayloadTooLarge, Error::TooLarge));
+        }
+
+        match T::decode(&buf[..]) {
+            Ok(val) => Success(Protobuf(val)),
+            Err(e) => {
+                error_!("Couldn't decode Protobuf body: {:?}", e);
+                Failure((Status::UnprocessableEntity, Error::Decode(e)))
+            }
+        }
+    }
+}
+
+/// Encodes the wrapped message. Returns a response with Content-Type
+/// `application/x-protobuf` and a fixed-size body with the encoding. If
+/// encoding fails, an `Err` of `Status::InternalServerError` is returned.
+impl<'r, T: Message> Responder<'r, 'static> for Protobuf<T> {
+    fn respond_to(self, req: &'r Request<'_>) -> response::Result<'static> {
+        let mut buf = Vec::with_capacity(self.0.encoded_len());
+        self.0.encode(&mut buf)
+            .map_err(|e| {
+                error_!("Protobuf failed to encode: {:?}", e);
+                Status::InternalServerError
+            })?;
+
+        let content_type = ContentType::new("application", "x-protobuf");
+        content::Content(content_type, buf).respond_to(req)
+    }
+}
+
+impl<T> Deref for Protobuf<T> {
+    type Target = T;
+
+    #[inline(always)]
+    fn deref(&self) -> &T {
+        &self.0
+    }
+}
+
+impl<T> DerefMut for Protobuf<T> {
+    #[inline(always)]
+    fn deref_mut(&mut self) -> &mut T {
+        &mut self.0
+    }
+}
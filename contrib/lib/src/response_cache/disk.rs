@@ -0,0 +1,300 @@
+use std::fs;
+use std::io::{self, Read};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use rocket::http::{Header, Status};
+
+use crate::response_cache::{CacheStore, CacheKey, CachedResponse};
+use crate::response_cache::store::fnv1a;
+
+/// A [`CacheStore`] that stores entries as files in a directory.
+///
+/// Each entry is stored in its own file, named after a hash of its key, in the
+/// directory passed to [`DiskStore::new()`]. Entries survive restarts of the
+/// application and may be shared by several instances of it that have access
+/// to the same directory. Files that cannot be read are ignored and replaced.
+///
+/// # Example
+///
+/// ```rust
+/// use rocket_contrib::response_cache::{ResponseCache, DiskStore};
+///
+/// # let dir = std::env::temp_dir().join("rocket-disk-store-doc");
+/// let cache = ResponseCache::with_store(DiskStore::new(dir).unwrap());
+/// ```
+pub struct DiskStore {
+    root: PathBuf,
+}
+
+/// Magic bytes at the start of every entry file, including a format version.
+const MAGIC: &[u8; 4] = b"RRC1";
+
+/// Extension of entry files.
+const EXTENSION: &str = "entry";
+
+impl DiskStore {
+    /// Returns a `DiskStore` storing entries in `root`, creating the directory
+    /// if it does not exist.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `root` cannot be created.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket_contrib::response_cache::DiskStore;
+    ///
+    /// # let dir = std::env::temp_dir().join("rocket-disk-store-doc");
+    /// let store = DiskStore::new(dir).unwrap();
+    /// ```
+    pub fn new<P: AsRef<Path>>(root: P) -> io::Result<Self> {
+        fs::create_dir_all(root.as_ref())?;
+        Ok(DiskStore { root: root.as_ref().to_path_buf() })
+    }
+
+    fn path_for(&self, key: &CacheKey) -> PathBuf {
+        let mut buf = vec![];
+        write_key(&mut buf, key);
+        self.root.join(format!("{:016x}.{}", fnv1a(&buf), EXTENSION))
+    }
+
+    async fn entries(&self) -> Vec<PathBuf> {
+        let mut paths = vec![];
+        if let Ok(mut dir) = tokio::fs::read_dir(&self.root).await {
+            while let Ok(Some(entry)) = dir.next_entry().await {
+                let path = entry.path();
+                if path.extension().map_or(false, |ext| ext == EXTENSION) {
+                    paths.push(path);
+                }
+            }
+        }
+
+        paths
+    }
+
+    async fn read(path: &Path) -> io::Result<(CacheKey, CachedResponse, SystemTime)> {
+        let bytes = tokio::fs::read(path).await?;
+        let mut reader = &bytes[..];
+
+        let mut magic = [0u8; 4];
+        reader.read_exact(&mut magic)?;
+        if &magic != MAGIC {
+            return Err(invalid("unknown entry format"));
+        }
+
+        let expires = UNIX_EPOCH + Duration::from_secs(read_u64(&mut reader)?);
+        let key = CacheKey {
+            path: read_str(&mut reader)?,
+            query: read_opt_str(&mut reader)?,
+            vary: (0..read_u64(&mut reader)?)
+                .map(|_| read_opt_str(&mut reader))
+                .collect::<io::Result<_>>()?,
+        };
+
+        let code = read_u64(&mut reader)? as u16;
+        let status = Status::from_code(code).unwrap_or_else(|| Status::new(code, ""));
+        let etag = read_str(&mut reader)?;
+        let headers = (0..read_u64(&mut reader)?)
+            .map(|_| -> io::Result<_> {
+                Ok(Header::new(read_str(&mut reader)?, read_str(&mut reader)?))
+            })
+            .collect::<io::Result<_>>()?;
+
+        let body = read_bytes(&mut reader)?;
+        Ok((key, CachedResponse { status, headers, body, etag }, expires))
+    }
+
+    async fn write(path: &Path, key: &CacheKey, response: &CachedResponse, expires: SystemTime)
+        -> io::Result<()>
+    {
+        static TEMPS: AtomicUsize = AtomicUsize::new(0);
+
+        let expires = expires.duration_since(UNIX_EPOCH).unwrap_or_default().as_secs();
+
+        let mut buf = MAGIC.to_vec();
+        write_u64(&mut buf, expires);
+        write_key(&mut buf, key);
+        write_u64(&mut buf, response.status.code as u64);
+        write_bytes(&mut buf, response.etag.as_bytes());
+        write_u64(&mut buf, response.headers.len() as u64);
+        for header in &response.headers {
+            write_bytes(&mut buf, header.name().as_str().as_bytes());
+            write_bytes(&mut buf, header.value().as_bytes());
+        }
+
+        write_bytes(&mut buf, &response.body);
+
+        // Write to a temporary file first so readers never see partial entries.
+        // The name is unique to this process and write so that concurrent
+        // writers, here or in another instance, never share a temporary file.
+        let id = TEMPS.fetch_add(1, Ordering::Relaxed);
+        let tmp = path.with_extension(format!("{}-{}.tmp", std::process::id(), id));
+        let result = match tokio::fs::write(&tmp, &buf).await {
+            Ok(()) => tokio::fs::rename(&tmp, path).await,
+            Err(e) => Err(e),
+        };
+
+        if result.is_err() {
+            let _ = tokio::fs::remove_file(&tmp).await;
+        }
+
+        result
+    }
+}
+
+#[rocket::async_trait]
+impl CacheStore for DiskStore {
+    async fn get(&self, key: &CacheKey) -> Option<Arc<CachedResponse>> {
+        let path = self.path_for(key);
+        let (stored_key, response, expires) = Self::read(&path).await.ok()?;
+        if &stored_key != key {
+            return None;
+        }
+
+        if expires <= SystemTime::now() {
+            let _ = tokio::fs::remove_file(&path).await;
+            return None;
+        }
+
+        Some(Arc::new(response))
+    }
+
+    async fn put(&self, key: CacheKey, response: CachedResponse, ttl: Duration) {
+        let path = self.path_for(&key);
+        if let Err(e) = Self::write(&path, &key, &response, SystemTime::now() + ttl).await {
+            warn_!("Failed to write cache entry {:?}: {}", path, e);
+        }
+    }
+
+    async fn invalidate(&self, predicate: &(dyn Fn(&CacheKey) -> bool + Sync)) {
+        for path in self.entries().await {
+            let stale = match Self::read(&path).await {
+                Ok((key, _, _)) => predicate(&key),
+                Err(_) => true,
+            };
+
+            if stale {
+                let _ = tokio::fs::remove_file(&path).await;
+            }
+        }
+    }
+
+    async fn len(&self) -> usize {
+        self.entries().await.len()
+    }
+}
+
+fn write_key(buf: &mut Vec<u8>, key: &CacheKey) {
+    write_bytes(buf, key.path.as_bytes());
+    write_opt_str(buf, key.query.as_deref());
+    write_u64(buf, key.vary.len() as u64);
+    for value in &key.vary {
+        write_opt_str(buf, value.as_deref());
+    }
+}
+
+fn invalid(msg: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, msg)
+}
+
+fn write_u64(buf: &mut Vec<u8>, n: u64) {
+    buf.extend_from_slice(&n.to_le_bytes());
+}
+
+fn write_bytes(buf: &mut Vec<u8>, bytes: &[u8]) {
+    write_u64(buf, bytes.len() as u64);
+    buf.extend_from_slice(bytes);
+}
+
+fn write_opt_str(buf: &mut Vec<u8>, string: Option<&str>) {
+    match string {
+        Some(string) => { buf.push(1); write_bytes(buf, string.as_bytes()); }
+        None => buf.push(0),
+    }
+}
+
+fn read_u64(reader: &mut &[u8]) -> io::Result<u64> {
+    let mut bytes = [0u8; 8];
+    reader.read_exact(&mut bytes)?;
+    Ok(u64::from_le_bytes(bytes))
+}
+
+fn read_bytes<'a>(reader: &mut &'a [u8]) -> io::Result<Vec<u8>> {
+    let len = read_u64(reader)?;
+    if len > reader.len() as u64 {
+        return Err(invalid("truncated entry"));
+    }
+
+    let (bytes, rest) = (*reader).split_at(len as usize);
+    *reader = rest;
+    Ok(bytes.to_vec())
+}
+
+fn read_str(reader: &mut &[u8]) -> io::Result<String> {
+    String::from_utf8(read_bytes(reader)?).map_err(|_| invalid("invalid UTF-8 in entry"))
+}
+
+fn read_opt_str(reader: &mut &[u8]) -> io::Result<Option<String>> {
+    let mut flag = [0u8; 1];
+    reader.read_exact(&mut flag)?;
+    match flag[0] {
+        0 => Ok(None),
+        _ => read_str(reader).map(Some),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use rocket::http::{Header, Status};
+    use super::*;
+
+    fn key(path: &str, vary: Option<&str>) -> CacheKey {
+        let vary = vec![vary.map(|s| s.into())];
+        CacheKey { path: path.into(), query: Some("a=b".into()), vary }
+    }
+
+    #[rocket::async_test]
+    async fn test_round_trip() {
+        let dir = std::env::temp_dir().join(format!("rocket-disk-store-{}", std::process::id()));
+        let store = DiskStore::new(&dir).unwrap();
+        let ttl = Duration::from_secs(60);
+
+        let response = CachedResponse {
+            status: Status::Ok,
+            headers: vec![Header::new("Content-Type", "text/plain; charset=utf-8")],
+            body: b"Hello, world!".to_vec(),
+            etag: "\"abc\"".into(),
+        };
+
+        store.put(key("/a", Some("fr")), response.clone(), ttl).await;
+        store.put(key("/b", None), response, Duration::from_secs(0)).await;
+        assert_eq!(store.len().await, 2);
+
+        let cached = store.get(&key("/a", Some("fr"))).await.unwrap();
+        assert_eq!(cached.status, Status::Ok);
+        assert_eq!(cached.headers[0].value(), "text/plain; charset=utf-8");
+        assert_eq!(cached.body, b"Hello, world!");
+        assert_eq!(cached.etag, "\"abc\"");
+
+        assert!(store.get(&key("/a", None)).await.is_none());
+        assert!(store.get(&key("/b", None)).await.is_none());
+        assert_eq!(store.len().await, 1);
+
+        store.invalidate(&|key| key.path == "/a").await;
+        assert!(store.is_empty().await);
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_stable_file_names() {
+        let store = DiskStore { root: PathBuf::from("/cache") };
+        let path = store.path_for(&key("/a", None));
+        assert_eq!(path, Path::new("/cache/f6d9a687bfd5724e.entry"));
+    }
+}
@@ -0,0 +1,417 @@
+use std::io::Cursor;
+use std::sync::Arc;
+use std::time::Duration;
+
+use rocket::{Rocket, Request, Response, Data, Route};
+use rocket::fairing::{Fairing, Info, Kind};
+use rocket::handler::{Handler, Outcome};
+use rocket::http::{Header, Method, Status};
+use rocket::http::uri::Origin;
+
+use crate::response_cache::{CacheStore, CacheKey, CachedResponse, MemoryStore};
+use crate::response_cache::store::fnv1a;
+
+/// The path of the internal route that serves cache hits.
+const HIT_PATH: &str = "/__rocket_contrib/response_cache";
+
+/// A fairing that caches rendered responses for configured paths.
+///
+/// A `ResponseCache` stores the successful (`200 OK`) responses to `GET`
+/// requests for the path prefixes registered via [`ResponseCache::cache()`],
+/// each for the configured time-to-live. Responses are keyed by their path,
+/// their query string, and the values of the request headers registered via
+/// [`ResponseCache::vary()`]. While an entry is fresh, matching requests are
+/// served from the cache without invoking the route's handler.
+///
+/// Every cached response carries an `ETag` header, computed from a hash of its
+/// body unless the handler set one. A request whose `If-None-Match` header
+/// matches the `ETag` of the response receives an empty `304 Not Modified`.
+///
+/// Entries are kept in a [`CacheStore`]: by default, a [`MemoryStore`] with a
+/// capacity of 32MiB. Use [`ResponseCache::with_store()`] to use a different
+/// store.
+///
+/// Responses that set cookies or that contain a `Cache-Control` header with a
+/// `no-store` or `private` directive are never cached.
+///
+/// # Invalidation
+///
+/// On attach, the fairing places a [`CacheHandle`] in managed state. Retrieve
+/// it via `State<CacheHandle>` to evict entries when the underlying data
+/// changes. A handle can also be retrieved before attaching via
+/// [`ResponseCache::handle()`].
+///
+/// # Ordering
+///
+/// Cache hits are served by rewriting the request's URI to that of an internal
+/// route. Request fairings attached after the `ResponseCache` observe the
+/// rewritten URI; attach the `ResponseCache` last.
+///
+/// # Example
+///
+/// ```rust,no_run
+/// # #[macro_use] extern crate rocket;
+/// # extern crate rocket_contrib;
+/// use std::time::Duration;
+///
+/// use rocket::State;
+/// use rocket_contrib::response_cache::{ResponseCache, CacheHandle};
+///
+/// #[get("/articles/<id>")]
+/// fn article(id: usize) -> String {
+///     /* expensive rendering */
+///     # format!("{}", id)
+/// }
+///
+/// #[post("/articles/<id>")]
+/// async fn update(id: usize, cache: State<'_, CacheHandle>) {
+///     /* update the article... */
+///     cache.invalidate(&format!("/articles/{}", id)).await;
+/// }
+///
+/// #[launch]
+/// fn rocket() -> rocket::Rocket {
+///     let cache = ResponseCache::new()
+///         .cache("/articles", Duration::from_secs(60))
+///         .vary("Accept-Language");
+///
+///     rocket::ignite()
+///         .mount("/", routes![article, update])
+///         .attach(cache)
+/// }
+/// ```
+pub struct ResponseCache {
+    paths: Vec<(String, Duration)>,
+    vary: Vec<String>,
+    handle: CacheHandle,
+}
+
+/// A handle to the entries stored by a [`ResponseCache`].
+///
+/// Handles are cheap to clone; all clones refer to the same store.
+#[derive(Clone)]
+pub struct CacheHandle {
+    store: Arc<dyn CacheStore>,
+}
+
+/// The result of a cache lookup, stored in request-local state.
+enum Lookup {
+    Bypass,
+    Miss(CacheKey, Duration),
+    Hit(Arc<CachedResponse>),
+}
+
+impl Default for ResponseCache {
+    fn default() -> Self {
+        ResponseCache::new()
+    }
+}
+
+impl ResponseCache {
+    /// Returns a `ResponseCache` that caches no paths, storing entries in a
+    /// [`MemoryStore`] with the default capacity.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket_contrib::response_cache::ResponseCache;
+    ///
+    /// let cache = ResponseCache::new();
+    /// ```
+    pub fn new() -> Self {
+        ResponseCache::with_store(MemoryStore::default())
+    }
+
+    /// Returns a `ResponseCache` that caches no paths, storing entries in
+    /// `store`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket_contrib::response_cache::{ResponseCache, MemoryStore};
+    ///
+    /// let cache = ResponseCache::with_store(MemoryStore::new(1 << 20));
+    /// ```
+    pub fn with_store<S: CacheStore>(store: S) -> Self {
+        let handle = CacheHandle { store: Arc::new(store) };
+        ResponseCache { paths: vec![], vary: vec![], handle }
+    }
+
+    /// Caches responses for requests to `prefix`, or to any path below it, for
+    /// `ttl`. When several registered prefixes match a path, the longest one
+    /// determines the time-to-live.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use std::time::Duration;
+    /// use rocket_contrib::response_cache::ResponseCache;
+    ///
+    /// // Cache `/articles`, `/articles/1`, and so on, but not `/articles-new`.
+    /// let cache = ResponseCache::new().cache("/articles", Duration::from_secs(60));
+    /// ```
+    pub fn cache<P: Into<String>>(mut self, prefix: P, ttl: Duration) -> Self {
+        let prefix = prefix.into();
+        let prefix = match prefix.trim_end_matches('/') {
+            "" => "/".to_string(),
+            trimmed => trimmed.to_string(),
+        };
+
+        self.paths.push((prefix, ttl));
+        self
+    }
+
+    /// Keys cached responses additionally on the value of the request header
+    /// `name`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use std::time::Duration;
+    /// use rocket_contrib::response_cache::ResponseCache;
+    ///
+    /// let cache = ResponseCache::new()
+    ///     .cache("/", Duration::from_secs(30))
+    ///     .vary("Accept-Language");
+    /// ```
+    pub fn vary<N: Into<String>>(mut self, name: N) -> Self {
+        self.vary.push(name.into());
+        self
+    }
+
+    /// Returns a handle to the entries of this cache.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket_contrib::response_cache::ResponseCache;
+    ///
+    /// # rocket::async_test(async {
+    /// let cache = ResponseCache::new();
+    /// let handle = cache.handle();
+    /// assert!(handle.is_empty().await);
+    /// # });
+    /// ```
+    pub fn handle(&self) -> CacheHandle {
+        self.handle.clone()
+    }
+
+    fn ttl_for(&self, path: &str) -> Option<Duration> {
+        self.paths.iter()
+            .filter(|(prefix, _)| is_prefix(prefix, path))
+            .max_by_key(|(prefix, _)| prefix.len())
+            .map(|&(_, ttl)| ttl)
+    }
+
+    fn key_for(&self, req: &Request<'_>) -> CacheKey {
+        CacheKey {
+            path: req.uri().path().to_string(),
+            query: req.uri().query().map(|q| q.to_string()),
+            vary: self.vary.iter()
+                .map(|name| req.headers().get_one(name).map(|v| v.to_string()))
+                .collect(),
+        }
+    }
+}
+
+fn is_prefix(prefix: &str, path: &str) -> bool {
+    prefix == "/" || path == prefix
+        || (path.starts_with(prefix) && path[prefix.len()..].starts_with('/'))
+}
+
+fn is_cacheable(response: &Response<'_>) -> bool {
+    let no_store = response.headers().get("Cache-Control")
+        .flat_map(|value| value.split(','))
+        .map(|directive| directive.trim())
+        .any(|d| d.eq_ignore_ascii_case("no-store") || d.eq_ignore_ascii_case("private"));
+
+    response.status() == Status::Ok
+        && !no_store
+        && !response.headers().contains("Set-Cookie")
+}
+
+fn etag_matches(req: &Request<'_>, etag: &str) -> bool {
+    req.headers().get("If-None-Match")
+        .flat_map(|value| value.split(','))
+        .map(|tag| tag.trim())
+        .any(|tag| tag == "*" || tag.trim_start_matches("W/") == etag)
+}
+
+impl CacheHandle {
+    /// Evicts every entry for `path`, regardless of query string or varying
+    /// headers.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket_contrib::response_cache::ResponseCache;
+    ///
+    /// # rocket::async_test(async {
+    /// let handle = ResponseCache::new().handle();
+    /// handle.invalidate("/articles/1").await;
+    /// # });
+    /// ```
+    pub async fn invalidate(&self, path: &str) {
+        self.store.invalidate(&|key| key.path == path).await
+    }
+
+    /// Evicts every entry for `prefix` or any path below it.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket_contrib::response_cache::ResponseCache;
+    ///
+    /// # rocket::async_test(async {
+    /// let handle = ResponseCache::new().handle();
+    /// handle.invalidate_prefix("/articles").await;
+    /// # });
+    /// ```
+    pub async fn invalidate_prefix(&self, prefix: &str) {
+        let prefix = match prefix.trim_end_matches('/') {
+            "" => "/",
+            trimmed => trimmed,
+        };
+
+        self.store.invalidate(&|key| is_prefix(prefix, &key.path)).await
+    }
+
+    /// Evicts every entry.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket_contrib::response_cache::ResponseCache;
+    ///
+    /// # rocket::async_test(async {
+    /// let handle = ResponseCache::new().handle();
+    /// handle.clear().await;
+    /// assert!(handle.is_empty().await);
+    /// # });
+    /// ```
+    pub async fn clear(&self) {
+        self.store.invalidate(&|_| true).await
+    }
+
+    /// Returns the number of stored entries, including those that have expired
+    /// but have not yet been evicted.
+    pub async fn len(&self) -> usize {
+        self.store.len().await
+    }
+
+    /// Returns `true` if there are no stored entries.
+    pub async fn is_empty(&self) -> bool {
+        self.store.is_empty().await
+    }
+}
+
+impl std::fmt::Debug for CacheHandle {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("CacheHandle").finish()
+    }
+}
+
+/// Serves the entry found by the `ResponseCache` request callback.
+#[derive(Clone)]
+struct CacheHit;
+
+#[rocket::async_trait]
+impl Handler for CacheHit {
+    async fn handle<'r, 's: 'r>(&'s self, req: &'r Request<'_>, data: Data) -> Outcome<'r> {
+        let entry = match req.local_cache(|| Lookup::Bypass) {
+            Lookup::Hit(entry) => entry,
+            _ => return Outcome::forward(data),
+        };
+
+        let mut builder = Response::build();
+        builder.header(Header::new("ETag", &*entry.etag));
+        if etag_matches(req, &entry.etag) {
+            return Outcome::from(req, builder.status(Status::NotModified).finalize());
+        }
+
+        for header in &entry.headers {
+            builder.header_adjoin(header.clone());
+        }
+
+        let response = builder.status(entry.status)
+            .sized_body(entry.body.len(), Cursor::new(&*entry.body))
+            .finalize();
+
+        Outcome::from(req, response)
+    }
+}
+
+#[rocket::async_trait]
+impl Fairing for ResponseCache {
+    fn info(&self) -> Info {
+        Info { name: "Response Cache", kind: Kind::Attach | Kind::Request | Kind::Response }
+    }
+
+    async fn on_attach(&self, rocket: Rocket) -> Result<Rocket, Rocket> {
+        Ok(rocket.manage(self.handle())
+            .mount(HIT_PATH, vec![Route::new(Method::Get, "/", CacheHit)]))
+    }
+
+    async fn on_request(&self, req: &mut Request<'_>, _: &mut Data) {
+        if req.method() != Method::Get {
+            return;
+        }
+
+        let ttl = match self.ttl_for(req.uri().path()) {
+            Some(ttl) => ttl,
+            None => return,
+        };
+
+        let key = self.key_for(req);
+        match self.handle.store.get(&key).await {
+            Some(entry) => {
+                req.local_cache(|| Lookup::Hit(entry));
+                req.set_uri(Origin::parse(HIT_PATH).expect("valid internal URI"));
+            }
+            None => {
+                req.local_cache(|| Lookup::Miss(key, ttl));
+            }
+        }
+    }
+
+    async fn on_response<'r>(&self, req: &'r Request<'_>, res: &mut Response<'r>) {
+        let (key, ttl) = match req.local_cache(|| Lookup::Bypass) {
+            Lookup::Miss(key, ttl) if is_cacheable(res) => (key.clone(), *ttl),
+            _ => return,
+        };
+
+        let body = match res.body_bytes().await {
+            Some(body) => body,
+            None => return,
+        };
+
+        let etag = match res.headers().get_one("ETag") {
+            Some(etag) => etag.to_string(),
+            None => {
+                let etag = format!("\"{:016x}\"", fnv1a(&body));
+                res.set_header(Header::new("ETag", etag.clone()));
+                etag
+            }
+        };
+
+        let headers = res.headers().iter()
+            .filter(|h| !h.name().as_str().eq_ignore_ascii_case("ETag"))
+            .map(|h| Header::new(h.name().to_string(), h.value().to_string()))
+            .collect();
+
+        let response = CachedResponse {
+            status: res.status(),
+            headers,
+            body: body.clone(),
+            etag: etag.clone(),
+        };
+
+        self.handle.store.put(key, response, ttl).await;
+
+        if etag_matches(req, &etag) {
+            res.set_status(Status::NotModified);
+        } else {
+            res.set_sized_body(body.len(), Cursor::new(body));
+        }
+    }
+}
@@ -0,0 +1,199 @@
+use std::collections::{BTreeMap, HashMap};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use crate::response_cache::{CacheStore, CacheKey, CachedResponse};
+
+/// An in-memory [`CacheStore`] bounded in size.
+///
+/// A `MemoryStore` holds at most `capacity` bytes of responses, as measured by
+/// [`CachedResponse::size()`]. When storing a response would exceed the
+/// capacity, the least recently used entries are evicted first. Responses
+/// larger than the capacity are not stored.
+///
+/// # Example
+///
+/// ```rust
+/// use rocket_contrib::response_cache::{ResponseCache, MemoryStore};
+///
+/// // A cache using at most 16MiB of memory for responses.
+/// let cache = ResponseCache::with_store(MemoryStore::new(16 << 20));
+/// ```
+pub struct MemoryStore {
+    capacity: usize,
+    inner: Mutex<Lru>,
+}
+
+struct Slot {
+    response: Arc<CachedResponse>,
+    expires: Instant,
+    last_used: u64,
+}
+
+#[derive(Default)]
+struct Lru {
+    slots: HashMap<CacheKey, Slot>,
+    // Maps a `last_used` tick to the key of the slot last used at that tick.
+    order: BTreeMap<u64, CacheKey>,
+    tick: u64,
+    size: usize,
+}
+
+impl Lru {
+    fn touch(&mut self, key: &CacheKey) {
+        self.tick += 1;
+        if let Some(slot) = self.slots.get_mut(key) {
+            self.order.remove(&slot.last_used);
+            self.order.insert(self.tick, key.clone());
+            slot.last_used = self.tick;
+        }
+    }
+
+    fn remove(&mut self, key: &CacheKey) {
+        if let Some(slot) = self.slots.remove(key) {
+            self.order.remove(&slot.last_used);
+            self.size -= slot.response.size();
+        }
+    }
+
+    fn evict_lru(&mut self) {
+        let key = self.order.values().next().cloned();
+        if let Some(key) = key {
+            self.remove(&key);
+        }
+    }
+}
+
+impl MemoryStore {
+    /// The default capacity, in bytes: 32MiB.
+    const DEFAULT_CAPACITY: usize = 32 << 20;
+
+    /// Returns a `MemoryStore` that holds at most `capacity` bytes.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket_contrib::response_cache::MemoryStore;
+    ///
+    /// let store = MemoryStore::new(1 << 20);
+    /// ```
+    pub fn new(capacity: usize) -> Self {
+        MemoryStore { capacity, inner: Mutex::new(Lru::default()) }
+    }
+
+    /// Returns the number of bytes of responses currently stored.
+    pub fn size(&self) -> usize {
+        self.inner.lock().expect("memory store lock").size
+    }
+}
+
+impl Default for MemoryStore {
+    /// Returns a `MemoryStore` with a capacity of 32MiB.
+    fn default() -> Self {
+        MemoryStore::new(Self::DEFAULT_CAPACITY)
+    }
+}
+
+#[rocket::async_trait]
+impl CacheStore for MemoryStore {
+    async fn get(&self, key: &CacheKey) -> Option<Arc<CachedResponse>> {
+        let mut lru = self.inner.lock().expect("memory store lock");
+        let (response, expires) = lru.slots.get(key)
+            .map(|slot| (slot.response.clone(), slot.expires))?;
+
+        if expires <= Instant::now() {
+            lru.remove(key);
+            return None;
+        }
+
+        lru.touch(key);
+        Some(response)
+    }
+
+    async fn put(&self, key: CacheKey, response: CachedResponse, ttl: Duration) {
+        let size = response.size();
+        let mut lru = self.inner.lock().expect("memory store lock");
+        lru.remove(&key);
+        if size > self.capacity {
+            return;
+        }
+
+        while lru.size + size > self.capacity {
+            lru.evict_lru();
+        }
+
+        lru.tick += 1;
+        let last_used = lru.tick;
+        lru.order.insert(last_used, key.clone());
+        lru.size += size;
+        lru.slots.insert(key, Slot {
+            response: Arc::new(response),
+            expires: Instant::now() + ttl,
+            last_used,
+        });
+    }
+
+    async fn invalidate(&self, predicate: &(dyn Fn(&CacheKey) -> bool + Sync)) {
+        let mut lru = self.inner.lock().expect("memory store lock");
+        let keys: Vec<_> = lru.slots.keys().filter(|k| predicate(k)).cloned().collect();
+        for key in &keys {
+            lru.remove(key);
+        }
+    }
+
+    async fn len(&self) -> usize {
+        self.inner.lock().expect("memory store lock").slots.len()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use rocket::http::Status;
+    use super::*;
+
+    fn key(path: &str) -> CacheKey {
+        CacheKey { path: path.into(), query: None, vary: vec![] }
+    }
+
+    fn response(len: usize) -> CachedResponse {
+        let body = vec![0; len];
+        CachedResponse { status: Status::Ok, headers: vec![], body, etag: "\"\"".into() }
+    }
+
+    #[rocket::async_test]
+    async fn test_lru_eviction() {
+        let ttl = Duration::from_secs(60);
+        let store = MemoryStore::new(30);
+        store.put(key("/a"), response(8), ttl).await;
+        store.put(key("/b"), response(8), ttl).await;
+        store.put(key("/c"), response(8), ttl).await;
+        assert_eq!(store.len().await, 3);
+        assert_eq!(store.size(), 30);
+
+        // `/a` is now the most recently used, so `/b` is evicted.
+        assert!(store.get(&key("/a")).await.is_some());
+        store.put(key("/d"), response(8), ttl).await;
+        assert_eq!(store.len().await, 3);
+        assert!(store.get(&key("/b")).await.is_none());
+        assert!(store.get(&key("/a")).await.is_some());
+
+        // Too large to store at all.
+        store.put(key("/e"), response(64), ttl).await;
+        assert!(store.get(&key("/e")).await.is_none());
+        assert_eq!(store.len().await, 3);
+
+        store.invalidate(&|k| k.path != "/a").await;
+        assert_eq!(store.len().await, 1);
+        assert_eq!(store.size(), 10);
+    }
+
+    #[rocket::async_test]
+    async fn test_expiration() {
+        let store = MemoryStore::default();
+        store.put(key("/a"), response(8), Duration::from_secs(0)).await;
+        assert!(store.get(&key("/a")).await.is_none());
+        assert!(store.is_empty().await);
+    }
+}
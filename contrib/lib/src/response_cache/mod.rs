@@ -0,0 +1,35 @@
+//! Caching of rendered responses with ETag validation.
+//!
+//! See the [`ResponseCache`] fairing for further details.
+//!
+//! # Stores
+//!
+//! Cached responses are kept in a [`CacheStore`]. Two stores are provided:
+//!
+//!   * [`MemoryStore`] - an in-memory LRU cache bounded in size (default)
+//!   * [`DiskStore`] - a directory with one file per entry
+//!
+//! Other backends, such as Redis, can be used by implementing [`CacheStore`]
+//! and passing the store to [`ResponseCache::with_store()`].
+//!
+//! # Enabling
+//!
+//! This module is only available when the `response_cache` feature is enabled.
+//! Enable it in `Cargo.toml` as follows:
+//!
+//! ```toml
+//! [dependencies.rocket_contrib]
+//! version = "0.5.0-dev"
+//! default-features = false
+//! features = ["response_cache"]
+//! ```
+
+mod fairing;
+mod store;
+mod memory;
+mod disk;
+
+pub use self::fairing::{ResponseCache, CacheHandle};
+pub use self::store::{CacheStore, CacheKey, CachedResponse};
+pub use self::memory::MemoryStore;
+pub use self::disk::DiskStore;
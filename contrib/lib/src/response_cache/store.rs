@@ -0,0 +1,124 @@
+use std::sync::Arc;
+use std::time::Duration;
+
+use rocket::http::{Header, Status};
+
+/// The key identifying a cached response.
+///
+/// A response is keyed by the path and query string of the request, and by the
+/// values of the request headers registered via
+/// [`ResponseCache::vary()`](crate::response_cache::ResponseCache::vary()), in
+/// order of registration. A header missing from the request is `None`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct CacheKey {
+    /// The path of the request.
+    pub path: String,
+    /// The query string of the request, if any.
+    pub query: Option<String>,
+    /// The values of the varying request headers.
+    pub vary: Vec<Option<String>>,
+}
+
+/// A response stored in a [`CacheStore`].
+#[derive(Debug, Clone)]
+pub struct CachedResponse {
+    /// The status of the response.
+    pub status: Status,
+    /// The headers of the response, excluding `ETag`.
+    pub headers: Vec<Header<'static>>,
+    /// The body of the response.
+    pub body: Vec<u8>,
+    /// The value of the `ETag` header of the response.
+    pub etag: String,
+}
+
+impl CachedResponse {
+    /// Returns the approximate number of bytes occupied by `self`.
+    pub fn size(&self) -> usize {
+        let headers: usize = self.headers.iter()
+            .map(|h| h.name().as_str().len() + h.value().len())
+            .sum();
+
+        headers + self.body.len() + self.etag.len()
+    }
+}
+
+/// Trait implemented by storage backends of a
+/// [`ResponseCache`](crate::response_cache::ResponseCache).
+///
+/// A store maps [`CacheKey`]s to [`CachedResponse`]s, each of which expires
+/// after the time-to-live it was stored with. Expired entries must not be
+/// returned by [`get()`](CacheStore::get()); when they are evicted is up to
+/// the store. Two stores are provided: [`MemoryStore`], a size-bounded LRU
+/// cache, and [`DiskStore`], which stores entries as files in a directory.
+///
+/// Methods are asynchronous and are awaited during request processing, so a
+/// store may perform I/O, such as querying a networked cache, but must not
+/// block the executor. Failures, such as an unreachable backend, should be
+/// logged and treated as a cache miss.
+///
+/// [`MemoryStore`]: crate::response_cache::MemoryStore
+/// [`DiskStore`]: crate::response_cache::DiskStore
+///
+/// # Example
+///
+/// A store that caches nothing:
+///
+/// ```rust
+/// use std::sync::Arc;
+/// use std::time::Duration;
+///
+/// use rocket_contrib::response_cache::{CacheStore, CacheKey, CachedResponse};
+///
+/// struct NoStore;
+///
+/// #[rocket::async_trait]
+/// impl CacheStore for NoStore {
+///     async fn get(&self, _: &CacheKey) -> Option<Arc<CachedResponse>> { None }
+///     async fn put(&self, _: CacheKey, _: CachedResponse, _: Duration) { }
+///     async fn invalidate(&self, _: &(dyn Fn(&CacheKey) -> bool + Sync)) { }
+///     async fn len(&self) -> usize { 0 }
+/// }
+/// ```
+#[rocket::async_trait]
+pub trait CacheStore: Send + Sync + 'static {
+    /// Returns the unexpired response stored for `key`, if any.
+    async fn get(&self, key: &CacheKey) -> Option<Arc<CachedResponse>>;
+
+    /// Stores `response` for `key`, replacing any existing entry, to expire
+    /// after `ttl`.
+    async fn put(&self, key: CacheKey, response: CachedResponse, ttl: Duration);
+
+    /// Evicts every entry whose key satisfies `predicate`.
+    async fn invalidate(&self, predicate: &(dyn Fn(&CacheKey) -> bool + Sync));
+
+    /// Returns the number of stored entries, including those that have expired
+    /// but have not yet been evicted.
+    async fn len(&self) -> usize;
+
+    /// Returns `true` if there are no stored entries.
+    async fn is_empty(&self) -> bool {
+        self.len().await == 0
+    }
+}
+
+/// Returns the 64-bit FNV-1a hash of `bytes`. Unlike `DefaultHasher`, the hash
+/// is stable across Rust releases and platforms, so it can name files and tag
+/// responses that outlive a build.
+pub(crate) fn fnv1a(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xcbf2_9ce4_8422_2325, |hash, &byte| {
+        (hash ^ u64::from(byte)).wrapping_mul(0x0000_0100_0000_01b3)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::fnv1a;
+
+    #[test]
+    fn test_fnv1a() {
+        assert_eq!(fnv1a(b""), 0xcbf2_9ce4_8422_2325);
+        assert_eq!(fnv1a(b"a"), 0xaf63_dc4c_8601_ec8c);
+        assert_eq!(fnv1a(b"foobar"), 0x8594_4171_f739_67e8);
+    }
+}
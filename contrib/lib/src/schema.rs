@@ -0,0 +1,620 @@
+//! Validation of JSON request and response bodies against per-route schemas.
+//!
+//! See the [`Validator`] fairing for further details.
+//!
+//! # Enabling
+//!
+//! This module is only available when the `schema` feature is enabled. Enable
+//! it in `Cargo.toml` as follows:
+//!
+//! ```toml
+//! [dependencies.rocket_contrib]
+//! version = "0.5.0-dev"
+//! default-features = false
+//! features = ["schema"]
+//! ```
+//!
+//! To derive schemas from types via [`Schema::of()`], also depend on
+//! [`schemars`](https://docs.rs/schemars/0.8) to derive its `JsonSchema` trait.
+
+use std::fmt;
+use std::io::Cursor;
+use std::sync::Arc;
+use std::collections::HashMap;
+
+use serde_json::{Map, Value};
+
+use rocket::{Request, Response, Data, Route};
+use rocket::data::ByteUnit;
+use rocket::fairing::{Fairing, Info, Kind};
+use rocket::handler::{Handler, Outcome};
+use rocket::http::{Status, StatusClass};
+
+/// The default size limit for request bodies read for validation: 1MiB. This is
+/// the default of the `json` limit, which is used when set.
+const DEFAULT_LIMIT: ByteUnit = ByteUnit::Mebibyte(1);
+
+/// A JSON Schema.
+///
+/// A `Schema` is created from a schema document via [`Schema::new()`] or
+/// derived from a Rust type via [`Schema::of()`]. Validation supports the
+/// commonly used subset of JSON Schema: `type`, `enum`, `const`,
+/// `properties`, `required`, `additionalProperties`, `items`, `minItems`,
+/// `maxItems`, `minLength`, `maxLength`, `minimum`, `maximum`,
+/// `exclusiveMinimum`, `exclusiveMaximum`, `allOf`, `anyOf`, `oneOf`, `not`,
+/// and `$ref`s to local definitions. Other keywords, such as `format` and
+/// `pattern`, are ignored.
+///
+/// # Example
+///
+/// ```rust
+/// use serde_json::json;
+/// use rocket_contrib::schema::Schema;
+///
+/// let schema = Schema::new(json!({
+///     "type": "object",
+///     "properties": { "name": { "type": "string", "minLength": 1 } },
+///     "required": ["name"]
+/// }));
+///
+/// assert!(schema.validate(&json!({ "name": "Bob" })).is_ok());
+///
+/// let mismatch = schema.validate(&json!({ "name": "" })).unwrap_err();
+/// assert_eq!(mismatch.path, "/name");
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct Schema(Value);
+
+/// A value's mismatch with a [`Schema`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Mismatch {
+    /// The JSON Pointer to the mismatched value, as in `/items/0/name`. Empty
+    /// for the document itself.
+    pub path: String,
+    /// A description of the mismatch.
+    pub message: String,
+}
+
+impl Mismatch {
+    fn new<M: Into<String>>(path: &str, message: M) -> Mismatch {
+        Mismatch { path: path.to_string(), message: message.into() }
+    }
+}
+
+impl fmt::Display for Mismatch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.path.is_empty() {
+            true => write!(f, "{}", self.message),
+            false => write!(f, "{} at `{}`", self.message, self.path),
+        }
+    }
+}
+
+impl std::error::Error for Mismatch { }
+
+impl Schema {
+    /// Creates a schema from the JSON Schema document `schema`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use serde_json::json;
+    /// use rocket_contrib::schema::Schema;
+    ///
+    /// let schema = Schema::new(json!({ "type": "array", "items": { "type": "integer" } }));
+    /// assert!(schema.validate(&json!([1, 2, 3])).is_ok());
+    /// assert!(schema.validate(&json!([1, 2.5])).is_err());
+    /// ```
+    pub fn new(schema: Value) -> Schema {
+        Schema(schema)
+    }
+
+    /// Derives the schema of `T` via its
+    /// [`JsonSchema`](https://docs.rs/schemars/0.8/schemars/trait.JsonSchema.html)
+    /// implementation.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use schemars::JsonSchema;
+    /// use serde_json::json;
+    /// use rocket_contrib::schema::Schema;
+    ///
+    /// #[derive(JsonSchema)]
+    /// struct Task {
+    ///     description: String,
+    ///     done: Option<bool>,
+    /// }
+    ///
+    /// let schema = Schema::of::<Task>();
+    /// assert!(schema.validate(&json!({ "description": "write docs" })).is_ok());
+    /// assert!(schema.validate(&json!({ "done": true })).is_err());
+    /// ```
+    pub fn of<T: schemars::JsonSchema>() -> Schema {
+        let schema = schemars::schema_for!(T);
+        Schema(serde_json::to_value(schema).expect("schemas serialize to JSON"))
+    }
+
+    /// Returns the schema document.
+    pub fn as_value(&self) -> &Value {
+        &self.0
+    }
+
+    /// Validates `value` against `self`, returning the first mismatch found.
+    pub fn validate(&self, value: &Value) -> Result<(), Mismatch> {
+        self.check(&self.0, value, "")
+    }
+
+    /// Validates the JSON document `bytes` against `self`.
+    fn validate_bytes(&self, bytes: &[u8]) -> Result<(), Mismatch> {
+        let value: Value = serde_json::from_slice(bytes)
+            .map_err(|e| Mismatch::new("", format!("invalid JSON: {}", e)))?;
+
+        self.validate(&value)
+    }
+
+    fn check(&self, schema: &Value, value: &Value, path: &str) -> Result<(), Mismatch> {
+        let schema = match schema {
+            Value::Bool(false) => return Err(Mismatch::new(path, "no value is allowed")),
+            Value::Object(schema) => schema,
+            _ => return Ok(()),
+        };
+
+        if let Some(reference) = schema.get("$ref").and_then(|r| r.as_str()) {
+            let target = reference.strip_prefix('#')
+                .and_then(|pointer| self.0.pointer(pointer))
+                .ok_or_else(|| Mismatch::new(path, format!("unknown reference `{}`", reference)))?;
+
+            self.check(target, value, path)?;
+        }
+
+        if let Some(types) = schema.get("type") {
+            let is = |ty: &Value| ty.as_str().map_or(false, |ty| has_type(value, ty));
+            let matches = match types {
+                Value::Array(types) => types.iter().any(is),
+                ty => is(ty),
+            };
+
+            if !matches {
+                let message = format!("expected {}, found {}", types, type_name(value));
+                return Err(Mismatch::new(path, message));
+            }
+        }
+
+        if let Some(values) = schema.get("enum").and_then(|v| v.as_array()) {
+            if !values.contains(value) {
+                return Err(Mismatch::new(path, format!("{} is not an allowed value", value)));
+            }
+        }
+
+        if let Some(expected) = schema.get("const") {
+            if expected != value {
+                return Err(Mismatch::new(path, format!("expected {}, found {}", expected, value)));
+            }
+        }
+
+        self.check_combinators(schema, value, path)?;
+        match value {
+            Value::String(string) => check_length(schema, path, string.chars().count()),
+            Value::Number(number) => check_range(schema, path, number.as_f64()),
+            Value::Array(items) => self.check_array(schema, items, path),
+            Value::Object(object) => self.check_object(schema, object, path),
+            _ => Ok(()),
+        }
+    }
+
+    fn check_combinators(
+        &self,
+        schema: &Map<String, Value>,
+        value: &Value,
+        path: &str
+    ) -> Result<(), Mismatch> {
+        let subschemas = |name: &str| schema.get(name).and_then(|v| v.as_array());
+        for subschema in subschemas("allOf").into_iter().flatten() {
+            self.check(subschema, value, path)?;
+        }
+
+        if let Some(options) = subschemas("anyOf") {
+            if !options.iter().any(|s| self.check(s, value, path).is_ok()) {
+                return Err(Mismatch::new(path, "matches none of the `anyOf` schemas"));
+            }
+        }
+
+        if let Some(options) = subschemas("oneOf") {
+            let matched = options.iter().filter(|s| self.check(s, value, path).is_ok()).count();
+            if matched != 1 {
+                let message = format!("matches {} of the `oneOf` schemas, not 1", matched);
+                return Err(Mismatch::new(path, message));
+            }
+        }
+
+        if let Some(negated) = schema.get("not") {
+            if self.check(negated, value, path).is_ok() {
+                return Err(Mismatch::new(path, "matches the `not` schema"));
+            }
+        }
+
+        Ok(())
+    }
+
+    fn check_array(
+        &self,
+        schema: &Map<String, Value>,
+        items: &[Value],
+        path: &str
+    ) -> Result<(), Mismatch> {
+        if let Some(min) = schema.get("minItems").and_then(|v| v.as_u64()) {
+            if (items.len() as u64) < min {
+                return Err(Mismatch::new(path, format!("expected at least {} items", min)));
+            }
+        }
+
+        if let Some(max) = schema.get("maxItems").and_then(|v| v.as_u64()) {
+            if (items.len() as u64) > max {
+                return Err(Mismatch::new(path, format!("expected at most {} items", max)));
+            }
+        }
+
+        for (i, item) in items.iter().enumerate() {
+            let item_schema = match schema.get("items") {
+                Some(Value::Array(schemas)) => schemas.get(i),
+                items => items,
+            };
+
+            if let Some(item_schema) = item_schema {
+                self.check(item_schema, item, &format!("{}/{}", path, i))?;
+            }
+        }
+
+        Ok(())
+    }
+
+    fn check_object(
+        &self,
+        schema: &Map<String, Value>,
+        object: &Map<String, Value>,
+        path: &str
+    ) -> Result<(), Mismatch> {
+        let required = schema.get("required").and_then(|v| v.as_array());
+        for name in required.into_iter().flatten().filter_map(|v| v.as_str()) {
+            if !object.contains_key(name) {
+                return Err(Mismatch::new(path, format!("missing property `{}`", name)));
+            }
+        }
+
+        let properties = schema.get("properties").and_then(|v| v.as_object());
+        for (name, value) in object {
+            let path = format!("{}/{}", path, name.replace('~', "~0").replace('/', "~1"));
+            match properties.and_then(|p| p.get(name)) {
+                Some(property) => self.check(property, value, &path)?,
+                None => match schema.get("additionalProperties") {
+                    Some(Value::Bool(false)) => {
+                        return Err(Mismatch::new(&path, "unexpected property"));
+                    }
+                    Some(additional) => self.check(additional, value, &path)?,
+                    None => {}
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+fn check_length(schema: &Map<String, Value>, path: &str, len: usize) -> Result<(), Mismatch> {
+    if let Some(min) = schema.get("minLength").and_then(|v| v.as_u64()) {
+        if (len as u64) < min {
+            return Err(Mismatch::new(path, format!("expected at least {} characters", min)));
+        }
+    }
+
+    if let Some(max) = schema.get("maxLength").and_then(|v| v.as_u64()) {
+        if (len as u64) > max {
+            return Err(Mismatch::new(path, format!("expected at most {} characters", max)));
+        }
+    }
+
+    Ok(())
+}
+
+fn check_range(schema: &Map<String, Value>, path: &str, n: Option<f64>) -> Result<(), Mismatch> {
+    let n = match n {
+        Some(n) => n,
+        None => return Ok(()),
+    };
+
+    let bound = |name: &str| schema.get(name).and_then(|v| v.as_f64());
+    let checks: [(&str, fn(f64, f64) -> bool, &str); 4] = [
+        ("minimum", |n, b| n >= b, ">="),
+        ("maximum", |n, b| n <= b, "<="),
+        ("exclusiveMinimum", |n, b| n > b, ">"),
+        ("exclusiveMaximum", |n, b| n < b, "<"),
+    ];
+
+    for &(name, holds, op) in &checks {
+        if let Some(bound) = bound(name) {
+            if !holds(n, bound) {
+                return Err(Mismatch::new(path, format!("expected {} {} {}", n, op, bound)));
+            }
+        }
+    }
+
+    Ok(())
+}
+
+fn has_type(value: &Value, ty: &str) -> bool {
+    match ty {
+        "null" => value.is_null(),
+        "boolean" => value.is_boolean(),
+        "object" => value.is_object(),
+        "array" => value.is_array(),
+        "string" => value.is_string(),
+        "number" => value.is_number(),
+        "integer" => value.is_i64() || value.is_u64()
+            || value.as_f64().map_or(false, |n| n.fract() == 0.0),
+        _ => false,
+    }
+}
+
+fn type_name(value: &Value) -> &'static str {
+    match value {
+        Value::Null => "null",
+        Value::Bool(_) => "boolean",
+        Value::Number(_) => "number",
+        Value::String(_) => "string",
+        Value::Array(_) => "array",
+        Value::Object(_) => "object",
+    }
+}
+
+/// The schemas registered for a route.
+#[derive(Debug, Clone, Default)]
+struct Schemas {
+    request: Option<Schema>,
+    response: Option<Schema>,
+}
+
+/// A fairing that validates JSON request and response bodies against the
+/// schemas registered for their routes.
+///
+/// Schemas are registered for a route by its name via
+/// [`Validator::request()`] and [`Validator::response()`]. Routes generated
+/// by codegen are named after their handler. A schema can be written by hand
+/// or derived from the type a handler accepts or returns via [`Schema::of()`].
+///
+///   * **Requests** are validated by the routes returned from
+///     [`Validator::routes()`], which wrap the handlers of the routes they're
+///     given. The body is read, subject to the `json` limit, validated, and
+///     passed on to the original handler.
+///   * **Responses** are validated by the fairing. Only successful (`2xx`)
+///     responses to requests handled by a route with a response schema are
+///     validated, since error responses are usually described separately.
+///
+/// Each mismatch is logged as a warning. A [strict](Validator::strict())
+/// validator additionally fails requests whose bodies mismatch with a `422
+/// Unprocessable Entity`, without calling the handler, and replaces
+/// responses whose bodies mismatch with an empty `500 Internal Server Error`.
+///
+/// Validation buffers and parses every body it checks, so a `Validator` is
+/// intended to catch drift between handlers and documented APIs in
+/// development, testing, and staging, not to be attached in production.
+///
+/// # Example
+///
+/// ```rust,no_run
+/// # #[macro_use] extern crate rocket;
+/// # extern crate rocket_contrib;
+/// use serde_json::{json, Value};
+/// use rocket_contrib::json::Json;
+/// use rocket_contrib::schema::{Schema, Validator};
+///
+/// #[post("/todos", format = "json", data = "<todo>")]
+/// fn create_todo(todo: Json<Value>) -> Json<Value> {
+///     Json(json!({ "id": 1 }))
+/// }
+///
+/// #[launch]
+/// fn rocket() -> rocket::Rocket {
+///     let todo = json!({
+///         "type": "object",
+///         "properties": { "text": { "type": "string" } },
+///         "required": ["text"]
+///     });
+///
+///     let created = json!({
+///         "type": "object",
+///         "properties": { "id": { "type": "integer", "minimum": 1 } },
+///         "required": ["id"]
+///     });
+///
+///     let validator = Validator::new()
+///         .request("create_todo", Schema::new(todo))
+///         .response("create_todo", Schema::new(created))
+///         .strict(cfg!(debug_assertions));
+///
+///     rocket::ignite()
+///         .mount("/", validator.routes(routes![create_todo]))
+///         .attach(validator)
+/// }
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct Validator {
+    schemas: Arc<HashMap<String, Schemas>>,
+    strict: bool,
+}
+
+impl Validator {
+    /// Creates a new, non-strict validator without any schemas.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket_contrib::schema::Validator;
+    ///
+    /// let validator = Validator::new();
+    /// ```
+    pub fn new() -> Validator {
+        Validator::default()
+    }
+
+    /// Registers `schema` as the schema of request bodies for the route named
+    /// `route`. Only routes returned from [`Validator::routes()`] validate
+    /// their requests.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use serde_json::json;
+    /// use rocket_contrib::schema::{Schema, Validator};
+    ///
+    /// let schema = Schema::new(json!({ "type": "object" }));
+    /// let validator = Validator::new().request("create_user", schema);
+    /// ```
+    pub fn request<N: Into<String>>(mut self, route: N, schema: Schema) -> Self {
+        Arc::make_mut(&mut self.schemas).entry(route.into()).or_default().request = Some(schema);
+        self
+    }
+
+    /// Registers `schema` as the schema of successful response bodies for the
+    /// route named `route`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use serde_json::json;
+    /// use rocket_contrib::schema::{Schema, Validator};
+    ///
+    /// let schema = Schema::new(json!({ "type": "array" }));
+    /// let validator = Validator::new().response("list_users", schema);
+    /// ```
+    pub fn response<N: Into<String>>(mut self, route: N, schema: Schema) -> Self {
+        Arc::make_mut(&mut self.schemas).entry(route.into()).or_default().response = Some(schema);
+        self
+    }
+
+    /// Sets whether mismatched requests and responses fail. Defaults to
+    /// `false`, where mismatches are only logged.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket_contrib::schema::Validator;
+    ///
+    /// let validator = Validator::new().strict(true);
+    /// ```
+    pub fn strict(mut self, enabled: bool) -> Self {
+        self.strict = enabled;
+        self
+    }
+
+    /// Returns `routes`, with the handlers of those that have a request schema
+    /// wrapped to validate request bodies. Call this method after registering
+    /// schemas and setting strictness: the routes aren't affected by later
+    /// changes to `self`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # #[macro_use] extern crate rocket;
+    /// # extern crate rocket_contrib;
+    /// use serde_json::json;
+    /// use rocket_contrib::schema::{Schema, Validator};
+    ///
+    /// #[post("/", data = "<body>")]
+    /// fn echo(body: String) -> String { body }
+    ///
+    /// let schema = Schema::new(json!({ "type": "string" }));
+    /// let validator = Validator::new().request("echo", schema);
+    /// let rocket = rocket::ignite().mount("/", validator.routes(routes![echo]));
+    /// ```
+    pub fn routes<R: Into<Vec<Route>>>(&self, routes: R) -> Vec<Route> {
+        routes.into().into_iter()
+            .map(|mut route| {
+                let name = match route.name {
+                    Some(name) if self.request_schema(name).is_some() => name,
+                    _ => return route,
+                };
+
+                let handler = route.handler.clone();
+                route.handler = Box::new(Validated { handler, validator: self.clone(), name });
+                route
+            })
+            .collect()
+    }
+
+    fn request_schema(&self, route: &str) -> Option<&Schema> {
+        self.schemas.get(route).and_then(|schemas| schemas.request.as_ref())
+    }
+
+    fn response_schema(&self, route: &str) -> Option<&Schema> {
+        self.schemas.get(route).and_then(|schemas| schemas.response.as_ref())
+    }
+}
+
+/// A handler validating request bodies before calling its handler.
+#[derive(Clone)]
+struct Validated {
+    handler: Box<dyn Handler>,
+    validator: Validator,
+    name: &'static str,
+}
+
+#[rocket::async_trait]
+impl Handler for Validated {
+    async fn handle<'r, 's: 'r>(&'s self, req: &'r Request<'_>, data: Data) -> Outcome<'r> {
+        let schema = match self.validator.request_schema(self.name) {
+            Some(schema) => schema,
+            None => return self.handler.handle(req, data).await,
+        };
+
+        let limit = req.limits().get("json").unwrap_or(DEFAULT_LIMIT);
+        let bytes = match data.open(limit).stream_to_vec().await {
+            Ok(bytes) => bytes,
+            Err(e) => {
+                warn_!("Schema: failed to read request body: {}", e);
+                return Outcome::failure(Status::BadRequest);
+            }
+        };
+
+        if let Err(mismatch) = schema.validate_bytes(&bytes) {
+            warn!("Schema: {} {}: request body: {}.", req.method(), req.uri(), mismatch);
+            if self.validator.strict {
+                return Outcome::failure(Status::UnprocessableEntity);
+            }
+        }
+
+        self.handler.handle(req, Data::from(bytes)).await
+    }
+}
+
+#[rocket::async_trait]
+impl Fairing for Validator {
+    fn info(&self) -> Info {
+        Info { name: "Schema Validator", kind: Kind::Response }
+    }
+
+    async fn on_response<'r>(&self, req: &'r Request<'_>, res: &mut Response<'r>) {
+        let schema = req.route().and_then(|route| self.response_schema(route.name?));
+        let schema = match schema {
+            Some(schema) if res.status().class() == StatusClass::Success => schema,
+            _ => return,
+        };
+
+        let bytes = match res.take_body() {
+            Some(body) => body.into_bytes().await,
+            None => None,
+        };
+
+        let result = schema.validate_bytes(bytes.as_deref().unwrap_or(&[]));
+        if let Some(bytes) = bytes {
+            res.set_sized_body(bytes.len(), Cursor::new(bytes));
+        }
+
+        if let Err(mismatch) = result {
+            warn!("Schema: {} {}: response body: {}.", req.method(), req.uri(), mismatch);
+            if self.strict {
+                *res = Response::build().status(Status::InternalServerError).finalize();
+            }
+        }
+    }
+}
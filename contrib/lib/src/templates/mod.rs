@@ -137,7 +137,7 @@ use std::borrow::Cow;
 use std::path::PathBuf;
 use std::error::Error;
 
-use rocket::Rocket;
+use rocket::{Rocket, Phase};
 use rocket::request::Request;
 use rocket::fairing::Fairing;
 use rocket::response::{self, Content, Responder};
@@ -373,8 +373,8 @@ impl Template {
     /// }
     /// ```
     #[inline]
-    pub fn show<S, C>(rocket: &Rocket, name: S, context: C) -> Option<String>
-        where S: Into<Cow<'static, str>>, C: Serialize
+    pub fn show<S, C, P>(rocket: &Rocket<P>, name: S, context: C) -> Option<String>
+        where S: Into<Cow<'static, str>>, C: Serialize, P: Phase
     {
         let ctxt = rocket.state::<ContextManager>().map(ContextManager::context).or_else(|| {
             warn!("Uninitialized template context: missing fairing.");
@@ -412,13 +412,40 @@ impl Template {
 
         Ok((string, info.data_type.clone()))
     }
+
+    /// Adds the request's [`Locale`](crate::locale::Locale) to the context as
+    /// `locale` if the context is an object without a `locale` field.
+    #[cfg(feature = "locale")]
+    fn inject_locale(&mut self, req: &Request<'_>) {
+        let locale = crate::locale::Locale::of(req);
+        if let (Some(Value::Object(map)), Some(locale)) = (&mut self.value, locale) {
+            map.entry("locale").or_insert_with(|| Value::String(locale.to_string()));
+        }
+    }
+
+    /// Adds the request's [`CspNonce`](crate::csp::CspNonce) to the context as
+    /// `csp_nonce` if the context is an object without a `csp_nonce` field.
+    #[cfg(feature = "csp")]
+    fn inject_csp_nonce(&mut self, req: &Request<'_>) {
+        let nonce = crate::csp::CspNonce::of(req);
+        if let (Some(Value::Object(map)), Some(nonce)) = (&mut self.value, nonce) {
+            map.entry("csp_nonce").or_insert_with(|| Value::String(nonce.to_string()));
+        }
+    }
 }
 
 /// Returns a response with the Content-Type derived from the template's
 /// extension and a fixed-size body containing the rendered template. If
 /// rendering fails, an `Err` of `Status::InternalServerError` is returned.
 impl<'r> Responder<'r, 'static> for Template {
-    fn respond_to(self, req: &'r Request<'_>) -> response::Result<'static> {
+    #[allow(unused_mut)]
+    fn respond_to(mut self, req: &'r Request<'_>) -> response::Result<'static> {
+        #[cfg(feature = "locale")]
+        self.inject_locale(req);
+
+        #[cfg(feature = "csp")]
+        self.inject_csp_nonce(req);
+
         let (render, content_type) = {
             let ctxt = req.managed_state::<ContextManager>().ok_or_else(|| {
                 error_!("Uninitialized template context: missing fairing.");
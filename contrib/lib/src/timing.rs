@@ -0,0 +1,128 @@
+//! Response time headers for lightweight performance monitoring.
+//!
+//! See the [`ResponseTime`] fairing for further details.
+//!
+//! # Enabling
+//!
+//! This module is only available when the `timing` feature is enabled. Enable
+//! it in `Cargo.toml` as follows:
+//!
+//! ```toml
+//! [dependencies.rocket_contrib]
+//! version = "0.5.0-dev"
+//! default-features = false
+//! features = ["timing"]
+//! ```
+
+use std::time::Duration;
+
+use rocket::{Request, Response};
+use rocket::fairing::{Fairing, Info, Kind};
+use rocket::http::Header;
+
+/// A fairing that reports how long each request took to handle in a response
+/// header.
+///
+/// The time reported is [`Request::elapsed()`] when the fairing's response
+/// callback runs: the time from when Rocket received the request until the
+/// response was ready to be written, including the time taken by request
+/// fairings, guards, the handler, and the response fairings attached before
+/// the `ResponseTime`. To cover as much of the work as possible, attach it
+/// last. The time taken to write the response isn't included.
+///
+/// By default, the time is reported in milliseconds in an `X-Response-Time`
+/// header, as in `X-Response-Time: 12.345ms`. With
+/// [`ResponseTime::server_timing()`], it's instead reported as a metric in a
+/// [`Server-Timing`] header, as in `Server-Timing: app;dur=12.345`, which
+/// browser developer tools display alongside their own timings.
+///
+/// [`Request::elapsed()`]: rocket::Request::elapsed()
+/// [`Server-Timing`]: https://www.w3.org/TR/server-timing/
+///
+/// # Example
+///
+/// ```rust,no_run
+/// # #[macro_use] extern crate rocket;
+/// # extern crate rocket_contrib;
+/// use rocket_contrib::timing::ResponseTime;
+///
+/// #[launch]
+/// fn rocket() -> rocket::Rocket {
+///     rocket::ignite().attach(ResponseTime::new())
+/// }
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct ResponseTime {
+    metric: Option<&'static str>,
+}
+
+impl ResponseTime {
+    /// The name of the header set by a `ResponseTime` created with
+    /// [`ResponseTime::new()`]: `X-Response-Time`.
+    pub const HEADER: &'static str = "X-Response-Time";
+
+    /// Creates a new fairing that sets the [`ResponseTime::HEADER`] header.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket_contrib::timing::ResponseTime;
+    ///
+    /// let timing = ResponseTime::new();
+    /// ```
+    pub fn new() -> ResponseTime {
+        ResponseTime::default()
+    }
+
+    /// Creates a new fairing that adds a `Server-Timing` metric named `metric`
+    /// to each response. Existing `Server-Timing` metrics are preserved.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket_contrib::timing::ResponseTime;
+    ///
+    /// let timing = ResponseTime::server_timing("app");
+    /// ```
+    pub fn server_timing(metric: &'static str) -> ResponseTime {
+        ResponseTime { metric: Some(metric) }
+    }
+
+    /// Returns the header reporting `elapsed` as configured in `self`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use std::time::Duration;
+    /// use rocket_contrib::timing::ResponseTime;
+    ///
+    /// let header = ResponseTime::new().header(Duration::from_micros(12345));
+    /// assert_eq!(header.to_string(), "X-Response-Time: 12.345ms");
+    ///
+    /// let header = ResponseTime::server_timing("app").header(Duration::from_millis(2));
+    /// assert_eq!(header.to_string(), "Server-Timing: app;dur=2.000");
+    /// ```
+    pub fn header(&self, elapsed: Duration) -> Header<'static> {
+        let millis = elapsed.as_nanos() as f64 / 1_000_000.0;
+        match self.metric {
+            Some(metric) => Header::new("Server-Timing", format!("{};dur={:.3}", metric, millis)),
+            None => Header::new(ResponseTime::HEADER, format!("{:.3}ms", millis)),
+        }
+    }
+}
+
+#[rocket::async_trait]
+impl Fairing for ResponseTime {
+    fn info(&self) -> Info {
+        Info { name: "Response Time", kind: Kind::Response }
+    }
+
+    async fn on_response<'r>(&self, req: &'r Request<'_>, res: &mut Response<'r>) {
+        let header = self.header(req.elapsed());
+        if self.metric.is_some() {
+            res.adjoin_header(header);
+        } else {
+            res.set_header(header);
+        }
+    }
+}
@@ -0,0 +1,522 @@
+use std::{fs, io};
+use std::fmt::Write;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use rocket::{Request, Data, Route, Response};
+use rocket::outcome::Outcome::*;
+use rocket::data::{ByteUnit, FromData};
+use rocket::handler::{Handler, Outcome};
+use rocket::response::{Responder, NamedFile, content::Content};
+use rocket::http::{Method, Status, ContentType};
+use rocket::http::uri::{Uri, Origin, Absolute, Segments};
+
+use super::{Depth, PROPFIND, MKCOL, MOVE, COPY, LOCK, UNLOCK};
+use super::xml::{Property, PropFind, Resource, Multistatus, escape, rfc1123};
+
+/// Custom handler serving a local directory as a WebDAV collection.
+///
+/// When mounted, `WebDav` generates routes for `GET` (and so `HEAD`), `PUT`,
+/// `DELETE`, `OPTIONS`, and the WebDAV methods at the mount point and every
+/// path below it. The default rank of the generated routes is `10`. To
+/// customize route ranking, use [`WebDav::rank()`].
+///
+/// The handler supports:
+///
+///   * `GET`: serves files. Requests for collections are forwarded.
+///   * `PUT`: creates or replaces a file with the request body. Bodies are
+///     limited to the `webdav` data limit, 16MiB by default.
+///   * `DELETE`: removes a file or, recursively, a collection.
+///   * `MKCOL`: creates a collection.
+///   * `COPY` and `MOVE`: copy or move a resource to the `Destination` URI,
+///     which must be below the same mount point, honoring the `Overwrite`
+///     header and, for `COPY`, a `Depth` of `0` or `infinity`.
+///   * `PROPFIND`: lists `displayname`, `resourcetype`, `getlastmodified`,
+///     `getcontentlength`, `getcontenttype`, and `supportedlock` for a
+///     resource and, with `Depth: 1`, its members. `Depth: infinity` is
+///     refused with `403 Forbidden`.
+///   * `LOCK` and `UNLOCK`: grant and release exclusive write locks. Locks are
+///     _not_ enforced: they exist for clients, like many desktop file
+///     managers, that refuse to write without one. A `LOCK` of a missing
+///     resource creates an empty file.
+///   * `OPTIONS`: advertises WebDAV compliance classes `1` and `2`.
+///
+/// Files and collections whose names start with `.` are hidden: they are not
+/// listed and requests for them are forwarded.
+///
+/// `WebDav` performs no authentication or authorization. Guard it with a
+/// fairing, or mount it only where every client may modify the directory.
+///
+/// # Example
+///
+/// To serve the `/srv/dav` directory as a WebDAV collection at `/dav`:
+///
+/// ```rust,no_run
+/// # #[macro_use] extern crate rocket;
+/// # extern crate rocket_contrib;
+/// use rocket_contrib::webdav::WebDav;
+///
+/// #[launch]
+/// fn rocket() -> rocket::Rocket {
+///     rocket::ignite().mount("/dav", WebDav::from("/srv/dav"))
+/// }
+/// ```
+#[derive(Clone)]
+pub struct WebDav {
+    root: PathBuf,
+    rank: isize,
+}
+
+impl WebDav {
+    /// The default rank use by `WebDav` routes.
+    const DEFAULT_RANK: isize = 10;
+
+    /// The default limit on `PUT` request bodies.
+    const DEFAULT_LIMIT: ByteUnit = ByteUnit::Mebibyte(16);
+
+    /// Constructs a new `WebDav` serving the file system directory `path`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `path` does not exist or is not a directory.
+    pub fn from<P: AsRef<Path>>(path: P) -> Self {
+        use rocket::yansi::Paint;
+
+        let path = path.as_ref();
+        if !path.is_dir() {
+            error!("`WebDav` supplied with invalid path");
+            info_!("'{}' is not a directory", Paint::white(path.display()));
+            panic!("refusing to continue due to invalid WebDAV path");
+        }
+
+        WebDav { root: path.into(), rank: Self::DEFAULT_RANK }
+    }
+
+    /// Sets the rank for generated routes to `rank`.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// # extern crate rocket_contrib;
+    /// use rocket_contrib::webdav::WebDav;
+    ///
+    /// WebDav::from("/srv/dav").rank(-1);
+    /// ```
+    pub fn rank(mut self, rank: isize) -> Self {
+        self.rank = rank;
+        self
+    }
+
+    /// Returns the local path for the request, or `None` if a segment is
+    /// invalid or names a hidden file.
+    fn path(&self, req: &Request<'_>) -> Option<PathBuf> {
+        let route = req.route().expect("route while handling");
+        if !route.uri.path().ends_with('>') {
+            return Some(self.root.clone());
+        }
+
+        req.get_segments::<Segments<'_>>(0)
+            .and_then(|res| res.ok())
+            .and_then(|segments| segments.into_path_buf(false).ok())
+            .map(|path| self.root.join(path))
+    }
+
+    /// Returns the local path for the request's `Destination` header.
+    fn destination(&self, req: &Request<'_>) -> Result<PathBuf, Status> {
+        let value = req.headers().get_one("Destination").ok_or(Status::BadRequest)?;
+        let path = match Absolute::parse(value) {
+            Ok(uri) => uri.origin().map(|origin| origin.path().to_string()),
+            Err(_) => Origin::parse(value).ok().map(|origin| origin.path().to_string()),
+        };
+
+        // The destination must be below our mount point.
+        let path = path.ok_or(Status::BadRequest)?;
+        let base = req.route().expect("route while handling").base().trim_end_matches('/');
+        let rest = path.strip_prefix(base)
+            .filter(|rest| rest.is_empty() || rest.starts_with('/'))
+            .ok_or(Status::BadGateway)?;
+
+        Segments(rest).into_path_buf(false)
+            .map(|path| self.root.join(path))
+            .map_err(|_| Status::Forbidden)
+    }
+
+    async fn get<'r>(&self, req: &'r Request<'_>, data: Data, path: PathBuf) -> Outcome<'r> {
+        if tokio::fs::metadata(&path).await.map_or(false, |m| m.is_dir()) {
+            return Outcome::forward(data);
+        }
+
+        Outcome::from_or_forward(req, data, NamedFile::open(path).await.ok())
+    }
+
+    async fn put<'r>(&self, req: &'r Request<'_>, data: Data, path: PathBuf) -> Outcome<'r> {
+        let target = path.clone();
+        let existed = blocking(move || {
+            Ok(if target.is_dir() {
+                Err(Status::MethodNotAllowed)
+            } else if !target.parent().map_or(false, |p| p.is_dir()) {
+                Err(Status::Conflict)
+            } else {
+                Ok(target.exists())
+            })
+        }).await;
+
+        let existed = match existed {
+            Ok(Ok(existed)) => existed,
+            Ok(Err(status)) => return Outcome::failure(status),
+            Err(e) => return Outcome::failure(io_status(&e)),
+        };
+
+        // Write to a temporary file first so that a failed or oversized upload
+        // leaves any existing resource untouched, then move it into place.
+        // Read one byte past the limit to detect bodies exceeding it.
+        let size_limit = req.limits().get("webdav").unwrap_or(Self::DEFAULT_LIMIT);
+        let stream = data.open(ByteUnit::from(size_limit.as_u64() + 1));
+        let temp = temp_path(&path);
+        let result = match stream.stream_to_file(&temp).await {
+            Ok(n) if n > size_limit.as_u64() => {
+                error_!("WebDAV upload exceeds the limit of {}.", size_limit);
+                Err(Status::PayloadTooLarge)
+            }
+            Ok(_) => tokio::fs::rename(&temp, &path).await.map_err(|e| io_status(&e)),
+            Err(e) => Err(io_status(&e)),
+        };
+
+        match result {
+            Ok(()) if existed => Outcome::from(req, Status::NoContent),
+            Ok(()) => Outcome::from(req, Status::Created),
+            Err(status) => {
+                let _ = tokio::fs::remove_file(&temp).await;
+                Outcome::failure(status)
+            }
+        }
+    }
+
+    async fn delete<'r>(&self, req: &'r Request<'_>, path: PathBuf) -> Outcome<'r> {
+        if path == self.root {
+            return Outcome::failure(Status::Forbidden);
+        }
+
+        let result = blocking(move || match fs::metadata(&path)?.is_dir() {
+            true => fs::remove_dir_all(&path),
+            false => fs::remove_file(&path),
+        }).await;
+
+        match result {
+            Ok(()) => Outcome::from(req, Status::NoContent),
+            Err(e) => Outcome::failure(io_status(&e)),
+        }
+    }
+
+    async fn mkcol<'r>(&self, req: &'r Request<'_>, data: Data, path: PathBuf) -> Outcome<'r> {
+        if !data.is_empty() {
+            return Outcome::failure(Status::UnsupportedMediaType);
+        }
+
+        let result = blocking(move || {
+            if path.exists() {
+                return Ok(Status::MethodNotAllowed);
+            } else if !path.parent().map_or(false, |p| p.is_dir()) {
+                return Ok(Status::Conflict);
+            }
+
+            fs::create_dir(&path).map(|_| Status::Created)
+        }).await;
+
+        match result {
+            Ok(status) if status.code < 300 => Outcome::from(req, status),
+            Ok(status) => Outcome::failure(status),
+            Err(e) => Outcome::failure(io_status(&e)),
+        }
+    }
+
+    async fn transfer<'r>(&self, req: &'r Request<'_>, path: PathBuf) -> Outcome<'r> {
+        let moving = req.method() == MOVE;
+        let dest = match self.destination(req) {
+            Ok(dest) => dest,
+            Err(status) => return Outcome::failure(status),
+        };
+
+        let recursive = match Depth::from_header(req.headers().get_one("Depth")) {
+            Some(Depth::Infinity) => true,
+            Some(Depth::Zero) if !moving => false,
+            _ => return Outcome::failure(Status::BadRequest),
+        };
+
+        let overwrite = req.headers().get_one("Overwrite").map_or(true, |v| v.trim() != "F");
+        if path == self.root || dest == self.root || dest.starts_with(&path) {
+            return Outcome::failure(Status::Forbidden);
+        }
+
+        let result = blocking(move || {
+            if !path.exists() {
+                return Ok(Status::NotFound);
+            } else if !dest.parent().map_or(false, |p| p.is_dir()) {
+                return Ok(Status::Conflict);
+            }
+
+            let existed = dest.exists();
+            if existed && !overwrite {
+                return Ok(Status::PreconditionFailed);
+            } else if existed && dest.is_dir() {
+                fs::remove_dir_all(&dest)?;
+            } else if existed {
+                fs::remove_file(&dest)?;
+            }
+
+            match moving {
+                true => fs::rename(&path, &dest)?,
+                false => copy(&path, &dest, recursive)?,
+            }
+
+            Ok(if existed { Status::NoContent } else { Status::Created })
+        }).await;
+
+        match result {
+            Ok(status) if status.code < 300 => Outcome::from(req, status),
+            Ok(status) => Outcome::failure(status),
+            Err(e) => Outcome::failure(io_status(&e)),
+        }
+    }
+
+    async fn propfind<'r>(&self, req: &'r Request<'_>, data: Data, path: PathBuf) -> Outcome<'r> {
+        let depth = match Depth::from_header(req.headers().get_one("Depth")) {
+            Some(Depth::Infinity) => return Outcome::failure(Status::Forbidden),
+            Some(depth) => depth,
+            None => return Outcome::failure(Status::BadRequest),
+        };
+
+        let query = match PropFind::from_data(req, data).await {
+            Success(query) => query,
+            Failure((status, _)) => return Outcome::failure(status),
+            Forward(data) => return Outcome::forward(data),
+        };
+
+        let href = req.uri().path().to_string();
+        let result = blocking(move || {
+            let metadata = fs::metadata(&path)?;
+            let name = path.file_name().map(|n| n.to_string_lossy().into_owned());
+            let mut multistatus = Multistatus::new();
+            if !metadata.is_dir() {
+                let resource = resource(&query, href, &metadata, name.as_deref());
+                return Ok(multistatus.resource(resource));
+            }
+
+            let href = if href.ends_with('/') { href } else { href + "/" };
+            let resource = resource(&query, href.clone(), &metadata, name.as_deref());
+            multistatus = multistatus.resource(resource);
+            if depth == Depth::Zero {
+                return Ok(multistatus);
+            }
+
+            let mut members = vec![];
+            for entry in fs::read_dir(&path)? {
+                let entry = entry?;
+                let name = entry.file_name().to_string_lossy().into_owned();
+                if !name.starts_with('.') {
+                    members.push((name, entry.metadata()?));
+                }
+            }
+
+            members.sort_by(|a, b| a.0.cmp(&b.0));
+            for (name, metadata) in members {
+                let mut member_href = format!("{}{}", href, Uri::percent_encode(&name));
+                if metadata.is_dir() {
+                    member_href.push('/');
+                }
+
+                let resource = resource(&query, member_href, &metadata, Some(&name));
+                multistatus = multistatus.resource(resource);
+            }
+
+            Ok(multistatus)
+        }).await;
+
+        match result {
+            Ok(multistatus) => Outcome::from(req, multistatus),
+            Err(e) => Outcome::failure(io_status(&e)),
+        }
+    }
+
+    async fn lock<'r>(&self, req: &'r Request<'_>, path: PathBuf) -> Outcome<'r> {
+        static LOCKS: AtomicUsize = AtomicUsize::new(0);
+
+        let status = blocking(move || {
+            if !path.parent().map_or(true, |p| p.is_dir()) {
+                return Ok(Status::Conflict);
+            } else if path.exists() {
+                return Ok(Status::Ok);
+            }
+
+            fs::File::create(&path).map(|_| Status::Created)
+        }).await;
+
+        let status = match status {
+            Ok(status) if status.code < 300 => status,
+            Ok(status) => return Outcome::failure(status),
+            Err(e) => return Outcome::failure(io_status(&e)),
+        };
+
+        let nanos = SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |d| d.as_nanos());
+        let id = LOCKS.fetch_add(1, Ordering::Relaxed);
+        let token = format!("opaquelocktoken:{:x}-{:x}", nanos, id);
+
+        let mut xml = String::from("<?xml version=\"1.0\" encoding=\"utf-8\"?>\n");
+        let _ = write!(xml, "<D:prop xmlns:D=\"DAV:\"><D:lockdiscovery><D:activelock>\
+            <D:locktype><D:write/></D:locktype><D:lockscope><D:exclusive/></D:lockscope>\
+            <D:depth>infinity</D:depth><D:timeout>Second-3600</D:timeout>\
+            <D:locktoken><D:href>{}</D:href></D:locktoken>\
+            <D:lockroot><D:href>{}</D:href></D:lockroot>\
+            </D:activelock></D:lockdiscovery></D:prop>", token, escape(req.uri().path()));
+
+        let mut response = Content(ContentType::XML, xml).respond_to(req)
+            .expect("string responder");
+
+        response.set_status(status);
+        response.set_raw_header("Lock-Token", format!("<{}>", token));
+        Outcome::Success(response)
+    }
+}
+
+impl Into<Vec<Route>> for WebDav {
+    fn into(self) -> Vec<Route> {
+        let methods = [
+            Method::Get, Method::Put, Method::Delete, Method::Options,
+            PROPFIND, MKCOL, MOVE, COPY, LOCK, UNLOCK,
+        ];
+
+        let mut routes = vec![];
+        for method in methods.iter() {
+            routes.push(Route::ranked(self.rank, *method, "/", self.clone()));
+            routes.push(Route::ranked(self.rank, *method, "/<path..>", self.clone()));
+        }
+
+        routes
+    }
+}
+
+#[rocket::async_trait]
+impl Handler for WebDav {
+    async fn handle<'r, 's: 'r>(&'s self, req: &'r Request<'_>, data: Data) -> Outcome<'r> {
+        let path = match self.path(req) {
+            Some(path) => path,
+            None => return Outcome::forward(data),
+        };
+
+        match req.method() {
+            Method::Get => self.get(req, data, path).await,
+            Method::Put => self.put(req, data, path).await,
+            Method::Delete => self.delete(req, path).await,
+            Method::Options => {
+                Outcome::Success(Response::build()
+                    .raw_header("DAV", "1, 2")
+                    .raw_header("MS-Author-Via", "DAV")
+                    .raw_header("Allow", "GET, HEAD, PUT, DELETE, OPTIONS, \
+                        PROPFIND, MKCOL, MOVE, COPY, LOCK, UNLOCK")
+                    .finalize())
+            }
+            PROPFIND => self.propfind(req, data, path).await,
+            MKCOL => self.mkcol(req, data, path).await,
+            MOVE | COPY => self.transfer(req, path).await,
+            LOCK => self.lock(req, path).await,
+            UNLOCK => Outcome::from(req, Status::NoContent),
+            _ => Outcome::forward(data),
+        }
+    }
+}
+
+/// Returns the properties of a file or collection requested by `query`.
+fn resource(
+    query: &PropFind,
+    href: String,
+    metadata: &fs::Metadata,
+    name: Option<&str>
+) -> Resource {
+    let mut live = vec![];
+    if let Some(name) = name {
+        live.push((Property::dav("displayname"), escape(name)));
+    }
+
+    let resourcetype = if metadata.is_dir() { "<D:collection/>" } else { "" };
+    live.push((Property::dav("resourcetype"), resourcetype.to_string()));
+    if let Ok(modified) = metadata.modified() {
+        live.push((Property::dav("getlastmodified"), rfc1123(modified.into())));
+    }
+
+    if !metadata.is_dir() {
+        let content_type = Path::new(name.unwrap_or(""))
+            .extension()
+            .and_then(|ext| ContentType::from_extension(&ext.to_string_lossy()))
+            .unwrap_or(ContentType::Binary);
+
+        live.push((Property::dav("getcontentlength"), metadata.len().to_string()));
+        live.push((Property::dav("getcontenttype"), escape(&content_type.to_string())));
+    }
+
+    live.push((Property::dav("supportedlock"), "<D:lockentry>\
+        <D:lockscope><D:exclusive/></D:lockscope><D:locktype><D:write/></D:locktype>\
+        </D:lockentry>".to_string()));
+
+    let resource = Resource::new(href);
+    match query {
+        PropFind::AllProp => live.into_iter()
+            .fold(resource, |r, (prop, value)| r.raw_prop(prop, value)),
+        PropFind::PropName => live.into_iter()
+            .fold(resource, |r, (prop, _)| r.raw_prop(prop, "")),
+        PropFind::Prop(props) => props.iter().fold(resource, |r, prop| {
+            match live.iter().find(|(p, _)| p == prop) {
+                Some((_, value)) => r.raw_prop(prop.clone(), value.clone()),
+                None => r.missing(prop.clone()),
+            }
+        })
+    }
+}
+
+/// Copies the file or collection `from` to `to`, with its members if
+/// `recursive`. Blocks.
+fn copy(from: &Path, to: &Path, recursive: bool) -> io::Result<()> {
+    if !from.is_dir() {
+        return fs::copy(from, to).map(|_| ());
+    }
+
+    fs::create_dir(to)?;
+    if recursive {
+        for entry in fs::read_dir(from)? {
+            let entry = entry?;
+            copy(&entry.path(), &to.join(entry.file_name()), true)?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Returns a unique path, hidden from listings, for a temporary file in the
+/// same directory as `path`.
+fn temp_path(path: &Path) -> PathBuf {
+    static TEMPS: AtomicUsize = AtomicUsize::new(0);
+
+    let name = path.file_name().map_or("".into(), |name| name.to_string_lossy());
+    let id = TEMPS.fetch_add(1, Ordering::Relaxed);
+    path.with_file_name(format!(".{}.{}-{}.tmp", name, std::process::id(), id))
+}
+
+/// Runs the blocking file system operation `f` on the blocking thread pool.
+async fn blocking<T, F>(f: F) -> io::Result<T>
+    where F: FnOnce() -> io::Result<T> + Send + 'static, T: Send + 'static
+{
+    match tokio::task::spawn_blocking(f).await {
+        Ok(result) => result,
+        Err(e) => Err(io::Error::new(io::ErrorKind::Other, e.to_string())),
+    }
+}
+
+/// The status to fail with for an I/O error.
+fn io_status(error: &io::Error) -> Status {
+    match error.kind() {
+        io::ErrorKind::NotFound => Status::NotFound,
+        io::ErrorKind::PermissionDenied => Status::Forbidden,
+        io::ErrorKind::AlreadyExists => Status::MethodNotAllowed,
+        _ => Status::InternalServerError,
+    }
+}
@@ -0,0 +1,130 @@
+//! WebDAV methods, XML body helpers, and a file-backed WebDAV handler.
+//!
+//! See the [`WebDav`](crate::webdav::WebDav) type for further details.
+//!
+//! # Enabling
+//!
+//! This module is only available when the `webdav` feature is enabled. Enable
+//! it in `Cargo.toml` as follows:
+//!
+//! ```toml
+//! [dependencies.rocket_contrib]
+//! version = "0.5.0-dev"
+//! default-features = false
+//! features = ["webdav"]
+//! ```
+//!
+//! # Overview
+//!
+//! WebDAV ([RFC 4918]) extends HTTP with methods for managing resources on a
+//! server. This module provides:
+//!
+//!   * The WebDAV methods as [`Method`] constants: [`PROPFIND`], [`MKCOL`],
+//!     [`MOVE`], [`COPY`], [`LOCK`], and [`UNLOCK`]. Use them with
+//!     `#[route]` as plain identifiers, as in `#[route(PROPFIND, path = "/")]`,
+//!     or with [`Route::new()`](rocket::Route::new()).
+//!   * [`Depth`], a request guard for the `Depth` header.
+//!   * [`PropFind`], a data guard for `PROPFIND` request bodies.
+//!   * [`Multistatus`] and [`Resource`], which build `207 Multi-Status`
+//!     responses.
+//!   * [`WebDav`], a handler that serves a directory on the local file system
+//!     as a WebDAV collection.
+//!
+//! [RFC 4918]: https://tools.ietf.org/html/rfc4918
+
+mod xml;
+mod handler;
+
+pub use self::xml::{Property, PropFind, Error, Resource, Multistatus};
+pub use self::handler::WebDav;
+
+use rocket::Request;
+use rocket::outcome::Outcome::*;
+use rocket::request::{self, FromRequest};
+use rocket::http::{Method, Status};
+
+/// The `PROPFIND` method: retrieves properties of a resource.
+pub const PROPFIND: Method = Method::Custom("PROPFIND");
+
+/// The `MKCOL` method: creates a collection.
+pub const MKCOL: Method = Method::Custom("MKCOL");
+
+/// The `MOVE` method: moves a resource to the `Destination` URI.
+pub const MOVE: Method = Method::Custom("MOVE");
+
+/// The `COPY` method: copies a resource to the `Destination` URI.
+pub const COPY: Method = Method::Custom("COPY");
+
+/// The `LOCK` method: takes out a lock on a resource.
+pub const LOCK: Method = Method::Custom("LOCK");
+
+/// The `UNLOCK` method: releases a lock on a resource.
+pub const UNLOCK: Method = Method::Custom("UNLOCK");
+
+/// The value of the `Depth` request header.
+///
+/// As a request guard, `Depth` fails with `400 Bad Request` if the header is
+/// malformed. A missing header is treated as `Depth: infinity`, as RFC 4918
+/// specifies for `PROPFIND` and `COPY`.
+///
+/// # Example
+///
+/// ```rust
+/// # #[macro_use] extern crate rocket;
+/// use rocket_contrib::webdav::Depth;
+///
+/// #[route(PROPFIND, path = "/")]
+/// fn root(depth: Depth) -> &'static str {
+///     match depth {
+///         Depth::Zero => "just the root",
+///         _ => "the root and its members",
+///     }
+/// }
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum Depth {
+    /// `Depth: 0`: the resource itself.
+    Zero,
+    /// `Depth: 1`: the resource and its immediate members.
+    One,
+    /// `Depth: infinity`: the resource and all of its descendants.
+    Infinity,
+}
+
+impl Depth {
+    /// Parses the value of a `Depth` header, returning `None` if it is
+    /// invalid. A missing header, `None`, is `Depth::Infinity`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket_contrib::webdav::Depth;
+    ///
+    /// assert_eq!(Depth::from_header(Some("1")), Some(Depth::One));
+    /// assert_eq!(Depth::from_header(Some("Infinity")), Some(Depth::Infinity));
+    /// assert_eq!(Depth::from_header(None), Some(Depth::Infinity));
+    /// assert_eq!(Depth::from_header(Some("2")), None);
+    /// ```
+    pub fn from_header(value: Option<&str>) -> Option<Depth> {
+        match value.map(|v| v.trim()) {
+            None => Some(Depth::Infinity),
+            Some("0") => Some(Depth::Zero),
+            Some("1") => Some(Depth::One),
+            Some(v) if v.eq_ignore_ascii_case("infinity") => Some(Depth::Infinity),
+            Some(_) => None,
+        }
+    }
+}
+
+#[rocket::async_trait]
+impl<'a, 'r> FromRequest<'a, 'r> for Depth {
+    type Error = ();
+
+    async fn from_request(req: &'a Request<'r>) -> request::Outcome<Self, ()> {
+        match Depth::from_header(req.headers().get_one("Depth")) {
+            Some(depth) => Success(depth),
+            None => Failure((Status::BadRequest, ())),
+        }
+    }
+}
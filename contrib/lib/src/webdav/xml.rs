@@ -0,0 +1,413 @@
+use std::io;
+use std::fmt::Write;
+use std::collections::HashMap;
+
+use time::{OffsetDateTime, UtcOffset};
+use tokio::io::AsyncReadExt;
+
+use rocket::{Request, Data};
+use rocket::outcome::Outcome::*;
+use rocket::data::{self, ByteUnit, FromData};
+use rocket::response::{self, Responder, content::Content, status::Custom};
+use rocket::http::{Status, ContentType};
+
+/// A property name: a local name in an XML namespace.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Property {
+    /// The namespace URI, if any. WebDAV's own properties are in `DAV:`.
+    pub namespace: Option<String>,
+    /// The local name of the property.
+    pub name: String,
+}
+
+impl Property {
+    /// The property `name` in the `DAV:` namespace.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket_contrib::webdav::Property;
+    ///
+    /// let prop = Property::dav("getcontentlength");
+    /// assert!(prop.is_dav("getcontentlength"));
+    /// ```
+    pub fn dav(name: &str) -> Property {
+        Property { namespace: Some("DAV:".into()), name: name.into() }
+    }
+
+    /// Returns `true` if `self` is the property `name` in the `DAV:`
+    /// namespace.
+    pub fn is_dav(&self, name: &str) -> bool {
+        self.namespace.as_deref() == Some("DAV:") && self.name == name
+    }
+
+    /// Writes `<name>value</name>`, or `<name/>` if `value` is `None`.
+    fn write(&self, xml: &mut String, value: Option<&str>) {
+        let tag = match self.namespace.as_deref() {
+            Some("DAV:") => format!("D:{}", self.name),
+            _ => self.name.clone(),
+        };
+
+        let _ = match self.namespace.as_deref() {
+            Some("DAV:") => write!(xml, "<{}", tag),
+            Some(ns) => write!(xml, "<{} xmlns=\"{}\"", tag, escape(ns)),
+            None => write!(xml, "<{} xmlns=\"\"", tag),
+        };
+
+        let _ = match value {
+            Some(value) => write!(xml, ">{}</{}>", value, tag),
+            None => write!(xml, "/>"),
+        };
+    }
+}
+
+/// The body of a `PROPFIND` request: which properties to retrieve.
+///
+/// As a data guard, `PropFind` reads and parses the request body. An empty
+/// body is [`PropFind::AllProp`], as RFC 4918 specifies. The body is limited
+/// to the `propfind` data limit, 32KiB by default; larger bodies fail with
+/// `413 Payload Too Large`, and bodies that can't be parsed fail with `400
+/// Bad Request`.
+///
+/// The parser understands exactly the `propfind` element and its children. It
+/// does not validate the document and ignores text, comments, and processing
+/// instructions. Namespace prefixes are resolved against every `xmlns`
+/// declaration in the document, regardless of scope.
+///
+/// # Example
+///
+/// ```rust
+/// use rocket_contrib::webdav::{PropFind, Property};
+///
+/// let body = r#"<?xml version="1.0"?>
+///     <D:propfind xmlns:D="DAV:">
+///         <D:prop><D:getetag/><D:displayname/></D:prop>
+///     </D:propfind>"#;
+///
+/// let props = vec![Property::dav("getetag"), Property::dav("displayname")];
+/// assert_eq!(PropFind::parse(body), Some(PropFind::Prop(props)));
+/// assert_eq!(PropFind::parse(""), Some(PropFind::AllProp));
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum PropFind {
+    /// Retrieve all properties and their values.
+    AllProp,
+    /// Retrieve the names of all properties.
+    PropName,
+    /// Retrieve the named properties and their values.
+    Prop(Vec<Property>),
+}
+
+/// Error returned by the [`PropFind`] data guard.
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum Error {
+    /// An I/O error occurred while reading the request body.
+    Io(io::Error),
+    /// The request body exceeded the `propfind` data limit.
+    TooLarge,
+    /// The request body is not a `propfind` document.
+    Malformed,
+}
+
+impl PropFind {
+    const DEFAULT_LIMIT: ByteUnit = ByteUnit::Kibibyte(32);
+
+    /// Parses a `PROPFIND` request body. Returns `None` if `xml` isn't empty
+    /// and isn't a `propfind` document.
+    pub fn parse(xml: &str) -> Option<PropFind> {
+        if xml.trim().is_empty() {
+            return Some(PropFind::AllProp);
+        }
+
+        let tags = tags(xml)?;
+        let mut namespaces = HashMap::new();
+        for tag in &tags {
+            for (key, value) in attributes(tag.attributes)? {
+                if key == "xmlns" {
+                    namespaces.insert("", value);
+                } else if key.starts_with("xmlns:") {
+                    namespaces.insert(&key["xmlns:".len()..], value);
+                }
+            }
+        }
+
+        let property = |qname: &str| {
+            let (prefix, name) = match qname.find(':') {
+                Some(i) => (&qname[..i], &qname[(i + 1)..]),
+                None => ("", qname),
+            };
+
+            let namespace = namespaces.get(prefix).map(|ns| ns.to_string());
+            Property { namespace, name: name.into() }
+        };
+
+        let mut tags = tags.iter();
+        match tags.next() {
+            Some(root) if !root.closing && property(root.name).is_dav("propfind") => {},
+            _ => return None,
+        }
+
+        // `depth` is the nesting depth inside of `<prop>`, if we're in one.
+        let (mut props, mut depth) = (vec![], None);
+        for tag in tags {
+            match depth {
+                Some(0) if tag.closing => depth = None,
+                Some(0) if tag.empty => props.push(property(tag.name)),
+                Some(0) => { props.push(property(tag.name)); depth = Some(1); }
+                Some(n) if tag.closing => depth = Some(n - 1),
+                Some(n) if !tag.empty => depth = Some(n + 1),
+                Some(_) => {},
+                None if tag.closing => {},
+                None => match property(tag.name) {
+                    p if p.is_dav("allprop") => return Some(PropFind::AllProp),
+                    p if p.is_dav("propname") => return Some(PropFind::PropName),
+                    p if p.is_dav("prop") && !tag.empty => depth = Some(0),
+                    _ => {},
+                }
+            }
+        }
+
+        Some(PropFind::Prop(props))
+    }
+}
+
+#[rocket::async_trait]
+impl FromData for PropFind {
+    type Error = Error;
+
+    async fn from_data(r: &Request<'_>, d: Data) -> data::Outcome<Self, Self::Error> {
+        let size_limit = r.limits().get("propfind").unwrap_or(Self::DEFAULT_LIMIT);
+
+        // Read one byte past the limit to detect bodies exceeding it.
+        let mut buf = String::new();
+        let mut reader = d.open(ByteUnit::from(size_limit.as_u64() + 1));
+        if let Err(e) = reader.read_to_string(&mut buf).await {
+            return Failure((Status::BadRequest, Error::Io(e)));
+        }
+
+        if buf.len() as u64 > size_limit.as_u64() {
+            error_!("PROPFIND body exceeds the limit of {}.", size_limit);
+            return Failure((Status::PayloadTooLarge, Error::TooLarge));
+        }
+
+        match PropFind::parse(&buf) {
+            Some(propfind) => Success(propfind),
+            None => Failure((Status::BadRequest, Error::Malformed)),
+        }
+    }
+}
+
+/// A tag in an XML document.
+struct Tag<'a> {
+    name: &'a str,
+    attributes: &'a str,
+    closing: bool,
+    empty: bool,
+}
+
+/// Splits `xml` into its tags, skipping declarations, processing
+/// instructions, and comments. Returns `None` if a tag is unterminated.
+fn tags(xml: &str) -> Option<Vec<Tag<'_>>> {
+    let mut tags = vec![];
+    let mut rest = xml;
+    while let Some(start) = rest.find('<') {
+        rest = &rest[(start + 1)..];
+        let end = if rest.starts_with("!--") {
+            rest.find("-->").map(|i| i + 2)?
+        } else {
+            rest.find('>')?
+        };
+
+        let content = &rest[..end];
+        rest = &rest[(end + 1)..];
+        if content.starts_with('?') || content.starts_with('!') {
+            continue;
+        }
+
+        let closing = content.starts_with('/');
+        let empty = content.ends_with('/');
+        let content = content.trim_start_matches('/').trim_end_matches('/');
+        let split = content.find(char::is_whitespace).unwrap_or(content.len());
+        let (name, attributes) = content.split_at(split);
+        if name.is_empty() {
+            return None;
+        }
+
+        tags.push(Tag { name, attributes, closing, empty });
+    }
+
+    Some(tags)
+}
+
+/// Parses the `key="value"` attributes in `string`.
+fn attributes(mut string: &str) -> Option<Vec<(&str, &str)>> {
+    let mut attributes = vec![];
+    while let Some(eq) = string.find('=') {
+        let key = string[..eq].trim();
+        let value = string[(eq + 1)..].trim_start();
+        let quote = value.chars().next().filter(|&c| c == '"' || c == '\'')?;
+        let end = value[1..].find(quote)? + 1;
+        attributes.push((key, &value[1..end]));
+        string = &value[(end + 1)..];
+    }
+
+    Some(attributes)
+}
+
+/// A resource in a [`Multistatus`] response, with the properties found and
+/// not found.
+///
+/// # Example
+///
+/// ```rust
+/// use rocket_contrib::webdav::{Resource, Property};
+///
+/// let resource = Resource::new("/docs/notes.txt")
+///     .prop(Property::dav("displayname"), "notes.txt")
+///     .prop(Property::dav("getcontentlength"), "1024")
+///     .missing(Property::dav("getetag"));
+/// ```
+#[derive(Debug, Clone)]
+pub struct Resource {
+    href: String,
+    found: Vec<(Property, String)>,
+    missing: Vec<Property>,
+}
+
+impl Resource {
+    /// A resource at `href`, a percent-encoded URI path, with no properties.
+    pub fn new<H: Into<String>>(href: H) -> Resource {
+        Resource { href: href.into(), found: vec![], missing: vec![] }
+    }
+
+    /// Adds the property `prop` with the text `value`, which is escaped.
+    pub fn prop<V: AsRef<str>>(self, prop: Property, value: V) -> Resource {
+        let value = escape(value.as_ref());
+        self.raw_prop(prop, value)
+    }
+
+    /// Adds the property `prop` whose value is the XML `xml`, which is written
+    /// verbatim. Elements in `xml` in the `DAV:` namespace must use the `D:`
+    /// prefix, as in `<D:collection/>`.
+    pub fn raw_prop<V: Into<String>>(mut self, prop: Property, xml: V) -> Resource {
+        self.found.push((prop, xml.into()));
+        self
+    }
+
+    /// Adds the property `prop` as not found.
+    pub fn missing(mut self, prop: Property) -> Resource {
+        self.missing.push(prop);
+        self
+    }
+
+    fn write(&self, xml: &mut String) {
+        let _ = write!(xml, "<D:response><D:href>{}</D:href>", escape(&self.href));
+        if !self.found.is_empty() {
+            xml.push_str("<D:propstat><D:prop>");
+            for (prop, value) in &self.found {
+                prop.write(xml, Some(value));
+            }
+
+            xml.push_str("</D:prop><D:status>HTTP/1.1 200 OK</D:status></D:propstat>");
+        }
+
+        if !self.missing.is_empty() {
+            xml.push_str("<D:propstat><D:prop>");
+            for prop in &self.missing {
+                prop.write(xml, None);
+            }
+
+            xml.push_str("</D:prop><D:status>HTTP/1.1 404 Not Found</D:status></D:propstat>");
+        }
+
+        xml.push_str("</D:response>");
+    }
+}
+
+/// A `207 Multi-Status` response listing the properties of resources.
+///
+/// # Example
+///
+/// ```rust
+/// # #[macro_use] extern crate rocket;
+/// use rocket_contrib::webdav::{Multistatus, Resource, Property};
+///
+/// #[route(PROPFIND, path = "/")]
+/// fn root() -> Multistatus {
+///     let root = Resource::new("/")
+///         .raw_prop(Property::dav("resourcetype"), "<D:collection/>")
+///         .prop(Property::dav("displayname"), "Home");
+///
+///     Multistatus::new().resource(root)
+/// }
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct Multistatus {
+    resources: Vec<Resource>,
+}
+
+impl Multistatus {
+    /// A `Multistatus` with no resources.
+    pub fn new() -> Multistatus {
+        Multistatus::default()
+    }
+
+    /// Adds `resource` to the response.
+    pub fn resource(mut self, resource: Resource) -> Multistatus {
+        self.resources.push(resource);
+        self
+    }
+
+    /// Renders the response body.
+    pub fn to_xml(&self) -> String {
+        let mut xml = String::from("<?xml version=\"1.0\" encoding=\"utf-8\"?>\n");
+        xml.push_str("<D:multistatus xmlns:D=\"DAV:\">");
+        for resource in &self.resources {
+            resource.write(&mut xml);
+        }
+
+        xml.push_str("</D:multistatus>");
+        xml
+    }
+}
+
+/// Responds with a status of `207 Multi-Status` and a Content-Type of
+/// `text/xml`.
+impl<'r> Responder<'r, 'static> for Multistatus {
+    fn respond_to(self, req: &'r Request<'_>) -> response::Result<'static> {
+        Custom(Status::MultiStatus, Content(ContentType::XML, self.to_xml())).respond_to(req)
+    }
+}
+
+/// Escapes the XML special characters in `string`.
+pub(crate) fn escape(string: &str) -> String {
+    let mut escaped = String::with_capacity(string.len());
+    for c in string.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            c => escaped.push(c),
+        }
+    }
+
+    escaped
+}
+
+/// Formats `date` per RFC 1123, as HTTP requires: `Sat, 04 Jul 2020 12:00:00 GMT`.
+pub(crate) fn rfc1123(date: OffsetDateTime) -> String {
+    const DAYS: [&str; 7] = ["Mon", "Tue", "Wed", "Thu", "Fri", "Sat", "Sun"];
+    const MONTHS: [&str; 12] = [
+        "Jan", "Feb", "Mar", "Apr", "May", "Jun", "Jul", "Aug", "Sep", "Oct", "Nov", "Dec"
+    ];
+
+    let date = date.to_offset(UtcOffset::UTC);
+    format!("{}, {:02} {} {:04} {:02}:{:02}:{:02} GMT",
+        DAYS[date.weekday().number_days_from_monday() as usize], date.day(),
+        MONTHS[date.month() as usize - 1], date.year(),
+        date.hour(), date.minute(), date.second())
+}
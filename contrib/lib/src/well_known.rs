@@ -0,0 +1,297 @@
+//! Handlers for `/favicon.ico` and `/.well-known/` resources.
+//!
+//! Browsers, crawlers, and platforms request a handful of resources from every
+//! site: a favicon at `/favicon.ico`, and files like `security.txt` and
+//! `apple-app-site-association` under `/.well-known/`. This module provides
+//! two custom handlers that serve these resources from memory, each mountable
+//! in one line:
+//!
+//!   * [`Favicon`] - serves an icon at `/favicon.ico`, or a `204 No Content`
+//!   * [`WellKnown`] - serves named files under `/.well-known/`
+//!
+//! # Enabling
+//!
+//! This module is only available when the `well_known` feature is enabled.
+//! Enable it in `Cargo.toml` as follows:
+//!
+//! ```toml
+//! [dependencies.rocket_contrib]
+//! version = "0.5.0-dev"
+//! default-features = false
+//! features = ["well_known"]
+//! ```
+//!
+//! # Example
+//!
+//! ```rust,no_run
+//! # #[macro_use] extern crate rocket;
+//! # extern crate rocket_contrib;
+//! use rocket_contrib::well_known::{Favicon, WellKnown};
+//!
+//! #[launch]
+//! fn rocket() -> rocket::Rocket {
+//!     let well_known = WellKnown::new()
+//!         .security_txt("Contact: mailto:security@example.com\n");
+//!
+//!     rocket::ignite()
+//!         .mount("/", Favicon::empty())
+//!         .mount("/", well_known)
+//! }
+//! ```
+
+use std::borrow::Cow;
+use std::collections::HashMap;
+
+use rocket::{Request, Data, Route};
+use rocket::http::{ContentType, Method, Status};
+use rocket::handler::{Handler, Outcome};
+use rocket::response::content::Content;
+
+/// Custom handler for serving a favicon at `/favicon.ico`.
+///
+/// A `Favicon` responds to `GET /favicon.ico`, relative to its mount point,
+/// with either the configured icon or, if none is configured, an empty `204 No
+/// Content` response, silencing the `404` that browsers otherwise provoke.
+///
+/// # Example
+///
+/// ```rust,no_run
+/// # #[macro_use] extern crate rocket;
+/// # extern crate rocket_contrib;
+/// use rocket::http::ContentType;
+/// use rocket_contrib::well_known::Favicon;
+///
+/// #[launch]
+/// fn rocket() -> rocket::Rocket {
+///     static ICON: &[u8] = &[0x89, b'P', b'N', b'G' /* ... */];
+///     rocket::ignite().mount("/", Favicon::with_type(ContentType::PNG, ICON))
+/// }
+/// ```
+#[derive(Debug, Clone)]
+pub struct Favicon {
+    icon: Option<(ContentType, Cow<'static, [u8]>)>,
+    rank: isize,
+}
+
+impl Favicon {
+    /// The default rank used by `Favicon` routes.
+    const DEFAULT_RANK: isize = 10;
+
+    /// Constructs a `Favicon` that serves `icon`, an icon in the ICO format.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket_contrib::well_known::Favicon;
+    ///
+    /// # let icon: Vec<u8> = vec![];
+    /// let favicon = Favicon::new(icon);
+    /// ```
+    pub fn new<B: Into<Cow<'static, [u8]>>>(icon: B) -> Self {
+        Favicon::with_type(ContentType::Icon, icon)
+    }
+
+    /// Constructs a `Favicon` that serves `icon` with Content-Type
+    /// `content_type`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::http::ContentType;
+    /// use rocket_contrib::well_known::Favicon;
+    ///
+    /// # let icon: Vec<u8> = vec![];
+    /// let favicon = Favicon::with_type(ContentType::PNG, icon);
+    /// ```
+    pub fn with_type<B>(content_type: ContentType, icon: B) -> Self
+        where B: Into<Cow<'static, [u8]>>
+    {
+        Favicon { icon: Some((content_type, icon.into())), rank: Self::DEFAULT_RANK }
+    }
+
+    /// Constructs a `Favicon` that responds with `204 No Content`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket_contrib::well_known::Favicon;
+    ///
+    /// let favicon = Favicon::empty();
+    /// ```
+    pub fn empty() -> Self {
+        Favicon { icon: None, rank: Self::DEFAULT_RANK }
+    }
+
+    /// Sets the rank for generated routes to `rank`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket_contrib::well_known::Favicon;
+    ///
+    /// // A `Favicon` created with `empty()` with routes of rank `3`.
+    /// Favicon::empty().rank(3);
+    /// ```
+    pub fn rank(mut self, rank: isize) -> Self {
+        self.rank = rank;
+        self
+    }
+}
+
+impl Into<Vec<Route>> for Favicon {
+    fn into(self) -> Vec<Route> {
+        vec![Route::ranked(self.rank, Method::Get, "/favicon.ico", self)]
+    }
+}
+
+#[rocket::async_trait]
+impl Handler for Favicon {
+    async fn handle<'r, 's: 'r>(&'s self, req: &'r Request<'_>, _: Data) -> Outcome<'r> {
+        match self.icon {
+            Some((ref content_type, ref icon)) => {
+                Outcome::from(req, Content(content_type.clone(), &icon[..]))
+            }
+            None => Outcome::from(req, Status::NoContent),
+        }
+    }
+}
+
+/// Custom handler for serving files under `/.well-known/`.
+///
+/// A `WellKnown` responds to `GET /.well-known/<name>`, relative to its mount
+/// point, with the contents of the file registered as `name`. Requests for
+/// names that aren't registered are forwarded. Files are registered via
+/// [`WellKnown::file()`] or one of the shorthands for common files.
+///
+/// # Example
+///
+/// ```rust,no_run
+/// # #[macro_use] extern crate rocket;
+/// # extern crate rocket_contrib;
+/// use rocket::http::ContentType;
+/// use rocket_contrib::well_known::WellKnown;
+///
+/// #[launch]
+/// fn rocket() -> rocket::Rocket {
+///     let well_known = WellKnown::new()
+///         .security_txt("Contact: mailto:security@example.com\n")
+///         .apple_app_site_association(r#"{"applinks": {"details": []}}"#)
+///         .file("dnt-policy.txt", ContentType::Plain, "Do Not Track\n".as_bytes());
+///
+///     rocket::ignite().mount("/", well_known)
+/// }
+/// ```
+#[derive(Debug, Clone)]
+pub struct WellKnown {
+    files: HashMap<Cow<'static, str>, (ContentType, Cow<'static, [u8]>)>,
+    rank: isize,
+}
+
+impl WellKnown {
+    /// The default rank used by `WellKnown` routes.
+    const DEFAULT_RANK: isize = 10;
+
+    /// Constructs a `WellKnown` with no registered files.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket_contrib::well_known::WellKnown;
+    ///
+    /// let well_known = WellKnown::new();
+    /// ```
+    pub fn new() -> Self {
+        WellKnown { files: HashMap::new(), rank: Self::DEFAULT_RANK }
+    }
+
+    /// Registers `contents` with Content-Type `content_type` to be served at
+    /// `/.well-known/{name}`. A file previously registered as `name` is
+    /// replaced.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::http::ContentType;
+    /// use rocket_contrib::well_known::WellKnown;
+    ///
+    /// let well_known = WellKnown::new()
+    ///     .file("assetlinks.json", ContentType::JSON, "[]".as_bytes());
+    /// ```
+    pub fn file<N, B>(mut self, name: N, content_type: ContentType, contents: B) -> Self
+        where N: Into<Cow<'static, str>>, B: Into<Cow<'static, [u8]>>
+    {
+        self.files.insert(name.into(), (content_type, contents.into()));
+        self
+    }
+
+    /// Registers `contents` as `security.txt` with Content-Type `text/plain`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket_contrib::well_known::WellKnown;
+    ///
+    /// let well_known = WellKnown::new()
+    ///     .security_txt("Contact: mailto:security@example.com\n");
+    /// ```
+    pub fn security_txt<B: Into<Cow<'static, str>>>(self, contents: B) -> Self {
+        self.file("security.txt", ContentType::Plain, str_bytes(contents.into()))
+    }
+
+    /// Registers `contents` as `apple-app-site-association` with Content-Type
+    /// `application/json`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket_contrib::well_known::WellKnown;
+    ///
+    /// let well_known = WellKnown::new()
+    ///     .apple_app_site_association(r#"{"applinks": {"details": []}}"#);
+    /// ```
+    pub fn apple_app_site_association<B: Into<Cow<'static, str>>>(self, contents: B) -> Self {
+        let contents = str_bytes(contents.into());
+        self.file("apple-app-site-association", ContentType::JSON, contents)
+    }
+
+    /// Sets the rank for generated routes to `rank`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket_contrib::well_known::WellKnown;
+    ///
+    /// // A `WellKnown` with routes of rank `3`.
+    /// WellKnown::new().rank(3);
+    /// ```
+    pub fn rank(mut self, rank: isize) -> Self {
+        self.rank = rank;
+        self
+    }
+}
+
+fn str_bytes(string: Cow<'static, str>) -> Cow<'static, [u8]> {
+    match string {
+        Cow::Borrowed(s) => Cow::Borrowed(s.as_bytes()),
+        Cow::Owned(s) => Cow::Owned(s.into_bytes()),
+    }
+}
+
+impl Into<Vec<Route>> for WellKnown {
+    fn into(self) -> Vec<Route> {
+        vec![Route::ranked(self.rank, Method::Get, "/.well-known/<name>", self)]
+    }
+}
+
+#[rocket::async_trait]
+impl Handler for WellKnown {
+    async fn handle<'r, 's: 'r>(&'s self, req: &'r Request<'_>, data: Data) -> Outcome<'r> {
+        let name = req.get_param::<Cow<'_, str>>(0).and_then(|name| name.ok());
+        match name.as_ref().and_then(|name| self.files.get(&**name)) {
+            Some((content_type, contents)) => {
+                Outcome::from(req, Content(content_type.clone(), &contents[..]))
+            }
+            None => Outcome::forward(data),
+        }
+    }
+}
@@ -0,0 +1,35 @@
+#[cfg(feature = "acme")]
+mod acme_tests {
+    use rocket::local::blocking::Client;
+    use rocket::figment::providers::{Format, Toml};
+
+    use rocket_contrib::acme::Acme;
+
+    fn attaches(config: &str) -> bool {
+        let figment = rocket::Config::figment().merge(Toml::string(config));
+        Client::untracked(rocket::custom(figment).attach(Acme::fairing())).is_ok()
+    }
+
+    #[test]
+    fn missing_config_fails_attach() {
+        assert!(!attaches(""));
+        assert!(!attaches("[acme]\ndomains = [\"example.com\"]"));
+        assert!(!attaches("[acme]\nemail = \"admin@example.com\""));
+    }
+
+    #[test]
+    fn invalid_config_fails_attach() {
+        assert!(!attaches("[acme]\ndomains = \"example.com\"\nemail = \"a@example.com\""));
+        assert!(!attaches("[acme]\ndomains = [\"a.com\"]\nemail = \"a@a.com\"\nhttp_port = -1"));
+    }
+
+    #[test]
+    fn empty_domains_fail_attach() {
+        assert!(!attaches("[acme]\ndomains = []\nemail = \"admin@example.com\""));
+    }
+
+    #[test]
+    fn missing_tls_fails_attach() {
+        assert!(!attaches("[acme]\ndomains = [\"example.com\"]\nemail = \"admin@example.com\""));
+    }
+}
@@ -0,0 +1,113 @@
+#[macro_use]
+#[cfg(feature = "archive")]
+extern crate rocket;
+
+#[cfg(feature = "archive")]
+mod archive_tests {
+    use std::io::{Cursor, Read};
+
+    use rocket::http::{ContentType, Status};
+    use rocket::local::blocking::Client;
+
+    use rocket_contrib::archive::Archive;
+
+    type Entries = Vec<(String, Cursor<String>)>;
+
+    fn entries() -> Entries {
+        vec![
+            ("/hello.txt".into(), Cursor::new("Hello, world!".into())),
+            ("nested/dir/goodbye.txt".into(), Cursor::new("Goodbye!".repeat(100))),
+        ]
+    }
+
+    #[get("/zip")]
+    fn zip() -> Archive<Entries> {
+        Archive::zip(entries())
+    }
+
+    #[get("/tar")]
+    fn tar() -> Archive<Entries> {
+        Archive::tar_gz(entries()).filename("files.tar.gz")
+    }
+
+    fn client() -> Client {
+        Client::tracked(rocket::ignite().mount("/", routes![zip, tar])).unwrap()
+    }
+
+    fn u16_at(bytes: &[u8], i: usize) -> u16 {
+        u16::from_le_bytes([bytes[i], bytes[i + 1]])
+    }
+
+    fn u32_at(bytes: &[u8], i: usize) -> u32 {
+        u32::from_le_bytes([bytes[i], bytes[i + 1], bytes[i + 2], bytes[i + 3]])
+    }
+
+    #[test]
+    fn zip_archive() {
+        let client = client();
+        let response = client.get("/zip").dispatch();
+        assert_eq!(response.status(), Status::Ok);
+        assert_eq!(response.content_type(), Some(ContentType::ZIP));
+        assert_eq!(response.headers().get_one("Content-Disposition"),
+            Some("attachment; filename=\"archive.zip\""));
+
+        let bytes = response.into_bytes().unwrap();
+        assert_eq!(u32_at(&bytes, 0), 0x04034b50);
+        assert_eq!(u16_at(&bytes, 26), "hello.txt".len() as u16);
+        assert_eq!(&bytes[30..39], b"hello.txt");
+        assert_eq!(&bytes[39..52], b"Hello, world!");
+        assert_eq!(u32_at(&bytes, 52), 0x08074b50);
+        assert_eq!(u32_at(&bytes, 60), 13);
+
+        // The end of central directory record closes the archive.
+        let end = bytes.len() - 22;
+        assert_eq!(u32_at(&bytes, end), 0x06054b50);
+        assert_eq!(u16_at(&bytes, end + 10), 2);
+
+        // The central directory lists both entries at their local offsets.
+        let directory = u32_at(&bytes, end + 16) as usize;
+        assert_eq!(u32_at(&bytes, directory), 0x02014b50);
+        assert_eq!(u32_at(&bytes, directory + 24), 13);
+        assert_eq!(u32_at(&bytes, directory + 42), 0);
+
+        let second = directory + 46 + "hello.txt".len();
+        assert_eq!(u32_at(&bytes, second), 0x02014b50);
+        assert_eq!(u32_at(&bytes, second + 24), 800);
+        let offset = u32_at(&bytes, second + 42) as usize;
+        assert_eq!(u32_at(&bytes, offset), 0x04034b50);
+        assert_eq!(&bytes[offset + 30..offset + 52], b"nested/dir/goodbye.txt");
+    }
+
+    #[test]
+    fn tar_gz_archive() {
+        let client = client();
+        let response = client.get("/tar").dispatch();
+        assert_eq!(response.status(), Status::Ok);
+        assert_eq!(response.content_type(), Some(ContentType::GZIP));
+        assert_eq!(response.headers().get_one("Content-Disposition"),
+            Some("attachment; filename=\"files.tar.gz\""));
+
+        let compressed = response.into_bytes().unwrap();
+        let mut bytes = vec![];
+        flate2::read::GzDecoder::new(&compressed[..]).read_to_end(&mut bytes).unwrap();
+
+        // Two entries of one and two blocks, each after a header, then two
+        // empty blocks.
+        assert_eq!(bytes.len(), 512 * (2 + 3 + 2));
+        assert!(bytes.starts_with(b"hello.txt\0"));
+        assert_eq!(&bytes[124..136], b"00000000015\0");
+        assert_eq!(&bytes[257..263], b"ustar\0");
+        assert_eq!(&bytes[512..525], b"Hello, world!");
+
+        let checksum: u32 = bytes[..512].iter().enumerate()
+            .map(|(i, &b)| if (148..156).contains(&i) { b' ' as u32 } else { b as u32 })
+            .sum();
+
+        assert_eq!(&bytes[148..156], format!("{:06o}\0 ", checksum).as_bytes());
+
+        assert!(bytes[1024..].starts_with(b"nested/dir/goodbye.txt\0"));
+        assert_eq!(&bytes[1024 + 124..1024 + 136], b"00000001440\0");
+        assert!(bytes[1536..2336].iter().eq("Goodbye!".repeat(100).as_bytes()));
+        assert!(bytes[2336..].iter().all(|&b| b == 0));
+    }
+}
@@ -0,0 +1,90 @@
+#[macro_use]
+#[cfg(feature = "audit")]
+extern crate rocket;
+
+#[cfg(feature = "audit")]
+mod audit_tests {
+    use std::io::Cursor;
+
+    use rocket::Response;
+    use rocket::http::{Accept, Cookie, CookieJar, Header, Status};
+    use rocket::local::blocking::Client;
+
+    use rocket_contrib::audit::Audit;
+
+    #[get("/untyped")]
+    fn untyped<'r>() -> Response<'r> {
+        Response::build().sized_body(None, Cursor::new("hi")).finalize()
+    }
+
+    #[get("/empty")]
+    fn empty() -> Status {
+        Status::NoContent
+    }
+
+    #[get("/not-empty")]
+    fn not_empty<'r>() -> Response<'r> {
+        Response::build()
+            .status(Status::NoContent)
+            .sized_body(None, Cursor::new("oops"))
+            .finalize()
+    }
+
+    #[get("/cached?<public>&<vary>")]
+    fn cached<'r>(jar: &CookieJar<'_>, public: bool, vary: bool) -> Response<'r> {
+        jar.add(Cookie::new("session", "1"));
+        let cache_control = match public {
+            true => "public, max-age=60",
+            false => "private, max-age=60",
+        };
+
+        let mut response = Response::build()
+            .header(Header::new("Cache-Control", cache_control))
+            .header(Header::new("Content-Encoding", "identity"))
+            .finalize();
+
+        if vary {
+            response.set_header(Header::new("Vary", "accept-encoding"));
+        }
+
+        response
+    }
+
+    #[get("/data", format = "json")]
+    fn data() -> &'static str {
+        "{}"
+    }
+
+    fn client(audit: Audit) -> Client {
+        let routes = routes![untyped, empty, not_empty, cached, data];
+        let rocket = rocket::ignite().mount("/", routes).attach(audit);
+        Client::tracked(rocket).unwrap()
+    }
+
+    fn issues(client: &Client, path: &'static str) -> Vec<String> {
+        let response = client.get(path).header(Accept::JSON).dispatch();
+        response.headers().get(Audit::HEADER).map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn reports_issues_in_header() {
+        let client = client(Audit::new().header(true));
+        assert_eq!(issues(&client, "/untyped"), vec!["missing-content-type"]);
+        assert!(issues(&client, "/empty").is_empty());
+        assert_eq!(issues(&client, "/not-empty"), vec!["body-not-allowed"]);
+
+        let expected = vec!["cacheable-with-cookie", "missing-vary"];
+        assert_eq!(issues(&client, "/cached?public=true&vary=false"), expected);
+        assert_eq!(issues(&client, "/cached?public=true&vary=true"), expected[..1].to_vec());
+        assert!(issues(&client, "/cached?public=false&vary=true").is_empty());
+
+        assert_eq!(issues(&client, "/data"), vec!["missing-vary"]);
+        assert_eq!(issues(&client, "/missing"), Vec::<String>::new());
+    }
+
+    #[test]
+    fn header_is_opt_in() {
+        let client = client(Audit::new());
+        assert!(issues(&client, "/untyped").is_empty());
+    }
+}
@@ -0,0 +1,70 @@
+#[macro_use]
+#[cfg(feature = "calendar")]
+extern crate rocket;
+
+#[cfg(feature = "calendar")]
+mod calendar_tests {
+    use rocket::http::{ContentType, Status};
+    use rocket::local::blocking::Client;
+
+    use rocket_contrib::calendar::{Calendar, Event};
+    use time::{Duration, OffsetDateTime};
+
+    fn calendar() -> Calendar {
+        let start = OffsetDateTime::from_unix_timestamp(1593864000);
+        Calendar::new()
+            .name("Events")
+            .event(Event::new("one@example.com", "Talks; then, food", start)
+                .end(start + Duration::hours(2))
+                .description("Line one\nLine two \\ three")
+                .location("x".repeat(100)))
+    }
+
+    #[get("/inline.ics")]
+    fn inline() -> Calendar {
+        calendar()
+    }
+
+    #[get("/download.ics")]
+    fn download() -> Calendar {
+        calendar().filename("events.ics")
+    }
+
+    fn client() -> Client {
+        Client::tracked(rocket::ignite().mount("/", routes![inline, download])).unwrap()
+    }
+
+    #[test]
+    fn calendar_contents() {
+        let client = client();
+        let response = client.get("/inline.ics").dispatch();
+        assert_eq!(response.status(), Status::Ok);
+        assert_eq!(response.content_type(), Some(ContentType::Calendar));
+        assert!(response.headers().get_one("Content-Disposition").is_none());
+
+        let ics = response.into_string().unwrap();
+        assert!(ics.starts_with("BEGIN:VCALENDAR\r\nVERSION:2.0\r\n\
+            PRODID:-//Rocket//rocket_contrib//EN\r\nX-WR-CALNAME:Events\r\n\
+            BEGIN:VEVENT\r\nUID:one@example.com\r\nDTSTAMP:"));
+
+        assert!(ics.contains("\r\nDTSTART:20200704T120000Z\r\nDTEND:20200704T140000Z\r\n\
+            SUMMARY:Talks\\; then\\, food\r\n\
+            DESCRIPTION:Line one\\nLine two \\\\ three\r\n"));
+
+        // The location line is folded at 75 octets.
+        let location = format!("LOCATION:{}", "x".repeat(100));
+        assert!(ics.contains(&format!("\r\n{}\r\n {}\r\n", &location[..75], &location[75..])));
+        assert!(ics.ends_with("END:VEVENT\r\nEND:VCALENDAR\r\n"));
+        assert!(ics.split("\r\n").all(|line| line.len() <= 75));
+    }
+
+    #[test]
+    fn calendar_download() {
+        let client = client();
+        let response = client.get("/download.ics").dispatch();
+        assert_eq!(response.status(), Status::Ok);
+        assert_eq!(response.content_type(), Some(ContentType::Calendar));
+        assert_eq!(response.headers().get_one("Content-Disposition"),
+            Some("attachment; filename=\"events.ics\""));
+    }
+}
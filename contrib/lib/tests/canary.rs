@@ -0,0 +1,104 @@
+#[macro_use]
+#[cfg(feature = "canary")]
+extern crate rocket;
+
+#[cfg(feature = "canary")]
+mod canary_tests {
+    use rocket::http::Cookie;
+    use rocket::local::blocking::Client;
+
+    use rocket_contrib::canary::{Split, Variant};
+
+    #[get("/")]
+    fn stable() -> &'static str {
+        "stable"
+    }
+
+    #[get("/")]
+    fn canary() -> &'static str {
+        "canary"
+    }
+
+    #[get("/other")]
+    fn other() -> Option<&'static str> {
+        None
+    }
+
+    #[get("/other")]
+    fn other_stable() -> &'static str {
+        "other stable"
+    }
+
+    fn client(split: &Split) -> Client {
+        let routes = split.routes(routes![stable, other_stable], routes![canary, other]);
+        let rocket = rocket::ignite().mount("/", routes).attach(split.clone());
+        Client::tracked(rocket).unwrap()
+    }
+
+    fn get(client: &Client, path: &'static str) -> (String, String) {
+        let response = client.get(path).dispatch();
+        let variant = response.headers().get_one(Split::HEADER).unwrap().to_string();
+        (variant, response.into_string().unwrap())
+    }
+
+    #[test]
+    fn percentages_select_variant() {
+        let split = Split::new(0);
+        let client = client(&split);
+        for _ in 0..10 {
+            assert_eq!(get(&client, "/"), ("a".into(), "stable".into()));
+        }
+
+        split.set_percent(100);
+        for _ in 0..10 {
+            assert_eq!(get(&client, "/"), ("b".into(), "canary".into()));
+        }
+
+        assert_eq!(split.served(Variant::A), 10);
+        assert_eq!(split.served(Variant::B), 10);
+    }
+
+    #[test]
+    fn forwarding_variant_falls_back_to_a() {
+        let split = Split::new(100);
+        let client = client(&split);
+        assert_eq!(get(&client, "/other"), ("a".into(), "other stable".into()));
+        assert_eq!(split.served(Variant::A), 1);
+        assert_eq!(split.served(Variant::B), 0);
+    }
+
+    #[test]
+    fn sticky_assignment_is_kept() {
+        let split = Split::new(50).sticky("variant");
+        let client = client(&split);
+        let (variant, _) = get(&client, "/");
+        let cookie = client.cookies().get("variant").map(|c| c.value().to_string());
+        assert_eq!(cookie, Some(variant.clone()));
+
+        for _ in 0..20 {
+            assert_eq!(get(&client, "/").0, variant);
+        }
+
+        let response = client.get("/").cookie(Cookie::new("variant", "b")).dispatch();
+        assert_eq!(response.into_string().unwrap(), "canary");
+    }
+
+    #[test]
+    fn rollback_overrides_sticky_assignment() {
+        let split = Split::new(50).sticky("variant");
+        let client = client(&split);
+        split.set_percent(0);
+
+        let response = client.get("/").cookie(Cookie::new("variant", "b")).dispatch();
+        assert_eq!(response.into_string().unwrap(), "stable");
+        assert_eq!(client.cookies().get("variant").unwrap().value(), "a");
+    }
+
+    #[test]
+    fn unsplit_routes_have_no_header() {
+        let split = Split::new(100);
+        let rocket = rocket::ignite().mount("/", routes![stable]).attach(split);
+        let client = Client::tracked(rocket).unwrap();
+        assert!(client.get("/").dispatch().headers().get_one(Split::HEADER).is_none());
+    }
+}
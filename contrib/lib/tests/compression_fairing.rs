@@ -4,12 +4,12 @@ extern crate rocket;
 
 #[cfg(all(feature = "brotli_compression", feature = "gzip_compression"))]
 mod compression_fairing_tests {
-    use rocket::config::{Config, Environment};
-    use rocket::http::hyper::header::{ContentEncoding, Encoding};
     use rocket::http::Status;
     use rocket::http::{ContentType, Header};
     use rocket::local::blocking::Client;
     use rocket::response::{Content, Response};
+    use rocket::figment::providers::{Format, Toml};
+    use rocket::tokio::io::AsyncReadExt;
     use rocket_contrib::compression::Compression;
 
     use std::io::Cursor;
@@ -20,45 +20,60 @@ mod compression_fairing_tests {
     const HELLO: &str = r"This is a message to hello with more than 100 bytes \
         in order to have to read more than one buffer when gzipping. こんにちは!";
 
+    fn hello() -> String {
+        HELLO.repeat(10)
+    }
+
+    #[get("/small")]
+    pub fn small() -> &'static str {
+        HELLO
+    }
+
+    #[get("/stream")]
+    pub fn stream() -> Response<'static> {
+        let body = AsyncReadExt::chain(Cursor::new(hello()), Cursor::new(hello()));
+        Response::build().streamed_body(body).finalize()
+    }
+
     #[get("/")]
     pub fn index() -> String {
-        String::from(HELLO)
+        hello()
     }
 
     #[get("/font")]
-    pub fn font() -> Content<&'static str> {
-        Content(ContentType::WOFF, HELLO)
+    pub fn font() -> Content<String> {
+        Content(ContentType::WOFF, hello())
     }
 
     #[get("/image")]
-    pub fn image() -> Content<&'static str> {
-        Content(ContentType::PNG, HELLO)
+    pub fn image() -> Content<String> {
+        Content(ContentType::PNG, hello())
     }
 
     #[get("/tar")]
-    pub fn tar() -> Content<&'static str> {
-        Content(ContentType::TAR, HELLO)
+    pub fn tar() -> Content<String> {
+        Content(ContentType::TAR, hello())
     }
 
     #[get("/already_encoded")]
     pub fn already_encoded() -> Response<'static> {
         let mut encoder = GzEncoder::new(
-            Cursor::new(String::from(HELLO)),
+            Cursor::new(hello()),
             flate2::Compression::default(),
         );
         let mut encoded = Vec::new();
         encoder.read_to_end(&mut encoded).unwrap();
         Response::build()
-            .header(ContentEncoding(vec![Encoding::Gzip]))
-            .sized_body(Cursor::new(encoded))
+            .header(Header::new("Content-Encoding", "gzip"))
+            .sized_body(None, Cursor::new(encoded))
             .finalize()
     }
 
     #[get("/identity")]
     pub fn identity() -> Response<'static> {
         Response::build()
-            .header(ContentEncoding(vec![Encoding::Identity]))
-            .sized_body(Cursor::new(String::from(HELLO)))
+            .header(Header::new("Content-Encoding", "identity"))
+            .sized_body(None, Cursor::new(hello()))
             .finalize()
     }
 
@@ -66,27 +81,23 @@ mod compression_fairing_tests {
         rocket::ignite()
             .mount(
                 "/",
-                routes![index, font, image, tar, already_encoded, identity],
+                routes![index, font, image, tar, already_encoded, identity, small, stream],
             )
             .attach(Compression::fairing())
     }
 
     fn rocket_tar_exception() -> rocket::Rocket {
-        let mut table = std::collections::BTreeMap::new();
-        table.insert("exclude".to_string(), vec!["application/x-tar"]);
-        let config = Config::build(Environment::Development)
-            .extra("compress", table)
-            .expect("valid configuration");
-
-        rocket::custom(config)
-            .mount("/", routes![image, tar])
+        let config = "[compress]\nexclude = [\"application/x-tar\"]\nmin_size = 0";
+        let figment = rocket::Config::figment().merge(Toml::string(config));
+        rocket::custom(figment)
+            .mount("/", routes![image, tar, small])
             .attach(Compression::fairing())
     }
 
     #[test]
     fn test_prioritizes_brotli() {
         let client = Client::tracked(rocket()).expect("valid rocket instance");
-        let mut response = client
+        let response = client
             .get("/")
             .header(Header::new("Accept-Encoding", "deflate, gzip, br"))
             .dispatch();
@@ -103,38 +114,27 @@ mod compression_fairing_tests {
         .expect("decompress response");
         assert_eq!(
             String::from_utf8(body_plain.get_mut().to_vec()).unwrap(),
-            String::from(HELLO)
+            hello()
         );
     }
 
     #[test]
-    fn test_br_font() {
+    fn test_does_not_compress_font() {
         let client = Client::tracked(rocket()).expect("valid rocket instance");
-        let mut response = client
+        let response = client
             .get("/font")
             .header(Header::new("Accept-Encoding", "deflate, gzip, br"))
             .dispatch();
         assert_eq!(response.status(), Status::Ok);
-        assert!(response
-            .headers()
-            .get("Content-Encoding")
-            .any(|x| x == "br"));
-        let mut body_plain = Cursor::new(Vec::<u8>::new());
-        brotli::BrotliDecompress(
-            &mut Cursor::new(response.into_bytes().unwrap()),
-            &mut body_plain,
-        )
-        .expect("decompress response");
-        assert_eq!(
-            String::from_utf8(body_plain.get_mut().to_vec()).unwrap(),
-            String::from(HELLO)
-        );
+        assert!(response.headers().get("Content-Encoding").next().is_none());
+        assert!(response.headers().get("Vary").next().is_none());
+        assert_eq!(String::from_utf8(response.into_bytes().unwrap()).unwrap(), hello());
     }
 
     #[test]
     fn test_fallback_gzip() {
         let client = Client::tracked(rocket()).expect("valid rocket instance");
-        let mut response = client
+        let response = client
             .get("/")
             .header(Header::new("Accept-Encoding", "deflate, gzip"))
             .dispatch();
@@ -151,13 +151,13 @@ mod compression_fairing_tests {
         GzDecoder::new(&response.into_bytes().unwrap()[..])
             .read_to_string(&mut s)
             .expect("decompress response");
-        assert_eq!(s, String::from(HELLO));
+        assert_eq!(s, hello());
     }
 
     #[test]
     fn test_does_not_recompress() {
         let client = Client::tracked(rocket()).expect("valid rocket instance");
-        let mut response = client
+        let response = client
             .get("/already_encoded")
             .header(Header::new("Accept-Encoding", "deflate, gzip, br"))
             .dispatch();
@@ -174,13 +174,13 @@ mod compression_fairing_tests {
         GzDecoder::new(&response.into_bytes().unwrap()[..])
             .read_to_string(&mut s)
             .expect("decompress response");
-        assert_eq!(s, String::from(HELLO));
+        assert_eq!(s, hello());
     }
 
     #[test]
     fn test_does_not_compress_explicit_identity() {
         let client = Client::tracked(rocket()).expect("valid rocket instance");
-        let mut response = client
+        let response = client
             .get("/identity")
             .header(Header::new("Accept-Encoding", "deflate, gzip, br"))
             .dispatch();
@@ -191,14 +191,14 @@ mod compression_fairing_tests {
             .any(|x| x != "identity"));
         assert_eq!(
             String::from_utf8(response.into_bytes().unwrap()).unwrap(),
-            String::from(HELLO)
+            hello()
         );
     }
 
     #[test]
     fn test_does_not_compress_image() {
         let client = Client::tracked(rocket()).expect("valid rocket instance");
-        let mut response = client
+        let response = client
             .get("/image")
             .header(Header::new("Accept-Encoding", "deflate, gzip, br"))
             .dispatch();
@@ -209,14 +209,14 @@ mod compression_fairing_tests {
             .any(|x| x != "identity"));
         assert_eq!(
             String::from_utf8(response.into_bytes().unwrap()).unwrap(),
-            String::from(HELLO)
+            hello()
         );
     }
 
     #[test]
     fn test_ignores_unimplemented_encodings() {
         let client = Client::tracked(rocket()).expect("valid rocket instance");
-        let mut response = client
+        let response = client
             .get("/")
             .header(Header::new("Accept-Encoding", "deflate"))
             .dispatch();
@@ -227,14 +227,14 @@ mod compression_fairing_tests {
             .any(|x| x != "identity"));
         assert_eq!(
             String::from_utf8(response.into_bytes().unwrap()).unwrap(),
-            String::from(HELLO)
+            hello()
         );
     }
 
     #[test]
     fn test_respects_identity_only() {
         let client = Client::tracked(rocket()).expect("valid rocket instance");
-        let mut response = client
+        let response = client
             .get("/")
             .header(Header::new("Accept-Encoding", "identity"))
             .dispatch();
@@ -245,14 +245,14 @@ mod compression_fairing_tests {
             .any(|x| x != "identity"));
         assert_eq!(
             String::from_utf8(response.into_bytes().unwrap()).unwrap(),
-            String::from(HELLO)
+            hello()
         );
     }
 
     #[test]
     fn test_does_not_compress_custom_exception() {
         let client = Client::tracked(rocket_tar_exception()).expect("valid rocket instance");
-        let mut response = client
+        let response = client
             .get("/tar")
             .header(Header::new("Accept-Encoding", "deflate, gzip, br"))
             .dispatch();
@@ -263,14 +263,14 @@ mod compression_fairing_tests {
             .any(|x| x != "identity"));
         assert_eq!(
             String::from_utf8(response.into_bytes().unwrap()).unwrap(),
-            String::from(HELLO)
+            hello()
         );
     }
 
     #[test]
     fn test_compress_custom_removed_exception() {
         let client = Client::tracked(rocket_tar_exception()).expect("valid rocket instance");
-        let mut response = client
+        let response = client
             .get("/image")
             .header(Header::new("Accept-Encoding", "deflate, gzip, br"))
             .dispatch();
@@ -287,7 +287,53 @@ mod compression_fairing_tests {
         .expect("decompress response");
         assert_eq!(
             String::from_utf8(body_plain.get_mut().to_vec()).unwrap(),
-            String::from(HELLO)
+            hello()
         );
     }
+
+    #[test]
+    fn test_does_not_compress_small_bodies() {
+        let client = Client::tracked(rocket()).expect("valid rocket instance");
+        let response = client
+            .get("/small")
+            .header(Header::new("Accept-Encoding", "deflate, gzip, br"))
+            .dispatch();
+        assert!(response.headers().get("Content-Encoding").next().is_none());
+        assert!(response.headers().get("Vary").next().is_none());
+        assert_eq!(response.into_string().unwrap(), HELLO);
+
+        let client = Client::tracked(rocket_tar_exception()).expect("valid rocket instance");
+        let response = client
+            .get("/small")
+            .header(Header::new("Accept-Encoding", "gzip"))
+            .dispatch();
+        assert_eq!(response.headers().get_one("Content-Encoding"), Some("gzip"));
+    }
+
+    #[test]
+    fn test_compresses_streamed_bodies() {
+        let client = Client::tracked(rocket()).expect("valid rocket instance");
+        let response = client
+            .get("/stream")
+            .header(Header::new("Accept-Encoding", "gzip"))
+            .dispatch();
+        assert_eq!(response.headers().get_one("Content-Encoding"), Some("gzip"));
+        let mut s = String::new();
+        GzDecoder::new(&response.into_bytes().unwrap()[..])
+            .read_to_string(&mut s)
+            .expect("decompress response");
+        assert_eq!(s, hello().repeat(2));
+    }
+
+    #[test]
+    fn test_varies_on_encoding() {
+        let client = Client::tracked(rocket()).expect("valid rocket instance");
+        let response = client
+            .get("/")
+            .header(Header::new("Accept-Encoding", "gzip;q=0, br;q=0"))
+            .dispatch();
+        assert!(response.headers().get("Content-Encoding").next().is_none());
+        assert_eq!(response.headers().get_one("Vary"), Some("Accept-Encoding"));
+        assert_eq!(response.into_string().unwrap(), hello());
+    }
 }
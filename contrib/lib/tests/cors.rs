@@ -0,0 +1,146 @@
+#[macro_use]
+#[cfg(feature = "cors")]
+extern crate rocket;
+
+#[cfg(feature = "cors")]
+mod cors_tests {
+    use std::time::Duration;
+
+    use rocket::http::{Header, Method, Status};
+    use rocket::local::blocking::Client;
+
+    use rocket_contrib::cors::Cors;
+
+    #[get("/")]
+    fn index() -> &'static str {
+        "index"
+    }
+
+    #[options("/custom")]
+    fn custom() -> &'static str {
+        "custom"
+    }
+
+    fn client(cors: Cors) -> Client {
+        let rocket = rocket::ignite().mount("/", routes![index, custom]).attach(cors);
+        Client::tracked(rocket).unwrap()
+    }
+
+    fn restricted() -> Cors {
+        Cors::new()
+            .allow_origin("https://rocket.rs")
+            .allow_methods(vec![Method::Get, Method::Post])
+            .allow_headers(vec!["Authorization", "Content-Type"])
+            .allow_credentials(true)
+            .max_age(Duration::from_secs(600))
+    }
+
+    #[test]
+    fn requests_without_origin_are_untouched() {
+        let client = client(Cors::default());
+        let response = client.get("/").dispatch();
+        assert_eq!(response.status(), Status::Ok);
+        assert!(response.headers().get_one("Access-Control-Allow-Origin").is_none());
+    }
+
+    #[test]
+    fn any_origin_is_wildcarded() {
+        let client = client(Cors::default());
+        let response = client.get("/")
+            .header(Header::new("Origin", "https://example.com"))
+            .dispatch();
+
+        assert_eq!(response.headers().get_one("Access-Control-Allow-Origin"), Some("*"));
+        assert!(response.headers().get_one("Vary").is_none());
+        assert!(response.headers().get_one("Access-Control-Allow-Credentials").is_none());
+        assert_eq!(response.into_string().unwrap(), "index");
+    }
+
+    #[test]
+    fn credentials_for_any_origin_fail_attach() {
+        let cors = Cors::default().allow_credentials(true);
+        let rocket = rocket::ignite().mount("/", routes![index]).attach(cors);
+        assert!(Client::tracked(rocket).is_err());
+
+        let cors = Cors::new().allow_origin("https://rocket.rs").allow_any_origin();
+        let rocket = rocket::ignite().attach(cors.allow_credentials(true));
+        assert!(Client::tracked(rocket).is_err());
+    }
+
+    #[test]
+    fn allowed_origins_are_echoed() {
+        let client = client(restricted());
+        let response = client.get("/")
+            .header(Header::new("Origin", "https://rocket.rs"))
+            .dispatch();
+
+        let headers = response.headers();
+        assert_eq!(headers.get_one("Access-Control-Allow-Origin"), Some("https://rocket.rs"));
+        assert_eq!(headers.get_one("Access-Control-Allow-Credentials"), Some("true"));
+        assert_eq!(headers.get_one("Vary"), Some("Origin"));
+
+        let response = client.get("/")
+            .header(Header::new("Origin", "https://evil.com"))
+            .dispatch();
+
+        assert!(response.headers().get_one("Access-Control-Allow-Origin").is_none());
+        assert_eq!(response.into_string().unwrap(), "index");
+    }
+
+    #[test]
+    fn preflight_requests_are_answered() {
+        let client = client(restricted());
+        let response = client.options("/")
+            .header(Header::new("Origin", "https://rocket.rs"))
+            .header(Header::new("Access-Control-Request-Method", "POST"))
+            .header(Header::new("Access-Control-Request-Headers", "content-type"))
+            .dispatch();
+
+        let headers = response.headers();
+        assert_eq!(response.status(), Status::NoContent);
+        assert_eq!(headers.get_one("Access-Control-Allow-Origin"), Some("https://rocket.rs"));
+        assert_eq!(headers.get_one("Access-Control-Allow-Methods"), Some("GET, POST"));
+        assert_eq!(headers.get_one("Access-Control-Allow-Headers"),
+            Some("Authorization, Content-Type"));
+        assert_eq!(headers.get_one("Access-Control-Max-Age"), Some("600"));
+        assert!(response.into_string().is_none());
+    }
+
+    #[test]
+    fn disallowed_preflight_requests_are_untouched() {
+        let client = client(restricted());
+        let response = client.options("/")
+            .header(Header::new("Origin", "https://rocket.rs"))
+            .header(Header::new("Access-Control-Request-Method", "DELETE"))
+            .dispatch();
+
+        assert_eq!(response.status(), Status::NotFound);
+        assert!(response.headers().get_one("Access-Control-Allow-Origin").is_none());
+
+        let response = client.options("/")
+            .header(Header::new("Origin", "https://rocket.rs"))
+            .header(Header::new("Access-Control-Request-Method", "GET"))
+            .header(Header::new("Access-Control-Request-Headers", "X-Secret"))
+            .dispatch();
+
+        assert_eq!(response.status(), Status::NotFound);
+        assert!(response.headers().get_one("Access-Control-Allow-Methods").is_none());
+    }
+
+    #[test]
+    fn any_header_is_echoed() {
+        let client = client(Cors::default().allow_any_header());
+        let response = client.options("/custom")
+            .header(Header::new("Origin", "https://example.com"))
+            .header(Header::new("Access-Control-Request-Method", "GET"))
+            .header(Header::new("Access-Control-Request-Headers", "X-One, X-Two"))
+            .dispatch();
+
+        // The route handled the preflight request, so its response is kept.
+        let headers = response.headers();
+        assert_eq!(response.status(), Status::Ok);
+        assert_eq!(headers.get_one("Access-Control-Allow-Headers"), Some("X-One, X-Two"));
+        assert_eq!(headers.get_one("Access-Control-Allow-Origin"), Some("*"));
+        assert_eq!(response.into_string().unwrap(), "custom");
+    }
+}
@@ -0,0 +1,75 @@
+#[macro_use]
+#[cfg(feature = "csp")]
+extern crate rocket;
+
+#[cfg(feature = "csp")]
+mod csp_tests {
+    use rocket::http::{Header, Status};
+    use rocket::local::blocking::Client;
+
+    use rocket_contrib::csp::{Csp, CspNonce, Source};
+
+    #[get("/nonce")]
+    fn nonce(nonce: CspNonce) -> String {
+        nonce.to_string()
+    }
+
+    #[derive(Responder)]
+    struct WithPolicy(&'static str, Header<'static>);
+
+    #[get("/custom")]
+    fn custom() -> WithPolicy {
+        WithPolicy("custom", Header::new(Csp::HEADER, "default-src *"))
+    }
+
+    fn client(csp: Csp) -> Client {
+        Client::tracked(rocket::ignite().mount("/", routes![nonce, custom]).attach(csp)).unwrap()
+    }
+
+    #[test]
+    fn default_policy_uses_request_nonce() {
+        let client = client(Csp::default());
+        let response = client.get("/nonce").dispatch();
+        let header = response.headers().get_one(Csp::HEADER).unwrap().to_string();
+        let nonce = response.into_string().unwrap();
+
+        assert_eq!(nonce.len(), 32);
+        assert_eq!(header, format!("default-src 'self'; \
+            script-src 'nonce-{}' 'strict-dynamic'; object-src 'none'; base-uri 'none'", nonce));
+
+        let other = client.get("/nonce").dispatch().into_string().unwrap();
+        assert_ne!(nonce, other);
+    }
+
+    #[test]
+    fn builder_replaces_directives_and_reports() {
+        let csp = Csp::new()
+            .default_src(vec![Source::SelfOrigin])
+            .img_src(vec![Source::SelfOrigin, "data:".into()])
+            .default_src(vec![Source::None])
+            .directive("upgrade-insecure-requests", vec![])
+            .report_uri("/csp-reports")
+            .report_only(true);
+
+        let client = client(csp);
+        let response = client.get("/nonce").dispatch();
+        assert!(response.headers().get_one(Csp::HEADER).is_none());
+        assert_eq!(response.headers().get_one(Csp::REPORT_ONLY_HEADER),
+            Some("default-src 'none'; img-src 'self' data:; upgrade-insecure-requests; \
+                report-uri /csp-reports"));
+    }
+
+    #[test]
+    fn existing_header_is_kept() {
+        let client = client(Csp::default());
+        let response = client.get("/custom").dispatch();
+        let headers: Vec<_> = response.headers().get(Csp::HEADER).collect();
+        assert_eq!(headers, vec!["default-src *"]);
+    }
+
+    #[test]
+    fn guard_fails_without_fairing() {
+        let client = Client::tracked(rocket::ignite().mount("/", routes![nonce])).unwrap();
+        assert_eq!(client.get("/nonce").dispatch().status(), Status::InternalServerError);
+    }
+}
@@ -0,0 +1,155 @@
+#[macro_use]
+#[cfg(feature = "csrf")]
+extern crate rocket;
+
+#[cfg(feature = "csrf")]
+mod csrf_tests {
+    use rocket::http::{ContentType, Header, Status};
+    use rocket::local::blocking::Client;
+    use rocket::request::LenientForm;
+
+    use rocket_contrib::csrf::{Csrf, CsrfToken};
+
+    #[get("/token")]
+    fn token(token: CsrfToken) -> String {
+        token.to_string()
+    }
+
+    #[post("/submit")]
+    fn submit() -> &'static str {
+        "submitted"
+    }
+
+    #[derive(FromForm)]
+    struct Comment {
+        body: String,
+    }
+
+    #[post("/comment", data = "<comment>")]
+    fn comment(comment: LenientForm<Comment>) -> String {
+        comment.into_inner().body
+    }
+
+    #[post("/<_path..>", rank = 20)]
+    fn catch_all(_path: std::path::PathBuf) -> &'static str {
+        "caught"
+    }
+
+    #[post("/hooks/deploy")]
+    fn deploy() -> &'static str {
+        "deployed"
+    }
+
+    fn client() -> Client {
+        let rocket = rocket::ignite()
+            .mount("/", routes![token, submit, comment, catch_all, deploy])
+            .attach(Csrf::new().exempt("/hooks"));
+
+        Client::tracked(rocket).unwrap()
+    }
+
+    fn fetch_token(client: &Client) -> String {
+        let response = client.get("/token").dispatch();
+        assert_eq!(response.status(), Status::Ok);
+        response.into_string().unwrap()
+    }
+
+    #[test]
+    fn token_is_stable() {
+        let client = client();
+        let token = fetch_token(&client);
+        assert_eq!(token.len(), 64);
+        assert_eq!(fetch_token(&client), token);
+        assert_ne!(fetch_token(&self::client()), token);
+    }
+
+    #[test]
+    fn requests_without_token_are_rejected() {
+        let client = client();
+        fetch_token(&client);
+
+        let response = client.post("/submit").dispatch();
+        assert_eq!(response.status(), Status::Forbidden);
+
+        let response = client.post("/submit")
+            .header(Header::new(Csrf::HEADER, "0".repeat(64)))
+            .dispatch();
+
+        assert_eq!(response.status(), Status::Forbidden);
+    }
+
+    #[test]
+    fn requests_without_cookie_are_rejected() {
+        let client = client();
+        let token = fetch_token(&client);
+
+        let other = self::client();
+        let response = other.post("/submit").header(Header::new(Csrf::HEADER, token)).dispatch();
+        assert_eq!(response.status(), Status::Forbidden);
+    }
+
+    #[test]
+    fn tokens_in_header_are_accepted() {
+        let client = client();
+        let token = fetch_token(&client);
+        let response = client.post("/submit").header(Header::new(Csrf::HEADER, token)).dispatch();
+        assert_eq!(response.status(), Status::Ok);
+        assert_eq!(response.into_string().unwrap(), "submitted");
+    }
+
+    #[test]
+    fn tokens_in_form_are_accepted() {
+        let client = client();
+        let token = fetch_token(&client);
+        let response = client.post("/submit")
+            .header(ContentType::Form)
+            .body(format!("{}={}&body=hello", Csrf::FORM_FIELD, token))
+            .dispatch();
+
+        assert_eq!(response.status(), Status::Ok);
+
+        let response = client.post("/submit")
+            .header(ContentType::Form)
+            .body(format!("{}=nope&body=hello", Csrf::FORM_FIELD))
+            .dispatch();
+
+        assert_eq!(response.status(), Status::Forbidden);
+    }
+
+    #[test]
+    fn tokens_after_long_fields_are_accepted() {
+        let client = client();
+        let token = fetch_token(&client);
+        let body = "a".repeat(2048);
+        let response = client.post("/comment")
+            .header(ContentType::Form)
+            .body(format!("body={}&{}={}", body, Csrf::FORM_FIELD, token))
+            .dispatch();
+
+        assert_eq!(response.status(), Status::Ok);
+        assert_eq!(response.into_string().unwrap(), body);
+    }
+
+    #[test]
+    fn rejected_requests_reach_no_route() {
+        let client = client();
+        fetch_token(&client);
+        let response = client.post("/unknown").dispatch();
+        assert_eq!(response.status(), Status::Forbidden);
+    }
+
+    #[test]
+    fn exempt_paths_are_not_checked() {
+        let client = client();
+        let response = client.post("/hooks/deploy").dispatch();
+        assert_eq!(response.status(), Status::Ok);
+        assert_eq!(response.into_string().unwrap(), "deployed");
+    }
+
+    #[test]
+    fn guard_fails_without_fairing() {
+        let client = Client::tracked(rocket::ignite().mount("/", routes![token])).unwrap();
+        let response = client.get("/token").dispatch();
+        assert_eq!(response.status(), Status::InternalServerError);
+    }
+}
@@ -0,0 +1,56 @@
+#[macro_use]
+#[cfg(feature = "csv_export")]
+extern crate rocket;
+
+#[cfg(feature = "csv_export")]
+mod csv_tests {
+    use rocket::http::{ContentType, Status};
+    use rocket::local::blocking::Client;
+    use serde::Serialize;
+
+    use rocket_contrib::csv::Csv;
+
+    #[derive(Serialize)]
+    struct Record {
+        id: usize,
+        name: &'static str,
+    }
+
+    #[get("/records")]
+    fn records() -> Csv<Vec<Record>> {
+        Csv::new(vec![
+            Record { id: 1, name: "Bob" },
+            Record { id: 2, name: "Alice, \"Jr.\"" },
+        ])
+    }
+
+    #[get("/numbers")]
+    fn numbers() -> Csv<impl Iterator<Item = (usize, usize)> + Send> {
+        Csv::new((0..3).map(|i| (i, i * i))).filename("squares \"1\".csv")
+    }
+
+    fn client() -> Client {
+        Client::tracked(rocket::ignite().mount("/", routes![records, numbers])).unwrap()
+    }
+
+    #[test]
+    fn test_csv_records() {
+        let client = client();
+        let response = client.get("/records").dispatch();
+        assert_eq!(response.status(), Status::Ok);
+        assert_eq!(response.content_type(), Some(ContentType::CSV));
+        assert!(response.headers().get_one("Content-Disposition").is_none());
+        assert_eq!(response.into_string().unwrap(),
+            "id,name\n1,Bob\n2,\"Alice, \"\"Jr.\"\"\"\n");
+    }
+
+    #[test]
+    fn test_csv_iterator_filename() {
+        let client = client();
+        let response = client.get("/numbers").dispatch();
+        assert_eq!(response.headers().get_one("Content-Disposition"),
+            Some("attachment; filename=\"squares \\\"1\\\".csv\""));
+
+        assert_eq!(response.into_string().unwrap(), "0,0\n1,1\n2,4\n");
+    }
+}
@@ -0,0 +1,88 @@
+#[macro_use]
+#[cfg(feature = "feed")]
+extern crate rocket;
+
+#[cfg(feature = "feed")]
+mod feed_tests {
+    use rocket::http::{ContentType, Status};
+    use rocket::local::blocking::Client;
+
+    use rocket_contrib::feed::{Feed, Entry};
+    use time::OffsetDateTime;
+
+    fn entries() -> Vec<Entry> {
+        let published = OffsetDateTime::from_unix_timestamp(1593864000);
+        vec![
+            Entry::new("Fish & <Chips>", "https://example.com/fish")
+                .summary("\"Tasty\"")
+                .author("Jo")
+                .published(published),
+            Entry::new("Second", "https://example.com/second")
+                .id("urn:second")
+                .content("<p>Hi</p>"),
+        ]
+    }
+
+    #[get("/rss")]
+    fn rss() -> Feed {
+        Feed::rss("Blog", "https://example.com").entries(entries())
+    }
+
+    #[get("/atom")]
+    fn atom() -> Feed {
+        Feed::atom("Blog", "https://example.com").description("News").entries(entries())
+    }
+
+    fn client() -> Client {
+        Client::tracked(rocket::ignite().mount("/", routes![rss, atom])).unwrap()
+    }
+
+    #[test]
+    fn rss_feed() {
+        let client = client();
+        let response = client.get("/rss").dispatch();
+        assert_eq!(response.status(), Status::Ok);
+        assert_eq!(response.content_type(), Some(ContentType::new("application", "rss+xml")));
+
+        let xml = response.into_string().unwrap();
+        assert!(xml.starts_with("<?xml version=\"1.0\" encoding=\"utf-8\"?>\n\
+            <rss version=\"2.0\">"));
+        assert!(xml.contains("<title>Blog</title><link>https://example.com</link>\
+            <description></description>"));
+        assert!(xml.contains("<lastBuildDate>Sat, 04 Jul 2020 12:00:00 +0000</lastBuildDate>"));
+        assert!(xml.contains("<item><title>Fish &amp; &lt;Chips&gt;</title>\
+            <link>https://example.com/fish</link>\
+            <guid isPermaLink=\"true\">https://example.com/fish</guid>\
+            <description>&quot;Tasty&quot;</description><author>Jo</author>\
+            <pubDate>Sat, 04 Jul 2020 12:00:00 +0000</pubDate></item>"));
+
+        assert!(xml.contains("<guid isPermaLink=\"false\">urn:second</guid>\
+            <description>&lt;p&gt;Hi&lt;/p&gt;</description></item>"));
+
+        assert!(xml.ends_with("</channel></rss>\n"));
+    }
+
+    #[test]
+    fn atom_feed() {
+        let client = client();
+        let response = client.get("/atom").dispatch();
+        assert_eq!(response.status(), Status::Ok);
+        assert_eq!(response.content_type(), Some(ContentType::new("application", "atom+xml")));
+
+        let xml = response.into_string().unwrap();
+        assert!(xml.contains("<feed xmlns=\"http://www.w3.org/2005/Atom\"><title>Blog</title>\
+            <subtitle>News</subtitle><link href=\"https://example.com\"/>\
+            <id>https://example.com</id><updated>2020-07-04T12:00:00Z</updated>"));
+
+        assert!(xml.contains("<entry><title>Fish &amp; &lt;Chips&gt;</title>\
+            <link href=\"https://example.com/fish\"/><id>https://example.com/fish</id>\
+            <updated>2020-07-04T12:00:00Z</updated><published>2020-07-04T12:00:00Z</published>\
+            <author><name>Jo</name></author><summary>&quot;Tasty&quot;</summary></entry>"));
+
+        // Entries without dates take the feed's.
+        assert!(xml.contains("<id>urn:second</id><updated>2020-07-04T12:00:00Z</updated>\
+            <content type=\"html\">&lt;p&gt;Hi&lt;/p&gt;</content></entry>"));
+
+        assert!(xml.ends_with("</feed>\n"));
+    }
+}
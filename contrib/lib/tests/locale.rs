@@ -0,0 +1,110 @@
+#[macro_use]
+#[cfg(feature = "locale")]
+extern crate rocket;
+
+#[cfg(feature = "locale")]
+mod locale_tests {
+    use rocket::http::{Cookie, Header, Status};
+    use rocket::local::blocking::Client;
+    use rocket::figment::providers::{Format, Toml};
+
+    use rocket_contrib::locale::Locale;
+
+    #[get("/")]
+    fn locale(locale: Locale) -> String {
+        locale.to_string()
+    }
+
+    #[get("/plain")]
+    fn plain() -> &'static str {
+        "plain"
+    }
+
+    fn client(config: &str) -> Client {
+        let figment = rocket::Config::figment().merge(Toml::string(config));
+        let rocket = rocket::custom(figment)
+            .mount("/", routes![locale, plain])
+            .attach(Locale::fairing());
+
+        Client::untracked(rocket).unwrap()
+    }
+
+    fn supported() -> Client {
+        client("[locale]\ndefault = \"en\"\nsupported = [\"en\", \"fr\", \"de-CH\"]")
+    }
+
+    fn get(client: &Client, uri: &str, accept: Option<&str>) -> String {
+        let mut request = client.get(uri.to_string());
+        if let Some(accept) = accept {
+            request = request.header(Header::new("Accept-Language", accept.to_string()));
+        }
+
+        request.dispatch().into_string().unwrap()
+    }
+
+    #[test]
+    fn default_locale() {
+        assert_eq!(get(&supported(), "/", None), "en");
+        assert_eq!(get(&supported(), "/", Some("ja, zh;q=0.5")), "en");
+        assert_eq!(get(&client(""), "/", None), "en");
+        assert_eq!(get(&client("[locale]\ndefault = \"fr\""), "/", None), "fr");
+    }
+
+    #[test]
+    fn accept_language() {
+        let client = supported();
+        assert_eq!(get(&client, "/", Some("fr")), "fr");
+        assert_eq!(get(&client, "/", Some("ja, fr-CA;q=0.8, en;q=0.9")), "en");
+        assert_eq!(get(&client, "/", Some("FR-ca")), "fr");
+        assert_eq!(get(&client, "/", Some("de")), "de-CH");
+        assert_eq!(get(&client, "/", Some("*, fr;q=0")), "en");
+
+        // Without supported locales, any well-formed tag is accepted.
+        let client = self::client("");
+        assert_eq!(get(&client, "/", Some("ja-JP, en;q=0.5")), "ja-JP");
+        assert_eq!(get(&client, "/", Some("<script>")), "en");
+    }
+
+    #[test]
+    fn query_and_cookie() {
+        let client = supported();
+        let response = client.get("/?lang=fr").dispatch();
+        assert_eq!(response.cookies().get(Locale::COOKIE).map(|c| c.value()), Some("fr"));
+        assert_eq!(response.into_string().unwrap(), "fr");
+
+        let response = client.get("/")
+            .cookie(Cookie::new(Locale::COOKIE, "de-CH"))
+            .header(Header::new("Accept-Language", "fr"))
+            .dispatch();
+
+        assert_eq!(response.into_string().unwrap(), "de-CH");
+
+        let response = client.get("/?lang=de-CH")
+            .cookie(Cookie::new(Locale::COOKIE, "fr"))
+            .dispatch();
+
+        assert_eq!(response.into_string().unwrap(), "de-CH");
+
+        // Unsupported choices are ignored and not persisted.
+        let response = client.get("/?lang=xx").dispatch();
+        assert!(response.cookies().get(Locale::COOKIE).is_none());
+        assert_eq!(response.into_string().unwrap(), "en");
+    }
+
+    #[test]
+    fn negotiated_responses_vary() {
+        let client = supported();
+        let response = client.get("/").dispatch();
+        assert_eq!(response.headers().get_one("Vary"), Some("Accept-Language"));
+
+        let response = client.get("/plain").dispatch();
+        assert!(response.headers().get_one("Vary").is_none());
+    }
+
+    #[test]
+    fn guard_fails_without_fairing() {
+        let client = Client::untracked(rocket::ignite().mount("/", routes![locale])).unwrap();
+        let response = client.get("/").dispatch();
+        assert_eq!(response.status(), Status::InternalServerError);
+    }
+}
@@ -0,0 +1,55 @@
+#[macro_use]
+#[cfg(feature = "msgpack")]
+extern crate rocket;
+
+#[cfg(feature = "msgpack")]
+mod msgpack_tests {
+    use rocket::http::{ContentType, Status};
+    use rocket::local::blocking::Client;
+
+    use rocket_contrib::msgpack::MsgPack;
+
+    #[post("/", format = "msgpack", data = "<person>")]
+    fn birthday(person: MsgPack<(String, u32)>) -> MsgPack<(String, u32)> {
+        let (name, age) = person.into_inner();
+        MsgPack((name.to_uppercase(), age + 1))
+    }
+
+    fn client() -> Client {
+        Client::tracked(rocket::ignite().mount("/", routes![birthday])).unwrap()
+    }
+
+    #[test]
+    fn test_msgpack_round_trip() {
+        let body = rmp_serde::to_vec(&("Bob", 21)).unwrap();
+        let response = client().post("/")
+            .header(ContentType::MsgPack)
+            .body(body)
+            .dispatch();
+
+        assert_eq!(response.status(), Status::Ok);
+        assert_eq!(response.content_type(), Some(ContentType::MsgPack));
+
+        let bytes = response.into_bytes().unwrap();
+        let person: (String, u32) = rmp_serde::from_slice(&bytes).unwrap();
+        assert_eq!(person, ("BOB".to_string(), 22));
+    }
+
+    #[test]
+    fn test_msgpack_bad_data() {
+        let client = client();
+        let response = client.post("/")
+            .header(ContentType::MsgPack)
+            .body(rmp_serde::to_vec(&"Bob").unwrap())
+            .dispatch();
+
+        assert_eq!(response.status(), Status::UnprocessableEntity);
+
+        let response = client.post("/")
+            .header(ContentType::MsgPack)
+            .body("")
+            .dispatch();
+
+        assert_eq!(response.status(), Status::BadRequest);
+    }
+}
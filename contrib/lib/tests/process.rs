@@ -0,0 +1,45 @@
+#[macro_use]
+#[cfg(all(feature = "process", unix))]
+extern crate rocket;
+
+#[cfg(all(feature = "process", unix))]
+mod process_tests {
+    use std::process::Command;
+
+    use rocket::http::Status;
+    use rocket::local::blocking::Client;
+
+    use rocket_contrib::process::CommandStream;
+
+    #[get("/lines")]
+    fn lines() -> CommandStream {
+        let mut command = Command::new("sh");
+        command.args(&["-c", "for i in 1 2 3; do echo line $i; done"]);
+        CommandStream::new(command)
+    }
+
+    #[get("/missing")]
+    fn missing() -> CommandStream {
+        CommandStream::new(Command::new("./this-command-does-not-exist"))
+    }
+
+    fn client() -> Client {
+        Client::tracked(rocket::ignite().mount("/", routes![lines, missing])).unwrap()
+    }
+
+    #[test]
+    fn test_command_output_is_streamed() {
+        let client = client();
+        let response = client.get("/lines").dispatch();
+        assert_eq!(response.status(), Status::Ok);
+        assert!(response.headers().get_one("Content-Type").is_none());
+        assert_eq!(response.into_string().unwrap(), "line 1\nline 2\nline 3\n");
+    }
+
+    #[test]
+    fn test_spawn_failure_is_500() {
+        let client = client();
+        let response = client.get("/missing").dispatch();
+        assert_eq!(response.status(), Status::InternalServerError);
+    }
+}
@@ -0,0 +1,51 @@
+#[macro_use]
+#[cfg(feature = "protobuf")]
+extern crate rocket;
+
+#[cfg(feature = "protobuf")]
+mod protobuf_tests {
+    use rocket::http::{ContentType, Status};
+    use rocket::local::blocking::Client;
+    use rocket::figment::Figment;
+
+    use rocket_contrib::protobuf::{Protobuf, Message};
+
+    #[post("/", data = "<message>")]
+    fn echo(message: Protobuf<String>) -> Protobuf<String> {
+        Protobuf(message.into_inner().to_uppercase())
+    }
+
+    fn client() -> Client {
+        let figment = Figment::from(rocket::Config::default())
+            .merge(("limits.protobuf", 32));
+
+        Client::tracked(rocket::custom(figment).mount("/", routes![echo])).unwrap()
+    }
+
+    fn encode(message: &str) -> Vec<u8> {
+        let mut buf = Vec::new();
+        message.to_string().encode(&mut buf).unwrap();
+        buf
+    }
+
+    #[test]
+    fn test_protobuf_roundtrip() {
+        let client = client();
+        let response = client.post("/").body(encode("hello")).dispatch();
+        assert_eq!(response.status(), Status::Ok);
+        assert_eq!(response.content_type(), Some(ContentType::new("application", "x-protobuf")));
+
+        let body = response.into_bytes().unwrap();
+        assert_eq!(String::decode(&body[..]).unwrap(), "HELLO");
+    }
+
+    #[test]
+    fn test_protobuf_errors() {
+        let client = client();
+        let response = client.post("/").body(encode(&"a".repeat(64))).dispatch();
+        assert_eq!(response.status(), Status::PayloadTooLarge);
+
+        let response = client.post("/").body(&[0xff, 0xff, 0xff][..]).dispatch();
+        assert_eq!(response.status(), Status::UnprocessableEntity);
+    }
+}
@@ -0,0 +1,123 @@
+#[macro_use]
+#[cfg(feature = "response_cache")]
+extern crate rocket;
+
+#[cfg(feature = "response_cache")]
+mod response_cache_tests {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::time::Duration;
+
+    use rocket::State;
+    use rocket::http::{Header, Status};
+    use rocket::local::blocking::Client;
+
+    use rocket_contrib::response_cache::{ResponseCache, CacheHandle};
+
+    #[derive(Default)]
+    struct Calls(AtomicUsize);
+
+    #[get("/articles/<id>")]
+    fn article(id: usize, calls: State<'_, Calls>) -> String {
+        let n = calls.0.fetch_add(1, Ordering::SeqCst);
+        format!("article {} render {}", id, n)
+    }
+
+    #[get("/uncached")]
+    fn uncached(calls: State<'_, Calls>) -> String {
+        format!("render {}", calls.0.fetch_add(1, Ordering::SeqCst))
+    }
+
+    #[post("/articles/<id>")]
+    async fn update(id: usize, cache: State<'_, CacheHandle>) {
+        cache.invalidate(&format!("/articles/{}", id)).await;
+    }
+
+    fn client() -> Client {
+        let cache = ResponseCache::new()
+            .cache("/articles", Duration::from_secs(60))
+            .vary("Accept-Language");
+
+        let rocket = rocket::ignite()
+            .mount("/", routes![article, uncached, update])
+            .manage(Calls::default())
+            .attach(cache);
+
+        Client::tracked(rocket).unwrap()
+    }
+
+    #[test]
+    fn test_hits_skip_handler() {
+        let client = client();
+        let response = client.get("/articles/1").dispatch();
+        assert_eq!(response.status(), Status::Ok);
+        assert!(response.headers().get_one("ETag").is_some());
+        assert_eq!(response.into_string().unwrap(), "article 1 render 0");
+
+        let response = client.get("/articles/1").dispatch();
+        assert_eq!(response.status(), Status::Ok);
+        assert_eq!(response.into_string().unwrap(), "article 1 render 0");
+
+        let response = client.get("/articles/2").dispatch();
+        assert_eq!(response.into_string().unwrap(), "article 2 render 1");
+
+        let response = client.get("/uncached").dispatch();
+        assert_eq!(response.into_string().unwrap(), "render 2");
+        let response = client.get("/uncached").dispatch();
+        assert_eq!(response.into_string().unwrap(), "render 3");
+    }
+
+    #[test]
+    fn test_vary_and_query() {
+        let client = client();
+        let response = client.get("/articles/1").dispatch();
+        assert_eq!(response.into_string().unwrap(), "article 1 render 0");
+
+        let response = client.get("/articles/1")
+            .header(Header::new("Accept-Language", "fr"))
+            .dispatch();
+        assert_eq!(response.into_string().unwrap(), "article 1 render 1");
+
+        let response = client.get("/articles/1?page=2").dispatch();
+        assert_eq!(response.into_string().unwrap(), "article 1 render 2");
+
+        let response = client.get("/articles/1")
+            .header(Header::new("Accept-Language", "fr"))
+            .dispatch();
+        assert_eq!(response.into_string().unwrap(), "article 1 render 1");
+    }
+
+    #[test]
+    fn test_etag_not_modified() {
+        let client = client();
+        let response = client.get("/articles/1").dispatch();
+        let etag = response.headers().get_one("ETag").unwrap().to_string();
+
+        let response = client.get("/articles/1")
+            .header(Header::new("If-None-Match", etag.clone()))
+            .dispatch();
+        assert_eq!(response.status(), Status::NotModified);
+        assert_eq!(response.headers().get_one("ETag"), Some(&*etag));
+        assert!(response.body().is_none());
+    }
+
+    #[test]
+    fn test_invalidation() {
+        let client = client();
+        let response = client.get("/articles/1").dispatch();
+        assert_eq!(response.into_string().unwrap(), "article 1 render 0");
+
+        client.post("/articles/1").dispatch();
+        let response = client.get("/articles/1").dispatch();
+        assert_eq!(response.into_string().unwrap(), "article 1 render 1");
+
+        let handle = client.rocket().state::<CacheHandle>().unwrap();
+        rocket::async_test(async {
+            assert_eq!(handle.len().await, 1);
+            handle.clear().await;
+            assert!(handle.is_empty().await);
+        });
+
+        let response = client.get("/articles/1").dispatch();
+        assert_eq!(response.into_string().unwrap(), "article 1 render 2");
+    }
+}
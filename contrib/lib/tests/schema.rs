@@ -0,0 +1,129 @@
+#[macro_use]
+#[cfg(feature = "schema")]
+extern crate rocket;
+
+#[cfg(feature = "schema")]
+mod schema_tests {
+    use rocket::http::{ContentType, Status};
+    use rocket::local::blocking::Client;
+    use rocket::response::content;
+
+    use schemars::JsonSchema;
+    use serde_json::json;
+    use rocket_contrib::schema::{Schema, Validator};
+
+    #[derive(JsonSchema)]
+    #[allow(dead_code)]
+    struct Task {
+        text: String,
+        tags: Vec<Tag>,
+        priority: Option<u8>,
+    }
+
+    #[derive(JsonSchema)]
+    #[allow(dead_code)]
+    struct Tag {
+        name: String,
+    }
+
+    #[post("/echo", data = "<body>")]
+    fn echo(body: String) -> content::Json<String> {
+        content::Json(body)
+    }
+
+    #[get("/task?<valid>")]
+    fn task(valid: bool) -> content::Json<&'static str> {
+        match valid {
+            true => content::Json(r#"{ "text": "hi", "tags": [{ "name": "a" }] }"#),
+            false => content::Json(r#"{ "text": "hi", "tags": [{}] }"#),
+        }
+    }
+
+    #[get("/missing")]
+    fn missing() -> Option<content::Json<&'static str>> {
+        None
+    }
+
+    fn client(strict: bool) -> Client {
+        let validator = Validator::new()
+            .request("echo", Schema::of::<Task>())
+            .response("echo", Schema::of::<Task>())
+            .response("task", Schema::of::<Task>())
+            .response("missing", Schema::of::<Task>())
+            .strict(strict);
+
+        let routes = validator.routes(routes![echo, task, missing]);
+        let rocket = rocket::ignite().mount("/", routes).attach(validator);
+        Client::tracked(rocket).unwrap()
+    }
+
+    #[test]
+    fn test_validate() {
+        let schema = Schema::of::<Task>();
+        assert!(schema.validate(&json!({ "text": "hi", "tags": [] })).is_ok());
+        assert!(schema.validate(&json!({ "text": "hi", "tags": [], "priority": 2 })).is_ok());
+
+        let mismatch = schema.validate(&json!({ "text": "hi", "tags": [{ "name": 1 }] }));
+        assert_eq!(mismatch.unwrap_err().path, "/tags/0/name");
+
+        let mismatch = schema.validate(&json!({ "text": "hi", "tags": [], "priority": -1 }));
+        assert_eq!(mismatch.unwrap_err().path, "/priority");
+
+        let mismatch = schema.validate(&json!({ "tags": [] })).unwrap_err();
+        assert_eq!(mismatch.to_string(), "missing property `text`");
+
+        let schema = Schema::new(json!({
+            "type": "object",
+            "properties": { "a/b": { "enum": ["x", "y"] } },
+            "additionalProperties": false,
+            "oneOf": [{ "required": ["a/b"] }, { "required": ["c"] }]
+        }));
+
+        assert!(schema.validate(&json!({ "a/b": "x" })).is_ok());
+        assert_eq!(schema.validate(&json!({ "a/b": "z" })).unwrap_err().path, "/a~1b");
+        assert_eq!(schema.validate(&json!({ "a/b": "x", "c": 1 })).unwrap_err().path, "/c");
+        assert!(schema.validate(&json!({})).is_err());
+    }
+
+    #[test]
+    fn test_request_validation() {
+        let valid = r#"{ "text": "hi", "tags": [] }"#;
+        let invalid = r#"{ "text": 1, "tags": [] }"#;
+
+        let client = client(false);
+        let response = client.post("/echo").header(ContentType::JSON).body(invalid).dispatch();
+        assert_eq!(response.status(), Status::Ok);
+        assert_eq!(response.into_string().unwrap(), invalid);
+
+        let client = client(true);
+        let response = client.post("/echo").header(ContentType::JSON).body(invalid).dispatch();
+        assert_eq!(response.status(), Status::UnprocessableEntity);
+
+        let response = client.post("/echo").header(ContentType::JSON).body(valid).dispatch();
+        assert_eq!(response.status(), Status::Ok);
+        assert_eq!(response.into_string().unwrap(), valid);
+
+        let response = client.post("/echo").dispatch();
+        assert_eq!(response.status(), Status::UnprocessableEntity);
+    }
+
+    #[test]
+    fn test_response_validation() {
+        let client = client(false);
+        let response = client.get("/task?valid=false").dispatch();
+        assert_eq!(response.status(), Status::Ok);
+        assert_eq!(response.into_string().unwrap(), r#"{ "text": "hi", "tags": [{}] }"#);
+
+        let client = client(true);
+        let response = client.get("/task?valid=true").dispatch();
+        assert_eq!(response.status(), Status::Ok);
+        assert_eq!(response.content_type(), Some(ContentType::JSON));
+
+        let response = client.get("/task?valid=false").dispatch();
+        assert_eq!(response.status(), Status::InternalServerError);
+        assert!(response.into_string().is_none());
+
+        let response = client.get("/missing").dispatch();
+        assert_eq!(response.status(), Status::NotFound);
+    }
+}
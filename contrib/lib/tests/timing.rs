@@ -0,0 +1,75 @@
+#[macro_use]
+#[cfg(feature = "timing")]
+extern crate rocket;
+
+#[cfg(feature = "timing")]
+mod timing_tests {
+    use std::time::Duration;
+
+    use rocket::Request;
+    use rocket::http::Header;
+    use rocket::fairing::AdHoc;
+    use rocket::local::blocking::Client;
+
+    use rocket_contrib::timing::ResponseTime;
+
+    #[get("/")]
+    fn index() -> &'static str {
+        "index"
+    }
+
+    #[get("/slow")]
+    async fn slow() -> &'static str {
+        rocket::tokio::time::delay_for(Duration::from_millis(50)).await;
+        "slow"
+    }
+
+    fn millis(value: &str, prefix: &str, suffix: &str) -> f64 {
+        let value = value.strip_prefix(prefix).and_then(|v| v.strip_suffix(suffix));
+        value.expect("well-formed timing header").parse().unwrap()
+    }
+
+    #[test]
+    fn sets_response_time_header() {
+        let rocket = rocket::ignite()
+            .mount("/", routes![index, slow])
+            .attach(ResponseTime::new());
+
+        let client = Client::tracked(rocket).unwrap();
+        let response = client.get("/").dispatch();
+        let header = response.headers().get_one(ResponseTime::HEADER).unwrap();
+        assert!(millis(header, "", "ms") >= 0.0);
+
+        let response = client.get("/slow").dispatch();
+        let header = response.headers().get_one(ResponseTime::HEADER).unwrap();
+        assert!(millis(header, "", "ms") >= 50.0);
+    }
+
+    #[test]
+    fn adds_server_timing_metric() {
+        let rocket = rocket::ignite()
+            .mount("/", routes![slow])
+            .attach(AdHoc::on_response("DB Timing", |_, res| Box::pin(async move {
+                res.set_header(Header::new("Server-Timing", "db;dur=1.5"));
+            })))
+            .attach(ResponseTime::server_timing("app"));
+
+        let client = Client::tracked(rocket).unwrap();
+        let response = client.get("/slow").dispatch();
+        let metrics: Vec<_> = response.headers().get("Server-Timing").collect();
+        assert_eq!(metrics.len(), 2);
+        assert_eq!(metrics[0], "db;dur=1.5");
+        assert!(millis(metrics[1], "app;dur=", "") >= 50.0);
+        assert!(response.headers().get_one(ResponseTime::HEADER).is_none());
+    }
+
+    #[test]
+    fn elapsed_is_monotonic() {
+        let client = Client::tracked(rocket::ignite()).unwrap();
+        let request = client.get("/");
+        let request: &Request<'_> = request.inner();
+        let first = request.elapsed();
+        assert!(request.elapsed() >= first);
+        assert!(request.started() + first <= std::time::Instant::now());
+    }
+}
@@ -0,0 +1,196 @@
+#[cfg(feature = "webdav")]
+mod webdav_tests {
+    use std::fs;
+    use std::path::PathBuf;
+
+    use rocket::http::{Header, Status};
+    use rocket::local::blocking::Client;
+    use rocket_contrib::webdav::*;
+
+    /// A scratch directory that's removed when dropped.
+    struct Scratch(PathBuf);
+
+    impl Scratch {
+        fn new(name: &str) -> Scratch {
+            let path = std::env::temp_dir().join(format!("rocket-webdav-{}", name));
+            let _ = fs::remove_dir_all(&path);
+            fs::create_dir_all(&path).expect("create scratch directory");
+            Scratch(path)
+        }
+    }
+
+    impl Drop for Scratch {
+        fn drop(&mut self) {
+            let _ = fs::remove_dir_all(&self.0);
+        }
+    }
+
+    fn client(root: &Scratch) -> Client {
+        let rocket = rocket::ignite().mount("/dav", WebDav::from(&root.0));
+        Client::tracked(rocket).expect("valid rocket")
+    }
+
+    #[test]
+    fn test_propfind_parse() {
+        let body = r#"<?xml version="1.0" encoding="utf-8"?>
+            <propfind xmlns="DAV:" xmlns:A="http://apple.com/ns/">
+              <!-- comment <prop/> -->
+              <prop><getcontentlength/><A:quota></A:quota></prop>
+            </propfind>"#;
+
+        let namespace = Some("http://apple.com/ns/".to_string());
+        let quota = Property { namespace, name: "quota".into() };
+        let props = vec![Property::dav("getcontentlength"), quota];
+        assert_eq!(PropFind::parse(body), Some(PropFind::Prop(props)));
+
+        let body = r#"<D:propfind xmlns:D="DAV:"><D:allprop/></D:propfind>"#;
+        assert_eq!(PropFind::parse(body), Some(PropFind::AllProp));
+
+        let body = r#"<D:propfind xmlns:D="DAV:"><D:propname/></D:propfind>"#;
+        assert_eq!(PropFind::parse(body), Some(PropFind::PropName));
+
+        assert_eq!(PropFind::parse("<D:lockinfo xmlns:D=\"DAV:\"/>"), None);
+        assert_eq!(PropFind::parse("<D:propfind"), None);
+    }
+
+    #[test]
+    fn test_collections_and_files() {
+        let root = Scratch::new("files");
+        let client = client(&root);
+
+        let response = client.req(MKCOL, "/dav/docs").dispatch();
+        assert_eq!(response.status(), Status::Created);
+        assert!(root.0.join("docs").is_dir());
+
+        let response = client.req(MKCOL, "/dav/docs").dispatch();
+        assert_eq!(response.status(), Status::MethodNotAllowed);
+
+        let response = client.req(MKCOL, "/dav/missing/docs").dispatch();
+        assert_eq!(response.status(), Status::Conflict);
+
+        let response = client.put("/dav/docs/a%20b.txt").body("hello").dispatch();
+        assert_eq!(response.status(), Status::Created);
+        let response = client.put("/dav/docs/a%20b.txt").body("hello!").dispatch();
+        assert_eq!(response.status(), Status::NoContent);
+
+        let response = client.get("/dav/docs/a%20b.txt").dispatch();
+        assert_eq!(response.into_string().unwrap(), "hello!");
+
+        let response = client.delete("/dav/docs").dispatch();
+        assert_eq!(response.status(), Status::NoContent);
+        assert!(!root.0.join("docs").exists());
+
+        let response = client.delete("/dav/docs").dispatch();
+        assert_eq!(response.status(), Status::NotFound);
+    }
+
+    #[test]
+    fn test_oversized_put_keeps_original() {
+        use rocket::data::{Limits, ToByteUnit};
+
+        let root = Scratch::new("oversized");
+        let limits = Limits::default().limit("webdav", 8.bytes());
+        let figment = rocket::Config::figment().merge(("limits", limits));
+        let rocket = rocket::custom(figment).mount("/dav", WebDav::from(&root.0));
+        let client = Client::tracked(rocket).expect("valid rocket");
+
+        let response = client.put("/dav/file.txt").body("original").dispatch();
+        assert_eq!(response.status(), Status::Created);
+
+        let response = client.put("/dav/file.txt").body("far too large").dispatch();
+        assert_eq!(response.status(), Status::PayloadTooLarge);
+        assert_eq!(fs::read_to_string(root.0.join("file.txt")).unwrap(), "original");
+        assert_eq!(fs::read_dir(&root.0).unwrap().count(), 1);
+    }
+
+    #[test]
+    fn test_copy_and_move() {
+        let root = Scratch::new("transfer");
+        let client = client(&root);
+        fs::write(root.0.join("a.txt"), "a").unwrap();
+
+        let response = client.req(COPY, "/dav/a.txt")
+            .header(Header::new("Destination", "http://localhost/dav/b.txt"))
+            .dispatch();
+
+        assert_eq!(response.status(), Status::Created);
+        assert_eq!(fs::read_to_string(root.0.join("b.txt")).unwrap(), "a");
+
+        let response = client.req(MOVE, "/dav/a.txt")
+            .header(Header::new("Destination", "/dav/b.txt"))
+            .header(Header::new("Overwrite", "F"))
+            .dispatch();
+
+        assert_eq!(response.status(), Status::PreconditionFailed);
+
+        let response = client.req(MOVE, "/dav/a.txt")
+            .header(Header::new("Destination", "/dav/b.txt"))
+            .dispatch();
+
+        assert_eq!(response.status(), Status::NoContent);
+        assert!(!root.0.join("a.txt").exists());
+
+        let response = client.req(MOVE, "/dav/b.txt")
+            .header(Header::new("Destination", "/elsewhere/b.txt"))
+            .dispatch();
+
+        assert_eq!(response.status(), Status::BadGateway);
+    }
+
+    #[test]
+    fn test_propfind() {
+        let root = Scratch::new("propfind");
+        let client = client(&root);
+        fs::create_dir(root.0.join("sub")).unwrap();
+        fs::write(root.0.join("notes.txt"), "12345").unwrap();
+        fs::write(root.0.join(".hidden"), "").unwrap();
+
+        let response = client.req(PROPFIND, "/dav").header(Header::new("Depth", "1")).dispatch();
+        assert_eq!(response.status(), Status::MultiStatus);
+        let body = response.into_string().unwrap();
+        assert!(body.contains("<D:href>/dav/</D:href>"));
+        assert!(body.contains("<D:href>/dav/notes.txt</D:href>"));
+        assert!(body.contains("<D:href>/dav/sub/</D:href>"));
+        assert!(body.contains("<D:getcontentlength>5</D:getcontentlength>"));
+        assert!(!body.contains(".hidden"));
+
+        let query = r#"<D:propfind xmlns:D="DAV:">
+            <D:prop><D:getcontentlength/><D:getetag/></D:prop>
+        </D:propfind>"#;
+
+        let response = client.req(PROPFIND, "/dav/notes.txt")
+            .header(Header::new("Depth", "0"))
+            .body(query)
+            .dispatch();
+
+        let body = response.into_string().unwrap();
+        assert!(body.contains("<D:getcontentlength>5</D:getcontentlength>"));
+        assert!(body.contains("<D:getetag/></D:prop><D:status>HTTP/1.1 404 Not Found"));
+        assert!(!body.contains("displayname"));
+
+        let response = client.req(PROPFIND, "/dav").dispatch();
+        assert_eq!(response.status(), Status::Forbidden);
+    }
+
+    #[test]
+    fn test_lock_and_options() {
+        let root = Scratch::new("lock");
+        let client = client(&root);
+
+        let response = client.req(LOCK, "/dav/new.txt").dispatch();
+        assert_eq!(response.status(), Status::Created);
+        assert!(root.0.join("new.txt").is_file());
+
+        let token = response.headers().get_one("Lock-Token").unwrap();
+        assert!(token.starts_with("<opaquelocktoken:"));
+
+        let response = client.req(UNLOCK, "/dav/new.txt")
+            .header(Header::new("Lock-Token", token.to_string()))
+            .dispatch();
+
+        assert_eq!(response.status(), Status::NoContent);
+
+        let response = client.options("/dav/new.txt").dispatch();
+        assert_eq!(response.headers().get_one("DAV"), Some("1, 2"));
+    }
+}
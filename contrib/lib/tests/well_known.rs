@@ -0,0 +1,54 @@
+#[cfg(feature = "well_known")]
+mod well_known_tests {
+    use rocket::http::{ContentType, Status};
+    use rocket::local::blocking::Client;
+
+    use rocket_contrib::well_known::{Favicon, WellKnown};
+
+    static ICON: &[u8] = &[0, 0, 1, 0];
+
+    fn client(favicon: Favicon) -> Client {
+        let well_known = WellKnown::new()
+            .security_txt("Contact: mailto:security@example.com\n")
+            .apple_app_site_association(String::from("{}"))
+            .file("assetlinks.json", ContentType::JSON, "[]".as_bytes());
+
+        let rocket = rocket::ignite()
+            .mount("/", favicon)
+            .mount("/", well_known);
+
+        Client::tracked(rocket).unwrap()
+    }
+
+    #[test]
+    fn test_favicon() {
+        let client = client(Favicon::new(ICON));
+        let response = client.get("/favicon.ico").dispatch();
+        assert_eq!(response.status(), Status::Ok);
+        assert_eq!(response.content_type(), Some(ContentType::Icon));
+        assert_eq!(response.into_bytes().unwrap(), ICON);
+
+        let client = self::client(Favicon::empty());
+        let response = client.get("/favicon.ico").dispatch();
+        assert_eq!(response.status(), Status::NoContent);
+        assert!(response.body().is_none());
+    }
+
+    #[test]
+    fn test_well_known() {
+        let client = client(Favicon::empty());
+        let response = client.get("/.well-known/security.txt").dispatch();
+        assert_eq!(response.content_type(), Some(ContentType::Plain));
+        assert_eq!(response.into_string().unwrap(), "Contact: mailto:security@example.com\n");
+
+        let response = client.get("/.well-known/apple-app-site-association").dispatch();
+        assert_eq!(response.content_type(), Some(ContentType::JSON));
+        assert_eq!(response.into_string().unwrap(), "{}");
+
+        let response = client.get("/.well-known/assetlinks.json").dispatch();
+        assert_eq!(response.into_string().unwrap(), "[]");
+
+        let response = client.get("/.well-known/unknown").dispatch();
+        assert_eq!(response.status(), Status::NotFound);
+    }
+}
@@ -46,6 +46,27 @@ impl EntryAttr for Test {
     }
 }
 
+struct RocketTest;
+
+impl EntryAttr for RocketTest {
+    const REQUIRES_ASYNC: bool = false;
+
+    fn function(f: &mut syn::ItemFn) -> Result<TokenStream> {
+        // The built-in attribute is named by its full path so that a `test`
+        // in scope at the call site can't shadow it.
+        let (attrs, vis, block, sig) = (&f.attrs, &f.vis, &f.block, &mut f.sig);
+        let test = quote_spanned!(block.span().into() => #[::core::prelude::v1::test]);
+        if sig.asyncness.is_none() {
+            return Ok(quote_spanned!(block.span().into() => #(#attrs)* #test #vis #sig #block));
+        }
+
+        sig.asyncness = None;
+        Ok(quote_spanned!(block.span().into() => #(#attrs)* #test #vis #sig {
+            ::rocket::async_test(async move #block)
+        }))
+    }
+}
+
 struct Launch;
 
 impl EntryAttr for Launch {
@@ -137,5 +158,6 @@ macro_rules! async_entry {
 }
 
 async_entry!(async_test_attribute, Test, quote!());
+async_entry!(rocket_test_attribute, RocketTest, quote!());
 async_entry!(main_attribute, Main, quote!(fn main() {}));
 async_entry!(launch_attribute, Launch, quote!(fn main() {}));
@@ -4,7 +4,7 @@ use devise::{syn, MetaItem, Spanned, Result, FromMeta, Diagnostic};
 use crate::http_codegen::{self, Optional};
 use crate::proc_macro2::{TokenStream, Span};
 use crate::syn_ext::{ReturnTypeExt, TokenStreamExt};
-use self::syn::{Attribute, parse::Parser};
+use self::syn::{Attribute, Token, parse::{Parser, ParseStream}};
 
 /// The raw, parsed `#[catch(code)]` attribute.
 #[derive(Debug, FromMeta)]
@@ -35,10 +35,61 @@ impl FromMeta for CatcherCode {
     }
 }
 
+/// The half-open range of codes in a `#[catch(start..end)]` attribute.
+#[derive(Debug)]
+struct CatcherRange(u16, u16);
+
+impl CatcherRange {
+    /// Parses `start..end` or `start..=end` from `input`, where both bounds are
+    /// integer literals. Either bound may be missing so that unbounded ranges
+    /// can be diagnosed.
+    fn parse(
+        input: ParseStream<'_>
+    ) -> syn::Result<(Option<syn::LitInt>, bool, Option<syn::LitInt>)> {
+        let start = if input.peek(syn::LitInt) { Some(input.parse()?) } else { None };
+        let inclusive = if input.peek(Token![..=]) {
+            input.parse::<Token![..=]>()?;
+            true
+        } else {
+            input.parse::<Token![..]>()?;
+            false
+        };
+
+        let end = if input.is_empty() { None } else { Some(input.parse()?) };
+        Ok((start, inclusive, end))
+    }
+
+    /// Returns `None` if `args` isn't a range, or the validated range if it is.
+    fn from_args(args: &TokenStream) -> Option<Result<Self>> {
+        let (start, inclusive, end) = match Self::parse.parse2(args.clone()).ok()? {
+            (Some(start), inclusive, Some(end)) => (start, inclusive, end),
+            _ => return Some(Err(args.span().error("status code range must be bounded")
+                .help("ranges need both a start and an end, as in `#[catch(400..500)]`"))),
+        };
+
+        let code = |lit: &syn::LitInt, min: u16, max: u16| match lit.base10_parse::<u16>() {
+            Ok(n) if n >= min && n <= max => Ok(n),
+            _ => Err(lit.span().error(format!("status must be in range [{}, {}]", min, max))),
+        };
+
+        let range = code(&start, 100, 599).and_then(|start| match inclusive {
+            true => code(&end, 100, 599).map(|end| CatcherRange(start, end + 1)),
+            false => code(&end, 101, 600).map(|end| CatcherRange(start, end)),
+        });
+
+        Some(range.and_then(|range| match range.0 < range.1 {
+            true => Ok(range),
+            false => Err(args.span().error("status code range must not be empty")),
+        }))
+    }
+}
+
 /// This structure represents the parsed `catch` attribute and associated items.
 struct CatchParams {
     /// The status associated with the code in the `#[catch(code)]` attribute.
     status: Option<http_codegen::Status>,
+    /// The range of codes in a `#[catch(start..end)]` attribute.
+    range: Option<CatcherRange>,
     /// The function that was decorated with the `catch` attribute.
     function: syn::ItemFn,
 }
@@ -51,6 +102,10 @@ fn parse_params(
         .map_err(Diagnostic::from)
         .map_err(|diag| diag.help("`#[catch]` can only be used on functions"))?;
 
+    if let Some(range) = CatcherRange::from_args(&args) {
+        return Ok(CatchParams { status: None, range: Some(range?), function });
+    }
+
     let full_attr = quote!(#[catch(#args)]);
     let attrs = Attribute::parse_outer.parse2(full_attr)?;
     let attribute = match CatchAttribute::from_attrs("catch", &attrs) {
@@ -61,7 +116,7 @@ fn parse_params(
         None => return Err(Span::call_site().error("internal error: bad attribute"))
     };
 
-    Ok(CatchParams { status: attribute.status.0, function })
+    Ok(CatchParams { status: attribute.status.0, range: None, function })
 }
 
 pub fn _catch(
@@ -76,6 +131,10 @@ pub fn _catch(
     let user_catcher_fn_name = catch.function.sig.ident.clone();
     let (vis, catcher_status) = (&catch.function.vis, &catch.status);
     let status_code = Optional(catcher_status.as_ref().map(|s| s.0.code));
+    let status_range = match catch.range {
+        Some(CatcherRange(start, end)) => quote!(::std::option::Option::Some(#start..#end)),
+        None => quote!(::std::option::Option::None),
+    };
 
     // Variables names we'll use and reuse.
     define_vars_and_mods!(catch.function.span().into() =>
@@ -140,6 +199,7 @@ pub fn _catch(
 
                 #StaticCatcherInfo {
                     code: #status_code,
+                    range: #status_range,
                     handler: monomorphized_function,
                 }
             }
@@ -7,9 +7,9 @@ use devise::ext::{SpanDiagnosticExt, TypeExt};
 use indexmap::IndexSet;
 
 use crate::proc_macro_ext::{Diagnostics, StringLit};
-use crate::syn_ext::{IdentExt, NameSource};
+use crate::syn_ext::{IdentExt, NameSource, ReturnTypeExt};
 use crate::proc_macro2::{TokenStream, Span};
-use crate::http_codegen::{Method, MediaType, RoutePath, DataSegment, Optional};
+use crate::http_codegen::{Method, MediaType, RoutePath, DataSegment, MatcherPath, Optional};
 use crate::attribute::segments::{Source, Kind, Segment};
 use crate::syn::{Attribute, parse::Parser};
 
@@ -22,8 +22,10 @@ struct RouteAttribute {
     method: SpanWrapped<Method>,
     path: RoutePath,
     data: Option<SpanWrapped<DataSegment>>,
-    format: Option<MediaType>,
+    format: Option<SpanWrapped<MediaType>>,
     rank: Option<isize>,
+    matcher: Option<MatcherPath>,
+    allow_payload: Option<SpanWrapped<bool>>,
 }
 
 /// The raw, parsed `#[method]` (e.g, `get`, `put`, `post`, etc.) attribute.
@@ -32,8 +34,10 @@ struct MethodRouteAttribute {
     #[meta(naked)]
     path: RoutePath,
     data: Option<SpanWrapped<DataSegment>>,
-    format: Option<MediaType>,
+    format: Option<SpanWrapped<MediaType>>,
     rank: Option<isize>,
+    matcher: Option<MatcherPath>,
+    allow_payload: Option<SpanWrapped<bool>>,
 }
 
 /// This structure represents the parsed `route` attribute and associated items.
@@ -55,13 +59,34 @@ fn parse_route(attr: RouteAttribute, function: syn::ItemFn) -> Result<Route> {
     // Gather diagnostics as we proceed.
     let mut diags = Diagnostics::new();
 
-    // Emit a warning if a `data` param was supplied for non-payload methods.
-    if let Some(ref data) = attr.data {
-        if !attr.method.0.supports_payload() {
-            let msg = format!("'{}' does not typically support payloads", attr.method.0);
+    // Check that `data` is only used with non-payload methods when allowed.
+    let method = &attr.method;
+    let allowed = attr.allow_payload.as_ref().map(|allow| allow.value);
+    match (&attr.data, &attr.allow_payload) {
+        (Some(data), _) if !method.0.supports_payload() && allowed != Some(true) => {
+            let msg = format!("'{}' does not typically support payloads", method.0);
+            diags.push(data.full_span.error("`data` used with non-payload-supporting method")
+                .span_note(method.span, msg)
+                .help("use `allow_payload = true` to accept a payload anyway"));
+        }
+        (None, Some(allow)) => {
+            diags.push(allow.full_span.error("`allow_payload` used without `data`")
+                .help("`allow_payload` only applies to routes with a `data` parameter"));
+        }
+        (Some(_), Some(allow)) if method.0.supports_payload() => {
+            let msg = format!("'{}' supports payloads", method.0);
+            diags.push(allow.full_span.error("`allow_payload` used with payload-supporting method")
+                .span_note(method.span, msg));
+        }
+        _ => {}
+    }
+
+    // Emit a warning if a non-payload route has a `format` that matches all.
+    if let Some(ref format) = attr.format {
+        if !method.0.supports_payload() && format.value.0 == crate::http::MediaType::Any {
             // FIXME(diag: warning)
-            data.full_span.warning("`data` used with non-payload-supporting method")
-                .span_note(attr.method.span, msg)
+            format.full_span.warning("`format = \"*/*\"` matches every request")
+                .help("remove the `format` parameter; it has no effect")
                 .emit_as_item_tokens();
         }
     }
@@ -129,6 +154,7 @@ fn param_expr(seg: &Segment, ident: &syn::Ident, ty: &syn::Type) -> TokenStream
     let i = seg.index.expect("dynamic parameters must be indexed");
     let span = ident.span().join(ty.span()).unwrap_or_else(|| ty.span());
     let name = ident.to_string();
+    let param = seg.name.name();
 
     // All dynamic parameter should be found if this function is being called;
     // that's the point of statically checking the URI parameters.
@@ -141,15 +167,26 @@ fn param_expr(seg: &Segment, ident: &syn::Ident, ty: &syn::Type) -> TokenStream
     // Returned when a dynamic parameter fails to parse.
     let parse_error = quote!({
         #log::warn_(&format!("Failed to parse '{}': {:?}", #name, #error));
+        #req._trace(|| #request::TraceEvent::Forwarded(
+            format!("failed to parse parameter '{}': {:?}", #param, #error)));
         #Outcome::Forward(#data)
     });
 
+    // Returned when a dynamic parameter fails to parse and fails the request.
+    let parse_failure = quote!({
+        #log::warn_(&format!("Failed to parse '{}': {:?}", #name, #error));
+        #req._trace(|| #request::TraceEvent::Failed(__c,
+            format!("failed to parse parameter '{}': {:?}", #param, #error)));
+        #Outcome::Failure(__c)
+    });
+
     let expr = match seg.kind {
         Kind::Single => quote_spanned! { span =>
             match #req.raw_segment_str(#i) {
-                #_Some(__s) => match <#ty as #request::FromParam>::from_param(__s) {
-                    #_Ok(__v) => __v,
-                    #_Err(#error) => return #parse_error,
+                #_Some(__s) => match <#ty as #request::FromParam>::from_request_param(#req, __s) {
+                    #Outcome::Success(__v) => __v,
+                    #Outcome::Forward(#error) => return #parse_error,
+                    #Outcome::Failure((__c, #error)) => return #parse_failure,
                 },
                 #_None => return #internal_error
             }
@@ -173,7 +210,7 @@ fn param_expr(seg: &Segment, ident: &syn::Ident, ty: &syn::Type) -> TokenStream
 }
 
 fn data_expr(ident: &syn::Ident, ty: &syn::Type) -> TokenStream {
-    define_vars_and_mods!(req, data, FromTransformedData, Outcome, Transform);
+    define_vars_and_mods!(req, data, request, FromTransformedData, Outcome, Transform);
     let span = ident.span().join(ty.span()).unwrap_or_else(|| ty.span());
     quote_spanned! { span =>
         let __transform = <#ty as #FromTransformedData>::transform(#req, #data).await;
@@ -195,8 +232,16 @@ fn data_expr(ident: &syn::Ident, ty: &syn::Type) -> TokenStream {
         #[allow(non_snake_case, unreachable_patterns, unreachable_code)]
         let #ident: #ty = match <#ty as #FromTransformedData>::from_data(#req, __outcome).await {
             #Outcome::Success(__d) => __d,
-            #Outcome::Forward(__d) => return #Outcome::Forward(__d),
-            #Outcome::Failure((__c, _)) => return #Outcome::Failure(__c),
+            #Outcome::Forward(__d) => {
+                #req._trace(|| #request::TraceEvent::Forwarded(format!(
+                    "data guard `{}` forwarded", ::std::any::type_name::<#ty>())));
+                return #Outcome::Forward(__d);
+            }
+            #Outcome::Failure((__c, _)) => {
+                #req._trace(|| #request::TraceEvent::Failed(__c, format!(
+                    "data guard `{}` failed", ::std::any::type_name::<#ty>())));
+                return #Outcome::Failure(__c);
+            }
         };
     }
 }
@@ -240,6 +285,8 @@ fn query_exprs(route: &Route) -> Option<TokenStream> {
                         #_Ok(__v) => __v,
                         #_Err(__e) => {
                             #log::warn_(&format!("Failed to parse '{}': {:?}", #name, __e));
+                            #req._trace(|| #request::TraceEvent::Forwarded(
+                                format!("failed to parse query parameter '{}': {:?}", #name, __e)));
                             return #Outcome::Forward(#data);
                         }
                     };
@@ -262,6 +309,8 @@ fn query_exprs(route: &Route) -> Option<TokenStream> {
                     #_Some(__v) => __v,
                     #_None => {
                         #log::warn_(&format!("Missing required query parameter '{}'.", #name));
+                        #req._trace(|| #request::TraceEvent::Forwarded(
+                            format!("missing required query parameter '{}'", #name)));
                         return #Outcome::Forward(#data);
                     }
                 };
@@ -272,6 +321,8 @@ fn query_exprs(route: &Route) -> Option<TokenStream> {
                     #_Ok(__v) => __v,
                     #_Err(__e) => {
                         #log::warn_(&format!("Failed to parse '{}': {:?}", #name, __e));
+                        #req._trace(|| #request::TraceEvent::Forwarded(
+                            format!("failed to parse query parameter '{}': {:?}", #name, __e)));
                         return #Outcome::Forward(#data);
                     }
                 };
@@ -313,8 +364,16 @@ fn request_guard_expr(ident: &syn::Ident, ty: &syn::Type) -> TokenStream {
         #[allow(non_snake_case, unreachable_patterns, unreachable_code)]
         let #ident: #ty = match <#ty as #request::FromRequest>::from_request(#req).await {
             #Outcome::Success(__v) => __v,
-            #Outcome::Forward(_) => return #Outcome::Forward(#data),
-            #Outcome::Failure((__c, _)) => return #Outcome::Failure(__c),
+            #Outcome::Forward(__e) => {
+                #req._trace(|| #request::TraceEvent::Forwarded(format!(
+                    "request guard `{}` forwarded: {:?}", ::std::any::type_name::<#ty>(), __e)));
+                return #Outcome::Forward(#data);
+            }
+            #Outcome::Failure((__c, __e)) => {
+                #req._trace(|| #request::TraceEvent::Failed(__c, format!(
+                    "request guard `{}` failed: {:?}", ::std::any::type_name::<#ty>(), __e)));
+                return #Outcome::Failure(__c);
+            }
         };
     }
 }
@@ -365,7 +424,7 @@ fn generate_respond_expr(route: &Route) -> TokenStream {
     };
 
     define_vars_and_mods!(req);
-    define_vars_and_mods!(ret_span => handler);
+    define_vars_and_mods!(ret_span => handler, response);
     let user_handler_fn_name = &route.function.sig.ident;
     let parameter_names = route.inputs.iter()
         .map(|(_, rocket_ident, _)| rocket_ident);
@@ -375,7 +434,22 @@ fn generate_respond_expr(route: &Route) -> TokenStream {
         let ___responder = #user_handler_fn_name(#(#parameter_names),*) #_await;
     };
 
+    // Assert that the return type is a `Responder` so that the error, if any,
+    // points at the return type instead of deep inside the generated handler.
+    // `impl Trait` can't be named here; the type checker reports those.
+    let responder_check = match route.function.sig.output.ty() {
+        Some(syn::Type::ImplTrait(_)) | None => None,
+        Some(ty) => {
+            let ty = ty.with_stripped_lifetimes();
+            Some(quote_spanned! { ret_span => {
+                fn _check<'r, 'o: 'r, T: #response::Responder<'r, 'o>>() {}
+                _check::<#ty>();
+            }})
+        }
+    };
+
     quote_spanned! { ret_span =>
+        #responder_check
         #responder_stmt
         #handler::Outcome::from(#req, ___responder)
     }
@@ -409,7 +483,8 @@ fn codegen_route(route: Route) -> Result<TokenStream> {
     }
 
     // Gather everything we need.
-    define_vars_and_mods!(req, data, _Box, Request, Data, Route, StaticRouteInfo, HandlerFuture);
+    define_vars_and_mods!(req, data, _Box, _Some, _None, Request, Data, Route, StaticRouteInfo,
+        HandlerFuture);
     let (vis, user_handler_fn) = (&route.function.vis, &route.function);
     let user_handler_fn_name = &user_handler_fn.sig.ident;
     let generated_internal_uri_macro = generate_internal_uri_macro(&route);
@@ -418,7 +493,11 @@ fn codegen_route(route: Route) -> Result<TokenStream> {
     let method = route.attribute.method;
     let path = route.attribute.path.origin.0.to_string();
     let rank = Optional(route.attribute.rank);
-    let format = Optional(route.attribute.format);
+    let format = Optional(route.attribute.format.map(|f| f.value));
+    let matcher = match route.attribute.matcher {
+        Some(MatcherPath(path)) => quote_spanned!(path.span().into() => #_Some(#path)),
+        None => quote!(#_None),
+    };
 
     Ok(quote! {
         #user_handler_fn
@@ -451,6 +530,7 @@ fn codegen_route(route: Route) -> Result<TokenStream> {
                     handler: monomorphized_function,
                     format: #format,
                     rank: #rank,
+                    matcher: #matcher,
                 }
             }
         }
@@ -514,6 +594,8 @@ fn incomplete_route(
         data: method_attribute.data,
         format: method_attribute.format,
         rank: method_attribute.rank,
+        matcher: method_attribute.matcher,
+        allow_payload: method_attribute.allow_payload,
     };
 
     codegen_route(parse_route(attribute, function)?)
@@ -130,6 +130,15 @@ fn into_diagnostic(
                 .help("a multi-segment param must be the final component")
                 .span_note(multi_span, "multi-segment param is here")
         }
+        Error::Constraint(_) => {
+            seg_span.error(error.to_string())
+                .help("built-in constraints are: `int`, `uint`, `alpha`, `alnum`, `hex`, `uuid`")
+                .help("patterns are literals and classes, such as `[a-z-]+` or `[0-9]{4}`")
+        }
+        Error::Unconstrainable => {
+            seg_span.error(error.to_string())
+                .help("validate other parameters with a `FromParam` or `FromFormValue` type")
+        }
     }
 }
 
@@ -10,7 +10,7 @@ use crate::syn::{Expr, Ident, Type, spanned::Spanned};
 use crate::http_codegen::Optional;
 use crate::syn_ext::IdentExt;
 use crate::bang::uri_parsing::*;
-use crate::proc_macro2::TokenStream;
+use crate::proc_macro2::{TokenStream, Span};
 
 use crate::URI_MACRO_PREFIX;
 
@@ -40,7 +40,7 @@ pub fn _uri_macro(input: TokenStream) -> Result<TokenStream> {
 
 fn extract_exprs<'a>(internal: &'a InternalUriParams) -> Result<(
         impl Iterator<Item = (&'a Ident, &'a Type, &'a Expr)>,
-        impl Iterator<Item = (&'a Ident, &'a Type, &'a ArgExpr)>,
+        impl Iterator<Item = (&'a Ident, &'a Type, Option<&'a ArgExpr>)>,
     )>
 {
     let route_name = &internal.uri_params.route_path;
@@ -48,6 +48,7 @@ fn extract_exprs<'a>(internal: &'a InternalUriParams) -> Result<(
         Validation::Ok(exprs) => {
             let path_param_count = internal.route_uri.path().matches('<').count();
             for expr in exprs.iter().take(path_param_count) {
+                let expr = expr.expect("path parameters are never omitted");
                 if !expr.as_expr().is_some() {
                     return Err(expr.span().error("path parameters cannot be ignored"));
                 }
@@ -61,7 +62,7 @@ fn extract_exprs<'a>(internal: &'a InternalUriParams) -> Result<(
             // Create iterators for just the path and query parts.
             let path_params = arguments.clone()
                 .take(path_param_count)
-                .map(|(i, t, e)| (i, t, e.unwrap_expr()));
+                .map(|(i, t, e)| (i, t, e.expect("path parameter").unwrap_expr()));
 
             let query_params = arguments.skip(path_param_count);
             Ok((path_params, query_params))
@@ -156,7 +157,7 @@ fn explode_path<'a, I: Iterator<Item = (&'a Ident, &'a Type, &'a Expr)>>(
     quote!(#uri_mod::UriArgumentsKind::Dynamic(&[#(#dyn_exprs),*]))
 }
 
-fn explode_query<'a, I: Iterator<Item = (&'a Ident, &'a Type, &'a ArgExpr)>>(
+fn explode_query<'a, I: Iterator<Item = (&'a Ident, &'a Type, Option<&'a ArgExpr>)>>(
     uri: &Origin<'_>,
     bindings: &mut Vec<TokenStream>,
     mut items: I
@@ -175,13 +176,16 @@ fn explode_query<'a, I: Iterator<Item = (&'a Ident, &'a Type, &'a ArgExpr)>>(
             return Some(quote!(#query_arg::Raw(#string)));
         }
 
+        // Parameters that are ignored with `_`, omitted, or `None` are not
+        // rendered. All three require the parameter's type to be `Ignorable`.
         let (ident, ty, arg_expr) = items.next().expect("one item for each dyn");
-        let expr = match arg_expr.as_expr() {
+        let expr = match arg_expr.filter(|e| !e.is_none()).and_then(|e| e.as_expr()) {
             Some(expr) => expr,
             None => {
                 // Force a typecheck for the `Ignoreable` trait. Note that write
                 // out the path to `is_ignorable` to get the right span.
-                bindings.push(quote_spanned! { arg_expr.span() =>
+                let span = arg_expr.map_or_else(Span::call_site, |e| e.span());
+                bindings.push(quote_spanned! { span =>
                     rocket::http::uri::assert_ignorable::<#uri_mod::Query, #ty>();
                 });
 
@@ -229,8 +233,13 @@ pub fn _uri_internal_macro(input: TokenStream) -> Result<TokenStream> {
     let path = explode_path(&uri, &mut bindings, path_params);
     let query = Optional(explode_query(&uri, &mut bindings, query_params));
 
+    let into_uri = match internal.uri_params.base {
+        Some(ref base) => quote!(into_absolute(#base)),
+        None => quote!(into_origin()),
+    };
+
      Ok(quote!({
          #(#bindings)*
-         #uri_mod::UriArguments { path: #path, query: #query, }.into_origin()
+         #uri_mod::UriArguments { path: #path, query: #query, }.#into_uri
      }))
 }
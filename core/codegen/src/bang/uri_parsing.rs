@@ -6,7 +6,7 @@ use crate::syn::{self, Expr, Ident, LitStr, Path, Token, Type};
 use crate::syn::parse::{self, Parse, ParseStream};
 use crate::syn::punctuated::Punctuated;
 
-use crate::http::{uri::Origin, ext::IntoOwned};
+use crate::http::{uri::{Origin, Absolute}, ext::IntoOwned};
 use crate::proc_macro2::{TokenStream, Span};
 use crate::syn_ext::NameSource;
 
@@ -35,8 +35,13 @@ pub enum Args {
 //       ^-------------| ^----------| ^---------|
 //           uri_params.mount_point |    uri_params.arguments
 //                      uri_params.route_path
+//
+// When the prefix is an absolute URI, as in `"https://rocket.rs/mount"`, its
+// scheme and authority (`https://rocket.rs`) are kept in `uri_params.base` and
+// its path (`/mount`) becomes the mount point.
 #[derive(Debug)]
 pub struct UriParams {
+    pub base: Option<String>,
     pub mount_point: Option<Origin<'static>>,
     pub route_path: Path,
     pub arguments: Args,
@@ -54,7 +59,8 @@ pub enum Validation<'a> {
     // (Missing, Extra, Duplicate)
     Named(Vec<NameSource>, Vec<&'a Ident>, Vec<&'a Ident>),
     // Everything is okay; here are the expressions in the route decl order.
-    Ok(Vec<&'a ArgExpr>)
+    // Query parameters omitted from named arguments are `None`.
+    Ok(Vec<Option<&'a ArgExpr>>)
 }
 
 // This is invoked by Rocket itself. The `uri!` macro expands to a call to a
@@ -107,6 +113,49 @@ fn err<T, S: AsRef<str>>(span: Span, s: S) -> parse::Result<T> {
     Err(parse::Error::new(span.into(), s.as_ref()))
 }
 
+// Returns `true` if `ty` is syntactically one of the `Ignorable` types: an
+// `Option` or a `Result`. Only query parameters of these types may be omitted.
+fn is_ignorable(ty: &Type) -> bool {
+    match ty {
+        Type::Path(p) => p.path.segments.last()
+            .map_or(false, |s| s.ident == "Option" || s.ident == "Result"),
+        _ => false
+    }
+}
+
+// Parses a `uri!` prefix: either an origin mount point (`/mount`) or an
+// absolute URI (`https://rocket.rs/mount`). Returns the scheme and authority of
+// an absolute URI, if any, and the mount point.
+fn parse_prefix(string: &LitStr) -> parse::Result<(Option<String>, Origin<'static>)> {
+    let value = string.value();
+    if let Ok(mount_point) = Origin::parse(&value) {
+        return Ok((None, mount_point.into_owned()));
+    }
+
+    // TODO(proc_macro): use error, add example as a help
+    let invalid = || parse::Error::new(string.span(), "invalid mount point; \
+        mount points must be static, absolute URIs: `/example`");
+
+    let absolute = Absolute::parse(&value).map_err(|_| invalid())?;
+
+    let base = match absolute.authority() {
+        Some(authority) => format!("{}://{}", absolute.scheme(), authority),
+        None => format!("{}:", absolute.scheme()),
+    };
+
+    let mount_point = match absolute.origin() {
+        Some(origin) if origin.query().is_some() => {
+            return err(string.span(), "invalid URI prefix; \
+                absolute URI prefixes cannot contain a query");
+        }
+        Some(origin) if origin.path().starts_with('/') => origin.clone().into_owned(),
+        Some(_) => return Err(invalid()),
+        None => Origin::dummy(),
+    };
+
+    Ok((Some(base), mount_point))
+}
+
 impl Parse for UriParams {
     // Parses the mount point, if any, route identifier, and arguments.
     fn parse(input: ParseStream<'_>) -> parse::Result<Self> {
@@ -114,24 +163,19 @@ impl Parse for UriParams {
             return Err(input.error("call to `uri!` cannot be empty"));
         }
 
-        // Parse the mount point and suffixing ',', if any.
-        let mount_point = if input.peek(LitStr) {
+        // Parse the mount point or absolute base and suffixing ',', if any.
+        let (base, mount_point) = if input.peek(LitStr) {
             let string = input.parse::<LitStr>()?;
-            let mount_point = Origin::parse_owned(string.value()).map_err(|_| {
-                // TODO(proc_macro): use error, add example as a help
-                parse::Error::new(string.span(), "invalid mount point; \
-                    mount points must be static, absolute URIs: `/example`")
-            })?;
-
+            let (base, mount_point) = parse_prefix(&string)?;
             if !input.peek(Token![,]) && input.cursor().eof() {
                 return err(string.span(), "unexpected end of input: \
                     expected ',' followed by route path");
             }
 
             input.parse::<Token![,]>()?;
-            Some(mount_point)
+            (base, Some(mount_point))
         } else {
-            None
+            (None, None)
         };
 
         // Parse the route identifier, which must always exist.
@@ -140,7 +184,7 @@ impl Parse for UriParams {
         // If there are no arguments, finish early.
         if !input.peek(Token![:]) && input.cursor().eof() {
             let arguments = Args::Unnamed(Punctuated::new());
-            return Ok(Self { mount_point, route_path, arguments });
+            return Ok(Self { base, mount_point, route_path, arguments });
         }
 
         // Parse arguments
@@ -171,7 +215,7 @@ impl Parse for UriParams {
             _ => Args::Unnamed(arguments)
         };
 
-        Ok(Self { mount_point, route_path, arguments })
+        Ok(Self { base, mount_point, route_path, arguments })
     }
 }
 
@@ -221,7 +265,7 @@ impl InternalUriParams {
             Args::Unnamed(inner) => {
                 let (expected, actual) = (self.fn_args.len(), inner.len());
                 if expected != actual { Validation::Unnamed(expected, actual) }
-                else { Validation::Ok(args.unnamed().unwrap().collect()) }
+                else { Validation::Ok(args.unnamed().unwrap().map(Some).collect()) }
             },
             Args::Named(_) => {
                 let mut params: IndexMap<NameSource, Option<&ArgExpr>> = self.fn_args.iter()
@@ -237,10 +281,17 @@ impl InternalUriParams {
                     }
                 }
 
+                // Query parameters of an ignorable type may be omitted; path
+                // parameters and required query parameters may not.
+                let path_param_count = self.route_uri.path().matches('<').count();
                 let (mut missing, mut exprs) = (vec![], vec![]);
-                for (name, expr) in params {
+                let params = params.into_iter().zip(self.fn_args.iter()).enumerate();
+                for (i, ((name, expr), arg)) in params {
                     match expr {
-                        Some(expr) => exprs.push(expr),
+                        Some(expr) => exprs.push(Some(expr)),
+                        None if i >= path_param_count && is_ignorable(&arg.ty) => {
+                            exprs.push(None)
+                        }
                         None => missing.push(name)
                     }
                 }
@@ -318,6 +369,16 @@ impl ArgExpr {
         }
     }
 
+    /// Returns `true` if `self` is the expression `None`.
+    pub fn is_none(&self) -> bool {
+        match self {
+            ArgExpr::Expr(Expr::Path(expr)) => {
+                expr.qself.is_none() && expr.path.is_ident("None")
+            }
+            _ => false
+        }
+    }
+
     pub fn unwrap_expr(&self) -> &Expr {
         match self {
             ArgExpr::Expr(expr) => expr,
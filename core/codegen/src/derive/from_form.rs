@@ -1,4 +1,4 @@
-use devise::{*, ext::{TypeExt, Split3, SpanDiagnosticExt}};
+use devise::{*, ext::{TypeExt, SpanDiagnosticExt}};
 
 use crate::proc_macro2::{Span, TokenStream};
 use crate::syn_ext::NameSource;
@@ -8,11 +8,32 @@ pub struct Form {
     pub field: FormField,
 }
 
+/// The parsed `#[field(..)]` attribute.
+#[derive(FromMeta)]
+pub struct Field {
+    pub name: Option<FormField>,
+    pub default: Option<FieldDefault>,
+    pub validate: Option<FieldValidator>,
+}
+
 pub struct FormField {
     pub span: Span,
     pub name: NameSource,
 }
 
+/// A literal default value for a field.
+pub struct FieldDefault(syn::Lit);
+
+/// The path to a `fn(&T) -> bool` validating a field's parsed value.
+pub struct FieldValidator(syn::Path);
+
+/// Everything the attributes on a single field specify.
+struct FieldAttrs {
+    field: FormField,
+    default: Option<FieldDefault>,
+    validate: Option<FieldValidator>,
+}
+
 fn is_valid_field_name(s: &str) -> bool {
     // The HTML5 spec (4.10.18.1) says 'isindex' is not allowed.
     if s == "isindex" || s.is_empty() {
@@ -35,6 +56,60 @@ impl FromMeta for FormField {
     }
 }
 
+impl FromMeta for FieldDefault {
+    fn from_meta(meta: MetaItem<'_>) -> Result<Self> {
+        Ok(FieldDefault(meta.lit()?.clone()))
+    }
+}
+
+impl FromMeta for FieldValidator {
+    fn from_meta(meta: MetaItem<'_>) -> Result<Self> {
+        if let syn::Lit::Str(s) = meta.lit()? {
+            return s.parse().map(FieldValidator).map_err(|_| {
+                meta.value_span().error("invalid validator: expected path to a function")
+            });
+        }
+
+        Err(meta.value_span().error("invalid value: expected string literal"))
+    }
+}
+
+impl quote::ToTokens for FieldDefault {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        // String literals may default fields of any type they convert into.
+        let lit = &self.0;
+        tokens.extend(match lit {
+            syn::Lit::Str(_) => quote_spanned!(lit.span() => ::std::convert::Into::into(#lit)),
+            _ => quote!(#lit),
+        });
+    }
+}
+
+impl FieldAttrs {
+    fn from_field(field: &syn::Field) -> Result<FieldAttrs> {
+        let ident = field.ident.as_ref().expect("named field");
+        let form = Form::from_attrs("form", &field.attrs).transpose()?;
+        let attr = Field::from_attrs("field", &field.attrs).transpose()?;
+
+        let (default, validate, name) = match attr {
+            Some(attr) => (attr.default, attr.validate, attr.name),
+            None => (None, None, None),
+        };
+
+        let field = match (form, name) {
+            (Some(form), Some(name)) => {
+                return Err(name.span.error("field name specified more than once")
+                    .span_note(form.field.span, "previously specified here"));
+            }
+            (Some(form), None) => form.field,
+            (None, Some(name)) => name,
+            (None, None) => FormField { span: Spanned::span(&ident), name: ident.clone().into() }
+        };
+
+        Ok(FieldAttrs { field, default, validate })
+    }
+}
+
 fn validate_struct(_: &DeriveGenerator, data: Struct<'_>) -> Result<()> {
     if data.fields().is_empty() {
         return Err(data.fields.span().error("at least one field is required"));
@@ -42,11 +117,7 @@ fn validate_struct(_: &DeriveGenerator, data: Struct<'_>) -> Result<()> {
 
     let mut names = ::std::collections::HashMap::new();
     for field in data.fields().iter() {
-        let id = field.ident.as_ref().expect("named field");
-        let field = match Form::from_attrs("form", &field.attrs) {
-            Some(result) => result?.field,
-            None => FormField { span: Spanned::span(&id), name: id.clone().into() }
-        };
+        let field = FieldAttrs::from_field(&field)?.field;
 
         if let Some(span) = names.get(&field.name) {
             return Err(field.span.error("duplicate field name")
@@ -61,73 +132,164 @@ fn validate_struct(_: &DeriveGenerator, data: Struct<'_>) -> Result<()> {
 
 pub fn derive_from_form(input: proc_macro::TokenStream) -> TokenStream {
     let form_error = quote!(::rocket::request::FormParseError);
-    DeriveGenerator::build_for(input, quote!(impl<'__f> ::rocket::request::FromForm<'__f>))
+    let form_errors = quote!(::rocket::request::FormParseErrors);
+    let form_item = quote!(::rocket::request::FormItem);
+    let raw_str = quote!(::rocket::http::RawStr);
+
+    // Field types can't be named in `Context`, so the context buffers items.
+    let gen_trait = quote!(impl<'__f> ::rocket::request::FromFormField<'__f>);
+    let from_form_field = DeriveGenerator::build_for(input.clone(), gen_trait)
         .generic_support(GenericSupport::Lifetime | GenericSupport::Type)
         .replace_generic(0, 0)
         .data_support(DataSupport::NamedStruct)
         .map_type_generic(|_, ident, _| quote! {
-            #ident : ::rocket::request::FromFormValue<'__f>
+            #ident : ::rocket::request::FromFormField<'__f>
         })
         .validate_generics(|_, generics| match generics.lifetimes().enumerate().last() {
             Some((i, lt)) if i >= 1 => Err(lt.span().error("only one lifetime is supported")),
             _ => Ok(())
         })
         .validate_struct(validate_struct)
-        .function(|_, inner| quote! {
-            type Error = ::rocket::request::FormParseError<'__f>;
+        .function(move |_, inner| quote! {
+            type Context = (bool, ::std::vec::Vec<(#form_item<'__f>, &'__f #raw_str)>);
 
-            fn from_form(
-                __items: &mut ::rocket::request::FormItems<'__f>,
-                __strict: bool,
-            ) -> ::std::result::Result<Self, Self::Error> {
+            fn init(__strict: bool) -> Self::Context {
+                (__strict, ::std::vec::Vec::new())
+            }
+
+            fn push(
+                __ctxt: &mut Self::Context,
+                __item: #form_item<'__f>,
+                __rest: &'__f #raw_str,
+            ) {
+                __ctxt.1.push((__item, __rest));
+            }
+
+            fn finalize(
+                __ctxt: Self::Context,
+                __name: &'__f #raw_str,
+            ) -> ::std::result::Result<Self, ::rocket::request::FormParseErrors<'__f>> {
+                let (__strict, __items) = __ctxt;
                 #inner
             }
         })
         .try_map_fields(move |_, fields| {
             define_vars_and_mods!(_None, _Some, _Ok, _Err);
-            let (constructors, matchers, builders) = fields.iter().map(|field| {
+            let mut idents = vec![];
+            let mut matchers = vec![];
+            let (constructors, builders): (Vec<_>, Vec<_>) = fields.iter().map(|field| {
                 let (ident, span) = (&field.ident, field.span());
-                let default_name = NameSource::from(ident.clone().expect("named"));
-                let name = Form::from_attrs("form", &field.attrs)
-                    .map(|result| result.map(|form| form.field.name))
-                    .unwrap_or_else(|| Ok(default_name))?;
+                let FieldAttrs { field: form_field, default, validate } =
+                    FieldAttrs::from_field(&field)?;
 
                 let ty = field.ty.with_stripped_lifetimes();
                 let ty = quote_spanned! {
-                    span => <#ty as ::rocket::request::FromFormValue>
+                    span => <#ty as ::rocket::request::FromFormField>
                 };
 
-                let constructor = quote_spanned!(span => let mut #ident = #_None;);
-
-                let name = name.name();
-                let matcher = quote_spanned! { span =>
-                    #name => { #ident = #_Some(#ty::from_form_value(__v)
-                                .map_err(|_| #form_error::BadValue(__k, __v))?); },
+                let constructor = quote_spanned! { span =>
+                    let mut #ident = (#ty::init(__strict), #_None);
                 };
 
+                let name = form_field.name.name().to_string();
+                matchers.push((name.clone(), quote_spanned! { span =>
+                    if let #_Some(__rest) = ::rocket::request::strip_field_name(__rest, #name) {
+                        #ident.1 = #_Some(__item);
+                        #ty::push(&mut #ident.0, __item, __rest);
+                        continue;
+                    }
+                }));
+
+                // Only values parsed from the form are validated. An invalid
+                // value is reported against the last item for the field.
+                let validation = validate.map(|FieldValidator(path)| quote_spanned! {
+                    path.span() => (#_Ok(__value), #_Some(__item)) if !#path(&__value) => {
+                        __errors.push(#form_error::BadValue(__item.key, __item.value));
+                        #_None
+                    }
+                });
+
+                let defaulted = default.map(|default| quote_spanned! { span =>
+                    (_, #_None) => #_Some(#default),
+                });
+
                 let builder = quote_spanned! { span =>
-                    #ident: #ident.or_else(#ty::default)
-                        .ok_or_else(|| #form_error::Missing(#name.into()))?,
+                    let #ident = match #ident {
+                        #defaulted
+                        (__ctxt, __last) => match (#ty::finalize(__ctxt, #name.into()), __last) {
+                            #validation
+                            (#_Ok(__value), _) => #_Some(__value),
+                            (#_Err(__e), _) => {
+                                __errors.extend(__e);
+                                #_None
+                            }
+                        }
+                    };
                 };
 
-                Ok((constructor, matcher, builder))
-            }).collect::<Result<Vec<_>>>()?.into_iter().split3();
+                idents.push(ident.clone());
+                Ok((constructor, builder))
+            }).collect::<Result<Vec<_>>>()?.into_iter().unzip();
+
+            // Longer names first so that `a.b` takes precedence over `a`.
+            matchers.sort_by(|(a, _), (b, _)| b.len().cmp(&a.len()));
+            let matchers = matchers.into_iter().map(|(_, matcher)| matcher);
 
             Ok(quote! {
+                let mut __errors = #form_errors::new();
                 #(#constructors)*
 
-                for (__k, __v) in __items.map(|item| item.key_value()) {
-                    match __k.as_str() {
-                        #(#matchers)*
-                        _ if __strict && __k != "_method" => {
-                            return #_Err(#form_error::Unknown(__k, __v));
-                        }
-                        _ => { /* lenient or "method"; let it pass */ }
+                for (__item, __rest) in __items {
+                    #(#matchers)*
+
+                    if __strict && __item.key != "_method" {
+                        __errors.push(#form_error::Unknown(__item.key, __item.value));
                     }
                 }
 
-                #_Ok(Self { #(#builders)* })
+                #(#builders)*
+
+                match (#(#idents,)*) {
+                    (#(#_Some(#idents),)*) if __errors.is_empty() => #_Ok(Self { #(#idents),* }),
+                    _ => #_Err(__errors._prefixed(__name.as_str())),
+                }
             })
         })
-        .to_tokens2()
+        .try_to_tokens();
+
+    let from_form_field = match from_form_field {
+        Ok(tokens) => tokens,
+        Err(diag) => return diag.emit_as_item_tokens()
+    };
+
+    let gen_trait = quote!(impl<'__f> ::rocket::request::FromForm<'__f>);
+    let from_form = DeriveGenerator::build_for(input, gen_trait)
+        .generic_support(GenericSupport::Lifetime | GenericSupport::Type)
+        .replace_generic(0, 0)
+        .data_support(DataSupport::NamedStruct)
+        .map_type_generic(|_, ident, _| quote! {
+            #ident : ::rocket::request::FromFormField<'__f>
+        })
+        .function(|_, _| quote! {
+            type Error = ::rocket::request::FormParseErrors<'__f>;
+
+            fn from_form(
+                __items: &mut ::rocket::request::FormItems<'__f>,
+                __strict: bool,
+            ) -> ::std::result::Result<Self, Self::Error> {
+                use ::rocket::request::FromFormField;
+
+                let mut __ctxt = <Self as FromFormField<'__f>>::init(__strict);
+                for __item in __items {
+                    <Self as FromFormField<'__f>>::push(&mut __ctxt, __item, __item.key);
+                }
+
+                <Self as FromFormField<'__f>>::finalize(__ctxt, "".into())
+            }
+        })
+        .to_tokens();
+
+    let mut ts = TokenStream::from(from_form_field);
+    ts.extend(TokenStream::from(from_form));
+    ts.into()
 }
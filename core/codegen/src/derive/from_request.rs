@@ -0,0 +1,87 @@
+use devise::{*, ext::{TypeExt, SpanDiagnosticExt}};
+
+use crate::proc_macro2::TokenStream;
+use crate::syn_ext::IdentExt;
+
+pub fn derive_from_request(input: proc_macro::TokenStream) -> TokenStream {
+    let gen_trait = quote!(impl<'__a, '__r> ::rocket::request::FromRequest<'__a, '__r>);
+    let from_request = DeriveGenerator::build_for(input, gen_trait)
+        .generic_support(GenericSupport::Lifetime | GenericSupport::Type)
+        .data_support(DataSupport::Struct)
+        .replace_generic(0, 0)
+        .map_type_generic(|_, ident, _| quote! {
+            #ident : ::rocket::request::FromRequest<'__a, '__r> + Send + '__a
+        })
+        .validate_generics(|_, generics| match generics.lifetimes().count() > 1 {
+            true => Err(generics.span().error("only one lifetime is supported")),
+            false => Ok(())
+        })
+        .validate_fields(|_, fields| match fields.is_empty() {
+            true => return Err(fields.span().error("need at least one field")),
+            false => Ok(())
+        })
+        .function(|_, inner| quote! {
+            type Error = ();
+
+            async fn from_request(
+                __req: &'__a ::rocket::request::Request<'__r>
+            ) -> ::rocket::request::Outcome<Self, ()> {
+                #inner
+            }
+        })
+        .try_map_fields(|_, fields| {
+            define_vars_and_mods!(req, request, Outcome);
+            let mut bindings = vec![];
+            let guards = fields.iter().enumerate().map(|(i, field)| {
+                let ty = field.ty.with_stripped_lifetimes();
+                let span = field.span().into();
+                let binding = match field.ident {
+                    Some(ref ident) => ident.prepend("__guard_"),
+                    None => syn::Ident::new(&format!("__guard_{}", i), span),
+                };
+
+                let guard = quote_spanned! { span =>
+                    let #binding = match <#ty as #request::FromRequest>::from_request(#req).await {
+                        #Outcome::Success(__v) => __v,
+                        #Outcome::Forward(__e) => {
+                            #req._trace(|| #request::TraceEvent::Forwarded(format!(
+                                "request guard `{}` forwarded: {:?}",
+                                ::std::any::type_name::<#ty>(), __e)));
+                            return #Outcome::Forward(__e);
+                        }
+                        #Outcome::Failure((__c, __e)) => {
+                            #req._trace(|| #request::TraceEvent::Failed(__c, format!(
+                                "request guard `{}` failed: {:?}",
+                                ::std::any::type_name::<#ty>(), __e)));
+                            return #Outcome::Failure((__c, ()));
+                        }
+                    };
+                };
+
+                bindings.push((field.ident.clone(), binding));
+                guard
+            }).collect::<Vec<_>>();
+
+            let value = match fields.are_unnamed() {
+                true => {
+                    let bindings = bindings.iter().map(|(_, binding)| binding);
+                    quote!(Self(#(#bindings),*))
+                }
+                false => {
+                    let fields = bindings.iter().map(|(ident, binding)| quote!(#ident: #binding));
+                    quote!(Self { #(#fields),* })
+                }
+            };
+
+            Ok(quote! {
+                #(#guards)*
+                #Outcome::Success(#value)
+            })
+        })
+        .try_to_tokens();
+
+    match from_request {
+        Ok(tokens) => quote!(#[::rocket::async_trait] #tokens),
+        Err(diag) => diag.emit_as_item_tokens()
+    }
+}
@@ -29,6 +29,10 @@ pub struct DataSegment(pub Segment);
 #[derive(Clone, Debug)]
 pub struct Optional<T>(pub Option<T>);
 
+/// The path to a route's matcher function: `matcher = path`.
+#[derive(Debug)]
+pub struct MatcherPath(pub devise::syn::Path);
+
 impl FromMeta for StringLit {
     fn from_meta(meta: MetaItem<'_>) -> Result<Self> {
         Ok(StringLit::new(String::from_meta(meta)?, meta.value_span()))
@@ -42,6 +46,17 @@ pub struct RoutePath {
     pub query: Option<Vec<Segment>>,
 }
 
+impl FromMeta for MatcherPath {
+    fn from_meta(meta: MetaItem<'_>) -> Result<Self> {
+        match meta {
+            MetaItem::Path(path) => Ok(MatcherPath(path.clone())),
+            _ => Err(meta.value_span().error(
+                format!("expected path to a function, found {}", meta.description()))
+                .help("the function must have type `fn(&Request<'_>) -> bool`"))
+        }
+    }
+}
+
 impl FromMeta for Status {
     fn from_meta(meta: MetaItem<'_>) -> Result<Self> {
         let num = usize::from_meta(meta)?;
@@ -105,7 +120,7 @@ impl ToTokens for MediaType {
 }
 
 const VALID_METHODS_STR: &str = "`GET`, `PUT`, `POST`, `DELETE`, `HEAD`, \
-    `PATCH`, `OPTIONS`";
+    `PATCH`, `OPTIONS`, or an uppercase extension method such as `PURGE`";
 
 const VALID_METHODS: &[http::Method] = &[
     http::Method::Get, http::Method::Put, http::Method::Post,
@@ -120,8 +135,16 @@ impl FromMeta for Method {
 
         if let MetaItem::Path(path) = meta {
             if let Some(ident) = path.last_ident() {
-                let method = ident.to_string().parse()
-                    .map_err(|_| span.error("invalid HTTP method").help(&*help_text))?;
+                let name = ident.to_string();
+                let method = match name.parse() {
+                    Ok(method) => method,
+                    Err(_) if is_extension_method(&name) => {
+                        // The generated code names the method with a literal;
+                        // leaking here gives us the `'static` name to check.
+                        return Ok(Method(http::Method::Custom(Box::leak(name.into_boxed_str()))));
+                    }
+                    Err(_) => return Err(span.error("invalid HTTP method").help(&*help_text)),
+                };
 
                 if !VALID_METHODS.contains(&method) {
                     return Err(span.error("invalid HTTP method for route handlers")
@@ -137,6 +160,13 @@ impl FromMeta for Method {
     }
 }
 
+/// Whether `name` is a valid extension method name: uppercase ASCII letters,
+/// digits, and `_`, starting with a letter.
+fn is_extension_method(name: &str) -> bool {
+    name.starts_with(|c: char| c.is_ascii_uppercase())
+        && name.chars().all(|c| c.is_ascii_uppercase() || c.is_ascii_digit() || c == '_')
+}
+
 impl ToTokens for Method {
     fn to_tokens(&self, tokens: &mut TokenStream) {
         let method_tokens = match self.0 {
@@ -149,6 +179,8 @@ impl ToTokens for Method {
             http::Method::Trace => quote!(::rocket::http::Method::Trace),
             http::Method::Connect => quote!(::rocket::http::Method::Connect),
             http::Method::Patch => quote!(::rocket::http::Method::Patch),
+            http::Method::Custom(name) => quote!(::rocket::http::Method::Custom(#name)),
+            _ => unreachable!("method isn't parsed by codegen"),
         };
 
         tokens.extend(method_tokens);
@@ -212,15 +212,22 @@ macro_rules! route_attribute {
         /// parameter := 'rank' '=' INTEGER
         ///            | 'format' '=' '"' MEDIA_TYPE '"'
         ///            | 'data' '=' '"' SINGLE_PARAM '"'
+        ///            | 'allow_payload' '=' BOOL
+        ///            | 'matcher' '=' PATH
         ///
-        /// SINGLE_PARAM := '<' IDENT '>'
+        /// SINGLE_PARAM := '<' IDENT (':' CONSTRAINT)? '>'
         /// MULTI_PARAM := '<' IDENT '..>'
         ///
         /// URI_SEG := valid, non-percent-encoded HTTP URI segment
         /// MEDIA_TYPE := valid HTTP media type or known shorthand
         ///
         /// INTEGER := unsigned integer, as defined by Rust
+        /// BOOL := 'true' | 'false'
         /// IDENT := valid identifier, as defined by Rust, except `_`
+        /// CONSTRAINT := 'int' | 'uint' | 'alpha' | 'alnum' | 'hex' | 'uuid'
+        ///             | pattern of literals and `[...]` classes, each
+        ///               optionally followed by `*`, `+`, or `{n,m}`
+        /// PATH := path to a function, as defined by Rust
         /// ```
         ///
         /// The generic route attribute is defined as:
@@ -229,6 +236,20 @@ macro_rules! route_attribute {
         /// generic-route := METHOD ',' 'path' '=' route
         /// ```
         ///
+        /// `METHOD` is one of the standard methods above or, for extension
+        /// methods like `PURGE`, any identifier of uppercase ASCII letters,
+        /// digits, and `_` that starts with a letter. Extension methods are
+        /// represented by [`Method::Custom`] at runtime.
+        ///
+        /// [`Method::Custom`]: ../rocket/http/enum.Method.html#variant.Custom
+        ///
+        /// A `CONSTRAINT` is only allowed on a `SINGLE_PARAM` in the path. The
+        /// route only matches requests whose segment in the parameter's
+        /// position, percent-decoded, matches the constraint in its entirety;
+        /// other requests are routed to the remaining routes instead. For
+        /// example, `/users/<id:int>` matches `/users/-12` but not
+        /// `/users/new`, so it doesn't collide with a route for `/users/new`.
+        ///
         /// # Typing Requirements
         ///
         /// Every identifier that appears in a dynamic parameter (`SINGLE_PARAM`
@@ -241,7 +262,7 @@ macro_rules! route_attribute {
         /// # use rocket::request::Form;
         /// # use std::path::PathBuf;
         /// # #[derive(FromForm)] struct F { a: usize }
-        /// #[get("/<foo>/bar/<baz..>?<msg>&closed&<rest..>", data = "<form>")]
+        /// #[post("/<foo>/bar/<baz..>?<msg>&closed&<rest..>", data = "<form>")]
         /// # fn f(foo: usize, baz: PathBuf, msg: String, rest: Form<F>, form: Form<F>) {  }
         /// ```
         ///
@@ -267,6 +288,28 @@ macro_rules! route_attribute {
         /// The return type of the decorated function must implement the
         /// [`Responder`] trait.
         ///
+        /// The function named by `matcher`, if any, must have the type
+        /// `fn(&Request<'_>) -> bool`. The route only matches requests for
+        /// which it returns `true`, checked after the route's method, path,
+        /// query, and format match. As matchers don't affect whether routes
+        /// collide, routes that differ only in their matcher need different
+        /// ranks:
+        ///
+        /// ```rust
+        /// # #[macro_use] extern crate rocket;
+        /// use rocket::Request;
+        ///
+        /// fn beta_cohort(req: &Request<'_>) -> bool {
+        ///     req.headers().get_one("X-Cohort") == Some("beta")
+        /// }
+        ///
+        /// #[get("/", matcher = beta_cohort, rank = 1)]
+        /// fn beta_index() -> &'static str { "beta" }
+        ///
+        /// #[get("/", rank = 2)]
+        /// fn index() -> &'static str { "stable" }
+        /// ```
+        ///
         /// [`FromParam`]: ../rocket/request/trait.FromParam.html
         /// [`FromSegments`]: ../rocket/request/trait.FromSegments.html
         /// [`FromFormValue`]: ../rocket/request/trait.FromFormValue.html
@@ -312,9 +355,9 @@ macro_rules! route_attribute {
         ///   2. A static structure used by [`routes!`] to generate a [`Route`].
         ///
         ///      The static structure (and resulting [`Route`]) is populated
-        ///      with the name (the function's name), path, query, rank, and
-        ///      format from the route attribute. The handler is set to the
-        ///      generated handler.
+        ///      with the name (the function's name), path, query, rank,
+        ///      format, and matcher from the route attribute. The handler is
+        ///      set to the generated handler.
         ///
         ///   3. A macro used by [`uri!`] to type-check and generate an
         ///      [`Origin`].
@@ -358,6 +401,11 @@ route_attribute!(options => Method::Options);
 ///     format!("Sorry, {} does not exist.", req.uri())
 /// }
 ///
+/// #[catch(500..600)]
+/// fn server_error(status: Status) -> String {
+///     format!("Sorry, something went wrong: {}", status)
+/// }
+///
 /// #[catch(default)]
 /// fn default(status: Status, req: &Request) -> String {
 ///     format!("{} - {} ({})", status.code, status.reason, req.uri())
@@ -369,11 +417,17 @@ route_attribute!(options => Method::Options);
 /// The grammar for the `#[catch]` attributes is defined as:
 ///
 /// ```text
-/// catch := STATUS | 'default'
+/// catch := STATUS | RANGE | 'default'
 ///
-/// STATUS := valid HTTP status code (integer in [200, 599])
+/// RANGE := STATUS '..' STATUS | STATUS '..=' STATUS
+/// STATUS := valid HTTP status code (integer in [100, 599])
 /// ```
 ///
+/// A `RANGE` catcher handles every status code in the range. The exclusive
+/// bound of a `..` range may be `600`. A catcher for an exact status code takes
+/// precedence over a range catcher, and the narrowest range containing a status
+/// code takes precedence over wider ones.
+///
 /// # Typing Requirements
 ///
 /// The decorated function may take zero, one, or two arguments. It's type
@@ -397,8 +451,9 @@ route_attribute!(options => Method::Options);
 ///   2. A static structure used by [`catchers!`] to generate a [`Catcher`].
 ///
 ///      The static structure (and resulting [`Catcher`]) is populated with the
-///      name (the function's name) and status code from the route attribute or
-///      `None` if `default`. The handler is set to the generated handler.
+///      name (the function's name) and status code or range from the route
+///      attribute, or `None` if `default`. The handler is set to the generated
+///      handler.
 ///
 /// [`&Request`]: ../rocket/struct.Request.html
 /// [`Status`]: ../rocket/http/struct.Status.html
@@ -412,19 +467,148 @@ pub fn catch(args: TokenStream, input: TokenStream) -> TokenStream {
     emit!(attribute::catch::catch_attribute(args, input))
 }
 
-/// FIXME: Document.
+/// Retrofits support for `async fn` in unit tests.
+///
+/// Simply decorate a test `async fn` with `#[async_test]` instead of `#[test]`:
+///
+/// ```rust
+/// #[cfg(test)]
+/// mod tests {
+///     #[rocket::async_test]
+///     async fn test() {
+///         /* .. */
+///     }
+/// }
+/// ```
+///
+/// The attribute rewrites the function to execute inside of a Rocket-compatible
+/// async runtime. The function must take no arguments.
 #[proc_macro_attribute]
 pub fn async_test(args: TokenStream, input: TokenStream) -> TokenStream {
     emit!(attribute::async_entry::async_test_attribute(args, input))
 }
 
-/// FIXME: Document.
+/// Marks a unit test, `async` or not, running `async` tests in a runtime.
+///
+/// Decorate a test function with `#[rocket::rocket_test]` instead of `#[test]`:
+///
+/// ```rust
+/// #[cfg(test)]
+/// mod tests {
+///     #[rocket::rocket_test]
+///     async fn async_test() {
+///         /* .. */
+///     }
+///
+///     #[rocket::rocket_test]
+///     fn blocking_test() {
+///         /* .. */
+///     }
+/// }
+/// ```
+///
+/// An `async` test is executed to completion by a Rocket-compatible runtime,
+/// exactly as with [`async_test`]. A test that isn't `async` is run exactly as
+/// with `#[test]`; in particular, it may use Rocket's blocking APIs, such as
+/// [`local::blocking`], which manage a runtime of their own. In either case,
+/// the test may return a `Result` and fails if it returns an `Err`. Other
+/// attributes, such as `#[should_panic]`, are preserved. The function must
+/// take no arguments.
+///
+/// [`local::blocking`]: ../rocket/local/blocking/index.html
+///
+/// [`async_test`]: attr.async_test.html
+#[proc_macro_attribute]
+pub fn rocket_test(args: TokenStream, input: TokenStream) -> TokenStream {
+    emit!(attribute::async_entry::rocket_test_attribute(args, input))
+}
+
+/// Retrofits `async fn` support in `main` functions.
+///
+/// A `main` `async fn` function decorated with `#[rocket::main]` is transformed
+/// into a regular `main` function that internally initializes a Rocket-specific
+/// tokio runtime and runs the attributed `async fn` inside of it:
+///
+/// ```rust,no_run
+/// #[rocket::main]
+/// async fn main() {
+///     let result = rocket::ignite()
+///         // .mount(..) and so on
+///         .launch()
+///         .await;
+///
+///     // Handle errors from launching as desired.
+///     if let Err(e) = result {
+///         println!("Rocket failed to launch: {}", e);
+///     }
+/// }
+/// ```
+///
+/// It should be used only when inspection of an ignited instance of `Rocket`,
+/// or of the launch error, is required. Otherwise, the [`launch`] attribute,
+/// which generates `main` in its entirety, should be preferred.
+///
+/// The decorated function must be `async` and take no arguments. Applying the
+/// attribute to a function not named `main` emits a warning.
+///
+/// [`launch`]: attr.launch.html
 #[proc_macro_attribute]
 pub fn main(args: TokenStream, input: TokenStream) -> TokenStream {
     emit!(attribute::async_entry::main_attribute(args, input))
 }
 
-/// FIXME: Document.
+/// Generates a `main` function that launches a returned `Rocket`.
+///
+/// When applied to a function that returns a `Rocket` instance, `#[launch]`
+/// automatically initializes an `async` runtime and launches the function's
+/// returned instance:
+///
+/// ```rust,no_run
+/// # #[macro_use] extern crate rocket;
+/// #[launch]
+/// fn rocket() -> rocket::Rocket {
+///     rocket::ignite()
+/// }
+/// ```
+///
+/// This generates code equivalent to the following:
+///
+/// ```rust,no_run
+/// # #[macro_use] extern crate rocket;
+/// #[rocket::main]
+/// async fn main() {
+///     let rocket: rocket::Rocket = {
+///         rocket::ignite()
+///     };
+///
+///     let _ = rocket.launch().await;
+/// }
+/// ```
+///
+/// The return type may be written as `_`, in which case it is inferred to be
+/// `Rocket`. The attributed function may also be `async`, allowing `.await`
+/// in its body:
+///
+/// ```rust,no_run
+/// # #[macro_use] extern crate rocket;
+/// # async fn load_config() { }
+/// #[launch]
+/// async fn rocket() -> _ {
+///     load_config().await;
+///     rocket::ignite()
+/// }
+/// ```
+///
+/// The function must take no arguments and cannot itself be named `main`.
+///
+/// # Errors
+///
+/// If launching fails, the returned [`Error`] is dropped without inspection,
+/// pretty-printing the error and panicking. To handle launch errors otherwise,
+/// use the [`main`] attribute instead.
+///
+/// [`Error`]: ../rocket/error/struct.Error.html
+/// [`main`]: attr.main.html
 #[proc_macro_attribute]
 pub fn launch(args: TokenStream, input: TokenStream) -> TokenStream {
     emit!(attribute::async_entry::launch_attribute(args, input))
@@ -509,52 +693,132 @@ pub fn derive_from_form_value(input: TokenStream) -> TokenStream {
 /// }
 /// ```
 ///
-/// Each field's type is required to implement [`FromFormValue`].
+/// Each field's type is required to implement [`FromFormField`], which is
+/// implemented for every [`FromFormValue`] type, for `Vec<T>` and
+/// `HashMap<K, V>` of such types, and for every structure deriving `FromForm`.
 ///
-/// The derive generates an implementation of the [`FromForm`] trait. The
-/// implementation parses a form whose field names match the field names of the
-/// structure on which the derive was applied. Each field's value is parsed with
-/// the [`FromFormValue`] implementation of the field's type. The `FromForm`
-/// implementation succeeds only when all of the field parses succeed. If
-/// parsing fails, an error ([`FromForm::Error`]) of type [`FormParseError`] is
-/// returned.
+/// The derive generates implementations of the [`FromForm`] and
+/// [`FromFormField`] traits. The implementation parses a form whose field names
+/// match the field names of the structure on which the derive was applied.
+/// Each field is parsed, with the [`FromFormField`] implementation of the
+/// field's type, from the items whose names begin with the field's name:
+/// `field=1` for values, `field[]=1` for vectors, `field[key]=1` for maps, and
+/// `field.inner=1` for nested structures. `Option<T>` is only a field when `T`
+/// implements [`FromFormValue`]; optional nested structures are not supported.
 ///
-/// The derive accepts one field attribute: `form`, with the following syntax:
+/// The `FromForm` implementation succeeds only when all of the field parses
+/// succeed. If parsing fails, an error ([`FromForm::Error`]) of type [`FormParseErrors`]
+/// is returned. It contains every error encountered while parsing the form,
+/// not only the first.
+///
+/// The derive accepts two field attributes: `form` and `field`, with the
+/// following syntax:
 ///
 /// ```text
 /// form := 'field' '=' '"' IDENT '"'
 ///
+/// field := param (',' param)*
+/// param := 'name' '=' '"' IDENT '"'
+///        | 'default' '=' LIT
+///        | 'validate' '=' '"' PATH '"'
+///
 /// IDENT := valid identifier, as defined by Rust
+/// LIT := any literal
+/// PATH := path to a function of type `fn(&T) -> bool`
 /// ```
 ///
-/// When applied, the attribute looks as follows:
+/// When applied, the attributes look as follows:
 ///
 /// ```rust
 /// # #[macro_use] extern crate rocket;
 /// #
+/// fn is_adult(age: &u8) -> bool {
+///     *age >= 18
+/// }
+///
 /// #[derive(FromForm)]
 /// struct MyStruct {
 ///     field: usize,
 ///     #[form(field = "renamed_field")]
-///     other: String
+///     other: String,
+///     #[field(name = "years", validate = "is_adult")]
+///     age: u8,
+///     #[field(default = "anonymous")]
+///     handle: String,
 /// }
 /// ```
 ///
-/// The field attribute directs that a different incoming field name is
-/// expected, and the value of the `field` attribute is used instead of the
-/// structure's actual field name when parsing a form. In the example above, the
-/// value of the `MyStruct::other` struct field will be parsed from the incoming
-/// form's `renamed_field` field.
+/// The `form(field)` and `field(name)` parameters are equivalent: they direct
+/// that a different incoming field name is expected, and the given name is
+/// used instead of the structure's actual field name when parsing a form. In
+/// the example above, the value of the `MyStruct::other` struct field will be
+/// parsed from the incoming form's `renamed_field` field.
+///
+/// The `default` parameter supplies a value for the field when it is missing
+/// from the incoming form. The literal is used as-is except for string
+/// literals, which are converted into the field's type with [`Into`]. Without
+/// `default`, a missing field takes the value of the type's
+/// [`FromFormValue::default()`], if any.
+///
+/// The `validate` parameter names a function that is called with a reference
+/// to the field's parsed value. If it returns `false`, the field is reported as
+/// a `FormParseError::BadValue`. Default values are not validated.
 ///
 /// [`FromForm`]: ../rocket/request/trait.FromForm.html
 /// [`FromFormValue`]: ../rocket/request/trait.FromFormValue.html
-/// [`FormParseError`]: ../rocket/request/enum.FormParseError.html
+/// [`FromFormField`]: ../rocket/request/trait.FromFormField.html
+/// [`FromFormValue::default()`]: ../rocket/request/trait.FromFormValue.html#method.default
+/// [`FormParseErrors`]: ../rocket/request/struct.FormParseErrors.html
 /// [`FromForm::Error`]: ../rocket/request/trait.FromForm.html#associatedtype.Error
-#[proc_macro_derive(FromForm, attributes(form))]
+#[proc_macro_derive(FromForm, attributes(form, field))]
 pub fn derive_from_form(input: TokenStream) -> TokenStream {
     emit!(derive::from_form::derive_from_form(input))
 }
 
+/// Derive for the [`FromRequest`] trait.
+///
+/// The [`FromRequest`] derive can be applied to structures with at least one
+/// field, named or unnamed, whose types all implement [`FromRequest`]:
+///
+/// ```rust
+/// # #[macro_use] extern crate rocket;
+/// use rocket::State;
+/// use rocket::http::{Method, uri::Origin};
+/// use rocket::request::ConnectionInfo;
+///
+/// # struct Config;
+/// #[derive(FromRequest)]
+/// struct Context<'r> {
+///     method: Method,
+///     uri: &'r Origin<'r>,
+///     config: State<'r, Config>,
+///     connection: Option<ConnectionInfo>,
+/// }
+///
+/// #[get("/")]
+/// fn index(context: Context<'_>) -> String {
+///     format!("{} {}", context.method, context.uri)
+/// }
+/// ```
+///
+/// The derive generates an implementation of the [`FromRequest`] trait. The
+/// implementation invokes the `FromRequest` implementation of each field's
+/// type, in field order, and succeeds only if every guard succeeds. The first
+/// guard to fail or forward short-circuits the remaining guards: a failure
+/// fails with the guard's status and a forward forwards. The guard's error is
+/// recorded in the request's trace, and the generated implementation's error
+/// type is `()`.
+///
+/// The structure may have at most one lifetime parameter. It is the lifetime
+/// of the borrow of the request, `'a` in `FromRequest<'a, 'r>`. Type
+/// parameters are required to implement `FromRequest` and `Send`.
+///
+/// [`FromRequest`]: ../rocket/request/trait.FromRequest.html
+#[proc_macro_derive(FromRequest)]
+pub fn derive_from_request(input: TokenStream) -> TokenStream {
+    emit!(derive::from_request::derive_from_request(input))
+}
+
 /// Derive for the [`Responder`] trait.
 ///
 /// The [`Responder`] derive can be applied to enums and structs with named
@@ -934,6 +1198,10 @@ pub fn catchers(input: TokenStream) -> TokenStream {
 /// let mike = uri!("/api", person: name = "Mike", age = Some(28));
 /// assert_eq!(mike.to_string(), "/api/person/Mike?age=28");
 ///
+/// // with an absolute base, yielding an `Absolute` URI
+/// let mike = uri!("https://rocket.rs/api", person: name = "Mike", age = Some(28));
+/// assert_eq!(mike.to_string(), "https://rocket.rs/api/person/Mike?age=28");
+///
 /// // with unnamed values ignored
 /// let mike = uri!(person: "Mike", _);
 /// assert_eq!(mike.to_string(), "/person/Mike");
@@ -951,6 +1219,11 @@ pub fn catchers(input: TokenStream) -> TokenStream {
 /// let option: Option<u8> = None;
 /// let mike = uri!(person: name = "Mike", age = option);
 /// assert_eq!(mike.to_string(), "/person/Mike");
+///
+/// // with named values, a literal `None` or omitted
+/// let mike = uri!(person: name = "Mike", age = None);
+/// let mike = uri!(person: name = "Mike");
+/// assert_eq!(mike.to_string(), "/person/Mike");
 /// ```
 ///
 /// ## Grammar
@@ -979,6 +1252,13 @@ pub fn catchers(input: TokenStream) -> TokenStream {
 /// implements `Into<Uri>` (and by extension, `TryInto<Uri>`), so it can be
 /// converted into a [`Uri`] using `.into()` as needed.
 ///
+/// The `mount` string is usually an origin URI such as `/api`. It may instead
+/// be an absolute URI with a scheme and authority such as
+/// `https://rocket.rs/api`, in which case `uri!` returns an [`Absolute`]
+/// structure prefixed with the scheme and authority. This is useful for
+/// generating fully qualified links to embed in emails or webhook payloads.
+/// An absolute `mount` cannot contain a query.
+///
 /// A `uri!` invocation only typechecks if the type of every value in the
 /// invocation matches the type declared for the parameter in the given route,
 /// after conversion with [`FromUriParam`], or if a value is ignored using `_`
@@ -1012,8 +1292,13 @@ pub fn catchers(input: TokenStream) -> TokenStream {
 /// parameters are not interpolated into the resulting `Origin`. Path parameters
 /// are not ignorable.
 ///
+/// A literal `None` is treated as `_`, and so is a query parameter of type
+/// `Option` or `Result` that is omitted from named parameters. Omitting any
+/// other parameter is an error.
+///
 /// [`Uri`]: ../rocket/http/uri/enum.Uri.html
 /// [`Origin`]: ../rocket/http/uri/struct.Origin.html
+/// [`Absolute`]: ../rocket/http/uri/struct.Absolute.html
 /// [`FromUriParam`]: ../rocket/http/uri/trait.FromUriParam.html
 /// [`UriDisplay`]: ../rocket/http/uri/trait.UriDisplay.html
 /// [`Ignorable`]: ../rocket/http/uri/trait.Ignorable.html
@@ -92,3 +92,23 @@ async fn main() -> Result<(), String> {
     let result = rocket::ignite().launch().await;
     result.map_err(|e| e.to_string())
 }
+
+mod h {
+    // rocket::rocket_test, both async and blocking.
+    #[rocket::rocket_test]
+    async fn async_test() {
+        let _ = rocket::tokio::spawn(async { 1 }).await;
+    }
+
+    #[rocket::rocket_test]
+    fn blocking_test() -> Result<(), String> {
+        let client = rocket::local::blocking::Client::tracked(rocket::ignite());
+        client.map(|_| ()).map_err(|e| e.to_string())
+    }
+
+    #[rocket::rocket_test]
+    #[should_panic]
+    fn blocking_test_panics() {
+        panic!("expected");
+    }
+}
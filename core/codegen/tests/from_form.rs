@@ -1,10 +1,12 @@
 #[macro_use] extern crate rocket;
 
-use rocket::request::{FromForm, FormItems, FormParseError};
+use std::collections::HashMap;
+
+use rocket::request::{FromForm, FormItems, FormParseError, FormParseErrors};
 use rocket::http::RawStr;
 
-fn parse<'f, T>(string: &'f str, strict: bool) -> Result<T, FormParseError<'f>>
-    where T: FromForm<'f, Error = FormParseError<'f>>
+fn parse<'f, T>(string: &'f str, strict: bool) -> Result<T, FormParseErrors<'f>>
+    where T: FromForm<'f, Error = FormParseErrors<'f>>
 {
     let mut items = FormItems::from(string);
     let result = T::from_form(items.by_ref(), strict);
@@ -15,14 +17,14 @@ fn parse<'f, T>(string: &'f str, strict: bool) -> Result<T, FormParseError<'f>>
     result
 }
 
-fn strict<'f, T>(string: &'f str) -> Result<T, FormParseError<'f>>
-    where T: FromForm<'f, Error = FormParseError<'f>>
+fn strict<'f, T>(string: &'f str) -> Result<T, FormParseErrors<'f>>
+    where T: FromForm<'f, Error = FormParseErrors<'f>>
 {
     parse(string, true)
 }
 
-fn lenient<'f, T>(string: &'f str) -> Result<T, FormParseError<'f>>
-    where T: FromForm<'f, Error = FormParseError<'f>>
+fn lenient<'f, T>(string: &'f str) -> Result<T, FormParseErrors<'f>>
+    where T: FromForm<'f, Error = FormParseErrors<'f>>
 {
     parse(string, false)
 }
@@ -293,29 +295,90 @@ struct WhoopsForm {
 
 #[test]
 fn form_errors() {
+    use FormParseError::*;
+
     let form: Result<WhoopsForm, _> = strict("complete=true&other=781");
     assert_eq!(form, Ok(WhoopsForm { complete: true, other: 781 }));
 
     let form: Result<WhoopsForm, _> = strict("complete=true&other=unknown");
-    assert_eq!(form, Err(FormParseError::BadValue("other".into(), "unknown".into())));
+    assert_eq!(form, Err(BadValue("other".into(), "unknown".into()).into()));
 
     let form: Result<WhoopsForm, _> = strict("complete=unknown&other=unknown");
-    assert_eq!(form, Err(FormParseError::BadValue("complete".into(), "unknown".into())));
+    assert_eq!(form, Err(vec![
+        BadValue("complete".into(), "unknown".into()),
+        BadValue("other".into(), "unknown".into()),
+    ].into()));
 
     let form: Result<WhoopsForm, _> = strict("complete=true&other=1&extra=foo");
-    assert_eq!(form, Err(FormParseError::Unknown("extra".into(), "foo".into())));
+    assert_eq!(form, Err(Unknown("extra".into(), "foo".into()).into()));
+
+    // Unknown fields are reported first, in form order...
+    let form: Result<WhoopsForm, _> = strict("unknown=foo&complete=unknown");
+    assert_eq!(form, Err(vec![
+        Unknown("unknown".into(), "foo".into()),
+        BadValue("complete".into(), "unknown".into()),
+        Missing("other".into()),
+    ].into()));
+
+    // ...followed by bad values and missing fields, in field order. Unknown
+    // fields are allowed when lenient.
+    let form: Result<WhoopsForm, _> = lenient("unknown=foo&complete=unknown");
+    assert_eq!(form, Err(vec![
+        BadValue("complete".into(), "unknown".into()),
+        Missing("other".into()),
+    ].into()));
 
-    // Bad values take highest precedence.
-    let form: Result<WhoopsForm, _> = strict("complete=unknown&unknown=foo");
-    assert_eq!(form, Err(FormParseError::BadValue("complete".into(), "unknown".into())));
+    let form: Result<WhoopsForm, _> = strict("complete=true");
+    assert_eq!(form, Err(Missing("other".into()).into()));
+}
 
-    // Then unknown key/values for strict parses.
-    let form: Result<WhoopsForm, _> = strict("complete=true&unknown=foo");
-    assert_eq!(form, Err(FormParseError::Unknown("unknown".into(), "foo".into())));
+fn is_even(n: &usize) -> bool {
+    n % 2 == 0
+}
 
-    // Finally, missing.
-    let form: Result<WhoopsForm, _> = strict("complete=true");
-    assert_eq!(form, Err(FormParseError::Missing("other".into())));
+#[derive(Debug, PartialEq, FromForm)]
+struct FieldAttrForm {
+    #[field(name = "renamed")]
+    original: String,
+    #[field(default = 10)]
+    count: usize,
+    #[field(default = "anonymous")]
+    user: String,
+    #[field(validate = "is_even")]
+    even: usize,
+    #[field(name = "odd", default = 2, validate = "is_even")]
+    other: usize,
+}
+
+#[test]
+fn field_attributes() {
+    use FormParseError::*;
+
+    let form: Result<FieldAttrForm, _> = strict("renamed=hi&even=4");
+    assert_eq!(form, Ok(FieldAttrForm {
+        original: "hi".into(),
+        count: 10,
+        user: "anonymous".into(),
+        even: 4,
+        other: 2,
+    }));
+
+    let form: Result<FieldAttrForm, _> = strict("renamed=a&count=3&user=bob&even=0&odd=8");
+    assert_eq!(form, Ok(FieldAttrForm {
+        original: "a".into(),
+        count: 3,
+        user: "bob".into(),
+        even: 0,
+        other: 8,
+    }));
+
+    let form: Result<FieldAttrForm, _> = strict("original=hi&even=3&odd=5");
+    assert_eq!(form, Err(vec![
+        Unknown("original".into(), "hi".into()),
+        Missing("renamed".into()),
+        BadValue("even".into(), "3".into()),
+        BadValue("odd".into(), "5".into()),
+    ].into()));
 }
 
 #[derive(Debug, PartialEq, FromForm)]
@@ -328,3 +391,107 @@ fn raw_ident_form() {
     let form: Result<RawIdentForm, _> = strict("type=a");
     assert_eq!(form, Ok(RawIdentForm { r#type: "a".into() }));
 }
+
+#[derive(Debug, PartialEq, FromForm)]
+struct Address {
+    street: String,
+    city: String,
+}
+
+#[derive(Debug, PartialEq, FromForm)]
+struct Person {
+    name: String,
+    address: Address,
+    tags: Vec<String>,
+    scores: HashMap<String, usize>,
+}
+
+#[test]
+fn nested_forms() {
+    use FormParseError::*;
+
+    let form_string = &[
+        "name=Bob", "address.street=Main", "address[city]=Paris",
+        "tags[]=a", "tags=b", "scores[math]=10", "scores.art=8",
+    ].join("&");
+
+    let form: Result<Person, _> = strict(form_string);
+    assert_eq!(form, Ok(Person {
+        name: "Bob".into(),
+        address: Address { street: "Main".into(), city: "Paris".into() },
+        tags: vec!["a".into(), "b".into()],
+        scores: vec![("math".into(), 10), ("art".into(), 8)].into_iter().collect(),
+    }));
+
+    // Browsers percent-encode brackets.
+    let form_string = &[
+        "name=Bob", "address%5Bstreet%5D=Main", "address%5bcity%5d=Paris",
+        "tags%5B%5D=a", "scores%5Bmath%5D=10",
+    ].join("&");
+
+    let form: Result<Person, _> = strict(form_string);
+    assert_eq!(form.map(|person| person.address.city), Ok("Paris".into()));
+
+    let form: Result<Person, _> = strict("name=Bob&address.town=Paris&scores[math]=A&scores=1");
+    assert_eq!(form, Err(vec![
+        Unknown("address.town".into(), "Paris".into()),
+        Missing("address.street".into()),
+        Missing("address.city".into()),
+        Unknown("scores".into(), "1".into()),
+        BadValue("scores[math]".into(), "A".into()),
+    ].into()));
+
+    let form: Result<Person, _> = lenient("name=Bob&address.town=Paris&address.city=Paris");
+    assert_eq!(form, Err(Missing("address.street".into()).into()));
+}
+
+#[derive(Debug, PartialEq, FromForm)]
+struct Item {
+    name: String,
+    #[field(default = 1)]
+    quantity: usize,
+}
+
+#[derive(Debug, PartialEq, FromForm)]
+struct Order {
+    items: Vec<Item>,
+    #[form(field = "notes.all")]
+    all_notes: String,
+    notes: HashMap<usize, Vec<String>>,
+}
+
+#[test]
+fn nested_collections() {
+    use FormParseError::*;
+
+    let form_string = &[
+        "items[0].name=apple", "items[1].name=pear", "items[0].quantity=3",
+        "notes.all=none", "notes[1]=ripe", "notes[1]=red", "notes[2][]=soft",
+    ].join("&");
+
+    let form: Result<Order, _> = strict(form_string);
+    assert_eq!(form, Ok(Order {
+        items: vec![
+            Item { name: "apple".into(), quantity: 3 },
+            Item { name: "pear".into(), quantity: 1 },
+        ],
+        all_notes: "none".into(),
+        notes: vec![
+            (1, vec!["ripe".into(), "red".into()]),
+            (2, vec!["soft".into()]),
+        ].into_iter().collect(),
+    }));
+
+    let form_string = &[
+        "items[0].quantity=2", "items[x].name=a", "notes.all=", "notes[a]=b",
+    ].join("&");
+
+    let form: Result<Order, _> = strict(form_string);
+    assert_eq!(form, Err(vec![
+        Missing("items.name".into()),
+        BadValue("notes[a]".into(), "b".into()),
+    ].into()));
+
+    let form: Result<Order, _> = strict("notes.all=");
+    assert_eq!(form, Ok(Order { items: vec![], all_notes: "".into(), notes: HashMap::new() }));
+}
@@ -0,0 +1,85 @@
+#[macro_use] extern crate rocket;
+
+use rocket::local::blocking::Client;
+use rocket::request::{self, FromRequest, Request};
+use rocket::http::{Header, Method, Status, uri::Origin};
+
+struct ApiKey<'r>(&'r str);
+
+#[rocket::async_trait]
+impl<'a, 'r> FromRequest<'a, 'r> for ApiKey<'a> {
+    type Error = &'static str;
+
+    async fn from_request(req: &'a Request<'r>) -> request::Outcome<Self, Self::Error> {
+        match req.headers().get_one("X-Api-Key") {
+            Some("forward") => request::Outcome::Forward(()),
+            Some(key) => request::Outcome::Success(ApiKey(key)),
+            None => request::Outcome::Failure((Status::Unauthorized, "missing key")),
+        }
+    }
+}
+
+#[derive(FromRequest)]
+struct Context<'r> {
+    method: Method,
+    uri: &'r Origin<'r>,
+    key: Option<ApiKey<'r>>,
+}
+
+#[derive(FromRequest)]
+struct Authorized<'r>(ApiKey<'r>, Method);
+
+#[derive(FromRequest)]
+struct Generic<T>(T);
+
+#[get("/context")]
+fn context(context: Context<'_>) -> String {
+    let key = context.key.map(|k| k.0).unwrap_or("none");
+    format!("{} {} {}", context.method, context.uri, key)
+}
+
+#[get("/authorized")]
+fn authorized(auth: Authorized<'_>) -> String {
+    format!("{} {}", (auth.0).0, auth.1)
+}
+
+#[get("/authorized", rank = 2)]
+fn forwarded() -> &'static str {
+    "forwarded"
+}
+
+#[get("/generic")]
+fn generic(method: Generic<Method>) -> String {
+    method.0.to_string()
+}
+
+fn client() -> Client {
+    let routes = routes![context, authorized, forwarded, generic];
+    Client::tracked(rocket::ignite().mount("/", routes)).unwrap()
+}
+
+#[test]
+fn derived_guard_collects_fields() {
+    let client = client();
+    let response = client.get("/context?a=b").dispatch();
+    assert_eq!(response.into_string().unwrap(), "GET /context?a=b none");
+
+    let response = client.get("/context").header(Header::new("X-Api-Key", "k")).dispatch();
+    assert_eq!(response.into_string().unwrap(), "GET /context k");
+
+    let response = client.get("/generic").dispatch();
+    assert_eq!(response.into_string().unwrap(), "GET");
+}
+
+#[test]
+fn derived_guard_short_circuits() {
+    let client = client();
+    let response = client.get("/authorized").header(Header::new("X-Api-Key", "k")).dispatch();
+    assert_eq!(response.into_string().unwrap(), "k GET");
+
+    let response = client.get("/authorized").header(Header::new("X-Api-Key", "forward")).dispatch();
+    assert_eq!(response.into_string().unwrap(), "forwarded");
+
+    let response = client.get("/authorized").dispatch();
+    assert_eq!(response.status(), Status::Unauthorized);
+}
@@ -33,9 +33,12 @@ fn form(form: Form<Inner<'_>>) -> String { form.field.url_decode_lossy() }
 #[post("/s", data = "<simple>")]
 fn simple(simple: Simple) -> String { simple.0 }
 
+#[get("/s", data = "<simple>", allow_payload = true)]
+fn get_simple(simple: Simple) -> String { simple.0 }
+
 #[test]
 fn test_data() {
-    let rocket = rocket::ignite().mount("/", routes![form, simple]);
+    let rocket = rocket::ignite().mount("/", routes![form, simple, get_simple]);
     let client = Client::tracked(rocket).unwrap();
 
     let response = client.post("/f")
@@ -50,4 +53,7 @@ fn test_data() {
 
     let response = client.post("/s").body("this%20is%20here").dispatch();
     assert_eq!(response.into_string().unwrap(), "this%20is%20here");
+
+    let response = client.get("/s").body("this is here").dispatch();
+    assert_eq!(response.into_string().unwrap(), "this is here");
 }
@@ -5,8 +5,8 @@
 use std::path::PathBuf;
 
 use rocket::http::{RawStr, CookieJar};
-use rocket::http::uri::{FromUriParam, Query};
-use rocket::request::Form;
+use rocket::http::uri::{FromUriParam, Query, Segments};
+use rocket::request::{self, Form, FromQuery};
 
 #[derive(FromForm, UriDisplayQuery)]
 struct User<'a> {
@@ -187,6 +187,36 @@ fn check_mount_point() {
     }
 }
 
+#[test]
+fn check_absolute_base() {
+    use rocket::http::uri::Absolute;
+
+    macro_rules! assert_absolute_eq {
+        ($($uri:expr => $expected:expr,)+) => {
+            $(assert_eq!($uri, Absolute::parse($expected).expect("valid absolute URI"));)+
+        };
+    }
+
+    assert_absolute_eq! {
+        uri!("https://rocket.rs", simple: 100) => "https://rocket.rs/100",
+        uri!("https://rocket.rs/", simple: id = 23) => "https://rocket.rs/23",
+        uri!("https://rocket.rs/api", simple: 100) => "https://rocket.rs/api/100",
+        uri!("http://user@localhost:8000/a", simple2: 1, "b c")
+            => "http://user@localhost:8000/a/1/b%20c",
+        uri!("https://rocket.rs", simple4: id = 1, name = "hi")
+            => "https://rocket.rs/?id=1&name=hi",
+        uri!("https://rocket.rs/c", segments: "one/two")
+            => "https://rocket.rs/c/a/one/two",
+        uri!("file:/web", simple: 7) => "file:/web/7",
+    }
+
+    let uri = uri!("https://api.example.com/v1", simple2: id = 5, name = "x");
+    assert_eq!(uri.scheme(), "https");
+    assert_eq!(uri.authority().unwrap().host(), "api.example.com");
+    assert_eq!(uri.origin().unwrap().path(), "/v1/5/x");
+    assert_eq!(uri.to_string(), "https://api.example.com/v1/5/x");
+}
+
 #[test]
 fn check_guards_ignored() {
     assert_uri_eq! {
@@ -434,3 +464,65 @@ fn test_optional_uri_parameters() {
         ) => "/10/hi%20there",
     }
 }
+
+#[derive(UriDisplayQuery)]
+struct Ids {
+    id: Vec<usize>,
+}
+
+impl<'q> FromQuery<'q> for Ids {
+    type Error = ();
+
+    fn from_query(query: request::Query<'q>) -> Result<Self, ()> {
+        let id = query.filter(|i| i.key == "id")
+            .map(|i| i.value.as_str().parse().map_err(|_| ()))
+            .collect::<Result<_, _>>()?;
+
+        Ok(Ids { id })
+    }
+}
+
+#[get("/<path..>?<page>&<sort>&<ids..>")]
+fn listing(path: Segments<'_>, page: Option<usize>, sort: Option<String>, ids: Option<Ids>) { }
+
+#[test]
+fn test_omitted_and_repeated_uri_parameters() {
+    let path = PathBuf::from("a/b c");
+    assert_uri_eq! {
+        uri!(listing: path = "a/b", page = Some(2), sort = Some("name"), ids = _)
+            => "/a/b?page=2&sort=name",
+        uri!(listing: path = "a/b", page = None, sort = None, ids = None)
+            => "/a/b",
+        uri!(listing: path = "a/b") => "/a/b",
+        uri!(listing: path = "a", sort = Some("date")) => "/a?sort=date",
+        uri!(listing: path = &path, page = Some(3)) => "/a/b%20c?page=3",
+        uri!(listing: path = Segments("x/y"), ids = Some(Ids { id: vec![1, 2] }))
+            => "/x/y?id=1&id=2",
+    }
+}
+
+#[get("/<id>/<big>?<ratio>&<count>")]
+fn widened(id: i32, big: i64, ratio: f64, count: Option<i32>) { }
+
+#[test]
+fn test_widened_uri_parameters() {
+    let (small, unsigned): (u8, u16) = (7, 9);
+    assert_uri_eq! {
+        uri!(widened: 5u8, 6i64, 0.5f32, Some(3u16)) => "/5/6?ratio=0.5&count=3",
+        uri!(widened: -3i16, -4, 0.25, None) => "/-3/-4?ratio=0.25",
+        uri!(widened: id = small, big = 9, ratio = 1.5, count = Some(small))
+            => "/7/9?ratio=1.5&count=7",
+        uri!(widened: id = &unsigned, big = 7, ratio = &0.5f32)
+            => "/9/7?ratio=0.5",
+        uri!(widened: 10, 20, 1.5, Some(30)) => "/10/20?ratio=1.5&count=30",
+    }
+}
+
+#[test]
+fn test_large_literal_uri_parameters() {
+    assert_uri_eq! {
+        uri!(widened: 1, 5_000_000_000, 1.5, None) => "/1/5000000000?ratio=1.5",
+        uri!(widened: 1, -9_000_000_000_000_000_000, 1.5, None)
+            => "/1/-9000000000000000000?ratio=1.5",
+    }
+}
@@ -0,0 +1 @@
+../ui-fail/catch-range.rs
\ No newline at end of file
@@ -0,0 +1 @@
+../ui-fail/rocket-phases.rs
\ No newline at end of file
@@ -0,0 +1 @@
+../ui-fail/route-payload.rs
\ No newline at end of file
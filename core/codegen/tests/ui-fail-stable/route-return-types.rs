@@ -0,0 +1 @@
+../ui-fail/route-return-types.rs
\ No newline at end of file
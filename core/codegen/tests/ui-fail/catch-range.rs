@@ -0,0 +1,23 @@
+#[macro_use] extern crate rocket;
+
+use rocket::http::Status;
+
+#[catch(400..)]
+fn r0(_status: Status) { }
+
+#[catch(..500)]
+fn r1(_status: Status) { }
+
+#[catch(400..=)]
+fn r2(_status: Status) { }
+
+#[catch(99..200)]
+fn r3(_status: Status) { }
+
+#[catch(500..601)]
+fn r4(_status: Status) { }
+
+#[catch(500..500)]
+fn r5(_status: Status) { }
+
+fn main() { }
@@ -141,4 +141,17 @@ struct BadName3 {
     field: String,
 }
 
+#[derive(FromForm)]
+struct DoubleName {
+    #[form(field = "a")]
+    #[field(name = "b")]
+    field: String,
+}
+
+#[derive(FromForm)]
+struct BadValidator {
+    #[field(validate = "not a path")]
+    field: String,
+}
+
 fn main() { }
@@ -0,0 +1,29 @@
+#[macro_use] extern crate rocket;
+
+#[get("/")]
+fn index() { }
+
+#[catch(404)]
+fn not_found() { }
+
+async fn ignited() -> rocket::Rocket<rocket::Ignite> {
+    rocket::ignite().ignited().await.unwrap()
+}
+
+async fn mount() {
+    let _ = ignited().await.mount("/", routes![index]);
+}
+
+async fn register() {
+    let _ = ignited().await.register(catchers![not_found]);
+}
+
+async fn manage() {
+    let _ = ignited().await.manage(1usize);
+}
+
+async fn attach() {
+    let _ = ignited().await.attach(rocket::fairing::AdHoc::on_launch("", |_| {}));
+}
+
+fn main() { }
@@ -95,7 +95,7 @@ fn e12() {}
 #[route(CONNECT, "/")]
 fn f0() {}
 
-#[route(FIX, "/")]
+#[route(Fix, "/")]
 fn f1() {}
 
 #[route("hi", "/")]
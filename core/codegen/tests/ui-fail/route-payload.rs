@@ -0,0 +1,26 @@
+#[macro_use] extern crate rocket;
+
+use rocket::Data;
+
+#[get("/", data = "<_d>")]
+fn f0(_d: Data) {}
+
+#[head("/", data = "<_d>", allow_payload = false)]
+fn f1(_d: Data) {}
+
+#[get("/", allow_payload = true)]
+fn f2() {}
+
+#[post("/", data = "<_d>", allow_payload = true)]
+fn f3(_d: Data) {}
+
+#[get("/ok", data = "<_d>", allow_payload = true)]
+fn f4(_d: Data) {}
+
+#[get("/", format = "*/*")]
+fn g0() {}
+
+#[head("/", format = "*/*")]
+fn g1() {}
+
+fn main() {}
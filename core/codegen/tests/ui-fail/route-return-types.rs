@@ -0,0 +1,15 @@
+#[macro_use] extern crate rocket;
+
+#[get("/")]
+fn f0() -> usize { 0 }
+
+#[get("/")]
+async fn f1() -> bool { true }
+
+#[get("/")]
+fn f2() -> Option<u8> { None }
+
+#[get("/")]
+fn f3() -> Option<String> { None }
+
+fn main() {}
@@ -14,4 +14,6 @@ fn main() {
     uri!("/mount/<id>", simple);
     uri!();
     uri!(simple: id = );
+    uri!("https://rocket.rs/?q", simple);
+    uri!("localhost:8000", simple);
 }
@@ -183,6 +183,8 @@ mod key {
 pub struct CookieJar<'a> {
     jar: cookie::CookieJar,
     key: &'a Key,
+    secure: bool,
+    same_site: SameSite,
     ops: Mutex<Vec<Op>>,
 }
 
@@ -191,6 +193,8 @@ impl<'a> Clone for CookieJar<'a> {
         CookieJar {
             jar: self.jar.clone(),
             key: self.key,
+            secure: self.secure,
+            same_site: self.same_site,
             ops: Mutex::new(self.ops.lock().clone()),
         }
     }
@@ -316,7 +320,10 @@ impl<'a> CookieJar<'a> {
     /// set on `cookie` before being added to `self`:
     ///
     ///    * `path`: `"/"`
-    ///    * `SameSite`: `Strict`
+    ///    * `SameSite`: the `cookie_same_site` configuration parameter, which
+    ///      is `Strict` by default
+    ///    * `Secure`: `true` if the `cookie_secure` configuration parameter is
+    ///      set, which it isn't by default, or if `SameSite` is `None`
     ///
     /// # Example
     ///
@@ -337,7 +344,7 @@ impl<'a> CookieJar<'a> {
     /// }
     /// ```
     pub fn add(&self, mut cookie: Cookie<'static>) {
-        Self::set_defaults(&mut cookie);
+        self.set_defaults(&mut cookie);
         self.ops.lock().push(Op::Add(cookie, false));
     }
 
@@ -351,12 +358,15 @@ impl<'a> CookieJar<'a> {
     /// set on `cookie` before being added to `self`:
     ///
     ///    * `path`: `"/"`
-    ///    * `SameSite`: `Strict`
+    ///    * `SameSite`: the `cookie_same_site` configuration parameter, which
+    ///      is `Strict` by default
     ///    * `HttpOnly`: `true`
     ///    * `Expires`: 1 week from now
+    ///    * `Secure`: `true` if the `cookie_secure` configuration parameter is
+    ///      set, which it isn't by default, or if `SameSite` is `None`
     ///
     /// These defaults ensure maximum usability and security. For additional
-    /// security, you may wish to set the `secure` flag.
+    /// security outside of `release`, you may wish to set the `secure` flag.
     ///
     /// # Example
     ///
@@ -372,7 +382,7 @@ impl<'a> CookieJar<'a> {
     #[cfg(feature = "private-cookies")]
     #[cfg_attr(nightly, doc(cfg(feature = "secrets")))]
     pub fn add_private(&self, mut cookie: Cookie<'static>) {
-        Self::set_private_defaults(&mut cookie);
+        self.set_private_defaults(&mut cookie);
         self.ops.lock().push(Op::Add(cookie, true));
     }
 
@@ -461,16 +471,18 @@ impl<'a> CookieJar<'a> {
 #[doc(hidden)]
 impl<'a> CookieJar<'a> {
     #[inline(always)]
-    pub fn new(key: &'a Key) -> Self {
-        CookieJar {
-            jar: cookie::CookieJar::new(),
-            key, ops: Mutex::new(Vec::new()),
-        }
+    pub fn new(key: &'a Key, secure: bool, same_site: SameSite) -> Self {
+        CookieJar::from(cookie::CookieJar::new(), key, secure, same_site)
     }
 
     #[inline(always)]
-    pub fn from(jar: cookie::CookieJar, key: &'a Key) -> CookieJar<'a> {
-        CookieJar { jar, key, ops: Mutex::new(Vec::new()) }
+    pub fn from(
+        jar: cookie::CookieJar,
+        key: &'a Key,
+        secure: bool,
+        same_site: SameSite
+    ) -> CookieJar<'a> {
+        CookieJar { jar, key, secure, same_site, ops: Mutex::new(Vec::new()) }
     }
 
     /// Removes all delta cookies.
@@ -526,15 +538,23 @@ impl<'a> CookieJar<'a> {
     /// values are:
     ///
     ///    * `path`: `"/"`
-    ///    * `SameSite`: `Strict`
+    ///    * `SameSite`: the `SameSite` `self` was created with
+    ///    * `Secure`: `true`, if `self` was created with secure defaults or
+    ///      `SameSite` is `None`
     ///
-    fn set_defaults(cookie: &mut Cookie<'static>) {
+    fn set_defaults(&self, cookie: &mut Cookie<'static>) {
         if cookie.path().is_none() {
             cookie.set_path("/");
         }
 
         if cookie.same_site().is_none() {
-            cookie.set_same_site(SameSite::Strict);
+            cookie.set_same_site(self.same_site);
+        }
+
+        // Browsers reject `SameSite=None` cookies that aren't `Secure`.
+        let cross_site = cookie.same_site() == Some(SameSite::None);
+        if (self.secure || cross_site) && cookie.secure().is_none() {
+            cookie.set_secure(true);
         }
     }
 
@@ -543,20 +563,16 @@ impl<'a> CookieJar<'a> {
     /// values are:
     ///
     ///    * `path`: `"/"`
-    ///    * `SameSite`: `Strict`
+    ///    * `SameSite`: the `SameSite` `self` was created with
     ///    * `HttpOnly`: `true`
     ///    * `Expires`: 1 week from now
+    ///    * `Secure`: `true`, if `self` was created with secure defaults or
+    ///      `SameSite` is `None`
     ///
     #[cfg(feature = "private-cookies")]
     #[cfg_attr(nightly, doc(cfg(feature = "secrets")))]
-    fn set_private_defaults(cookie: &mut Cookie<'static>) {
-        if cookie.path().is_none() {
-            cookie.set_path("/");
-        }
-
-        if cookie.same_site().is_none() {
-            cookie.set_same_site(SameSite::Strict);
-        }
+    fn set_private_defaults(&self, cookie: &mut Cookie<'static>) {
+        self.set_defaults(cookie);
 
         if cookie.http_only().is_none() {
             cookie.set_http_only(true);
@@ -102,6 +102,107 @@ impl<'h> Header<'h> {
     pub fn value(&self) -> &str {
         &self.value
     }
+
+    /// Constructs a new header after validating its name and value. Returns an
+    /// `Err` if either is malformed. See [`Header::is_valid_name()`] and
+    /// [`Header::is_valid_value()`] for what constitutes a valid name or value.
+    ///
+    /// Prefer this method to [`Header::new()`] when the name or value is
+    /// derived from untrusted input, such as a request.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # extern crate rocket;
+    /// use rocket::http::{Header, HeaderError};
+    ///
+    /// let header = Header::try_new("X-Custom-Header", "custom value");
+    /// assert_eq!(header.unwrap().value(), "custom value");
+    ///
+    /// let header = Header::try_new("X-Custom-Header", "value\r\nSet-Cookie: a=b");
+    /// assert_eq!(header, Err(HeaderError::InvalidValue));
+    ///
+    /// let header = Header::try_new("X Custom Header", "custom value");
+    /// assert_eq!(header, Err(HeaderError::InvalidName));
+    /// ```
+    pub fn try_new<'a: 'h, 'b: 'h, N, V>(name: N, value: V) -> Result<Header<'h>, HeaderError>
+        where N: Into<Cow<'a, str>>, V: Into<Cow<'b, str>>
+    {
+        let header = Header::new(name, value);
+        header.validate()?;
+        Ok(header)
+    }
+
+    /// Returns `Ok` if this header's name and value are valid. Otherwise
+    /// returns an `Err` indicating which of the two is malformed.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # extern crate rocket;
+    /// use rocket::http::{Header, HeaderError};
+    ///
+    /// assert!(Header::new("X-Custom-Header", "custom value").validate().is_ok());
+    ///
+    /// let header = Header::new("X-Custom-Header", "bad\nvalue");
+    /// assert_eq!(header.validate(), Err(HeaderError::InvalidValue));
+    /// ```
+    pub fn validate(&self) -> Result<(), HeaderError> {
+        if !Header::is_valid_name(self.name.as_str()) {
+            return Err(HeaderError::InvalidName);
+        }
+
+        if !Header::is_valid_value(&self.value) {
+            return Err(HeaderError::InvalidValue);
+        }
+
+        Ok(())
+    }
+
+    /// Returns `true` if `name` is a valid header name: a non-empty string of
+    /// RFC 7230 token characters. In particular, whitespace, `:`, and control
+    /// characters are not allowed.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # extern crate rocket;
+    /// use rocket::http::Header;
+    ///
+    /// assert!(Header::is_valid_name("X-Custom-Header"));
+    /// assert!(!Header::is_valid_name("X-Custom: Header"));
+    /// assert!(!Header::is_valid_name(""));
+    /// ```
+    pub fn is_valid_name(name: &str) -> bool {
+        fn is_tchar(c: u8) -> bool {
+            match c {
+                b'0'..=b'9' | b'a'..=b'z' | b'A'..=b'Z'
+                    | b'!' | b'#' | b'$' | b'%' | b'&' | b'\'' | b'*'
+                    | b'+' | b'-' | b'.' | b'^' | b'_' | b'`' | b'|' | b'~' => true,
+                _ => false
+            }
+        }
+
+        !name.is_empty() && name.bytes().all(is_tchar)
+    }
+
+    /// Returns `true` if `value` is a valid header value: one that contains no
+    /// control characters other than horizontal tab. In particular, a valid
+    /// value contains neither a CR nor an LF, preventing header injection.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # extern crate rocket;
+    /// use rocket::http::Header;
+    ///
+    /// assert!(Header::is_valid_value("custom\tvalue"));
+    /// assert!(!Header::is_valid_value("custom\r\nvalue"));
+    /// assert!(!Header::is_valid_value("custom\0value"));
+    /// ```
+    pub fn is_valid_value(value: &str) -> bool {
+        value.bytes().all(|c| c == b'\t' || (c >= 0x20 && c != 0x7f))
+    }
 }
 
 impl fmt::Display for Header<'_> {
@@ -111,6 +212,29 @@ impl fmt::Display for Header<'_> {
     }
 }
 
+/// An error returned when a header's name or value is malformed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum HeaderError {
+    /// The header's name is empty or contains a character that is not a valid
+    /// token character, such as whitespace, `:`, or a control character.
+    InvalidName,
+    /// The header's value contains a control character other than horizontal
+    /// tab, such as a CR or LF.
+    InvalidValue,
+}
+
+impl fmt::Display for HeaderError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            HeaderError::InvalidName => write!(f, "invalid header name"),
+            HeaderError::InvalidValue => write!(f, "invalid header value"),
+        }
+    }
+}
+
+impl std::error::Error for HeaderError {  }
+
 /// A collection of headers, mapping a header name to its many ordered values.
 ///
 /// # Case-Insensitivity
@@ -629,7 +753,7 @@ impl<'h> HeaderMap<'h> {
 
 #[cfg(test)]
 mod tests {
-    use super::HeaderMap;
+    use super::{Header, HeaderMap, HeaderError};
 
     #[test]
     fn case_insensitive_add_get() {
@@ -653,4 +777,23 @@ mod tests {
         let vals: Vec<_> = map.get("x-CuStOm").collect();
         assert_eq!(vals, vec!["a", "b", "c"]);
     }
+
+    #[test]
+    fn header_validation() {
+        for name in &["X-Custom", "x_custom", "A1!#$%&'*+-.^_`|~"] {
+            assert!(Header::try_new(*name, "v").is_ok(), "{:?}", name);
+        }
+
+        for name in &["", "X Custom", "X-Custom:", "X\r\nY", "X(Y)", "Ünicode", "{x}"] {
+            assert_eq!(Header::try_new(*name, "v"), Err(HeaderError::InvalidName), "{:?}", name);
+        }
+
+        for value in &["", "a value", "tab\tvalue", "\"quoted\" (c) ü"] {
+            assert!(Header::try_new("X", *value).is_ok(), "{:?}", value);
+        }
+
+        for value in &["a\r\nSet-Cookie: x", "a\nb", "a\rb", "a\0b", "a\x7fb", "a\x1bb"] {
+            assert_eq!(Header::try_new("X", *value), Err(HeaderError::InvalidValue), "{:?}", value);
+        }
+    }
 }
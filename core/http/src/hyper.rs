@@ -14,6 +14,7 @@
 #[doc(hidden)] pub use http::header::HeaderName as HeaderName;
 #[doc(hidden)] pub use http::header::HeaderValue as HeaderValue;
 #[doc(hidden)] pub use http::method::Method;
+#[doc(hidden)] pub use http::version::Version;
 #[doc(hidden)] pub use http::request::Parts as RequestParts;
 #[doc(hidden)] pub use http::response::Builder as ResponseBuilder;
 #[doc(hidden)] pub use http::status::StatusCode;
@@ -35,6 +35,7 @@ macro_rules! known_media_types {
         TIFF (is_tiff): "TIFF", "image", "tiff",
         AAC (is_aac): "AAC Audio", "audio", "aac",
         Calendar (is_ical): "iCalendar", "text", "calendar",
+        EventStream (is_event_stream): "SSE stream", "text", "event-stream",
         MPEG (is_mpeg): "MPEG Video", "video", "mpeg",
         TAR (is_tar): "tape archive", "application", "x-tar",
         GZIP (is_gzip): "gzipped binary", "application", "gzip",
@@ -31,6 +31,7 @@ mod docify;
 mod known_media_types;
 mod cookies;
 mod method;
+mod version;
 mod media_type;
 mod content_type;
 mod status;
@@ -39,6 +40,7 @@ mod accept;
 mod raw_str;
 mod parse;
 mod listener;
+mod proxy_proto;
 
 /// Case-preserving, ASCII case-insensitive string types.
 ///
@@ -60,14 +62,19 @@ pub mod private {
         pub use crate::cookies::Key;
     }
 
-    pub use crate::listener::{Incoming, Listener, Connection, bind_tcp};
+    pub use crate::listener::{Incoming, TlsInfo};
+    pub use crate::listener::{Timed, Activity, Busy};
+    pub use crate::listener::{bind_tcp, from_std_tcp};
+    pub use crate::proxy_proto::ProxyProtoListener;
 }
 
 pub use crate::method::Method;
+pub use crate::version::Version;
 pub use crate::content_type::ContentType;
 pub use crate::accept::{Accept, QMediaType};
 pub use crate::status::{Status, StatusClass};
-pub use crate::header::{Header, HeaderMap};
+pub use crate::header::{Header, HeaderMap, HeaderError};
 pub use crate::raw_str::RawStr;
 pub use crate::media_type::MediaType;
 pub use crate::cookies::{Cookie, CookieJar, SameSite};
+pub use crate::listener::{Listener, Connection};
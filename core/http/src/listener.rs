@@ -3,6 +3,8 @@ use std::future::Future;
 use std::io;
 use std::net::SocketAddr;
 use std::pin::Pin;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
 use std::task::{Context, Poll};
 use std::time::Duration;
 
@@ -14,22 +16,116 @@ use tokio::time::Delay;
 use tokio::io::{AsyncRead, AsyncWrite};
 use tokio::net::{TcpListener, TcpStream};
 
-// TODO.async: 'Listener' and 'Connection' provide common enough functionality
-// that they could be introduced in upstream libraries.
-/// A 'Listener' yields incoming connections
+/// A source of incoming connections for Rocket to serve.
+///
+/// Rocket binds a TCP listener, with TLS if configured, when launched via
+/// `Rocket::launch()`. To serve over any other transport, implement `Listener`
+/// and launch via `Rocket::launch_on()`. This makes it possible to serve over
+/// a socket bound by another process, as with systemd socket activation, over
+/// in-memory streams in tests, or over a custom TLS stack.
+///
+/// `Listener` is implemented for [`tokio::net::TcpListener`].
+///
+/// # Example
+///
+/// Serving over a socket passed by systemd, which it places at file
+/// descriptor `3`:
+///
+/// ```rust,no_run
+/// # extern crate rocket;
+/// # #[cfg(unix)]
+/// use std::os::unix::io::FromRawFd;
+///
+/// use rocket::tokio::net::TcpListener;
+///
+/// # #[cfg(unix)]
+/// #[rocket::main]
+/// async fn main() {
+///     let std_listener = unsafe { std::net::TcpListener::from_raw_fd(3) };
+///     std_listener.set_nonblocking(true).expect("nonblocking socket");
+///     let listener = TcpListener::from_std(std_listener).expect("tokio listener");
+///
+///     let result = rocket::ignite().launch_on(listener).await;
+///     assert!(result.is_ok());
+/// }
+/// # #[cfg(not(unix))] fn main() { }
+/// ```
 pub trait Listener {
+    /// The type of connections accepted by this listener.
     type Connection: Connection;
 
-    /// Return the actual address this listener bound to.
+    /// Returns the address this listener is bound to, if it has one.
+    ///
+    /// Rocket reports the address's port as the port it launched on.
     fn local_addr(&self) -> Option<SocketAddr>;
 
-    /// Try to accept an incoming Connection if ready
+    /// Polls for an incoming connection, registering the current task for
+    /// wakeup if none is ready.
+    ///
+    /// Errors that pertain to a single connection, such as
+    /// `ConnectionReset`, are skipped. Other errors cause Rocket to log the
+    /// error and pause accepting for a second.
     fn poll_accept(&mut self, cx: &mut Context<'_>) -> Poll<io::Result<Self::Connection>>;
 }
 
-/// A 'Connection' represents an open connection to a client
+/// A connection to a client, accepted by a [`Listener`].
+///
+/// `Connection` is implemented for [`tokio::net::TcpStream`].
 pub trait Connection: AsyncRead + AsyncWrite {
+    /// Returns the address of the client, if known. The address is available
+    /// to requests via `Request::remote()`.
     fn remote_addr(&self) -> Option<SocketAddr>;
+
+    /// Metadata negotiated during the TLS handshake, if this connection is
+    /// secured by TLS. Connections secured by a custom TLS stack should
+    /// return one built with [`TlsInfo::new()`].
+    fn tls_info(&self) -> Option<TlsInfo> {
+        None
+    }
+}
+
+/// Metadata negotiated during the TLS handshake of a connection.
+///
+/// This information is available for every request received over a TLS
+/// connection via [`Request::tls_info()`] or by using `&TlsInfo` as a request
+/// guard.
+///
+/// [`Request::tls_info()`]: ../rocket/struct.Request.html#method.tls_info
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TlsInfo {
+    pub(crate) peer_certs: Vec<Vec<u8>>,
+    pub(crate) sni_hostname: Option<String>,
+    pub(crate) alpn_protocol: Option<Vec<u8>>,
+}
+
+impl TlsInfo {
+    /// Creates a `TlsInfo` from the client's DER-encoded certificate chain,
+    /// client certificate first, the SNI hostname it requested, and the
+    /// protocol negotiated via ALPN.
+    pub fn new(
+        peer_certificates: Vec<Vec<u8>>,
+        sni_hostname: Option<String>,
+        alpn_protocol: Option<Vec<u8>>,
+    ) -> TlsInfo {
+        TlsInfo { peer_certs: peer_certificates, sni_hostname, alpn_protocol }
+    }
+
+    /// The DER-encoded X.509 certificate chain presented by the client, if
+    /// any, with the client's own certificate first. Empty when the client did
+    /// not present a certificate.
+    pub fn peer_certificates(&self) -> &[Vec<u8>] {
+        &self.peer_certs
+    }
+
+    /// The server name requested by the client via SNI, if any.
+    pub fn sni_hostname(&self) -> Option<&str> {
+        self.sni_hostname.as_deref()
+    }
+
+    /// The application protocol negotiated via ALPN, if any.
+    pub fn alpn_protocol(&self) -> Option<&[u8]> {
+        self.alpn_protocol.as_deref()
+    }
 }
 
 /// This is a generic version of hyper's AddrIncoming that is intended to be
@@ -41,6 +137,8 @@ pub struct Incoming<L> {
     listener: L,
     sleep_on_errors: Option<Duration>,
     pending_error_delay: Option<Delay>,
+    read_timeout: Option<Duration>,
+    write_timeout: Option<Duration>,
 }
 
 impl<L: Listener> Incoming<L> {
@@ -50,9 +148,20 @@ impl<L: Listener> Incoming<L> {
             listener,
             sleep_on_errors: Some(Duration::from_secs(1)),
             pending_error_delay: None,
+            read_timeout: None,
+            write_timeout: None,
         }
     }
 
+    /// Set the read and write timeouts of accepted connections. See
+    /// [`Timed`] for their semantics. `None` disables a timeout.
+    ///
+    /// Default is `None` for both.
+    pub fn set_timeouts(&mut self, read: Option<Duration>, write: Option<Duration>) {
+        self.read_timeout = read;
+        self.write_timeout = write;
+    }
+
     /// Set whether to sleep on accept errors.
     ///
     /// A possible scenario is that the process has hit the max open files
@@ -122,14 +231,171 @@ impl<L: Listener> Incoming<L> {
 }
 
 impl<L: Listener + Unpin> Accept for Incoming<L> {
-    type Conn = L::Connection;
+    type Conn = Timed<L::Connection>;
     type Error = io::Error;
 
     fn poll_accept(
         mut self: Pin<&mut Self>,
         cx: &mut Context<'_>
     ) -> Poll<Option<io::Result<Self::Conn>>> {
-        self.poll_next(cx).map(Some)
+        let (read, write) = (self.read_timeout, self.write_timeout);
+        self.poll_next(cx).map(|result| Some(result.map(|conn| Timed::new(conn, read, write))))
+    }
+}
+
+/// A connection whose reads and writes fail with `TimedOut` errors when they
+/// don't make progress in time.
+///
+/// The read timeout bounds the time a connection may sit idle, waiting for a
+/// request, plus the time to receive the request's head. It is suspended
+/// while the connection is [`Busy`] handling a request, so slow handlers and
+/// long responses aren't affected, and restarted whenever data is written. A
+/// request is busy until its response body has been written in its entirety,
+/// so a streaming response that pauses between chunks isn't timed out.
+/// Reading slowly does _not_ restart it, so clients can't hold connections
+/// open by trickling in a request byte by byte.
+///
+/// The write timeout bounds the time a single write or flush may wait for
+/// the client to accept data.
+pub struct Timed<C> {
+    conn: C,
+    read_timeout: Option<Duration>,
+    write_timeout: Option<Duration>,
+    read_deadline: Option<Delay>,
+    write_deadline: Option<Delay>,
+    busy: Arc<AtomicUsize>,
+}
+
+/// A handle to the activity of a [`Timed`] connection, used to mark it busy.
+#[derive(Clone)]
+pub struct Activity(Arc<AtomicUsize>);
+
+/// Marks a [`Timed`] connection as busy handling a request until dropped.
+pub struct Busy(Arc<AtomicUsize>);
+
+impl Activity {
+    /// Marks the connection as busy until the returned guard is dropped.
+    pub fn busy(&self) -> Busy {
+        self.0.fetch_add(1, Ordering::AcqRel);
+        Busy(self.0.clone())
+    }
+}
+
+impl Drop for Busy {
+    fn drop(&mut self) {
+        self.0.fetch_sub(1, Ordering::AcqRel);
+    }
+}
+
+impl<C> Timed<C> {
+    fn new(conn: C, read_timeout: Option<Duration>, write_timeout: Option<Duration>) -> Self {
+        Timed {
+            conn,
+            read_timeout,
+            write_timeout,
+            read_deadline: None,
+            write_deadline: None,
+            busy: Arc::new(AtomicUsize::new(0)),
+        }
+    }
+
+    /// Returns a handle to mark `self` busy with.
+    pub fn activity(&self) -> Activity {
+        Activity(self.busy.clone())
+    }
+}
+
+/// Polls the deadline in `deadline`, first arming it to expire in `timeout`
+/// if it isn't armed. Returns `true` if there is a deadline and it expired.
+fn expired(deadline: &mut Option<Delay>, timeout: Option<Duration>, cx: &mut Context<'_>) -> bool {
+    let timeout = match timeout {
+        Some(timeout) => timeout,
+        None => return false,
+    };
+
+    let delay = deadline.get_or_insert_with(|| tokio::time::delay_for(timeout));
+    if Pin::new(delay).poll(cx).is_ready() {
+        *deadline = None;
+        return true;
+    }
+
+    false
+}
+
+fn timed_out<T>(op: &str) -> Poll<io::Result<T>> {
+    Poll::Ready(Err(io::Error::new(io::ErrorKind::TimedOut, format!("{} timed out", op))))
+}
+
+impl<C: AsyncRead + Unpin> AsyncRead for Timed<C> {
+    fn poll_read(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut [u8]
+    ) -> Poll<io::Result<usize>> {
+        let this = self.get_mut();
+        if this.busy.load(Ordering::Acquire) > 0 {
+            this.read_deadline = None;
+            return Pin::new(&mut this.conn).poll_read(cx, buf);
+        }
+
+        match Pin::new(&mut this.conn).poll_read(cx, buf) {
+            Poll::Pending if expired(&mut this.read_deadline, this.read_timeout, cx) => {
+                debug!("connection read timed out");
+                timed_out("read")
+            }
+            poll => poll,
+        }
+    }
+}
+
+impl<C: AsyncWrite + Unpin> AsyncWrite for Timed<C> {
+    fn poll_write(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &[u8]
+    ) -> Poll<io::Result<usize>> {
+        let this = self.get_mut();
+        match Pin::new(&mut this.conn).poll_write(cx, buf) {
+            Poll::Pending if expired(&mut this.write_deadline, this.write_timeout, cx) => {
+                debug!("connection write timed out");
+                timed_out("write")
+            }
+            Poll::Pending => Poll::Pending,
+            poll => {
+                this.write_deadline = None;
+                this.read_deadline = None;
+                poll
+            }
+        }
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        let this = self.get_mut();
+        match Pin::new(&mut this.conn).poll_flush(cx) {
+            Poll::Pending if expired(&mut this.write_deadline, this.write_timeout, cx) => {
+                debug!("connection flush timed out");
+                timed_out("flush")
+            }
+            Poll::Pending => Poll::Pending,
+            poll => {
+                this.write_deadline = None;
+                poll
+            }
+        }
+    }
+
+    fn poll_shutdown(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.get_mut().conn).poll_shutdown(cx)
+    }
+}
+
+impl<C: Connection + Unpin> Connection for Timed<C> {
+    fn remote_addr(&self) -> Option<SocketAddr> {
+        self.conn.remote_addr()
+    }
+
+    fn tls_info(&self) -> Option<TlsInfo> {
+        self.conn.tls_info()
     }
 }
 
@@ -161,6 +427,13 @@ pub async fn bind_tcp(address: SocketAddr) -> io::Result<TcpListener> {
     Ok(TcpListener::bind(address).await?)
 }
 
+/// Converts an already bound standard library listener, such as one inherited
+/// from a parent process, into a `TcpListener`.
+pub fn from_std_tcp(listener: std::net::TcpListener) -> io::Result<TcpListener> {
+    listener.set_nonblocking(true)?;
+    TcpListener::from_std(listener)
+}
+
 impl Listener for TcpListener {
     type Connection = TcpStream;
 
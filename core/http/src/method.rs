@@ -3,10 +3,23 @@ use std::str::FromStr;
 
 use self::Method::*;
 
-// TODO: Support non-standard methods, here and in codegen.
-
 /// Representation of HTTP methods.
+///
+/// Besides the standard methods, extension methods such as `PURGE` or
+/// WebDAV's `PROPFIND` are represented by [`Method::Custom`] and can be routed
+/// like any other method:
+///
+/// ```rust
+/// # #[macro_use] extern crate rocket;
+/// #[route(PURGE, path = "/cache/<key>")]
+/// fn purge(key: String) { /* .. */ }
+/// # fn main() { }
+/// ```
+///
+/// An incoming request with an extension method is only accepted if a route
+/// for the method is mounted.
 #[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+#[non_exhaustive]
 pub enum Method {
     Get,
     Put,
@@ -16,7 +29,10 @@ pub enum Method {
     Head,
     Trace,
     Connect,
-    Patch
+    Patch,
+    /// An extension method. The name is case-sensitive and, by convention,
+    /// uppercase, as in `Method::Custom("PURGE")`.
+    Custom(&'static str),
 }
 
 impl Method {
@@ -48,6 +64,8 @@ impl Method {
     ///
     ///   * `GET`, `HEAD`, `CONNECT`, `TRACE`, `OPTIONS`
     ///
+    /// Extension methods are assumed to support payloads.
+    ///
     /// # Example
     ///
     /// ```rust
@@ -60,7 +78,7 @@ impl Method {
     #[inline]
     pub fn supports_payload(self) -> bool {
         match self {
-            Put | Post | Delete | Patch => true,
+            Put | Post | Delete | Patch | Custom(_) => true,
             Get | Head | Connect | Trace | Options => false,
         }
     }
@@ -87,6 +105,7 @@ impl Method {
             Trace => "TRACE",
             Connect => "CONNECT",
             Patch => "PATCH",
+            Custom(name) => name,
         }
     }
 }
@@ -96,6 +115,7 @@ impl FromStr for Method {
 
     // According to the RFC, method names are case-sensitive. But some old
     // clients don't follow this, so we just do a case-insensitive match here.
+    // Extension methods aren't parsed: they can't be `'static`.
     fn from_str(s: &str) -> Result<Method, ()> {
         match s {
             x if uncased::eq(x, Get.as_str()) => Ok(Get),
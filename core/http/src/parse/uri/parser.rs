@@ -39,7 +39,29 @@ pub fn origin<'a>(input: &mut RawInput<'a>) -> Result<'a, Origin<'a>> {
 pub fn rocket_route_origin<'a>(input: &mut RawInput<'a>) -> Result<'a, Origin<'a>> {
     fn is_pchar_or_rchar(c: &u8) -> bool { is_pchar(c) || is_rchar(c) }
     fn is_qchar_or_rchar(c: &u8) -> bool { is_qchar(c) || is_rchar(c) }
-    (peek(b'/')?, path_and_query(is_pchar_or_rchar, is_qchar_or_rchar)?).1
+    let origin = (peek(b'/')?, path_and_query(is_pchar_or_rchar, is_qchar_or_rchar)?).1;
+    if !only_in_constraints(origin.path()) || !origin.query().map_or(true, only_in_constraints) {
+        parse_error!("'[', ']', '{{', and '}}' are only allowed in parameter constraints")?;
+    }
+
+    origin
+}
+
+/// Returns `true` if the route characters `[`, `]`, `{`, and `}` in `string`
+/// only appear in parameter constraints, as in `<slug:[a-z]{2,}>`.
+fn only_in_constraints(string: &str) -> bool {
+    let (mut in_param, mut in_constraint) = (false, false);
+    for c in string.bytes() {
+        match c {
+            b'<' => in_param = true,
+            b'>' => { in_param = false; in_constraint = false; }
+            b':' if in_param => in_constraint = true,
+            b'[' | b']' | b'{' | b'}' if !in_constraint => return false,
+            _ => { }
+        }
+    }
+
+    true
 }
 
 #[parser]
@@ -41,7 +41,11 @@ pub const PATH_CHARS: [u8; 256] = char_table(&[
 ]);
 
 const ROUTE_CHARS: [u8; 256] = char_table(&[&[
-    b'<', b'>'
+    b'<', b'>',
+
+    // Only valid in parameter constraints, such as `<slug:[a-z]{2,}>`. The
+    // parser rejects them anywhere else.
+    b'[', b']', b'{', b'}',
 ]]);
 
 const QUERY_CHARS: [u8; 256] = char_table(&[
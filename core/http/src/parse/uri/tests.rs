@@ -259,3 +259,14 @@ fn display() {
         "abc", "@):0", "[a]"
     }
 }
+
+#[test]
+fn route_constraint_chars() {
+    assert!(Origin::parse_route("/<slug:[a-z]{2,}>").is_ok());
+    assert!(Origin::parse_route("/a/<id:[0-9]+>/b?<q:[a-z]{0,1}>").is_ok());
+    assert!(Origin::parse_route("/a[b]").is_err());
+    assert!(Origin::parse_route("/<a>/{b}").is_err());
+    assert!(Origin::parse_route("/<[a]:int>").is_err());
+    assert!(Origin::parse_route("/<a:int>[b]").is_err());
+    assert!(Origin::parse_route("/a?b={c}").is_err());
+}
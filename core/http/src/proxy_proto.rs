@@ -0,0 +1,348 @@
+use std::future::Future;
+use std::io;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr};
+use std::pin::Pin;
+use std::task::{Context, Poll};
+use std::time::Duration;
+
+use log::debug;
+
+use tokio::time::Delay;
+use tokio::io::{AsyncRead, AsyncWrite};
+
+use crate::listener::{Connection, Listener, TlsInfo};
+
+/// The signature beginning a version 1 (text) header.
+const V1_SIGNATURE: &[u8] = b"PROXY ";
+
+/// The signature beginning a version 2 (binary) header.
+const V2_SIGNATURE: &[u8] = b"\r\n\r\n\0\r\nQUIT\n";
+
+/// The maximum length of a version 1 header, including the `\r\n`.
+const V1_MAX_LEN: usize = 107;
+
+/// The time a connection has to send its header before it's dropped.
+const HEADER_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// The maximum number of connections whose headers are read concurrently.
+/// While this many are pending, no further connections are accepted.
+const MAX_PENDING: usize = 1024;
+
+/// A listener that reads a PROXY protocol header, version 1 or 2, from each
+/// connection accepted by `L` before handing the connection over. The client
+/// address in the header becomes the connection's remote address.
+///
+/// The PROXY protocol is spoken by load balancers such as HAProxy and AWS
+/// Network Load Balancers to forward the client's address to servers without
+/// modifying the forwarded stream. When enabled, connections without a valid
+/// header are dropped, since anyone could otherwise spoof their address by
+/// sending one. Headers for `LOCAL` connections, such as a proxy's health
+/// checks, and for unknown or non-IP address families are accepted but leave
+/// the remote address as reported by `L`.
+///
+/// Headers are read from all pending connections concurrently, so a
+/// connection that is slow to send its header only delays itself.
+///
+/// When disabled, connections are passed through as accepted.
+pub struct ProxyProtoListener<L: Listener> {
+    listener: L,
+    enabled: bool,
+    pending: Vec<Pending<L::Connection>>,
+}
+
+/// A connection whose header is being read.
+struct Pending<C> {
+    conn: C,
+    buffer: Vec<u8>,
+    deadline: Delay,
+}
+
+/// A connection accepted by a [`ProxyProtoListener`].
+pub struct ProxyStream<C> {
+    conn: C,
+    remote: Option<SocketAddr>,
+    /// Data read after the header, which precedes any further data from `conn`.
+    leftover: Vec<u8>,
+    position: usize,
+}
+
+impl<L: Listener> ProxyProtoListener<L> {
+    /// Wraps `listener`, reading PROXY protocol headers only if `enabled`.
+    pub fn new(listener: L, enabled: bool) -> Self {
+        ProxyProtoListener { listener, enabled, pending: vec![] }
+    }
+}
+
+impl<C: AsyncRead + Unpin> Pending<C> {
+    /// Reads into the buffer until it contains a complete header, returning
+    /// the address in the header and the header's length.
+    fn poll_header(
+        &mut self,
+        cx: &mut Context<'_>
+    ) -> Poll<io::Result<(Option<SocketAddr>, usize)>> {
+        let mut chunk = [0u8; 512];
+        loop {
+            match parse(&self.buffer) {
+                Ok(Some(header)) => return Poll::Ready(Ok(header)),
+                Ok(None) => {},
+                Err(e) => return Poll::Ready(Err(io::Error::new(io::ErrorKind::InvalidData, e))),
+            }
+
+            match Pin::new(&mut self.conn).poll_read(cx, &mut chunk) {
+                Poll::Ready(Ok(0)) => {
+                    let kind = io::ErrorKind::UnexpectedEof;
+                    return Poll::Ready(Err(io::Error::new(kind, "closed before header")));
+                }
+                Poll::Ready(Ok(n)) => self.buffer.extend_from_slice(&chunk[..n]),
+                Poll::Ready(Err(e)) => return Poll::Ready(Err(e)),
+                Poll::Pending => {
+                    if Pin::new(&mut self.deadline).poll(cx).is_ready() {
+                        let kind = io::ErrorKind::TimedOut;
+                        return Poll::Ready(Err(io::Error::new(kind, "header timed out")));
+                    }
+
+                    return Poll::Pending;
+                }
+            }
+        }
+    }
+}
+
+impl<L: Listener> Listener for ProxyProtoListener<L> where L::Connection: Unpin {
+    type Connection = ProxyStream<L::Connection>;
+
+    fn local_addr(&self) -> Option<SocketAddr> {
+        self.listener.local_addr()
+    }
+
+    fn poll_accept(&mut self, cx: &mut Context<'_>) -> Poll<io::Result<Self::Connection>> {
+        if !self.enabled {
+            return self.listener.poll_accept(cx)
+                .map(|result| result.map(|conn| ProxyStream::new(conn, None, vec![])));
+        }
+
+        // Accept every connection that's ready, adding each to those pending.
+        while self.pending.len() < MAX_PENDING {
+            match self.listener.poll_accept(cx) {
+                Poll::Ready(Ok(conn)) => {
+                    let deadline = tokio::time::delay_for(HEADER_TIMEOUT);
+                    self.pending.push(Pending { conn, buffer: vec![], deadline });
+                }
+                Poll::Ready(Err(e)) => return Poll::Ready(Err(e)),
+                Poll::Pending => break,
+            }
+        }
+
+        // Hand over the first connection with a complete header. Every pending
+        // connection is polled, so each wakes this task when it makes progress.
+        let mut i = 0;
+        while i < self.pending.len() {
+            match self.pending[i].poll_header(cx) {
+                Poll::Pending => i += 1,
+                Poll::Ready(Ok((remote, len))) => {
+                    let Pending { conn, mut buffer, .. } = self.pending.swap_remove(i);
+                    buffer.drain(..len);
+                    return Poll::Ready(Ok(ProxyStream::new(conn, remote, buffer)));
+                }
+                Poll::Ready(Err(e)) => {
+                    // Like a failed TLS handshake, a bad header only affects
+                    // this connection. Drop it and keep listening for others.
+                    debug!("PROXY protocol header rejected: {}", e);
+                    self.pending.swap_remove(i);
+                }
+            }
+        }
+
+        Poll::Pending
+    }
+}
+
+impl<C> ProxyStream<C> {
+    fn new(conn: C, remote: Option<SocketAddr>, leftover: Vec<u8>) -> Self {
+        ProxyStream { conn, remote, leftover, position: 0 }
+    }
+}
+
+impl<C: AsyncRead + Unpin> AsyncRead for ProxyStream<C> {
+    fn poll_read(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut [u8]
+    ) -> Poll<io::Result<usize>> {
+        let this = self.get_mut();
+        let leftover = &this.leftover[this.position..];
+        if !leftover.is_empty() {
+            let n = std::cmp::min(leftover.len(), buf.len());
+            buf[..n].copy_from_slice(&leftover[..n]);
+            this.position += n;
+            return Poll::Ready(Ok(n));
+        }
+
+        Pin::new(&mut this.conn).poll_read(cx, buf)
+    }
+}
+
+impl<C: AsyncWrite + Unpin> AsyncWrite for ProxyStream<C> {
+    fn poll_write(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &[u8]
+    ) -> Poll<io::Result<usize>> {
+        Pin::new(&mut self.get_mut().conn).poll_write(cx, buf)
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.get_mut().conn).poll_flush(cx)
+    }
+
+    fn poll_shutdown(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.get_mut().conn).poll_shutdown(cx)
+    }
+}
+
+impl<C: Connection + Unpin> Connection for ProxyStream<C> {
+    fn remote_addr(&self) -> Option<SocketAddr> {
+        self.remote.or_else(|| self.conn.remote_addr())
+    }
+
+    fn tls_info(&self) -> Option<TlsInfo> {
+        self.conn.tls_info()
+    }
+}
+
+/// Parses the PROXY protocol header at the start of `buf`. Returns `Ok(None)`
+/// if `buf` is a valid but incomplete prefix of a header. Otherwise returns
+/// the source address in the header, if any, and the length of the header.
+fn parse(buf: &[u8]) -> Result<Option<(Option<SocketAddr>, usize)>, &'static str> {
+    let is_prefix = |signature: &[u8]| {
+        let len = std::cmp::min(buf.len(), signature.len());
+        buf[..len] == signature[..len]
+    };
+
+    if is_prefix(V1_SIGNATURE) {
+        parse_v1(buf)
+    } else if is_prefix(V2_SIGNATURE) {
+        parse_v2(buf)
+    } else {
+        Err("missing PROXY protocol signature")
+    }
+}
+
+/// Parses a version 1 header: `PROXY TCP4 1.2.3.4 5.6.7.8 1234 80\r\n`.
+fn parse_v1(buf: &[u8]) -> Result<Option<(Option<SocketAddr>, usize)>, &'static str> {
+    let end = match buf.windows(2).position(|w| w == b"\r\n") {
+        Some(end) => end,
+        None if buf.len() >= V1_MAX_LEN => return Err("v1 header is too long"),
+        None => return Ok(None),
+    };
+
+    let line = std::str::from_utf8(&buf[..end]).map_err(|_| "v1 header isn't UTF-8")?;
+    let fields: Vec<&str> = line.split(' ').collect();
+    let remote = match fields[..] {
+        ["PROXY", "UNKNOWN", ..] => None,
+        ["PROXY", family, source, _, port, _] => {
+            let ip: IpAddr = source.parse().map_err(|_| "invalid v1 source address")?;
+            let port: u16 = port.parse().map_err(|_| "invalid v1 source port")?;
+            match (family, ip) {
+                ("TCP4", IpAddr::V4(_)) | ("TCP6", IpAddr::V6(_)) => Some((ip, port).into()),
+                _ => return Err("invalid v1 address family"),
+            }
+        }
+        _ => return Err("malformed v1 header"),
+    };
+
+    Ok(Some((remote, end + 2)))
+}
+
+/// Parses a version 2 header: the signature, version and command, address
+/// family and protocol, address length, and addresses, followed by TLVs.
+fn parse_v2(buf: &[u8]) -> Result<Option<(Option<SocketAddr>, usize)>, &'static str> {
+    if buf.len() < 16 {
+        return Ok(None);
+    }
+
+    let (version_command, family) = (buf[12], buf[13]);
+    let len = 16 + u16::from_be_bytes([buf[14], buf[15]]) as usize;
+    if version_command >> 4 != 2 {
+        return Err("unsupported PROXY protocol version");
+    } else if buf.len() < len {
+        return Ok(None);
+    }
+
+    let addresses = &buf[16..len];
+    let port = |at: usize| u16::from_be_bytes([addresses[at], addresses[at + 1]]);
+    let remote = match (version_command & 0xF, family >> 4) {
+        // A `LOCAL` connection from the proxy itself.
+        (0x0, _) => None,
+        (0x1, 0x1) if addresses.len() >= 12 => {
+            let mut ip = [0u8; 4];
+            ip.copy_from_slice(&addresses[..4]);
+            Some(SocketAddr::new(Ipv4Addr::from(ip).into(), port(8)))
+        }
+        (0x1, 0x2) if addresses.len() >= 36 => {
+            let mut ip = [0u8; 16];
+            ip.copy_from_slice(&addresses[..16]);
+            Some(SocketAddr::new(Ipv6Addr::from(ip).into(), port(32)))
+        }
+        (0x1, 0x1) | (0x1, 0x2) => return Err("v2 addresses are truncated"),
+        // `AF_UNSPEC`, `AF_UNIX`, or an unknown family.
+        (0x1, _) => None,
+        _ => return Err("unsupported v2 command"),
+    };
+
+    Ok(Some((remote, len)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_v1() {
+        let header = b"PROXY TCP4 192.0.2.1 198.51.100.1 56324 443\r\nGET /";
+        let remote = "192.0.2.1:56324".parse().ok();
+        assert_eq!(parse(header), Ok(Some((remote, 45))));
+
+        let header = b"PROXY TCP6 2001:db8::1 2001:db8::2 4711 80\r\n";
+        let remote = "[2001:db8::1]:4711".parse().ok();
+        assert_eq!(parse(header), Ok(Some((remote, header.len()))));
+
+        assert_eq!(parse(b"PROXY UNKNOWN\r\n"), Ok(Some((None, 15))));
+        assert_eq!(parse(b"PRO"), Ok(None));
+        assert_eq!(parse(b"PROXY TCP4 192.0.2.1"), Ok(None));
+        assert!(parse(b"PROXY TCP4 2001:db8::1 2001:db8::2 4711 80\r\n").is_err());
+        assert!(parse(b"PROXY TCP4 192.0.2.1 198.51.100.1 56324\r\n").is_err());
+        assert!(parse(b"GET / HTTP/1.1\r\n").is_err());
+        assert!(parse(&[b'P'; 200]).is_err());
+        assert!(parse(&[b"PROXY "[..].to_vec(), vec![b'A'; 200]].concat()).is_err());
+    }
+
+    #[test]
+    fn test_parse_v2() {
+        let mut header = V2_SIGNATURE.to_vec();
+        header.extend_from_slice(&[0x21, 0x11, 0, 15]);
+        header.extend_from_slice(&[192, 0, 2, 1, 198, 51, 100, 1, 0xDC, 0x04, 0x01, 0xBB]);
+        header.extend_from_slice(&[0x04, 0, 0]);
+
+        assert_eq!(parse(&header[..10]), Ok(None));
+        assert_eq!(parse(&header[..20]), Ok(None));
+
+        let remote = "192.0.2.1:56324".parse().ok();
+        assert_eq!(parse(&header), Ok(Some((remote, 31))));
+
+        header[12] = 0x20;
+        assert_eq!(parse(&header), Ok(Some((None, 31))));
+
+        header[12] = 0x11;
+        assert!(parse(&header).is_err());
+
+        let mut header = V2_SIGNATURE.to_vec();
+        header.extend_from_slice(&[0x21, 0x21, 0, 36]);
+        header.extend_from_slice(&Ipv6Addr::LOCALHOST.octets());
+        header.extend_from_slice(&Ipv6Addr::LOCALHOST.octets());
+        header.extend_from_slice(&[0, 80, 0, 81]);
+
+        let remote = "[::1]:80".parse().ok();
+        assert_eq!(parse(&header), Ok(Some((remote, 52))));
+    }
+}
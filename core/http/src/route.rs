@@ -3,6 +3,7 @@ use std::marker::PhantomData;
 
 use unicode_xid::UnicodeXID;
 
+use crate::RawStr;
 use crate::ext::IntoOwned;
 use crate::uri::{Origin, UriPart, Path, Query};
 use crate::uri::encoding::unsafe_percent_encode;
@@ -22,6 +23,7 @@ pub struct RouteSegment<'a, P: UriPart> {
     pub kind: Kind,
     pub name: Cow<'a, str>,
     pub index: Option<usize>,
+    pub constraint: Option<Constraint>,
     _part: PhantomData<P>,
 }
 
@@ -35,6 +37,7 @@ impl<P: UriPart + 'static> IntoOwned for RouteSegment<'_, P> {
             kind: self.kind,
             name: IntoOwned::into_owned(self.name),
             index: self.index,
+            constraint: self.constraint,
             _part: PhantomData
         }
     }
@@ -48,7 +51,9 @@ pub enum Error<'a> {
     MissingClose,
     Malformed,
     Uri,
-    Trailing(&'a str)
+    Trailing(&'a str),
+    Constraint(&'a str),
+    Unconstrainable,
 }
 
 impl std::fmt::Display for Error<'_> {
@@ -60,7 +65,9 @@ impl std::fmt::Display for Error<'_> {
             MissingClose => "parameter is missing a closing bracket".fmt(f),
             Malformed => "malformed parameter or identifier".fmt(f),
             Uri => "segment contains invalid URI characters".fmt(f),
-            Trailing(i) => write!(f, "unexpected trailing text after `{}`", i)
+            Trailing(i) => write!(f, "unexpected trailing text after `{}`", i),
+            Constraint(c) => write!(f, "`{}` is not a valid parameter constraint", c),
+            Unconstrainable => "only single-segment path parameters can be constrained".fmt(f),
         }
     }
 }
@@ -97,11 +104,25 @@ impl<'a, P: UriPart> RouteSegment<'a, P> {
         if segment.starts_with('<') && segment.ends_with('>') {
             let mut kind = Kind::Single;
             let mut name = &segment[1..(segment.len() - 1)];
+            let mut constraint = None;
+            if let Some(i) = name.find(':') {
+                constraint = Some(&name[(i + 1)..]);
+                name = &name[..i];
+            }
+
             if name.ends_with("..") {
                 kind = Kind::Multi;
                 name = &name[..(name.len() - 2)];
             }
 
+            let constraint = match constraint {
+                Some(_) if kind == Kind::Multi || P::DELIMITER != '/' => {
+                    return Err(Unconstrainable);
+                }
+                Some(c) => Some(Constraint::parse(c).ok_or(Error::Constraint(c))?),
+                None => None,
+            };
+
             if name.is_empty() {
                 return Err(Empty);
             } else if !is_valid_ident(name) {
@@ -112,7 +133,7 @@ impl<'a, P: UriPart> RouteSegment<'a, P> {
             }
 
             let name = name.into();
-            return Ok(RouteSegment { string, name, kind, index, _part: PhantomData });
+            return Ok(RouteSegment { string, name, kind, index, constraint, _part: PhantomData });
         } else if segment.is_empty() {
             return Err(Empty);
         } else if segment.starts_with('<') && segment.len() > 1
@@ -128,6 +149,7 @@ impl<'a, P: UriPart> RouteSegment<'a, P> {
             string, index,
             name: segment.into(),
             kind: Kind::Static,
+            constraint: None,
             _part: PhantomData
         })
     }
@@ -167,3 +189,324 @@ impl<'a> RouteSegment<'a, Query> {
         uri.query().map(|q| Self::parse_many(q))
     }
 }
+
+/// A constraint on the value of a dynamic path parameter, as in `<id:int>`.
+///
+/// A constraint is the name of a built-in pattern or a pattern. A pattern is a
+/// sequence of literal characters and character classes, such as `[a-z-]`,
+/// each optionally followed by a quantifier: `?`, `*`, `+`, `{n}`, `{n,}`, or
+/// `{n,m}`. Patterns are a subset of regular expressions: there are no
+/// alternatives, groups, anchors, escapes, negated classes, or `.` wildcard. A
+/// segment satisfies a constraint when the pattern matches the entire,
+/// percent-decoded segment. Because `?` begins the query of a route's URI,
+/// `{0,1}` must be used in its place in route paths. The built-in patterns are:
+///
+///   * `int`: `-?[0-9]+`
+///   * `uint`: `[0-9]+`
+///   * `alpha`: `[a-zA-Z]+`
+///   * `alnum`: `[a-zA-Z0-9]+`
+///   * `hex`: `[0-9a-fA-F]+`
+///   * `uuid`: hyphenated groups of 8, 4, 4, 4, and 12 hex digits
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Constraint {
+    atoms: Vec<Atom>,
+}
+
+/// A character class repeated between `min` and `max` (unbounded if `None`)
+/// times. A literal character is a class with a single member.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Atom {
+    ranges: Vec<(char, char)>,
+    min: usize,
+    max: Option<usize>,
+}
+
+/// A position in a pattern: the index of the current atom and the number of
+/// times it has been repeated, saturating once further repetitions are
+/// indistinguishable.
+type State = (usize, usize);
+
+impl Atom {
+    fn contains(&self, c: char) -> bool {
+        self.ranges.iter().any(|&(lo, hi)| lo <= c && c <= hi)
+    }
+
+    fn overlaps(&self, other: &Atom) -> bool {
+        self.ranges.iter().any(|&(lo, hi)| {
+            other.ranges.iter().any(|&(o_lo, o_hi)| lo <= o_hi && o_lo <= hi)
+        })
+    }
+}
+
+impl Constraint {
+    /// Parses `string`, the name of a built-in pattern or a pattern, into a
+    /// `Constraint`. Returns `None` if `string` is neither.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # extern crate rocket;
+    /// use rocket::http::route::Constraint;
+    ///
+    /// assert!(Constraint::parse("int").is_some());
+    /// assert!(Constraint::parse("[a-z-]+").is_some());
+    /// assert!(Constraint::parse("(a|b)").is_none());
+    /// ```
+    pub fn parse(string: &str) -> Option<Constraint> {
+        let pattern = match string {
+            "int" => "-?[0-9]+",
+            "uint" => "[0-9]+",
+            "alpha" => "[a-zA-Z]+",
+            "alnum" => "[a-zA-Z0-9]+",
+            "hex" => "[0-9a-fA-F]+",
+            "uuid" => concat!("[0-9a-fA-F]{8}-[0-9a-fA-F]{4}-[0-9a-fA-F]{4}-",
+                "[0-9a-fA-F]{4}-[0-9a-fA-F]{12}"),
+            pattern => pattern,
+        };
+
+        let mut atoms = vec![];
+        let mut chars = pattern.chars().peekable();
+        while let Some(c) = chars.next() {
+            let ranges = match c {
+                '[' => parse_class(&mut chars)?,
+                c if "]{}?*+.()|^$\\".contains(c) => return None,
+                c => vec![(c, c)],
+            };
+
+            let (min, max) = match chars.peek() {
+                Some('?') => { chars.next(); (0, Some(1)) }
+                Some('*') => { chars.next(); (0, None) }
+                Some('+') => { chars.next(); (1, None) }
+                Some('{') => { chars.next(); parse_repetition(&mut chars)? }
+                _ => (1, Some(1)),
+            };
+
+            atoms.push(Atom { ranges, min, max });
+        }
+
+        match atoms.is_empty() {
+            true => None,
+            false => Some(Constraint { atoms }),
+        }
+    }
+
+    /// Returns `true` if the entire `segment` matches `self`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # extern crate rocket;
+    /// use rocket::http::route::Constraint;
+    ///
+    /// let int = Constraint::parse("int").unwrap();
+    /// assert!(int.matches("-42"));
+    /// assert!(!int.matches("42a"));
+    ///
+    /// let slug = Constraint::parse("[a-z-]+").unwrap();
+    /// assert!(slug.matches("hello-world"));
+    /// assert!(!slug.matches("Hello"));
+    /// ```
+    pub fn matches(&self, segment: &str) -> bool {
+        let mut states = self.closure(vec![(0, 0)]);
+        for c in segment.chars() {
+            let next = states.iter()
+                .filter(|&&(i, _)| i < self.atoms.len() && self.atoms[i].contains(c))
+                .filter_map(|&state| self.step(state))
+                .collect();
+
+            states = self.closure(next);
+            if states.is_empty() {
+                return false;
+            }
+        }
+
+        states.contains(&(self.atoms.len(), 0))
+    }
+
+    /// Returns `true` if some non-empty string matches both `self` and `other`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # extern crate rocket;
+    /// use rocket::http::route::Constraint;
+    ///
+    /// let int = Constraint::parse("int").unwrap();
+    /// let hex = Constraint::parse("hex").unwrap();
+    /// let alpha = Constraint::parse("alpha").unwrap();
+    /// assert!(int.overlaps(&hex));
+    /// assert!(!int.overlaps(&alpha));
+    /// ```
+    pub fn overlaps(&self, other: &Constraint) -> bool {
+        let (a_end, b_end) = ((self.atoms.len(), 0), (other.atoms.len(), 0));
+        let mut seen = std::collections::HashSet::new();
+        let mut pending = vec![];
+        for a in self.closure(vec![(0, 0)]) {
+            for b in other.closure(vec![(0, 0)]) {
+                pending.push((a, b));
+            }
+        }
+
+        // Explore pairs of states reachable by consuming at least one
+        // character that both patterns accept at the same position.
+        while let Some((a, b)) = pending.pop() {
+            if a.0 == self.atoms.len() || b.0 == other.atoms.len() {
+                continue;
+            }
+
+            if !self.atoms[a.0].overlaps(&other.atoms[b.0]) {
+                continue;
+            }
+
+            let (next_a, next_b) = match (self.step(a), other.step(b)) {
+                (Some(next_a), Some(next_b)) => (next_a, next_b),
+                _ => continue,
+            };
+
+            for a in self.closure(vec![next_a]) {
+                for b in other.closure(vec![next_b]) {
+                    if a == a_end && b == b_end {
+                        return true;
+                    }
+
+                    if seen.insert((a, b)) {
+                        pending.push((a, b));
+                    }
+                }
+            }
+        }
+
+        false
+    }
+
+    /// Returns `true` if the percent-decoded `segment` matches `self`.
+    pub fn matches_raw(&self, segment: &RawStr) -> bool {
+        segment.percent_decode().map_or(false, |segment| self.matches(&segment))
+    }
+
+    /// Returns the state after repeating the current atom of `state` once
+    /// more, if it may be.
+    fn step(&self, (i, count): State) -> Option<State> {
+        let atom = &self.atoms[i];
+        match atom.max {
+            Some(max) if count >= max => None,
+            Some(_) => Some((i, count + 1)),
+            None => Some((i, std::cmp::min(count + 1, atom.min))),
+        }
+    }
+
+    /// Adds to `states` every state reachable by moving past atoms that have
+    /// been repeated often enough.
+    fn closure(&self, mut states: Vec<State>) -> Vec<State> {
+        let mut i = 0;
+        while i < states.len() {
+            let (atom, count) = states[i];
+            if atom < self.atoms.len() && count >= self.atoms[atom].min {
+                let next = (atom + 1, 0);
+                if !states.contains(&next) {
+                    states.push(next);
+                }
+            }
+
+            i += 1;
+        }
+
+        states.sort_unstable();
+        states.dedup();
+        states
+    }
+}
+
+impl std::fmt::Display for Constraint {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for atom in &self.atoms {
+            match &*atom.ranges {
+                [(lo, hi)] if lo == hi => write!(f, "{}", lo)?,
+                ranges => {
+                    write!(f, "[")?;
+                    for &(lo, hi) in ranges {
+                        match lo == hi {
+                            true => write!(f, "{}", lo)?,
+                            false => write!(f, "{}-{}", lo, hi)?,
+                        }
+                    }
+
+                    write!(f, "]")?;
+                }
+            }
+
+            match (atom.min, atom.max) {
+                (1, Some(1)) => {}
+                (0, Some(1)) => write!(f, "?")?,
+                (0, None) => write!(f, "*")?,
+                (1, None) => write!(f, "+")?,
+                (min, None) => write!(f, "{{{},}}", min)?,
+                (min, Some(max)) if min == max => write!(f, "{{{}}}", min)?,
+                (min, Some(max)) => write!(f, "{{{},{}}}", min, max)?,
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Parses the members of a character class following its opening `[`.
+fn parse_class<I>(chars: &mut std::iter::Peekable<I>) -> Option<Vec<(char, char)>>
+    where I: Iterator<Item = char> + Clone
+{
+    let mut ranges = vec![];
+    loop {
+        let lo = match chars.next()? {
+            ']' if !ranges.is_empty() => return Some(ranges),
+            '^' if ranges.is_empty() => return None,
+            '[' | ']' | '\\' => return None,
+            c => c,
+        };
+
+        // A `-` is a literal at the start or end of the class.
+        let mut ahead = chars.clone();
+        match (ahead.next(), ahead.next()) {
+            (Some('-'), Some(hi)) if hi != ']' => {
+                if hi < lo || hi == '[' || hi == '\\' {
+                    return None;
+                }
+
+                chars.next();
+                chars.next();
+                ranges.push((lo, hi));
+            }
+            _ => ranges.push((lo, lo)),
+        }
+    }
+}
+
+/// The largest repetition count accepted in a `{n,m}` quantifier.
+const MAX_REPETITION: usize = 1024;
+
+/// Parses a repetition quantifier following its opening `{`.
+fn parse_repetition<I>(chars: &mut I) -> Option<(usize, Option<usize>)>
+    where I: Iterator<Item = char>
+{
+    let mut quantifier = String::new();
+    loop {
+        match chars.next()? {
+            '}' => break,
+            c => quantifier.push(c),
+        }
+    }
+
+    let count = |s: &str| s.parse::<usize>().ok().filter(|&n| n <= MAX_REPETITION);
+    let (min, max) = match quantifier.find(',') {
+        Some(i) if i + 1 == quantifier.len() => (count(&quantifier[..i])?, None),
+        Some(i) => (count(&quantifier[..i])?, Some(count(&quantifier[(i + 1)..])?)),
+        None => {
+            let n = count(&quantifier)?;
+            (n, Some(n))
+        }
+    };
+
+    match max {
+        Some(max) if max < min => None,
+        _ => Some((min, max)),
+    }
+}
@@ -5,17 +5,24 @@ use std::pin::Pin;
 use std::sync::Arc;
 use std::task::{Context, Poll};
 
+use parking_lot::RwLock;
 use rustls::internal::pemfile;
+use rustls::sign::{self, CertifiedKey};
 use rustls::{Certificate, PrivateKey, ServerConfig};
-use tokio::net::{TcpListener, TcpStream};
+use tokio::net::TcpListener;
 use tokio_rustls::{TlsAcceptor, Accept, server::TlsStream};
 use tokio_rustls::rustls;
 
-use crate::listener::{Connection, Listener};
+use crate::listener::{Connection, Listener, TlsInfo};
 
 fn load_certs(reader: &mut dyn io::BufRead) -> io::Result<Vec<Certificate>> {
-    pemfile::certs(reader)
-        .map_err(|_| io::Error::new(io::ErrorKind::Other, "invalid certificate"))
+    use std::io::{Error, ErrorKind::Other};
+
+    match pemfile::certs(reader) {
+        Ok(certs) if !certs.is_empty() => Ok(certs),
+        Ok(_) => Err(Error::new(Other, "no valid certificates found")),
+        Err(_) => Err(Error::new(Other, "invalid certificate")),
+    }
 }
 
 fn load_private_key(reader: &mut dyn io::BufRead) -> io::Result<PrivateKey> {
@@ -41,27 +48,105 @@ fn load_private_key(reader: &mut dyn io::BufRead) -> io::Result<PrivateKey> {
         })?;
 
     // Ensure we can use the key.
-    rustls::sign::RSASigningKey::new(&key)
+    sign::any_supported_type(&key)
         .map_err(|_| Error::new(Other, "key parsed but is unusable"))
         .map(|_| key)
 }
 
-pub struct TlsListener {
-    listener: TcpListener,
+fn load_certified_key(
+    cert_chain: &mut dyn io::BufRead,
+    private_key: &mut dyn io::BufRead,
+) -> io::Result<CertifiedKey> {
+    let cert_chain = load_certs(cert_chain).map_err(|e| {
+        let msg = format!("malformed TLS certificate chain: {}", e);
+        io::Error::new(e.kind(), msg)
+    })?;
+
+    let key = load_private_key(private_key).map_err(|e| {
+        let msg = format!("malformed TLS private key: {}", e);
+        io::Error::new(e.kind(), msg)
+    })?;
+
+    let signing_key = sign::any_supported_type(&key)
+        .map_err(|_| io::Error::new(io::ErrorKind::Other, "unusable TLS private key"))?;
+
+    Ok(CertifiedKey::new(cert_chain, Arc::new(signing_key)))
+}
+
+/// A server certificate resolver whose certificate chain and private key can
+/// be replaced while the listener is accepting connections.
+///
+/// Replacement is atomic: a handshake uses either the previous or the new
+/// certificate, never a mix of both. Established connections are unaffected.
+pub struct CertResolver(RwLock<CertifiedKey>);
+
+impl CertResolver {
+    fn new(
+        cert_chain: &mut dyn io::BufRead,
+        private_key: &mut dyn io::BufRead,
+    ) -> io::Result<Self> {
+        Ok(CertResolver(RwLock::new(load_certified_key(cert_chain, private_key)?)))
+    }
+
+    /// Replaces the served certificate chain and private key with the ones
+    /// read from `cert_chain` and `private_key`. If either is invalid, an
+    /// error is returned and the current certificate remains in use.
+    pub fn set<C, K>(&self, mut cert_chain: C, mut private_key: K) -> io::Result<()>
+        where C: io::BufRead, K: io::BufRead
+    {
+        let key = load_certified_key(&mut cert_chain, &mut private_key)?;
+        *self.0.write() = key;
+        Ok(())
+    }
+}
+
+impl rustls::ResolvesServerCert for CertResolver {
+    fn resolve(&self, _: rustls::ClientHello<'_>) -> Option<CertifiedKey> {
+        Some(self.0.read().clone())
+    }
+}
+
+/// Client certificate verification: certificates presented by clients are
+/// validated against the CA certificates in `ca_certs`. When `mandatory`, the
+/// handshake fails for clients that don't present a valid certificate.
+pub struct ClientAuth<R> {
+    pub ca_certs: R,
+    pub mandatory: bool,
+}
+
+fn load_ca_certs(reader: &mut dyn io::BufRead) -> io::Result<rustls::RootCertStore> {
+    let mut roots = rustls::RootCertStore::empty();
+    match roots.add_pem_file(reader) {
+        Ok((valid, _)) if valid > 0 => Ok(roots),
+        Ok(_) => Err(io::Error::new(io::ErrorKind::Other, "no valid CA certificates found")),
+        Err(_) => Err(io::Error::new(io::ErrorKind::Other, "invalid CA certificate")),
+    }
+}
+
+pub struct TlsListener<L: Listener = TcpListener> {
+    listener: L,
     acceptor: TlsAcceptor,
-    state: TlsListenerState,
+    resolver: Arc<CertResolver>,
+    state: TlsListenerState<L::Connection>,
+}
+
+impl<L: Listener> TlsListener<L> {
+    /// Returns the resolver for the certificate served by this listener.
+    pub fn resolver(&self) -> &Arc<CertResolver> {
+        &self.resolver
+    }
 }
 
-enum TlsListenerState {
+enum TlsListenerState<C> {
     Listening,
-    Accepting(Accept<TcpStream>),
+    Accepting(Accept<C>),
 }
 
-impl Listener for TlsListener {
-    type Connection = TlsStream<TcpStream>;
+impl<L: Listener> Listener for TlsListener<L> where L::Connection: Unpin {
+    type Connection = TlsStream<L::Connection>;
 
     fn local_addr(&self) -> Option<SocketAddr> {
-        self.listener.local_addr().ok()
+        self.listener.local_addr()
     }
 
     fn poll_accept(&mut self, cx: &mut Context<'_>) -> Poll<io::Result<Self::Connection>> {
@@ -71,7 +156,7 @@ impl Listener for TlsListener {
                     match self.listener.poll_accept(cx) {
                         Poll::Pending => return Poll::Pending,
                         Poll::Ready(Err(e)) => return Poll::Ready(Err(e)),
-                        Poll::Ready(Ok((stream, _addr))) => {
+                        Poll::Ready(Ok(stream)) => {
                             let fut = self.acceptor.accept(stream);
                             self.state = TlsListenerState::Accepting(fut);
                         }
@@ -80,9 +165,16 @@ impl Listener for TlsListener {
                 TlsListenerState::Accepting(ref mut fut) => {
                     match Pin::new(fut).poll(cx) {
                         Poll::Pending => return Poll::Pending,
-                        Poll::Ready(result) => {
+                        Poll::Ready(Ok(stream)) => {
+                            self.state = TlsListenerState::Listening;
+                            return Poll::Ready(Ok(stream));
+                        }
+                        Poll::Ready(Err(e)) => {
+                            // A failed handshake, including one rejected due
+                            // to client authentication, only affects this
+                            // connection. Keep listening for others.
+                            log::debug!("TLS handshake failed: {}", e);
                             self.state = TlsListenerState::Listening;
-                            return Poll::Ready(result);
                         }
                     }
                 }
@@ -91,38 +183,110 @@ impl Listener for TlsListener {
     }
 }
 
-pub async fn bind_tls<C: io::BufRead + Send, K: io::BufRead + Send>(
+pub async fn bind_tls<C, K, R>(
     address: SocketAddr,
+    cert_chain: C,
+    private_key: K,
+    client_auth: Option<ClientAuth<R>>,
+) -> io::Result<TlsListener>
+    where C: io::BufRead + Send, K: io::BufRead + Send, R: io::BufRead + Send
+{
+    let listener = TcpListener::bind(address).await?;
+    tls_from_listener(listener, cert_chain, private_key, client_auth)
+}
+
+/// Wraps an already bound `listener` in TLS using the given certificate chain
+/// and private key, optionally verifying client certificates.
+pub fn tls_from_listener<L, C, K, R>(
+    listener: L,
     mut cert_chain: C,
     mut private_key: K,
-) -> io::Result<TlsListener> {
-    let cert_chain = load_certs(&mut cert_chain).map_err(|e| {
-        let msg = format!("malformed TLS certificate chain: {}", e);
-        io::Error::new(e.kind(), msg)
-    })?;
+    client_auth: Option<ClientAuth<R>>,
+) -> io::Result<TlsListener<L>>
+    where L: Listener,
+          C: io::BufRead + Send, K: io::BufRead + Send, R: io::BufRead + Send
+{
+    let resolver = Arc::new(CertResolver::new(&mut cert_chain, &mut private_key)?);
 
-    let key = load_private_key(&mut private_key).map_err(|e| {
-        let msg = format!("malformed TLS private key: {}", e);
-        io::Error::new(e.kind(), msg)
-    })?;
+    let client_auth = match client_auth {
+        Some(ClientAuth { mut ca_certs, mandatory }) => {
+            let roots = load_ca_certs(&mut ca_certs).map_err(|e| {
+                let msg = format!("malformed CA certificates: {}", e);
+                io::Error::new(e.kind(), msg)
+            })?;
 
-    let listener = TcpListener::bind(address).await?;
+            match mandatory {
+                true => rustls::AllowAnyAuthenticatedClient::new(roots),
+                false => rustls::AllowAnyAnonymousOrAuthenticatedClient::new(roots),
+            }
+        }
+        None => rustls::NoClientAuth::new(),
+    };
 
-    let client_auth = rustls::NoClientAuth::new();
     let mut tls_config = ServerConfig::new(client_auth);
     let cache = rustls::ServerSessionMemoryCache::new(1024);
     tls_config.set_persistence(cache);
     tls_config.ticketer = rustls::Ticketer::new();
-    tls_config.set_single_cert(cert_chain, key).expect("invalid key");
+    tls_config.cert_resolver = resolver.clone();
+    tls_config.set_protocols(&[b"h2".to_vec(), b"http/1.1".to_vec()]);
 
     let acceptor = TlsAcceptor::from(Arc::new(tls_config));
     let state = TlsListenerState::Listening;
 
-    Ok(TlsListener { listener, acceptor, state })
+    Ok(TlsListener { listener, acceptor, resolver, state })
 }
 
-impl Connection for TlsStream<TcpStream> {
+impl<C: Connection + Unpin> Connection for TlsStream<C> {
     fn remote_addr(&self) -> Option<SocketAddr> {
         self.get_ref().0.remote_addr()
     }
+
+    fn tls_info(&self) -> Option<TlsInfo> {
+        use rustls::Session;
+
+        let session = self.get_ref().1;
+        let peer_certs = session.get_peer_certificates()
+            .map(|certs| certs.into_iter().map(|cert| cert.0).collect())
+            .unwrap_or_default();
+
+        Some(TlsInfo {
+            peer_certs,
+            sni_hostname: session.get_sni_hostname().map(|s| s.to_string()),
+            alpn_protocol: session.get_alpn_protocol().map(|p| p.to_vec()),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CERT: &[u8] = include_bytes!("../../../examples/tls/private/cert.pem");
+    const KEY: &[u8] = include_bytes!("../../../examples/tls/private/key.pem");
+    const CA_CERT: &[u8] = include_bytes!("../../../examples/tls/private/ca_cert.pem");
+    const CA_KEY: &[u8] = include_bytes!("../../../examples/tls/private/ca_key.pem");
+
+    fn served(resolver: &CertResolver) -> Vec<Certificate> {
+        resolver.0.read().cert.clone()
+    }
+
+    #[test]
+    fn test_cert_resolver_set() {
+        let resolver = CertResolver::new(&mut &*CERT, &mut &*KEY).unwrap();
+        let original = served(&resolver);
+        assert_eq!(original, load_certs(&mut &*CERT).unwrap());
+
+        // Malformed input is rejected and the current certificate is kept.
+        assert!(resolver.set(&b"garbage"[..], KEY).is_err());
+        assert!(resolver.set(CERT, &b"garbage"[..]).is_err());
+        assert!(resolver.set(&b""[..], &b""[..]).is_err());
+        assert_eq!(served(&resolver), original);
+
+        // Valid input replaces the certificate.
+        assert!(resolver.set(CA_CERT, CA_KEY).is_ok());
+        assert_eq!(served(&resolver), load_certs(&mut &*CA_CERT).unwrap());
+
+        assert!(resolver.set(CERT, KEY).is_ok());
+        assert_eq!(served(&resolver), original);
+    }
 }
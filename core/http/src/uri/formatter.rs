@@ -3,7 +3,7 @@ use std::marker::PhantomData;
 
 use smallvec::SmallVec;
 
-use crate::uri::{UriPart, Path, Query, UriDisplay, Origin};
+use crate::uri::{UriPart, Path, Query, UriDisplay, Origin, Absolute};
 
 /// A struct used to format strings for [`UriDisplay`].
 ///
@@ -467,4 +467,14 @@ impl UriArguments<'_> {
 
         Origin::new(path, query)
     }
+
+    #[doc(hidden)]
+    pub fn into_absolute(self, base: &str) -> Absolute<'static> {
+        use crate::ext::IntoOwned;
+
+        let string = format!("{}{}", base, self.into_origin());
+        Absolute::parse(&string)
+            .map(|uri| uri.into_owned())
+            .expect("base and origin form a valid absolute URI")
+    }
 }
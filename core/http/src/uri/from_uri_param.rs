@@ -1,7 +1,7 @@
 use std::path::{Path, PathBuf};
 
 use crate::RawStr;
-use crate::uri::{self, UriPart, UriDisplay};
+use crate::uri::{self, UriPart, UriDisplay, Segments};
 
 /// Conversion trait for parameters used in [`uri!`] invocations.
 ///
@@ -91,6 +91,8 @@ use crate::uri::{self, UriPart, UriDisplay};
 ///   * `String` to `&str`
 ///   * `String` to `RawStr`
 ///   * `T` to `Form<T>`
+///   * `i8`, `i16`, `u8`, `u16` to `i32`
+///   * `f32` to `f64`
 ///
 /// The following conversions are implemented _only in [`Path`]_:
 ///
@@ -318,6 +320,16 @@ impl_conversion_ref! {
     ('a) String => &'a RawStr
 }
 
+// Lossless numeric widenings. Only the types that an unconstrained integer or
+// float literal falls back to (`i32`, `f64`) are targets: with more than one
+// impl for a wider type like `i64`, a literal argument for an `i64` parameter
+// would fall back to `i32`, and large literals would overflow.
+impl_conversion_ref! {
+    i8 => i32, i16 => i32, u8 => i32, u16 => i32,
+
+    f32 => f64
+}
+
 impl_from_uri_param_identity!([uri::Path] ('a) &'a Path);
 impl_from_uri_param_identity!([uri::Path] PathBuf);
 
@@ -346,6 +358,23 @@ impl<'a, 'b> FromUriParam<uri::Path, &'a &'b str> for PathBuf {
     }
 }
 
+impl_from_uri_param_identity!([uri::Path] ('a) Segments<'a>);
+
+impl_conversion_ref! {
+    [uri::Path] ('a, 'b) &'a Path => Segments<'b>,
+    [uri::Path] ('a) PathBuf => Segments<'a>
+}
+
+/// A no cost conversion allowing an `&str` to be used in place of `Segments`.
+impl<'a, 'b> FromUriParam<uri::Path, &'a str> for Segments<'b> {
+    type Target = &'a Path;
+
+    #[inline(always)]
+    fn from_uri_param(param: &'a str) -> &'a Path {
+        Path::new(param)
+    }
+}
+
 /// A no cost conversion allowing any `T` to be used in place of an `Option<T>`.
 impl<A, T: FromUriParam<uri::Path, A>> FromUriParam<uri::Path, A> for Option<T> {
     type Target = T::Target;
@@ -401,3 +430,36 @@ impl<A, E, T: FromUriParam<uri::Query, A>> FromUriParam<uri::Query, Result<A, E>
         param.map(|a| T::from_uri_param(a))
     }
 }
+
+/// A conversion allowing a `Vec<A>` to be used in place of a `Vec<T>` when an
+/// `A` can be used in place of a `T`.
+impl<A, T: FromUriParam<uri::Query, A>> FromUriParam<uri::Query, Vec<A>> for Vec<T> {
+    type Target = Vec<T::Target>;
+
+    #[inline(always)]
+    fn from_uri_param(param: Vec<A>) -> Self::Target {
+        param.into_iter().map(T::from_uri_param).collect()
+    }
+}
+
+/// A conversion allowing an `&[A]` to be used in place of a `Vec<T>` when an
+/// `&A` can be used in place of a `T`.
+impl<'a, A, T: FromUriParam<uri::Query, &'a A>> FromUriParam<uri::Query, &'a [A]> for Vec<T> {
+    type Target = Vec<T::Target>;
+
+    #[inline(always)]
+    fn from_uri_param(param: &'a [A]) -> Self::Target {
+        param.iter().map(T::from_uri_param).collect()
+    }
+}
+
+/// A conversion allowing an `&Vec<A>` to be used in place of a `Vec<T>` when
+/// an `&A` can be used in place of a `T`.
+impl<'a, A, T: FromUriParam<uri::Query, &'a A>> FromUriParam<uri::Query, &'a Vec<A>> for Vec<T> {
+    type Target = Vec<T::Target>;
+
+    #[inline(always)]
+    fn from_uri_param(param: &'a Vec<A>) -> Self::Target {
+        param.iter().map(T::from_uri_param).collect()
+    }
+}
@@ -1,4 +1,4 @@
-use std::path::PathBuf;
+use std::path::{Component, Path, PathBuf};
 use std::str::Utf8Error;
 
 use crate::uri::Uri;
@@ -29,6 +29,7 @@ pub struct Segments<'a>(pub &'a str);
 /// Errors which can occur when attempting to interpret a segment string as a
 /// valid path segment.
 #[derive(Debug, PartialEq, Eq, Clone)]
+#[non_exhaustive]
 pub enum SegmentError {
     /// The segment contained invalid UTF8 characters when percent decoded.
     Utf8(Utf8Error),
@@ -38,26 +39,32 @@ pub enum SegmentError {
     BadChar(char),
     /// The segment ended with the wrapped invalid character.
     BadEnd(char),
+    /// The segment was `..`, which would traverse to the parent directory.
+    Traversal,
+    /// The segment would replace or alter the root of the path, as a drive
+    /// prefix such as `C:` does on Windows.
+    Absolute,
 }
 
 impl Segments<'_> {
     /// Creates a `PathBuf` from a `Segments` iterator. The returned `PathBuf`
-    /// is percent-decoded. If a segment is equal to "..", the previous segment
-    /// (if any) is skipped.
+    /// is percent-decoded. Segments equal to `.` after decoding are skipped.
     ///
     /// For security purposes, if a segment meets any of the following
     /// conditions, an `Err` is returned indicating the condition met:
     ///
+    ///   * Decoded segment is `..`, even if encoded, as in `%2e%2e`.
     ///   * Decoded segment starts with any of: '*'
     ///   * Decoded segment ends with any of: `:`, `>`, `<`
-    ///   * Decoded segment contains any of: `/`
+    ///   * Decoded segment contains any of: `/`, NUL
     ///   * On Windows, decoded segment contains any of: `\`
+    ///   * Decoded segment is not a plain file name, as with a drive prefix.
     ///   * Percent-encoding results in invalid UTF8.
     ///
     /// Additionally, if `allow_dotfiles` is `false`, an `Err` is returned if
     /// the following condition is met:
     ///
-    ///   * Decoded segment starts with any of: `.` (except `..`)
+    ///   * Decoded segment starts with any of: `.`
     ///
     /// As a result of these conditions, a `PathBuf` derived via `FromSegments`
     /// is safe to interpolate within, or use as a suffix of, a path without
@@ -69,7 +76,7 @@ impl Segments<'_> {
                 .map_err(SegmentError::Utf8)?;
 
             if decoded == ".." {
-                buf.pop();
+                return Err(SegmentError::Traversal)
             } else if !allow_dotfiles && decoded.starts_with('.') {
                 return Err(SegmentError::BadStart('.'))
             } else if decoded.starts_with('*') {
@@ -82,8 +89,14 @@ impl Segments<'_> {
                 return Err(SegmentError::BadEnd('<'))
             } else if decoded.contains('/') {
                 return Err(SegmentError::BadChar('/'))
+            } else if decoded.contains('\0') {
+                return Err(SegmentError::BadChar('\0'))
             } else if cfg!(windows) && decoded.contains('\\') {
                 return Err(SegmentError::BadChar('\\'))
+            } else if decoded == "." {
+                continue
+            } else if !is_file_name(&decoded) {
+                return Err(SegmentError::Absolute)
             } else {
                 buf.push(&*decoded)
             }
@@ -93,6 +106,13 @@ impl Segments<'_> {
     }
 }
 
+/// Returns `true` if `segment` is parsed as a single, normal path component,
+/// and so cannot alter the root or prefix of a path it is pushed onto.
+fn is_file_name(segment: &str) -> bool {
+    let mut components = Path::new(segment).components();
+    matches!(components.next(), Some(Component::Normal(_))) && components.next().is_none()
+}
+
 impl<'a> Iterator for Segments<'a> {
     type Item = &'a str;
 
@@ -120,3 +140,44 @@ impl<'a> Iterator for Segments<'a> {
     //     self.1.unwrap_or_else(self.fold(0, |cnt, _| cnt + 1))
     // }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use super::{Segments, SegmentError};
+
+    fn path(segments: &str) -> Result<PathBuf, SegmentError> {
+        Segments(segments).into_path_buf(false)
+    }
+
+    #[test]
+    fn test_into_path_buf() {
+        assert_eq!(path("/a/b/c.txt"), Ok(PathBuf::from("a/b/c.txt")));
+        assert_eq!(path("/a/%20b/c"), Ok(PathBuf::from("a/ b/c")));
+        assert_eq!(Segments("/a/./b").into_path_buf(true), Ok(PathBuf::from("a/b")));
+        assert_eq!(Segments("/a/.b").into_path_buf(true), Ok(PathBuf::from("a/.b")));
+    }
+
+    #[test]
+    fn test_into_path_buf_rejects_traversal() {
+        assert_eq!(path("/a/../b"), Err(SegmentError::Traversal));
+        assert_eq!(path("/.."), Err(SegmentError::Traversal));
+        assert_eq!(path("/a/%2e%2e/b"), Err(SegmentError::Traversal));
+        assert_eq!(path("/a/%2E%2E"), Err(SegmentError::Traversal));
+        assert_eq!(Segments("/a/..").into_path_buf(true), Err(SegmentError::Traversal));
+        assert_eq!(path("/a/..%2f..%2fetc"), Err(SegmentError::BadStart('.')));
+        assert_eq!(path("/a/b%2f..%2f..%2fetc"), Err(SegmentError::BadChar('/')));
+        assert_eq!(path("/%2fetc/passwd"), Err(SegmentError::BadChar('/')));
+        assert_eq!(path("/a/b%00.txt"), Err(SegmentError::BadChar('\0')));
+        assert_eq!(path("/.env"), Err(SegmentError::BadStart('.')));
+        assert_eq!(path("/C:"), Err(SegmentError::BadEnd(':')));
+    }
+
+    #[test]
+    #[cfg(windows)]
+    fn test_into_path_buf_rejects_windows_prefixes() {
+        assert_eq!(path("/C:foo"), Err(SegmentError::Absolute));
+        assert_eq!(path("/a%5cb"), Err(SegmentError::BadChar('\\')));
+    }
+}
@@ -2,7 +2,7 @@ use std::{fmt, path};
 use std::borrow::Cow;
 
 use crate::RawStr;
-use crate::uri::{Uri, UriPart, Path, Query, Formatter};
+use crate::uri::{Uri, UriPart, Path, Query, Formatter, Segments};
 
 /// Trait implemented by types that can be displayed as part of a URI in
 /// [`uri!`].
@@ -130,6 +130,14 @@ use crate::uri::{Uri, UriPart, Path, Query, Formatter};
 /// Rocket implements `UriDisplay<Path>` (but not `UriDisplay<Query>`) for
 /// several built-in types.
 ///
+///   * **`PathBuf`, `&Path`**
+///
+///     Each component of the path is percent encoded and written as a segment.
+///
+///   * **[`Segments`]**
+///
+///     Each segment, which is already percent encoded, is written as is.
+///
 ///   * `T` for **`Option<T>`** _where_ **`T: UriDisplay<Path>`**
 ///
 ///     Uses the implementation of `UriDisplay` for `T::Target`.
@@ -168,6 +176,12 @@ use crate::uri::{Uri, UriPart, Path, Query, Formatter};
 ///     If the `Result` is `Ok`, uses the implementation of `UriDisplay` for
 ///     `T`. Otherwise, nothing is rendered.
 ///
+///   * **`Vec<T>`, `[T]`** _where_ **`T: UriDisplay<Query>`**
+///
+///     Each value is written as a separate query value using the
+///     implementation of `UriDisplay` for `T`. As a named query parameter
+///     `name`, `vec![1, 2]` is rendered as `name=1&name=2`.
+///
 /// [`FromUriParam`]: crate::uri::FromUriParam
 ///
 /// # Deriving
@@ -423,6 +437,34 @@ impl<T: UriDisplay<Query>, E> UriDisplay<Query> for Result<T, E> {
     }
 }
 
+/// Writes each value in the slice as a separate query value.
+impl<T: UriDisplay<Query>> UriDisplay<Query> for [T] {
+    fn fmt(&self, f: &mut Formatter<'_, Query>) -> fmt::Result {
+        self.iter().try_for_each(|value| f.write_value(value))
+    }
+}
+
+/// Defers to the `UriDisplay<Query>` implementation for `[T]`.
+impl<T: UriDisplay<Query>> UriDisplay<Query> for Vec<T> {
+    #[inline(always)]
+    fn fmt(&self, f: &mut Formatter<'_, Query>) -> fmt::Result {
+        self.as_slice().fmt(f)
+    }
+}
+
+/// Writes each segment as is: segments are already percent encoded.
+impl UriDisplay<Path> for Segments<'_> {
+    fn fmt(&self, f: &mut Formatter<'_, Path>) -> fmt::Result {
+        for segment in self.clone() {
+            f.refresh();
+            f.write_raw(segment)?;
+        }
+
+        f.refresh();
+        Ok(())
+    }
+}
+
 // And finally, the `Ignorable` trait, which has sugar of `_` in the `uri!`
 // macro, which expands to a typecheck.
 
@@ -0,0 +1,59 @@
+use std::fmt;
+
+use self::Version::*;
+
+/// Representation of HTTP protocol versions.
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
+#[non_exhaustive]
+pub enum Version {
+    /// `HTTP/0.9`
+    Http09,
+    /// `HTTP/1.0`
+    Http10,
+    /// `HTTP/1.1`
+    Http11,
+    /// `HTTP/2`
+    Http2,
+}
+
+impl Version {
+    /// WARNING: This is unstable! Do not use this method outside of Rocket!
+    #[doc(hidden)]
+    pub fn from_hyp(version: http::Version) -> Option<Version> {
+        match version {
+            http::Version::HTTP_09 => Some(Http09),
+            http::Version::HTTP_10 => Some(Http10),
+            http::Version::HTTP_11 => Some(Http11),
+            http::Version::HTTP_2 => Some(Http2),
+            _ => None,
+        }
+    }
+
+    /// Returns the string representation of `self`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # extern crate rocket;
+    /// use rocket::http::Version;
+    ///
+    /// assert_eq!(Version::Http11.as_str(), "HTTP/1.1");
+    /// assert_eq!(Version::Http2.as_str(), "HTTP/2");
+    /// ```
+    #[inline]
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Http09 => "HTTP/0.9",
+            Http10 => "HTTP/1.0",
+            Http11 => "HTTP/1.1",
+            Http2 => "HTTP/2",
+        }
+    }
+}
+
+impl fmt::Display for Version {
+    #[inline(always)]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.as_str().fmt(f)
+    }
+}
@@ -0,0 +1,409 @@
+//! Broadcast channels for pushing values to many subscribers.
+//!
+//! A [`Channel`] delivers every value sent to it to each of its current
+//! subscribers. Each subscriber buffers values independently, up to the
+//! channel's capacity, and a [`Lag`] policy decides what happens to a
+//! subscriber that doesn't keep up. Subscriptions are [`Stream`]s and convert
+//! into an [`EventStream`] responder, making a `Channel` managed as state the
+//! backbone of chat rooms and notification feeds.
+//!
+//! # Example
+//!
+//! ```rust
+//! # #[macro_use] extern crate rocket;
+//! use rocket::State;
+//! use rocket::broadcast::{Channel, Subscription};
+//! use rocket::response::EventStream;
+//!
+//! #[post("/message", data = "<message>")]
+//! fn send(channel: State<'_, Channel<String>>, message: String) {
+//!     channel.send(message);
+//! }
+//!
+//! #[get("/messages")]
+//! fn messages(channel: State<'_, Channel<String>>) -> EventStream<Subscription<String>> {
+//!     channel.subscribe()
+//! }
+//!
+//! #[launch]
+//! fn rocket() -> rocket::Rocket {
+//!     rocket::ignite()
+//!         .manage(Channel::<String>::new(64))
+//!         .mount("/", routes![send, messages])
+//! }
+//! ```
+//!
+//! [`EventStream`]: crate::response::EventStream
+
+use std::fmt;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::collections::VecDeque;
+use std::task::{Context, Poll, Waker};
+
+use futures::stream::Stream;
+use parking_lot::Mutex;
+
+use crate::response::EventStream;
+
+/// What a [`Channel`] does when a value is sent to a subscriber whose buffer
+/// is full.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Lag {
+    /// Discard the oldest buffered value to make room for the new one. The
+    /// subscriber misses values but always receives the latest.
+    DropOldest,
+    /// Discard the new value. The subscriber misses values but receives the
+    /// ones it buffered, in order.
+    DropNewest,
+    /// End the subscription. The subscriber receives the values it buffered,
+    /// and then its stream ends, closing its [`EventStream`] response. A
+    /// client is expected to reconnect and resynchronize.
+    ///
+    /// [`EventStream`]: crate::response::EventStream
+    Disconnect,
+}
+
+/// A broadcast channel delivering each sent value to every subscriber.
+///
+/// Values are sent with [`Channel::send()`], from any handler or task, and
+/// received via the [`Subscription`] streams returned by
+/// [`Channel::subscription()`] or, wrapped in an [`EventStream`] responder, by
+/// [`Channel::subscribe()`]. A subscription only receives values sent after
+/// it's created.
+///
+/// Each subscriber buffers at most `capacity` values. When a value is sent to
+/// a subscriber whose buffer is full, the channel's [`Lag`] policy applies:
+/// by default, [`Lag::DropOldest`]. Slow subscribers thus never hold up the
+/// sender or other subscribers.
+///
+/// Clones of a `Channel` share subscribers. A `Channel` is usually managed as
+/// state; a clone can be moved into a background task that sends values.
+///
+/// Subscriptions don't end on their own, so `EventStream` responses of a
+/// channel stay open, holding up graceful shutdown, until the channel is
+/// closed with [`Channel::close()`], for instance in a handler that initiates
+/// shutdown.
+///
+/// See the [module level docs](self) for an example.
+///
+/// [`EventStream`]: crate::response::EventStream
+pub struct Channel<T> {
+    inner: Arc<Inner<T>>,
+}
+
+struct Inner<T> {
+    capacity: usize,
+    lag: Lag,
+    subscribers: Mutex<Subscribers<T>>,
+}
+
+struct Subscribers<T> {
+    queues: Vec<Arc<Queue<T>>>,
+    closed: bool,
+}
+
+/// A subscription to a [`Channel`]: a stream of the values sent to the
+/// channel after the subscription was created.
+///
+/// The stream ends when the channel is closed or dropped, or, with
+/// [`Lag::Disconnect`], when the subscriber falls behind. Dropping a
+/// subscription unsubscribes it.
+pub struct Subscription<T> {
+    queue: Arc<Queue<T>>,
+}
+
+struct Queue<T> {
+    state: Mutex<QueueState<T>>,
+}
+
+struct QueueState<T> {
+    values: VecDeque<T>,
+    missed: u64,
+    closed: bool,
+    waker: Option<Waker>,
+}
+
+impl<T> Channel<T> {
+    /// Creates a new channel whose subscribers each buffer at most `capacity`
+    /// values, with a lag policy of [`Lag::DropOldest`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if `capacity` is `0`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::broadcast::Channel;
+    ///
+    /// let channel = Channel::<String>::new(16);
+    /// ```
+    pub fn new(capacity: usize) -> Channel<T> {
+        assert!(capacity > 0, "broadcast channel capacity must be non-zero");
+        let inner = Inner {
+            capacity,
+            lag: Lag::DropOldest,
+            subscribers: Mutex::new(Subscribers { queues: vec![], closed: false }),
+        };
+
+        Channel { inner: Arc::new(inner) }
+    }
+
+    /// Sets the policy applied to subscribers that fall behind to `lag`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the channel has been cloned.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::broadcast::{Channel, Lag};
+    ///
+    /// let channel = Channel::<String>::new(16).lag(Lag::Disconnect);
+    /// ```
+    pub fn lag(mut self, lag: Lag) -> Channel<T> {
+        Arc::get_mut(&mut self.inner)
+            .expect("the lag policy is set before the channel is cloned")
+            .lag = lag;
+
+        self
+    }
+
+    /// Subscribes to `self`, returning a stream of the values sent to `self`
+    /// from now on.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::broadcast::Channel;
+    ///
+    /// let channel = Channel::new(16);
+    /// let subscription = channel.subscription();
+    /// channel.send("hello");
+    /// ```
+    pub fn subscription(&self) -> Subscription<T> {
+        let mut subscribers = self.inner.subscribers.lock();
+        let queue = Arc::new(Queue {
+            state: Mutex::new(QueueState {
+                values: VecDeque::new(),
+                missed: 0,
+                closed: subscribers.closed,
+                waker: None,
+            })
+        });
+
+        if !subscribers.closed {
+            subscribers.queues.push(queue.clone());
+        }
+
+        Subscription { queue }
+    }
+
+    /// Subscribes to `self`, returning an [`EventStream`] that sends each
+    /// value sent to `self` from now on as an event.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # #[macro_use] extern crate rocket;
+    /// use rocket::State;
+    /// use rocket::broadcast::{Channel, Subscription};
+    /// use rocket::response::EventStream;
+    ///
+    /// #[get("/events")]
+    /// fn events(channel: State<'_, Channel<String>>) -> EventStream<Subscription<String>> {
+    ///     channel.subscribe()
+    /// }
+    /// ```
+    ///
+    /// [`EventStream`]: crate::response::EventStream
+    pub fn subscribe(&self) -> EventStream<Subscription<T>> {
+        EventStream::from(self.subscription())
+    }
+
+    /// Returns the number of current subscribers.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::broadcast::Channel;
+    ///
+    /// let channel = Channel::<String>::new(16);
+    /// let subscription = channel.subscription();
+    /// assert_eq!(channel.subscriber_count(), 1);
+    ///
+    /// drop(subscription);
+    /// assert_eq!(channel.subscriber_count(), 0);
+    /// ```
+    pub fn subscriber_count(&self) -> usize {
+        let mut subscribers = self.inner.subscribers.lock();
+        subscribers.queues.retain(|queue| queue.is_subscribed());
+        subscribers.queues.len()
+    }
+
+    /// Closes `self`: all current and future subscriptions end once they've
+    /// received the values they buffered, and values sent afterwards are
+    /// discarded.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::broadcast::Channel;
+    ///
+    /// let channel = Channel::new(16);
+    /// channel.close();
+    /// assert_eq!(channel.send("hello"), 0);
+    /// ```
+    pub fn close(&self) {
+        self.inner.subscribers.lock().close();
+    }
+}
+
+impl<T: Clone> Channel<T> {
+    /// Sends `value` to every current subscriber, applying the lag policy to
+    /// subscribers whose buffer is full. Returns the number of subscribers
+    /// the value was buffered for.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::broadcast::Channel;
+    ///
+    /// let channel = Channel::new(16);
+    /// assert_eq!(channel.send("nobody is listening"), 0);
+    ///
+    /// let subscription = channel.subscription();
+    /// assert_eq!(channel.send("hello"), 1);
+    /// ```
+    pub fn send(&self, value: T) -> usize {
+        let (capacity, lag) = (self.inner.capacity, self.inner.lag);
+        let mut subscribers = self.inner.subscribers.lock();
+        let mut delivered = 0;
+        subscribers.queues.retain(|queue| {
+            if !queue.is_subscribed() {
+                return false;
+            }
+
+            let mut state = queue.state.lock();
+            if state.values.len() >= capacity {
+                state.missed += 1;
+                match lag {
+                    Lag::DropOldest => { state.values.pop_front(); }
+                    Lag::DropNewest => return true,
+                    Lag::Disconnect => {
+                        state.close();
+                        return false;
+                    }
+                }
+            }
+
+            state.values.push_back(value.clone());
+            state.wake();
+            delivered += 1;
+            true
+        });
+
+        delivered
+    }
+}
+
+impl<T> Clone for Channel<T> {
+    fn clone(&self) -> Self {
+        Channel { inner: self.inner.clone() }
+    }
+}
+
+impl<T> Drop for Inner<T> {
+    fn drop(&mut self) {
+        self.subscribers.get_mut().close();
+    }
+}
+
+impl<T> fmt::Debug for Channel<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Channel")
+            .field("capacity", &self.inner.capacity)
+            .field("lag", &self.inner.lag)
+            .field("subscribers", &self.inner.subscribers.lock().queues.len())
+            .finish()
+    }
+}
+
+impl<T> Subscribers<T> {
+    fn close(&mut self) {
+        self.closed = true;
+        for queue in self.queues.drain(..) {
+            queue.state.lock().close();
+        }
+    }
+}
+
+impl<T> Queue<T> {
+    fn is_subscribed(self: &Arc<Self>) -> bool {
+        Arc::strong_count(self) > 1
+    }
+}
+
+impl<T> QueueState<T> {
+    fn close(&mut self) {
+        self.closed = true;
+        self.wake();
+    }
+
+    fn wake(&mut self) {
+        if let Some(waker) = self.waker.take() {
+            waker.wake();
+        }
+    }
+}
+
+impl<T> Subscription<T> {
+    /// Returns the number of values this subscription missed because it fell
+    /// behind.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::broadcast::Channel;
+    ///
+    /// let channel = Channel::new(1);
+    /// let subscription = channel.subscription();
+    /// channel.send("first");
+    /// channel.send("second");
+    /// assert_eq!(subscription.missed(), 1);
+    /// ```
+    pub fn missed(&self) -> u64 {
+        self.queue.state.lock().missed
+    }
+}
+
+impl<T> Stream for Subscription<T> {
+    type Item = T;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<T>> {
+        let mut state = self.queue.state.lock();
+        if let Some(value) = state.values.pop_front() {
+            return Poll::Ready(Some(value));
+        }
+
+        if state.closed {
+            return Poll::Ready(None);
+        }
+
+        state.waker = Some(cx.waker().clone());
+        Poll::Pending
+    }
+}
+
+impl<T> fmt::Debug for Subscription<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let state = self.queue.state.lock();
+        f.debug_struct("Subscription")
+            .field("buffered", &state.values.len())
+            .field("missed", &state.missed)
+            .field("closed", &state.closed)
+            .finish()
+    }
+}
@@ -0,0 +1,249 @@
+//! Translations of the messages Rocket produces for clients.
+//!
+//! See [`Catalog`] for details.
+
+use std::borrow::Cow;
+use std::cmp::Ordering;
+use std::collections::HashMap;
+
+use crate::request::Request;
+
+/// A catalog of translated messages, selected via `Accept-Language`.
+///
+/// Rocket produces a handful of human-readable messages for clients: the
+/// reasons and descriptions in the pages of its default catchers, and the
+/// messages of form errors returned by [`FormParseError::message()`] and
+/// [`FormDataError::message()`]. All are in English by default. To translate
+/// them, [manage](crate::Rocket::manage()) a `Catalog` containing translations
+/// of the messages' keys. Rocket then uses the translation in the language most
+/// preferred by the client's `Accept-Language` header, falling back to English
+/// when the catalog has no suitable translation.
+///
+/// Languages are matched case-insensitively, first by the full language tag and
+/// then by its primary subtag: a request accepting `fr-CA` receives a `fr-ca`
+/// translation if there is one, and otherwise a `fr` translation.
+///
+/// # Keys
+///
+/// | key                        | message                                      |
+/// |----------------------------|----------------------------------------------|
+/// | `error.<code>.reason`      | reason of the status `<code>`, e.g. `404`    |
+/// | `error.<code>.description` | description of the status `<code>`           |
+/// | `form.bad_value`           | a form field failed to parse or validate     |
+/// | `form.unknown`             | a form contained an unexpected field         |
+/// | `form.missing`             | a form was missing a field                   |
+/// | `form.malformed`           | a form string was malformed                  |
+/// | `form.io`                  | reading a form failed                        |
+///
+/// Messages may refer to arguments by name in braces. The form messages receive
+/// the arguments `{field}` and, for `form.bad_value` and `form.unknown`,
+/// `{value}`.
+///
+/// [`FormParseError::message()`]: crate::request::FormParseError::message()
+/// [`FormDataError::message()`]: crate::request::FormDataError::message()
+///
+/// # Example
+///
+/// ```rust,no_run
+/// # #[macro_use] extern crate rocket;
+/// use rocket::catalog::Catalog;
+///
+/// #[launch]
+/// fn rocket() -> rocket::Rocket {
+///     let catalog = Catalog::new()
+///         .add("fr", "error.404.reason", "Introuvable")
+///         .add("fr", "error.404.description", "La ressource demandée est introuvable.")
+///         .add("fr", "form.missing", "Le champ « {field} » est obligatoire.");
+///
+///     rocket::ignite().manage(catalog)
+/// }
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct Catalog {
+    languages: HashMap<String, HashMap<String, String>>,
+}
+
+impl Catalog {
+    /// Returns an empty catalog.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::catalog::Catalog;
+    ///
+    /// let catalog = Catalog::new();
+    /// assert_eq!(catalog.get("fr", "error.404.reason"), None);
+    /// ```
+    pub fn new() -> Catalog {
+        Catalog::default()
+    }
+
+    /// Adds `message` as the translation of `key` in the language `lang`,
+    /// replacing any existing translation, and returns `self`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::catalog::Catalog;
+    ///
+    /// let catalog = Catalog::new().add("de", "error.404.reason", "Nicht gefunden");
+    /// assert_eq!(catalog.get("de", "error.404.reason"), Some("Nicht gefunden"));
+    /// ```
+    pub fn add<L, K, M>(mut self, lang: L, key: K, message: M) -> Self
+        where L: Into<String>, K: Into<String>, M: Into<String>
+    {
+        let lang = lang.into().to_ascii_lowercase();
+        self.languages.entry(lang).or_default().insert(key.into(), message.into());
+        self
+    }
+
+    /// Returns the translation of `key` in the language `lang`, if any. `lang`
+    /// is matched exactly, though case-insensitively.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::catalog::Catalog;
+    ///
+    /// let catalog = Catalog::new().add("pt-BR", "error.404.reason", "Não encontrado");
+    /// assert_eq!(catalog.get("pt-br", "error.404.reason"), Some("Não encontrado"));
+    /// assert_eq!(catalog.get("pt", "error.404.reason"), None);
+    /// ```
+    pub fn get(&self, lang: &str, key: &str) -> Option<&str> {
+        self.languages.get(&lang.to_ascii_lowercase())?.get(key).map(|s| s.as_str())
+    }
+
+    /// Returns the translation of `key` in the language most preferred by the
+    /// `Accept-Language` header of `req` along with that language, if any.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::catalog::Catalog;
+    /// # use rocket::Request;
+    /// # use rocket::http::{Header, Method};
+    ///
+    /// let catalog = Catalog::new()
+    ///     .add("fr", "error.404.reason", "Introuvable")
+    ///     .add("de", "error.404.reason", "Nicht gefunden");
+    ///
+    /// # Request::example(Method::Get, "/", |req| {
+    /// req.add_header(Header::new("Accept-Language", "es, de;q=0.8, fr;q=0.9"));
+    /// assert_eq!(catalog.lookup(req, "error.404.reason"), Some(("fr", "Introuvable")));
+    /// # });
+    /// ```
+    pub fn lookup(&self, req: &Request<'_>, key: &str) -> Option<(&str, &str)> {
+        for tag in accepted_languages(req) {
+            let tag = tag.to_ascii_lowercase();
+            let primary = tag.split('-').next().unwrap_or("");
+            for lang in &[&*tag, primary] {
+                let found = self.languages.get_key_value(*lang)
+                    .and_then(|(lang, messages)| Some((lang, messages.get(key)?)));
+
+                if let Some((lang, message)) = found {
+                    return Some((lang.as_str(), message.as_str()));
+                }
+            }
+        }
+
+        None
+    }
+}
+
+/// Returns the message for `key` for `req`: the translation in the managed
+/// [`Catalog`], if any, or else `default`, with `{name}` arguments in `args`
+/// replaced by their values.
+pub(crate) fn localize<'a>(
+    req: &'a Request<'_>,
+    key: &str,
+    default: &'a str,
+    args: &[(&str, &str)]
+) -> Cow<'a, str> {
+    let message = req.managed_state::<Catalog>()
+        .and_then(|catalog| catalog.lookup(req, key))
+        .map_or(default, |(_, message)| message);
+
+    interpolate(message, args)
+}
+
+/// Replaces every `{name}` in `message` with the value of `name` in `args`.
+pub(crate) fn interpolate<'a>(message: &'a str, args: &[(&str, &str)]) -> Cow<'a, str> {
+    if args.is_empty() || !message.contains('{') {
+        return Cow::Borrowed(message);
+    }
+
+    let mut string = message.to_string();
+    for (name, value) in args {
+        string = string.replace(&format!("{{{}}}", name), value);
+    }
+
+    Cow::Owned(string)
+}
+
+/// Returns the language tags in the `Accept-Language` header of `req`, most
+/// preferred first, excluding the wildcard `*` and refused (`q=0`) languages.
+fn accepted_languages<'r>(req: &'r Request<'_>) -> Vec<&'r str> {
+    let mut languages: Vec<(f32, &str)> = req.headers().get("Accept-Language")
+        .flat_map(|header| header.split(','))
+        .filter_map(|item| {
+            let mut parts = item.split(';');
+            let tag = parts.next()?.trim();
+            let q = parts.find_map(|p| p.trim().strip_prefix("q="))
+                .map_or(Some(1.0), |q| q.trim().parse::<f32>().ok())?;
+
+            Some((q, tag))
+        })
+        .filter(|&(q, tag)| q > 0.0 && !tag.is_empty() && tag != "*")
+        .collect();
+
+    // The sort is stable: equally preferred languages keep their order.
+    languages.sort_by(|a, b| b.0.partial_cmp(&a.0).unwrap_or(Ordering::Equal));
+    languages.into_iter().map(|(_, tag)| tag).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::http::{Header, Method};
+
+    #[test]
+    fn test_accepted_languages() {
+        Request::example(Method::Get, "/", |req| {
+            req.add_header(Header::new("Accept-Language", "fr-CH, fr;q=0.9, en;q=0.8, *;q=0.5"));
+            assert_eq!(accepted_languages(req), vec!["fr-CH", "fr", "en"]);
+
+            req.replace_header(Header::new("Accept-Language", "de;q=0.1, es, it;q=0, pt;q=x"));
+            assert_eq!(accepted_languages(req), vec!["es", "de"]);
+        });
+    }
+
+    #[test]
+    fn test_lookup() {
+        let catalog = Catalog::new()
+            .add("fr", "key", "fr")
+            .add("fr-CA", "key", "fr-ca")
+            .add("en", "other", "en");
+
+        Request::example(Method::Get, "/", |req| {
+            assert_eq!(catalog.lookup(req, "key"), None);
+
+            req.add_header(Header::new("Accept-Language", "fr-CA"));
+            assert_eq!(catalog.lookup(req, "key"), Some(("fr-ca", "fr-ca")));
+
+            req.replace_header(Header::new("Accept-Language", "fr-BE, en"));
+            assert_eq!(catalog.lookup(req, "key"), Some(("fr", "fr")));
+
+            req.replace_header(Header::new("Accept-Language", "en, fr"));
+            assert_eq!(catalog.lookup(req, "key"), Some(("fr", "fr")));
+            assert_eq!(catalog.lookup(req, "other"), Some(("en", "en")));
+        });
+    }
+
+    #[test]
+    fn test_interpolate() {
+        let args = [("field", "name"), ("value", "{field}")];
+        assert_eq!(interpolate("no args", &args), "no args");
+        assert_eq!(interpolate("{field} = {value}!", &args), "name = {field}!");
+        assert_eq!(interpolate("{field}", &[]), "{field}");
+    }
+}
@@ -2,10 +2,13 @@
 //! values.
 
 use std::fmt;
+use std::ops::Range;
 
 use crate::response::Response;
 use crate::codegen::StaticCatcherInfo;
 use crate::request::Request;
+use crate::http::MediaType;
+use crate::catalog::Catalog;
 
 use futures::future::BoxFuture;
 use yansi::Paint;
@@ -34,11 +37,19 @@ pub type ErrorHandlerFuture<'r> = BoxFuture<'r, Result<'r>>;
 /// failure is always a `404`. Rocket invokes the error handler for the catcher
 /// with the error's status code.
 ///
+/// ## Ranged Catchers
+///
+/// A _ranged_ catcher handles every status code in a range, such as all `4xx`
+/// errors. A ranged catcher is a `Catcher` with a `code` of `None` and a
+/// `range` of `Some`. A catcher for an exact status code takes precedence over
+/// a ranged catcher. When several ranges contain a status code, the narrowest
+/// range is used.
+///
 /// ## Default Catchers
 ///
-/// If no catcher for a given status code exists, the _default_ catcher is
-/// called. A _default_ catcher is a `Catcher` with a `code` of `None`. There is
-/// at-most one default catcher.
+/// If no catcher for a given status code or range containing it exists, the
+/// _default_ catcher is called. A _default_ catcher is a `Catcher` with a
+/// `code` and `range` of `None`. There is at-most one default catcher.
 ///
 /// ## Error Handler Restrictions
 ///
@@ -52,6 +63,26 @@ pub type ErrorHandlerFuture<'r> = BoxFuture<'r, Result<'r>>;
 /// JSON, depending on the value of the `Accept` header. As such, catchers only
 /// need to be registered if an error needs to be handled in a custom fashion.
 ///
+/// JSON is produced when the client's preferred media type, as determined by
+/// [`Accept::preferred()`], is JSON or a `+json` type such as
+/// `application/problem+json`. When the request has no `Accept` header, JSON is
+/// produced if the request's body is JSON. Otherwise, HTML is produced. JSON
+/// errors are of the form:
+///
+/// ```json
+/// {
+///   "error": {
+///     "code": 404,
+///     "reason": "Not Found",
+///     "description": "The requested resource could not be found."
+///   }
+/// }
+/// ```
+///
+/// Responses from the default catcher include a `Vary: Accept` header.
+///
+/// [`Accept::preferred()`]: crate::http::Accept::preferred()
+///
 /// # Code Generation
 ///
 /// Catchers should rarely be constructed or used directly. Instead, they are
@@ -73,6 +104,11 @@ pub type ErrorHandlerFuture<'r> = BoxFuture<'r, Result<'r>>;
 ///     format!("I couldn't find '{}'. Try something else?", req.uri())
 /// }
 ///
+/// #[catch(400..500)]
+/// fn client_error(status: Status) -> String {
+///     format!("Client error: {}", status)
+/// }
+///
 /// #[catch(default)]
 /// fn default(status: Status, req: &Request) -> String {
 ///     format!("{} - {} ({})", status.code, status.reason, req.uri())
@@ -80,7 +116,8 @@ pub type ErrorHandlerFuture<'r> = BoxFuture<'r, Result<'r>>;
 ///
 /// #[launch]
 /// fn rocket() -> rocket::Rocket {
-///     rocket::ignite().register(catchers![internal_error, not_found, default])
+///     rocket::ignite()
+///         .register(catchers![internal_error, not_found, client_error, default])
 /// }
 /// ```
 ///
@@ -102,6 +139,10 @@ pub struct Catcher {
     /// The HTTP status code to match against if this route is not `default`.
     pub code: Option<u16>,
 
+    /// The range of HTTP status codes to match against if this is a ranged
+    /// catcher. Only consulted when `code` is `None`.
+    pub range: Option<Range<u16>>,
+
     /// The catcher's associated error handler.
     pub handler: Box<dyn ErrorHandler>,
 }
@@ -141,7 +182,36 @@ impl Catcher {
     pub fn new<C, H>(code: C, handler: H) -> Catcher
         where C: Into<Option<u16>>, H: ErrorHandler
     {
-        Catcher { code: code.into(), handler: Box::new(handler) }
+        Catcher { code: code.into(), range: None, handler: Box::new(handler) }
+    }
+
+    /// Creates a catcher for every status code in `range` using the given
+    /// error handler. This should only be used when routing manually.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `range` is empty.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::request::Request;
+    /// use rocket::catcher::{Catcher, ErrorHandlerFuture};
+    /// use rocket::response::{Responder, status::Custom};
+    /// use rocket::http::Status;
+    ///
+    /// fn handle_5xx<'r>(status: Status, req: &'r Request<'_>) -> ErrorHandlerFuture<'r> {
+    ///    let res = Custom(status, "Something went wrong on our end.");
+    ///    Box::pin(async move { res.respond_to(req) })
+    /// }
+    ///
+    /// let server_error_catcher = Catcher::ranged(500..600, handle_5xx);
+    /// assert_eq!(server_error_catcher.range, Some(500..600));
+    /// ```
+    #[inline]
+    pub fn ranged<H: ErrorHandler>(range: Range<u16>, handler: H) -> Catcher {
+        assert!(range.start < range.end, "catcher range {:?} is empty", range);
+        Catcher { code: None, range: Some(range), handler: Box::new(handler) }
     }
 }
 
@@ -151,7 +221,7 @@ impl Default for Catcher {
             Box::pin(async move { default(status, request) })
         }
 
-        Catcher { code: None, handler: Box::new(async_default) }
+        Catcher { code: None, range: None, handler: Box::new(async_default) }
     }
 }
 
@@ -262,15 +332,19 @@ impl<F: Clone + Sync + Send + 'static> ErrorHandler for F
 impl From<StaticCatcherInfo> for Catcher {
     #[inline]
     fn from(info: StaticCatcherInfo) -> Catcher {
-        Catcher::new(info.code, info.handler)
+        match info.range {
+            Some(range) => Catcher::ranged(range, info.handler),
+            None => Catcher::new(info.code, info.handler),
+        }
     }
 }
 
 impl fmt::Display for Catcher {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        match self.code {
-            Some(code) => write!(f, "{}", Paint::blue(code)),
-            None => write!(f, "{}", Paint::blue("default"))
+        match (self.code, &self.range) {
+            (Some(code), _) => write!(f, "{}", Paint::blue(code)),
+            (None, Some(range)) => write!(f, "{}", Paint::blue(format!("{:?}", range))),
+            (None, None) => write!(f, "{}", Paint::blue("default"))
         }
     }
 }
@@ -279,6 +353,7 @@ impl fmt::Debug for Catcher {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         f.debug_struct("Catcher")
             .field("code", &self.code)
+            .field("range", &self.range)
             .finish()
     }
 }
@@ -337,6 +412,180 @@ r#"{{
     )
 }
 
+/// Returns `true` if the default catcher should respond to `req` with JSON.
+fn prefers_json(req: &Request<'_>) -> bool {
+    fn is_json(media_type: &MediaType) -> bool {
+        media_type.is_json() || media_type.sub().as_str().to_ascii_lowercase().ends_with("+json")
+    }
+
+    match req.accept() {
+        Some(accept) => is_json(accept.preferred().media_type()),
+        None => req.content_type().map_or(false, |ct| is_json(ct.media_type())),
+    }
+}
+
+/// Marks `response`, the default catcher's response to `req`, as varying by
+/// `Accept` and, if a [`Catalog`] may translate it, by `Accept-Language`.
+fn vary<'r>(req: &Request<'_>, mut response: Response<'r>) -> Response<'r> {
+    match req.managed_state::<Catalog>() {
+        Some(_) => response.set_raw_header("Vary", "Accept, Accept-Language"),
+        None => response.set_raw_header("Vary", "Accept"),
+    };
+
+    response
+}
+
+/// Returns the trace of `req` as an HTML fragment to replace the `<hr />` in
+/// `html_error_template!` with, if there is a trace to show.
+fn html_trace(req: &Request<'_>) -> Option<String> {
+    let trace = req.trace();
+    if trace.is_empty() {
+        return None;
+    }
+
+    let escaped = html_escape(&trace.to_string());
+    Some(format!("<hr />\n<pre align=\"left\">{}</pre>\n<hr />", escaped))
+}
+
+fn html_escape(string: &str) -> String {
+    string.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;")
+}
+
+/// Returns the diagnostic page for an error with `status`, `reason`, and
+/// `description` while processing `req`, used in place of
+/// `html_error_template!` when the `diagnostics` configuration parameter is
+/// enabled.
+fn diagnostic_html(
+    status: Status,
+    reason: &str,
+    description: &str,
+    req: &Request<'_>
+) -> String {
+    use std::fmt::Write;
+    use crate::request::{TraceEvent, describe_route};
+
+    let mut html = format!(r#"<!DOCTYPE html>
+<html lang="en">
+<head>
+    <meta charset="utf-8">
+    <title>{code} {reason}</title>
+</head>
+<body>
+    <h1>{code}: {reason}</h1>
+    <p>{description}</p>
+    <h2>Request</h2>
+    <pre>{method} {uri}</pre>
+"#, code = status.code, reason = html_escape(reason), description = html_escape(description),
+        method = req.method(), uri = html_escape(&req.uri().to_string()));
+
+    let _ = writeln!(html, "    <h2>Matching Routes</h2>");
+    let candidates = req.candidate_routes();
+    if candidates.is_empty() {
+        let _ = writeln!(html, "    <p>No mounted route matches this request.</p>");
+    } else {
+        let _ = writeln!(html, "    <ol>");
+        for route in candidates {
+            let _ = writeln!(html, "        <li>{}</li>", html_escape(&describe_route(route)));
+        }
+
+        let _ = writeln!(html, "    </ol>");
+    }
+
+    let _ = writeln!(html, "    <h2>Trace</h2>");
+    let trace = req.trace();
+    if trace.is_empty() {
+        let _ = writeln!(html, "    <p>No routes were tried.</p>");
+    } else {
+        let _ = writeln!(html, "    <ol>");
+        for event in trace.iter() {
+            let _ = writeln!(html, "        <li>{}</li>", html_escape(&event.to_string()));
+        }
+
+        let _ = writeln!(html, "    </ol>");
+        if trace.iter().any(|e| matches!(e, TraceEvent::Panicked(_))) {
+            let _ = writeln!(html, "    <p>A handler panicked. Run with \
+                <code>RUST_BACKTRACE=1</code> to print the panic's backtrace to the \
+                server's standard error.</p>");
+        }
+    }
+
+    let config = req.state.config;
+    let _ = writeln!(html, "    <h2>Configuration</h2>\n    <table>");
+    let rows: &[(&str, &dyn fmt::Display)] = &[
+        ("address", &config.address),
+        ("port", &config.port),
+        ("workers", &config.workers),
+        ("keep-alive", &config.keep_alive),
+        ("forward limit", &config.forward_limit),
+        ("log level", &config.log_level),
+        ("tls", &config.tls_enabled()),
+        ("limits", &config.limits),
+    ];
+
+    for (name, value) in rows {
+        let value = html_escape(&value.to_string());
+        let _ = writeln!(html, "        <tr><th align=\"left\">{}</th><td>{}</td></tr>",
+            name, value);
+    }
+
+    let _ = writeln!(html, r#"    </table>
+    <hr />
+    <small>Rocket diagnostics. Disable the <code>diagnostics</code> configuration
+    parameter to show terse error pages.</small>
+</body>
+</html>"#);
+
+    html
+}
+
+/// Returns the trace of `req` as a JSON fragment to replace the closing
+/// `\n  }\n}` of `json_error_template!` with, if there is a trace to show.
+fn json_trace(req: &Request<'_>) -> Option<String> {
+    let trace = req.trace();
+    if trace.is_empty() {
+        return None;
+    }
+
+    let events: Vec<String> = trace.iter()
+        .map(|event| format!("\"{}\"", json_escape(&event.to_string())))
+        .collect();
+
+    Some(format!(",\n    \"trace\": [{}]\n  }}\n}}", events.join(", ")))
+}
+
+/// Escapes `string` for use inside a JSON string literal.
+fn json_escape(string: &str) -> String {
+    let mut escaped = String::with_capacity(string.len());
+    for c in string.chars() {
+        match c {
+            '"' => escaped.push_str("\\\""),
+            '\\' => escaped.push_str("\\\\"),
+            c if c.is_control() => escaped.push_str(&format!("\\u{:04x}", c as u32)),
+            c => escaped.push(c),
+        }
+    }
+
+    escaped
+}
+
+/// Returns the language, reason, and description of the error page for
+/// `status` when the managed [`Catalog`] translates the reason or description
+/// into a language accepted by `req`. `description` is the English default.
+fn translated<'r>(
+    status: Status,
+    description: &'r str,
+    req: &'r Request<'_>
+) -> Option<(&'r str, &'r str, &'r str)> {
+    let catalog = req.managed_state::<Catalog>()?;
+    let reason_key = format!("error.{}.reason", status.code);
+    let description_key = format!("error.{}.description", status.code);
+    let (lang, reason) = catalog.lookup(req, &reason_key)
+        .or_else(|| catalog.lookup(req, &description_key).map(|(lang, _)| (lang, status.reason)))?;
+
+    let description = catalog.get(lang, &description_key).unwrap_or(description);
+    Some((lang, reason, description))
+}
+
 macro_rules! default_catcher_fn {
     ($($code:expr, $reason:expr, $description:expr),+) => (
         use std::borrow::Cow;
@@ -344,22 +593,60 @@ macro_rules! default_catcher_fn {
         use crate::response::{content, status, Responder};
 
         pub(crate) fn default<'r>(status: Status, req: &'r Request<'_>) -> Result<'r> {
-            if req.accept().map(|a| a.preferred().is_json()).unwrap_or(false) {
-                let json: Cow<'_, str> = match status.code {
-                    $($code => json_error_template!($code, $reason, $description).into(),)*
-                    code => format!(json_error_fmt_template!("{}", "Unknown Error",
+            let description = match status.code {
+                $($code => $description,)*
+                _ => "An unknown error has occurred.",
+            };
+
+            let translation = translated(status, description, req);
+            if prefers_json(req) {
+                let mut json: Cow<'_, str> = match (translation, status.code) {
+                    (Some((_, reason, description)), code) => {
+                        format!(json_error_fmt_template!("{}", "{}", "{}"), code,
+                            json_escape(reason), json_escape(description)).into()
+                    }
+                    $((None, $code) => json_error_template!($code, $reason, $description).into(),)*
+                    (None, code) => format!(json_error_fmt_template!("{}", "Unknown Error",
                             "An unknown error has occurred."), code).into()
                 };
 
-                status::Custom(status, content::Json(json)).respond_to(req)
+                if let Some(trace) = json_trace(req) {
+                    json = json.replacen("\n  }\n}", &trace, 1).into();
+                }
+
+                let response = status::Custom(status, content::Json(json)).respond_to(req);
+                response.map(|response| vary(req, response))
             } else {
-                let html: Cow<'_, str> = match status.code {
-                    $($code => html_error_template!($code, $reason, $description).into(),)*
-                    code => format!(html_error_template!("{}", "Unknown Error",
+                if req.state.config.diagnostics {
+                    let (reason, description) = translation
+                        .map_or((status.reason, description), |(_, r, d)| (r, d));
+
+                    let html = diagnostic_html(status, reason, description, req);
+                    let response = status::Custom(status, content::Html(html)).respond_to(req);
+                    return response.map(|response| vary(req, response));
+                }
+
+                let mut html: Cow<'_, str> = match (translation, status.code) {
+                    (Some((lang, reason, description)), code) => {
+                        let lang_attr = format!("lang=\"{}\"", html_escape(lang));
+                        format!(html_error_template!("{code}", "{reason}", "{description}"),
+                            code = code,
+                            reason = html_escape(reason),
+                            description = html_escape(description))
+                            .replacen("lang=\"en\"", &lang_attr, 1)
+                            .into()
+                    }
+                    $((None, $code) => html_error_template!($code, $reason, $description).into(),)*
+                    (None, code) => format!(html_error_template!("{}", "Unknown Error",
                             "An unknown error has occurred."), code, code).into(),
                 };
 
-                status::Custom(status, content::Html(html)).respond_to(req)
+                if let Some(trace) = html_trace(req) {
+                    html = html.replacen("<hr />", &trace, 1).into();
+                }
+
+                let response = status::Custom(status, content::Html(html)).respond_to(req);
+                response.map(|response| vary(req, response))
             }
         }
     )
@@ -23,12 +23,16 @@ pub struct StaticRouteInfo {
     pub handler: StaticHandler,
     /// The route's rank, if any.
     pub rank: Option<isize>,
+    /// The route's matcher, if any.
+    pub matcher: Option<fn(&Request<'_>) -> bool>,
 }
 
 /// Information generated by the `catch` attribute during codegen.
 pub struct StaticCatcherInfo {
     /// The catcher's status code.
     pub code: Option<u16>,
+    /// The catcher's range of status codes, if it is a ranged catcher.
+    pub range: Option<std::ops::Range<u16>>,
     /// The catcher's handler, i.e, the annotated function.
     pub handler: StaticErrorHandler,
 }
@@ -6,8 +6,9 @@ use figment::value::{Map, Dict};
 use serde::{Deserialize, Serialize};
 use yansi::Paint;
 
-use crate::config::{SecretKey, TlsConfig, LogLevel};
+use crate::config::{SecretKey, TlsConfig, LogLevel, LogFormat};
 use crate::data::Limits;
+use crate::http::SameSite;
 
 /// Rocket server configuration.
 ///
@@ -24,8 +25,9 @@ use crate::data::Limits;
 /// the release profile. The [`Config::default()`] method automatically selects
 /// the appropriate of the two based on the selected profile. With the exception
 /// of `log_level`, which is `normal` in `debug` and `critical` in `release`,
-/// and `secret_key`, which is regenerated from a random value if not set in
-/// "debug" mode only, all of the values are identical in either profile.
+/// `trace` and `diagnostics`, which are `true` in `debug` and `false` in
+/// `release`, and `secret_key`, which is regenerated from a random value if not
+/// set in "debug" mode only, all of the values are identical in either profile.
 ///
 /// # Provider Details
 ///
@@ -58,11 +60,36 @@ pub struct Config {
     /// Port to serve on. **(default: `8000`)**
     pub port: u16,
     /// Number of threads to use for executing futures. **(default: `cores * 2`)**
+    ///
+    /// The value is read from [`Config::figment()`] when the runtime is
+    /// started by `#[launch]` or `#[rocket::main]`.
     pub workers: u16,
     /// Keep-alive timeout in seconds; disabled when `0`. **(default: `5`)**
     pub keep_alive: u32,
+    /// Seconds a connection may sit idle waiting for a request, plus the time
+    /// to receive its head, before it is closed; disabled when `0`. Doesn't
+    /// apply while a request is being handled or its response is being
+    /// written. **(default: `0`)**
+    pub read_timeout: u32,
+    /// Seconds a write to a connection may wait for the client to accept
+    /// data before the connection is closed; disabled when `0`.
+    /// **(default: `0`)**
+    pub write_timeout: u32,
+    /// Whether connections without TLS may speak HTTP/2 with prior knowledge
+    /// (`h2c`). HTTP/2 over TLS is negotiated via ALPN regardless. Only
+    /// enable this for internal deployments whose clients speak `h2c`.
+    /// **(default: `false`)**
+    #[serde(deserialize_with = "figment::util::bool_from_str_or_int")]
+    pub h2c: bool,
+    /// Max number of routes a request may be forwarded through before it is
+    /// failed with a `500`. **(default: `100`)**
+    pub forward_limit: usize,
     /// Max level to log. **(default: _debug_ `normal` / _release_ `critical`)**
     pub log_level: LogLevel,
+    /// The format of log messages: `pretty`, for people, or `json`, for log
+    /// aggregators. Ignored if the application installs its own `log` logger.
+    /// **(default: `pretty`)**
+    pub log_format: LogFormat,
     /// Whether to use colors and emoji when logging. **(default: `true`)**
     #[serde(deserialize_with = "figment::util::bool_from_str_or_int")]
     pub cli_colors: bool,
@@ -75,6 +102,89 @@ pub struct Config {
     /// Whether `ctrl-c` initiates a server shutdown. **(default: `true`)**
     #[serde(deserialize_with = "figment::util::bool_from_str_or_int")]
     pub ctrlc: bool,
+    /// Whether to record how requests are routed, show the record in the
+    /// default catchers' pages, and count how often each route is tried and
+    /// wins. **(default: _debug_ `true` / _release_ `false`)**
+    #[serde(deserialize_with = "figment::util::bool_from_str_or_int")]
+    pub trace: bool,
+    /// Whether the default catchers respond with detailed diagnostic pages.
+    /// **(default: _debug_ `true` / _release_ `false`)**
+    #[serde(deserialize_with = "figment::util::bool_from_str_or_int")]
+    pub diagnostics: bool,
+    /// Whether cookies default to `Secure`, sent only over HTTPS. Applies to
+    /// cookies added without an explicit `Secure` attribute. Enable it when
+    /// the application is only served over HTTPS. **(default: `false`)**
+    #[serde(deserialize_with = "figment::util::bool_from_str_or_int")]
+    pub cookie_secure: bool,
+    /// The `SameSite` attribute of cookies added without one: `strict`, `lax`,
+    /// or `none`. Browsers reject `SameSite=None` cookies that aren't
+    /// `Secure`, so cookies with `none` default to `Secure` regardless of
+    /// `cookie_secure`. **(default: `strict`)**
+    #[serde(with = "same_site")]
+    pub cookie_same_site: SameSite,
+    /// Whether cookies set while processing a request that fails are sent
+    /// with the error response, along with those set by the catcher. When
+    /// `false`, only cookies set by the catcher and committed via
+    /// [`ResponseParts`](crate::response::ResponseParts) are sent.
+    /// **(default: `false`)**
+    #[serde(deserialize_with = "figment::util::bool_from_str_or_int")]
+    pub error_cookies: bool,
+    /// Whether to trust the `X-Forwarded-Proto` and `X-Forwarded-Host` headers
+    /// set by a reverse proxy. When trusted and the proxy reports `https`,
+    /// [`Redirect`](crate::response::Redirect)s to relative URIs become
+    /// absolute `https` URIs. Only enable this when Rocket is exclusively
+    /// reachable through a proxy that sets or strips these headers.
+    /// **(default: `false`)**
+    #[serde(deserialize_with = "figment::util::bool_from_str_or_int")]
+    pub trust_proxy_headers: bool,
+    /// Whether connections begin with a PROXY protocol header, version 1 or
+    /// 2, from a load balancer such as HAProxy or an AWS Network Load
+    /// Balancer. When enabled, the client address in the header becomes the
+    /// request's [`remote()`](crate::Request::remote()) address, and
+    /// connections without a valid header are dropped. Only applies to
+    /// servers launched via [`Rocket::launch()`](crate::Rocket::launch()).
+    /// **(default: `false`)**
+    #[serde(deserialize_with = "figment::util::bool_from_str_or_int")]
+    pub proxy_protocol: bool,
+    /// The headers, in order of preference, consulted by
+    /// [`Request::real_ip()`](crate::Request::real_ip()) for the client's IP
+    /// address. `X-Forwarded-For` and `Forwarded` yield the address appended
+    /// by the nearest proxy; any other header must contain a single address.
+    /// Only list headers that the proxy in front of Rocket sets or strips, and
+    /// leave the list empty when Rocket is directly reachable by clients.
+    /// **(default: `["X-Real-IP"]`)**
+    pub ip_headers: Vec<String>,
+    /// Whether to assign every request a [`RequestId`](crate::request::RequestId),
+    /// prefix the log lines emitted while dispatching the request with it, and
+    /// echo it in an `X-Request-Id` response header. A valid `X-Request-Id`
+    /// request header, as set by a proxy, is propagated instead of generating
+    /// a new identifier. **(default: `false`)**
+    #[serde(deserialize_with = "figment::util::bool_from_str_or_int")]
+    pub request_id: bool,
+    /// Whether unmatched `OPTIONS` requests are answered with a `204` and an
+    /// `Allow` header listing the methods of the routes matching the path.
+    /// Response fairings, such as a CORS fairing, see and may extend the
+    /// response. **(default: `false`)**
+    #[serde(deserialize_with = "figment::util::bool_from_str_or_int")]
+    pub auto_options: bool,
+    /// The path, such as `/app`, under which a reverse proxy serves the
+    /// application. The prefix is stripped from request paths beginning with
+    /// it before request fairings and routing, and prepended to the `Location`
+    /// of [`Redirect`](crate::response::Redirect)s to origin URIs. See
+    /// [`Request::prefixed_uri()`](crate::Request::prefixed_uri()) for links.
+    /// **(default: `None`)**
+    pub path_prefix: Option<String>,
+    /// Whether request bodies with a `Content-Encoding` of `gzip`, `deflate`,
+    /// or `br` are decoded before request fairings and data guards see them.
+    /// [`Limits`] apply to the decoded body. Only has an effect when the
+    /// `decompression` feature is enabled. **(default: `true`)**
+    #[serde(deserialize_with = "figment::util::bool_from_str_or_int")]
+    pub decompress: bool,
+    /// The formats of request bodies to decode when `decompress` is enabled,
+    /// as media types or extensions such as `"json"`. If empty, bodies of any
+    /// format are decoded. Decoding happens before routing, so it can't be
+    /// configured per route. **(default: `[]`)**
+    pub decompress_formats: Vec<String>,
 }
 
 impl Default for Config {
@@ -110,6 +220,9 @@ impl Config {
     #[cfg(not(debug_assertions))]
     pub const DEFAULT_PROFILE: Profile = Self::RELEASE_PROFILE;
 
+    // `LISTEN_FD` and `READY_FD` are set during a `Handover`; they aren't config.
+    const IGNORED_ENV_KEYS: &'static [&'static str] = &["PROFILE", "LISTEN_FD", "READY_FD"];
+
     const DEPRECATED_KEYS: &'static [(&'static str, Option<&'static str>)] = &[
         ("env", Some("profile")), ("log", Some("log_level")),
     ];
@@ -132,12 +245,30 @@ impl Config {
             port: 8000,
             workers: num_cpus::get() as u16 * 2,
             keep_alive: 5,
+            read_timeout: 0,
+            write_timeout: 0,
+            h2c: false,
+            forward_limit: 100,
             log_level: LogLevel::Normal,
+            log_format: LogFormat::Pretty,
             cli_colors: true,
             secret_key: SecretKey::zero(),
             tls: None,
             limits: Limits::default(),
             ctrlc: true,
+            trace: true,
+            diagnostics: true,
+            cookie_secure: false,
+            cookie_same_site: SameSite::Strict,
+            error_cookies: false,
+            trust_proxy_headers: false,
+            proxy_protocol: false,
+            ip_headers: vec!["X-Real-IP".into()],
+            request_id: false,
+            auto_options: false,
+            path_prefix: None,
+            decompress: true,
+            decompress_formats: vec![],
         }
     }
 
@@ -156,6 +287,8 @@ impl Config {
     pub fn release_default() -> Config {
         Config {
             log_level: LogLevel::Critical,
+            trace: false,
+            diagnostics: false,
             ..Config::debug_default()
         }
     }
@@ -191,7 +324,7 @@ impl Config {
     pub fn figment() -> Figment {
         Figment::from(Config::default())
             .merge(Toml::file(Env::var_or("ROCKET_CONFIG", "Rocket.toml")).nested())
-            .merge(Env::prefixed("ROCKET_").ignore(&["PROFILE"]).global())
+            .merge(Env::prefixed("ROCKET_").ignore(Self::IGNORED_ENV_KEYS).global())
     }
 
     /// Attempts to extract a `Config` from `provider`.
@@ -235,7 +368,7 @@ impl Config {
         #[cfg(all(feature = "secrets", not(test), not(rocket_unsafe_secret_key)))] {
             if config.secret_key.is_zero() {
                 if figment.profile() != Self::DEBUG_PROFILE {
-                    crate::logger::try_init(LogLevel::Debug, true, false);
+                    crate::logger::try_init(LogLevel::Debug, LogFormat::Pretty, true, false);
                     error!("secrets enabled in `release` without `secret_key`");
                     info_!("disable `secrets` feature or configure a `secret_key`");
                     panic!("aborting due to configuration error(s)")
@@ -272,6 +405,14 @@ impl Config {
         cfg!(feature = "tls") && self.tls.is_some()
     }
 
+    /// Returns the segments of `path_prefix`, without leading or trailing
+    /// slashes, if there are any.
+    pub(crate) fn path_prefix_segments(&self) -> Option<&str> {
+        self.path_prefix.as_deref()
+            .map(|prefix| prefix.trim_matches('/'))
+            .filter(|prefix| !prefix.is_empty())
+    }
+
     pub(crate) fn pretty_print(&self, profile: &Profile) {
         use crate::logger::PaintExt;
 
@@ -279,10 +420,52 @@ impl Config {
         launch_info_!("address: {}", Paint::default(&self.address).bold());
         launch_info_!("port: {}", Paint::default(&self.port).bold());
         launch_info_!("workers: {}", Paint::default(self.workers).bold());
+        launch_info_!("forward limit: {}", Paint::default(self.forward_limit).bold());
         launch_info_!("log level: {}", Paint::default(self.log_level).bold());
+        launch_info_!("log format: {}", Paint::default(self.log_format).bold());
         launch_info_!("secret key: {:?}", Paint::default(&self.secret_key).bold());
         launch_info_!("limits: {}", Paint::default(&self.limits).bold());
         launch_info_!("cli colors: {}", Paint::default(&self.cli_colors).bold());
+        launch_info_!("trace: {}", Paint::default(&self.trace).bold());
+        launch_info_!("diagnostics: {}", Paint::default(&self.diagnostics).bold());
+        launch_info_!("cookie secure: {}", Paint::default(&self.cookie_secure).bold());
+        let same_site = same_site::as_str(self.cookie_same_site);
+        launch_info_!("cookie same site: {}", Paint::default(same_site).bold());
+        launch_info_!("error cookies: {}", Paint::default(&self.error_cookies).bold());
+        launch_info_!("trust proxy headers: {}", Paint::default(&self.trust_proxy_headers).bold());
+        launch_info_!("proxy protocol: {}", Paint::default(&self.proxy_protocol).bold());
+        if self.ip_headers.is_empty() {
+            launch_info_!("ip headers: {}", Paint::default("none").bold());
+        } else {
+            launch_info_!("ip headers: {}", Paint::default(self.ip_headers.join(", ")).bold());
+        }
+
+        launch_info_!("request id: {}", Paint::default(&self.request_id).bold());
+
+        launch_info_!("auto options: {}", Paint::default(&self.auto_options).bold());
+        launch_info_!("h2c: {}", Paint::default(&self.h2c).bold());
+        #[cfg(feature = "decompression")]
+        launch_info_!("decompress: {}", Paint::default(&self.decompress).bold());
+        #[cfg(feature = "decompression")]
+        if !self.decompress_formats.is_empty() {
+            let formats = self.decompress_formats.join(", ");
+            launch_info_!("decompress formats: {}", Paint::default(formats).bold());
+        }
+        if let Some(prefix) = self.path_prefix_segments() {
+            launch_info_!("path prefix: {}", Paint::default(format!("/{}", prefix)).bold());
+        }
+
+        let timeouts = [
+            ("read timeout", self.read_timeout),
+            ("write timeout", self.write_timeout),
+        ];
+
+        for &(name, timeout) in &timeouts {
+            match timeout {
+                0 => launch_info_!("{}: {}", name, Paint::default("disabled").bold()),
+                n => launch_info_!("{}: {}", name, Paint::default(format!("{}s", n)).bold()),
+            }
+        }
 
         let ka = self.keep_alive;
         if ka > 0 {
@@ -291,9 +474,21 @@ impl Config {
             launch_info_!("keep-alive: {}", Paint::default("disabled").bold());
         }
 
-        match self.tls_enabled() {
-            true => launch_info_!("tls: {}", Paint::default("enabled").bold()),
-            false => launch_info_!("tls: {}", Paint::default("disabled").bold()),
+        match (self.tls_enabled(), self.tls.as_ref().and_then(|tls| tls.mutual())) {
+            (true, Some(m)) if m.is_mandatory() => {
+                launch_info_!("tls: {}", Paint::default("enabled (mandatory mtls)").bold())
+            }
+            (true, Some(_)) => launch_info_!("tls: {}", Paint::default("enabled (mtls)").bold()),
+            (true, None) => launch_info_!("tls: {}", Paint::default("enabled").bold()),
+            (false, _) => launch_info_!("tls: {}", Paint::default("disabled").bold()),
+        }
+
+        let secure_cookies = self.cookie_secure || self.cookie_same_site == SameSite::None;
+        if secure_cookies && !self.tls_enabled() && !self.trust_proxy_headers {
+            warn!("cookies default to `Secure` but nothing provides HTTPS");
+            warn_!("clients will not send `Secure` cookies back over HTTP");
+            warn_!("enable TLS, or set `trust_proxy_headers` behind a TLS proxy");
+            warn_!("otherwise, disable `cookie_secure` and avoid `cookie_same_site = none`");
         }
     }
 }
@@ -313,9 +508,40 @@ impl Provider for Config {
     }
 }
 
+/// (De)serializes a `SameSite` as one of `strict`, `lax`, or `none`.
+mod same_site {
+    use serde::{de, Serializer, Deserialize, Deserializer};
+    use crate::http::SameSite;
+
+    pub fn as_str(same_site: SameSite) -> &'static str {
+        match same_site {
+            SameSite::Strict => "strict",
+            SameSite::Lax => "lax",
+            SameSite::None => "none",
+        }
+    }
+
+    pub fn serialize<S: Serializer>(same_site: &SameSite, ser: S) -> Result<S::Ok, S::Error> {
+        ser.serialize_str(as_str(*same_site))
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(de: D) -> Result<SameSite, D::Error> {
+        let string = String::deserialize(de)?;
+        match &*string.to_ascii_lowercase() {
+            "strict" => Ok(SameSite::Strict),
+            "lax" => Ok(SameSite::Lax),
+            "none" => Ok(SameSite::None),
+            _ => Err(de::Error::invalid_value(
+                de::Unexpected::Str(&string),
+                &figment::error::OneOf(&["strict", "lax", "none"])
+            ))
+        }
+    }
+}
+
 #[doc(hidden)]
 pub fn pretty_print_error(error: figment::Error) {
-    crate::logger::try_init(LogLevel::Debug, true, false);
+    crate::logger::try_init(LogLevel::Debug, LogFormat::Pretty, true, false);
 
     for e in error {
         error!("{}", e.kind);
@@ -105,17 +105,17 @@ mod tls;
 #[doc(hidden)] pub use config::pretty_print_error;
 
 pub use config::Config;
-pub use crate::logger::LogLevel;
+pub use crate::logger::{LogLevel, LogFormat};
 pub use secret_key::SecretKey;
-pub use tls::TlsConfig;
+pub use tls::{TlsConfig, MutualTls};
 
 #[cfg(test)]
 mod tests {
     use std::net::Ipv4Addr;
     use figment::Figment;
 
-    use crate::config::{Config, TlsConfig};
-    use crate::logger::LogLevel;
+    use crate::config::{Config, SecretKey, TlsConfig, MutualTls};
+    use crate::logger::{LogLevel, LogFormat};
     use crate::data::{Limits, ToByteUnit};
 
     #[test]
@@ -170,7 +170,10 @@ mod tests {
                 port = 1234
                 workers = 20
                 keep_alive = 10
+                read_timeout = 10
+                write_timeout = 30
                 log_level = "off"
+                log_format = "JSON"
                 cli_colors = 0
             "#)?;
 
@@ -180,7 +183,10 @@ mod tests {
                 port: 1234,
                 workers: 20,
                 keep_alive: 10,
+                read_timeout: 10,
+                write_timeout: 30,
                 log_level: LogLevel::Off,
+                log_format: LogFormat::Json,
                 cli_colors: false,
                 ..Config::default()
             });
@@ -271,6 +277,72 @@ mod tests {
         });
     }
 
+    #[test]
+    fn test_tls_watch() {
+        figment::Jail::expect_with(|jail| {
+            jail.create_file("Rocket.toml", r#"
+                [global.tls]
+                certs = "/ssl/cert.pem"
+                key = "/ssl/key.pem"
+                watch = true
+            "#)?;
+
+            let config = Config::from(Config::figment());
+            assert_eq!(config, Config {
+                tls: Some(TlsConfig::from_paths("/ssl/cert.pem", "/ssl/key.pem")
+                    .with_watch(true)),
+                ..Config::default()
+            });
+
+            jail.create_file("Rocket.toml", r#"
+                [global.tls]
+                certs = "/ssl/cert.pem"
+                key = "/ssl/key.pem"
+            "#)?;
+
+            let config = Config::from(Config::figment());
+            assert!(!config.tls.unwrap().watch());
+
+            Ok(())
+        });
+    }
+
+    #[test]
+    fn test_mutual_tls() {
+        figment::Jail::expect_with(|jail| {
+            jail.create_file("Rocket.toml", r#"
+                [global.tls]
+                certs = "/ssl/cert.pem"
+                key = "/ssl/key.pem"
+                mutual = { ca_certs = "/ssl/ca.pem" }
+            "#)?;
+
+            let config = Config::from(Config::figment());
+            assert_eq!(config, Config {
+                tls: Some(TlsConfig::from_paths("/ssl/cert.pem", "/ssl/key.pem")
+                    .with_mutual(MutualTls::from_path("/ssl/ca.pem"))),
+                ..Config::default()
+            });
+
+            jail.create_file("Rocket.toml", r#"
+                [global.tls]
+                certs = "/ssl/cert.pem"
+                key = "/ssl/key.pem"
+                mutual = { ca_certs = "ca.pem", mandatory = true }
+            "#)?;
+
+            let config = Config::from(Config::figment());
+            let mutual = MutualTls::from_path(jail.directory().join("ca.pem"));
+            assert_eq!(config, Config {
+                tls: Some(TlsConfig::from_paths("/ssl/cert.pem", "/ssl/key.pem")
+                    .with_mutual(mutual.mandatory(true))),
+                ..Config::default()
+            });
+
+            Ok(())
+        });
+    }
+
     #[test]
     fn test_profiles_merge() {
         figment::Jail::expect_with(|jail| {
@@ -387,4 +459,22 @@ mod tests {
             Ok(())
         });
     }
+
+    #[test]
+    fn test_secret_key_formats() {
+        let key = |value: String| Figment::from(Config::default())
+            .merge(("secret_key", value))
+            .extract_inner::<SecretKey>("secret_key");
+
+        let b64_256 = "hPRYyVRiMyxpw5sBB1XeCMN1kFsDCqKvBi2QJxBVHQk=".to_string();
+        let b64_512 = format!("{}AQ==", "AQEB".repeat(21));
+        for value in vec![b64_256, b64_512, "ab".repeat(32), "ab".repeat(64)] {
+            assert!(!key(value).unwrap().is_zero());
+        }
+
+        assert!(key("0".repeat(64)).unwrap().is_zero());
+        assert!(key("0".repeat(128)).unwrap().is_zero());
+        assert!(key("ab".repeat(50)).is_err());
+        assert!(key("zz".repeat(64)).is_err());
+    }
 }
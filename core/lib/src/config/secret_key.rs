@@ -1,5 +1,6 @@
 use std::fmt;
 use std::ops::Deref;
+use std::sync::atomic::{compiler_fence, Ordering};
 
 use serde::{de, ser, Deserialize, Serialize};
 
@@ -17,10 +18,14 @@ enum Kind {
 ///
 /// A `SecretKey` is primarily used by [private cookies]. See the [configuration
 /// guide] for further details. It can be configured from 256-bit random
-/// material or a 512-bit master key, each as either a base64-encoded string or
-/// raw bytes. When compiled in debug mode with the `secrets` feature enabled, a
-/// key set a `0` is automatically regenerated from the OS's random source if
-/// available.
+/// material or a 512-bit master key, each as either a base64 or hex-encoded
+/// string or raw bytes. When compiled in debug mode with the `secrets` feature
+/// enabled, a key set a `0` is automatically regenerated from the OS's random
+/// source if available. In any other profile, a missing or `0` key aborts
+/// launch.
+///
+/// The key material is overwritten with zeroes when a `SecretKey` is dropped,
+/// as are the buffers used while decoding it from configuration.
 ///
 /// ```rust
 /// # use rocket::figment::Figment;
@@ -162,21 +167,23 @@ impl<'de> Deserialize<'de> for SecretKey {
             type Value = SecretKey;
 
             fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-                f.write_str("256-bit base64 or hex string, or 32-byte slice")
+                f.write_str("256 or 512-bit base64 or hex string, or 32 or 64-byte slice")
             }
 
             fn visit_str<E: de::Error>(self, val: &str) -> Result<SecretKey, E> {
-                let e = |s| E::invalid_value(Str(s), &"256-bit base64 or hex");
+                let e = |s| E::invalid_value(Str(s), &"256 or 512-bit base64 or hex");
 
                 // `binascii` requires a more space than actual output for padding
                 let mut buf = [0u8; 96];
                 let bytes = match val.len() {
-                    44 | 88 => b64decode(val.as_bytes(), &mut buf).map_err(|_| e(val))?,
-                    64 => hex2bin(val.as_bytes(), &mut buf).map_err(|_| e(val))?,
-                    n => Err(E::invalid_length(n, &"44 or 88 for base64, 64 for hex"))?
+                    44 | 88 => b64decode(val.as_bytes(), &mut buf).map_err(|_| e(val)),
+                    64 | 128 => hex2bin(val.as_bytes(), &mut buf).map_err(|_| e(val)),
+                    n => Err(E::invalid_length(n, &"44 or 88 for base64, 64 or 128 for hex"))
                 };
 
-                self.visit_bytes(bytes)
+                let key = bytes.and_then(|bytes| self.visit_bytes(bytes));
+                zeroize(&mut buf);
+                key
             }
 
             fn visit_bytes<E: de::Error>(self, bytes: &[u8]) -> Result<SecretKey, E> {
@@ -199,7 +206,9 @@ impl<'de> Deserialize<'de> for SecretKey {
                     bytes.push(byte);
                 }
 
-                self.visit_bytes(&bytes)
+                let key = self.visit_bytes(&bytes);
+                zeroize(&mut bytes);
+                key
             }
         }
 
@@ -207,6 +216,25 @@ impl<'de> Deserialize<'de> for SecretKey {
     }
 }
 
+impl Drop for SecretKey {
+    fn drop(&mut self) {
+        // SAFETY: `self.key` is valid for writes and `Key` has no destructor,
+        // so overwriting it in place without dropping it leaks nothing.
+        unsafe { std::ptr::write_volatile(&mut self.key, Key::from(&[0; 64])); }
+        compiler_fence(Ordering::SeqCst);
+    }
+}
+
+/// Overwrites `bytes` with zeroes in a way the compiler won't optimize away.
+fn zeroize(bytes: &mut [u8]) {
+    for byte in bytes.iter_mut() {
+        // SAFETY: `byte` is a valid, aligned, exclusive reference.
+        unsafe { std::ptr::write_volatile(byte, 0); }
+    }
+
+    compiler_fence(Ordering::SeqCst);
+}
+
 impl fmt::Debug for SecretKey {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self.kind {
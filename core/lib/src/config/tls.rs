@@ -23,6 +23,24 @@ use serde::{Deserialize, Serialize};
 ///
 /// When a path is configured in a file source, such as `Rocket.toml`, relative
 /// paths are interpreted as being relative to the source file's directory.
+///
+/// Client certificates can additionally be verified by configuring `mutual`.
+/// See [`MutualTls`] for details.
+///
+/// # Reloading
+///
+/// Certificates can be replaced without restarting the server. On Unix, a
+/// running TLS-enabled server reloads `certs` and `key` when it receives a
+/// `SIGHUP`. If `watch` is set to `true`, the server additionally reloads them
+/// whenever the files configured as `certs` or `key` change. `watch` defaults
+/// to `false`. If reloading fails, the previous certificate remains in use.
+///
+/// ```toml
+/// [global.tls]
+/// certs = "/etc/letsencrypt/live/example.com/fullchain.pem"
+/// key = "/etc/letsencrypt/live/example.com/privkey.pem"
+/// watch = true
+/// ```
 #[derive(PartialEq, Debug, Clone, Deserialize, Serialize)]
 pub struct TlsConfig {
     /// Path or raw bytes for the DER-encoded X.509 TLS certificate chain.
@@ -30,6 +48,57 @@ pub struct TlsConfig {
     /// Path or raw bytes to DER-encoded ASN.1 key in either PKCS#8 or PKCS#1
     /// format.
     pub(crate) key: Either<RelativePathBuf, Vec<u8>>,
+    /// Client certificate verification configuration, if any.
+    #[serde(default)]
+    pub(crate) mutual: Option<MutualTls>,
+    /// Whether to reload `certs` and `key` when the files change.
+    #[serde(default)]
+    pub(crate) watch: bool,
+}
+
+/// Mutual TLS configuration: verification of client certificates.
+///
+/// When configured, clients are asked to present a certificate during the TLS
+/// handshake. A presented certificate is validated against the certificate
+/// authorities in `ca_certs`; handshakes with invalid certificates are
+/// rejected. If `mandatory` is `true`, handshakes from clients that do not
+/// present a certificate are rejected as well. Otherwise, such clients are
+/// accepted and the request-level [`Certificate`] guard forwards. `mandatory`
+/// defaults to `false`.
+///
+/// Like `certs` and `key`, `ca_certs` can be configured as a path or as raw
+/// bytes of PEM-encoded X.509 certificates:
+///
+/// ```toml
+/// [global.tls]
+/// certs = "private/cert.pem"
+/// key = "private/key.pem"
+/// mutual = { ca_certs = "private/ca.pem", mandatory = true }
+/// ```
+///
+/// The following example illustrates manual configuration:
+///
+/// ```rust
+/// # use rocket::figment::Figment;
+/// let figment = Figment::from(rocket::Config::default())
+///     .merge(("tls.certs", "private/cert.pem"))
+///     .merge(("tls.key", "private/key.pem"))
+///     .merge(("tls.mutual.ca_certs", "private/ca.pem"));
+///
+/// let config = rocket::Config::from(figment);
+/// let mutual = config.tls.as_ref().and_then(|tls| tls.mutual()).unwrap();
+/// assert!(mutual.ca_certs().is_left());
+/// assert!(!mutual.is_mandatory());
+/// ```
+///
+/// [`Certificate`]: crate::request::Certificate
+#[derive(PartialEq, Debug, Clone, Deserialize, Serialize)]
+pub struct MutualTls {
+    /// Path or raw bytes to PEM-encoded CA certificates.
+    pub(crate) ca_certs: Either<RelativePathBuf, Vec<u8>>,
+    /// Whether clients must present a valid certificate.
+    #[serde(default)]
+    pub(crate) mandatory: bool,
 }
 
 impl TlsConfig {
@@ -49,7 +118,9 @@ impl TlsConfig {
     {
         TlsConfig {
             certs: Either::Left(certs.as_ref().to_path_buf().into()),
-            key: Either::Left(key.as_ref().to_path_buf().into())
+            key: Either::Left(key.as_ref().to_path_buf().into()),
+            mutual: None,
+            watch: false,
         }
     }
 
@@ -70,10 +141,63 @@ impl TlsConfig {
     pub fn from_bytes(certs: &[u8], key: &[u8]) -> Self {
         TlsConfig {
             certs: Either::Right(certs.to_vec().into()),
-            key: Either::Right(key.to_vec().into())
+            key: Either::Right(key.to_vec().into()),
+            mutual: None,
+            watch: false,
         }
     }
 
+    /// Enables verification of client certificates with the configuration
+    /// `mutual`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::config::{TlsConfig, MutualTls};
+    ///
+    /// let mutual = MutualTls::from_path("/ssl/ca.pem").mandatory(true);
+    /// let tls_config = TlsConfig::from_paths("/ssl/certs.pem", "/ssl/key.pem")
+    ///     .with_mutual(mutual);
+    ///
+    /// assert!(tls_config.mutual().unwrap().is_mandatory());
+    /// ```
+    pub fn with_mutual(mut self, mutual: MutualTls) -> Self {
+        self.mutual = Some(mutual);
+        self
+    }
+
+    /// Sets whether the certificate chain and private key are reloaded when
+    /// the files configured as `certs` or `key` change.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::config::TlsConfig;
+    ///
+    /// let tls_config = TlsConfig::from_paths("/ssl/certs.pem", "/ssl/key.pem")
+    ///     .with_watch(true);
+    ///
+    /// assert!(tls_config.watch());
+    /// ```
+    pub fn with_watch(mut self, watch: bool) -> Self {
+        self.watch = watch;
+        self
+    }
+
+    /// Returns the value of the `watch` parameter.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::config::TlsConfig;
+    ///
+    /// let tls_config = TlsConfig::from_paths("/ssl/certs.pem", "/ssl/key.pem");
+    /// assert!(!tls_config.watch());
+    /// ```
+    pub fn watch(&self) -> bool {
+        self.watch
+    }
+
     /// Returns the value of the `certs` parameter.
     ///
     /// # Example
@@ -118,6 +242,105 @@ impl TlsConfig {
             Either::Right(bytes) => either::Either::Right(&bytes),
         }
     }
+
+    /// Returns the mutual TLS configuration, if any.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::config::TlsConfig;
+    ///
+    /// let tls_config = TlsConfig::from_paths("/ssl/certs.pem", "/ssl/key.pem");
+    /// assert!(tls_config.mutual().is_none());
+    /// ```
+    pub fn mutual(&self) -> Option<&MutualTls> {
+        self.mutual.as_ref()
+    }
+}
+
+impl MutualTls {
+    /// Constructs a `MutualTls` configuration from a path to PEM-encoded CA
+    /// certificates. Client certificates are not mandatory.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::config::MutualTls;
+    ///
+    /// let mutual = MutualTls::from_path("/ssl/ca.pem");
+    /// assert!(!mutual.is_mandatory());
+    /// ```
+    pub fn from_path<C: AsRef<std::path::Path>>(ca_certs: C) -> Self {
+        MutualTls {
+            ca_certs: Either::Left(ca_certs.as_ref().to_path_buf().into()),
+            mandatory: false,
+        }
+    }
+
+    /// Constructs a `MutualTls` configuration from a byte buffer containing
+    /// PEM-encoded CA certificates. Client certificates are not mandatory.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::config::MutualTls;
+    ///
+    /// # let ca_certs_buf = &[];
+    /// let mutual = MutualTls::from_bytes(ca_certs_buf);
+    /// ```
+    pub fn from_bytes(ca_certs: &[u8]) -> Self {
+        MutualTls {
+            ca_certs: Either::Right(ca_certs.to_vec().into()),
+            mandatory: false,
+        }
+    }
+
+    /// Sets whether clients must present a valid certificate.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::config::MutualTls;
+    ///
+    /// let mutual = MutualTls::from_path("/ssl/ca.pem").mandatory(true);
+    /// assert!(mutual.is_mandatory());
+    /// ```
+    pub fn mandatory(mut self, mandatory: bool) -> Self {
+        self.mandatory = mandatory;
+        self
+    }
+
+    /// Returns `true` if clients must present a valid certificate.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::config::MutualTls;
+    ///
+    /// let mutual = MutualTls::from_path("/ssl/ca.pem");
+    /// assert!(!mutual.is_mandatory());
+    /// ```
+    pub fn is_mandatory(&self) -> bool {
+        self.mandatory
+    }
+
+    /// Returns the value of the `ca_certs` parameter.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use std::path::Path;
+    /// use rocket::config::MutualTls;
+    ///
+    /// let mutual = MutualTls::from_path("/ssl/ca.pem");
+    /// assert_eq!(mutual.ca_certs().left().unwrap(), Path::new("/ssl/ca.pem"));
+    /// ```
+    pub fn ca_certs(&self) -> either::Either<std::path::PathBuf, &[u8]> {
+        match &self.ca_certs {
+            Either::Left(path) => either::Either::Left(path.relative()),
+            Either::Right(bytes) => either::Either::Right(&bytes),
+        }
+    }
 }
 
 #[cfg(feature = "tls")]
@@ -126,24 +349,39 @@ type Reader = Box<dyn std::io::BufRead + Sync + Send>;
 #[cfg(feature = "tls")]
 impl TlsConfig {
     pub(crate) fn to_readers(&self) -> std::io::Result<(Reader, Reader)> {
-        use std::{io::{self, Error}, fs};
-        use yansi::Paint;
-
-        fn to_reader(value: &Either<RelativePathBuf, Vec<u8>>) -> io::Result<Reader> {
-            match value {
-                Either::Left(path) => {
-                    let path = path.relative();
-                    let file = fs::File::open(&path).map_err(move |e| {
-                        Error::new(e.kind(), format!("error reading TLS file `{}`: {}",
-                                Paint::white(figment::Source::File(path)), e))
-                    })?;
-
-                    Ok(Box::new(io::BufReader::new(file)))
-                }
-                Either::Right(vec) => Ok(Box::new(io::Cursor::new(vec.clone()))),
-            }
+        Ok((to_reader(&self.certs)?, to_reader(&self.key)?))
+    }
+
+    pub(crate) fn to_client_auth(
+        &self
+    ) -> std::io::Result<Option<crate::http::tls::ClientAuth<Reader>>> {
+        use crate::http::tls::ClientAuth;
+
+        match &self.mutual {
+            Some(m) => Ok(Some(ClientAuth {
+                ca_certs: to_reader(&m.ca_certs)?,
+                mandatory: m.mandatory
+            })),
+            None => Ok(None)
         }
+    }
+}
 
-        Ok((to_reader(&self.certs)?, to_reader(&self.key)?))
+#[cfg(feature = "tls")]
+fn to_reader(value: &Either<RelativePathBuf, Vec<u8>>) -> std::io::Result<Reader> {
+    use std::{io::{self, Error}, fs};
+    use yansi::Paint;
+
+    match value {
+        Either::Left(path) => {
+            let path = path.relative();
+            let file = fs::File::open(&path).map_err(move |e| {
+                Error::new(e.kind(), format!("error reading TLS file `{}`: {}",
+                        Paint::white(figment::Source::File(path)), e))
+            })?;
+
+            Ok(Box::new(io::BufReader::new(file)))
+        }
+        Either::Right(vec) => Ok(Box::new(io::Cursor::new(vec.clone()))),
     }
 }
@@ -1,6 +1,6 @@
 use std::io::Cursor;
 
-use crate::http::hyper;
+use crate::http::hyper::{self, HttpBody};
 use crate::ext::AsyncReadBody;
 use crate::tokio::io::AsyncReadExt;
 use crate::data::data_stream::DataStream;
@@ -44,6 +44,7 @@ pub const PEEK_BYTES: usize = 512;
 pub struct Data {
     buffer: Vec<u8>,
     is_complete: bool,
+    is_known_empty: bool,
     stream: AsyncReadBody,
 }
 
@@ -53,21 +54,35 @@ impl Data {
         // Such a short read timeout is likely no longer necessary, but some
         // kind of idle timeout should be implemented.
 
+        let is_known_empty = body.is_end_stream();
         let stream = AsyncReadBody::from(body);
         let buffer = Vec::with_capacity(PEEK_BYTES / 8);
-        Data { buffer, stream, is_complete: false }
+        Data { buffer, stream, is_complete: false, is_known_empty }
     }
 
     /// This creates a `data` object from a local data source `data`.
     #[inline]
     pub(crate) fn local(data: Vec<u8>) -> Data {
         Data {
+            is_known_empty: data.is_empty(),
             buffer: data,
             stream: AsyncReadBody::empty(),
             is_complete: true,
         }
     }
 
+    /// Replaces the data with its decoding as `encoding`, including any data
+    /// already in the `peek` buffer.
+    #[cfg(feature = "decompression")]
+    pub(crate) fn decode(&mut self, encoding: crate::data::Encoding) {
+        use crate::data::decode::Decoder;
+
+        let buffer = Cursor::new(std::mem::take(&mut self.buffer));
+        let stream = std::mem::replace(&mut self.stream, AsyncReadBody::empty());
+        self.stream = AsyncReadBody::reader(Decoder::new(buffer.chain(stream), encoding));
+        self.is_complete = false;
+    }
+
     /// Returns the raw data stream, limited to `limit` bytes.
     ///
     /// The stream contains all of the data in the body of the request,
@@ -193,4 +208,57 @@ impl Data {
     pub fn peek_complete(&self) -> bool {
         self.is_complete
     }
+
+    /// Returns `true` if the request is known to have no body: it was sent
+    /// without one, with a `Content-Length` of `0`, or the `peek` buffer is
+    /// complete and empty. Returns `false` if the request has a body or if it
+    /// is not known if it does, as with an unread chunked body.
+    ///
+    /// This method never reads from the data stream. It can be used to
+    /// distinguish a request without a payload from one with an empty
+    /// payload, such as `{}`, before parsing the body. Data guards wrapped in
+    /// an `Option` make this distinction automatically: `Option<T>` is `None`
+    /// for a request known to have no body.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # #[macro_use] extern crate rocket;
+    /// use rocket::data::Data;
+    ///
+    /// #[patch("/profile", data = "<data>")]
+    /// fn update(data: Data) -> &'static str {
+    ///     match data.is_empty() {
+    ///         true => "no update",
+    ///         false => "updating",
+    ///     }
+    /// }
+    /// # fn main() { }
+    /// ```
+    #[inline(always)]
+    pub fn is_empty(&self) -> bool {
+        self.is_known_empty || (self.is_complete && self.buffer.is_empty())
+    }
+}
+
+impl From<Vec<u8>> for Data {
+    /// Creates a `Data` whose body is `bytes`, as if `bytes` were received as
+    /// the body of a request. This allows a handler that reads and inspects a
+    /// request's body to pass it on to another handler.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::data::Data;
+    ///
+    /// # rocket::async_test(async {
+    /// let mut data = Data::from(b"hello".to_vec());
+    /// assert!(data.peek_complete());
+    /// assert_eq!(data.peek(512).await, b"hello");
+    /// # })
+    /// ```
+    #[inline(always)]
+    fn from(bytes: Vec<u8>) -> Data {
+        Data::local(bytes)
+    }
 }
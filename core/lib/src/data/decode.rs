@@ -0,0 +1,305 @@
+use std::io;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+use tokio::io::AsyncRead;
+use flate2::{Crc, Decompress, FlushDecompress, Status};
+use brotli_decompressor::{BrotliDecompressStream, BrotliResult, BrotliState};
+use brotli_decompressor::{HeapAlloc, HuffmanCode};
+
+/// The number of encoded bytes read from the inner stream at a time.
+const INPUT_CHUNK: usize = 8 * 1024;
+
+/// The maximum number of decoded bytes produced at a time. Bounding this, as
+/// opposed to the size of a decoded body, keeps a highly compressed body from
+/// exhausting memory before size limits apply to the decoded stream.
+const OUTPUT_CHUNK: usize = 8 * 1024;
+
+/// A `Content-Encoding` of a request body that can be decoded.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub(crate) enum Encoding {
+    Gzip,
+    Deflate,
+    Brotli,
+}
+
+impl Encoding {
+    /// Returns the encoding named by the value of a `Content-Encoding` header,
+    /// if it is a single, supported encoding. `identity` is not an encoding.
+    pub(crate) fn parse(value: &str) -> Option<Encoding> {
+        match value.trim() {
+            v if v.eq_ignore_ascii_case("gzip") || v.eq_ignore_ascii_case("x-gzip") => {
+                Some(Encoding::Gzip)
+            }
+            v if v.eq_ignore_ascii_case("deflate") => Some(Encoding::Deflate),
+            v if v.eq_ignore_ascii_case("br") => Some(Encoding::Brotli),
+            _ => None,
+        }
+    }
+}
+
+type Brotli = BrotliState<HeapAlloc<u8>, HeapAlloc<u32>, HeapAlloc<HuffmanCode>>;
+
+enum Codec {
+    /// Waiting for the complete gzip header.
+    GzipHeader,
+    /// Inflating the deflate stream of a gzip member.
+    Gzip(Decompress, Crc),
+    /// Waiting for the gzip trailer to check the decoded data against.
+    GzipTrailer(Crc),
+    /// Inflating a zlib stream, as `deflate` is defined to be.
+    Zlib(Decompress),
+    Brotli(Box<Brotli>),
+    Done,
+}
+
+/// The result of one decoding step.
+enum Step {
+    Progress,
+    NeedInput,
+}
+
+/// A stream that decodes the encoded stream `R`.
+pub(crate) struct Decoder<R> {
+    inner: R,
+    codec: Codec,
+    input: Vec<u8>,
+    input_pos: usize,
+    eof: bool,
+    output: Vec<u8>,
+    output_pos: usize,
+}
+
+fn invalid<E: std::fmt::Display>(error: E) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, error.to_string())
+}
+
+/// Returns the length of the gzip header at the start of `buf`, or `None` if
+/// `buf` doesn't contain the complete header.
+fn gzip_header_len(buf: &[u8]) -> io::Result<Option<usize>> {
+    const FHCRC: u8 = 0x02;
+    const FEXTRA: u8 = 0x04;
+    const FNAME: u8 = 0x08;
+    const FCOMMENT: u8 = 0x10;
+
+    if buf.len() < 10 {
+        return Ok(None);
+    }
+
+    if buf[0] != 0x1f || buf[1] != 0x8b || buf[2] != 8 {
+        return Err(invalid("invalid gzip header"));
+    }
+
+    let (flags, mut len) = (buf[3], 10);
+    if flags & FEXTRA != 0 {
+        match buf.get(len..len + 2) {
+            Some(xlen) => len += 2 + (xlen[0] as usize | (xlen[1] as usize) << 8),
+            None => return Ok(None),
+        }
+    }
+
+    for &flag in &[FNAME, FCOMMENT] {
+        if flags & flag != 0 {
+            match buf.get(len..).and_then(|rest| rest.iter().position(|&b| b == 0)) {
+                Some(i) => len += i + 1,
+                None => return Ok(None),
+            }
+        }
+    }
+
+    if flags & FHCRC != 0 {
+        len += 2;
+    }
+
+    Ok(if buf.len() >= len { Some(len) } else { None })
+}
+
+fn le_u32(bytes: &[u8]) -> u32 {
+    bytes.iter().rev().fold(0, |acc, &b| acc << 8 | b as u32)
+}
+
+impl<R: AsyncRead + Unpin> Decoder<R> {
+    pub(crate) fn new(inner: R, encoding: Encoding) -> Self {
+        let codec = match encoding {
+            Encoding::Gzip => Codec::GzipHeader,
+            Encoding::Deflate => Codec::Zlib(Decompress::new(true)),
+            Encoding::Brotli => Codec::Brotli(Box::new(BrotliState::new(
+                HeapAlloc::new(0),
+                HeapAlloc::new(0),
+                HeapAlloc::new(HuffmanCode::default()),
+            ))),
+        };
+
+        Decoder {
+            inner,
+            codec,
+            input: Vec::with_capacity(INPUT_CHUNK),
+            input_pos: 0,
+            eof: false,
+            output: Vec::with_capacity(OUTPUT_CHUNK),
+            output_pos: 0,
+        }
+    }
+
+    /// Inflates from the input into the output with `inflate`.
+    fn inflate(
+        &mut self,
+        inflate: &mut Decompress,
+        crc: Option<&mut Crc>
+    ) -> io::Result<(Step, bool)> {
+        let (in_before, out_before) = (inflate.total_in(), inflate.total_out());
+        self.output.resize(OUTPUT_CHUNK, 0);
+        let status = inflate.decompress(&self.input[self.input_pos..], &mut self.output,
+            FlushDecompress::None).map_err(invalid)?;
+
+        let consumed = (inflate.total_in() - in_before) as usize;
+        let produced = (inflate.total_out() - out_before) as usize;
+        self.input_pos += consumed;
+        self.output.truncate(produced);
+        if let Some(crc) = crc {
+            crc.update(&self.output);
+        }
+
+        let step = match consumed == 0 && produced == 0 {
+            true => Step::NeedInput,
+            false => Step::Progress,
+        };
+
+        Ok((step, status == Status::StreamEnd))
+    }
+
+    /// Decodes some of the input into the output, replacing the output.
+    fn step(&mut self) -> io::Result<Step> {
+        self.output.clear();
+        self.output_pos = 0;
+
+        match std::mem::replace(&mut self.codec, Codec::Done) {
+            Codec::GzipHeader => match gzip_header_len(&self.input[self.input_pos..])? {
+                Some(len) => {
+                    self.input_pos += len;
+                    self.codec = Codec::Gzip(Decompress::new(false), Crc::new());
+                    Ok(Step::Progress)
+                }
+                None => {
+                    self.codec = Codec::GzipHeader;
+                    Ok(Step::NeedInput)
+                }
+            },
+            Codec::Gzip(mut inflate, mut crc) => {
+                let (step, end) = self.inflate(&mut inflate, Some(&mut crc))?;
+                self.codec = match end {
+                    true => Codec::GzipTrailer(crc),
+                    false => Codec::Gzip(inflate, crc),
+                };
+
+                Ok(if end { Step::Progress } else { step })
+            }
+            Codec::GzipTrailer(crc) => match self.input.get(self.input_pos..self.input_pos + 8) {
+                Some(trailer) => {
+                    if le_u32(&trailer[..4]) != crc.sum() || le_u32(&trailer[4..]) != crc.amount() {
+                        return Err(invalid("gzip checksum mismatch"));
+                    }
+
+                    self.input_pos += 8;
+                    Ok(Step::Progress)
+                }
+                None => {
+                    self.codec = Codec::GzipTrailer(crc);
+                    Ok(Step::NeedInput)
+                }
+            },
+            Codec::Zlib(mut inflate) => {
+                let (step, end) = self.inflate(&mut inflate, None)?;
+                if !end {
+                    self.codec = Codec::Zlib(inflate);
+                }
+
+                Ok(if end { Step::Progress } else { step })
+            }
+            Codec::Brotli(mut state) => {
+                let mut available_in = self.input.len() - self.input_pos;
+                let mut input_offset = self.input_pos;
+                let (mut available_out, mut output_offset, mut total_out) = (OUTPUT_CHUNK, 0, 0);
+                self.output.resize(OUTPUT_CHUNK, 0);
+                let result = BrotliDecompressStream(
+                    &mut available_in, &mut input_offset, &self.input,
+                    &mut available_out, &mut output_offset, &mut self.output,
+                    &mut total_out, &mut *state
+                );
+
+                let consumed = input_offset - self.input_pos;
+                self.input_pos = input_offset;
+                self.output.truncate(output_offset);
+                match result {
+                    BrotliResult::ResultSuccess => Ok(Step::Progress),
+                    BrotliResult::ResultFailure => Err(invalid("invalid brotli stream")),
+                    BrotliResult::NeedsMoreInput if consumed == 0 && output_offset == 0 => {
+                        self.codec = Codec::Brotli(state);
+                        Ok(Step::NeedInput)
+                    }
+                    BrotliResult::NeedsMoreInput | BrotliResult::NeedsMoreOutput => {
+                        self.codec = Codec::Brotli(state);
+                        Ok(Step::Progress)
+                    }
+                }
+            }
+            Codec::Done => Ok(Step::Progress),
+        }
+    }
+
+    /// Reads more encoded bytes from the inner stream, keeping unconsumed ones.
+    fn poll_fill(&mut self, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        self.input.drain(..self.input_pos);
+        self.input_pos = 0;
+
+        let len = self.input.len();
+        self.input.resize(len + INPUT_CHUNK, 0);
+        let result = Pin::new(&mut self.inner).poll_read(cx, &mut self.input[len..]);
+        let read = match result {
+            Poll::Ready(Ok(n)) => n,
+            Poll::Ready(Err(e)) => { self.input.truncate(len); return Poll::Ready(Err(e)) },
+            Poll::Pending => { self.input.truncate(len); return Poll::Pending },
+        };
+
+        self.input.truncate(len + read);
+        self.eof = read == 0;
+        Poll::Ready(Ok(()))
+    }
+}
+
+impl<R: AsyncRead + Unpin> AsyncRead for Decoder<R> {
+    fn poll_read(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut [u8]
+    ) -> Poll<io::Result<usize>> {
+        let this = self.get_mut();
+        loop {
+            if this.output_pos < this.output.len() {
+                let available = &this.output[this.output_pos..];
+                let n = std::cmp::min(available.len(), buf.len());
+                buf[..n].copy_from_slice(&available[..n]);
+                this.output_pos += n;
+                return Poll::Ready(Ok(n));
+            }
+
+            if let Codec::Done = this.codec {
+                return Poll::Ready(Ok(0));
+            }
+
+            let step = match this.input_pos < this.input.len() {
+                true => this.step()?,
+                false => Step::NeedInput,
+            };
+
+            if let Step::NeedInput = step {
+                if this.eof {
+                    let error = io::Error::new(io::ErrorKind::UnexpectedEof, "truncated body");
+                    return Poll::Ready(Err(error));
+                }
+
+                futures::ready!(this.poll_fill(cx))?;
+            }
+        }
+    }
+}
@@ -289,9 +289,12 @@ pub type FromDataFuture<'fut, T, E> = BoxFuture<'fut, Outcome<T, E>>;
 ///
 ///   * **Option&lt;T>** _where_ **T: FromTransformedData**
 ///
-///     The type `T` is derived from the incoming data using `T`'s `FromTransformedData`
-///     implementation. If the derivation is a `Success`, the derived value is
-///     returned in `Some`. Otherwise, a `None` is returned.
+///     If the request is known to have no body, as determined by
+///     [`Data::is_empty()`], `None` is returned without deriving `T`.
+///     Otherwise, the type `T` is derived from the incoming data using `T`'s
+///     `FromTransformedData` implementation. If the derivation is a `Success`,
+///     the derived value is returned in `Some`. Otherwise, a `None` is
+///     returned.
 ///
 ///     _This implementation always returns successfully._
 ///
@@ -590,11 +593,19 @@ impl<'r, T: FromTransformedData<'r> + 'r> FromTransformedData<'r> for Option<T>
 
     #[inline(always)]
     fn transform(r: &'r Request<'_>, d: Data) -> TransformFuture<'r, Self::Owned, Self::Error> {
+        if d.is_empty() {
+            return Box::pin(ready(Transform::Owned(Forward(d))));
+        }
+
         T::transform(r, d)
     }
 
     #[inline(always)]
     fn from_data(r: &'r Request<'_>, o: Transformed<'r, Self>) -> FromDataFuture<'r, Self, Self::Error> {
+        if let Transform::Owned(Forward(_)) = o {
+            return Box::pin(ready(Success(None)));
+        }
+
         Box::pin(T::from_data(r, o).map(|x| match x {
             Success(val) => Success(Some(val)),
             Failure(_) | Forward(_) => Success(None),
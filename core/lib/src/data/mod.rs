@@ -4,6 +4,8 @@ mod data;
 mod data_stream;
 mod from_data;
 mod limits;
+#[cfg(feature = "decompression")]
+mod decode;
 
 pub use self::data::Data;
 pub use self::data_stream::DataStream;
@@ -11,3 +13,6 @@ pub use self::from_data::{FromData, Outcome, FromTransformedData, FromDataFuture
 pub use self::from_data::{Transform, Transformed, TransformFuture};
 pub use self::limits::Limits;
 pub use ubyte::{ByteUnit, ToByteUnit};
+
+#[cfg(feature = "decompression")]
+pub(crate) use self::decode::Encoding;
@@ -0,0 +1,142 @@
+//! Work deferred until a response has been sent.
+//!
+//! Work such as audit logging, cache population, or calling webhooks often
+//! needn't delay a response. Instead of spawning a task or thread by hand, a
+//! handler defers the work via the [`Deferrer`] request guard, and a fairing,
+//! guard, or responder via [`Request::defer()`] or [`Response::on_complete()`].
+//! Deferred work runs on Rocket's runtime once the response has been written to
+//! the client in its entirety, or once writing it has failed. For requests
+//! dispatched by a [local client](crate::local), it runs before the dispatch
+//! completes.
+//!
+//! The work deferred by a request runs in the order in which it was deferred,
+//! that deferred by the request before that deferred by the response. A task
+//! that returns an `Err` or panics is logged and doesn't affect other tasks.
+//!
+//! [`Request::defer()`]: crate::Request::defer()
+//! [`Response::on_complete()`]: crate::Response::on_complete()
+//!
+//! # Example
+//!
+//! ```rust
+//! # #[macro_use] extern crate rocket;
+//! use rocket::deferred::Deferrer;
+//!
+//! # async fn notify_webhook(_: &str) -> std::io::Result<()> { Ok(()) }
+//! #[post("/publish", data = "<post>")]
+//! fn publish(post: String, deferrer: Deferrer<'_>) -> &'static str {
+//!     deferrer.defer(move || async move {
+//!         notify_webhook(&post).await
+//!     });
+//!
+//!     "published"
+//! }
+//! ```
+
+use std::fmt;
+use std::future::Future;
+use std::panic::AssertUnwindSafe;
+
+use futures::future::{BoxFuture, FutureExt};
+use parking_lot::Mutex;
+
+use crate::request::{FromRequest, Outcome, Request};
+
+/// The result of deferred work: `()`, or a `Result` whose error is logged.
+pub trait TaskResult: Send + 'static {
+    #[doc(hidden)]
+    fn log_error(self);
+}
+
+impl TaskResult for () {
+    fn log_error(self) { }
+}
+
+impl<E: fmt::Display + Send + 'static> TaskResult for Result<(), E> {
+    fn log_error(self) {
+        if let Err(e) = self {
+            error_!("Deferred task failed: {}", e);
+        }
+    }
+}
+
+pub(crate) type Task = BoxFuture<'static, ()>;
+
+/// Returns a task running `f` to completion, logging its failure.
+pub(crate) fn task<F, Fut>(f: F) -> Task
+    where F: FnOnce() -> Fut + Send + 'static,
+          Fut: Future + Send + 'static,
+          Fut::Output: TaskResult
+{
+    async move {
+        match AssertUnwindSafe(async move { f().await }).catch_unwind().await {
+            Ok(result) => result.log_error(),
+            Err(_) => error_!("A deferred task panicked."),
+        }
+    }.boxed()
+}
+
+/// The work deferred by a request, stored in its local cache.
+#[derive(Default)]
+pub(crate) struct Deferred(Mutex<Vec<Task>>);
+
+impl Deferred {
+    pub(crate) fn push(&self, task: Task) {
+        self.0.lock().push(task);
+    }
+
+    /// Returns a future running the work deferred by `request` followed by
+    /// `tasks`, in order, or `None` if there is no such work.
+    pub(crate) fn run(
+        request: &Request<'_>,
+        tasks: Vec<Task>
+    ) -> Option<impl Future<Output = ()> + Send + 'static> {
+        let mut all = std::mem::take(&mut *request.local_cache(Deferred::default).0.lock());
+        all.extend(tasks);
+        if all.is_empty() {
+            return None;
+        }
+
+        Some(async move {
+            for task in all {
+                task.await;
+            }
+        })
+    }
+}
+
+/// Request guard for deferring work until the response has been sent.
+///
+/// See the [module level docs](self) for details and an example. This guard
+/// always succeeds.
+#[derive(Clone, Copy)]
+pub struct Deferrer<'r> {
+    deferred: &'r Deferred,
+}
+
+impl Deferrer<'_> {
+    /// Defers `f` until the response to the request has been sent. See
+    /// [`Request::defer()`](crate::Request::defer()).
+    pub fn defer<F, Fut>(&self, f: F)
+        where F: FnOnce() -> Fut + Send + 'static,
+              Fut: Future + Send + 'static,
+              Fut::Output: TaskResult
+    {
+        self.deferred.push(task(f));
+    }
+}
+
+impl fmt::Debug for Deferrer<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Deferrer").field("tasks", &self.deferred.0.lock().len()).finish()
+    }
+}
+
+#[crate::async_trait]
+impl<'a, 'r> FromRequest<'a, 'r> for Deferrer<'a> {
+    type Error = std::convert::Infallible;
+
+    async fn from_request(request: &'a Request<'r>) -> Outcome<Self, Self::Error> {
+        Outcome::Success(Deferrer { deferred: request.local_cache(Deferred::default) })
+    }
+}
@@ -0,0 +1,205 @@
+//! Typed, in-process events between fairings and handlers.
+//!
+//! Events decouple the code that observes something from the code that reacts
+//! to it. A handler or fairing _publishes_ an event, a value of any type, via a
+//! [`Publisher`]. Subscribers to the event's type, registered on a
+//! [`Rocket`](crate::Rocket) instance via
+//! [`Rocket::subscribe()`](crate::Rocket::subscribe()) or
+//! [`Rocket::subscribe_async()`](crate::Rocket::subscribe_async()), usually by
+//! a fairing when it's attached, then receive the event.
+//!
+//! Events are delivered once the response to the request that published them
+//! has been sent, so subscribers never add to the latency of a response. Events
+//! are delivered in the order they were published, and each event to its
+//! subscribers in the order they subscribed. A subscriber that panics is
+//! logged and doesn't prevent delivery to other subscribers. Events published
+//! by a request whose response isn't sent, for instance because the client
+//! disconnected, are still delivered.
+//!
+//! # Example
+//!
+//! A handler publishes a `UserRegistered` event, which is consumed by a
+//! fairing that sends a welcome email:
+//!
+//! ```rust
+//! # #[macro_use] extern crate rocket;
+//! use rocket::Rocket;
+//! use rocket::events::Publisher;
+//! use rocket::fairing::AdHoc;
+//!
+//! struct UserRegistered {
+//!     email: String,
+//! }
+//!
+//! #[post("/register", data = "<email>")]
+//! fn register(email: String, events: Publisher<'_>) -> &'static str {
+//!     /* create the user... */
+//!     events.publish(UserRegistered { email });
+//!     "Welcome!"
+//! }
+//!
+//! fn welcome_emails() -> AdHoc {
+//!     AdHoc::on_attach("Welcome Emails", |rocket| async {
+//!         Ok(rocket.subscribe_async(|event: std::sync::Arc<UserRegistered>| async move {
+//!             /* send an email to `event.email`... */
+//!         }))
+//!     })
+//! }
+//!
+//! #[launch]
+//! fn rocket() -> Rocket {
+//!     rocket::ignite()
+//!         .mount("/", routes![register])
+//!         .attach(welcome_emails())
+//! }
+//! ```
+
+use std::any::{Any, TypeId};
+use std::collections::HashMap;
+use std::future::Future;
+use std::panic::AssertUnwindSafe;
+use std::sync::Arc;
+
+use futures::future::{BoxFuture, FutureExt};
+use parking_lot::Mutex;
+
+use crate::request::{FromRequest, Outcome, Request};
+
+type Event = Arc<dyn Any + Send + Sync>;
+
+type Subscriber = Arc<dyn Fn(Event) -> BoxFuture<'static, ()> + Send + Sync>;
+
+/// The subscribers registered on a `Rocket` instance, by event type.
+#[derive(Default)]
+pub(crate) struct Events {
+    subscribers: HashMap<TypeId, Vec<(&'static str, Subscriber)>>,
+}
+
+impl Events {
+    pub(crate) fn subscribe<E, F>(&mut self, f: F)
+        where E: Send + Sync + 'static, F: Fn(&E) + Send + Sync + 'static
+    {
+        let f = Arc::new(f);
+        self.subscribe_async(move |event: Arc<E>| {
+            let f = f.clone();
+            async move { f(&event) }
+        });
+    }
+
+    pub(crate) fn subscribe_async<E, F, Fut>(&mut self, f: F)
+        where E: Send + Sync + 'static,
+              F: Fn(Arc<E>) -> Fut + Send + Sync + 'static,
+              Fut: Future<Output = ()> + Send + 'static
+    {
+        let subscriber: Subscriber = Arc::new(move |event: Event| {
+            match event.downcast::<E>() {
+                Ok(event) => f(event).boxed(),
+                Err(_) => futures::future::ready(()).boxed(),
+            }
+        });
+
+        self.subscribers.entry(TypeId::of::<E>())
+            .or_default()
+            .push((std::any::type_name::<E>(), subscriber));
+    }
+
+    /// Returns a future that delivers the events published by `request` to
+    /// their subscribers.
+    pub(crate) fn deliver(
+        &self,
+        request: &Request<'_>
+    ) -> impl Future<Output = ()> + Send + 'static {
+        let published = std::mem::take(&mut *Publisher::of(request).pending.0.lock());
+        let deliveries: Vec<_> = published.into_iter()
+            .flat_map(|(id, event)| {
+                self.subscribers.get(&id)
+                    .into_iter()
+                    .flatten()
+                    .map(move |(name, subscriber)| (*name, subscriber(event.clone())))
+            })
+            .collect();
+
+        async move {
+            for (name, delivery) in deliveries {
+                if AssertUnwindSafe(delivery).catch_unwind().await.is_err() {
+                    error_!("A subscriber to `{}` events panicked.", name);
+                }
+            }
+        }
+    }
+
+    pub(crate) fn is_empty(&self) -> bool {
+        self.subscribers.is_empty()
+    }
+}
+
+/// Request guard for publishing events.
+///
+/// Events published via a `Publisher` are delivered to their subscribers once
+/// the response to the request has been sent. See the [module level
+/// docs](self) for details and an example. Fairings, which don't use request
+/// guards, retrieve a `Publisher` via [`Publisher::of()`].
+///
+/// This guard always succeeds.
+#[derive(Debug, Clone, Copy)]
+pub struct Publisher<'r> {
+    pending: &'r Pending,
+}
+
+#[derive(Default)]
+struct Pending(Mutex<Vec<(TypeId, Event)>>);
+
+impl std::fmt::Debug for Pending {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Pending").field("events", &self.0.lock().len()).finish()
+    }
+}
+
+impl<'r> Publisher<'r> {
+    /// Returns the publisher for events of `request`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::events::Publisher;
+    /// use rocket::fairing::AdHoc;
+    ///
+    /// struct Served(u16);
+    ///
+    /// let fairing = AdHoc::on_response("Statistics", |req, res| Box::pin(async move {
+    ///     Publisher::of(req).publish(Served(res.status().code));
+    /// }));
+    /// ```
+    pub fn of(request: &'r Request<'_>) -> Publisher<'r> {
+        Publisher { pending: request.local_cache(Pending::default) }
+    }
+
+    /// Publishes `event` to the subscribers of events of type `E`. The event is
+    /// discarded if there are none.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # #[macro_use] extern crate rocket;
+    /// use rocket::events::Publisher;
+    ///
+    /// struct Liked(usize);
+    ///
+    /// #[post("/<id>/like")]
+    /// fn like(id: usize, events: Publisher<'_>) {
+    ///     events.publish(Liked(id));
+    /// }
+    /// ```
+    pub fn publish<E: Send + Sync + 'static>(&self, event: E) {
+        self.pending.0.lock().push((TypeId::of::<E>(), Arc::new(event)));
+    }
+}
+
+#[crate::async_trait]
+impl<'a, 'r> FromRequest<'a, 'r> for Publisher<'a> {
+    type Error = std::convert::Infallible;
+
+    async fn from_request(request: &'a Request<'r>) -> Outcome<Self, Self::Error> {
+        Outcome::Success(Publisher::of(request))
+    }
+}
@@ -54,12 +54,19 @@ enum State {
     Pending,
     Partial(Cursor<Bytes>),
     Done,
+    #[cfg(feature = "decompression")]
+    Reader(Pin<Box<dyn AsyncRead + Send>>),
 }
 
 impl AsyncReadBody {
     pub fn empty() -> Self {
         Self { inner: hyper::Body::empty(), state: State::Done }
     }
+
+    #[cfg(feature = "decompression")]
+    pub fn reader<R: AsyncRead + Send + 'static>(reader: R) -> Self {
+        Self { inner: hyper::Body::empty(), state: State::Reader(Box::pin(reader)) }
+    }
 }
 
 impl From<hyper::Body> for AsyncReadBody {
@@ -95,6 +102,8 @@ impl AsyncRead for AsyncReadBody {
                     }
                 }
                 State::Done => return Poll::Ready(Ok(0)),
+                #[cfg(feature = "decompression")]
+                State::Reader(ref mut reader) => return reader.as_mut().poll_read(cx, buf),
             }
         }
     }
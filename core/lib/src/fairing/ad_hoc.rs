@@ -2,8 +2,8 @@ use std::sync::Mutex;
 
 use futures::future::{Future, BoxFuture};
 
-use crate::{Rocket, Request, Response, Data};
-use crate::fairing::{Fairing, Kind, Info};
+use crate::{Rocket, Request, Response, Data, Orbit};
+use crate::fairing::{Fairing, Kind, Info, BodyReader};
 
 /// A ad-hoc fairing that can be created from a function or closure.
 ///
@@ -13,9 +13,10 @@ use crate::fairing::{Fairing, Kind, Info};
 /// # Usage
 ///
 /// Use the [`on_attach`](#method.on_attach), [`on_launch`](#method.on_launch),
-/// [`on_request`](#method.on_request), or [`on_response`](#method.on_response)
-/// constructors to create an `AdHoc` structure from a function or closure.
-/// Then, simply attach the structure to the `Rocket` instance.
+/// [`on_request`](#method.on_request), [`on_response`](#method.on_response), or
+/// [`on_response_body`](#method.on_response_body) constructors to create an
+/// `AdHoc` structure from a function or closure. Then, simply attach the
+/// structure to the `Rocket` instance.
 ///
 /// # Example
 ///
@@ -67,7 +68,7 @@ enum AdHocKind {
         -> BoxFuture<'static, Result<Rocket, Rocket>> + Send + 'static>>>),
 
     /// An ad-hoc **launch** fairing. Called just before Rocket launches.
-    Launch(Mutex<Option<Box<dyn FnOnce(&Rocket) + Send + 'static>>>),
+    Launch(Mutex<Option<Box<dyn FnOnce(&Rocket<Orbit>) + Send + 'static>>>),
 
     /// An ad-hoc **request** fairing. Called when a request is received.
     Request(Box<dyn for<'a> Fn(&'a mut Request<'_>, &'a Data)
@@ -77,6 +78,11 @@ enum AdHocKind {
     /// sent to a client.
     Response(Box<dyn for<'a> Fn(&'a Request<'_>, &'a mut Response<'_>)
         -> BoxFuture<'a, ()> + Send + Sync + 'static>),
+
+    /// An ad-hoc **response body** fairing. Called with the body of a response
+    /// that is ready to be sent to a client.
+    ResponseBody(Box<dyn for<'a> Fn(&'a Request<'_>, BodyReader<'a>)
+        -> BodyReader<'a> + Send + Sync + 'static>),
 }
 
 impl AdHoc {
@@ -137,6 +143,55 @@ impl AdHoc {
         })
     }
 
+    /// Constructs an `AdHoc` attach fairing that extracts a configuration of
+    /// type `T` from the value at `section` in the configured provider and
+    /// stores it in managed state. If the section is missing or extraction
+    /// fails, pretty-prints the error message and errors the attach fairing,
+    /// aborting launch.
+    ///
+    /// `section` is a key path, so `"app.database"` refers to the `database`
+    /// table nested in the `app` table.
+    ///
+    /// # Example
+    ///
+    /// With the following in `Rocket.toml`:
+    ///
+    /// ```toml
+    /// [default.mailer]
+    /// host = "smtp.example.com"
+    /// port = 587
+    /// ```
+    ///
+    /// The `mailer` section can be extracted and managed with:
+    ///
+    /// ```rust
+    /// use serde::Deserialize;
+    /// use rocket::fairing::AdHoc;
+    ///
+    /// #[derive(Deserialize)]
+    /// struct MailerConfig {
+    ///     host: String,
+    ///     port: u16,
+    /// }
+    ///
+    /// let fairing = AdHoc::config_section::<MailerConfig>("mailer");
+    /// ```
+    pub fn config_section<'de, T>(section: &'static str) -> AdHoc
+        where T: serde::Deserialize<'de> + Send + Sync + 'static
+    {
+        AdHoc::on_attach(std::any::type_name::<T>(), move |rocket| async move {
+            let app_config = match rocket.figment().extract_inner::<T>(section) {
+                Ok(config) => config,
+                Err(e) => {
+                    crate::config::pretty_print_error(e);
+                    return Err(rocket);
+                }
+            };
+
+            Ok(rocket.manage(app_config))
+        })
+    }
+
     /// Constructs an `AdHoc` launch fairing named `name`. The function `f` will
     /// be called by Rocket just prior to launching.
     ///
@@ -151,7 +206,7 @@ impl AdHoc {
     /// });
     /// ```
     pub fn on_launch<F: Send + 'static>(name: &'static str, f: F) -> AdHoc
-        where F: FnOnce(&Rocket)
+        where F: FnOnce(&Rocket<Orbit>)
     {
         AdHoc { name, kind: AdHocKind::Launch(Mutex::new(Some(Box::new(f)))) }
     }
@@ -212,6 +267,27 @@ impl AdHoc {
     {
         AdHoc { name, kind: AdHocKind::Response(Box::new(f)) }
     }
+
+    /// Constructs an `AdHoc` response body fairing named `name`. The function
+    /// `f` will be called by Rocket with the body of a response that is ready
+    /// to be sent. The reader returned by `f` replaces the response body.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use tokio::io::AsyncReadExt;
+    /// use rocket::fairing::AdHoc;
+    ///
+    /// // Truncate every response body to at most 1KiB.
+    /// let fairing = AdHoc::on_response_body("Truncate", |_, body| {
+    ///     Box::pin(body.take(1024))
+    /// });
+    /// ```
+    pub fn on_response_body<F: Send + Sync + 'static>(name: &'static str, f: F) -> AdHoc
+        where F: for<'a> Fn(&'a Request<'_>, BodyReader<'a>) -> BodyReader<'a>
+    {
+        AdHoc { name, kind: AdHocKind::ResponseBody(Box::new(f)) }
+    }
 }
 
 #[crate::async_trait]
@@ -222,6 +298,7 @@ impl Fairing for AdHoc {
             AdHocKind::Launch(_) => Kind::Launch,
             AdHocKind::Request(_) => Kind::Request,
             AdHocKind::Response(_) => Kind::Response,
+            AdHocKind::ResponseBody(_) => Kind::ResponseBody,
         };
 
         Info { name: self.name, kind }
@@ -239,7 +316,7 @@ impl Fairing for AdHoc {
         }
     }
 
-    fn on_launch(&self, state: &Rocket) {
+    fn on_launch(&self, state: &Rocket<Orbit>) {
         if let AdHocKind::Launch(ref mutex) = self.kind {
             let mut opt = mutex.lock().expect("AdHoc::Launch lock");
             let f = opt.take().expect("internal error: `on_launch` one-call invariant broken");
@@ -258,4 +335,11 @@ impl Fairing for AdHoc {
             callback(req, res).await;
         }
     }
+
+    fn on_response_body<'r>(&self, req: &'r Request<'_>, body: BodyReader<'r>) -> BodyReader<'r> {
+        match self.kind {
+            AdHocKind::ResponseBody(ref callback) => callback(req, body),
+            _ => body,
+        }
+    }
 }
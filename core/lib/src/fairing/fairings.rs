@@ -1,5 +1,6 @@
-use crate::{Rocket, Request, Response, Data};
-use crate::fairing::{Fairing, Kind};
+use crate::{Rocket, Request, Response, Data, Orbit};
+use crate::fairing::{Fairing, Kind, BodyReader};
+use crate::response::{Body, DEFAULT_CHUNK_SIZE};
 use crate::logger::PaintExt;
 
 use yansi::Paint;
@@ -12,6 +13,7 @@ pub struct Fairings {
     launch: Vec<usize>,
     request: Vec<usize>,
     response: Vec<usize>,
+    response_body: Vec<usize>,
 }
 
 impl Fairings {
@@ -42,6 +44,7 @@ impl Fairings {
             if kind.is(Kind::Launch) { self.launch.push(index); }
             if kind.is(Kind::Request) { self.request.push(index); }
             if kind.is(Kind::Response) { self.response.push(index); }
+            if kind.is(Kind::ResponseBody) { self.response_body.push(index); }
         }
     }
 
@@ -52,7 +55,7 @@ impl Fairings {
     }
 
     #[inline(always)]
-    pub fn handle_launch(&self, rocket: &Rocket) {
+    pub fn handle_launch(&self, rocket: &Rocket<Orbit>) {
         for &i in &self.launch {
             self.all_fairings[i].on_launch(rocket);
         }
@@ -72,6 +75,25 @@ impl Fairings {
         }
     }
 
+    pub fn handle_response_body<'r>(&self, request: &'r Request<'_>, response: &mut Response<'r>) {
+        if self.response_body.is_empty() {
+            return;
+        }
+
+        // The transformed body's length is unknown, so it is always streamed.
+        let (mut body, chunk_size): (BodyReader<'r>, usize) = match response.take_body() {
+            Some(Body::Sized(body, _)) => (Box::pin(body), DEFAULT_CHUNK_SIZE),
+            Some(Body::Chunked(body, chunk_size)) => (body, chunk_size),
+            None => return,
+        };
+
+        for &i in &self.response_body {
+            body = self.all_fairings[i].on_response_body(request, body);
+        }
+
+        response.set_chunked_body(body, chunk_size);
+    }
+
     pub fn failures(&self) -> Option<&[&'static str]> {
         if self.attach_failures.is_empty() {
             None
@@ -98,6 +120,7 @@ impl Fairings {
             self.info_for("launch", &self.launch);
             self.info_for("request", &self.request);
             self.info_for("response", &self.response);
+            self.info_for("response body", &self.response_body);
         }
     }
 }
@@ -40,6 +40,7 @@ pub struct Info {
 ///   * Launch
 ///   * Request
 ///   * Response
+///   * ResponseBody
 ///
 /// Two `Kind` structures can be `or`d together to represent a combination. For
 /// instance, to represent a fairing that is both a launch and request fairing,
@@ -58,6 +59,8 @@ impl Kind {
     pub const Request: Kind = Kind(0b0100);
     /// `Kind` flag representing a request for a 'response' callback.
     pub const Response: Kind = Kind(0b1000);
+    /// `Kind` flag representing a request for a 'response body' callback.
+    pub const ResponseBody: Kind = Kind(0b1_0000);
 
     /// Returns `true` if `self` is a superset of `other`. In other words,
     /// returns `true` if all of the kinds in `other` are also in `self`.
@@ -47,7 +47,11 @@
 //! of other `Fairings` are not jeopardized. For instance, unless it is made
 //! abundantly clear, a fairing should not rewrite every request.
 
-use crate::{Rocket, Request, Response, Data};
+use std::pin::Pin;
+
+use tokio::io::AsyncRead;
+
+use crate::{Rocket, Request, Response, Data, Orbit};
 
 mod fairings;
 mod ad_hoc;
@@ -57,6 +61,10 @@ pub(crate) use self::fairings::Fairings;
 pub use self::ad_hoc::AdHoc;
 pub use self::info_kind::{Info, Kind};
 
+/// The type of a response body as seen by [`Fairing::on_response_body()`]: a
+/// boxed, pinned [`AsyncRead`].
+pub type BodyReader<'r> = Pin<Box<dyn AsyncRead + Send + 'r>>;
+
 // We might imagine that a request fairing returns an `Outcome`. If it returns
 // `Success`, we don't do any routing and use that response directly. Same if it
 // returns `Failure`. We only route if it returns `Forward`. I've chosen not to
@@ -94,12 +102,13 @@ pub use self::info_kind::{Info, Kind};
 ///
 /// ## Fairing Callbacks
 ///
-/// There are four kinds of fairing callbacks: attach, launch, request, and
-/// response. A fairing can request any combination of these callbacks through
-/// the `kind` field of the `Info` structure returned from the `info` method.
-/// Rocket will only invoke the callbacks set in the `kind` field.
+/// There are five kinds of fairing callbacks: attach, launch, request,
+/// response, and response body. A fairing can request any combination of
+/// these callbacks through the `kind` field of the `Info` structure returned
+/// from the `info` method. Rocket will only invoke the callbacks set in the
+/// `kind` field.
 ///
-/// The four callback kinds are as follows:
+/// The five callback kinds are as follows:
 ///
 ///   * **Attach (`on_attach`)**
 ///
@@ -122,7 +131,7 @@ pub use self::info_kind::{Info, Kind};
 ///     is called immediately before the Rocket application has launched. At
 ///     this point, Rocket has opened a socket for listening but has not yet
 ///     begun accepting connections. A launch callback can inspect the `Rocket`
-///     instance being launched.
+///     instance being launched, which is in the [`Orbit`] phase.
 ///
 ///   * **Request (`on_request`)**
 ///
@@ -132,10 +141,12 @@ pub use self::info_kind::{Info, Kind};
 ///     fields. At this point, Rocket has parsed the incoming HTTP request into
 ///     [`Request`] and [`Data`] structures but has not routed the request. A
 ///     request callback can modify the request at will and [`Data::peek()`]
-///     into the incoming data. It may not, however, abort or respond directly
-///     to the request; these issues are better handled via [request guards] or
-///     via response callbacks. Any modifications to a request are persisted and
-///     can potentially alter how a request is routed.
+///     into the incoming data. It may not respond directly to the request,
+///     but it may fail it with a status via [`Request::set_failure()`], in
+///     which case the request isn't routed and the status's catcher responds.
+///     Other issues are better handled via [request guards] or via response
+///     callbacks. Any modifications to a request are persisted and can
+///     potentially alter how a request is routed.
 ///
 ///   * **Response (`on_response`)**
 ///
@@ -152,13 +163,26 @@ pub use self::info_kind::{Info, Kind};
 ///     request. Additionally, Rocket will automatically strip the body for
 ///     `HEAD` requests _after_ response fairings have run.
 ///
+///   * **Response Body (`on_response_body`)**
+///
+///     A response body callback, represented by the
+///     [`Fairing::on_response_body()`] method, is called after all response
+///     callbacks have run, if the response has a body. It receives the body as
+///     a [`BodyReader`] and returns a reader that will be used in its place,
+///     allowing a fairing to transform the body as it is streamed to the
+///     client, for instance to minify or rewrite HTML, or to compute a digest
+///     of the bytes written. Because the length of a transformed body is
+///     unknown, a response whose body has been passed through a response body
+///     callback is always streamed. Response body callbacks are not called for
+///     `HEAD` requests.
+///
 /// # Implementing
 ///
 /// A `Fairing` implementation has one required method: [`info`]. A `Fairing`
 /// can also implement any of the available callbacks: `on_attach`, `on_launch`,
-/// `on_request`, and `on_response`. A `Fairing` _must_ set the appropriate
-/// callback kind in the `kind` field of the returned `Info` structure from
-/// [`info`] for a callback to actually be called by Rocket.
+/// `on_request`, `on_response`, and `on_response_body`. A `Fairing` _must_ set
+/// the appropriate callback kind in the `kind` field of the returned `Info`
+/// structure from [`info`] for a callback to actually be called by Rocket.
 ///
 /// ## Fairing `Info`
 ///
@@ -196,7 +220,7 @@ pub use self::info_kind::{Info, Kind};
 /// decorated with an attribute of `#[rocket::async_trait]`:
 ///
 /// ```rust
-/// use rocket::{Rocket, Request, Data, Response};
+/// use rocket::{Rocket, Orbit, Request, Data, Response};
 /// use rocket::fairing::{Fairing, Info, Kind};
 ///
 /// # struct MyType;
@@ -212,7 +236,7 @@ pub use self::info_kind::{Info, Kind};
 ///         # unimplemented!()
 ///     }
 ///
-///     fn on_launch(&self, rocket: &Rocket) {
+///     fn on_launch(&self, rocket: &Rocket<Orbit>) {
 ///         /* ... */
 ///         # unimplemented!()
 ///     }
@@ -421,13 +445,14 @@ pub trait Fairing: Send + Sync + 'static {
     /// This method is called just prior to launching the application if
     /// `Kind::Launch` is in the `kind` field of the `Info` structure for this
     /// fairing. The `Rocket` parameter corresponds to the application that
-    /// will be launched.
+    /// has been launched. The port in its configuration is the port the
+    /// server is listening on.
     ///
     /// ## Default Implementation
     ///
     /// The default implementation of this method does nothing.
     #[allow(unused_variables)]
-    fn on_launch(&self, rocket: &Rocket) {}
+    fn on_launch(&self, rocket: &Rocket<Orbit>) {}
 
     /// The request callback.
     ///
@@ -454,6 +479,22 @@ pub trait Fairing: Send + Sync + 'static {
     /// The default implementation of this method does nothing.
     #[allow(unused_variables)]
     async fn on_response<'r>(&self, req: &'r Request<'_>, res: &mut Response<'r>) {}
+
+    /// The response body callback.
+    ///
+    /// This method is called after all response callbacks have run if
+    /// `Kind::ResponseBody` is in the `kind` field of the `Info` structure for
+    /// this fairing and the response has a body. The `&Request` parameter is
+    /// the request that was routed, and `body` is the response body. The
+    /// returned reader replaces the response body.
+    ///
+    /// ## Default Implementation
+    ///
+    /// The default implementation of this method returns `body` unchanged.
+    #[allow(unused_variables)]
+    fn on_response_body<'r>(&self, req: &'r Request<'_>, body: BodyReader<'r>) -> BodyReader<'r> {
+        body
+    }
 }
 
 #[crate::async_trait]
@@ -469,7 +510,7 @@ impl<T: Fairing> Fairing for std::sync::Arc<T> {
     }
 
     #[inline]
-    fn on_launch(&self, rocket: &Rocket) {
+    fn on_launch(&self, rocket: &Rocket<Orbit>) {
         (self as &T).on_launch(rocket)
     }
 
@@ -482,4 +523,9 @@ impl<T: Fairing> Fairing for std::sync::Arc<T> {
     async fn on_response<'r>(&self, req: &'r Request<'_>, res: &mut Response<'r>) {
         (self as &T).on_response(req, res).await;
     }
+
+    #[inline]
+    fn on_response_body<'r>(&self, req: &'r Request<'_>, body: BodyReader<'r>) -> BodyReader<'r> {
+        (self as &T).on_response_body(req, body)
+    }
 }
@@ -136,6 +136,58 @@ pub type HandlerFuture<'r> = BoxFuture<'r, Outcome<'r>>;
 /// Use this alternative when a single configuration is desired and your custom
 /// handler is private to your application. For all other cases, a custom
 /// `Handler` implementation is preferred.
+///
+/// # Manual Routing
+///
+/// Everything Rocket's code generation does can also be done by hand with
+/// `Handler`s, which is useful when the routes of an application are only
+/// known at runtime. The manual equivalents of the code generation attributes
+/// and parameters are:
+///
+/// | codegen                             | manual equivalent                          |
+/// |-------------------------------------|--------------------------------------------|
+/// | `#[get("/<path>")]`                 | [`Route::new()`], [`Route::builder()`]     |
+/// | `rank`, `format`, and the fn's name | [`RouteBuilder`] methods                   |
+/// | `<param>` path parameters           | [`Request::get_param_by_name()`]           |
+/// | `<param..>` path parameters         | [`Request::get_segments_by_name()`]        |
+/// | `<param>` query parameters          | [`Request::get_query_value()`]             |
+/// | request guards                      | [`Request::guard()`]                       |
+/// | `data = "<param>"` data guards      | [`FromData`] or [`Data::open()`]           |
+/// | `#[catch(404)]`                     | [`Catcher::new()`]                         |
+///
+/// For example, the following route is equivalent to a
+/// `#[post("/<id>", format = "json", rank = 2)]` route named `update`:
+///
+/// ```rust
+/// use rocket::{Request, Data, Route, http::{Method, MediaType}};
+/// use rocket::handler::{Outcome, HandlerFuture};
+///
+/// fn update<'r>(req: &'r Request, data: Data) -> HandlerFuture<'r> {
+///     let id = match req.get_param_by_name::<usize>("id") {
+///         Some(Ok(id)) => id,
+///         _ => return Outcome::forward(data).pin(),
+///     };
+///
+///     Outcome::from(req, format!("updated {}", id)).pin()
+/// }
+///
+/// let route = Route::builder(Method::Post, "/<id>", update)
+///     .format(MediaType::JSON)
+///     .rank(2)
+///     .name("update")
+///     .build();
+/// ```
+///
+/// [`Route::new()`]: crate::Route::new()
+/// [`Route::builder()`]: crate::Route::builder()
+/// [`RouteBuilder`]: crate::RouteBuilder
+/// [`Request::get_param_by_name()`]: crate::Request::get_param_by_name()
+/// [`Request::get_segments_by_name()`]: crate::Request::get_segments_by_name()
+/// [`Request::get_query_value()`]: crate::Request::get_query_value()
+/// [`Request::guard()`]: crate::Request::guard()
+/// [`FromData`]: crate::data::FromData
+/// [`Data::open()`]: crate::Data::open()
+/// [`Catcher::new()`]: crate::Catcher::new()
 #[crate::async_trait]
 pub trait Handler: Cloneable + Send + Sync + 'static {
     /// Called by Rocket when a `Request` with its associated `Data` should be
@@ -161,6 +213,56 @@ impl<F: Clone + Sync + Send + 'static> Handler for F
     }
 }
 
+/// Returns a [`Handler`] that responds to requests with the responder returned
+/// by the closure `f`.
+///
+/// Plain functions can be used as handlers directly, but they must return a
+/// [`HandlerFuture`]. `from_fn` instead accepts a closure that returns any
+/// [`Responder`] of `'static` responses, such as `&'static str` or `String`,
+/// which makes it convenient for small routes like health checks:
+///
+/// ```rust
+/// use rocket::{Route, http::Method};
+/// use rocket::handler::from_fn;
+///
+/// let ping = Route::new(Method::Get, "/ping", from_fn(|_, _| "pong"));
+/// let hello = Route::new(Method::Get, "/hello/<name>", from_fn(|req, _| {
+///     let name = req.get_param_by_name::<String>("name").and_then(|r| r.ok());
+///     format!("Hello, {}!", name.unwrap_or_else(|| "stranger".into()))
+/// }));
+///
+/// # let rocket = rocket::ignite().mount("/", vec![ping, hello]);
+/// # let client = rocket::local::blocking::Client::tracked(rocket).unwrap();
+/// # assert_eq!(client.get("/ping").dispatch().into_string().unwrap(), "pong");
+/// # let response = client.get("/hello/Bob").dispatch();
+/// # assert_eq!(response.into_string().unwrap(), "Hello, Bob!");
+/// ```
+///
+/// The request's [`Data`] is passed to the closure as its second argument. The
+/// closure cannot forward the request; use a `Handler` that returns
+/// [`Outcome::forward()`] for that.
+pub fn from_fn<F, R>(f: F) -> FnHandler<F>
+    where F: Fn(&Request<'_>, Data) -> R + Clone + Send + Sync + 'static,
+          R: for<'r> Responder<'r, 'static>
+{
+    FnHandler(f)
+}
+
+/// A [`Handler`] created from a closure by [`from_fn()`].
+#[derive(Clone)]
+pub struct FnHandler<F>(F);
+
+#[crate::async_trait]
+impl<F, R> Handler for FnHandler<F>
+    where F: Fn(&Request<'_>, Data) -> R + Clone + Send + Sync + 'static,
+          R: for<'r> Responder<'r, 'static>
+{
+    #[inline]
+    async fn handle<'r, 's: 'r>(&'s self, req: &'r Request<'_>, data: Data) -> Outcome<'r> {
+        Outcome::from(req, (self.0)(req, data))
+    }
+}
+
 // A handler to use when one is needed temporarily. Don't use outside of Rocket!
 #[doc(hidden)]
 pub fn dummy<'r>(r: &'r Request<'_>, _: Data) -> HandlerFuture<'r> {
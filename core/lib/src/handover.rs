@@ -0,0 +1,178 @@
+use std::{io, env};
+use std::io::{Read, Write};
+use std::os::unix::io::{AsRawFd, FromRawFd, RawFd};
+use std::os::unix::net::UnixStream;
+use std::os::unix::process::CommandExt;
+use std::process::{Command, Child};
+
+use tokio::sync::oneshot;
+
+use crate::request::{FromRequest, Outcome, Request};
+use crate::shutdown::Shutdown;
+
+/// The environment variable holding the inherited listening socket.
+const LISTEN_FD_VAR: &str = "ROCKET_LISTEN_FD";
+
+/// The environment variable holding the readiness notification socket.
+const READY_FD_VAR: &str = "ROCKET_READY_FD";
+
+/// A request guard to hand the listening socket over to a new process.
+///
+/// A `Handover` enables zero-downtime restarts: a new process, typically a
+/// freshly deployed binary of the same application, is spawned with the
+/// listening socket of the running server. The new process begins accepting
+/// connections on the shared socket and signals when it is ready. Only then is
+/// the running server gracefully shut down, finishing any pending requests
+/// before exiting. No connection is refused during the upgrade.
+///
+/// The new process must be a Rocket application. Rocket detects the inherited
+/// socket at launch and uses it in place of binding to the configured address
+/// and port. Readiness is signaled automatically once the server is
+/// listening.
+///
+/// A `Handover` is only available on Unix platforms and only while the server
+/// is running; in particular, it is not available to local clients. When it is
+/// not available, the request is forwarded.
+///
+/// # Example
+///
+/// ```rust,no_run
+/// # #[macro_use] extern crate rocket;
+/// use rocket::Handover;
+///
+/// #[post("/upgrade")]
+/// async fn upgrade(handover: Handover) -> &'static str {
+///     match handover.reexec().await {
+///         Ok(_) => "New process is serving. Shutting down...",
+///         Err(_) => "Upgrade failed. Still serving.",
+///     }
+/// }
+/// ```
+#[derive(Debug, Clone)]
+pub struct Handover {
+    fd: RawFd,
+    shutdown: Shutdown,
+}
+
+impl Handover {
+    pub(crate) fn new(fd: RawFd, shutdown: Shutdown) -> Handover {
+        Handover { fd, shutdown }
+    }
+
+    /// Spawns `command` with the listening socket and waits for the new
+    /// process to signal that it is serving requests. Once it has, a graceful
+    /// shutdown of this server is initiated and the spawned child is returned.
+    ///
+    /// If the new process fails to start or exits before signaling readiness,
+    /// an error is returned and this server continues to serve requests.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// # #[macro_use] extern crate rocket;
+    /// use std::process::Command;
+    /// use rocket::Handover;
+    ///
+    /// #[post("/upgrade")]
+    /// async fn upgrade(handover: Handover) -> Option<&'static str> {
+    ///     let command = Command::new("/srv/app/current/server");
+    ///     handover.spawn(command).await.ok().map(|_| "upgraded")
+    /// }
+    /// ```
+    pub async fn spawn(self, mut command: Command) -> io::Result<Child> {
+        let (parent, child) = UnixStream::pair()?;
+        let (listen_fd, ready_fd) = (self.fd, child.as_raw_fd());
+        command.env(LISTEN_FD_VAR, listen_fd.to_string());
+        command.env(READY_FD_VAR, ready_fd.to_string());
+
+        // Both descriptors are close-on-exec. Clear the flag in the child only.
+        unsafe {
+            command.pre_exec(move || {
+                clear_cloexec(listen_fd)?;
+                clear_cloexec(ready_fd)
+            });
+        }
+
+        let process = command.spawn()?;
+
+        // Our copy of the child's end must be closed so that a child that exits
+        // early results in an EOF instead of a reader that blocks forever.
+        drop(child);
+
+        let (tx, rx) = oneshot::channel();
+        std::thread::spawn(move || {
+            let mut buf = [0u8; 1];
+            let _ = tx.send((&parent).read(&mut buf).map(|n| n == 1));
+        });
+
+        match rx.await {
+            Ok(Ok(true)) => {
+                info!("New process {} is ready. Shutting down.", process.id());
+                self.shutdown.shutdown();
+                Ok(process)
+            }
+            Ok(Err(e)) => Err(e),
+            _ => {
+                let msg = "new process exited before signaling readiness";
+                Err(io::Error::new(io::ErrorKind::Other, msg))
+            }
+        }
+    }
+
+    /// Spawns the current executable with the same arguments via
+    /// [`Handover::spawn()`].
+    pub async fn reexec(self) -> io::Result<Child> {
+        let mut command = Command::new(env::current_exe()?);
+        command.args(env::args_os().skip(1));
+        self.spawn(command).await
+    }
+}
+
+fn clear_cloexec(fd: RawFd) -> io::Result<()> {
+    let flags = unsafe { libc::fcntl(fd, libc::F_GETFD) };
+    if flags < 0 || unsafe { libc::fcntl(fd, libc::F_SETFD, flags & !libc::FD_CLOEXEC) } < 0 {
+        return Err(io::Error::last_os_error());
+    }
+
+    Ok(())
+}
+
+fn take_fd(var: &str) -> Option<RawFd> {
+    let value = env::var(var).ok()?;
+    env::remove_var(var);
+    match value.parse() {
+        Ok(fd) => Some(fd),
+        Err(_) => {
+            warn!("ignoring malformed `{}` environment variable: {}", var, value);
+            None
+        }
+    }
+}
+
+/// Returns the listening socket handed over by a parent process, if any.
+pub(crate) fn inherited_listener() -> Option<std::net::TcpListener> {
+    take_fd(LISTEN_FD_VAR).map(|fd| unsafe { std::net::TcpListener::from_raw_fd(fd) })
+}
+
+/// Notifies the parent process, if any, that this server is now listening.
+pub(crate) fn notify_ready() {
+    if let Some(fd) = take_fd(READY_FD_VAR) {
+        let mut stream = unsafe { UnixStream::from_raw_fd(fd) };
+        if let Err(e) = stream.write_all(&[1]) {
+            warn!("failed to notify parent process of readiness: {}", e);
+        }
+    }
+}
+
+#[crate::async_trait]
+impl<'a, 'r> FromRequest<'a, 'r> for Handover {
+    type Error = std::convert::Infallible;
+
+    #[inline]
+    async fn from_request(request: &'a Request<'r>) -> Outcome<Self, Self::Error> {
+        match request.state.handover {
+            Some(handover) => Outcome::Success(handover.clone()),
+            None => Outcome::Forward(())
+        }
+    }
+}
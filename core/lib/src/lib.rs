@@ -62,10 +62,13 @@
 //!
 //! ## Features
 //!
-//! There are two optional, disabled-by-default features:
+//! There are five optional, disabled-by-default features:
 //!
 //!   * **secrets:** Enables support for [private cookies].
 //!   * **tls:** Enables support for [TLS].
+//!   * **quiet:** Compiles out per-request logging. Useful for benchmarks.
+//!   * **decompression:** Decodes compressed request bodies.
+//!   * **tracing:** Forwards log messages to a [`tracing`] subscriber.
 //!
 //! The features can be enabled in `Rocket.toml`:
 //!
@@ -76,6 +79,13 @@
 //!
 //! [private cookies]: https://rocket.rs/master/guide/requests/#private-cookies
 //! [TLS]: https://rocket.rs/master/guide/configuration/#tls
+//! [`tracing`]: https://docs.rs/tracing
+//!
+//! Per-request logging can also be disabled at runtime, without recompiling,
+//! by setting the [`log_level`] to `critical` or `off`. Messages that aren't
+//! logged are never formatted.
+//!
+//! [`log_level`]: crate::config::Config::log_level
 //!
 //! ## Configuration
 //!
@@ -120,6 +130,10 @@ pub mod handler;
 pub mod fairing;
 pub mod error;
 pub mod catcher;
+pub mod catalog;
+pub mod broadcast;
+pub mod events;
+pub mod deferred;
 
 // Reexport of HTTP everything.
 pub mod http {
@@ -133,8 +147,11 @@ pub mod http {
 }
 
 mod shutdown;
+#[cfg(unix)] mod handover;
+#[cfg(feature = "tls")] mod tls_handle;
 mod router;
 mod rocket;
+mod phase;
 mod server;
 mod codegen;
 mod ext;
@@ -145,10 +162,14 @@ mod ext;
 #[doc(inline)] pub use crate::data::Data;
 #[doc(inline)] pub use crate::config::Config;
 #[doc(inline)] pub use crate::catcher::Catcher;
-pub use crate::router::Route;
+pub use crate::router::{Route, RouteBuilder, RouteStats, RouteMatcher};
+pub use crate::router::{RouteTable, RouteEntry, RouteChange};
 pub use crate::request::{Request, State};
 pub use crate::rocket::Rocket;
-pub use crate::shutdown::Shutdown;
+pub use crate::phase::{Phase, Build, Ignite, Orbit};
+pub use crate::shutdown::{Shutdown, Terminator};
+#[cfg(unix)] pub use crate::handover::Handover;
+#[cfg(feature = "tls")] pub use crate::tls_handle::TlsHandle;
 
 /// Alias to [`Rocket::ignite()`] Creates a new instance of `Rocket`.
 pub fn ignite() -> Rocket {
@@ -178,8 +199,15 @@ pub fn async_test<R>(fut: impl std::future::Future<Output = R> + Send) -> R {
 /// WARNING: This is unstable! Do not use this method outside of Rocket!
 #[doc(hidden)]
 pub fn async_main<R>(fut: impl std::future::Future<Output = R> + Send) -> R {
+    // The runtime must exist before the application's `Rocket` does, so the
+    // `workers` value is read from the default figment. Configuration from
+    // custom providers passed to `rocket::custom()` isn't reflected here.
+    let workers = Config::figment().extract_inner::<u16>("workers")
+        .unwrap_or_else(|_| Config::default().workers);
+
     tokio::runtime::Builder::new()
         .threaded_scheduler()
+        .core_threads(std::cmp::max(workers, 1) as usize)
         .thread_name("rocket-worker-thread")
         .enable_all()
         .build()
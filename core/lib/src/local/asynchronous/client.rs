@@ -4,6 +4,7 @@ use parking_lot::RwLock;
 
 use crate::local::asynchronous::{LocalRequest, LocalResponse};
 use crate::rocket::Rocket;
+use crate::phase::Orbit;
 use crate::http::{private::cookie, Method};
 use crate::error::Error;
 
@@ -48,17 +49,17 @@ use crate::error::Error;
 /// # });
 /// ```
 pub struct Client {
-    rocket: Rocket,
+    rocket: Rocket<Orbit>,
     cookies: RwLock<cookie::CookieJar>,
     pub(in super) tracked: bool,
 }
 
 impl Client {
     pub(crate) async fn _new(
-        mut rocket: Rocket,
+        rocket: Rocket,
         tracked: bool
     ) -> Result<Client, Error> {
-        rocket.prelaunch_check().await?;
+        let rocket = rocket.ignited().await?.into_orbit();
         let cookies = RwLock::new(cookie::CookieJar::new());
         Ok(Client { rocket, tracked, cookies })
     }
@@ -78,7 +79,7 @@ impl Client {
     }
 
     #[inline(always)]
-    pub(crate) fn _rocket(&self) -> &Rocket {
+    pub(crate) fn _rocket(&self) -> &Rocket<Orbit> {
         &self.rocket
     }
 
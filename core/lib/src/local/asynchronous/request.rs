@@ -1,6 +1,7 @@
 use std::borrow::Cow;
 
 use crate::{Request, Data};
+use crate::deferred::Deferred;
 use crate::http::{Status, Method, uri::Origin, ext::IntoOwned};
 
 use super::{Client, LocalResponse};
@@ -87,8 +88,17 @@ impl<'c> LocalRequest<'c> {
         // Actually dispatch the request.
         let mut data = Data::local(self.data);
         let token = rocket.preprocess_request(&mut self.request, &mut data).await;
-        let response = LocalResponse::new(self.request, move |req| {
-            rocket.dispatch(token, req, data)
+        let response = LocalResponse::new(self.request, move |req| async move {
+            let mut response = rocket.dispatch(token, req, data).await;
+
+            // There's no response to send: deliver published events and run
+            // the deferred work right away.
+            rocket.events.deliver(req).await;
+            if let Some(deferred) = Deferred::run(req, response.take_on_complete()) {
+                deferred.await;
+            }
+
+            response
         }).await;
 
         // If the client is tracking cookies, updates the internal cookie jar
@@ -89,7 +89,9 @@ impl<'c> LocalResponse<'c> {
 
         async move {
             let response: Response<'c> = f(request).await;
-            let mut cookies = CookieJar::new(&request.state.config.secret_key);
+            let config = request.state.config;
+            let (secure, same_site) = (config.cookie_secure, config.cookie_same_site);
+            let mut cookies = CookieJar::new(&config.secret_key, secure, same_site);
             for cookie in response.cookies() {
                 cookies.add_original(cookie.into_owned());
             }
@@ -4,6 +4,7 @@ use std::cell::RefCell;
 use crate::error::Error;
 use crate::local::{asynchronous, blocking::{LocalRequest, LocalResponse}};
 use crate::rocket::Rocket;
+use crate::phase::Orbit;
 use crate::http::Method;
 
 /// A `blocking` client to construct and dispatch local requests.
@@ -63,7 +64,7 @@ impl Client {
     }
 
     #[inline(always)]
-    fn _rocket(&self) -> &Rocket {
+    fn _rocket(&self) -> &Rocket<Orbit> {
         self.inner._rocket()
     }
 
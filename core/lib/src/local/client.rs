@@ -105,7 +105,7 @@ macro_rules! pub_client_impl {
     }
 
     /// Returns a reference to the `Rocket` this client is creating requests
-    /// for.
+    /// for. The instance is in the [`Orbit`](crate::Orbit) phase.
     ///
     /// # Example
     ///
@@ -118,7 +118,7 @@ macro_rules! pub_client_impl {
     /// # });
     /// ```
     #[inline(always)]
-    pub fn rocket(&self) -> &Rocket {
+    pub fn rocket(&self) -> &Rocket<Orbit> {
         &*self._rocket()
     }
 
@@ -141,9 +141,10 @@ macro_rules! pub_client_impl {
     /// ```
     #[inline(always)]
     pub fn cookies(&self) -> crate::http::CookieJar<'_> {
-        let key = &self.rocket().config.secret_key;
+        let config = &self.rocket().config;
         let jar = self._with_raw_cookies(|jar| jar.clone());
-        crate::http::CookieJar::from(jar, key)
+        let (secure, same_site) = (config.cookie_secure, config.cookie_same_site);
+        crate::http::CookieJar::from(jar, &config.secret_key, secure, same_site)
     }
 
     req_method!($import, "GET", get, Method::Get);
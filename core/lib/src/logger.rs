@@ -1,14 +1,59 @@
 //! Rocket's logging infrastructure.
 
-use std::fmt;
+use std::fmt::{self, Write};
+use std::pin::Pin;
+use std::sync::Arc;
+use std::cell::Cell;
 use std::str::FromStr;
+use std::future::Future;
+use std::task::{Context, Poll};
+use std::time::{SystemTime, UNIX_EPOCH};
 
 use log;
 use yansi::Paint;
 use serde::{de, Serialize, Serializer, Deserialize, Deserializer};
 
 #[derive(Debug)]
-struct RocketLogger(LogLevel);
+struct RocketLogger {
+    level: LogLevel,
+    format: LogFormat,
+}
+
+thread_local! {
+    // The ID of the request whose dispatch is being polled on this thread.
+    static REQUEST_ID: Cell<Option<Arc<str>>> = Cell::new(None);
+}
+
+/// A future that prefixes the log lines emitted while it's polled with the ID
+/// of the request it dispatches.
+pub(crate) struct Tagged<F> {
+    id: Arc<str>,
+    future: F,
+}
+
+impl<F: Future + Unpin> Tagged<F> {
+    pub(crate) fn new(id: Arc<str>, future: F) -> Self {
+        Tagged { id, future }
+    }
+}
+
+impl<F: Future + Unpin> Future for Tagged<F> {
+    type Output = F::Output;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<F::Output> {
+        // Restores the previous ID even if polling panics.
+        struct Reset(Option<Arc<str>>);
+
+        impl Drop for Reset {
+            fn drop(&mut self) {
+                let _ = REQUEST_ID.try_with(|id| id.set(self.0.take()));
+            }
+        }
+
+        let _reset = Reset(REQUEST_ID.with(|id| id.replace(Some(self.id.clone()))));
+        Pin::new(&mut self.future).poll(cx)
+    }
+}
 
 /// Defines the maximum level of log messages to show.
 #[derive(PartialEq, Eq, Debug, Clone, Copy)]
@@ -82,6 +127,62 @@ impl<'de> Deserialize<'de> for LogLevel {
     }
 }
 
+/// Defines the format of log messages.
+#[derive(PartialEq, Eq, Debug, Clone, Copy)]
+pub enum LogFormat {
+    /// Human-readable, colored, and indented messages: `"pretty"`.
+    Pretty,
+    /// One JSON object per message, with the fields `ts` (milliseconds since
+    /// the Unix epoch), `level`, `target`, `message`, and, for messages
+    /// emitted while dispatching a request with an ID, `request_id`: `"json"`.
+    Json,
+}
+
+impl LogFormat {
+    fn as_str(&self) -> &str {
+        match self {
+            LogFormat::Pretty => "pretty",
+            LogFormat::Json => "json",
+        }
+    }
+}
+
+impl FromStr for LogFormat {
+    type Err = &'static str;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let format = match &*s.to_ascii_lowercase() {
+            "pretty" => LogFormat::Pretty,
+            "json" => LogFormat::Json,
+            _ => return Err("a log format (pretty, json)")
+        };
+
+        Ok(format)
+    }
+}
+
+impl fmt::Display for LogFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+impl Serialize for LogFormat {
+    fn serialize<S: Serializer>(&self, ser: S) -> Result<S::Ok, S::Error> {
+        ser.serialize_str(self.as_str())
+    }
+}
+
+impl<'de> Deserialize<'de> for LogFormat {
+    fn deserialize<D: Deserializer<'de>>(de: D) -> Result<Self, D::Error> {
+        let string = String::deserialize(de)?;
+        LogFormat::from_str(&string).map_err(|_| de::Error::invalid_value(
+            de::Unexpected::Str(&string),
+            &figment::error::OneOf( &["pretty", "json"])
+        ))
+    }
+}
+
 #[doc(hidden)] #[macro_export]
 macro_rules! log_ { ($name:ident: $($args:tt)*) => { $name!(target: "_", $($args)*) }; }
 #[doc(hidden)] #[macro_export]
@@ -99,33 +200,25 @@ macro_rules! debug_ { ($($args:expr),+) => { log_!(debug: $($args),+); }; }
 #[doc(hidden)] #[macro_export]
 macro_rules! warn_ { ($($args:expr),+) => { log_!(warn: $($args),+); }; }
 
-impl log::Log for RocketLogger {
-    #[inline(always)]
-    fn enabled(&self, record: &log::Metadata<'_>) -> bool {
-        match self.0.to_level_filter().to_level() {
-            Some(max) => record.level() <= max || record.target().starts_with("launch"),
-            None => false
-        }
-    }
-
-    fn log(&self, record: &log::Record<'_>) {
-        // Print nothing if this level isn't enabled and this isn't launch info.
-        if !self.enabled(record.metadata()) {
-            return;
-        }
+// Logs a message emitted for every dispatched request. With the `quiet`
+// feature enabled, the call is compiled out of the hot path entirely.
+macro_rules! dispatch_log {
+    ($name:ident: $($args:tt)*) => {
+        if !cfg!(feature = "quiet") { $name!($($args)*); }
+    };
+}
 
-        // Don't print Hyper or Rustls messages unless debug is enabled.
-        let configged_level = self.0;
-        let from_hyper = record.module_path().map_or(false, |m| m.starts_with("hyper::"));
-        let from_rustls = record.module_path().map_or(false, |m| m.starts_with("rustls::"));
-        if configged_level != LogLevel::Debug && (from_hyper || from_rustls) {
-            return;
+impl RocketLogger {
+    fn pretty(&self, record: &log::Record<'_>) {
+        // Tag lines emitted while dispatching a request with the request's ID.
+        if let Some(id) = current_request_id() {
+            print!("{} ", Paint::default(format!("[{}]", id)).dimmed());
         }
 
         // In Rocket, we abuse targets with suffix "_" to indicate indentation.
         let is_launch = record.target().starts_with("launch");
         if record.target().ends_with('_') {
-            if configged_level != LogLevel::Critical || is_launch {
+            if self.level != LogLevel::Critical || is_launch {
                 print!("    {} ", Paint::default("=>").bold());
             }
         }
@@ -158,12 +251,129 @@ impl log::Log for RocketLogger {
         }
     }
 
+    fn json(&self, record: &log::Record<'_>) {
+        let ts = SystemTime::now().duration_since(UNIX_EPOCH)
+            .map(|duration| duration.as_millis())
+            .unwrap_or(0);
+
+        println!("{}", json_line(record, ts));
+    }
+
+    #[cfg(feature = "tracing")]
+    fn trace(&self, record: &log::Record<'_>) {
+        let target = record_target(record);
+        let request_id = current_request_id();
+        let request_id = request_id.as_deref().unwrap_or("");
+        macro_rules! event {
+            ($level:ident) => (tracing::event!(tracing::Level::$level,
+                module = target, request_id, "{}", record.args()))
+        }
+
+        match record.level() {
+            log::Level::Error => event!(ERROR),
+            log::Level::Warn => event!(WARN),
+            log::Level::Info => event!(INFO),
+            log::Level::Debug => event!(DEBUG),
+            log::Level::Trace => event!(TRACE),
+        }
+    }
+}
+
+impl log::Log for RocketLogger {
+    #[inline(always)]
+    fn enabled(&self, record: &log::Metadata<'_>) -> bool {
+        match self.level.to_level_filter().to_level() {
+            Some(max) => record.level() <= max || record.target().starts_with("launch"),
+            None => false
+        }
+    }
+
+    fn log(&self, record: &log::Record<'_>) {
+        // Print nothing if this level isn't enabled and this isn't launch info.
+        if !self.enabled(record.metadata()) {
+            return;
+        }
+
+        // Don't print Hyper or Rustls messages unless debug is enabled.
+        let from_hyper = record.module_path().map_or(false, |m| m.starts_with("hyper::"));
+        let from_rustls = record.module_path().map_or(false, |m| m.starts_with("rustls::"));
+        if self.level != LogLevel::Debug && (from_hyper || from_rustls) {
+            return;
+        }
+
+        // Forward to a `tracing` subscriber, if one has been installed.
+        #[cfg(feature = "tracing")] {
+            if tracing::dispatcher::has_been_set() {
+                return self.trace(record);
+            }
+        }
+
+        match self.format {
+            LogFormat::Pretty => self.pretty(record),
+            LogFormat::Json => self.json(record),
+        }
+    }
+
     fn flush(&self) {
         // NOOP: We don't buffer any records.
     }
 }
 
-pub(crate) fn try_init(level: LogLevel, colors: bool, verbose: bool) -> bool {
+/// Returns the ID of the request being dispatched on this thread, if any.
+fn current_request_id() -> Option<Arc<str>> {
+    REQUEST_ID.try_with(|id| {
+        let current = id.take();
+        id.set(current.clone());
+        current
+    }).ok().flatten()
+}
+
+/// Returns the target to report for `record`. Rocket's own targets only encode
+/// indentation, so the module path is used for those instead.
+fn record_target<'a>(record: &log::Record<'a>) -> &'a str {
+    match record.target().trim_end_matches('_') {
+        "" => record.module_path().unwrap_or("rocket"),
+        target => target,
+    }
+}
+
+/// Returns `record`, logged at `ts` milliseconds since the Unix epoch, as a
+/// single-line JSON object.
+fn json_line(record: &log::Record<'_>, ts: u128) -> String {
+    let mut line = format!("{{\"ts\":{},\"level\":\"{}\",\"target\":", ts,
+        record.level().as_str().to_ascii_lowercase());
+
+    json_string(&mut line, record_target(record));
+    line.push_str(",\"message\":");
+    json_string(&mut line, &record.args().to_string());
+    if let Some(id) = current_request_id() {
+        line.push_str(",\"request_id\":");
+        json_string(&mut line, &id);
+    }
+
+    line.push('}');
+    line
+}
+
+/// Appends `string` to `out` as a quoted and escaped JSON string.
+fn json_string(out: &mut String, string: &str) {
+    out.push('"');
+    for c in string.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if c.is_control() => { let _ = write!(out, "\\u{:04x}", c as u32); }
+            c => out.push(c),
+        }
+    }
+
+    out.push('"');
+}
+
+pub(crate) fn try_init(level: LogLevel, format: LogFormat, colors: bool, verbose: bool) -> bool {
     if level == LogLevel::Off {
         return false;
     }
@@ -171,11 +381,12 @@ pub(crate) fn try_init(level: LogLevel, colors: bool, verbose: bool) -> bool {
     if !atty::is(atty::Stream::Stdout)
         || (cfg!(windows) && !Paint::enable_windows_ascii())
         || !colors
+        || format == LogFormat::Json
     {
         Paint::disable();
     }
 
-    if let Err(e) = log::set_boxed_logger(Box::new(RocketLogger(level))) {
+    if let Err(e) = log::set_boxed_logger(Box::new(RocketLogger { level, format })) {
         if verbose {
             eprintln!("Logger failed to initialize: {}", e);
         }
@@ -201,7 +412,7 @@ impl PaintExt for Paint<&str> {
 
 #[doc(hidden)]
 pub fn init(level: LogLevel) -> bool {
-    try_init(level, true, true)
+    try_init(level, LogFormat::Pretty, true, true)
 }
 
 // Expose logging macros as (hidden) funcions for use by core/contrib codegen.
@@ -216,3 +427,60 @@ external_log_function!(error: error);
 external_log_function!(error_: error_);
 external_log_function!(warn: warn);
 external_log_function!(warn_: warn_);
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn test_dispatch_log_quiet() {
+        let mut logged = false;
+        macro_rules! mark { ($flag:ident) => { $flag = true; } }
+
+        dispatch_log!(mark: logged);
+        assert_eq!(logged, !cfg!(feature = "quiet"));
+    }
+
+    #[test]
+    fn test_json_string() {
+        let escaped = |string: &str| {
+            let mut out = String::new();
+            super::json_string(&mut out, string);
+            out
+        };
+
+        assert_eq!(escaped(""), r#""""#);
+        assert_eq!(escaped("hello"), r#""hello""#);
+        assert_eq!(escaped(r#"say "hi""#), r#""say \"hi\"""#);
+        assert_eq!(escaped(r"C:\dir"), r#""C:\\dir""#);
+        assert_eq!(escaped("a\nb\r\tc"), r#""a\nb\r\tc""#);
+        assert_eq!(escaped("\u{0}\u{1b}[1m"), r#""\u0000\u001b[1m""#);
+        assert_eq!(escaped("caf\u{e9} \u{1f680}"), "\"caf\u{e9} \u{1f680}\"");
+    }
+
+    #[test]
+    fn test_json_line() {
+        let line = |target: &str, module: Option<&str>| {
+            super::json_line(&log::Record::builder()
+                .args(format_args!("Matched: {}", "GET \"/\""))
+                .level(log::Level::Warn)
+                .target(target)
+                .module_path(module)
+                .build(), 1602720000000)
+        };
+
+        assert_eq!(line("launch", None), r#"{"ts":1602720000000,"level":"warn","#.to_string()
+            + r#""target":"launch","message":"Matched: GET \"/\""}"#);
+
+        assert_eq!(line("_", Some("rocket::server")), r#"{"ts":1602720000000,"#.to_string()
+            + r#""level":"warn","target":"rocket::server","message":"Matched: GET \"/\""}"#);
+
+        assert_eq!(line("launch_", None), r#"{"ts":1602720000000,"level":"warn","#.to_string()
+            + r#""target":"launch","message":"Matched: GET \"/\""}"#);
+
+        let id: std::sync::Arc<str> = "01EMNBX2Q7Y8P6J2Z3H9R0K4VA".into();
+        super::REQUEST_ID.with(|current| current.set(Some(id)));
+        let tagged = line("_", None);
+        super::REQUEST_ID.with(|current| current.set(None));
+        assert_eq!(tagged, r#"{"ts":1602720000000,"level":"warn","target":"rocket","#.to_string()
+            + r#""message":"Matched: GET \"/\"","request_id":"01EMNBX2Q7Y8P6J2Z3H9R0K4VA"}"#);
+    }
+}
@@ -0,0 +1,57 @@
+//! The phases of a [`Rocket`](crate::Rocket) instance's lifecycle.
+
+mod private {
+    pub trait Sealed {}
+
+    impl Sealed for super::Build {}
+    impl Sealed for super::Ignite {}
+    impl Sealed for super::Orbit {}
+}
+
+/// A marker trait for the phases of a [`Rocket`](crate::Rocket) instance.
+///
+/// An instance of `Rocket` moves through three phases, in order:
+///
+///   * [`Build`]: routes, catchers, managed state, and fairings may be added.
+///     This is the phase of instances created by [`rocket::ignite()`] and
+///     [`rocket::custom()`].
+///
+///   * [`Ignite`]: attach fairings have run and the instance has passed its
+///     pre-launch checks. The instance can no longer be modified. Reached via
+///     [`Rocket::ignited()`].
+///
+///   * [`Orbit`]: the instance is serving requests. Launch fairings observe
+///     the instance in this phase, as do request handlers and local clients.
+///
+/// Methods that only make sense in a given phase are only implemented for
+/// `Rocket` in that phase. Calling `mount()` on a launched instance, for
+/// example, fails to compile.
+///
+/// This trait is sealed and cannot be implemented outside of Rocket.
+///
+/// [`rocket::ignite()`]: crate::ignite()
+/// [`rocket::custom()`]: crate::custom()
+/// [`Rocket::ignited()`]: crate::Rocket::ignited()
+pub trait Phase: private::Sealed + Send + Sync + 'static {}
+
+/// The initial phase: the instance is being built.
+///
+/// See [`Phase`] for details.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Build {}
+
+/// The second phase: the instance has been validated and can be launched.
+///
+/// See [`Phase`] for details.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Ignite {}
+
+/// The final phase: the instance is serving requests.
+///
+/// See [`Phase`] for details.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Orbit {}
+
+impl Phase for Build {}
+impl Phase for Ignite {}
+impl Phase for Orbit {}
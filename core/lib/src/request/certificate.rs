@@ -0,0 +1,75 @@
+use std::ops::Deref;
+
+use crate::request::{FromRequest, Outcome, Request};
+
+/// Request guard for the certificate presented by a TLS client.
+///
+/// A `Certificate` is the DER-encoded X.509 certificate the client presented
+/// during the TLS handshake, its end-entity certificate. The remainder of the
+/// chain, if any, is available via [`TlsInfo::peer_certificates()`].
+///
+/// The request is forwarded if the connection is not secured by TLS or if the
+/// client did not present a certificate.
+///
+/// [`TlsInfo::peer_certificates()`]: crate::request::TlsInfo::peer_certificates()
+///
+/// # Example
+///
+/// ```rust
+/// # #[macro_use] extern crate rocket;
+/// use rocket::request::Certificate;
+///
+/// #[get("/whoami")]
+/// fn whoami(cert: Certificate<'_>) -> String {
+///     format!("Your certificate is {} bytes long.", cert.len())
+/// }
+///
+/// #[get("/whoami", rank = 2)]
+/// fn anonymous() -> &'static str {
+///     "You didn't present a certificate."
+/// }
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Certificate<'r>(&'r [u8]);
+
+impl<'r> Certificate<'r> {
+    /// Returns the raw DER-encoded bytes of the certificate.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # #[macro_use] extern crate rocket;
+    /// use rocket::request::Certificate;
+    ///
+    /// #[get("/cert")]
+    /// fn cert(cert: Certificate<'_>) -> Vec<u8> {
+    ///     cert.as_bytes().to_vec()
+    /// }
+    /// ```
+    #[inline(always)]
+    pub fn as_bytes(&self) -> &'r [u8] {
+        self.0
+    }
+}
+
+impl Deref for Certificate<'_> {
+    type Target = [u8];
+
+    #[inline(always)]
+    fn deref(&self) -> &[u8] {
+        self.0
+    }
+}
+
+#[crate::async_trait]
+impl<'a, 'r> FromRequest<'a, 'r> for Certificate<'a> {
+    type Error = std::convert::Infallible;
+
+    async fn from_request(request: &'a Request<'r>) -> Outcome<Self, Self::Error> {
+        let cert = request.tls_info().and_then(|tls| tls.peer_certificates().first());
+        match cert {
+            Some(cert) => Outcome::Success(Certificate(cert)),
+            None => Outcome::Forward(())
+        }
+    }
+}
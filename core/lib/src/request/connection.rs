@@ -0,0 +1,128 @@
+use std::net::SocketAddr;
+
+use crate::request::{FromRequest, Outcome, Request};
+
+/// Request guard for information about the connection carrying a request.
+///
+/// Every connection accepted by a launched server is assigned an identifier,
+/// unique for the lifetime of the server and increasing with each connection.
+/// Along with the number of requests previously served over the same
+/// connection, the identifier can be used to implement sticky sessions,
+/// per-connection rate limiting, or to debug proxies that reuse connections.
+///
+/// The request is forwarded if the request did not arrive over a connection,
+/// as is the case for requests dispatched by a local client.
+///
+/// # Example
+///
+/// ```rust
+/// # #[macro_use] extern crate rocket;
+/// use rocket::request::ConnectionInfo;
+///
+/// #[get("/connection")]
+/// fn connection(conn: ConnectionInfo) -> String {
+///     format!("connection #{} has carried {} requests before this one",
+///         conn.id(), conn.reuse_count())
+/// }
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ConnectionInfo {
+    pub(crate) id: u64,
+    pub(crate) local: Option<SocketAddr>,
+    pub(crate) remote: Option<SocketAddr>,
+    pub(crate) reuses: usize,
+}
+
+impl ConnectionInfo {
+    /// Returns the identifier of the connection. The first connection accepted
+    /// by a server has an identifier of `0`, the next `1`, and so on.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # #[macro_use] extern crate rocket;
+    /// use rocket::request::ConnectionInfo;
+    ///
+    /// #[get("/id")]
+    /// fn id(conn: ConnectionInfo) -> String {
+    ///     conn.id().to_string()
+    /// }
+    /// ```
+    #[inline(always)]
+    pub fn id(&self) -> u64 {
+        self.id
+    }
+
+    /// Returns the local address the server accepted the connection on, if it
+    /// is known.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # #[macro_use] extern crate rocket;
+    /// use rocket::request::ConnectionInfo;
+    ///
+    /// #[get("/local")]
+    /// fn local(conn: ConnectionInfo) -> Option<String> {
+    ///     conn.local_addr().map(|addr| addr.to_string())
+    /// }
+    /// ```
+    #[inline(always)]
+    pub fn local_addr(&self) -> Option<SocketAddr> {
+        self.local
+    }
+
+    /// Returns the address of the connection's peer, if it is known. Unlike
+    /// [`Request::client_ip()`], this is never derived from headers.
+    ///
+    /// [`Request::client_ip()`]: crate::Request::client_ip()
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # #[macro_use] extern crate rocket;
+    /// use rocket::request::ConnectionInfo;
+    ///
+    /// #[get("/peer")]
+    /// fn peer(conn: ConnectionInfo) -> Option<String> {
+    ///     conn.remote_addr().map(|addr| addr.to_string())
+    /// }
+    /// ```
+    #[inline(always)]
+    pub fn remote_addr(&self) -> Option<SocketAddr> {
+        self.remote
+    }
+
+    /// Returns the number of requests the connection carried before this one.
+    /// A value of `0` indicates that this is the first request on the
+    /// connection; any other value indicates that the connection was kept
+    /// alive and reused.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # #[macro_use] extern crate rocket;
+    /// use rocket::request::ConnectionInfo;
+    ///
+    /// #[get("/reused")]
+    /// fn reused(conn: ConnectionInfo) -> &'static str {
+    ///     if conn.reuse_count() > 0 { "reused" } else { "fresh" }
+    /// }
+    /// ```
+    #[inline(always)]
+    pub fn reuse_count(&self) -> usize {
+        self.reuses
+    }
+}
+
+#[crate::async_trait]
+impl<'a, 'r> FromRequest<'a, 'r> for ConnectionInfo {
+    type Error = std::convert::Infallible;
+
+    async fn from_request(request: &'a Request<'r>) -> Outcome<Self, Self::Error> {
+        match request.connection_info() {
+            Some(info) => Outcome::Success(*info),
+            None => Outcome::Forward(())
+        }
+    }
+}
@@ -1,15 +1,14 @@
 use std::io;
+use std::ops::Deref;
+use std::borrow::Cow;
+
 use crate::http::RawStr;
+use crate::request::Request;
+use crate::catalog::localize;
 
-/// Error returned by the [`FromForm`](crate::request::FromForm) derive on form
-/// parsing errors.
-///
-/// If multiple errors occur while parsing a form, the first error in the
-/// following precedence, from highest to lowest, is returned:
-///
-///   * `BadValue` or `Unknown` in incoming form string field order
-///   * `Missing` in lexical field order
-#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+/// A single form parsing error. The [`FromForm`](crate::request::FromForm)
+/// derive collects these into [`FormParseErrors`].
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub enum FormParseError<'f> {
     /// The field named `.0` with value `.1` failed to parse or validate.
     BadValue(&'f RawStr, &'f RawStr),
@@ -19,7 +18,196 @@ pub enum FormParseError<'f> {
     /// This error cannot occur when parsing is lenient.
     Unknown(&'f RawStr, &'f RawStr),
     /// The field named `.0` was expected but is missing in the incoming form.
-    Missing(&'f RawStr),
+    /// The name of a nested field includes the names of the fields enclosing
+    /// it, separated by `.`: `address.street`.
+    Missing(Cow<'f, str>),
+}
+
+impl FormParseError<'_> {
+    /// Returns a message describing `self` for the client that made `req`,
+    /// translated via the managed [`Catalog`](crate::catalog::Catalog) if it
+    /// has a translation in a language the client accepts.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use rocket::Request;
+    /// # use rocket::http::Method;
+    /// use rocket::request::FormParseError;
+    ///
+    /// # Request::example(Method::Get, "/", |req| {
+    /// let error = FormParseError::Missing("name".into());
+    /// assert_eq!(error.message(req), "The field 'name' is missing.");
+    /// # });
+    /// ```
+    pub fn message<'a>(&self, req: &'a Request<'_>) -> Cow<'a, str> {
+        match self {
+            FormParseError::BadValue(field, value) => localize(req, "form.bad_value",
+                "The value '{value}' of the field '{field}' is invalid.",
+                &[("field", field.as_str()), ("value", value.as_str())]),
+            FormParseError::Unknown(field, value) => localize(req, "form.unknown",
+                "The field '{field}' is unexpected.",
+                &[("field", field.as_str()), ("value", value.as_str())]),
+            FormParseError::Missing(field) => localize(req, "form.missing",
+                "The field '{field}' is missing.",
+                &[("field", &field[..])]),
+        }
+    }
+}
+
+/// Error returned by the [`FromForm`](crate::request::FromForm) derive on form
+/// parsing errors: every [`FormParseError`] encountered while parsing a form.
+///
+/// Errors are ordered as follows:
+///
+///   * `Unknown` in incoming form string field order
+///   * `BadValue` and `Missing` in lexical field order, with the errors of
+///     nested fields in the order of the nested structure's fields
+///
+/// A value of this type dereferences to a slice of `FormParseError`s.
+///
+/// # Example
+///
+/// ```rust
+/// # #[macro_use] extern crate rocket;
+/// use rocket::request::{FromForm, FormItems, FormParseError};
+///
+/// #[derive(FromForm)]
+/// struct Login {
+///     user: String,
+///     remember: bool,
+///     age: u8,
+/// }
+///
+/// let mut items = FormItems::from("age=old");
+/// let errors = Login::from_form(&mut items, true).err().unwrap();
+/// assert_eq!(errors.len(), 2);
+/// assert_eq!(errors[0], FormParseError::Missing("user".into()));
+/// assert_eq!(errors[1], FormParseError::BadValue("age".into(), "old".into()));
+/// ```
+#[derive(Debug, Default, Clone, PartialEq, Eq, Hash)]
+pub struct FormParseErrors<'f>(Vec<FormParseError<'f>>);
+
+impl<'f> FormParseErrors<'f> {
+    /// Returns an empty collection of errors.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::request::FormParseErrors;
+    ///
+    /// let errors = FormParseErrors::new();
+    /// assert!(errors.is_empty());
+    /// ```
+    pub fn new() -> Self {
+        FormParseErrors(Vec::new())
+    }
+
+    /// Appends `error` to `self`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::request::{FormParseErrors, FormParseError};
+    ///
+    /// let mut errors = FormParseErrors::new();
+    /// errors.push(FormParseError::Missing("name".into()));
+    /// assert_eq!(errors.len(), 1);
+    /// ```
+    pub fn push(&mut self, error: FormParseError<'f>) {
+        self.0.push(error);
+    }
+
+    /// Prefixes the names of the missing fields in `self` with `name`. Used by
+    /// the `FromForm` derive.
+    #[doc(hidden)]
+    pub fn _prefixed(mut self, name: &str) -> Self {
+        if name.is_empty() {
+            return self;
+        }
+
+        for error in &mut self.0 {
+            if let FormParseError::Missing(field) = error {
+                *field = Cow::Owned(format!("{}.{}", name, field));
+            }
+        }
+
+        self
+    }
+
+    /// Returns the messages describing each error in `self`, separated by
+    /// spaces, for the client that made `req`. See
+    /// [`FormParseError::message()`].
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use rocket::Request;
+    /// # use rocket::http::Method;
+    /// use rocket::request::{FormParseErrors, FormParseError};
+    ///
+    /// # Request::example(Method::Get, "/", |req| {
+    /// let mut errors = FormParseErrors::new();
+    /// errors.push(FormParseError::Missing("name".into()));
+    /// errors.push(FormParseError::Missing("age".into()));
+    ///
+    /// let message = errors.message(req);
+    /// assert_eq!(message, "The field 'name' is missing. The field 'age' is missing.");
+    /// # });
+    /// ```
+    pub fn message<'a>(&self, req: &'a Request<'_>) -> Cow<'a, str> {
+        match self.0.as_slice() {
+            [error] => error.message(req),
+            errors => {
+                let messages: Vec<_> = errors.iter().map(|e| e.message(req)).collect();
+                Cow::Owned(messages.join(" "))
+            }
+        }
+    }
+}
+
+impl<'f> Deref for FormParseErrors<'f> {
+    type Target = [FormParseError<'f>];
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl<'f> From<FormParseError<'f>> for FormParseErrors<'f> {
+    fn from(error: FormParseError<'f>) -> Self {
+        FormParseErrors(vec![error])
+    }
+}
+
+impl<'f> From<Vec<FormParseError<'f>>> for FormParseErrors<'f> {
+    fn from(errors: Vec<FormParseError<'f>>) -> Self {
+        FormParseErrors(errors)
+    }
+}
+
+impl<'f> Extend<FormParseError<'f>> for FormParseErrors<'f> {
+    fn extend<I: IntoIterator<Item = FormParseError<'f>>>(&mut self, iter: I) {
+        self.0.extend(iter)
+    }
+}
+
+impl<'f> IntoIterator for FormParseErrors<'f> {
+    type Item = FormParseError<'f>;
+    type IntoIter = std::vec::IntoIter<FormParseError<'f>>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.0.into_iter()
+    }
+}
+
+impl<'a, 'f> IntoIterator for &'a FormParseErrors<'f> {
+    type Item = &'a FormParseError<'f>;
+    type IntoIter = std::slice::Iter<'a, FormParseError<'f>>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.0.iter()
+    }
 }
 
 /// Error returned by the [`FromTransformedData`](crate::data::FromTransformedData) implementations of
@@ -70,4 +258,22 @@ pub enum FormDataError<'f, E> {
 /// }
 /// # fn main() {}
 /// ```
-pub type FormError<'f> = FormDataError<'f, FormParseError<'f>>;
+pub type FormError<'f> = FormDataError<'f, FormParseErrors<'f>>;
+
+impl FormError<'_> {
+    /// Returns a message describing `self` for the client that made `req`,
+    /// translated via the managed [`Catalog`](crate::catalog::Catalog) if it
+    /// has a translation in a language the client accepts. See
+    /// [`FormParseErrors::message()`].
+    pub fn message<'a>(&self, req: &'a Request<'_>) -> Cow<'a, str> {
+        match self {
+            FormDataError::Io(_) => {
+                localize(req, "form.io", "The form could not be read.", &[])
+            }
+            FormDataError::Malformed(_) => {
+                localize(req, "form.malformed", "The form is malformed.", &[])
+            }
+            FormDataError::Parse(e, _) => e.message(req),
+        }
+    }
+}
@@ -0,0 +1,360 @@
+use std::collections::HashMap;
+use std::hash::Hash;
+
+use crate::http::RawStr;
+use crate::request::{FormItem, FromFormValue, FormParseError, FormParseErrors};
+
+/// Trait implemented by the types of fields in structures deriving
+/// [`FromForm`](crate::request::FromForm).
+///
+/// A field is parsed from every form item whose name begins with the field's
+/// name. Names are made up of _segments_, separated by `.` or enclosed in
+/// brackets, as split by [`split_field_name()`]. The remainder of an item's
+/// name after the field's own segment is passed along with the item,
+/// allowing types to parse structured data:
+///
+///   * Every [`FromFormValue`] type is a field. It is parsed from the value of
+///     the last item with no remaining segments: `age=21`.
+///
+///   * `Vec<T>` collects one `T` per item named `field`, `field[]`, or
+///     `field.`, and one `T` per distinct index in items named `field[index]`
+///     or `field.index`. Items with the same index are parsed into the same
+///     `T`, so `items[0].name=a&items[0].qty=1` yields a single element.
+///
+///   * `HashMap<K, V>` collects one `V` per distinct key in items named
+///     `field[key]` or `field.key`. The key is parsed with `K`'s
+///     `FromFormValue` implementation.
+///
+///   * Structures deriving `FromForm` are fields as well, parsed from items
+///     named `field.inner` or `field[inner]`, so structures can be nested
+///     arbitrarily: `user.address.city=Paris`.
+///
+/// Parsing proceeds in three steps: a context is created with
+/// [`init()`](FromFormField::init()), every matching item is
+/// [`push()`](FromFormField::push())ed into it, and the value is produced by
+/// [`finalize()`](FromFormField::finalize()).
+///
+/// # Example
+///
+/// ```rust
+/// # #[macro_use] extern crate rocket;
+/// use std::collections::HashMap;
+/// use rocket::request::{FromForm, FormItems};
+///
+/// #[derive(FromForm)]
+/// struct Address {
+///     city: String,
+/// }
+///
+/// #[derive(FromForm)]
+/// struct User {
+///     name: String,
+///     address: Address,
+///     tags: Vec<String>,
+///     scores: HashMap<String, usize>,
+/// }
+///
+/// let form = "name=Bob&address.city=Paris&tags[]=a&tags[]=b&scores[math]=10";
+/// let user = User::from_form(&mut FormItems::from(form), true).unwrap();
+/// assert_eq!(user.address.city, "Paris");
+/// assert_eq!(user.tags, vec!["a", "b"]);
+/// assert_eq!(user.scores["math"], 10);
+/// ```
+pub trait FromFormField<'f>: Sized {
+    /// The state accumulated while items are pushed.
+    type Context;
+
+    /// Returns a new context. When `strict` is `true`, unexpected items must
+    /// be reported as [`FormParseError::Unknown`] during finalization.
+    fn init(strict: bool) -> Self::Context;
+
+    /// Pushes `item` into `ctxt`. `rest` is the part of `item`'s name after
+    /// the segments already consumed by enclosing fields.
+    fn push(ctxt: &mut Self::Context, item: FormItem<'f>, rest: &'f RawStr);
+
+    /// Produces a value from `ctxt`. `name` is the name of the field being
+    /// parsed and should be used to report it as missing. Structures deriving
+    /// `FromForm` prefix the missing fields reported by their own fields with
+    /// `name`, so errors name the full path to a field: `address.street`.
+    fn finalize(ctxt: Self::Context, name: &'f RawStr) -> Result<Self, FormParseErrors<'f>>;
+}
+
+// Returns the length of the delimiter `ch`, or its percent-encoded form `enc`,
+// at the start of `bytes`, or `0` if there is none.
+fn delimiter(bytes: &[u8], ch: u8, enc: &[u8; 2]) -> usize {
+    match bytes {
+        [b, ..] if *b == ch => 1,
+        [b'%', x, y, ..] if *x == enc[0] && y.to_ascii_uppercase() == enc[1] => 3,
+        _ => 0,
+    }
+}
+
+/// Splits the first segment off of the form field name `name`, returning the
+/// segment and the remainder of the name, or `None` if `name` is empty.
+///
+/// Segments are either separated by `.` or enclosed in `[]`. Brackets may be
+/// percent-encoded, as they are by browsers. The first segment of a name needs
+/// no leading `.`.
+///
+/// # Example
+///
+/// ```rust
+/// use rocket::http::RawStr;
+/// use rocket::request::split_field_name;
+///
+/// fn segments(name: &str) -> Vec<&str> {
+///     let mut name = RawStr::from_str(name);
+///     let mut segments = vec![];
+///     while let Some((segment, rest)) = split_field_name(name) {
+///         segments.push(segment.as_str());
+///         name = rest;
+///     }
+///
+///     segments
+/// }
+///
+/// assert_eq!(segments("user"), vec!["user"]);
+/// assert_eq!(segments("user.address.city"), vec!["user", "address", "city"]);
+/// assert_eq!(segments("items[0].name"), vec!["items", "0", "name"]);
+/// assert_eq!(segments("tags[]"), vec!["tags", ""]);
+/// assert_eq!(segments("map%5Bkey%5D"), vec!["map", "key"]);
+/// ```
+pub fn split_field_name(name: &RawStr) -> Option<(&RawStr, &RawStr)> {
+    let (string, bytes) = (name.as_str(), name.as_bytes());
+    if bytes.is_empty() {
+        return None;
+    }
+
+    let open = |i: usize| delimiter(&bytes[i..], b'[', b"5B");
+    let close = |i: usize| delimiter(&bytes[i..], b']', b"5D");
+
+    // All delimiters are ASCII, so every index we slice at is a boundary.
+    let (start, end, rest) = match open(0) {
+        0 => {
+            let start = if bytes[0] == b'.' { 1 } else { 0 };
+            let mut end = start;
+            while end < bytes.len() && bytes[end] != b'.' && open(end) == 0 {
+                end += 1;
+            }
+
+            (start, end, end)
+        }
+        n => {
+            let mut end = n;
+            while end < bytes.len() && close(end) == 0 {
+                end += 1;
+            }
+
+            (n, end, if end < bytes.len() { end + close(end) } else { end })
+        }
+    };
+
+    Some((RawStr::from_str(&string[start..end]), RawStr::from_str(&string[rest..])))
+}
+
+/// Strips the field name `field` from the start of the form field name `name`,
+/// returning the remainder of `name`, or `None` if `name` doesn't begin with a
+/// segment named `field`.
+///
+/// Unlike [`split_field_name()`], `field` may itself contain delimiters, as
+/// is the case for fields renamed to names like `a.b`.
+///
+/// # Example
+///
+/// ```rust
+/// use rocket::http::RawStr;
+/// use rocket::request::strip_field_name;
+///
+/// let name = RawStr::from_str("user.address[city]");
+/// assert_eq!(strip_field_name(name, "user").unwrap(), ".address[city]");
+/// assert_eq!(strip_field_name(name, "user.address").unwrap(), "[city]");
+/// assert!(strip_field_name(name, "use").is_none());
+///
+/// let rest = RawStr::from_str("[city]");
+/// assert_eq!(strip_field_name(rest, "city").unwrap(), "");
+/// ```
+pub fn strip_field_name<'a>(name: &'a RawStr, field: &str) -> Option<&'a RawStr> {
+    let (string, bytes) = (name.as_str(), name.as_bytes());
+    let rest = match delimiter(bytes, b'[', b"5B") {
+        0 => {
+            let start = if bytes.first() == Some(&b'.') { 1 } else { 0 };
+            let rest = string[start..].strip_prefix(field)?;
+            let next = rest.as_bytes();
+            if !next.is_empty() && next[0] != b'.' && delimiter(next, b'[', b"5B") == 0 {
+                return None;
+            }
+
+            rest
+        }
+        n => {
+            let rest = string[n..].strip_prefix(field)?;
+            match delimiter(rest.as_bytes(), b']', b"5D") {
+                0 => return None,
+                m => &rest[m..],
+            }
+        }
+    };
+
+    Some(RawStr::from_str(rest))
+}
+
+/// The [`FromFormField::Context`] of [`FromFormValue`] types.
+#[derive(Debug)]
+pub struct ValueContext<'f> {
+    strict: bool,
+    item: Option<FormItem<'f>>,
+    errors: FormParseErrors<'f>,
+}
+
+impl<'f, T: FromFormValue<'f>> FromFormField<'f> for T {
+    type Context = ValueContext<'f>;
+
+    fn init(strict: bool) -> Self::Context {
+        ValueContext { strict, item: None, errors: FormParseErrors::new() }
+    }
+
+    fn push(ctxt: &mut Self::Context, item: FormItem<'f>, rest: &'f RawStr) {
+        if rest.is_empty() {
+            ctxt.item = Some(item);
+        } else if ctxt.strict {
+            ctxt.errors.push(FormParseError::Unknown(item.key, item.value));
+        }
+    }
+
+    fn finalize(ctxt: Self::Context, name: &'f RawStr) -> Result<Self, FormParseErrors<'f>> {
+        let mut errors = ctxt.errors;
+        let value = match ctxt.item {
+            Some(item) => T::from_form_value(item.value)
+                .map_err(|_| FormParseError::BadValue(item.key, item.value)),
+            None => T::default().ok_or(FormParseError::Missing(name.as_str().into())),
+        };
+
+        match value {
+            Ok(value) if errors.is_empty() => Ok(value),
+            Ok(_) => Err(errors),
+            Err(e) => {
+                errors.push(e);
+                Err(errors)
+            }
+        }
+    }
+}
+
+/// The [`FromFormField::Context`] of `Vec<T>`.
+pub struct VecContext<'f, T: FromFormField<'f>> {
+    strict: bool,
+    elements: Vec<T::Context>,
+    indices: HashMap<&'f RawStr, usize>,
+}
+
+impl<'f, T: FromFormField<'f>> FromFormField<'f> for Vec<T> {
+    type Context = VecContext<'f, T>;
+
+    fn init(strict: bool) -> Self::Context {
+        VecContext { strict, elements: vec![], indices: HashMap::new() }
+    }
+
+    fn push(ctxt: &mut Self::Context, item: FormItem<'f>, rest: &'f RawStr) {
+        // `field`, `field[]`, and `field.` always begin a new element.
+        let (index, rest) = match split_field_name(rest) {
+            Some((index, rest)) if !index.is_empty() => (Some(index), rest),
+            Some((_, rest)) => (None, rest),
+            None => (None, rest),
+        };
+
+        let existing = index.and_then(|index| ctxt.indices.get(index));
+        match existing {
+            Some(&i) => T::push(&mut ctxt.elements[i], item, rest),
+            None => {
+                if let Some(index) = index {
+                    ctxt.indices.insert(index, ctxt.elements.len());
+                }
+
+                let mut element = T::init(ctxt.strict);
+                T::push(&mut element, item, rest);
+                ctxt.elements.push(element);
+            }
+        }
+    }
+
+    fn finalize(ctxt: Self::Context, name: &'f RawStr) -> Result<Self, FormParseErrors<'f>> {
+        let mut errors = FormParseErrors::new();
+        let mut values = Vec::with_capacity(ctxt.elements.len());
+        for element in ctxt.elements {
+            match T::finalize(element, name) {
+                Ok(value) => values.push(value),
+                Err(e) => errors.extend(e),
+            }
+        }
+
+        if errors.is_empty() { Ok(values) } else { Err(errors) }
+    }
+}
+
+/// The [`FromFormField::Context`] of `HashMap<K, V>`.
+pub struct MapContext<'f, V: FromFormField<'f>> {
+    strict: bool,
+    entries: Vec<(FormItem<'f>, &'f RawStr, V::Context)>,
+    keys: HashMap<&'f RawStr, usize>,
+    errors: FormParseErrors<'f>,
+}
+
+impl<'f, K, V> FromFormField<'f> for HashMap<K, V>
+    where K: FromFormValue<'f> + Eq + Hash, V: FromFormField<'f>
+{
+    type Context = MapContext<'f, V>;
+
+    fn init(strict: bool) -> Self::Context {
+        MapContext {
+            strict,
+            entries: vec![],
+            keys: HashMap::new(),
+            errors: FormParseErrors::new(),
+        }
+    }
+
+    fn push(ctxt: &mut Self::Context, item: FormItem<'f>, rest: &'f RawStr) {
+        let (key, rest) = match split_field_name(rest) {
+            Some((key, rest)) if !key.is_empty() => (key, rest),
+            _ => {
+                if ctxt.strict {
+                    ctxt.errors.push(FormParseError::Unknown(item.key, item.value));
+                }
+
+                return;
+            }
+        };
+
+        match ctxt.keys.get(key) {
+            Some(&i) => V::push(&mut ctxt.entries[i].2, item, rest),
+            None => {
+                ctxt.keys.insert(key, ctxt.entries.len());
+                let mut value = V::init(ctxt.strict);
+                V::push(&mut value, item, rest);
+                ctxt.entries.push((item, key, value));
+            }
+        }
+    }
+
+    fn finalize(ctxt: Self::Context, name: &'f RawStr) -> Result<Self, FormParseErrors<'f>> {
+        let mut errors = ctxt.errors;
+        let mut map = HashMap::with_capacity(ctxt.entries.len());
+        for (item, key, value) in ctxt.entries {
+            let key = match K::from_form_value(key) {
+                Ok(key) => Some(key),
+                Err(_) => {
+                    errors.push(FormParseError::BadValue(item.key, item.value));
+                    None
+                }
+            };
+
+            match (key, V::finalize(value, name)) {
+                (Some(key), Ok(value)) => { map.insert(key, value); }
+                (_, Err(e)) => errors.extend(e),
+                (None, Ok(_)) => { /* the error was recorded above */ }
+            }
+        }
+
+        if errors.is_empty() { Ok(map) } else { Err(errors) }
+    }
+}
@@ -3,6 +3,7 @@
 mod form_items;
 mod from_form;
 mod from_form_value;
+mod from_form_field;
 mod lenient;
 mod error;
 mod form;
@@ -10,6 +11,7 @@ mod form;
 pub use self::form_items::{FormItems, FormItem};
 pub use self::from_form::FromForm;
 pub use self::from_form_value::FromFormValue;
+pub use self::from_form_field::{FromFormField, split_field_name, strip_field_name};
 pub use self::form::Form;
 pub use self::lenient::LenientForm;
-pub use self::error::{FormError, FormParseError, FormDataError};
+pub use self::error::{FormError, FormParseError, FormParseErrors, FormDataError};
@@ -9,6 +9,7 @@ use crate::outcome::{self, IntoOutcome};
 use crate::outcome::Outcome::*;
 
 use crate::http::{Status, ContentType, Accept, Method, CookieJar, uri::Origin};
+use crate::http::private::TlsInfo;
 
 /// Type alias for the `Outcome` of a `FromRequest` conversion.
 pub type Outcome<S, E> = outcome::Outcome<S, (Status, E), ()>;
@@ -165,6 +166,23 @@ impl<S, E> IntoOutcome<S, (Status, E), ()> for Result<S, E> {
 ///
 ///     _This implementation always returns successfully._
 ///
+///   * **&TlsInfo**
+///
+///     Extracts the [`TlsInfo`] of the connection carrying the request. If the
+///     connection is not secured by TLS, the request is forwarded.
+///
+///   * **Certificate**
+///
+///     Extracts the [`Certificate`](crate::request::Certificate) presented by
+///     the client. If the connection is not secured by TLS or the client did
+///     not present a certificate, the request is forwarded.
+///
+///   * **ConnectionInfo**
+///
+///     Extracts the [`ConnectionInfo`](crate::request::ConnectionInfo) of the
+///     connection carrying the request. If the request did not arrive over a
+///     connection, as with local clients, the request is forwarded.
+///
 ///   * **Option&lt;T>** _where_ **T: FromRequest**
 ///
 ///     The type `T` is derived from the incoming request using `T`'s
@@ -471,6 +489,18 @@ impl<'a, 'r> FromRequest<'a, 'r> for SocketAddr {
     }
 }
 
+#[crate::async_trait]
+impl<'a, 'r> FromRequest<'a, 'r> for &'a TlsInfo {
+    type Error = std::convert::Infallible;
+
+    async fn from_request(request: &'a Request<'r>) -> Outcome<Self, Self::Error> {
+        match request.tls_info() {
+            Some(tls_info) => Success(tls_info),
+            None => Forward(())
+        }
+    }
+}
+
 impl<'a, 'r, T: FromRequest<'a, 'r> + 'a> FromRequest<'a, 'r> for Result<T, T::Error> {
     type Error = std::convert::Infallible;
 
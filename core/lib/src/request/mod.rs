@@ -6,20 +6,38 @@ mod form;
 mod from_request;
 mod state;
 mod query;
+mod certificate;
+mod connection;
+mod request_id;
+mod trace;
+#[cfg(feature = "secrets")]
+mod signed;
 
 #[cfg(test)]
 mod tests;
 
-#[doc(hidden)] pub use rocket_codegen::{FromForm, FromFormValue};
+#[doc(hidden)] pub use rocket_codegen::{FromForm, FromFormValue, FromRequest};
 
 pub use self::request::Request;
 pub use self::from_request::{FromRequest, Outcome};
 pub use self::param::{FromParam, FromSegments};
-pub use self::form::{FromForm, FromFormValue};
+pub use self::form::{FromForm, FromFormValue, FromFormField};
+pub use self::form::{split_field_name, strip_field_name};
 pub use self::form::{Form, LenientForm, FormItems, FormItem};
-pub use self::form::{FormError, FormParseError, FormDataError};
+pub use self::form::{FormError, FormParseError, FormParseErrors, FormDataError};
 pub use self::state::State;
+pub(crate) use self::state::ScopedState;
 pub use self::query::{Query, FromQuery};
+pub use self::certificate::Certificate;
+pub use self::connection::ConnectionInfo;
+pub use self::request_id::RequestId;
+pub use self::trace::{Trace, TraceEvent, RouteCheck, Rejection};
+pub(crate) use self::trace::{TraceLog, describe as describe_route};
+#[cfg(feature = "secrets")]
+pub use self::signed::{Signed, SignedError};
+
+#[doc(inline)]
+pub use crate::http::private::TlsInfo;
 
 #[doc(inline)]
 pub use crate::response::flash::FlashMessage;
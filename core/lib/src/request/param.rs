@@ -3,7 +3,9 @@ use std::path::PathBuf;
 use std::fmt::Debug;
 use std::borrow::Cow;
 
-use crate::http::{RawStr, uri::{Segments, SegmentError}};
+use crate::request::Request;
+use crate::outcome::Outcome;
+use crate::http::{RawStr, Status, uri::{Segments, SegmentError}};
 
 /// Trait to convert a dynamic path segment string to a concrete value.
 ///
@@ -199,6 +201,35 @@ pub trait FromParam<'a>: Sized {
     /// Parses and validates an instance of `Self` from a path parameter string
     /// or returns an `Error` if parsing or validation fails.
     fn from_param(param: &'a RawStr) -> Result<Self, Self::Error>;
+
+    /// Parses and validates an instance of `Self` from the path parameter
+    /// string `param` of the request `req`.
+    ///
+    /// Rocket's code generation calls this method, not `from_param()`, to parse
+    /// route parameters. Errors returned as a `Forward` forward the request,
+    /// while a `Failure` fails it with the given status. The default
+    /// implementation calls `from_param()` and forwards on error; override it
+    /// only when parsing depends on the request, as it does for
+    /// [`Signed`](crate::request::Signed).
+    #[inline(always)]
+    fn from_request_param(
+        req: &'a Request<'_>,
+        param: &'a RawStr
+    ) -> Outcome<Self, (Status, Self::Error), Self::Error> {
+        let _ = req;
+        match Self::from_param(param) {
+            Ok(val) => Outcome::Success(val),
+            Err(e) => Outcome::Forward(e),
+        }
+    }
+}
+
+/// Converts the outcome of [`FromParam::from_request_param()`] into a `Result`.
+pub(crate) fn param_result<T, E>(outcome: Outcome<T, (Status, E), E>) -> Result<T, E> {
+    match outcome {
+        Outcome::Success(val) => Ok(val),
+        Outcome::Forward(e) | Outcome::Failure((_, e)) => Err(e),
+    }
 }
 
 impl<'a> FromParam<'a> for &'a RawStr {
@@ -264,6 +295,17 @@ impl<'a, T: FromParam<'a>> FromParam<'a> for Result<T, T::Error> {
             Err(e) => Ok(Err(e)),
         }
     }
+
+    #[inline]
+    fn from_request_param(
+        req: &'a Request<'_>,
+        param: &'a RawStr
+    ) -> Outcome<Self, (Status, Self::Error), Self::Error> {
+        match T::from_request_param(req, param) {
+            Outcome::Success(val) => Outcome::Success(Ok(val)),
+            Outcome::Forward(e) | Outcome::Failure((_, e)) => Outcome::Success(Err(e)),
+        }
+    }
 }
 
 impl<'a, T: FromParam<'a>> FromParam<'a> for Option<T> {
@@ -276,6 +318,17 @@ impl<'a, T: FromParam<'a>> FromParam<'a> for Option<T> {
             Err(_) => Ok(None)
         }
     }
+
+    #[inline]
+    fn from_request_param(
+        req: &'a Request<'_>,
+        param: &'a RawStr
+    ) -> Outcome<Self, (Status, Self::Error), Self::Error> {
+        match T::from_request_param(req, param) {
+            Outcome::Success(val) => Outcome::Success(Some(val)),
+            Outcome::Forward(_) | Outcome::Failure(_) => Outcome::Success(None),
+        }
+    }
 }
 
 /// Trait to convert _many_ dynamic path segment strings to a concrete value.
@@ -291,11 +344,17 @@ impl<'a, T: FromParam<'a>> FromParam<'a> for Option<T> {
 /// **`PathBuf`**
 ///
 /// The `PathBuf` implementation constructs a path from the segments iterator.
-/// Each segment is percent-decoded. If a segment equals ".." before or after
-/// decoding, the previous segment (if any) is omitted. For security purposes,
-/// any other segments that begin with "*" or "." are ignored.  If a
-/// percent-decoded segment results in invalid UTF8, an `Err` is returned with
-/// the `Utf8Error`.
+/// Each segment is percent-decoded. For security purposes, segments that could
+/// be used to escape the directory the path is joined to, such as `..`, even
+/// when percent-encoded, are rejected with a [`SegmentError`] indicating why,
+/// as are segments that begin with `*` or `.`. As a result, a `PathBuf` guard
+/// can be safely joined to a base directory to serve files. See the
+/// implementation's documentation for the full list of conditions.
+///
+/// **`Segments`**
+///
+/// The raw, percent-encoded [`Segments`] iterator itself. This implementation
+/// always succeeds.
 pub trait FromSegments<'a>: Sized {
     /// The associated error to be returned when parsing fails.
     type Error: Debug;
@@ -315,16 +374,17 @@ impl<'a> FromSegments<'a> for Segments<'a> {
 }
 
 /// Creates a `PathBuf` from a `Segments` iterator. The returned `PathBuf` is
-/// percent-decoded. If a segment is equal to "..", the previous segment (if
-/// any) is skipped.
+/// percent-decoded.
 ///
 /// For security purposes, if a segment meets any of the following conditions,
 /// an `Err` is returned indicating the condition met:
 ///
-///   * Decoded segment starts with any of: `.` (except `..`), `*`
+///   * Decoded segment is `..`, even if encoded, as in `%2e%2e`.
+///   * Decoded segment starts with any of: `.`, `*`
 ///   * Decoded segment ends with any of: `:`, `>`, `<`
-///   * Decoded segment contains any of: `/`
+///   * Decoded segment contains any of: `/`, NUL
 ///   * On Windows, decoded segment contains any of: `\`
+///   * Decoded segment is not a plain file name, as with a drive prefix.
 ///   * Percent-encoding results in invalid UTF8.
 ///
 /// As a result of these conditions, a `PathBuf` derived via `FromSegments` is
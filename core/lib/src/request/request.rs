@@ -1,8 +1,9 @@
-use std::sync::Arc;
+use std::sync::{Arc, Mutex};
 use std::net::{IpAddr, SocketAddr};
 use std::future::Future;
 use std::fmt;
 use std::str;
+use std::time::{Duration, Instant};
 
 use yansi::Paint;
 use state::{Container, Storage};
@@ -10,14 +11,21 @@ use futures::future::BoxFuture;
 use atomic::{Atomic, Ordering};
 
 use crate::request::{FromParam, FromSegments, FromRequest, Outcome};
-use crate::request::{FromFormValue, FormItems, FormItem};
+use crate::request::param::param_result;
+use crate::request::{FromFormValue, FormItems, FormItem, ScopedState};
+use crate::request::{Trace, TraceEvent, TraceLog, ConnectionInfo, RouteCheck};
 
-use crate::{Rocket, Config, Shutdown, Route};
+use crate::{Rocket, Orbit, Config, Shutdown, Route};
 use crate::http::{hyper, uri::{Origin, Segments}};
-use crate::http::{Method, Header, HeaderMap, uncased::UncasedStr};
-use crate::http::{RawStr, ContentType, Accept, MediaType, CookieJar, Cookie};
-use crate::http::private::{Indexed, SmallVec};
+use crate::http::{Method, Version, Header, HeaderMap, uncased::UncasedStr};
+use crate::http::{RawStr, ContentType, Accept, MediaType, CookieJar, Cookie, Status};
+use crate::http::private::{Indexed, SmallVec, TlsInfo};
+use crate::http::ext::IntoOwned;
+use crate::http::route::Kind;
 use crate::data::Limits;
+use crate::response::ResponseParts;
+use crate::router::Router;
+use crate::deferred::{self, Deferred, TaskResult};
 
 /// The type of an incoming web request.
 ///
@@ -27,16 +35,23 @@ use crate::data::Limits;
 /// data. This includes the HTTP method, URI, cookies, headers, and more.
 pub struct Request<'r> {
     method: Atomic<Method>,
+    version: Version,
     uri: Origin<'r>,
     headers: HeaderMap<'r>,
     remote: Option<SocketAddr>,
+    tls_info: Option<Arc<TlsInfo>>,
+    connection: Option<ConnectionInfo>,
     pub(crate) state: RequestState<'r>,
 }
 
 pub(crate) struct RequestState<'r> {
     pub config: &'r Config,
     pub managed: &'r Container,
+    pub scoped: &'r ScopedState,
+    pub router: &'r Router,
     pub shutdown: &'r Shutdown,
+    #[cfg(unix)]
+    pub handover: Option<&'r crate::Handover>,
     pub path_segments: SmallVec<[Indices; 12]>,
     pub query_items: Option<SmallVec<[IndexedFormItem; 6]>>,
     pub route: Atomic<Option<&'r Route>>,
@@ -44,15 +59,22 @@ pub(crate) struct RequestState<'r> {
     pub accept: Storage<Option<Accept>>,
     pub content_type: Storage<Option<ContentType>>,
     pub cache: Arc<Container>,
+    pub trace: TraceLog,
+    pub started: Instant,
+    pub guards: Arc<Mutex<GuardChain>>,
+    pub failure: Option<Status>,
 }
 
 impl Request<'_> {
     pub(crate) fn clone(&self) -> Self {
         Request {
             method: Atomic::new(self.method()),
+            version: self.version,
             uri: self.uri.clone(),
             headers: self.headers.clone(),
             remote: self.remote.clone(),
+            tls_info: self.tls_info.clone(),
+            connection: self.connection,
             state: self.state.clone(),
         }
     }
@@ -63,7 +85,11 @@ impl RequestState<'_> {
         RequestState {
             config: self.config,
             managed: self.managed,
+            scoped: self.scoped,
+            router: self.router,
             shutdown: self.shutdown,
+            #[cfg(unix)]
+            handover: self.handover,
             path_segments: self.path_segments.clone(),
             query_items: self.query_items.clone(),
             route: Atomic::new(self.route.load(Ordering::Acquire)),
@@ -71,6 +97,10 @@ impl RequestState<'_> {
             accept: self.accept.clone(),
             content_type: self.content_type.clone(),
             cache: self.cache.clone(),
+            trace: self.trace.clone(),
+            started: self.started,
+            guards: self.guards.clone(),
+            failure: self.failure,
         }
     }
 }
@@ -79,26 +109,41 @@ impl<'r> Request<'r> {
     /// Create a new `Request` with the given `method` and `uri`.
     #[inline(always)]
     pub(crate) fn new<'s: 'r>(
-        rocket: &'r Rocket,
+        rocket: &'r Rocket<Orbit>,
         method: Method,
         uri: Origin<'s>
     ) -> Request<'r> {
         let mut request = Request {
             uri,
             method: Atomic::new(method),
+            version: Version::Http11,
             headers: HeaderMap::new(),
             remote: None,
+            tls_info: None,
+            connection: None,
             state: RequestState {
                 path_segments: SmallVec::new(),
                 query_items: None,
                 config: &rocket.config,
                 managed: &rocket.managed_state,
+                scoped: &rocket.scoped_state,
+                router: &rocket.router,
                 shutdown: &rocket.shutdown_handle,
+                #[cfg(unix)]
+                handover: rocket.handover.as_ref(),
                 route: Atomic::new(None),
-                cookies: CookieJar::new(&rocket.config.secret_key),
+                cookies: CookieJar::new(
+                    &rocket.config.secret_key,
+                    rocket.config.cookie_secure,
+                    rocket.config.cookie_same_site,
+                ),
                 accept: Storage::new(),
                 content_type: Storage::new(),
                 cache: Arc::new(Container::new()),
+                trace: TraceLog::new(rocket.config.trace || rocket.config.diagnostics),
+                started: Instant::now(),
+                guards: Arc::default(),
+                failure: None,
             }
         };
 
@@ -181,15 +226,89 @@ impl<'r> Request<'r> {
         self.update_cached_uri_info();
     }
 
+    /// Fails `self` with `status` before it is routed: no route is attempted,
+    /// and the catcher for `status` responds instead. This allows a request
+    /// fairing to reject a request regardless of the routes that would match
+    /// it.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use rocket::Request;
+    /// use rocket::http::{Method, Status};
+    ///
+    /// # Request::example(Method::Get, "/uri", |mut request| {
+    /// assert_eq!(request.failure(), None);
+    ///
+    /// request.set_failure(Status::Forbidden);
+    /// assert_eq!(request.failure(), Some(Status::Forbidden));
+    /// # });
+    /// ```
+    #[inline(always)]
+    pub fn set_failure(&mut self, status: Status) {
+        self.state.failure = Some(status);
+    }
+
+    /// Returns the status `self` was failed with via
+    /// [`set_failure()`](Request::set_failure()), if any.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use rocket::Request;
+    /// # use rocket::http::Method;
+    /// # Request::example(Method::Get, "/uri", |request| {
+    /// assert_eq!(request.failure(), None);
+    /// # });
+    /// ```
+    #[inline(always)]
+    pub fn failure(&self) -> Option<Status> {
+        self.state.failure
+    }
+
+    /// Returns `uri` with the configured `path_prefix`, if any, prepended.
+    /// This is the URI clients must use to reach `uri` through the reverse
+    /// proxy serving the application under the prefix. Use this method to
+    /// generate links to routes, typically with URIs from [`uri!`].
+    /// [`Redirect`](crate::response::Redirect) applies the prefix itself.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # #[macro_use] extern crate rocket;
+    /// use rocket::Request;
+    /// use rocket::http::uri::Origin;
+    ///
+    /// #[get("/profile")]
+    /// fn profile() { /* .. */ }
+    ///
+    /// fn profile_link(request: &Request<'_>) -> String {
+    ///     // With a `path_prefix` of `/app`, this is `/app/profile`.
+    ///     request.prefixed_uri(uri!(profile)).to_string()
+    /// }
+    /// ```
+    pub fn prefixed_uri(&self, uri: Origin<'_>) -> Origin<'static> {
+        match self.state.config.path_prefix_segments() {
+            Some(prefix) => uri.map_path(|path| format!("/{}{}", prefix, path))
+                .unwrap_or(uri)
+                .into_owned(),
+            None => uri.into_owned(),
+        }
+    }
+
     /// Returns the address of the remote connection that initiated this
     /// request if the address is known. If the address is not known, `None` is
     /// returned.
     ///
     /// Because it is common for proxies to forward connections for clients, the
     /// remote address may contain information about the proxy instead of the
-    /// client. For this reason, proxies typically set the "X-Real-IP" header
-    /// with the client's true IP. To extract this IP from the request, use the
-    /// [`real_ip()`] or [`client_ip()`] methods.
+    /// client. For this reason, proxies typically set a header such as
+    /// "X-Real-IP", "X-Forwarded-For", or "Forwarded" with the client's true
+    /// IP. To extract this IP from the request, use the [`real_ip()`] or
+    /// [`client_ip()`] methods. Proxies that speak the PROXY protocol instead
+    /// send the client's address ahead of the connection's data: when the
+    /// `proxy_protocol` configuration parameter is enabled, this method
+    /// returns that address.
     ///
     /// [`real_ip()`]: #method.real_ip
     /// [`client_ip()`]: #method.client_ip
@@ -232,8 +351,75 @@ impl<'r> Request<'r> {
         self.remote = Some(address);
     }
 
-    /// Returns the IP address in the "X-Real-IP" header of the request if such
-    /// a header exists and contains a valid IP address.
+    /// Returns the HTTP version of the protocol that carried this request.
+    /// Local requests are always `HTTP/1.1`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use rocket::Request;
+    /// # use rocket::http::Method;
+    /// use rocket::http::Version;
+    ///
+    /// # Request::example(Method::Get, "/uri", |request| {
+    /// assert_eq!(request.version(), Version::Http11);
+    /// # });
+    /// ```
+    #[inline(always)]
+    pub fn version(&self) -> Version {
+        self.version
+    }
+
+    /// Returns the metadata negotiated during the TLS handshake of the
+    /// connection that carried this request. Returns `None` if the connection
+    /// was not secured by TLS.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use rocket::Request;
+    /// # use rocket::http::Method;
+    /// # Request::example(Method::Get, "/uri", |request| {
+    /// if let Some(tls) = request.tls_info() {
+    ///     println!("SNI: {:?}", tls.sni_hostname());
+    /// }
+    /// # });
+    /// ```
+    #[inline(always)]
+    pub fn tls_info(&self) -> Option<&TlsInfo> {
+        self.tls_info.as_deref()
+    }
+
+    /// Returns information about the connection that carried this request.
+    /// Returns `None` if the request did not arrive over a connection, as is
+    /// the case for requests dispatched by a local client.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use rocket::Request;
+    /// # use rocket::http::Method;
+    /// # Request::example(Method::Get, "/uri", |request| {
+    /// if let Some(conn) = request.connection_info() {
+    ///     println!("connection #{}", conn.id());
+    /// }
+    /// # });
+    /// ```
+    #[inline(always)]
+    pub fn connection_info(&self) -> Option<&ConnectionInfo> {
+        self.connection.as_ref()
+    }
+
+    /// Returns the client's IP address as reported by the first of the
+    /// trusted proxy headers, configured via
+    /// [`Config::ip_headers`](crate::Config::ip_headers), that is present and
+    /// contains a valid IP address. By default, only "X-Real-IP" is trusted.
+    ///
+    /// For "X-Forwarded-For" and "Forwarded", the last address in the header is
+    /// used: earlier addresses are supplied by the client or by proxies that
+    /// are not necessarily trusted. Any other header must contain a single IP
+    /// address, optionally with a port. Obfuscated and `unknown` `Forwarded`
+    /// identifiers are ignored.
     ///
     /// # Example
     ///
@@ -248,21 +434,24 @@ impl<'r> Request<'r> {
     /// # });
     /// ```
     pub fn real_ip(&self) -> Option<IpAddr> {
-        self.headers()
-            .get_one("X-Real-IP")
-            .and_then(|ip| {
-                ip.parse()
-                    .map_err(|_| warn_!("'X-Real-IP' header is malformed: {}", ip))
-                    .ok()
+        self.state.config.ip_headers.iter()
+            .filter_map(|name| Some((name, self.headers().get(name).last()?)))
+            .filter_map(|(name, value)| match parse_ip_header(name, value) {
+                Ok(ip) => ip,
+                Err(_) => {
+                    warn_!("'{}' header is malformed: {}", name, value);
+                    None
+                }
             })
+            .next()
     }
 
     /// Attempts to return the client's IP address by first inspecting the
-    /// "X-Real-IP" header and then using the remote connection's IP address.
+    /// trusted proxy headers and then using the remote connection's IP address.
     ///
-    /// If the "X-Real-IP" header exists and contains a valid IP address, that
-    /// address is returned. Otherwise, if the address of the remote connection
-    /// is known, that address is returned. Otherwise, `None` is returned.
+    /// If [`real_ip()`](#method.real_ip) returns an address, that address is
+    /// returned. Otherwise, if the address of the remote connection is known,
+    /// that address is returned. Otherwise, `None` is returned.
     ///
     /// # Example
     ///
@@ -312,6 +501,26 @@ impl<'r> Request<'r> {
         &self.state.cookies
     }
 
+    /// Returns the [`ResponseParts`] of `self`: headers and cookies committed
+    /// to the response to `self`, whether it succeeds or fails.
+    ///
+    /// # Example
+    ///
+    /// Commit the removal of a cookie, even if the request fails:
+    ///
+    /// ```rust
+    /// # use rocket::Request;
+    /// # use rocket::http::Method;
+    /// use rocket::http::Cookie;
+    ///
+    /// # Request::example(Method::Get, "/uri", |request| {
+    /// request.response_parts().remove_cookie(Cookie::named("session"));
+    /// # });
+    /// ```
+    pub fn response_parts(&self) -> &ResponseParts {
+        self.local_cache(ResponseParts::default)
+    }
+
     /// Returns a [`HeaderMap`] of all of the headers in `self`.
     ///
     /// # Example
@@ -504,8 +713,115 @@ impl<'r> Request<'r> {
         self.state.route.load(Ordering::Acquire)
     }
 
+    /// Returns the instant at which Rocket began dispatching `self`, before any
+    /// request fairings ran. The instant is read from a monotonic clock.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use rocket::Request;
+    /// # use rocket::http::Method;
+    /// # Request::example(Method::Get, "/uri", |mut request| {
+    /// let started = request.started();
+    /// assert!(started <= std::time::Instant::now());
+    /// # });
+    /// ```
+    #[inline(always)]
+    pub fn started(&self) -> Instant {
+        self.state.started
+    }
+
+    /// Returns the time elapsed since Rocket began dispatching `self`. Called
+    /// from a response fairing, this is the time taken to handle `self`,
+    /// including that taken by fairings, guards, and the handler, but not that
+    /// taken to write the response.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::fairing::AdHoc;
+    ///
+    /// let slow_requests = AdHoc::on_response("Slow Requests", |req, _| Box::pin(async move {
+    ///     if req.elapsed().as_millis() > 500 {
+    ///         println!("{} {} took {:?}", req.method(), req.uri(), req.elapsed());
+    ///     }
+    /// }));
+    /// ```
+    #[inline(always)]
+    pub fn elapsed(&self) -> Duration {
+        self.state.started.elapsed()
+    }
+
+    /// Returns the routes, guard failures, and forwards recorded so far while
+    /// routing `self`. The trace is empty if tracing is disabled via the
+    /// `trace` and `diagnostics` configuration parameters. See [`Trace`] for
+    /// details.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use rocket::Request;
+    /// # use rocket::http::Method;
+    /// # Request::example(Method::Get, "/uri", |mut request| {
+    /// // Routing hasn't commenced, so nothing has been recorded.
+    /// assert!(request.trace().is_empty());
+    /// # });
+    /// ```
+    pub fn trace(&self) -> Trace {
+        self.state.trace.get()
+    }
+
+    /// Checks `self` against every mounted route, explaining why each route
+    /// does or doesn't match. The routes that match are listed first, in the
+    /// order they would be tried, followed by the routes that don't along with
+    /// the [`Rejection`](crate::request::Rejection) for each.
+    ///
+    /// Unlike [`Request::trace()`], the explanation doesn't depend on how
+    /// `self` was actually routed and is always available, making it useful
+    /// for debugging overlapping routes from a local client or a catcher.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # #[macro_use] extern crate rocket;
+    /// use rocket::local::blocking::Client;
+    /// use rocket::request::Rejection;
+    ///
+    /// #[get("/hello/<name>")]
+    /// fn hello(name: String) -> String { name }
+    ///
+    /// #[post("/hello/<name>")]
+    /// fn post_hello(name: String) -> String { name }
+    ///
+    /// let rocket = rocket::ignite().mount("/", routes![hello, post_hello]);
+    /// let client = Client::tracked(rocket).unwrap();
+    /// let request = client.get("/hello/Bob");
+    /// let checks = request.inner().explain_routing();
+    ///
+    /// assert_eq!(checks[0].rejection, None);
+    /// assert_eq!(checks[1].rejection, Some(Rejection::Method));
+    /// ```
+    pub fn explain_routing(&self) -> Vec<RouteCheck> {
+        self.state.router.explain(self)
+    }
+
     /// Invokes the request guard implementation for `T`, returning its outcome.
     ///
+    /// This is the sanctioned way for one request guard to depend on another:
+    /// a `FromRequest` implementation for an `AdminUser` can invoke the guard
+    /// for `User`, which in turn invokes the guard for a `Session`. To avoid
+    /// invoking the same guard more than once per request, see
+    /// [`Request::cached_guard()`].
+    ///
+    /// # Cycles
+    ///
+    /// Guards invoked through this method are tracked. If a guard for `T`
+    /// directly or indirectly invokes the guard for `T` again, which would
+    /// never complete, the cycle is logged, the inner invocation forwards, and
+    /// the request fails with a `500 Internal Server Error`. Since guards are
+    /// tracked per request, invoking the guard for the same `T` concurrently,
+    /// as with `join!`, is also treated as a cycle.
+    ///
     /// # Example
     ///
     /// Assuming a `User` request guard exists, invoke it:
@@ -534,14 +850,77 @@ impl<'r> Request<'r> {
     pub fn guard<'z, 'a, T>(&'a self) -> BoxFuture<'z, Outcome<T, T::Error>>
         where T: FromRequest<'a, 'r> + 'z, 'a: 'z, 'r: 'z
     {
-        T::from_request(self)
+        let name = std::any::type_name::<T>();
+        let active = match GuardChain::enter(&self.state.guards, name) {
+            Some(active) => active,
+            None => return Box::pin(async { Outcome::Forward(()) }),
+        };
+
+        Box::pin(async move {
+            let outcome = T::from_request(self).await;
+            drop(active);
+            outcome
+        })
+    }
+
+    /// Invokes the request guard implementation for `T` at most once per
+    /// request, returning a clone of the cached outcome on subsequent calls.
+    ///
+    /// Use this method to share the work of an expensive guard, such as one
+    /// that loads a user from a database, between the guards and handler of a
+    /// request. The guard is invoked via [`Request::guard()`], so cycles are
+    /// detected as described there.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::request::{self, Request, FromRequest};
+    /// use rocket::outcome::try_outcome;
+    ///
+    /// # #[derive(Clone)]
+    /// # struct User { admin: bool }
+    /// # #[rocket::async_trait]
+    /// # impl<'a, 'r> FromRequest<'a, 'r> for User {
+    /// #     type Error = ();
+    /// #     async fn from_request(_: &'a Request<'r>) -> request::Outcome<Self, ()> {
+    /// #         request::Outcome::Success(User { admin: true })
+    /// #     }
+    /// # }
+    /// struct AdminUser(User);
+    ///
+    /// #[rocket::async_trait]
+    /// impl<'a, 'r> FromRequest<'a, 'r> for AdminUser {
+    ///     type Error = ();
+    ///
+    ///     async fn from_request(req: &'a Request<'r>) -> request::Outcome<Self, ()> {
+    ///         // `User` is loaded at most once, even if a handler also uses it.
+    ///         let user = try_outcome!(req.cached_guard::<User>().await);
+    ///         if user.admin {
+    ///             request::Outcome::Success(AdminUser(user))
+    ///         } else {
+    ///             request::Outcome::Forward(())
+    ///         }
+    ///     }
+    /// }
+    /// ```
+    pub async fn cached_guard<'a, T>(&'a self) -> Outcome<T, T::Error>
+        where T: FromRequest<'a, 'r> + Clone + Send + Sync + 'static,
+              T::Error: Clone + Send + Sync + 'static
+    {
+        let cached = self.local_cache_async(async {
+            CachedOutcome(self.guard::<T>().await)
+        }).await;
+
+        cached.0.clone()
     }
 
     #[inline(always)]
     pub fn managed_state<T>(&self) -> Option<&'r T>
         where T: Send + Sync + 'static
     {
-        self.state.managed.try_get::<T>()
+        self.route()
+            .and_then(|route| self.state.scoped.get::<T>(route.base()))
+            .or_else(|| self.state.managed.try_get::<T>())
     }
 
     /// Retrieves the cached value for type `T` from the request-local cached
@@ -607,6 +986,32 @@ impl<'r> Request<'r> {
         }
     }
 
+    /// Defers `f` until the response to `self` has been sent: the future
+    /// returned by `f` is then run to completion on Rocket's runtime. If the
+    /// future resolves to an `Err`, the error is logged. Handlers defer work
+    /// via the [`Deferrer`](crate::deferred::Deferrer) request guard instead.
+    /// See [`deferred`](crate::deferred) for details.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::fairing::AdHoc;
+    ///
+    /// let audit = AdHoc::on_request("Audit Log", |req, _| Box::pin(async move {
+    ///     let line = format!("{} {}", req.method(), req.uri());
+    ///     req.defer(move || async move {
+    ///         /* append `line` to the audit log... */
+    ///     });
+    /// }));
+    /// ```
+    pub fn defer<F, Fut>(&self, f: F)
+        where F: FnOnce() -> Fut + Send + 'static,
+              Fut: Future + Send + 'static,
+              Fut::Output: TaskResult
+    {
+        self.local_cache(Deferred::default).push(deferred::task(f));
+    }
+
     /// Retrieves and parses into `T` the 0-indexed `n`th segment from the
     /// request. Returns `None` if `n` is greater than the number of segments.
     /// Returns `Some(Err(T::Error))` if the parameter type `T` failed to be
@@ -642,7 +1047,7 @@ impl<'r> Request<'r> {
     pub fn get_param<'a, T>(&'a self, n: usize) -> Option<Result<T, T::Error>>
         where T: FromParam<'a>
     {
-        Some(T::from_param(self.raw_segment_str(n)?))
+        Some(param_result(T::from_request_param(self, self.raw_segment_str(n)?)))
     }
 
     /// Retrieves and parses into `T` all of the path segments in the request
@@ -689,6 +1094,114 @@ impl<'r> Request<'r> {
         Some(T::from_segments(self.raw_segments(n)?))
     }
 
+    /// Retrieves and parses into `T` the dynamic path parameter named `name`
+    /// in the currently matched route, as in `<name>`. `T` must implement
+    /// [`FromParam`], which is used to parse the parameter's value.
+    ///
+    /// Unlike [`Request::get_param()`], which requires the position of the
+    /// parameter's segment, this method looks the parameter up by the name it
+    /// was given in the route's path. This is useful in custom handlers and
+    /// response fairings, which don't go through Rocket's code generation.
+    ///
+    /// # Error
+    ///
+    /// If no route has been matched or the matched route has no single-segment
+    /// parameter named `name`, returns `None`. If parsing the parameter fails,
+    /// returns `Some(Err(T::Error))`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::{Request, Data, Route, http::Method};
+    /// use rocket::handler::{Handler, Outcome};
+    ///
+    /// #[derive(Clone)]
+    /// struct Greeter;
+    ///
+    /// #[rocket::async_trait]
+    /// impl Handler for Greeter {
+    ///     async fn handle<'r, 's: 'r>(&'s self, req: &'r Request<'_>, _: Data) -> Outcome<'r> {
+    ///         let name = req.get_param_by_name::<String>("name")
+    ///             .and_then(|r| r.ok())
+    ///             .unwrap_or_else(|| "stranger".into());
+    ///
+    ///         Outcome::from(req, format!("Hello, {}!", name))
+    ///     }
+    /// }
+    ///
+    /// let route = Route::new(Method::Get, "/hello/<name>", Greeter);
+    /// # let rocket = rocket::ignite().mount("/greet", vec![route]);
+    /// # let client = rocket::local::blocking::Client::tracked(rocket).unwrap();
+    /// # let response = client.get("/greet/hello/Bob").dispatch();
+    /// # assert_eq!(response.into_string().unwrap(), "Hello, Bob!");
+    /// ```
+    pub fn get_param_by_name<'a, T>(&'a self, name: &str) -> Option<Result<T, T::Error>>
+        where T: FromParam<'a>
+    {
+        let (i, j) = self.named_path_segment(name, Kind::Single)?;
+        Some(param_result(T::from_request_param(self, self.uri.path()[i..j].into())))
+    }
+
+    /// Retrieves and parses into `T` the segments matched by the dynamic
+    /// multi-segment parameter named `name` in the currently matched route, as
+    /// in `<name..>`. `T` must implement [`FromSegments`], which is used to
+    /// parse the segments.
+    ///
+    /// This is the by-name analog of [`Request::get_segments()`].
+    ///
+    /// # Error
+    ///
+    /// If no route has been matched or the matched route has no multi-segment
+    /// parameter named `name`, returns `None`. If parsing the segments fails,
+    /// returns `Some(Err(T::Error))`. If the parameter matched no segments, `T`
+    /// is parsed from an empty set of segments.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use std::path::PathBuf;
+    ///
+    /// use rocket::{Request, Data, Route, http::Method};
+    /// use rocket::handler::{Handler, Outcome};
+    ///
+    /// #[derive(Clone)]
+    /// struct Echo;
+    ///
+    /// #[rocket::async_trait]
+    /// impl Handler for Echo {
+    ///     async fn handle<'r, 's: 'r>(&'s self, req: &'r Request<'_>, _: Data) -> Outcome<'r> {
+    ///         let path = req.get_segments_by_name::<PathBuf>("path")
+    ///             .and_then(|r| r.ok())
+    ///             .unwrap_or_default();
+    ///
+    ///         Outcome::from(req, path.display().to_string())
+    ///     }
+    /// }
+    ///
+    /// let route = Route::new(Method::Get, "/files/<path..>", Echo);
+    /// # let rocket = rocket::ignite().mount("/", vec![route]);
+    /// # let client = rocket::local::blocking::Client::tracked(rocket).unwrap();
+    /// # let response = client.get("/files/a/b.txt").dispatch();
+    /// # assert_eq!(response.into_string().unwrap(), "a/b.txt");
+    /// ```
+    pub fn get_segments_by_name<'a, T>(&'a self, name: &str) -> Option<Result<T, T::Error>>
+        where T: FromSegments<'a>
+    {
+        let path = self.uri.path();
+        let segments = match self.named_path_segment(name, Kind::Multi) {
+            Some((i, _)) => Segments(&path[i..]),
+            None => {
+                // A trailing `<name..>` may match zero segments.
+                self.route()?.metadata.path_segments.iter()
+                    .find(|s| s.kind == Kind::Multi && s.name == name)?;
+
+                Segments("")
+            }
+        };
+
+        Some(T::from_segments(segments))
+    }
+
     /// Retrieves and parses into `T` the query value with key `key`. `T` must
     /// implement [`FromFormValue`], which is used to parse the query's value.
     /// Key matching is performed case-sensitively. If there are multiple pairs
@@ -737,6 +1250,48 @@ impl<'r> Request<'r> {
     }
 }
 
+/// The request guards being invoked via `Request::guard()` for a request.
+#[derive(Default)]
+pub(crate) struct GuardChain {
+    /// The type names of the active guards, outermost first.
+    active: Vec<&'static str>,
+    /// The first cycle found, as the chain of type names that formed it.
+    cycle: Option<String>,
+}
+
+/// Marks a guard as active in a `GuardChain` until dropped.
+struct ActiveGuard<'a>(&'a Mutex<GuardChain>, &'static str);
+
+impl GuardChain {
+    /// Marks the guard `name` as active in `chain`. If it already is, records
+    /// and logs the cycle and returns `None`.
+    fn enter<'a>(chain: &'a Mutex<GuardChain>, name: &'static str) -> Option<ActiveGuard<'a>> {
+        let mut guards = chain.lock().expect("guard chain lock");
+        if guards.active.contains(&name) {
+            let cycle = format!("{} -> {}", guards.active.join(" -> "), name);
+            error_!("Request guard cycle detected: {}.", cycle);
+            guards.cycle.get_or_insert(cycle);
+            return None;
+        }
+
+        guards.active.push(name);
+        Some(ActiveGuard(chain, name))
+    }
+}
+
+impl Drop for ActiveGuard<'_> {
+    fn drop(&mut self) {
+        if let Ok(mut guards) = self.0.lock() {
+            if let Some(i) = guards.active.iter().rposition(|name| *name == self.1) {
+                guards.active.remove(i);
+            }
+        }
+    }
+}
+
+/// The outcome of a request guard, as cached by `Request::cached_guard()`.
+struct CachedOutcome<T, E>(Outcome<T, E>);
+
 // All of these methods only exist for internal, including codegen, purposes.
 // They _are not_ part of the stable API. Please, don't use these.
 #[doc(hidden)]
@@ -756,7 +1311,7 @@ impl<'r> Request<'r> {
 
     // Only used by doc-tests! Needs to be `pub` because doc-test are external.
     pub fn example<F: Fn(&mut Request<'_>)>(method: Method, uri: &str, f: F) {
-        let rocket = Rocket::custom(Config::default());
+        let rocket = Rocket::custom(Config::default())._orbit();
         let uri = Origin::parse(uri).expect("invalid URI in example");
         let mut request = Request::new(&rocket, method, uri);
         f(&mut request);
@@ -805,6 +1360,16 @@ impl<'r> Request<'r> {
             .map(move |(i, j)| path[i..j].into())
     }
 
+    // Returns the bounds of the request path segment matched by the route
+    // segment of kind `kind` named `name`, if there is one.
+    #[inline]
+    fn named_path_segment(&self, name: &str, kind: Kind) -> Option<(usize, usize)> {
+        let index = self.route()?.metadata.path_segments.iter()
+            .position(|s| s.kind == kind && s.name == name)?;
+
+        self.state.path_segments.get(index).cloned()
+    }
+
     #[inline]
     fn routed_path_segment(&self, n: usize) -> Option<(usize, usize)> {
         let mount_segments = self.route()
@@ -825,6 +1390,27 @@ impl<'r> Request<'r> {
         })
     }
 
+    /// Returns the routes matching `self` in the order they would be tried,
+    /// regardless of whether they were. Used by the diagnostic catcher pages.
+    #[inline]
+    pub(crate) fn candidate_routes(&self) -> Vec<&'r Route> {
+        self.state.router.route(self)
+    }
+
+    /// Records the event returned by `event` in the trace of `self` if tracing
+    /// is enabled. Used by routing and codegen.
+    #[inline]
+    pub fn _trace<F: FnOnce() -> TraceEvent>(&self, event: F) {
+        self.state.trace.record(event)
+    }
+
+    /// Returns the chain of request guards that formed a cycle if one was
+    /// detected by `Request::guard()` for `self`.
+    #[inline]
+    pub(crate) fn guard_cycle(&self) -> Option<String> {
+        self.state.guards.lock().ok().and_then(|guards| guards.cycle.clone())
+    }
+
     /// Set `self`'s parameters given that the route used to reach this request
     /// was `route`. Use during routing when attempting a given route.
     #[inline(always)]
@@ -832,6 +1418,14 @@ impl<'r> Request<'r> {
         self.state.route.store(Some(route), Ordering::Release)
     }
 
+    /// Removes all headers named `name` from `self`.
+    #[cfg(feature = "decompression")]
+    #[inline(always)]
+    pub(crate) fn _remove_header(&mut self, name: &str) {
+        self.bust_header_cache(UncasedStr::new(name), true);
+        self.headers.remove(name);
+    }
+
     /// Set the method of `self`, even when `self` is a shared reference. Used
     /// during routing to override methods for re-routing.
     #[inline(always)]
@@ -839,28 +1433,52 @@ impl<'r> Request<'r> {
         self.method.store(method, Ordering::Release)
     }
 
+    /// Set the TLS metadata of the connection carrying this request.
+    #[inline(always)]
+    pub(crate) fn set_tls_info(&mut self, tls_info: Option<Arc<TlsInfo>>) {
+        self.tls_info = tls_info;
+    }
+
+    /// Set the information about the connection carrying this request.
+    #[inline(always)]
+    pub(crate) fn set_connection_info(&mut self, connection: ConnectionInfo) {
+        self.connection = Some(connection);
+    }
+
     pub(crate) fn cookies_mut(&mut self) -> &mut CookieJar<'r> {
         &mut self.state.cookies
     }
 
     /// Convert from Hyper types into a Rocket Request.
     pub(crate) fn from_hyp(
-        rocket: &'r Rocket,
+        rocket: &'r Rocket<Orbit>,
         h_method: hyper::Method,
+        h_version: hyper::Version,
         h_headers: hyper::HeaderMap<hyper::HeaderValue>,
         h_uri: &'r hyper::Uri,
         h_addr: SocketAddr,
     ) -> Result<Request<'r>, String> {
+        let version = match Version::from_hyp(h_version) {
+            Some(version) => version,
+            None => return Err(format!("Unsupported HTTP version: {:?}", h_version)),
+        };
+
         // Get a copy of the URI (only supports path-and-query) for later use.
+        // HTTP/2 requests carry the scheme and authority as pseudo-headers,
+        // which appear as part of the URI.
         let uri = match (h_uri.scheme(), h_uri.authority(), h_uri.path_and_query()) {
             (None, None, Some(paq)) => paq.as_str(),
+            (_, _, Some(paq)) if version == Version::Http2 => paq.as_str(),
             _ => return Err(format!("Bad URI: {}", h_uri)),
         };
 
-        // Ensure that the method is known. TODO: Allow made-up methods?
+        // Ensure that the method is known: either standard or used by a route.
         let method = match Method::from_hyp(&h_method) {
             Some(method) => method,
-            None => return Err(format!("Unknown or invalid method: {}", h_method))
+            None => match rocket.router.custom_method(h_method.as_str()) {
+                Some(method) => method,
+                None => return Err(format!("Unknown or invalid method: {}", h_method))
+            }
         };
 
         // We need to re-parse the URI since we don't trust Hyper... :(
@@ -868,8 +1486,16 @@ impl<'r> Request<'r> {
 
         // Construct the request object.
         let mut request = Request::new(rocket, method, uri);
+        request.version = version;
         request.set_remote(h_addr);
 
+        // HTTP/2 requests name the host in the `:authority` pseudo-header.
+        if let Some(authority) = h_uri.authority() {
+            if !h_headers.contains_key("Host") {
+                request.add_header(Header::new("Host", authority.as_str().to_string()));
+            }
+        }
+
         // Set the request cookies, if they exist.
         for header in h_headers.get_all("Cookie") {
             let raw_str = match std::str::from_utf8(header.as_bytes()) {
@@ -903,6 +1529,8 @@ impl fmt::Debug for Request<'_> {
             .field("uri", &self.uri)
             .field("headers", &self.headers())
             .field("remote", &self.remote())
+            .field("tls_info", &self.tls_info())
+            .field("connection", &self.connection_info())
             .field("cookies", &self.cookies())
             .finish()
     }
@@ -925,6 +1553,44 @@ impl fmt::Display for Request<'_> {
     }
 }
 
+/// Parses the client's IP address from the value of the proxy header `name`.
+/// Returns `Ok(None)` if the header deliberately doesn't identify the client,
+/// as with an obfuscated `Forwarded` identifier, and `Err` if it's malformed.
+fn parse_ip_header(name: &str, value: &str) -> Result<Option<IpAddr>, ()> {
+    fn parse_addr(addr: &str) -> Result<IpAddr, ()> {
+        addr.parse::<IpAddr>()
+            .or_else(|_| addr.parse::<SocketAddr>().map(|addr| addr.ip()))
+            .map_err(|_| ())
+    }
+
+    // The nearest proxy appends its peer's address to the end of the list.
+    let last = value.rsplit(',').next().unwrap_or(value).trim();
+    if name.eq_ignore_ascii_case("X-Forwarded-For") {
+        return parse_addr(last).map(Some);
+    } else if !name.eq_ignore_ascii_case("Forwarded") {
+        return parse_addr(value.trim()).map(Some);
+    }
+
+    let node = last.split(';')
+        .filter_map(|pair| {
+            let mut kv = pair.splitn(2, '=');
+            Some((kv.next()?.trim(), kv.next()?.trim()))
+        })
+        .find(|(key, _)| key.eq_ignore_ascii_case("for"))
+        .map(|(_, node)| node.trim_matches('"'))
+        .ok_or(())?;
+
+    if node.eq_ignore_ascii_case("unknown") || node.starts_with('_') {
+        return Ok(None);
+    }
+
+    // An IPv6 address is bracketed, with or without a port: `[::1]:8000`.
+    match node.strip_prefix('[').and_then(|node| node.strip_suffix(']')) {
+        Some(ipv6) => parse_addr(ipv6).map(Some),
+        None => parse_addr(node).map(Some),
+    }
+}
+
 type Indices = (usize, usize);
 
 #[derive(Clone)]
@@ -0,0 +1,132 @@
+use std::fmt;
+use std::sync::Arc;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::request::{FromRequest, Outcome, Request};
+
+/// Request guard for the identifier of a request, used to correlate log lines
+/// and traces across services.
+///
+/// The identifier is taken from the request's `X-Request-Id` header, as set by
+/// a proxy or an upstream service, if it's present and consists of at most
+/// 128 ASCII alphanumeric characters and `-`, `_`, `.`, and `:`. Otherwise, a
+/// new [ULID](https://github.com/ulid/spec) is generated. Either way, the
+/// identifier remains the same for the lifetime of the request.
+///
+/// When the `request_id` configuration parameter is `true`, every request is
+/// assigned an identifier before request fairings run, the log lines emitted
+/// while dispatching the request are prefixed with it, and it is echoed in an
+/// `X-Request-Id` response header. Otherwise, an identifier is only assigned
+/// when it's first requested, via this guard or [`RequestId::of()`].
+///
+/// # Example
+///
+/// ```rust
+/// # #[macro_use] extern crate rocket;
+/// use rocket::request::RequestId;
+///
+/// #[get("/")]
+/// fn index(id: &RequestId) -> String {
+///     format!("request {}", id)
+/// }
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct RequestId(Arc<str>);
+
+impl RequestId {
+    /// The name of the request and response header carrying the identifier:
+    /// `X-Request-Id`.
+    pub const HEADER: &'static str = "X-Request-Id";
+
+    /// Returns the identifier of `request`, assigning one if it doesn't have
+    /// one yet.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::request::RequestId;
+    /// # use rocket::http::{Method, Header};
+    /// # use rocket::Request;
+    ///
+    /// # Request::example(Method::Get, "/uri", |request| {
+    /// request.add_header(Header::new("X-Request-Id", "abc-123"));
+    /// assert_eq!(RequestId::of(request).as_str(), "abc-123");
+    /// # });
+    /// ```
+    pub fn of<'r>(request: &'r Request<'_>) -> &'r RequestId {
+        request.local_cache(|| {
+            request.headers().get_one(Self::HEADER)
+                .filter(|id| Self::is_valid(id))
+                .map(|id| RequestId(id.into()))
+                .unwrap_or_else(RequestId::generate)
+        })
+    }
+
+    /// Generates a new identifier: a ULID, 26 characters long, whose first
+    /// 10 characters encode the current time.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::request::RequestId;
+    ///
+    /// let id = RequestId::generate();
+    /// assert_eq!(id.as_str().len(), 26);
+    /// assert_ne!(id, RequestId::generate());
+    /// ```
+    pub fn generate() -> RequestId {
+        const ALPHABET: &[u8; 32] = b"0123456789ABCDEFGHJKMNPQRSTVWXYZ";
+
+        let millis = SystemTime::now().duration_since(UNIX_EPOCH)
+            .map(|duration| duration.as_millis() as u64)
+            .unwrap_or(0);
+
+        let random = rand::random::<u128>() >> 48;
+        let value = (u128::from(millis & 0xFFFF_FFFF_FFFF) << 80) | random;
+        let id: String = (0..26)
+            .map(|i| ALPHABET[((value >> (125 - i * 5)) & 0x1F) as usize] as char)
+            .collect();
+
+        RequestId(id.into())
+    }
+
+    /// Returns the identifier as a string.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::request::RequestId;
+    ///
+    /// let id = RequestId::generate();
+    /// assert_eq!(id.as_str(), id.to_string());
+    /// ```
+    #[inline(always)]
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    /// Returns the identifier as a shared string.
+    pub(crate) fn shared(&self) -> Arc<str> {
+        self.0.clone()
+    }
+
+    fn is_valid(id: &str) -> bool {
+        !id.is_empty() && id.len() <= 128 && id.bytes()
+            .all(|b| b.is_ascii_alphanumeric() || matches!(b, b'-' | b'_' | b'.' | b':'))
+    }
+}
+
+impl fmt::Display for RequestId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+#[crate::async_trait]
+impl<'a, 'r> FromRequest<'a, 'r> for &'a RequestId {
+    type Error = std::convert::Infallible;
+
+    async fn from_request(request: &'a Request<'r>) -> Outcome<Self, Self::Error> {
+        Outcome::Success(RequestId::of(request))
+    }
+}
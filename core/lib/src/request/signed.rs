@@ -0,0 +1,183 @@
+use std::fmt;
+use std::ops::Deref;
+
+use hmac::{Hmac, Mac, NewMac};
+use sha2::Sha256;
+
+use crate::request::{Request, FromParam};
+use crate::outcome::Outcome;
+use crate::config::SecretKey;
+use crate::http::{RawStr, Status};
+use crate::http::uri::{Formatter, Path, UriDisplay};
+use crate::http::private::cookie::Key;
+
+/// A path parameter whose value is signed with the configured secret key.
+///
+/// A `Signed<T>` parameter consists of a value followed by a `.` and a keyed
+/// signature, or MAC, of the value: `/unsubscribe/42.SzuXP…`. Links containing
+/// signed parameters are generated with [`Signed::new()`] and [`uri!`]. When a
+/// request arrives, the signature is verified before the value is parsed as a
+/// `T`. If the signature is missing or doesn't match the value, the request
+/// fails with a `403 Forbidden`. If the value fails to parse, the request is
+/// forwarded. Since only the holder of the secret key can sign values, signed
+/// parameters make for capability-style links such as unsubscribe or download
+/// links.
+///
+/// A signature is valid for the value alone: a value signed for one route is
+/// accepted by any other route with a `Signed` parameter. Where this matters,
+/// sign a value that identifies its purpose, such as `unsubscribe-42`. Signed
+/// values are readable by anyone: use private cookies to store secrets.
+///
+/// This type is only available when the `secrets` feature is enabled.
+///
+/// [`uri!`]: ../macro.uri.html
+///
+/// # Example
+///
+/// ```rust
+/// # #[macro_use] extern crate rocket;
+/// use rocket::config::SecretKey;
+/// use rocket::request::Signed;
+///
+/// #[get("/unsubscribe/<user>")]
+/// fn unsubscribe(user: Signed<u64>) -> String {
+///     format!("User {} has been unsubscribed.", *user)
+/// }
+///
+/// #[get("/link/<user>")]
+/// fn link(user: u64, key: &SecretKey) -> String {
+///     uri!(unsubscribe: Signed::new(user, key)).to_string()
+/// }
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Signed<T> {
+    value: T,
+    encoded: String,
+    signature: String,
+}
+
+/// The error returned when a [`Signed`] parameter fails to parse.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SignedError<E> {
+    /// The signature is missing or doesn't match the value.
+    BadSignature,
+    /// The signature matches, but the value failed to parse.
+    Param(E),
+}
+
+/// Returns the URL-safe, unpadded base64 HMAC-SHA256 of `value` under the
+/// signing half of `key`.
+fn signature(value: &str, key: &Key) -> String {
+    let mut mac = Hmac::<Sha256>::new_varkey(key.signing()).expect("HMAC takes any key size");
+    mac.update(value.as_bytes());
+    let tag = mac.finalize().into_bytes();
+
+    // `binascii` requires space for the padding, which is then trimmed.
+    let mut buffer = [0u8; 44];
+    let encoded = binascii::b64encode(&tag, &mut buffer).expect("buffer fits a SHA-256 MAC");
+    encoded.iter()
+        .take_while(|&&b| b != b'=')
+        .map(|&b| match b { b'+' => '-', b'/' => '_', b => b as char })
+        .collect()
+}
+
+/// Compares `a` and `b` in time independent of the position of any difference.
+fn signatures_match(a: &str, b: &str) -> bool {
+    a.len() == b.len() && a.bytes().zip(b.bytes()).fold(0, |acc, (x, y)| acc | (x ^ y)) == 0
+}
+
+impl<T: UriDisplay<Path>> Signed<T> {
+    /// Signs `value` with `key`, typically the application's configured secret
+    /// key, which can be retrieved via the `&SecretKey` request guard.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::config::SecretKey;
+    /// use rocket::request::Signed;
+    ///
+    /// let key = SecretKey::generate().unwrap();
+    /// let signed = Signed::new(42, &key);
+    /// assert_eq!(*signed, 42);
+    /// ```
+    pub fn new(value: T, key: &SecretKey) -> Signed<T> {
+        let encoded = (&value as &dyn UriDisplay<Path>).to_string();
+        let signature = signature(&encoded, key);
+        Signed { value, encoded, signature }
+    }
+}
+
+impl<T> Signed<T> {
+    /// Consumes `self` and returns the signed value.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::config::SecretKey;
+    /// use rocket::request::Signed;
+    ///
+    /// let key = SecretKey::generate().unwrap();
+    /// assert_eq!(Signed::new("hello", &key).into_inner(), "hello");
+    /// ```
+    #[inline(always)]
+    pub fn into_inner(self) -> T {
+        self.value
+    }
+}
+
+impl<T> Deref for Signed<T> {
+    type Target = T;
+
+    #[inline(always)]
+    fn deref(&self) -> &T {
+        &self.value
+    }
+}
+
+/// Writes the value followed by a `.` and its signature.
+impl<T> UriDisplay<Path> for Signed<T> {
+    fn fmt(&self, f: &mut Formatter<'_, Path>) -> fmt::Result {
+        f.write_raw(&format!("{}.{}", self.encoded, self.signature))
+    }
+}
+
+crate::http::impl_from_uri_param_identity!([Path] (T) Signed<T>);
+
+/// Verifies the signature of the parameter with the configured secret key,
+/// then parses the value as a `T`.
+impl<'a, T: FromParam<'a>> FromParam<'a> for Signed<T> {
+    type Error = SignedError<T::Error>;
+
+    /// Signatures can only be verified with the request's secret key, so this
+    /// method always fails with `BadSignature`. Rocket calls
+    /// [`FromParam::from_request_param()`] instead.
+    fn from_param(_: &'a RawStr) -> Result<Self, Self::Error> {
+        Err(SignedError::BadSignature)
+    }
+
+    fn from_request_param(
+        req: &'a Request<'_>,
+        param: &'a RawStr
+    ) -> Outcome<Self, (Status, Self::Error), Self::Error> {
+        let param = param.as_str();
+        let verified = param.rfind('.').map(|i| (&param[..i], &param[(i + 1)..]))
+            .filter(|(value, sig)| {
+                signatures_match(&signature(value, &req.state.config.secret_key), sig)
+            });
+
+        let value = match verified {
+            Some((value, _)) => value,
+            None => return Outcome::Failure((Status::Forbidden, SignedError::BadSignature)),
+        };
+
+        match T::from_request_param(req, value.into()) {
+            Outcome::Success(inner) => Outcome::Success(Signed {
+                value: inner,
+                encoded: value.to_string(),
+                signature: param[(value.len() + 1)..].to_string(),
+            }),
+            Outcome::Forward(e) => Outcome::Forward(SignedError::Param(e)),
+            Outcome::Failure((status, e)) => Outcome::Failure((status, SignedError::Param(e))),
+        }
+    }
+}
@@ -1,6 +1,9 @@
 use std::ops::Deref;
 
+use state::Container;
+
 use crate::rocket::Rocket;
+use crate::phase::Phase;
 use crate::request::{self, FromRequest, Request};
 use crate::outcome::Outcome;
 use crate::http::Status;
@@ -75,6 +78,16 @@ use crate::http::Status;
 /// }
 /// ```
 ///
+/// # Scoped State
+///
+/// State can also be managed for the routes mounted at a given base via
+/// [`Rocket::mount_with_state()`]. When resolving a `State<T>` guard for a
+/// request, Rocket first looks for a `T` managed for the base of the matched
+/// route or, failing that, for the nearest enclosing base, before falling back
+/// to state managed via [`Rocket::manage()`].
+///
+/// [`Rocket::mount_with_state()`]: crate::Rocket::mount_with_state()
+///
 /// # Testing with `State`
 ///
 /// When unit testing your application, you may find it necessary to manually
@@ -154,7 +167,7 @@ impl<'r, T: Send + Sync + 'static> State<'r, T> {
     /// assert_eq!(state, None);
     /// ```
     #[inline(always)]
-    pub fn from(rocket: &'r Rocket) -> Option<Self> {
+    pub fn from<P: Phase>(rocket: &'r Rocket<P>) -> Option<Self> {
         rocket.state().map(State)
     }
 }
@@ -165,7 +178,7 @@ impl<'a, 'r, T: Send + Sync + 'static> FromRequest<'a, 'r> for State<'r, T> {
 
     #[inline(always)]
     async fn from_request(req: &'a Request<'r>) -> request::Outcome<State<'r, T>, ()> {
-        match req.state.managed.try_get::<T>() {
+        match req.managed_state::<T>() {
             Some(state) => Outcome::Success(State(state)),
             None => {
                 error_!("Attempted to retrieve unmanaged state!");
@@ -190,6 +203,50 @@ impl<T: Send + Sync + 'static> Clone for State<'_, T> {
     }
 }
 
+/// State managed for routes mounted at specific bases, ordered from the
+/// longest base to the shortest.
+#[derive(Default)]
+pub(crate) struct ScopedState(Vec<(String, Container)>);
+
+impl ScopedState {
+    /// Returns the container for `base`, a normalized path, creating it if
+    /// necessary.
+    pub fn scope(&mut self, base: &str) -> &Container {
+        let i = match self.0.iter().position(|(b, _)| b == base) {
+            Some(i) => i,
+            None => {
+                let i = self.0.iter()
+                    .position(|(b, _)| b.len() < base.len())
+                    .unwrap_or(self.0.len());
+
+                self.0.insert(i, (base.to_string(), Container::new()));
+                i
+            }
+        };
+
+        &self.0[i].1
+    }
+
+    /// Returns the `T` managed for the longest scope enclosing `base`.
+    pub fn get<T: Send + Sync + 'static>(&self, base: &str) -> Option<&T> {
+        self.0.iter()
+            .filter(|(scope, _)| encloses(scope, base))
+            .find_map(|(_, container)| container.try_get::<T>())
+    }
+
+    pub fn freeze(&mut self) {
+        for (_, container) in &mut self.0 {
+            container.freeze();
+        }
+    }
+}
+
+/// Returns `true` if the path `scope` is equal to or a parent of `base`.
+fn encloses(scope: &str, base: &str) -> bool {
+    scope == "/" || base == scope
+        || (base.starts_with(scope) && base[scope.len()..].starts_with('/'))
+}
+
 #[cfg(test)]
 mod tests {
     #[test]
@@ -201,4 +258,23 @@ mod tests {
         assert_eq!(state.0, 123);
         assert_eq!(state.clone().0, 123);
     }
+
+    #[test]
+    fn scoped_state_resolves_nearest_scope() {
+        use super::ScopedState;
+
+        let mut scopes = ScopedState::default();
+        scopes.scope("/blog").set(1usize);
+        scopes.scope("/").set("root");
+        scopes.scope("/blog/en").set(2usize);
+        scopes.scope("/blog").set("blog");
+
+        assert_eq!(scopes.get::<usize>("/blog/en"), Some(&2));
+        assert_eq!(scopes.get::<usize>("/blog/en/x"), Some(&2));
+        assert_eq!(scopes.get::<usize>("/blog/de"), Some(&1));
+        assert_eq!(scopes.get::<usize>("/blog"), Some(&1));
+        assert_eq!(scopes.get::<usize>("/blogs"), None);
+        assert_eq!(scopes.get::<&str>("/blog/en"), Some(&"blog"));
+        assert_eq!(scopes.get::<&str>("/blogs"), Some(&"root"));
+    }
 }
@@ -8,6 +8,7 @@ macro_rules! assert_headers {
     ($($key:expr => [$($value:expr),+]),+) => ({
         // Set up the parameters to the hyper request object.
         let h_method = hyper::Method::GET;
+        let h_version = hyper::Version::HTTP_11;
         let h_uri = "/test".parse().unwrap();
         let h_addr = SocketAddr::new(IpAddr::V4(Ipv4Addr::new(127, 0, 0, 1)), 8000);
         let mut h_headers = hyper::HeaderMap::new();
@@ -20,8 +21,8 @@ macro_rules! assert_headers {
         $(expected.entry($key).or_insert(vec![]).append(&mut vec![$($value),+]);)+
 
         // Dispatch the request and check that the headers are what we expect.
-        let r = Rocket::custom(Config::default());
-        let req = Request::from_hyp(&r, h_method, h_headers, &h_uri, h_addr).unwrap();
+        let r = Rocket::custom(Config::default())._orbit();
+        let req = Request::from_hyp(&r, h_method, h_version, h_headers, &h_uri, h_addr).unwrap();
         let actual_headers = req.headers();
         for (key, values) in expected.iter() {
             let actual: Vec<_> = actual_headers.get(key).collect();
@@ -46,3 +47,110 @@ fn test_multiple_headers_merge_into_one_from_hyp() {
     assert_headers!("friend" => ["alice"], "friend" => ["bob"], "friend" => ["carol"]);
     assert_headers!("friend" => ["alice"], "friend" => ["bob"], "enemy" => ["carol"]);
 }
+
+#[test]
+fn test_http2_request_from_hyp() {
+    use crate::http::Version;
+
+    let h_uri = "https://rocket.rs/test?q=1".parse().unwrap();
+    let h_addr = SocketAddr::new(IpAddr::V4(Ipv4Addr::new(127, 0, 0, 1)), 8000);
+    let r = Rocket::custom(Config::default())._orbit();
+
+    let (method, headers) = (hyper::Method::GET, hyper::HeaderMap::new());
+    let req = Request::from_hyp(&r, method, hyper::Version::HTTP_2, headers, &h_uri, h_addr);
+    let req = req.unwrap();
+    assert_eq!(req.version(), Version::Http2);
+    assert_eq!(req.uri().to_string(), "/test?q=1");
+    assert_eq!(req.headers().get_one("Host"), Some("rocket.rs"));
+
+    let (method, headers) = (hyper::Method::GET, hyper::HeaderMap::new());
+    let req = Request::from_hyp(&r, method, hyper::Version::HTTP_11, headers, &h_uri, h_addr);
+    assert!(req.is_err());
+}
+
+#[test]
+fn test_real_ip_from_trusted_headers() {
+    use crate::http::{Header, Method, uri::Origin};
+
+    let headers = vec!["Forwarded".into(), "X-Forwarded-For".into(), "X-Real-IP".into()];
+    let config = Config { ip_headers: headers, ..Config::debug_default() };
+    let r = Rocket::custom(config)._orbit();
+
+    let real_ip = |headers: &[(&'static str, &'static str)]| {
+        let mut req = Request::new(&r, Method::Get, Origin::dummy());
+        req.set_remote("127.0.0.1:8000".parse().unwrap());
+        for &(name, value) in headers {
+            req.add_header(Header::new(name, value));
+        }
+
+        (req.real_ip(), req.client_ip())
+    };
+
+    let ip = |s: &str| Some(s.parse::<IpAddr>().unwrap());
+    assert_eq!(real_ip(&[]), (None, ip("127.0.0.1")));
+    assert_eq!(real_ip(&[("X-Real-IP", "8.8.8.8")]), (ip("8.8.8.8"), ip("8.8.8.8")));
+    assert_eq!(real_ip(&[("X-Real-IP", "garbage")]), (None, ip("127.0.0.1")));
+
+    let xff = ("X-Forwarded-For", "1.1.1.1, 2.2.2.2,3.3.3.3");
+    assert_eq!(real_ip(&[xff]).0, ip("3.3.3.3"));
+    assert_eq!(real_ip(&[xff, ("X-Forwarded-For", "4.4.4.4")]).0, ip("4.4.4.4"));
+    assert_eq!(real_ip(&[("X-Real-IP", "8.8.8.8"), xff]).0, ip("3.3.3.3"));
+
+    let forwarded = ("Forwarded", r#"for=1.1.1.1, for="[2001:db8::17]:4711";proto=https"#);
+    assert_eq!(real_ip(&[forwarded, xff]).0, ip("2001:db8::17"));
+    assert_eq!(real_ip(&[("Forwarded", "proto=http;FOR=\"[::1]\"")]).0, ip("::1"));
+    assert_eq!(real_ip(&[("Forwarded", "for=\"5.5.5.5:80\"")]).0, ip("5.5.5.5"));
+    assert_eq!(real_ip(&[("Forwarded", "for=_hidden"), xff]).0, ip("3.3.3.3"));
+    assert_eq!(real_ip(&[("Forwarded", "for=unknown")]).0, None);
+    assert_eq!(real_ip(&[("Forwarded", "by=1.1.1.1")]).0, None);
+
+    let config = Config { ip_headers: vec![], ..Config::debug_default() };
+    let r = Rocket::custom(config)._orbit();
+    let mut req = Request::new(&r, Method::Get, Origin::dummy());
+    req.add_header(Header::new("X-Real-IP", "8.8.8.8"));
+    assert_eq!(req.real_ip(), None);
+}
+
+#[test]
+fn test_tls_guards() {
+    use std::sync::Arc;
+
+    use crate::outcome::Outcome;
+    use crate::http::{Method, uri::Origin, private::TlsInfo};
+    use crate::request::Certificate;
+
+    let r = Rocket::custom(Config::debug_default())._orbit();
+    let guards = |tls_info: Option<TlsInfo>| {
+        let mut req = Request::new(&r, Method::Get, Origin::dummy());
+        req.set_tls_info(tls_info.map(Arc::new));
+        crate::async_test(async {
+            let tls_info = req.guard::<&TlsInfo>().await.map(|tls| tls.clone());
+            let cert = req.guard::<Certificate<'_>>().await.map(|cert| cert.to_vec());
+            (tls_info, cert)
+        })
+    };
+
+    // Without TLS, both guards forward.
+    let (tls_info, cert) = guards(None);
+    assert!(tls_info.is_forward());
+    assert!(cert.is_forward());
+
+    // With TLS but no client certificate, only `&TlsInfo` succeeds.
+    let info = TlsInfo::new(vec![], Some("rocket.rs".into()), Some(b"h2".to_vec()));
+    let (tls_info, cert) = guards(Some(info.clone()));
+    match tls_info {
+        Outcome::Success(tls) => {
+            assert_eq!(tls, info);
+            assert_eq!(tls.sni_hostname(), Some("rocket.rs"));
+            assert_eq!(tls.alpn_protocol(), Some(&b"h2"[..]));
+        }
+        _ => panic!("expected `&TlsInfo` to succeed"),
+    }
+
+    assert!(cert.is_forward());
+
+    // With a client certificate chain, `Certificate` is the first certificate.
+    let info = TlsInfo::new(vec![vec![1, 2, 3], vec![4, 5]], None, None);
+    let (_, cert) = guards(Some(info));
+    assert_eq!(cert.succeeded(), Some(vec![1, 2, 3]));
+}
@@ -0,0 +1,198 @@
+use std::fmt;
+use std::sync::{Arc, Mutex};
+
+use yansi::Paint;
+
+use crate::Route;
+use crate::http::{Method, Status};
+
+/// A record of how a request was routed, for diagnostics.
+///
+/// When tracing is enabled via the `trace` or `diagnostics` configuration
+/// parameters, which it is by default in the debug profile, Rocket records
+/// every route a request is matched against, every request guard or parameter
+/// that fails or forwards, every handler that panics, and every internal
+/// re-dispatch of the request. The trace so far is returned by
+/// [`Request::trace()`](crate::Request::trace()) and is included in the pages
+/// generated by Rocket's default catchers, answering questions such as "why
+/// did this request `404`?".
+///
+/// # Example
+///
+/// ```rust
+/// # #[macro_use] extern crate rocket;
+/// use rocket::Request;
+///
+/// #[catch(404)]
+/// fn not_found(req: &Request) -> String {
+///     format!("Nothing here. Tried:\n{}", req.trace())
+/// }
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Trace {
+    events: Vec<TraceEvent>,
+}
+
+/// An event recorded in a [`Trace`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TraceEvent {
+    /// The request was matched against the route described by the string.
+    Matched(String),
+    /// The matched route forwarded the request for the given reason.
+    Forwarded(String),
+    /// The matched route failed with the given status for the given reason.
+    Failed(Status, String),
+    /// The request was re-dispatched with the given method.
+    Redispatched(Method),
+    /// No more routes matched the request.
+    Unmatched,
+    /// The matched route panicked with the given message.
+    Panicked(String),
+}
+
+/// How a route compares against a request, as returned by
+/// [`Request::explain_routing()`](crate::Request::explain_routing()).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RouteCheck {
+    /// The route, described like its `Display` implementation, without colors.
+    pub route: String,
+    /// The rank of the route.
+    pub rank: isize,
+    /// Why the route doesn't match the request, or `None` if it does.
+    pub rejection: Option<Rejection>,
+}
+
+/// The reason a route doesn't match a request.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum Rejection {
+    /// The route's method differs from the request's.
+    Method,
+    /// A static segment of the route's path differs from the request's, or the
+    /// paths have a different number of segments.
+    Path,
+    /// A static segment of the route's query is missing from the request's.
+    Query,
+    /// The route's format doesn't match the request's `Content-Type` or
+    /// `Accept` header.
+    Format,
+    /// The route's matcher rejected the request.
+    Matcher,
+}
+
+impl Trace {
+    /// Returns an iterator over the events in `self`, oldest first.
+    pub fn iter(&self) -> impl Iterator<Item = &TraceEvent> {
+        self.events.iter()
+    }
+
+    /// Returns the number of events in `self`.
+    pub fn len(&self) -> usize {
+        self.events.len()
+    }
+
+    /// Returns `true` if `self` contains no events.
+    pub fn is_empty(&self) -> bool {
+        self.events.is_empty()
+    }
+}
+
+impl TraceEvent {
+    /// Returns a `Matched` event for `route`.
+    pub(crate) fn matched(route: &Route) -> Self {
+        TraceEvent::Matched(describe(route))
+    }
+}
+
+/// Describes `route` like its `Display` implementation does, without colors.
+pub(crate) fn describe(route: &Route) -> String {
+    let mut description = format!("{} ", route.method);
+    if route.base.path() != "/" {
+        description.push_str(route.base.path());
+    }
+
+    description.push_str(&route.path.to_string());
+    if route.rank > 1 {
+        description.push_str(&format!(" [{}]", route.rank));
+    }
+
+    if let Some(ref format) = route.format {
+        description.push_str(&format!(" {}", format));
+    }
+
+    if let Some(name) = route.name {
+        description.push_str(&format!(" ({})", name));
+    }
+
+    description
+}
+
+impl fmt::Display for Trace {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, event) in self.events.iter().enumerate() {
+            writeln!(f, "{}. {}", i + 1, event)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl fmt::Display for TraceEvent {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TraceEvent::Matched(route) => write!(f, "matched {}", route),
+            TraceEvent::Forwarded(reason) => write!(f, "forwarded: {}", reason),
+            TraceEvent::Failed(status, reason) => write!(f, "failed with {}: {}", status, reason),
+            TraceEvent::Redispatched(method) => write!(f, "re-dispatched as {}", method),
+            TraceEvent::Unmatched => write!(f, "no further matching routes"),
+            TraceEvent::Panicked(message) => write!(f, "panicked: {}", message),
+        }
+    }
+}
+
+impl fmt::Display for RouteCheck {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.rejection {
+            Some(rejection) => write!(f, "{}: {}", self.route, rejection),
+            None => write!(f, "{}: matches", self.route),
+        }
+    }
+}
+
+impl fmt::Display for Rejection {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Rejection::Method => write!(f, "method mismatch"),
+            Rejection::Path => write!(f, "path mismatch"),
+            Rejection::Query => write!(f, "query mismatch"),
+            Rejection::Format => write!(f, "format mismatch"),
+            Rejection::Matcher => write!(f, "matcher rejected"),
+        }
+    }
+}
+
+/// The trace of a request, as stored in the request. `None` when disabled.
+#[derive(Clone)]
+pub(crate) struct TraceLog(Option<Arc<Mutex<Vec<TraceEvent>>>>);
+
+impl TraceLog {
+    pub(crate) fn new(enabled: bool) -> Self {
+        TraceLog(if enabled { Some(Arc::default()) } else { None })
+    }
+
+    pub(crate) fn record<F: FnOnce() -> TraceEvent>(&self, event: F) {
+        if let Some(ref events) = self.0 {
+            let event = event();
+            debug_!("{} {}", Paint::default("Trace:").bold(), event);
+            events.lock().expect("trace lock").push(event);
+        }
+    }
+
+    pub(crate) fn get(&self) -> Trace {
+        let events = self.0.as_ref()
+            .map(|events| events.lock().expect("trace lock").clone())
+            .unwrap_or_default();
+
+        Trace { events }
+    }
+}
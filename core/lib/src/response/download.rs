@@ -0,0 +1,180 @@
+use std::borrow::Cow;
+
+use crate::request::Request;
+use crate::response::{self, Responder, Response};
+use crate::http::Header;
+
+/// Sets the `Content-Disposition` header of a response, marking it as a file
+/// download.
+///
+/// A `Download` wraps an arbitrary responder `R`, setting the
+/// `Content-Disposition` header of its response. An _attachment_, created via
+/// [`Download::attachment()`], prompts browsers to save the response as a file
+/// with the given name. An _inline_ download, created via
+/// [`Download::inline()`], is displayed by browsers if possible, with the name,
+/// if any, used if the user chooses to save it.
+///
+/// File names are sent as a quoted ASCII `filename` parameter. If the name
+/// contains characters that cannot be represented in it, such as non-ASCII
+/// characters, the name is additionally sent as an RFC 5987-encoded
+/// `filename*` parameter, which is preferred by modern clients, while the
+/// `filename` parameter contains an ASCII approximation.
+///
+/// # Example
+///
+/// ```rust
+/// # #[macro_use] extern crate rocket;
+/// use rocket::response::{Download, NamedFile};
+///
+/// #[get("/report")]
+/// async fn report() -> Option<Download<NamedFile>> {
+///     let file = NamedFile::open("reports/latest.pdf").await.ok()?;
+///     Some(Download::attachment(file, "Quarterly Report.pdf"))
+/// }
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct Download<R> {
+    responder: R,
+    disposition: &'static str,
+    filename: Option<Cow<'static, str>>,
+}
+
+impl<R> Download<R> {
+    /// Wraps `responder` as an attachment to be saved as `filename`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::response::Download;
+    ///
+    /// let download = Download::attachment("a,b,c", "résumé.csv");
+    /// assert_eq!(download.header_value(),
+    ///     "attachment; filename=\"r_sum_.csv\"; filename*=UTF-8''r%C3%A9sum%C3%A9.csv");
+    /// ```
+    pub fn attachment<N: Into<Cow<'static, str>>>(responder: R, filename: N) -> Self {
+        Download { responder, disposition: "attachment", filename: Some(filename.into()) }
+    }
+
+    /// Wraps `responder` as inline content. A name can be set via
+    /// [`Download::filename()`].
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::response::Download;
+    ///
+    /// let download = Download::inline("%PDF-1.4");
+    /// assert_eq!(download.header_value(), "inline");
+    /// ```
+    pub fn inline(responder: R) -> Self {
+        Download { responder, disposition: "inline", filename: None }
+    }
+
+    /// Sets the name of the downloaded file to `filename`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::response::Download;
+    ///
+    /// let name = format!("report-{}.pdf", 2020);
+    /// let download = Download::inline("%PDF-1.4").filename(name);
+    /// assert_eq!(download.header_value(), r#"inline; filename="report-2020.pdf""#);
+    /// ```
+    pub fn filename<N: Into<Cow<'static, str>>>(mut self, filename: N) -> Self {
+        self.filename = Some(filename.into());
+        self
+    }
+
+    /// Returns the value of the `Content-Disposition` header `self` sets.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::response::Download;
+    ///
+    /// let download = Download::attachment((), "my \"file\".txt");
+    /// assert_eq!(download.header_value(), r#"attachment; filename="my \"file\".txt""#);
+    /// ```
+    pub fn header_value(&self) -> String {
+        let filename = match self.filename {
+            Some(ref filename) => filename,
+            None => return self.disposition.into(),
+        };
+
+        let mut value = format!("{}; filename=\"{}\"", self.disposition, ascii_fallback(filename));
+        if !filename.chars().all(|c| c.is_ascii() && !c.is_ascii_control()) {
+            value.push_str("; filename*=UTF-8''");
+            value.push_str(&rfc5987_encode(filename));
+        }
+
+        value
+    }
+}
+
+/// Returns `name` as the contents of a quoted string with non-ASCII and
+/// control characters replaced by `_`.
+fn ascii_fallback(name: &str) -> String {
+    let mut fallback = String::with_capacity(name.len());
+    for c in name.chars() {
+        match c {
+            '"' | '\\' => { fallback.push('\\'); fallback.push(c); }
+            c if c.is_ascii() && !c.is_ascii_control() => fallback.push(c),
+            _ => fallback.push('_'),
+        }
+    }
+
+    fallback
+}
+
+/// Percent-encodes `name` as an RFC 5987 `value-chars`.
+fn rfc5987_encode(name: &str) -> String {
+    let mut encoded = String::with_capacity(name.len());
+    for &byte in name.as_bytes() {
+        match byte {
+            b'a'..=b'z' | b'A'..=b'Z' | b'0'..=b'9' | b'!' | b'#' | b'$' | b'&' | b'+'
+                | b'-' | b'.' | b'^' | b'_' | b'`' | b'|' | b'~' => encoded.push(byte as char),
+            _ => encoded.push_str(&format!("%{:02X}", byte)),
+        }
+    }
+
+    encoded
+}
+
+/// Sets the `Content-Disposition` header and delegates the remainder of the
+/// response to the wrapped responder.
+impl<'r, 'o: 'r, R: Responder<'r, 'o>> Responder<'r, 'o> for Download<R> {
+    fn respond_to(self, req: &'r Request<'_>) -> response::Result<'o> {
+        let value = self.header_value();
+        Response::build_from(self.responder.respond_to(req)?)
+            .header(Header::new("Content-Disposition", value))
+            .ok()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Download;
+
+    #[test]
+    fn test_header_value() {
+        let value = Download::attachment((), "report.pdf").header_value();
+        assert_eq!(value, "attachment; filename=\"report.pdf\"");
+
+        let value = Download::inline(()).header_value();
+        assert_eq!(value, "inline");
+
+        let value = Download::inline(()).filename("report.pdf").header_value();
+        assert_eq!(value, "inline; filename=\"report.pdf\"");
+
+        let value = Download::attachment((), "a\\b\".txt").header_value();
+        assert_eq!(value, "attachment; filename=\"a\\\\b\\\".txt\"");
+
+        let value = Download::attachment((), "€ rates.txt").header_value();
+        assert_eq!(value, "attachment; filename=\"_ rates.txt\"; \
+            filename*=UTF-8''%E2%82%AC%20rates.txt");
+
+        let value = Download::attachment((), "tab\there").header_value();
+        assert_eq!(value, "attachment; filename=\"tab_here\"; filename*=UTF-8''tab%09here");
+    }
+}
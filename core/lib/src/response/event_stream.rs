@@ -0,0 +1,345 @@
+use std::io::{self, Cursor, Read};
+use std::future::Future;
+use std::pin::Pin;
+use std::borrow::Cow;
+use std::time::Duration;
+use std::task::{Context, Poll};
+
+use futures::stream::Stream;
+use tokio::io::AsyncRead;
+use tokio::time::{Delay, Instant};
+
+use crate::request::Request;
+use crate::response::{self, Response, Responder};
+use crate::http::{ContentType, Header};
+
+/// A server-sent event, sent by an [`EventStream`].
+///
+/// An event is created with [`Event::data()`] or [`Event::comment()`] and
+/// optionally given a type, an ID, and a reconnection time. Line breaks in the
+/// data or comment are preserved: each line is sent as its own field, as the
+/// [HTML standard] requires. Line breaks in the type and ID are removed.
+///
+/// `String` and `&str` values convert into data-only events.
+///
+/// [HTML standard]: https://html.spec.whatwg.org/multipage/server-sent-events.html
+///
+/// # Example
+///
+/// ```rust
+/// use std::time::Duration;
+/// use rocket::response::Event;
+///
+/// let event = Event::data("hello\nworld")
+///     .event("greeting")
+///     .id("1")
+///     .retry(Duration::from_secs(5));
+///
+/// let encoded = "event: greeting\nid: 1\nretry: 5000\ndata: hello\ndata: world\n\n";
+/// assert_eq!(event.encode(), encoded.as_bytes());
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Event {
+    comment: Option<String>,
+    event: Option<String>,
+    id: Option<String>,
+    retry: Option<Duration>,
+    data: Option<String>,
+}
+
+impl Event {
+    /// Creates a new event with the data `data`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::response::Event;
+    ///
+    /// assert_eq!(Event::data("hi").encode(), b"data: hi\n\n");
+    /// ```
+    pub fn data<T: Into<String>>(data: T) -> Event {
+        Event { data: Some(data.into()), ..Event::empty() }
+    }
+
+    /// Creates a new comment, which clients ignore. Comments are useful to keep
+    /// idle connections from being closed by proxies.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::response::Event;
+    ///
+    /// assert_eq!(Event::comment("ping").encode(), b": ping\n\n");
+    /// ```
+    pub fn comment<T: Into<String>>(comment: T) -> Event {
+        Event { comment: Some(comment.into()), ..Event::empty() }
+    }
+
+    fn empty() -> Event {
+        Event { comment: None, event: None, id: None, retry: None, data: None }
+    }
+
+    /// Sets the type of `self` to `event`. Clients dispatch the event to the
+    /// listeners for this type instead of to the `message` listeners.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::response::Event;
+    ///
+    /// let event = Event::data("hi").event("chat");
+    /// assert_eq!(event.encode(), b"event: chat\ndata: hi\n\n");
+    /// ```
+    pub fn event<T: Into<String>>(mut self, event: T) -> Event {
+        self.event = Some(event.into());
+        self
+    }
+
+    /// Sets the ID of `self` to `id`. A reconnecting client sends the last ID
+    /// it received in a `Last-Event-ID` header.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::response::Event;
+    ///
+    /// let event = Event::data("hi").id("42");
+    /// assert_eq!(event.encode(), b"id: 42\ndata: hi\n\n");
+    /// ```
+    pub fn id<T: Into<String>>(mut self, id: T) -> Event {
+        self.id = Some(id.into());
+        self
+    }
+
+    /// Sets the time a client waits before reconnecting after the connection
+    /// is lost to `retry`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use std::time::Duration;
+    /// use rocket::response::Event;
+    ///
+    /// let event = Event::data("hi").retry(Duration::from_millis(1500));
+    /// assert_eq!(event.encode(), b"retry: 1500\ndata: hi\n\n");
+    /// ```
+    pub fn retry(mut self, retry: Duration) -> Event {
+        self.retry = Some(retry);
+        self
+    }
+
+    /// Returns `self` encoded as it is sent to a client.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::response::Event;
+    ///
+    /// assert_eq!(Event::data("").encode(), b"data: \n\n");
+    /// ```
+    pub fn encode(&self) -> Vec<u8> {
+        fn field(out: &mut String, name: &str, value: &str) {
+            out.push_str(name);
+            out.push_str(": ");
+            out.push_str(value);
+            out.push('\n');
+        }
+
+        fn lines(value: &str) -> impl Iterator<Item = &str> {
+            value.split('\n').map(|line| line.strip_suffix('\r').unwrap_or(line))
+        }
+
+        fn single_line(value: &str) -> Cow<'_, str> {
+            match value.contains(|c: char| c == '\n' || c == '\r') {
+                true => value.replace(|c: char| c == '\n' || c == '\r', "").into(),
+                false => value.into(),
+            }
+        }
+
+        let mut out = String::new();
+        if let Some(ref comment) = self.comment {
+            lines(comment).for_each(|line| field(&mut out, "", line));
+        }
+
+        if let Some(ref event) = self.event {
+            field(&mut out, "event", &single_line(event));
+        }
+
+        if let Some(ref id) = self.id {
+            field(&mut out, "id", &single_line(id));
+        }
+
+        if let Some(retry) = self.retry {
+            field(&mut out, "retry", &retry.as_millis().to_string());
+        }
+
+        if let Some(ref data) = self.data {
+            lines(data).for_each(|line| field(&mut out, "data", line));
+        }
+
+        out.push('\n');
+        out.into_bytes()
+    }
+}
+
+impl From<String> for Event {
+    fn from(data: String) -> Event {
+        Event::data(data)
+    }
+}
+
+impl From<&str> for Event {
+    fn from(data: &str) -> Event {
+        Event::data(data)
+    }
+}
+
+/// Streams [server-sent events] to a client.
+///
+/// An `EventStream` wraps a [`Stream`] of items that convert into an
+/// [`Event`]. Each event is sent to the client as soon as the stream yields
+/// it, and the response ends when the stream does. The response has a
+/// Content-Type of `text/event-stream` and a `Cache-Control` of `no-cache`.
+///
+/// A stream that never ends holds up graceful shutdown for as long as the
+/// client stays connected. A [`Channel`](crate::broadcast::Channel)'s
+/// subscriptions, for instance, end once the channel is closed.
+///
+/// Proxies and the server's own write timeout may close a connection on which
+/// nothing is sent for a while. To keep quiet streams open, enable a heartbeat
+/// via [`EventStream::heartbeat()`].
+///
+/// [server-sent events]: https://html.spec.whatwg.org/multipage/server-sent-events.html
+///
+/// # Example
+///
+/// ```rust
+/// # #[macro_use] extern crate rocket;
+/// use rocket::futures::stream::{self, Stream};
+/// use rocket::response::{Event, EventStream};
+///
+/// #[get("/countdown")]
+/// fn countdown() -> EventStream<impl Stream<Item = Event>> {
+///     let events = (1..=3).rev().map(|n| Event::data(n.to_string()).id(n.to_string()));
+///     EventStream::from(stream::iter(events))
+/// }
+/// ```
+#[derive(Debug)]
+pub struct EventStream<S> {
+    stream: S,
+    heartbeat: Option<Duration>,
+}
+
+impl<S> EventStream<S> {
+    /// Sends an empty comment, which clients ignore, whenever `period` elapses
+    /// without an event being sent. Disabled by default.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # #[macro_use] extern crate rocket;
+    /// use std::time::Duration;
+    ///
+    /// use rocket::State;
+    /// use rocket::broadcast::{Channel, Subscription};
+    /// use rocket::response::EventStream;
+    ///
+    /// #[get("/messages")]
+    /// fn messages(channel: State<'_, Channel<String>>) -> EventStream<Subscription<String>> {
+    ///     channel.subscribe().heartbeat(Duration::from_secs(15))
+    /// }
+    /// ```
+    pub fn heartbeat(mut self, period: Duration) -> EventStream<S> {
+        self.heartbeat = Some(period);
+        self
+    }
+
+    /// Returns the wrapped stream.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::futures::stream;
+    /// use rocket::response::EventStream;
+    ///
+    /// let events = EventStream::from(stream::iter(vec!["a", "b"]));
+    /// let inner = events.into_inner();
+    /// ```
+    pub fn into_inner(self) -> S {
+        self.stream
+    }
+}
+
+impl<S> From<S> for EventStream<S> {
+    fn from(stream: S) -> EventStream<S> {
+        EventStream { stream, heartbeat: None }
+    }
+}
+
+/// Streams the events to the client, setting the Content-Type to
+/// `text/event-stream`. Always returns `Ok`.
+impl<'r, 'o: 'r, S> Responder<'r, 'o> for EventStream<S>
+    where S: Stream + Send + 'o, S::Item: Into<Event>
+{
+    fn respond_to(self, _: &'r Request<'_>) -> response::Result<'o> {
+        let reader = EventReader {
+            stream: Box::pin(self.stream),
+            buffer: Cursor::new(vec![]),
+            heartbeat: self.heartbeat,
+            delay: None,
+        };
+
+        Response::build()
+            .header(ContentType::EventStream)
+            .header(Header::new("Cache-Control", "no-cache"))
+            .streamed_body(reader)
+            .ok()
+    }
+}
+
+/// Reads the encoding of each event in `stream` as it becomes available,
+/// interspersed with a comment whenever `heartbeat` elapses without one.
+struct EventReader<S> {
+    stream: Pin<Box<S>>,
+    buffer: Cursor<Vec<u8>>,
+    heartbeat: Option<Duration>,
+    delay: Option<Delay>,
+}
+
+impl<S: Stream> AsyncRead for EventReader<S> where S::Item: Into<Event> {
+    fn poll_read(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut [u8]
+    ) -> Poll<io::Result<usize>> {
+        let this = self.get_mut();
+        loop {
+            let n = this.buffer.read(buf)?;
+            if n > 0 || buf.is_empty() {
+                return Poll::Ready(Ok(n));
+            }
+
+            let period = match this.stream.as_mut().poll_next(cx) {
+                Poll::Ready(Some(item)) => {
+                    this.buffer = Cursor::new(item.into().encode());
+                    if let (Some(period), Some(delay)) = (this.heartbeat, &mut this.delay) {
+                        delay.reset(Instant::now() + period);
+                    }
+
+                    continue;
+                }
+                Poll::Ready(None) => return Poll::Ready(Ok(0)),
+                Poll::Pending => match this.heartbeat {
+                    Some(period) => period,
+                    None => return Poll::Pending,
+                }
+            };
+
+            // The timer is created here, while polled, where a runtime exists.
+            let delay = this.delay.get_or_insert_with(|| tokio::time::delay_for(period));
+            futures::ready!(Pin::new(&mut *delay).poll(cx));
+            delay.reset(Instant::now() + period);
+            this.buffer = Cursor::new(Event::comment("").encode());
+        }
+    }
+}
@@ -36,6 +36,13 @@ const FLASH_COOKIE_DELIM: char = ':';
 /// Messages can be retrieved on the request side via the [`FlashMessage`] type
 /// and the [name](#method.name) and [msg](#method.msg) methods.
 ///
+/// # Security
+///
+/// When the `secrets` feature is enabled, the message cookie is a [private
+/// cookie](crate::http::CookieJar::add_private()), encrypted and authenticated
+/// with the configured [`SecretKey`](crate::config::SecretKey). Otherwise, the
+/// message is sent in plaintext and can be read and forged by the client.
+///
 /// # Response
 ///
 /// The `Responder` implementation for `Flash` sets the message cookie and then
@@ -194,7 +201,12 @@ impl<R> Flash<R> {
 impl<'r, 'o: 'r, R: Responder<'r, 'o>> Responder<'r, 'o> for Flash<R> {
     fn respond_to(self, req: &'r Request<'_>) -> response::Result<'o> {
         trace_!("Flash: setting message: {}:{}", self.name, self.message);
+        #[cfg(feature = "secrets")]
+        req.cookies().add_private(self.cookie());
+
+        #[cfg(not(feature = "secrets"))]
         req.cookies().add(self.cookie());
+
         self.inner.respond_to(req)
     }
 }
@@ -215,6 +227,10 @@ impl<'a, 'r> Flash<&'a Request<'r>> {
     fn clear_cookie_if_needed(&self) {
         // Remove the cookie if it hasn't already been removed.
         if !self.consumed.swap(true, Ordering::Relaxed) {
+            #[cfg(feature = "secrets")]
+            self.inner.cookies().remove_private(Cookie::named(FLASH_COOKIE_NAME));
+
+            #[cfg(not(feature = "secrets"))]
             self.inner.cookies().remove(Cookie::named(FLASH_COOKIE_NAME));
         }
     }
@@ -243,7 +259,14 @@ impl<'a, 'r> FromRequest<'a, 'r> for Flash<&'a Request<'r>> {
 
     async fn from_request(req: &'a Request<'r>) -> request::Outcome<Self, Self::Error> {
         trace_!("Flash: attempting to retrieve message.");
-        req.cookies().get(FLASH_COOKIE_NAME).ok_or(()).and_then(|cookie| {
+
+        #[cfg(feature = "secrets")]
+        let cookie = req.cookies().get_private(FLASH_COOKIE_NAME);
+
+        #[cfg(not(feature = "secrets"))]
+        let cookie = req.cookies().get(FLASH_COOKIE_NAME).cloned();
+
+        cookie.ok_or(()).and_then(|cookie| {
             trace_!("Flash: retrieving message: {:?}", cookie);
 
             // Parse the flash message.
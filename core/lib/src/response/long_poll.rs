@@ -0,0 +1,278 @@
+use std::sync::Arc;
+use std::time::Duration;
+
+use parking_lot::RwLock;
+use tokio::sync::watch;
+
+use crate::request::Request;
+use crate::response::{self, Responder, Response};
+use crate::http::{Header, Status};
+
+/// A value that clients wait on for changes via long polling.
+///
+/// A `LongPoll` holds a value and its _version_, which starts at `1` and is
+/// incremented whenever the value is changed via [`LongPoll::set()`] or
+/// [`LongPoll::update()`]. A handler calls [`LongPoll::wait()`] with the
+/// version the client last saw: if the current version differs, the current
+/// value is returned at once; otherwise, the handler waits until the value
+/// changes or a maximum wait elapses, whichever comes first.
+///
+/// The [`Update`] returned by `wait()` is a responder. A change is sent as
+/// the value's response, and a timeout as a `204 No Content`. Both carry the
+/// current version in a [`LongPoll::HEADER`] header, which the client sends
+/// back in its next request. A client that has seen no version yet sends `0`
+/// to receive the current value immediately.
+///
+/// A `LongPoll` is usually managed as state so that handlers can wait on it
+/// and other handlers or background tasks can change it. Clones share the
+/// value, so a clone can be moved into a task.
+///
+/// Requests waiting on a `LongPoll` hold up graceful shutdown until they
+/// complete, so keep the maximum wait reasonably short: a few tens of seconds
+/// is typical and stays below the idle timeouts of most proxies.
+///
+/// # Example
+///
+/// ```rust
+/// # #[macro_use] extern crate rocket;
+/// use std::time::Duration;
+///
+/// use rocket::State;
+/// use rocket::response::{LongPoll, Update};
+///
+/// type Inbox = LongPoll<Vec<String>>;
+///
+/// #[get("/inbox?<since>")]
+/// async fn inbox(inbox: State<'_, Inbox>, since: u64) -> Update<String> {
+///     let update = inbox.wait(since, Duration::from_secs(30)).await;
+///     update.map(|messages| messages.join("\n"))
+/// }
+///
+/// #[post("/inbox", data = "<message>")]
+/// fn send(inbox: State<'_, Inbox>, message: String) {
+///     inbox.update(|messages| messages.push(message));
+/// }
+///
+/// #[launch]
+/// fn rocket() -> rocket::Rocket {
+///     rocket::ignite()
+///         .manage(Inbox::new(vec![]))
+///         .mount("/", routes![inbox, send])
+/// }
+/// ```
+#[derive(Debug)]
+pub struct LongPoll<T> {
+    value: Arc<RwLock<(u64, T)>>,
+    sender: Arc<watch::Sender<u64>>,
+    receiver: watch::Receiver<u64>,
+}
+
+/// The outcome of [`LongPoll::wait()`].
+///
+/// As a responder, a [`Changed`](Update::Changed) update responds with its
+/// value, and a [`Timeout`](Update::Timeout) with a `204 No Content`. Both
+/// set the [`LongPoll::HEADER`] header to the version.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Update<T> {
+    /// The value changed: the new version and value.
+    Changed(u64, T),
+    /// The wait elapsed without a change: the current version.
+    Timeout(u64),
+}
+
+impl<T> LongPoll<T> {
+    /// The name of the response header containing the version of the value:
+    /// `X-Poll-Version`.
+    pub const HEADER: &'static str = "X-Poll-Version";
+
+    /// Creates a new `LongPoll` with the initial value `value` at version `1`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::response::LongPoll;
+    ///
+    /// let poll = LongPoll::new("initial");
+    /// assert_eq!(poll.version(), 1);
+    /// ```
+    pub fn new(value: T) -> LongPoll<T> {
+        let (sender, receiver) = watch::channel(1);
+        LongPoll { value: Arc::new(RwLock::new((1, value))), sender: Arc::new(sender), receiver }
+    }
+
+    /// Returns the current version of the value.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::response::LongPoll;
+    ///
+    /// let poll = LongPoll::new(0);
+    /// poll.set(10);
+    /// assert_eq!(poll.version(), 2);
+    /// ```
+    pub fn version(&self) -> u64 {
+        self.value.read().0
+    }
+
+    /// Replaces the value with `value`, waking all waiting requests. Returns
+    /// the new version.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::response::LongPoll;
+    ///
+    /// let poll = LongPoll::new("old");
+    /// assert_eq!(poll.set("new"), 2);
+    /// ```
+    pub fn set(&self, value: T) -> u64 {
+        self.update(|current| *current = value)
+    }
+
+    /// Changes the value via `f`, waking all waiting requests. Returns the new
+    /// version. The value is locked while `f` runs.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::response::LongPoll;
+    ///
+    /// let poll = LongPoll::new(vec![1]);
+    /// assert_eq!(poll.update(|numbers| numbers.push(2)), 2);
+    /// ```
+    pub fn update<F: FnOnce(&mut T)>(&self, f: F) -> u64 {
+        let version = {
+            let mut value = self.value.write();
+            f(&mut value.1);
+            value.0 += 1;
+            value.0
+        };
+
+        // There's always a receiver, in `self`, so this never fails.
+        let _ = self.sender.broadcast(version);
+        version
+    }
+}
+
+impl<T> Clone for LongPoll<T> {
+    fn clone(&self) -> Self {
+        LongPoll {
+            value: self.value.clone(),
+            sender: self.sender.clone(),
+            receiver: self.receiver.clone(),
+        }
+    }
+}
+
+impl<T: Clone> LongPoll<T> {
+    /// Returns the current version and a clone of the current value.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::response::LongPoll;
+    ///
+    /// let poll = LongPoll::new("hello");
+    /// assert_eq!(poll.current(), (1, "hello"));
+    /// ```
+    pub fn current(&self) -> (u64, T) {
+        self.value.read().clone()
+    }
+
+    /// Waits for the value to change from the version `since`, for at most
+    /// `max_wait`. If the current version differs from `since`, returns the
+    /// current value immediately. This includes a `since` newer than the
+    /// current version, as a client may have seen before a restart.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use std::time::Duration;
+    /// use rocket::response::{LongPoll, Update};
+    ///
+    /// # rocket::async_test(async {
+    /// let poll = LongPoll::new("hello");
+    ///
+    /// let update = poll.wait(0, Duration::from_secs(30)).await;
+    /// assert_eq!(update, Update::Changed(1, "hello"));
+    ///
+    /// let update = poll.wait(1, Duration::from_millis(10)).await;
+    /// assert_eq!(update, Update::Timeout(1));
+    /// # });
+    /// ```
+    pub async fn wait(&self, since: u64, max_wait: Duration) -> Update<T> {
+        // The clone wakes as soon as the version differs from the one it last
+        // saw, so a change between checking and waiting isn't missed.
+        let mut receiver = self.receiver.clone();
+        let changed = async {
+            loop {
+                {
+                    let current = self.value.read();
+                    if current.0 != since {
+                        return Update::Changed(current.0, current.1.clone());
+                    }
+                }
+
+                // The sender lives as long as `self`: this never returns `None`.
+                receiver.recv().await;
+            }
+        };
+
+        match tokio::time::timeout(max_wait, changed).await {
+            Ok(update) => update,
+            Err(_) => Update::Timeout(self.version()),
+        }
+    }
+}
+
+impl<T> Update<T> {
+    /// Returns the version in `self`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::response::Update;
+    ///
+    /// assert_eq!(Update::Changed(3, "value").version(), 3);
+    /// assert_eq!(Update::<()>::Timeout(2).version(), 2);
+    /// ```
+    pub fn version(&self) -> u64 {
+        match *self {
+            Update::Changed(version, _) | Update::Timeout(version) => version,
+        }
+    }
+
+    /// Maps the value of a [`Changed`](Update::Changed) update via `f`, as to
+    /// convert it into a responder.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::response::Update;
+    ///
+    /// let update = Update::Changed(3, vec!["a", "b"]).map(|v| v.join(","));
+    /// assert_eq!(update, Update::Changed(3, "a,b".to_string()));
+    /// ```
+    pub fn map<U, F: FnOnce(T) -> U>(self, f: F) -> Update<U> {
+        match self {
+            Update::Changed(version, value) => Update::Changed(version, f(value)),
+            Update::Timeout(version) => Update::Timeout(version),
+        }
+    }
+}
+
+/// Responds with the value of a change, or a `204 No Content` on a timeout,
+/// setting the [`LongPoll::HEADER`] header to the version.
+impl<'r, 'o: 'r, R: Responder<'r, 'o>> Responder<'r, 'o> for Update<R> {
+    fn respond_to(self, req: &'r Request<'_>) -> response::Result<'o> {
+        let header = Header::new(LongPoll::<()>::HEADER, self.version().to_string());
+        let mut response = match self {
+            Update::Changed(_, value) => value.respond_to(req)?,
+            Update::Timeout(_) => Response::build().status(Status::NoContent).finalize(),
+        };
+
+        response.set_header(header);
+        Ok(response)
+    }
+}
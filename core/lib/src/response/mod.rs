@@ -26,9 +26,15 @@
 mod responder;
 mod redirect;
 mod named_file;
+mod temp_file;
 mod stream;
 mod response;
 mod debug;
+mod download;
+mod multipart;
+mod parts;
+mod long_poll;
+mod event_stream;
 
 pub(crate) mod flash;
 
@@ -38,13 +44,20 @@ pub mod status;
 #[doc(hidden)] pub use rocket_codegen::Responder;
 
 pub use self::response::DEFAULT_CHUNK_SIZE;
-pub use self::response::{Response, ResponseBody, ResponseBuilder, Body};
-pub use self::responder::Responder;
+pub use self::response::{Response, ResponseBody, ResponseTrailers, ResponseBuilder, Body};
+pub use self::responder::{Responder, ErasedResponder};
 pub use self::redirect::Redirect;
+pub(crate) use self::redirect::RedirectHandler;
 pub use self::flash::Flash;
 pub use self::named_file::NamedFile;
+pub use self::temp_file::TempFile;
 pub use self::stream::Stream;
 pub use self::debug::Debug;
+pub use self::download::Download;
+pub use self::multipart::{Multipart, Part, ByteRanges};
+pub use self::parts::ResponseParts;
+pub use self::long_poll::{LongPoll, Update};
+pub use self::event_stream::{Event, EventStream};
 #[doc(inline)] pub use self::content::Content;
 
 /// Type alias for the `Result` of a [`Responder::respond_to()`] call.
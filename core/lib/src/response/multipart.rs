@@ -0,0 +1,588 @@
+use std::{io, fmt};
+use std::borrow::Cow;
+use std::collections::VecDeque;
+use std::io::{Cursor, SeekFrom};
+use std::ops::Range;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+use rand::{thread_rng, Rng, distributions::Alphanumeric};
+use tokio::io::{AsyncRead, AsyncSeek};
+
+use crate::request::Request;
+use crate::response::{self, Body, Responder, Response};
+use crate::http::{ContentType, Header, HeaderMap, Method, Status};
+
+/// The maximum number of ranges in a `Range` header that are served. Requests
+/// for more ranges are served the complete body instead.
+const MAX_RANGES: usize = 32;
+
+/// Returns a new, random multipart boundary.
+fn boundary() -> String {
+    thread_rng().sample_iter(&Alphanumeric).take(32).collect()
+}
+
+/// Returns the delimiter and header section that begin a part.
+fn part_head(boundary: &str, first: bool, headers: &HeaderMap<'_>) -> Vec<u8> {
+    let mut head = if first { String::new() } else { String::from("\r\n") };
+    head.push_str("--");
+    head.push_str(boundary);
+    head.push_str("\r\n");
+    for header in headers.iter() {
+        head.push_str(&format!("{}: {}\r\n", header.name(), header.value()));
+    }
+
+    head.push_str("\r\n");
+    head.into_bytes()
+}
+
+/// Returns the delimiter that closes a multipart body.
+fn close_delimiter(boundary: &str, first: bool) -> Vec<u8> {
+    let prefix = if first { "" } else { "\r\n" };
+    format!("{}--{}--\r\n", prefix, boundary).into_bytes()
+}
+
+/// A single part of a [`Multipart`] response: a header section and a body.
+///
+/// # Example
+///
+/// ```rust
+/// use rocket::response::Part;
+/// use rocket::http::{ContentType, Header};
+///
+/// let part = Part::new(&b"{ \"id\": 1 }"[..])
+///     .content_type(ContentType::JSON)
+///     .header(Header::new("Content-ID", "<item-1>"));
+/// ```
+pub struct Part<'r> {
+    headers: HeaderMap<'r>,
+    body: Pin<Box<dyn AsyncRead + Send + 'r>>,
+}
+
+impl<'r> Part<'r> {
+    /// Creates a new part with no headers and the body `body`, which is
+    /// streamed to the client.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use std::io::Cursor;
+    /// use rocket::response::Part;
+    ///
+    /// let part = Part::new(Cursor::new(format!("Hello, {}!", "world")));
+    /// ```
+    pub fn new<B: AsyncRead + Send + 'r>(body: B) -> Part<'r> {
+        Part { headers: HeaderMap::new(), body: Box::pin(body) }
+    }
+
+    /// Adds `header` to the headers of `self`, replacing any existing headers
+    /// of the same name.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::response::Part;
+    /// use rocket::http::Header;
+    ///
+    /// let part = Part::new(&b"hi"[..]).header(Header::new("Content-ID", "<greeting>"));
+    /// ```
+    pub fn header<'h: 'r, H: Into<Header<'h>>>(mut self, header: H) -> Self {
+        self.headers.replace(header);
+        self
+    }
+
+    /// Sets the `Content-Type` header of `self` to `content_type`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::response::Part;
+    /// use rocket::http::ContentType;
+    ///
+    /// let part = Part::new(&b"hi"[..]).content_type(ContentType::Plain);
+    /// ```
+    pub fn content_type(self, content_type: ContentType) -> Self {
+        self.header(content_type)
+    }
+
+    /// Returns the headers of `self`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::response::Part;
+    /// use rocket::http::ContentType;
+    ///
+    /// let part = Part::new(&b"hi"[..]).content_type(ContentType::Plain);
+    /// assert_eq!(part.headers().get_one("Content-Type"), Some("text/plain; charset=utf-8"));
+    /// ```
+    pub fn headers(&self) -> &HeaderMap<'r> {
+        &self.headers
+    }
+}
+
+impl fmt::Debug for Part<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Part").field("headers", &self.headers).finish()
+    }
+}
+
+/// Streams a multipart response, such as `multipart/mixed`, to the client.
+///
+/// A `Multipart` response consists of [`Part`]s, each with its own headers
+/// and body, which are sent to the client in order. Rocket generates a random
+/// boundary to delimit the parts and sets the `Content-Type` of the response
+/// to the multipart type with that boundary. Part bodies are streamed: they
+/// are not read into memory before the response is sent.
+///
+/// # Example
+///
+/// Respond to a batch request with one part for each item:
+///
+/// ```rust
+/// # #[macro_use] extern crate rocket;
+/// use rocket::response::{Multipart, Part};
+/// use rocket::http::{ContentType, Header};
+///
+/// #[get("/batch")]
+/// fn batch() -> Multipart<'static> {
+///     let items = vec!["{ \"id\": 1 }", "{ \"id\": 2 }"];
+///     items.into_iter().enumerate().fold(Multipart::mixed(), |multipart, (i, item)| {
+///         multipart.part(Part::new(item.as_bytes())
+///             .content_type(ContentType::JSON)
+///             .header(Header::new("Content-ID", format!("<item-{}>", i))))
+///     })
+/// }
+/// ```
+#[derive(Debug)]
+pub struct Multipart<'r> {
+    subtype: Cow<'static, str>,
+    boundary: String,
+    parts: Vec<Part<'r>>,
+}
+
+impl<'r> Multipart<'r> {
+    /// Creates a new, empty multipart response of type `multipart/{subtype}`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::response::Multipart;
+    ///
+    /// let multipart = Multipart::new("related");
+    /// assert_eq!(multipart.content_type().sub(), "related");
+    /// ```
+    pub fn new<S: Into<Cow<'static, str>>>(subtype: S) -> Multipart<'r> {
+        Multipart { subtype: subtype.into(), boundary: boundary(), parts: vec![] }
+    }
+
+    /// Creates a new, empty `multipart/mixed` response.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::response::Multipart;
+    ///
+    /// let multipart = Multipart::mixed();
+    /// assert_eq!(multipart.content_type().sub(), "mixed");
+    /// ```
+    pub fn mixed() -> Multipart<'r> {
+        Multipart::new("mixed")
+    }
+
+    /// Appends `part` to the parts of `self`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::response::{Multipart, Part};
+    ///
+    /// let multipart = Multipart::mixed()
+    ///     .part(Part::new(&b"one"[..]))
+    ///     .part(Part::new(&b"two"[..]));
+    /// ```
+    pub fn part(mut self, part: Part<'r>) -> Self {
+        self.parts.push(part);
+        self
+    }
+
+    /// Returns the boundary delimiting the parts of `self`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::response::Multipart;
+    ///
+    /// let multipart = Multipart::mixed();
+    /// assert!(multipart.boundary().chars().all(|c| c.is_ascii_alphanumeric()));
+    /// ```
+    pub fn boundary(&self) -> &str {
+        &self.boundary
+    }
+
+    /// Returns the `Content-Type` of the response, including the boundary.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::response::Multipart;
+    ///
+    /// let multipart = Multipart::mixed();
+    /// let content_type = multipart.content_type();
+    /// assert_eq!(content_type.top(), "multipart");
+    /// assert_eq!(content_type.sub(), "mixed");
+    ///
+    /// let boundary = content_type.params().find(|(k, _)| *k == "boundary");
+    /// assert_eq!(boundary, Some(("boundary", multipart.boundary())));
+    /// ```
+    pub fn content_type(&self) -> ContentType {
+        ContentType::with_params("multipart", self.subtype.clone(),
+            ("boundary", self.boundary.clone()))
+    }
+}
+
+/// Streams the parts to the client, setting the `Content-Type` to the
+/// multipart type with the boundary. Always returns `Ok`.
+impl<'r, 'o: 'r> Responder<'r, 'o> for Multipart<'o> {
+    fn respond_to(self, _: &'r Request<'_>) -> response::Result<'o> {
+        let content_type = self.content_type();
+        let Multipart { boundary, parts, .. } = self;
+
+        let mut readers: VecDeque<Pin<Box<dyn AsyncRead + Send + 'o>>> = VecDeque::new();
+        for (i, part) in parts.into_iter().enumerate() {
+            readers.push_back(Box::pin(Cursor::new(part_head(&boundary, i == 0, &part.headers))));
+            readers.push_back(part.body);
+        }
+
+        let close = close_delimiter(&boundary, readers.is_empty());
+        readers.push_back(Box::pin(Cursor::new(close)));
+
+        Response::build()
+            .header(content_type)
+            .streamed_body(Chain(readers))
+            .ok()
+    }
+}
+
+/// Reads each reader to completion, in order.
+struct Chain<'r>(VecDeque<Pin<Box<dyn AsyncRead + Send + 'r>>>);
+
+impl AsyncRead for Chain<'_> {
+    fn poll_read(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut [u8]
+    ) -> Poll<io::Result<usize>> {
+        let this = self.get_mut();
+        while let Some(reader) = this.0.front_mut() {
+            let n = futures::ready!(reader.as_mut().poll_read(cx, buf))?;
+            if n == 0 && !buf.is_empty() {
+                this.0.pop_front();
+                continue;
+            }
+
+            return Poll::Ready(Ok(n));
+        }
+
+        Poll::Ready(Ok(0))
+    }
+}
+
+/// Serves `Range` requests for the body of the wrapped responder.
+///
+/// A `ByteRanges` marks the response of the wrapped responder `R` as
+/// supporting range requests via `Accept-Ranges: bytes` and, when a `GET`
+/// request contains a `Range` header, responds with only the requested bytes:
+///
+///   * A single satisfiable range is sent with a status of `206 Partial
+///     Content` and a `Content-Range` header.
+///   * Multiple satisfiable ranges are sent with a status of `206 Partial
+///     Content` as a `multipart/byteranges` body, with one part per range.
+///     Each part has a `Content-Range` header and the `Content-Type` of the
+///     original response, if any.
+///   * If no range is satisfiable, the response has a status of `416 Range
+///     Not Satisfiable`, no body, and a `Content-Range` header with the size
+///     of the complete body.
+///
+/// A `Range` header that is malformed, that uses a unit other than `bytes`,
+/// or that requests an excessive number of ranges is ignored, as is one whose
+/// `If-Range` precondition doesn't match the response's `ETag` or
+/// `Last-Modified` header. Only a successful response with a body of known
+/// size can be served in part; any other response is sent unchanged. Notably,
+/// this means that the body must be _sized_ with a known size, as is the case
+/// for in-memory responders such as `String` and `Vec<u8>`.
+///
+/// # Example
+///
+/// ```rust
+/// # #[macro_use] extern crate rocket;
+/// use rocket::response::ByteRanges;
+///
+/// #[get("/video")]
+/// fn video() -> ByteRanges<Vec<u8>> {
+///     # let bytes = vec![];
+///     ByteRanges(bytes)
+/// }
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct ByteRanges<R>(pub R);
+
+/// Returns the ranges in the value of a `Range` header over a body of `size`
+/// bytes. Returns `None` if the header should be ignored. The returned ranges
+/// are empty if none are satisfiable.
+fn parse_ranges(value: &str, size: u64) -> Option<Vec<Range<u64>>> {
+    let eq = value.find('=')?;
+    if !value[..eq].trim().eq_ignore_ascii_case("bytes") {
+        return None;
+    }
+
+    let (mut ranges, mut specs) = (vec![], 0);
+    for spec in value[eq + 1..].split(',').map(|s| s.trim()).filter(|s| !s.is_empty()) {
+        specs += 1;
+        if specs > MAX_RANGES {
+            return None;
+        }
+
+        let dash = spec.find('-')?;
+        let (first, last) = (spec[..dash].trim(), spec[dash + 1..].trim());
+        if first.is_empty() {
+            let suffix: u64 = last.parse().ok()?;
+            if suffix > 0 && size > 0 {
+                ranges.push(size.saturating_sub(suffix)..size);
+            }
+        } else {
+            let first: u64 = first.parse().ok()?;
+            let last: u64 = match last.is_empty() {
+                true => u64::max_value(),
+                false => last.parse().ok()?,
+            };
+
+            if last < first {
+                return None;
+            }
+
+            if first < size {
+                ranges.push(first..std::cmp::min(last, size - 1) + 1);
+            }
+        }
+    }
+
+    if specs == 0 {
+        return None;
+    }
+
+    Some(ranges)
+}
+
+/// Returns `true` if the request has no `If-Range` header or if its value
+/// matches the strong `ETag` or the `Last-Modified` date of `response`.
+fn if_range_matches(req: &Request<'_>, response: &Response<'_>) -> bool {
+    let value = match req.headers().get_one("If-Range") {
+        Some(value) => value.trim(),
+        None => return true,
+    };
+
+    if value.starts_with("W/") {
+        return false;
+    }
+
+    let headers = response.headers();
+    headers.get_one("ETag") == Some(value) || headers.get_one("Last-Modified") == Some(value)
+}
+
+fn content_range(range: &Range<u64>, size: u64) -> String {
+    format!("bytes {}-{}/{}", range.start, range.end - 1, size)
+}
+
+/// Responds with the requested ranges of the wrapped responder's body, as
+/// described in [`ByteRanges`]. Returns `Err` only if the wrapped responder
+/// does.
+impl<'r, 'o: 'r, R: Responder<'r, 'o>> Responder<'r, 'o> for ByteRanges<R> {
+    fn respond_to(self, req: &'r Request<'_>) -> response::Result<'o> {
+        let mut response = self.0.respond_to(req)?;
+        let size = match response.body().and_then(|body| body.known_size()) {
+            Some(size) if response.status() == Status::Ok => size as u64,
+            _ => return Ok(response),
+        };
+
+        response.set_raw_header("Accept-Ranges", "bytes");
+        let ranges = match req.headers().get_one("Range") {
+            Some(value) if req.method() == Method::Get && if_range_matches(req, &response) => {
+                parse_ranges(value, size)
+            }
+            _ => None,
+        };
+
+        let ranges = match ranges {
+            Some(ranges) => ranges,
+            None => return Ok(response),
+        };
+
+        if ranges.is_empty() {
+            response.take_body();
+            response.remove_header("Content-Type");
+            response.set_status(Status::RangeNotSatisfiable);
+            response.set_raw_header("Content-Range", format!("bytes */{}", size));
+            return Ok(response);
+        }
+
+        let body = match response.take_body() {
+            Some(Body::Sized(body, _)) => body,
+            _ => return Ok(response),
+        };
+
+        response.set_status(Status::PartialContent);
+        if ranges.len() == 1 {
+            let range = ranges[0].clone();
+            response.set_raw_header("Content-Range", content_range(&range, size));
+            response.set_raw_header("Content-Length", (range.end - range.start).to_string());
+            response.set_streamed_body(RangeReader::new(body, vec![Segment::Range(range)]));
+            return Ok(response);
+        }
+
+        let boundary = boundary();
+        let content_type = response.content_type();
+        let (mut segments, mut length) = (Vec::with_capacity(2 * ranges.len() + 1), 0);
+        for (i, range) in ranges.into_iter().enumerate() {
+            let mut headers = HeaderMap::new();
+            if let Some(ref content_type) = content_type {
+                headers.add(content_type.clone());
+            }
+
+            headers.add(Header::new("Content-Range", content_range(&range, size)));
+            let head = part_head(&boundary, i == 0, &headers);
+            length += head.len() as u64 + (range.end - range.start);
+            segments.push(Segment::Bytes(head));
+            segments.push(Segment::Range(range));
+        }
+
+        let close = close_delimiter(&boundary, false);
+        length += close.len() as u64;
+        segments.push(Segment::Bytes(close));
+
+        let multipart = ContentType::with_params("multipart", "byteranges", ("boundary", boundary));
+        response.set_header(multipart);
+        response.set_raw_header("Content-Length", length.to_string());
+        response.set_streamed_body(RangeReader::new(body, segments));
+        Ok(response)
+    }
+}
+
+/// A segment of a response served by `RangeReader`.
+enum Segment {
+    /// Literal bytes, such as the delimiter and headers of a part.
+    Bytes(Vec<u8>),
+    /// A range of the bytes of the inner body.
+    Range(Range<u64>),
+}
+
+enum ReadState {
+    Next,
+    Bytes(Cursor<Vec<u8>>),
+    Seek { start: u64, len: u64, started: bool },
+    Read(u64),
+}
+
+/// Reads a sequence of segments, seeking the inner body to each range.
+struct RangeReader<S> {
+    inner: S,
+    segments: VecDeque<Segment>,
+    state: ReadState,
+}
+
+impl<S> RangeReader<S> {
+    fn new(inner: S, segments: Vec<Segment>) -> Self {
+        RangeReader { inner, segments: segments.into(), state: ReadState::Next }
+    }
+}
+
+impl<S: AsyncRead + AsyncSeek + Unpin> AsyncRead for RangeReader<S> {
+    fn poll_read(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut [u8]
+    ) -> Poll<io::Result<usize>> {
+        let this = self.get_mut();
+        loop {
+            match this.state {
+                ReadState::Next => match this.segments.pop_front() {
+                    Some(Segment::Bytes(bytes)) => {
+                        this.state = ReadState::Bytes(Cursor::new(bytes));
+                    }
+                    Some(Segment::Range(range)) => {
+                        let len = range.end - range.start;
+                        this.state = ReadState::Seek { start: range.start, len, started: false };
+                    }
+                    None => return Poll::Ready(Ok(0)),
+                },
+                ReadState::Bytes(ref mut cursor) => {
+                    let n = futures::ready!(Pin::new(&mut *cursor).poll_read(cx, buf))?;
+                    if n == 0 && !buf.is_empty() {
+                        this.state = ReadState::Next;
+                        continue;
+                    }
+
+                    return Poll::Ready(Ok(n));
+                }
+                ReadState::Seek { start, len, ref mut started } => {
+                    if !*started {
+                        let position = SeekFrom::Start(start);
+                        futures::ready!(Pin::new(&mut this.inner).start_seek(cx, position))?;
+                        *started = true;
+                    }
+
+                    futures::ready!(Pin::new(&mut this.inner).poll_complete(cx))?;
+                    this.state = ReadState::Read(len);
+                }
+                ReadState::Read(ref mut remaining) => {
+                    if *remaining == 0 || buf.is_empty() {
+                        this.state = ReadState::Next;
+                        if buf.is_empty() {
+                            return Poll::Ready(Ok(0));
+                        }
+
+                        continue;
+                    }
+
+                    let max = std::cmp::min(buf.len() as u64, *remaining) as usize;
+                    let inner = Pin::new(&mut this.inner);
+                    let n = futures::ready!(inner.poll_read(cx, &mut buf[..max]))?;
+                    if n == 0 {
+                        let error = io::Error::new(io::ErrorKind::UnexpectedEof, "body too short");
+                        return Poll::Ready(Err(error));
+                    }
+
+                    *remaining -= n as u64;
+                    return Poll::Ready(Ok(n));
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::parse_ranges;
+
+    #[test]
+    fn test_parse_ranges() {
+        assert_eq!(parse_ranges("bytes=0-499", 1000), Some(vec![0..500]));
+        assert_eq!(parse_ranges("bytes=500-", 1000), Some(vec![500..1000]));
+        assert_eq!(parse_ranges("bytes=-100", 1000), Some(vec![900..1000]));
+        assert_eq!(parse_ranges("bytes=-2000", 1000), Some(vec![0..1000]));
+        assert_eq!(parse_ranges("bytes=900-2000", 1000), Some(vec![900..1000]));
+        assert_eq!(parse_ranges("Bytes = 0-0, 2-3", 10), Some(vec![0..1, 2..4]));
+        assert_eq!(parse_ranges("bytes=1000-", 1000), Some(vec![]));
+        assert_eq!(parse_ranges("bytes=-0", 1000), Some(vec![]));
+        assert_eq!(parse_ranges("bytes=0-1, 2000-3000", 1000), Some(vec![0..2]));
+
+        assert_eq!(parse_ranges("bytes=", 1000), None);
+        assert_eq!(parse_ranges("bytes=5-1", 1000), None);
+        assert_eq!(parse_ranges("bytes=a-b", 1000), None);
+        assert_eq!(parse_ranges("items=0-1", 1000), None);
+        assert_eq!(parse_ranges("0-1", 1000), None);
+
+        let many = format!("bytes={}", vec!["0-0"; 33].join(","));
+        assert_eq!(parse_ranges(&many, 1000), None);
+    }
+}
@@ -0,0 +1,174 @@
+use std::sync::Mutex;
+
+use crate::request::{self, Request, FromRequest};
+use crate::response::Response;
+use crate::http::{Header, Cookie, CookieJar};
+
+/// Headers and cookies committed to the response to a request, whatever that
+/// response turns out to be.
+///
+/// Headers and cookies set directly on a [`Response`] or via the request's
+/// [`CookieJar`] are tied to the path that produced them: when a request
+/// fails, cookies set before the failure are discarded unless the
+/// `error_cookies` configuration parameter is enabled, and a catcher's
+/// response carries only the headers the catcher sets. Parts committed to
+/// `ResponseParts`, on the other hand, are applied to the final response,
+/// successful or not, including those from default catchers. Committed parts
+/// are applied last and so take precedence over headers and cookies of the
+/// same name set elsewhere.
+///
+/// A request's `ResponseParts` are retrieved via
+/// [`Request::response_parts()`] or as a request guard. This makes them
+/// usable from request guards, handlers, and catchers alike.
+///
+/// # Example
+///
+/// A catcher that clears the session cookie and asks for credentials on every
+/// `401` response, no matter where the request failed:
+///
+/// ```rust
+/// # #[macro_use] extern crate rocket;
+/// use rocket::Request;
+/// use rocket::http::{Cookie, Header};
+///
+/// #[catch(401)]
+/// fn unauthorized(req: &Request<'_>) -> &'static str {
+///     let parts = req.response_parts();
+///     parts.remove_cookie(Cookie::named("session"));
+///     parts.set_header(Header::new("WWW-Authenticate", "Bearer"));
+///     "please log in"
+/// }
+/// ```
+#[derive(Debug, Default)]
+pub struct ResponseParts {
+    ops: Mutex<Vec<Op>>,
+}
+
+#[derive(Debug)]
+enum Op {
+    Header(Header<'static>, bool),
+    Add(Cookie<'static>, bool),
+    Remove(Cookie<'static>, bool),
+}
+
+impl ResponseParts {
+    fn push(&self, op: Op) {
+        self.ops.lock().expect("response parts lock").push(op);
+    }
+
+    /// Commits `header` to the response, replacing any headers of the same
+    /// name.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use rocket::Request;
+    /// # use rocket::http::Method;
+    /// use rocket::http::Header;
+    ///
+    /// # Request::example(Method::Get, "/", |request| {
+    /// request.response_parts().set_header(Header::new("X-Request-Id", "42"));
+    /// # });
+    /// ```
+    pub fn set_header<H: Into<Header<'static>>>(&self, header: H) {
+        self.push(Op::Header(header.into(), false));
+    }
+
+    /// Commits `header` to the response, keeping any existing headers of the
+    /// same name.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use rocket::Request;
+    /// # use rocket::http::Method;
+    /// use rocket::http::Header;
+    ///
+    /// # Request::example(Method::Get, "/", |request| {
+    /// request.response_parts().adjoin_header(Header::new("Vary", "Cookie"));
+    /// # });
+    /// ```
+    pub fn adjoin_header<H: Into<Header<'static>>>(&self, header: H) {
+        self.push(Op::Header(header.into(), true));
+    }
+
+    /// Commits the addition of `cookie` to the response. The cookie is added
+    /// as if by [`CookieJar::add()`].
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use rocket::Request;
+    /// # use rocket::http::Method;
+    /// use rocket::http::Cookie;
+    ///
+    /// # Request::example(Method::Get, "/", |request| {
+    /// request.response_parts().add_cookie(Cookie::new("seen", "1"));
+    /// # });
+    /// ```
+    pub fn add_cookie(&self, cookie: Cookie<'static>) {
+        self.push(Op::Add(cookie, false));
+    }
+
+    /// Commits the removal of `cookie` to the response. The cookie is removed
+    /// as if by [`CookieJar::remove()`].
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use rocket::Request;
+    /// # use rocket::http::Method;
+    /// use rocket::http::Cookie;
+    ///
+    /// # Request::example(Method::Get, "/", |request| {
+    /// request.response_parts().remove_cookie(Cookie::named("session"));
+    /// # });
+    /// ```
+    pub fn remove_cookie(&self, cookie: Cookie<'static>) {
+        self.push(Op::Remove(cookie, false));
+    }
+
+    /// Commits the addition of the private `cookie` to the response. The
+    /// cookie is added as if by [`CookieJar::add_private()`].
+    #[cfg(feature = "secrets")]
+    #[cfg_attr(nightly, doc(cfg(feature = "secrets")))]
+    pub fn add_private_cookie(&self, cookie: Cookie<'static>) {
+        self.push(Op::Add(cookie, true));
+    }
+
+    /// Commits the removal of the private `cookie` to the response. The
+    /// cookie is removed as if by [`CookieJar::remove_private()`].
+    #[cfg(feature = "secrets")]
+    #[cfg_attr(nightly, doc(cfg(feature = "secrets")))]
+    pub fn remove_private_cookie(&self, cookie: Cookie<'static>) {
+        self.push(Op::Remove(cookie, true));
+    }
+
+    /// Applies the committed cookies to `jar` and headers to `response`.
+    pub(crate) fn apply(&self, jar: &CookieJar<'_>, response: &mut Response<'_>) {
+        let ops = std::mem::replace(&mut *self.ops.lock().expect("response parts lock"), vec![]);
+        for op in ops {
+            match op {
+                Op::Header(header, false) => { response.set_header(header); }
+                Op::Header(header, true) => response.adjoin_header(header),
+                Op::Add(cookie, false) => jar.add(cookie),
+                Op::Remove(cookie, false) => jar.remove(cookie),
+                #[cfg(feature = "secrets")]
+                Op::Add(cookie, true) => jar.add_private(cookie),
+                #[cfg(feature = "secrets")]
+                Op::Remove(cookie, true) => jar.remove_private(cookie),
+                #[cfg(not(feature = "secrets"))]
+                Op::Add(..) | Op::Remove(..) => unreachable!("private cookies are disabled"),
+            }
+        }
+    }
+}
+
+#[crate::async_trait]
+impl<'a, 'r> FromRequest<'a, 'r> for &'a ResponseParts {
+    type Error = std::convert::Infallible;
+
+    async fn from_request(req: &'a Request<'r>) -> request::Outcome<Self, Self::Error> {
+        request::Outcome::Success(req.response_parts())
+    }
+}
@@ -1,9 +1,11 @@
 use std::convert::TryInto;
 
+use crate::{Data, Route};
 use crate::request::Request;
 use crate::response::{self, Response, Responder};
-use crate::http::uri::Uri;
-use crate::http::Status;
+use crate::handler::{Handler, Outcome};
+use crate::http::uri::{Uri, Authority};
+use crate::http::{Status, route::Kind};
 
 /// An empty redirect response to a given URL.
 ///
@@ -40,6 +42,18 @@ use crate::http::Status;
 /// }
 /// ```
 ///
+/// # Proxies
+///
+/// When the `trust_proxy_headers` configuration parameter is set and a request
+/// arrives with an `X-Forwarded-Proto: https` header, redirects to an
+/// [`Origin`] URI are made absolute using the `https` scheme and the host in
+/// the `X-Forwarded-Host` or `Host` header. This ensures that clients of an
+/// application behind a TLS-terminating proxy are never redirected to HTTP.
+///
+/// When the `path_prefix` configuration parameter is set, the prefix is
+/// prepended to redirects to an [`Origin`] URI, so `Redirect::to(uri!(..))`
+/// reaches the route through a proxy serving the application under the prefix.
+///
 /// [`Origin`]: crate::http::uri::Origin
 /// [`uri!`]: ../macro.uri.html
 #[derive(Debug)]
@@ -147,11 +161,19 @@ impl Redirect {
 /// value used to create the `Responder` is an invalid URI, an error of
 /// `Status::InternalServerError` is returned.
 impl<'r> Responder<'r, 'static> for Redirect {
-    fn respond_to(self, _: &'r Request<'_>) -> response::Result<'static> {
+    fn respond_to(self, req: &'r Request<'_>) -> response::Result<'static> {
         if let Some(uri) = self.1 {
+            let location = match (&uri, forwarded_https_host(req)) {
+                (Uri::Origin(origin), Some(host)) => {
+                    format!("https://{}{}", host, req.prefixed_uri(origin.clone()))
+                }
+                (Uri::Origin(origin), None) => req.prefixed_uri(origin.clone()).to_string(),
+                _ => uri.to_string(),
+            };
+
             Response::build()
                 .status(self.0)
-                .raw_header("Location", uri.to_string())
+                .raw_header("Location", location)
                 .ok()
         } else {
             error!("Invalid URI used for redirect.");
@@ -159,3 +181,108 @@ impl<'r> Responder<'r, 'static> for Redirect {
         }
     }
 }
+
+// Returns the host the client used to reach a trusted proxy if the proxy
+// reports that the client connected via HTTPS.
+fn forwarded_https_host(req: &Request<'_>) -> Option<String> {
+    if !req.state.config.trust_proxy_headers {
+        return None;
+    }
+
+    // Proxies may append to these headers, so the first value is the client's.
+    let first = |name: &str| req.headers().get_one(name)
+        .and_then(|value: &str| value.split(',').next())
+        .map(|value| value.trim());
+
+    if !first("X-Forwarded-Proto")?.eq_ignore_ascii_case("https") {
+        return None;
+    }
+
+    let host = first("X-Forwarded-Host").or_else(|| first("Host"))?;
+    match Authority::parse(host) {
+        Ok(authority) if authority.user_info().is_none() => Some(authority.to_string()),
+        _ => None
+    }
+}
+
+/// A piece of the target URI template of a [`RedirectHandler`].
+#[derive(Debug, Clone)]
+enum Piece {
+    Literal(String),
+    Segment(usize),
+    Segments(usize),
+}
+
+/// The handler of a route registered via
+/// [`Rocket::redirect()`](crate::Rocket::redirect()): redirects to a URI built
+/// from a template referring to the dynamic path segments of the route.
+#[derive(Debug, Clone)]
+pub(crate) struct RedirectHandler {
+    status: Status,
+    target: Vec<Piece>,
+    has_query: bool,
+}
+
+impl RedirectHandler {
+    /// Parses `target`, where each `<param>` or `<param..>` refers to the
+    /// dynamic path segment of the same name in `route`.
+    pub(crate) fn new(status: Status, route: &Route, target: &str) -> Result<Self, String> {
+        let mut pieces = vec![];
+        let mut rest = target;
+        while let Some(start) = rest.find('<') {
+            let end = rest[start..].find('>')
+                .map(|i| start + i)
+                .ok_or_else(|| format!("unterminated parameter in `{}`", target))?;
+
+            if start > 0 {
+                pieces.push(Piece::Literal(rest[..start].to_string()));
+            }
+
+            let param = &rest[start + 1..end];
+            let name = param.strip_suffix("..").unwrap_or(param);
+            let (i, segment) = route.metadata.path_segments.iter()
+                .enumerate()
+                .find(|(_, s)| s.kind != Kind::Static && s.name == name)
+                .ok_or_else(|| format!("`<{}>` is not a dynamic path segment of `{}`",
+                    param, route.uri))?;
+
+            match segment.kind {
+                Kind::Multi => pieces.push(Piece::Segments(i)),
+                _ => pieces.push(Piece::Segment(i)),
+            }
+
+            rest = &rest[end + 1..];
+        }
+
+        if !rest.is_empty() {
+            pieces.push(Piece::Literal(rest.to_string()));
+        }
+
+        Ok(RedirectHandler { status, target: pieces, has_query: target.contains('?') })
+    }
+}
+
+#[crate::async_trait]
+impl Handler for RedirectHandler {
+    async fn handle<'r, 's: 'r>(&'s self, req: &'r Request<'_>, _: Data) -> Outcome<'r> {
+        let mut uri = String::new();
+        for piece in &self.target {
+            match *piece {
+                Piece::Literal(ref string) => uri.push_str(string),
+                Piece::Segment(i) => {
+                    uri.push_str(req.raw_segment_str(i).map(|s| s.as_str()).unwrap_or(""));
+                }
+                Piece::Segments(i) => {
+                    uri.push_str(req.raw_segments(i).map(|s| s.0).unwrap_or(""));
+                }
+            }
+        }
+
+        if let Some(query) = req.uri().query().filter(|_| !self.has_query) {
+            uri.push('?');
+            uri.push_str(query);
+        }
+
+        Outcome::from(req, Redirect(self.status, uri.try_into().ok()))
+    }
+}
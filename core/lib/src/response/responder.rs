@@ -328,3 +328,65 @@ impl<'r> Responder<'r, 'static> for Status {
         }
     }
 }
+
+mod private {
+    pub trait Sealed {}
+    impl<R: for<'r> super::Responder<'r, 'static>> Sealed for R {}
+}
+
+/// An object-safe [`Responder`] for dynamically typed responses.
+///
+/// Because [`Responder::respond_to()`] takes `self` by value, a `Responder`
+/// cannot be used as a trait object. `ErasedResponder` can: a `Box<dyn
+/// ErasedResponder>` implements `Responder` by delegating to the boxed value.
+/// This is useful when the concrete response type can't be named at compile
+/// time, as in plugin systems or highly dynamic handlers.
+///
+/// This trait cannot be implemented by any type. Instead, all types that
+/// implement `Responder<'r, 'static>` for every `'r` automatically implement
+/// `ErasedResponder`. This includes nearly all of Rocket's built-in responders.
+///
+/// # Example
+///
+/// ```rust
+/// # #[macro_use] extern crate rocket;
+/// use rocket::http::Status;
+/// use rocket::response::{ErasedResponder, content::Html};
+///
+/// #[get("/<kind>")]
+/// fn dynamic(kind: usize) -> Box<dyn ErasedResponder + Send> {
+///     match kind {
+///         0 => Box::new("plain text"),
+///         1 => Box::new(Html("<b>markup</b>")),
+///         _ => Box::new(Status::NotFound),
+///     }
+/// }
+/// ```
+pub trait ErasedResponder: private::Sealed {
+    /// Returns `Ok` if a `Response` could be generated successfully from the
+    /// boxed value. Otherwise, returns an `Err` with a failing `Status`.
+    ///
+    /// This method is called by the `Responder` implementation for `Box<dyn
+    /// ErasedResponder>` and needn't be called directly.
+    fn respond_to_boxed(self: Box<Self>, req: &Request<'_>) -> response::Result<'static>;
+}
+
+impl<R: for<'r> Responder<'r, 'static>> ErasedResponder for R {
+    fn respond_to_boxed(self: Box<Self>, req: &Request<'_>) -> response::Result<'static> {
+        (*self).respond_to(req)
+    }
+}
+
+/// Responds with the boxed `ErasedResponder`.
+impl<'r, 'a> Responder<'r, 'static> for Box<dyn ErasedResponder + 'a> {
+    fn respond_to(self, req: &'r Request<'_>) -> response::Result<'static> {
+        self.respond_to_boxed(req)
+    }
+}
+
+/// Responds with the boxed `ErasedResponder`.
+impl<'r, 'a> Responder<'r, 'static> for Box<dyn ErasedResponder + Send + 'a> {
+    fn respond_to(self, req: &'r Request<'_>) -> response::Result<'static> {
+        self.respond_to_boxed(req)
+    }
+}
@@ -1,11 +1,13 @@
 use std::{io, fmt, str};
 use std::borrow::Cow;
+use std::future::Future;
 use std::pin::Pin;
 
 use tokio::io::{AsyncRead, AsyncReadExt, AsyncSeek, AsyncSeekExt};
 
 use crate::response::{self, Responder};
-use crate::http::{Header, HeaderMap, Status, ContentType, Cookie};
+use crate::deferred::{self, Task, TaskResult};
+use crate::http::{Header, HeaderMap, HeaderError, Status, ContentType, Cookie};
 
 /// The default size, in bytes, of a chunk for streamed responses.
 pub const DEFAULT_CHUNK_SIZE: usize = 4096;
@@ -462,6 +464,57 @@ impl<'r> ResponseBuilder<'r> {
         self
     }
 
+    /// Declares the trailer fields `names` and sets the future that resolves
+    /// to their values to `trailers`. See [`Response::set_trailers()`].
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::Response;
+    /// use rocket::http::{Header, HeaderMap};
+    ///
+    /// let response = Response::build()
+    ///     .streamed_body(&b"hello"[..])
+    ///     .trailers(vec!["X-Checksum"], async {
+    ///         let mut trailers = HeaderMap::new();
+    ///         trailers.add(Header::new("X-Checksum", "abc123"));
+    ///         trailers
+    ///     })
+    ///     .finalize();
+    ///
+    /// assert_eq!(response.headers().get_one("Trailer"), Some("X-Checksum"));
+    /// ```
+    #[inline(always)]
+    pub fn trailers<N, F>(&mut self, names: N, trailers: F) -> &mut ResponseBuilder<'r>
+        where N: IntoIterator, N::Item: Into<Cow<'static, str>>,
+              F: Future<Output = HeaderMap<'static>> + Send + 'r
+    {
+        self.response.set_trailers(names, trailers);
+        self
+    }
+
+    /// Defers `f` until the response has been sent. See
+    /// [`Response::on_complete()`].
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::Response;
+    ///
+    /// let response = Response::build()
+    ///     .on_complete(|| async { /* evict the cached response... */ })
+    ///     .finalize();
+    /// ```
+    #[inline(always)]
+    pub fn on_complete<F, Fut>(&mut self, f: F) -> &mut ResponseBuilder<'r>
+        where F: FnOnce() -> Fut + Send + 'static,
+              Fut: Future + Send + 'static,
+              Fut::Output: TaskResult
+    {
+        self.response.on_complete(f);
+        self
+    }
+
     /// Merges the `other` `Response` into `self` by setting any fields in
     /// `self` to the corresponding value in `other` if they are set in `other`.
     /// Fields in `self` are unchanged if they are not set in `other`. If a
@@ -592,12 +645,21 @@ pub type ResponseBody<'r> = Body<
     Pin<Box<dyn AsyncRead + Send + 'r>>
 >;
 
+/// The trailers of a response: a future that resolves to the trailer fields.
+///
+/// The future is polled only once the body has been written in its entirety,
+/// allowing trailer values, such as a checksum, to be computed as the body is
+/// streamed.
+pub type ResponseTrailers<'r> = Pin<Box<dyn Future<Output = HeaderMap<'static>> + Send + 'r>>;
+
 /// A response, as returned by types implementing [`Responder`].
 #[derive(Default)]
 pub struct Response<'r> {
     status: Option<Status>,
     headers: HeaderMap<'r>,
     body: Option<ResponseBody<'r>>,
+    trailers: Option<ResponseTrailers<'r>>,
+    on_complete: Vec<Task>,
 }
 
 impl<'r> Response<'r> {
@@ -624,6 +686,8 @@ impl<'r> Response<'r> {
             status: None,
             headers: HeaderMap::new(),
             body: None,
+            trailers: None,
+            on_complete: vec![],
         }
     }
 
@@ -828,6 +892,37 @@ impl<'r> Response<'r> {
         self.set_header(Header::new(name, value))
     }
 
+    /// Sets the header `header` in `self` exactly like
+    /// [`set_header()`](#method.set_header), but only if its name and value are
+    /// valid. Otherwise, `self` is left unchanged and an `Err` indicating which
+    /// of the two is malformed is returned. Use this method when the header is
+    /// derived from untrusted input, such as a value from the request.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::Response;
+    /// use rocket::http::{Header, HeaderError};
+    ///
+    /// let mut response = Response::new();
+    ///
+    /// let result = response.try_set_header(Header::new("X-Custom", "1"));
+    /// assert_eq!(result, Ok(false));
+    /// assert_eq!(response.headers().get_one("X-Custom"), Some("1"));
+    ///
+    /// let result = response.try_set_header(Header::new("X-Custom", "2\r\nX-Evil: 3"));
+    /// assert_eq!(result, Err(HeaderError::InvalidValue));
+    /// assert_eq!(response.headers().get_one("X-Custom"), Some("1"));
+    /// ```
+    #[inline]
+    pub fn try_set_header<'h: 'r, H>(&mut self, header: H) -> Result<bool, HeaderError>
+        where H: Into<Header<'h>>
+    {
+        let header = header.into();
+        header.validate()?;
+        Ok(self.set_header(header))
+    }
+
     /// Adds the header `header` to `self`. If `self` contains headers with the
     /// name `header.name`, another header with the same name and value
     /// `header.value` is added. The type of `header` can be any type that
@@ -885,6 +980,36 @@ impl<'r> Response<'r> {
         self.adjoin_header(Header::new(name, value));
     }
 
+    /// Adds the header `header` to `self` exactly like
+    /// [`adjoin_header()`](#method.adjoin_header), but only if its name and
+    /// value are valid. Otherwise, `self` is left unchanged and an `Err`
+    /// indicating which of the two is malformed is returned. Use this method
+    /// when the header is derived from untrusted input, such as a value from
+    /// the request.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::Response;
+    /// use rocket::http::{Header, HeaderError};
+    ///
+    /// let mut response = Response::new();
+    /// assert!(response.try_adjoin_header(Header::new("X-Custom", "one")).is_ok());
+    ///
+    /// let result = response.try_adjoin_header(Header::new("X Custom", "two"));
+    /// assert_eq!(result, Err(HeaderError::InvalidName));
+    /// assert_eq!(response.headers().len(), 1);
+    /// ```
+    #[inline]
+    pub fn try_adjoin_header<'h: 'r, H>(&mut self, header: H) -> Result<(), HeaderError>
+        where H: Into<Header<'h>>
+    {
+        let header = header.into();
+        header.validate()?;
+        self.adjoin_header(header);
+        Ok(())
+    }
+
     /// Removes all headers with the name `name`.
     ///
     /// # Example
@@ -1041,7 +1166,8 @@ impl<'r> Response<'r> {
     }
 
     // Makes the `AsyncRead`er in the body empty but leaves the size of the body if
-    // it exists. Only meant to be used to handle HEAD requests automatically.
+    // it exists. Trailers, and the `Trailer` header declaring them, are removed.
+    // Only meant to be used to handle HEAD requests automatically.
     #[inline(always)]
     pub(crate) fn strip_body(&mut self) {
         if let Some(body) = self.take_body() {
@@ -1050,6 +1176,10 @@ impl<'r> Response<'r> {
                 Body::Chunked(..) => None
             };
         }
+
+        if self.trailers.take().is_some() {
+            self.remove_header("Trailer");
+        }
     }
 
     /// Sets the body of `self` to be the fixed-sized `body` with size
@@ -1153,6 +1283,101 @@ impl<'r> Response<'r> {
         });
     }
 
+    /// Declares the trailer fields `names` in the `Trailer` header and sets
+    /// `trailers` as the future that resolves to their values.
+    ///
+    /// Trailers are header fields sent after the body. `trailers` is polled
+    /// only after the body has been written in its entirety, allowing values
+    /// computed while streaming the body, such as a checksum or a gRPC status,
+    /// to be sent. Names returned by `trailers` that were not declared should
+    /// be avoided; clients may ignore them.
+    ///
+    /// Trailers are sent on HTTP/2 connections. HTTP/1.1 responses are written
+    /// without them, but the `Trailer` header is still emitted.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::Response;
+    /// use rocket::http::{Header, HeaderMap};
+    ///
+    /// # rocket::async_test(async {
+    /// let mut response = Response::new();
+    /// response.set_streamed_body(&b"hello"[..]);
+    /// response.set_trailers(vec!["X-Checksum"], async {
+    ///     let mut trailers = HeaderMap::new();
+    ///     trailers.add(Header::new("X-Checksum", "abc123"));
+    ///     trailers
+    /// });
+    ///
+    /// assert_eq!(response.headers().get_one("Trailer"), Some("X-Checksum"));
+    ///
+    /// let trailers = response.take_trailers().unwrap().await;
+    /// assert_eq!(trailers.get_one("X-Checksum"), Some("abc123"));
+    /// # })
+    /// ```
+    pub fn set_trailers<N, F>(&mut self, names: N, trailers: F)
+        where N: IntoIterator, N::Item: Into<Cow<'static, str>>,
+              F: Future<Output = HeaderMap<'static>> + Send + 'r
+    {
+        let names: Vec<Cow<'static, str>> = names.into_iter().map(Into::into).collect();
+        self.set_raw_header("Trailer", names.join(", "));
+        self.trailers = Some(Box::pin(trailers));
+    }
+
+    /// Removes and returns the trailers of `self`, if any, leaving the
+    /// declaring `Trailer` header in place.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::Response;
+    /// use rocket::http::HeaderMap;
+    ///
+    /// let mut response = Response::new();
+    /// assert!(response.take_trailers().is_none());
+    ///
+    /// response.set_trailers(vec!["X-Checksum"], async { HeaderMap::new() });
+    /// assert!(response.take_trailers().is_some());
+    /// assert!(response.take_trailers().is_none());
+    /// ```
+    #[inline(always)]
+    pub fn take_trailers(&mut self) -> Option<ResponseTrailers<'r>> {
+        self.trailers.take()
+    }
+
+    /// Defers `f` until `self` has been sent: the future returned by `f` is
+    /// then run to completion on Rocket's runtime. If the future resolves to an
+    /// `Err`, the error is logged. Work deferred by a response runs after that
+    /// deferred by its request. See [`deferred`](crate::deferred) for details.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use std::io::Cursor;
+    /// use rocket::Response;
+    ///
+    /// let body = "this response is sent once";
+    /// let mut response = Response::new();
+    /// response.set_sized_body(body.len(), Cursor::new(body));
+    /// response.on_complete(|| async {
+    ///     /* remove the file that was sent... */
+    ///     Ok::<(), std::io::Error>(())
+    /// });
+    /// ```
+    pub fn on_complete<F, Fut>(&mut self, f: F)
+        where F: FnOnce() -> Fut + Send + 'static,
+              Fut: Future + Send + 'static,
+              Fut::Output: TaskResult
+    {
+        self.on_complete.push(deferred::task(f));
+    }
+
+    /// Removes and returns the work deferred by `self`.
+    pub(crate) fn take_on_complete(&mut self) -> Vec<Task> {
+        std::mem::take(&mut self.on_complete)
+    }
+
     /// Replaces this response's status and body with that of `other`, if they
     /// exist in `other`. Any headers that exist in `other` replace the ones in
     /// `self`. Any in `self` that aren't in `other` remain in `self`.
@@ -1193,6 +1418,12 @@ impl<'r> Response<'r> {
             self.body = Some(body);
         }
 
+        if let Some(trailers) = other.trailers {
+            self.trailers = Some(trailers);
+        }
+
+        self.on_complete.extend(other.on_complete);
+
         for (name, values) in other.headers.into_iter_raw() {
             self.headers.replace_all(name.into_cow(), values);
         }
@@ -1238,6 +1469,12 @@ impl<'r> Response<'r> {
             self.body = other.body;
         }
 
+        if self.trailers.is_none() {
+            self.trailers = other.trailers;
+        }
+
+        self.on_complete.extend(other.on_complete);
+
         for (name, mut values) in other.headers.into_iter_raw() {
             self.headers.add_all(name.into_cow(), &mut values);
         }
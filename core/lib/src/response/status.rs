@@ -8,7 +8,6 @@
 //! out additional headers and, importantly, the body of the response.
 
 use std::hash::{Hash, Hasher};
-use std::collections::hash_map::DefaultHasher;
 use std::borrow::Cow;
 
 use crate::request::Request;
@@ -97,7 +96,8 @@ impl<'r, R> Created<R> {
     }
 
     /// Adds `responder` as the body of `self`. Computes a hash of the
-    /// `responder` to be used as the value of the `ETag` header.
+    /// `responder` to be used as the value of the `ETag` header. The hash is
+    /// stable: it doesn't change when Rocket or Rust is upgraded.
     ///
     /// # Example
     ///
@@ -119,21 +119,42 @@ impl<'r, R> Created<R> {
     /// assert_eq!(loc, Some("http://myservice.com/resource.json"));
     ///
     /// let etag = response.headers().get_one("ETag");
-    /// assert_eq!(etag, Some(r#""13046220615156895040""#));
+    /// assert_eq!(etag, Some(r#""6977989795294101765""#));
     ///
     /// let body = response.into_string();
     /// assert_eq!(body.unwrap(), "{ 'resource': 'Hello, world!' }");
     /// ```
     pub fn tagged_body(mut self, responder: R) -> Self where R: Hash {
-        let mut hasher = &mut DefaultHasher::default();
+        let mut hasher = Fnv1a::default();
         responder.hash(&mut hasher);
-        let hash = hasher.finish();
+        self.2 = Some(hasher.finish());
         self.1 = Some(responder);
-        self.2 = Some(hash);
         self
     }
 }
 
+/// A 64-bit FNV-1a hasher. `DefaultHasher`'s algorithm is unspecified and may
+/// change between Rust releases, which would change every `ETag` we send.
+struct Fnv1a(u64);
+
+impl Default for Fnv1a {
+    fn default() -> Self {
+        Fnv1a(0xcbf2_9ce4_8422_2325)
+    }
+}
+
+impl Hasher for Fnv1a {
+    fn finish(&self) -> u64 {
+        self.0
+    }
+
+    fn write(&mut self, bytes: &[u8]) {
+        for &byte in bytes {
+            self.0 = (self.0 ^ u64::from(byte)).wrapping_mul(0x0000_0100_0000_01b3);
+        }
+    }
+}
+
 /// Sets the status code of the response to 201 Created. Sets the `Location`
 /// header to the parameter in the [`Created::new()`] constructor.
 ///
@@ -0,0 +1,159 @@
+use std::io::{self, SeekFrom};
+use std::path::{Path, PathBuf};
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+use tokio::fs::File;
+use tokio::io::{AsyncRead, AsyncSeek};
+
+use crate::request::Request;
+use crate::response::{self, Responder, Response};
+use crate::http::ContentType;
+
+/// A file that is deleted once it has been sent to the client.
+///
+/// A `TempFile` responds like a [`NamedFile`](crate::response::NamedFile): with
+/// the file's contents and a Content-Type based on the file's extension. Once
+/// the response body is dropped, the file is deleted. This happens when the
+/// response has been written in full, when the client disconnects before then,
+/// and when the response is discarded without being written at all, as happens
+/// when a response fairing replaces the body. If the `TempFile` is dropped
+/// without being used as a responder, the file is also deleted.
+///
+/// `TempFile` is intended for files generated on demand for a single response,
+/// such as archives or reports: it obviates the need to clean up such files
+/// separately.
+///
+/// Deletion errors, such as the file having already been deleted, are logged
+/// and otherwise ignored.
+///
+/// # Example
+///
+/// ```rust
+/// # #[macro_use] extern crate rocket;
+/// use std::io;
+/// use rocket::response::TempFile;
+///
+/// # async fn generate_report(path: &str) -> io::Result<()> { Ok(()) }
+/// #[get("/report")]
+/// async fn report() -> io::Result<TempFile> {
+///     let path = "/tmp/report.csv";
+///     generate_report(path).await?;
+///     TempFile::open(path).await
+/// }
+/// ```
+#[derive(Debug)]
+pub struct TempFile {
+    file: File,
+    len: u64,
+    cleanup: Cleanup,
+}
+
+/// Deletes the file at the contained path when dropped.
+#[derive(Debug)]
+struct Cleanup(PathBuf);
+
+impl Drop for Cleanup {
+    fn drop(&mut self) {
+        if let Err(e) = std::fs::remove_file(&self.0) {
+            warn_!("Failed to delete temporary file {:?}: {}", self.0, e);
+        }
+    }
+}
+
+impl TempFile {
+    /// Attempts to open the file at `path` in read-only mode. The file is
+    /// deleted when the returned `TempFile`, or the body of the response it
+    /// generates, is dropped.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if path does not already exist or its
+    /// metadata can't be read. Other errors may also be returned according to
+    /// [`OpenOptions::open()`](std::fs::OpenOptions::open()). The file isn't
+    /// deleted if an error is returned.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::response::TempFile;
+    ///
+    /// # rocket::async_test(async {
+    /// let file = TempFile::open("report.zip").await;
+    /// # });
+    /// ```
+    pub async fn open<P: AsRef<Path>>(path: P) -> io::Result<TempFile> {
+        let file = File::open(path.as_ref()).await?;
+        let len = file.metadata().await?.len();
+        Ok(TempFile { file, len, cleanup: Cleanup(path.as_ref().to_path_buf()) })
+    }
+
+    /// Retrieve the path of this file.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use std::io;
+    /// use rocket::response::TempFile;
+    ///
+    /// # #[allow(dead_code)]
+    /// # async fn demo_path() -> io::Result<()> {
+    /// let file = TempFile::open("report.zip").await?;
+    /// assert_eq!(file.path().as_os_str(), "report.zip");
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[inline(always)]
+    pub fn path(&self) -> &Path {
+        &self.cleanup.0
+    }
+}
+
+/// The body of a `TempFile` response, which deletes the file when dropped.
+struct TempFileBody {
+    file: File,
+    _cleanup: Cleanup,
+}
+
+impl AsyncRead for TempFileBody {
+    fn poll_read(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut [u8],
+    ) -> Poll<io::Result<usize>> {
+        Pin::new(&mut self.file).poll_read(cx, buf)
+    }
+}
+
+impl AsyncSeek for TempFileBody {
+    fn start_seek(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        position: SeekFrom,
+    ) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.file).start_seek(cx, position)
+    }
+
+    fn poll_complete(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<u64>> {
+        Pin::new(&mut self.file).poll_complete(cx)
+    }
+}
+
+/// Streams the file to the client with a sized body and deletes it once the
+/// body is dropped. Sets or overrides the Content-Type in the response
+/// according to the file's extension if the extension is recognized.
+impl<'r> Responder<'r, 'static> for TempFile {
+    fn respond_to(self, _: &'r Request<'_>) -> response::Result<'static> {
+        let content_type = self.path().extension()
+            .and_then(|ext| ContentType::from_extension(&ext.to_string_lossy()));
+
+        let len = self.len as usize;
+        let body = TempFileBody { file: self.file, _cleanup: self.cleanup };
+        let mut response = Response::build().sized_body(len, body).finalize();
+        if let Some(ct) = content_type {
+            response.set_header(ct);
+        }
+
+        Ok(response)
+    }
+}
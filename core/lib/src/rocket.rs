@@ -1,36 +1,60 @@
+use std::sync::Arc;
+use std::future::Future;
 use std::collections::HashMap;
+use std::marker::PhantomData;
 
 use yansi::Paint;
 use state::Container;
 use figment::Figment;
 use tokio::sync::mpsc;
-use futures::future::FutureExt;
+use futures::future::{FutureExt, BoxFuture};
 
 use crate::logger;
 use crate::config::Config;
 use crate::catcher::Catcher;
-use crate::router::{Router, Route};
+use crate::router::{Router, Route, RouteTable};
+use crate::request::ScopedState;
 use crate::fairing::{Fairing, Fairings};
 use crate::logger::PaintExt;
-use crate::shutdown::Shutdown;
+use crate::shutdown::{self, Shutdown, Terminator};
 use crate::http::uri::Origin;
+use crate::http::{Method, Status, Listener};
+use crate::handler;
+use crate::events::Events;
+use crate::response::RedirectHandler;
 use crate::error::{Error, ErrorKind};
+use crate::phase::{Phase, Build, Ignite, Orbit};
 
 /// The main `Rocket` type: used to mount routes and catchers and launch the
 /// application.
-pub struct Rocket {
+///
+/// The type parameter `P` is the [`Phase`] the instance is in. Instances
+/// start in the [`Build`] phase, where they can be modified, move to the
+/// [`Ignite`] phase once validated via [`Rocket::ignited()`], and reach the
+/// [`Orbit`] phase once launched. Methods are only available in the phases in
+/// which they make sense.
+pub struct Rocket<P: Phase = Build> {
     pub(crate) config: Config,
     pub(crate) figment: Figment,
     pub(crate) managed_state: Container,
+    pub(crate) scoped_state: ScopedState,
     pub(crate) router: Router,
     pub(crate) default_catcher: Option<Catcher>,
     pub(crate) catchers: HashMap<u16, Catcher>,
+    pub(crate) range_catchers: Vec<Catcher>,
     pub(crate) fairings: Fairings,
     pub(crate) shutdown_receiver: Option<mpsc::Receiver<()>>,
     pub(crate) shutdown_handle: Shutdown,
+    pub(crate) terminators: Vec<fn(&Container) -> BoxFuture<'_, ()>>,
+    pub(crate) events: Events,
+    #[cfg(unix)]
+    pub(crate) handover: Option<crate::Handover>,
+    #[cfg(feature = "tls")]
+    pub(crate) tls_handle: Option<crate::TlsHandle>,
+    phase: PhantomData<P>,
 }
 
-impl Rocket {
+impl Rocket<Build> {
     /// Create a new `Rocket` application using the configuration information in
     /// `Rocket.toml`. If the file does not exist or if there is an I/O error
     /// reading the file, the defaults, overridden by any environment-based
@@ -82,7 +106,7 @@ impl Rocket {
     #[inline]
     pub fn custom<T: figment::Provider>(provider: T) -> Rocket {
         let (config, figment) = (Config::from(&provider), Figment::from(provider));
-        logger::try_init(config.log_level, config.cli_colors, false);
+        logger::try_init(config.log_level, config.log_format, config.cli_colors, false);
         config.pretty_print(figment.profile());
 
         let managed_state = Container::new();
@@ -90,12 +114,21 @@ impl Rocket {
         Rocket {
             config, figment,
             managed_state,
+            scoped_state: ScopedState::default(),
             shutdown_handle: Shutdown(shutdown_sender),
             router: Router::new(),
             default_catcher: None,
             catchers: HashMap::new(),
+            range_catchers: vec![],
             fairings: Fairings::new(),
             shutdown_receiver: Some(shutdown_receiver),
+            terminators: vec![],
+            events: Events::default(),
+            #[cfg(unix)]
+            handover: None,
+            #[cfg(feature = "tls")]
+            tls_handle: None,
+            phase: PhantomData,
         }
     }
 
@@ -185,6 +218,217 @@ impl Rocket {
         self
     }
 
+    /// Mounts all of the routes in the supplied vector at the given `base` path,
+    /// as [`mount()`](Rocket::mount()) does, and manages `state` for routes
+    /// mounted at `base` or below it.
+    ///
+    /// A [`State<T>`](crate::State) guard in a route resolves to the `T`
+    /// managed for the nearest enclosing base of the route, falling back to
+    /// state managed via [`manage()`](Rocket::manage()) if there is none.
+    /// This allows several instances of the same set of routes to be mounted
+    /// with different state. Scoped state is also returned by
+    /// [`Request::managed_state()`](crate::Request::managed_state()) once the
+    /// request has been routed.
+    ///
+    /// To manage several values for the same base, call this method once per
+    /// value; `routes` may be empty.
+    ///
+    /// # Panics
+    ///
+    /// Panics if a value of type `T` is already being managed for `base`, or
+    /// if `base` is invalid, as described in [`mount()`](Rocket::mount()).
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # #[macro_use] extern crate rocket;
+    /// use rocket::State;
+    /// use rocket::local::blocking::Client;
+    ///
+    /// struct BlogConfig { title: &'static str }
+    ///
+    /// #[get("/")]
+    /// fn index(config: State<BlogConfig>) -> &'static str {
+    ///     config.title
+    /// }
+    ///
+    /// let rocket = rocket::ignite()
+    ///     .mount_with_state("/news", BlogConfig { title: "News" }, routes![index])
+    ///     .mount_with_state("/travel", BlogConfig { title: "Travel" }, routes![index])
+    ///     .mount("/", routes![index])
+    ///     .manage(BlogConfig { title: "Home" });
+    ///
+    /// let client = Client::tracked(rocket).unwrap();
+    /// assert_eq!(client.get("/news").dispatch().into_string().unwrap(), "News");
+    /// assert_eq!(client.get("/travel").dispatch().into_string().unwrap(), "Travel");
+    /// assert_eq!(client.get("/").dispatch().into_string().unwrap(), "Home");
+    /// ```
+    pub fn mount_with_state<T, R>(mut self, base: &str, state: T, routes: R) -> Self
+        where T: Send + Sync + 'static, R: Into<Vec<Route>>
+    {
+        self = self.mount(base, routes);
+
+        let scope = Origin::parse_owned(base.to_string())
+            .expect("valid mount point")
+            .into_normalized();
+
+        let type_name = std::any::type_name::<T>();
+        if !self.scoped_state.scope(scope.path()).set(state) {
+            error!("State for type '{}' is already being managed at '{}'!", type_name, scope);
+            panic!("Aborting due to duplicately managed state.");
+        }
+
+        self
+    }
+
+    /// Removes all routes mounted at `base`, as in a call to
+    /// [`mount()`](Rocket::mount()) with the same `base`. Routes mounted at
+    /// other bases, including those nested below `base`, are not removed.
+    ///
+    /// Along with [`mount()`](Rocket::mount()), this allows a set of routes to
+    /// be replaced before launch, for instance by a fairing or in tests. Routes
+    /// cannot be removed once the application has launched.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `base` is not a valid origin URI or contains a query string.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # #[macro_use] extern crate rocket;
+    /// #[get("/")]
+    /// fn admin() -> &'static str { "admin" }
+    ///
+    /// #[get("/")]
+    /// fn maintenance() -> &'static str { "down for maintenance" }
+    ///
+    /// let rocket = rocket::ignite()
+    ///     .mount("/", routes![admin])
+    ///     .mount("/admin", routes![admin])
+    ///     .unmount("/admin")
+    ///     .mount("/admin", routes![maintenance]);
+    ///
+    /// assert_eq!(rocket.routes().count(), 2);
+    /// assert!(rocket.routes().any(|r| r.uri.path() == "/"));
+    /// assert!(rocket.routes().any(|r| r.name == Some("maintenance")));
+    /// ```
+    pub fn unmount(self, base: &str) -> Self {
+        let base_uri = Origin::parse_owned(base.to_string())
+            .unwrap_or_else(|e| {
+                error!("Invalid mount point URI: {}.", Paint::white(base));
+                panic!("Error: {}", e);
+            })
+            .into_normalized();
+
+        if base_uri.query().is_some() {
+            error!("Mount point '{}' contains query string.", base);
+            panic!("Invalid mount point.");
+        }
+
+        info!("{}{} {}{}",
+              Paint::emoji("🛰  "),
+              Paint::magenta("Unmounting"),
+              Paint::blue(&base_uri),
+              Paint::magenta(":"));
+
+        self.remove_routes(|route| route.base.path() == base_uri.path())
+    }
+
+    /// Removes all routes for which `f` returns `true`, regardless of where
+    /// they are mounted.
+    ///
+    /// Routes cannot be removed once the application has launched.
+    ///
+    /// # Example
+    ///
+    /// Remove a route by name:
+    ///
+    /// ```rust
+    /// # #[macro_use] extern crate rocket;
+    /// #[get("/")]
+    /// fn index() -> &'static str { "index" }
+    ///
+    /// #[get("/debug")]
+    /// fn debug() -> &'static str { "debug" }
+    ///
+    /// let rocket = rocket::ignite()
+    ///     .mount("/", routes![index, debug])
+    ///     .remove_routes(|route| route.name == Some("debug"));
+    ///
+    /// assert_eq!(rocket.routes().count(), 1);
+    /// ```
+    pub fn remove_routes<F: FnMut(&Route) -> bool>(mut self, f: F) -> Self {
+        for route in self.router.remove(f) {
+            info_!("{} {}", Paint::red("removed"), route);
+        }
+
+        self
+    }
+
+    /// Registers a `GET` route at `from` that redirects to `to` with the
+    /// redirect status `status`, without the need for a handler function.
+    ///
+    /// The route participates in routing and collision checking as any other
+    /// route mounted at `/` does. `to` may refer to the dynamic path segments
+    /// of `from` by name, as `<name>` or `<name..>`, which are replaced by
+    /// the corresponding segments of the request's path. If `to` doesn't have
+    /// a query string, the request's query string, if any, is appended to it.
+    /// The redirect is otherwise performed as by [`Redirect`], so a relative
+    /// target is subject to the `path_prefix` configuration parameter.
+    ///
+    /// This is useful to keep old URIs working after changing the URI scheme
+    /// of an application, without writing a handler for every redirect.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `from` is not a valid route URI, if `to` refers to a segment
+    /// that is not a dynamic path segment of `from`, or if `status` is not a
+    /// redirection (`3xx`) status.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # #[macro_use] extern crate rocket;
+    /// use rocket::http::Status;
+    /// use rocket::local::blocking::Client;
+    ///
+    /// let rocket = rocket::ignite()
+    ///     .redirect("/old/<id>", "/new/<id>", Status::PermanentRedirect)
+    ///     .redirect("/files/<path..>", "https://cdn.example.com/<path>", Status::Found);
+    ///
+    /// let client = Client::tracked(rocket).unwrap();
+    /// let response = client.get("/old/10?page=2").dispatch();
+    /// assert_eq!(response.status(), Status::PermanentRedirect);
+    /// assert_eq!(response.headers().get_one("Location"), Some("/new/10?page=2"));
+    ///
+    /// let response = client.get("/files/css/site.css").dispatch();
+    /// assert_eq!(response.headers().get_one("Location"),
+    ///     Some("https://cdn.example.com/css/site.css"));
+    /// ```
+    ///
+    /// [`Redirect`]: crate::response::Redirect
+    pub fn redirect(mut self, from: &str, to: &str, status: Status) -> Self {
+        if !status.class().is_redirection() {
+            error!("Redirect from '{}' has a non-redirect status: {}.", from, status);
+            panic!("Invalid redirect status.");
+        }
+
+        let mut route = Route::new(Method::Get, from, handler::dummy);
+        let handler = RedirectHandler::new(status, &route, to)
+            .unwrap_or_else(|e| {
+                error!("Invalid redirect target '{}'.", Paint::white(to));
+                panic!("Error: {}", e);
+            });
+
+        route.handler = Box::new(handler);
+        info!("{}{} {} {} {}", Paint::emoji("🛰  "), Paint::magenta("Redirecting"),
+            Paint::blue(&route.uri), Paint::magenta("=>"), Paint::blue(to));
+
+        self.router.add(route);
+        self
+    }
+
     /// Registers all of the catchers in the supplied vector.
     ///
     /// # Examples
@@ -215,9 +459,16 @@ impl Rocket {
         for catcher in catchers {
             info_!("{}", catcher);
 
-            let existing = match catcher.code {
-                Some(code) => self.catchers.insert(code, catcher),
-                None => self.default_catcher.replace(catcher)
+            let existing = match (catcher.code, catcher.range.clone()) {
+                (Some(code), _) => self.catchers.insert(code, catcher),
+                (None, Some(range)) => {
+                    let ranges = &mut self.range_catchers;
+                    match ranges.iter_mut().find(|c| c.range.as_ref() == Some(&range)) {
+                        Some(existing) => Some(std::mem::replace(existing, catcher)),
+                        None => { ranges.push(catcher); None }
+                    }
+                }
+                (None, None) => self.default_catcher.replace(catcher)
             };
 
             if let Some(existing) = existing {
@@ -273,6 +524,41 @@ impl Rocket {
         self
     }
 
+    /// Add `state` to the state managed by this instance of Rocket, as
+    /// [`manage()`](Rocket::manage()) does, and register it to be terminated
+    /// when the server shuts down. See [`Terminator`] for details.
+    ///
+    /// # Panics
+    ///
+    /// Panics if state of type `T` is already being managed.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// # #[macro_use] extern crate rocket;
+    /// use std::sync::atomic::{AtomicBool, Ordering};
+    /// use rocket::Terminator;
+    ///
+    /// struct Pool(AtomicBool);
+    ///
+    /// #[rocket::async_trait]
+    /// impl Terminator for Pool {
+    ///     async fn terminate(&self) {
+    ///         self.0.store(false, Ordering::SeqCst);
+    ///     }
+    /// }
+    ///
+    /// #[launch]
+    /// fn rocket() -> rocket::Rocket {
+    ///     rocket::ignite().manage_terminable(Pool(AtomicBool::new(true)))
+    /// }
+    /// ```
+    #[inline]
+    pub fn manage_terminable<T: Terminator>(mut self, state: T) -> Self {
+        self.terminators.push(shutdown::terminate::<T>);
+        self.manage(state)
+    }
+
     /// Attaches a fairing to this instance of Rocket. If the fairing is an
     /// _attach_ fairing, it is run immediately. All other kinds of fairings
     /// will be executed at their appropriate time.
@@ -323,6 +609,172 @@ impl Rocket {
         self
     }
 
+    /// Subscribes `f` to [events](crate::events) of type `E`. `f` is called
+    /// with each event of type `E` once the response to the request that
+    /// published the event has been sent.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # #[macro_use] extern crate rocket;
+    /// use rocket::events::Publisher;
+    ///
+    /// struct Deleted(usize);
+    ///
+    /// #[delete("/<id>")]
+    /// fn delete(id: usize, events: Publisher<'_>) {
+    ///     events.publish(Deleted(id));
+    /// }
+    ///
+    /// #[launch]
+    /// fn rocket() -> rocket::Rocket {
+    ///     rocket::ignite()
+    ///         .mount("/", routes![delete])
+    ///         .subscribe(|event: &Deleted| println!("deleted item {}", event.0))
+    /// }
+    /// ```
+    pub fn subscribe<E, F>(mut self, f: F) -> Self
+        where E: Send + Sync + 'static, F: Fn(&E) + Send + Sync + 'static
+    {
+        self.events.subscribe(f);
+        self
+    }
+
+    /// Subscribes the asynchronous function `f` to [events](crate::events) of
+    /// type `E`. The future returned by `f` for each event of type `E` is
+    /// awaited once the response to the request that published the event has
+    /// been sent. Events are delivered one at a time: a subscriber that awaits
+    /// for long delays the delivery of subsequent events of the same request.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # #[macro_use] extern crate rocket;
+    /// use std::sync::Arc;
+    /// use rocket::events::Publisher;
+    ///
+    /// struct Ordered(String);
+    ///
+    /// #[post("/order", data = "<item>")]
+    /// fn order(item: String, events: Publisher<'_>) {
+    ///     events.publish(Ordered(item));
+    /// }
+    ///
+    /// #[launch]
+    /// fn rocket() -> rocket::Rocket {
+    ///     rocket::ignite()
+    ///         .mount("/", routes![order])
+    ///         .subscribe_async(|event: Arc<Ordered>| async move {
+    ///             /* notify the warehouse of `event.0`... */
+    ///         })
+    /// }
+    /// ```
+    pub fn subscribe_async<E, F, Fut>(mut self, f: F) -> Self
+        where E: Send + Sync + 'static,
+              F: Fn(Arc<E>) -> Fut + Send + Sync + 'static,
+              Fut: Future<Output = ()> + Send + 'static
+    {
+        self.events.subscribe_async(f);
+        self
+    }
+
+    /// Runs the pre-launch checks on `self`, moving it into the [`Ignite`]
+    /// phase if they pass. The checks verify that there are no routing
+    /// collisions and that no attach fairing failed.
+    ///
+    /// Once ignited, an instance can no longer be modified but can still be
+    /// inspected and, finally, launched.
+    ///
+    /// # Error
+    ///
+    /// If a check fails, an [`Error`] describing the failure is returned. Note
+    /// that a value of type `Error` panics if dropped without first being
+    /// inspected.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # #[macro_use] extern crate rocket;
+    /// #[get("/")]
+    /// fn index() -> &'static str { "Hello!" }
+    ///
+    /// # rocket::async_test(async {
+    /// let rocket = rocket::ignite().mount("/", routes![index]);
+    /// let rocket = rocket.ignited().await.expect("no collisions");
+    /// assert_eq!(rocket.routes().count(), 1);
+    /// # });
+    /// ```
+    pub async fn ignited(self) -> Result<Rocket<Ignite>, Error> {
+        if let Err(e) = self.router.collisions() {
+            return Err(Error::new(ErrorKind::Collision(e)));
+        }
+
+        if let Some(failures) = self.fairings.failures() {
+            return Err(Error::new(ErrorKind::FailedFairings(failures.to_vec())))
+        }
+
+        Ok(self.into_phase())
+    }
+
+    /// Ignites `self` via [`Rocket::ignited()`], then launches the ignited
+    /// instance. See `launch()` on `Rocket<Ignite>` for details.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// #[rocket::main]
+    /// async fn main() {
+    /// # if false {
+    ///     let result = rocket::ignite().launch().await;
+    ///     assert!(result.is_ok());
+    /// # }
+    /// }
+    /// ```
+    pub async fn launch(self) -> Result<(), Error> {
+        self.ignited().await?.launch().await
+    }
+
+    /// Ignites `self` via [`Rocket::ignited()`], then launches the ignited
+    /// instance on `listener`. See `launch_on()` on `Rocket<Ignite>` for
+    /// details.
+    pub async fn launch_on<L>(self, listener: L) -> Result<(), Error>
+        where L: Listener + Send + Unpin + 'static,
+              <L as Listener>::Connection: Send + Unpin + 'static,
+    {
+        self.ignited().await?.launch_on(listener).await
+    }
+
+    // Skips the pre-launch checks. Only for use by tests and doc-test helpers.
+    pub(crate) fn _orbit(self) -> Rocket<Orbit> {
+        self.into_phase::<Ignite>().into_orbit()
+    }
+}
+
+impl<P: Phase> Rocket<P> {
+    // Moves `self` into the phase `Q`, leaving everything else untouched.
+    fn into_phase<Q: Phase>(self) -> Rocket<Q> {
+        Rocket {
+            config: self.config,
+            figment: self.figment,
+            managed_state: self.managed_state,
+            scoped_state: self.scoped_state,
+            router: self.router,
+            default_catcher: self.default_catcher,
+            catchers: self.catchers,
+            range_catchers: self.range_catchers,
+            fairings: self.fairings,
+            shutdown_receiver: self.shutdown_receiver,
+            shutdown_handle: self.shutdown_handle,
+            terminators: self.terminators,
+            events: self.events,
+            #[cfg(unix)]
+            handover: self.handover,
+            #[cfg(feature = "tls")]
+            tls_handle: self.tls_handle,
+            phase: PhantomData,
+        }
+    }
+
     /// Returns the active configuration.
     ///
     /// # Example
@@ -397,6 +849,29 @@ impl Rocket {
         self.router.routes()
     }
 
+    /// Returns a serializable snapshot of the routes mounted on this instance
+    /// of Rocket. See [`RouteTable`] for details.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # #[macro_use] extern crate rocket;
+    /// #[get("/hello")]
+    /// fn hello() -> &'static str {
+    ///     "Hello, world!"
+    /// }
+    ///
+    /// let rocket = rocket::ignite().mount("/hi", routes![hello]);
+    /// let table = rocket.route_table();
+    /// assert_eq!(table.routes.len(), 1);
+    /// assert_eq!(table.routes[0].path, "/hi/hello");
+    /// assert_eq!(table.routes[0].method, "GET");
+    /// assert_eq!(table.routes[0].name.as_deref(), Some("hello"));
+    /// ```
+    pub fn route_table(&self) -> RouteTable {
+        self.router.export()
+    }
+
     /// Returns an iterator over all of the catchers registered on this instance
     /// of Rocket. The order is unspecified.
     ///
@@ -409,21 +884,24 @@ impl Rocket {
     ///
     /// #[catch(404)] fn not_found() -> &'static str { "Nothing here, sorry!" }
     /// #[catch(500)] fn just_500() -> &'static str { "Whoops!?" }
+    /// #[catch(400..500)] fn client_error() -> &'static str { "Try again." }
     /// #[catch(default)] fn some_default() -> &'static str { "Everything else." }
     ///
     /// fn main() {
     ///     let mut rocket = rocket::ignite()
-    ///         .register(catchers![not_found, just_500, some_default]);
+    ///         .register(catchers![not_found, just_500, client_error, some_default]);
     ///
     ///     let mut codes: Vec<_> = rocket.catchers().map(|c| c.code).collect();
     ///     codes.sort();
     ///
-    ///     assert_eq!(codes, vec![None, Some(404), Some(500)]);
+    ///     assert_eq!(codes, vec![None, None, Some(404), Some(500)]);
     /// }
     /// ```
     #[inline(always)]
     pub fn catchers(&self) -> impl Iterator<Item = &Catcher> + '_ {
-        self.catchers.values().chain(self.default_catcher.as_ref())
+        self.catchers.values()
+            .chain(self.range_catchers.iter())
+            .chain(self.default_catcher.as_ref())
     }
 
     /// Returns `Some` of the managed state value for the type `T` if it is
@@ -468,26 +946,17 @@ impl Rocket {
     pub fn shutdown(&self) -> Shutdown {
         self.shutdown_handle.clone()
     }
+}
 
-    /// Perform "pre-launch" checks: verify that there are no routing colisions
-    /// and that there were no fairing failures.
-    pub(crate) async fn prelaunch_check(&mut self) -> Result<(), Error> {
-        if let Err(e) = self.router.collisions() {
-            return Err(Error::new(ErrorKind::Collision(e)));
-        }
-
-        if let Some(failures) = self.fairings.failures() {
-            return Err(Error::new(ErrorKind::FailedFairings(failures.to_vec())))
-        }
-
-        Ok(())
-    }
-
+impl Rocket<Ignite> {
     /// Returns a `Future` that drives the server, listening for and dispatching
     /// requests to mounted routes and catchers. The `Future` completes when the
     /// server is shut down via [`Shutdown`], encounters a fatal error, or if
     /// the the `ctrlc` configuration option is set, when `Ctrl+C` is pressed.
     ///
+    /// Once the server is listening, the instance moves into the [`Orbit`]
+    /// phase and launch fairings are run.
+    ///
     /// # Error
     ///
     /// If there is a problem starting the application, an [`Error`] is
@@ -501,51 +970,110 @@ impl Rocket {
     /// #[rocket::main]
     /// async fn main() {
     /// # if false {
-    ///     let result = rocket::ignite().launch().await;
+    ///     let rocket = rocket::ignite().ignited().await.expect("valid rocket");
+    ///     let result = rocket.launch().await;
     ///     assert!(result.is_ok());
     /// # }
     /// }
     /// ```
     pub async fn launch(mut self) -> Result<(), Error> {
         use std::net::ToSocketAddrs;
-        use futures::future::Either;
-        use crate::http::private::bind_tcp;
-
-        self.prelaunch_check().await?;
+        use crate::http::private::{bind_tcp, ProxyProtoListener};
 
         let full_addr = format!("{}:{}", self.config.address, self.config.port);
         let addr = full_addr.to_socket_addrs()
             .map(|mut addrs| addrs.next().expect(">= 1 socket addr"))
             .map_err(|e| Error::new(ErrorKind::Io(e)))?;
 
-        // If `ctrl-c` shutdown is enabled, we `select` on `the ctrl-c` signal
-        // and server. Otherwise, we only wait on the `server`, hence `pending`.
-        let shutdown_handle = self.shutdown_handle.clone();
-        let shutdown_signal = match self.config.ctrlc {
-            true => tokio::signal::ctrl_c().boxed(),
-            false => futures::future::pending().boxed(),
+        // If a previous process handed over its socket, use it instead.
+        #[cfg(unix)]
+        let listener = match crate::handover::inherited_listener() {
+            Some(std_listener) => {
+                use crate::http::private::from_std_tcp;
+
+                info!("{}Using listening socket from previous process.", Paint::emoji("🤝 "));
+                from_std_tcp(std_listener).map_err(ErrorKind::Bind)?
+            }
+            None => bind_tcp(addr).await.map_err(ErrorKind::Bind)?,
         };
 
+        #[cfg(not(unix))]
+        let listener = bind_tcp(addr).await.map_err(ErrorKind::Bind)?;
+
+        #[cfg(unix)] {
+            use std::os::unix::io::AsRawFd;
+            let handover = crate::Handover::new(listener.as_raw_fd(), self.shutdown());
+            self.handover = Some(handover);
+        }
+
+        // The PROXY protocol header precedes everything else, including TLS.
+        let listener = ProxyProtoListener::new(listener, self.config.proxy_protocol);
+
         #[cfg(feature = "tls")]
         let server = {
-            use crate::http::tls::bind_tls;
+            use crate::http::tls::tls_from_listener;
 
             if let Some(tls_config) = &self.config.tls {
                 let (certs, key) = tls_config.to_readers().map_err(ErrorKind::Io)?;
-                let l = bind_tls(addr, certs, key).await.map_err(ErrorKind::Bind)?;
-                self.listen_on(l).boxed()
+                let client_auth = tls_config.to_client_auth().map_err(ErrorKind::Io)?;
+                let l = tls_from_listener(listener, certs, key, client_auth)
+                    .map_err(ErrorKind::Bind)?;
+
+                let handle = crate::TlsHandle::new(l.resolver().clone(), tls_config.clone());
+                handle.spawn_reloaders();
+                self.tls_handle = Some(handle);
+                self.launch_on(l).boxed()
             } else {
-                let l = bind_tcp(addr).await.map_err(ErrorKind::Bind)?;
-                self.listen_on(l).boxed()
+                self.launch_on(listener).boxed()
             }
         };
 
         #[cfg(not(feature = "tls"))]
-        let server = {
-            let l = bind_tcp(addr).await.map_err(ErrorKind::Bind)?;
-            self.listen_on(l).boxed()
+        let server = self.launch_on(listener).boxed();
+
+        server.await
+    }
+
+    /// Like [`launch()`](#method.launch), but serves connections accepted by
+    /// `listener` instead of binding to the configured address and port.
+    /// Rocket doesn't apply the `tls` configuration to these connections:
+    /// securing them, if desired, is up to `listener`. The port reported at
+    /// launch is that of `listener`'s local address, or `0` if it has none.
+    ///
+    /// See [`Listener`] for details on implementing a custom listener.
+    ///
+    /// # Example
+    ///
+    /// Serving over a socket bound before igniting Rocket:
+    ///
+    /// ```rust
+    /// use rocket::tokio::net::TcpListener;
+    ///
+    /// #[rocket::main]
+    /// async fn main() {
+    /// # if false {
+    ///     let listener = TcpListener::bind("127.0.0.1:8000").await.expect("bound socket");
+    ///     let rocket = rocket::ignite().ignited().await.expect("valid rocket");
+    ///     let result = rocket.launch_on(listener).await;
+    ///     assert!(result.is_ok());
+    /// # }
+    /// }
+    /// ```
+    pub async fn launch_on<L>(self, listener: L) -> Result<(), Error>
+        where L: Listener + Send + Unpin + 'static,
+              <L as Listener>::Connection: Send + Unpin + 'static,
+    {
+        use futures::future::Either;
+
+        // If `ctrl-c` shutdown is enabled, we `select` on `the ctrl-c` signal
+        // and server. Otherwise, we only wait on the `server`, hence `pending`.
+        let shutdown_handle = self.shutdown_handle.clone();
+        let shutdown_signal = match self.config.ctrlc {
+            true => tokio::signal::ctrl_c().boxed(),
+            false => futures::future::pending().boxed(),
         };
 
+        let server = self.listen_on(listener).boxed();
         match futures::future::select(shutdown_signal, server).await {
             Either::Left((Ok(()), server)) => {
                 // Ctrl-was pressed. Signal shutdown, wait for the server.
@@ -562,4 +1090,32 @@ impl Rocket {
             Either::Right((result, _)) => result,
         }
     }
+
+    // Moves `self` into the `Orbit` phase. The server does this once it's
+    // listening; local clients do so immediately as they never listen.
+    pub(crate) fn into_orbit(self) -> Rocket<Orbit> {
+        self.into_phase()
+    }
+}
+
+impl Rocket<Orbit> {
+    /// Returns a handle to the certificate served by this instance of Rocket
+    /// if it has been launched with TLS enabled. Otherwise, returns `None`.
+    ///
+    /// See [`TlsHandle`](crate::TlsHandle) for details.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::fairing::AdHoc;
+    ///
+    /// let fairing = AdHoc::on_launch("TLS Check", |rocket| {
+    ///     assert!(rocket.tls_handle().is_none());
+    /// });
+    /// ```
+    #[cfg(feature = "tls")]
+    #[inline(always)]
+    pub fn tls_handle(&self) -> Option<&crate::TlsHandle> {
+        self.tls_handle.as_ref()
+    }
 }
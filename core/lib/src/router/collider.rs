@@ -1,8 +1,10 @@
 use super::Route;
 
 use crate::http::MediaType;
-use crate::http::route::Kind;
-use crate::request::Request;
+use crate::http::RawStr;
+use crate::http::route::{Kind, RouteSegment};
+use crate::http::uri::Path;
+use crate::request::{Request, Rejection};
 
 impl Route {
     /// Determines if two routes can match against some request. That is, if two
@@ -15,8 +17,13 @@ impl Route {
     ///   * If route specifies a format, it only gets requests for that format.
     ///   * If route doesn't specify a format, it gets requests for any format.
     ///
+    /// Path parameter constraints, such as `<id:int>`, are taken into account:
+    /// a constrained parameter only collides with static segments and other
+    /// parameters it has some value in common with.
+    ///
     /// Because query parsing is lenient, and dynamic query parameters can be
-    /// missing, queries do not impact whether two routes collide.
+    /// missing, queries do not impact whether two routes collide. Neither do
+    /// matchers, which are arbitrary predicates.
     #[doc(hidden)]
     pub fn collides_with(&self, other: &Route) -> bool {
         self.method == other.method
@@ -37,12 +44,39 @@ impl Route {
     ///   * All static components in the route's query string are also in the
     ///     request query string, though in any position.
     ///     - If no query in route, requests with/without queries match.
+    ///   * The route's matcher (if any) returns `true` for the request.
     #[doc(hidden)]
     pub fn matches(&self, req: &Request<'_>) -> bool {
         self.method == req.method()
             && paths_match(self, req)
             && queries_match(self, req)
             && formats_match(self, req)
+            && matchers_match(self, req)
+    }
+
+    /// Returns the reason this route doesn't match against the given request,
+    /// if any. The conditions are checked in the order listed in
+    /// [`Route::matches()`], and the first to fail is returned.
+    pub(crate) fn rejection(&self, req: &Request<'_>) -> Option<Rejection> {
+        if self.method != req.method() {
+            Some(Rejection::Method)
+        } else if !paths_match(self, req) {
+            Some(Rejection::Path)
+        } else if !queries_match(self, req) {
+            Some(Rejection::Query)
+        } else if !formats_match(self, req) {
+            Some(Rejection::Format)
+        } else if !matchers_match(self, req) {
+            Some(Rejection::Matcher)
+        } else {
+            None
+        }
+    }
+
+    /// Determines if this route's path and query match against the given
+    /// request, irrespective of the request's method and format.
+    pub(crate) fn matches_path(&self, req: &Request<'_>) -> bool {
+        paths_match(self, req) && queries_match(self, req)
     }
 }
 
@@ -54,16 +88,24 @@ fn paths_collide(route: &Route, other: &Route) -> bool {
             return true;
         }
 
-        if seg_a.kind == Kind::Static && seg_b.kind == Kind::Static {
-            if seg_a.string != seg_b.string {
-                return false;
-            }
+        if !segments_collide(seg_a, seg_b) {
+            return false;
         }
     }
 
     a_segments.len() == b_segments.len()
 }
 
+fn segments_collide(a: &RouteSegment<'_, Path>, b: &RouteSegment<'_, Path>) -> bool {
+    match (a.kind, &a.constraint, b.kind, &b.constraint) {
+        (Kind::Static, _, Kind::Static, _) => a.string == b.string,
+        (Kind::Static, _, _, Some(c)) => c.matches_raw(RawStr::from_str(&a.string)),
+        (_, Some(c), Kind::Static, _) => c.matches_raw(RawStr::from_str(&b.string)),
+        (_, Some(c), _, Some(d)) => c.overlaps(d),
+        _ => true,
+    }
+}
+
 fn paths_match(route: &Route, request: &Request<'_>) -> bool {
     let route_segments = &route.metadata.path_segments;
     if route_segments.len() > request.state.path_segments.len() {
@@ -75,6 +117,10 @@ fn paths_match(route: &Route, request: &Request<'_>) -> bool {
         match route_seg.kind {
             Kind::Multi => return true,
             Kind::Static if &*route_seg.string != req_seg.as_str() => return false,
+            Kind::Single => match route_seg.constraint {
+                Some(ref c) if !c.matches_raw(req_seg) => return false,
+                _ => continue,
+            },
             _ => continue,
         }
     }
@@ -143,6 +189,10 @@ fn formats_match(route: &Route, request: &Request<'_>) -> bool {
     }
 }
 
+fn matchers_match(route: &Route, request: &Request<'_>) -> bool {
+    route.matcher.as_ref().map_or(true, |matcher| matcher(request))
+}
+
 fn media_types_collide(first: &MediaType, other: &MediaType) -> bool {
     let collide = |a, b| a == "*" || b == "*" || a == b;
     collide(first.top(), other.top()) && collide(first.sub(), other.sub())
@@ -258,6 +308,31 @@ mod tests {
         assert!(!unranked_collide("/", "/a"));
     }
 
+    #[test]
+    fn constrained_param_collisions() {
+        assert!(unranked_collide("/users/<id:int>", "/users/12"));
+        assert!(unranked_collide("/users/<id:int>", "/users/-12"));
+        assert!(unranked_collide("/users/<id:int>", "/users/<name>"));
+        assert!(unranked_collide("/users/<id:int>", "/users/<n:uint>"));
+        assert!(unranked_collide("/<a:int>", "/<b:hex>"));
+        assert!(unranked_collide("/<a:[a-z-]+>", "/<b:alpha>"));
+        assert!(unranked_collide("/<a:[a-z]{2,4}>", "/<b:[c-e]{4}>"));
+        assert!(unranked_collide("/<a:x[0-9]*>", "/<b:[a-z]>"));
+        assert!(unranked_collide("/<a:uuid>", "/67e55044-10b1-426f-9247-bb680e5fe0c8"));
+    }
+
+    #[test]
+    fn constrained_param_non_collisions() {
+        assert!(!unranked_collide("/users/<id:int>", "/users/new"));
+        assert!(!unranked_collide("/users/<id:int>", "/users/1a"));
+        assert!(!unranked_collide("/<a:int>", "/<b:alpha>"));
+        assert!(!unranked_collide("/<a:uint>", "/<b:-[0-9]+>"));
+        assert!(!unranked_collide("/<a:[a-z]{2}>", "/<b:[a-z]{3,}>"));
+        assert!(!unranked_collide("/<a:x[0-9]*>", "/<b:y>"));
+        assert!(!unranked_collide("/<a:uuid>", "/67e55044"));
+        assert!(!unranked_collide("/<a:[a-z]+>/<b..>", "/<c:[0-9]+>/<d..>"));
+    }
+
     #[test]
     fn query_non_collisions() {
         assert!(!unranked_collide("/a?<b>", "/b"));
@@ -401,7 +476,7 @@ mod tests {
     fn req_route_mt_collide<S1, S2>(m: Method, mt1: S1, mt2: S2) -> bool
         where S1: Into<Option<&'static str>>, S2: Into<Option<&'static str>>
     {
-        let rocket = Rocket::custom(Config::default());
+        let rocket = Rocket::custom(Config::default())._orbit();
         let mut req = Request::new(&rocket, m, Origin::dummy());
         if let Some(mt_str) = mt1.into() {
             if m.supports_payload() {
@@ -468,12 +543,27 @@ mod tests {
     }
 
     fn req_route_path_match(a: &'static str, b: &'static str) -> bool {
-        let rocket = Rocket::custom(Config::default());
+        let rocket = Rocket::custom(Config::default())._orbit();
         let req = Request::new(&rocket, Get, Origin::parse(a).expect("valid URI"));
         let route = Route::ranked(0, Get, b.to_string(), dummy);
         route.matches(&req)
     }
 
+    #[test]
+    fn test_req_route_constrained_path_matches() {
+        assert!(req_route_path_match("/users/12", "/users/<id:int>"));
+        assert!(req_route_path_match("/users/-12", "/users/<id:int>"));
+        assert!(req_route_path_match("/users/12?a=b", "/users/<id:int>"));
+        assert!(req_route_path_match("/hello-world/x", "/<slug:[a-z-]+>/<rest..>"));
+        assert!(req_route_path_match("/%31%32", "/<id:uint>"));
+
+        assert!(!req_route_path_match("/users/new", "/users/<id:int>"));
+        assert!(!req_route_path_match("/users/12a", "/users/<id:int>"));
+        assert!(!req_route_path_match("/users/-", "/users/<id:int>"));
+        assert!(!req_route_path_match("/Hello/x", "/<slug:[a-z-]+>/<rest..>"));
+        assert!(!req_route_path_match("/%FF", "/<id:[a-z]+>"));
+    }
+
     #[test]
     fn test_req_route_query_collisions() {
         assert!(req_route_path_match("/a/b?a=b", "/a/b?<c>"));
@@ -1,13 +1,15 @@
 mod collider;
 mod route;
+mod table;
 
 use std::collections::HashMap;
 
-use crate::request::Request;
+use crate::request::{Request, RouteCheck, describe_route};
 use crate::http::Method;
 use crate::handler::dummy;
 
-pub use self::route::Route;
+pub use self::route::{Route, RouteBuilder, RouteStats, RouteMatcher};
+pub use self::table::{RouteTable, RouteEntry, RouteChange};
 
 // type Selector = (Method, usize);
 type Selector = Method;
@@ -22,7 +24,9 @@ impl Router {
         Router { routes: HashMap::new() }
     }
 
-    pub fn add(&mut self, route: Route) {
+    pub fn add(&mut self, mut route: Route) {
+        // Clones of a route share counters; each added route counts its own.
+        route.counters = Default::default();
         let selector = route.method;
         let entries = self.routes.entry(selector).or_insert_with(|| vec![]);
         let i = entries.binary_search_by_key(&route.rank, |r| r.rank)
@@ -31,6 +35,23 @@ impl Router {
         entries.insert(i, route);
     }
 
+    /// Removes and returns all routes for which `f` returns `true`. The relative
+    /// order of the remaining routes is preserved.
+    pub fn remove<F: FnMut(&Route) -> bool>(&mut self, mut f: F) -> Vec<Route> {
+        let mut removed = vec![];
+        for routes in self.routes.values_mut() {
+            let (gone, kept): (Vec<_>, Vec<_>) = std::mem::take(routes)
+                .into_iter()
+                .partition(|r| f(r));
+
+            *routes = kept;
+            removed.extend(gone);
+        }
+
+        self.routes.retain(|_, routes| !routes.is_empty());
+        removed
+    }
+
     pub fn route<'b>(&'b self, req: &Request<'_>) -> Vec<&'b Route> {
         // Note that routes are presorted by rank on each `add`.
         let matches = self.routes.get(&req.method()).map_or(vec![], |routes| {
@@ -39,11 +60,55 @@ impl Router {
                 .collect()
         });
 
-        trace_!("Routing the request: {}", req);
-        trace_!("All matches: {:?}", matches);
+        dispatch_log!(trace_: "Routing the request: {}", req);
+        dispatch_log!(trace_: "All matches: {:?}", matches);
         matches
     }
 
+    /// Returns the methods, in a fixed order, of the routes whose path and
+    /// query match `req`, irrespective of the request's method and format.
+    /// Extension methods follow the standard methods, ordered by name.
+    pub fn allowed_methods(&self, req: &Request<'_>) -> Vec<Method> {
+        use crate::http::Method::*;
+
+        let has_route = |method: &Method| self.routes.get(method)
+            .map_or(false, |routes| routes.iter().any(|r| r.matches_path(req)));
+
+        let mut custom: Vec<_> = self.routes.keys()
+            .filter(|method| matches!(method, Custom(_)) && has_route(*method))
+            .cloned()
+            .collect();
+
+        custom.sort_by_key(|method| method.as_str());
+        [Get, Head, Post, Put, Patch, Delete, Options, Trace, Connect].iter()
+            .filter(|method| has_route(*method))
+            .cloned()
+            .chain(custom)
+            .collect()
+    }
+
+    /// Returns the extension method named `name` if any route uses it.
+    pub fn custom_method(&self, name: &str) -> Option<Method> {
+        self.routes.keys()
+            .find(|method| matches!(method, Method::Custom(n) if *n == name))
+            .cloned()
+    }
+
+    /// Checks every route against `req`. The routes that match are first, in
+    /// the order they would be tried, followed by the routes that don't.
+    pub(crate) fn explain(&self, req: &Request<'_>) -> Vec<RouteCheck> {
+        let mut checks = self.routes()
+            .map(|route| RouteCheck {
+                route: describe_route(route),
+                rank: route.rank,
+                rejection: route.rejection(req),
+            })
+            .collect::<Vec<_>>();
+
+        checks.sort_by_key(|check| (check.rejection.is_some(), check.rank));
+        checks
+    }
+
     pub(crate) fn collisions(&mut self) -> Result<(), Vec<(Route, Route)>> {
         let mut collisions = vec![];
         for routes in self.routes.values_mut() {
@@ -75,6 +140,11 @@ impl Router {
         self.routes.values().flat_map(|v| v.iter())
     }
 
+    /// Returns a snapshot of the routes in `self`.
+    pub fn export(&self) -> RouteTable {
+        RouteTable::new(self.routes())
+    }
+
     // This is slow. Don't expose this publicly; only for tests.
     #[cfg(test)]
     fn has_collisions(&self) -> bool {
@@ -243,7 +313,7 @@ mod test {
     }
 
     fn route<'a>(router: &'a Router, method: Method, uri: &str) -> Option<&'a Route> {
-        let rocket = Rocket::custom(Config::default());
+        let rocket = Rocket::custom(Config::default())._orbit();
         let request = Request::new(&rocket, method, Origin::parse(uri).unwrap());
         let matches = router.route(&request);
         if matches.len() > 0 {
@@ -254,7 +324,7 @@ mod test {
     }
 
     fn matches<'a>(router: &'a Router, method: Method, uri: &str) -> Vec<&'a Route> {
-        let rocket = Rocket::custom(Config::default());
+        let rocket = Rocket::custom(Config::default())._orbit();
         let request = Request::new(&rocket, method, Origin::parse(uri).unwrap());
         router.route(&request)
     }
@@ -488,4 +558,44 @@ mod test {
             expect: "/a/b?c", "/a/b?<c>", "/a/b", "/a/<b>?c", "/a/<b>?<c>", "/<a>/<b>"
         );
     }
+
+    #[test]
+    fn test_remove() {
+        let mut router = router_with_routes(&["/a", "/b/<c>", "/a/<b>"]);
+        router.add(Route::new(Post, "/a", dummy));
+
+        let removed = router.remove(|r| r.uri.path() == "/a");
+        assert_eq!(removed.len(), 2);
+        assert_eq!(router.routes().count(), 2);
+
+        let req_uri = Origin::parse("/b/c").unwrap();
+        let rocket = Rocket::custom(Config::default())._orbit();
+        let req = Request::new(&rocket, Get, req_uri);
+        assert_eq!(router.route(&req).len(), 1);
+
+        let removed = router.remove(|_| true);
+        assert_eq!(removed.len(), 2);
+        assert_eq!(router.routes().count(), 0);
+    }
+
+    #[test]
+    fn test_export_and_diff() {
+        use super::RouteChange::*;
+
+        let blue = router_with_ranked_routes(&[(1, "/a"), (2, "/a"), (0, "/b")]).export();
+        let paths: Vec<_> = blue.routes.iter().map(|r| (&*r.path, r.rank)).collect();
+        assert_eq!(paths, vec![("/a", 1), ("/a", 2), ("/b", 0)]);
+
+        let same = router_with_ranked_routes(&[(0, "/b"), (2, "/a"), (1, "/a")]).export();
+        assert_eq!(blue, same);
+        assert!(blue.diff(&same).is_empty());
+
+        let green = router_with_ranked_routes(&[(3, "/a"), (0, "/c")]).export();
+        let changes = blue.diff(&green);
+        assert_eq!(changes.len(), 4);
+        assert!(matches!(&changes[0], Changed { from, to } if from.rank == 1 && to.rank == 3));
+        assert!(matches!(&changes[1], Removed(r) if r.path == "/a" && r.rank == 2));
+        assert!(matches!(&changes[2], Removed(r) if r.path == "/b"));
+        assert!(matches!(&changes[3], Added(r) if r.path == "/c"));
+    }
 }
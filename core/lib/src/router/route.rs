@@ -1,16 +1,27 @@
 use std::fmt::{self, Display};
 use std::convert::From;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
 
 use yansi::Paint;
 
 use crate::codegen::StaticRouteInfo;
 use crate::handler::Handler;
+use crate::request::Request;
 use crate::http::{Method, MediaType};
 use crate::http::route::{RouteSegment, Kind};
 use crate::error::RouteUriError;
 use crate::http::ext::IntoOwned;
 use crate::http::uri::{Origin, Path, Query};
 
+/// An additional condition a request must satisfy for a route to match it.
+///
+/// A route with a matcher only matches requests for which the matcher returns
+/// `true`. The matcher is consulted after the route's method, path, query, and
+/// format match the request. Set via [`Route::with_matcher()`],
+/// [`RouteBuilder::matcher()`], or the `matcher` route attribute parameter.
+pub type RouteMatcher = Arc<dyn Fn(&Request<'_>) -> bool + Send + Sync>;
+
 /// A route: a method, its handler, path, rank, and format/media type.
 #[derive(Clone)]
 pub struct Route {
@@ -31,8 +42,39 @@ pub struct Route {
     pub rank: isize,
     /// The media type this route matches against, if any.
     pub format: Option<MediaType>,
+    /// The predicate this route's requests must satisfy, if any.
+    pub matcher: Option<RouteMatcher>,
     /// Cached metadata that aids in routing later.
     pub(crate) metadata: Metadata,
+    /// How often this route was tried and how often it handled a request.
+    pub(crate) counters: Arc<Counters>,
+}
+
+/// How often a [`Route`] was tried and how often it handled a request, as
+/// returned by [`Route::stats()`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct RouteStats {
+    /// The number of requests the route's handler was invoked for.
+    pub attempts: usize,
+    /// The number of requests the route's handler succeeded or failed, rather
+    /// than forwarded, for.
+    pub wins: usize,
+}
+
+#[derive(Debug, Default)]
+pub(crate) struct Counters {
+    attempts: AtomicUsize,
+    wins: AtomicUsize,
+}
+
+impl Counters {
+    pub(crate) fn attempted(&self) {
+        self.attempts.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub(crate) fn won(&self) {
+        self.wins.fetch_add(1, Ordering::Relaxed);
+    }
 }
 
 #[derive(Debug, Default, Clone)]
@@ -180,9 +222,11 @@ impl Route {
             uri: route_path,
             name: None,
             format: None,
+            matcher: None,
             base: Origin::dummy(),
             handler: Box::new(handler),
             metadata: Metadata::default(),
+            counters: Arc::default(),
             method, rank,
         };
 
@@ -190,6 +234,45 @@ impl Route {
         route
     }
 
+    /// Returns a builder for a route with the given method, path, and handler
+    /// with a base of `/`. The builder can set the route's rank, format, and
+    /// name, everything that can be set via route attributes, and is thus
+    /// useful when routes are constructed without code generation.
+    ///
+    /// Unless a rank is set via [`RouteBuilder::rank()`], the route's rank is
+    /// the default rank described in [`Route::new()`].
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::Route;
+    /// use rocket::http::{Method, MediaType};
+    /// # use rocket::handler::dummy as handler;
+    ///
+    /// let route = Route::builder(Method::Post, "/users", handler)
+    ///     .format(MediaType::JSON)
+    ///     .rank(3)
+    ///     .name("create_user")
+    ///     .build();
+    ///
+    /// assert_eq!(route.rank, 3);
+    /// assert_eq!(route.format, Some(MediaType::JSON));
+    /// assert_eq!(route.name, Some("create_user"));
+    ///
+    /// // this is a rank -4 route (static path, no query)
+    /// let route = Route::builder(Method::Get, "/", handler).build();
+    /// assert_eq!(route.rank, -4);
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Panics if `path` is not a valid origin URI or Rocket route URI.
+    pub fn builder<S, H>(method: Method, path: S, handler: H) -> RouteBuilder
+        where S: AsRef<str>, H: Handler
+    {
+        RouteBuilder { route: Route::ranked(0, method, path, handler), rank: None }
+    }
+
     /// Updates the cached routing metadata. MUST be called whenver the route's
     /// URI is set or changes.
     fn update_metadata(&mut self) -> Result<(), RouteUriError> {
@@ -238,6 +321,52 @@ impl Route {
         &self.path
     }
 
+    /// Sets the matcher of `self` to `matcher`, so that `self` only matches
+    /// requests for which `matcher` returns `true`.
+    ///
+    /// Matchers don't affect whether routes collide: routes that differ only
+    /// in their matchers must be given different ranks.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::Route;
+    /// use rocket::http::Method;
+    /// # use rocket::handler::dummy as handler;
+    ///
+    /// let route = Route::ranked(1, Method::Get, "/", handler)
+    ///     .with_matcher(|req| req.headers().contains("X-Api-Key"));
+    ///
+    /// assert!(route.matcher.is_some());
+    /// ```
+    pub fn with_matcher<F>(mut self, matcher: F) -> Self
+        where F: Fn(&Request<'_>) -> bool + Send + Sync + 'static
+    {
+        self.matcher = Some(Arc::new(matcher));
+        self
+    }
+
+    /// Returns how often this route has been tried against a request and how
+    /// often it handled the request, rather than forwarding it. Routes are
+    /// only counted when the `trace` configuration parameter is enabled.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::{Route, RouteStats};
+    /// use rocket::http::Method;
+    /// # use rocket::handler::dummy as handler;
+    ///
+    /// let route = Route::new(Method::Get, "/", handler);
+    /// assert_eq!(route.stats(), RouteStats { attempts: 0, wins: 0 });
+    /// ```
+    pub fn stats(&self) -> RouteStats {
+        RouteStats {
+            attempts: self.counters.attempts.load(Ordering::Relaxed),
+            wins: self.counters.wins.load(Ordering::Relaxed),
+        }
+    }
+
     /// Maps the `base` of this route using `mapper`, returning a new `Route`
     /// with the returned base.
     ///
@@ -276,6 +405,49 @@ impl Route {
     }
 }
 
+/// A builder for a [`Route`], returned by [`Route::builder()`].
+#[derive(Clone)]
+pub struct RouteBuilder {
+    route: Route,
+    rank: Option<isize>,
+}
+
+impl RouteBuilder {
+    /// Sets the rank of the route to `rank`.
+    pub fn rank(mut self, rank: isize) -> Self {
+        self.rank = Some(rank);
+        self
+    }
+
+    /// Sets the media type the route matches against to `format`.
+    pub fn format(mut self, format: MediaType) -> Self {
+        self.route.format = Some(format);
+        self
+    }
+
+    /// Sets the matcher of the route to `matcher`, as
+    /// [`Route::with_matcher()`] does.
+    pub fn matcher<F>(mut self, matcher: F) -> Self
+        where F: Fn(&Request<'_>) -> bool + Send + Sync + 'static
+    {
+        self.route = self.route.with_matcher(matcher);
+        self
+    }
+
+    /// Sets the name of the route to `name`.
+    pub fn name(mut self, name: &'static str) -> Self {
+        self.route.name = Some(name);
+        self
+    }
+
+    /// Returns the built route.
+    pub fn build(self) -> Route {
+        let mut route = self.route;
+        route.rank = self.rank.unwrap_or_else(|| default_rank(&route));
+        route
+    }
+}
+
 impl fmt::Display for Route {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         write!(f, "{} ", Paint::green(&self.method))?;
@@ -311,6 +483,7 @@ impl fmt::Debug for Route {
             .field("uri", &self.uri)
             .field("rank", &self.rank)
             .field("format", &self.format)
+            .field("matcher", &self.matcher.is_some())
             .field("metadata", &self.metadata)
             .finish()
     }
@@ -323,6 +496,7 @@ impl From<StaticRouteInfo> for Route {
         let mut route = Route::new(info.method, info.path, info.handler);
         route.format = info.format;
         route.name = Some(info.name);
+        route.matcher = info.matcher.map(|matcher| Arc::new(matcher) as RouteMatcher);
         if let Some(rank) = info.rank {
             route.rank = rank;
         }
@@ -0,0 +1,160 @@
+use std::collections::BTreeMap;
+
+use serde::{Serialize, Deserialize};
+
+use crate::router::Route;
+
+/// A serializable snapshot of the routes mounted on an instance of Rocket.
+///
+/// A `RouteTable` is retrieved via [`Rocket::route_table()`]. It records each
+/// route's method, complete path, rank, format, and name, sorted so that two
+/// snapshots of the same routes are equal no matter the order in which the
+/// routes were mounted. Snapshots can be serialized with any `serde` format,
+/// stored, and compared with [`RouteTable::diff()`]: deployment tooling can
+/// use this to verify that the route table of a new build changed only as
+/// expected before switching traffic to it.
+///
+/// [`Rocket::route_table()`]: crate::Rocket::route_table()
+///
+/// # Example
+///
+/// ```rust
+/// # #[macro_use] extern crate rocket;
+/// use rocket::RouteChange;
+///
+/// #[get("/")] fn index() { }
+/// #[get("/about")] fn about() { }
+/// #[post("/about", format = "json")] fn update() { }
+///
+/// let blue = rocket::ignite().mount("/", routes![index, about]).route_table();
+/// let green = rocket::ignite().mount("/", routes![index, update]).route_table();
+///
+/// let changes = blue.diff(&green);
+/// assert_eq!(changes.len(), 2);
+/// assert!(matches!(&changes[0], RouteChange::Removed(r) if r.method == "GET"));
+/// assert!(matches!(&changes[1], RouteChange::Added(r) if r.method == "POST"));
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RouteTable {
+    /// The routes, sorted by path, method, format, rank, and name.
+    pub routes: Vec<RouteEntry>,
+}
+
+/// A route in a [`RouteTable`].
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub struct RouteEntry {
+    /// The complete path of the route, including its mount point, in Rocket's
+    /// route format, as in `/users/<id>`.
+    pub path: String,
+    /// The route's method, as in `GET`.
+    pub method: String,
+    /// The route's format, if any, as in `application/json`.
+    pub format: Option<String>,
+    /// The route's rank.
+    pub rank: isize,
+    /// The route's name, if any. Routes generated by codegen are named after
+    /// their handler.
+    pub name: Option<String>,
+}
+
+/// A difference between two [`RouteTable`]s, as returned by
+/// [`RouteTable::diff()`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum RouteChange {
+    /// A route present only in the newer table.
+    Added(RouteEntry),
+    /// A route present only in the older table.
+    Removed(RouteEntry),
+    /// A route whose rank or name changed.
+    Changed {
+        /// The route in the older table.
+        from: RouteEntry,
+        /// The route in the newer table.
+        to: RouteEntry,
+    },
+}
+
+impl RouteEntry {
+    /// The route's identity for diffing: what it matches, as opposed to how it
+    /// is prioritized or what it's called.
+    fn key(&self) -> (&str, &str, Option<&str>) {
+        (&self.path, &self.method, self.format.as_deref())
+    }
+}
+
+impl From<&Route> for RouteEntry {
+    fn from(route: &Route) -> Self {
+        RouteEntry {
+            path: route.uri.to_string(),
+            method: route.method.to_string(),
+            format: route.format.as_ref().map(|f| f.to_string()),
+            rank: route.rank,
+            name: route.name.map(|n| n.to_string()),
+        }
+    }
+}
+
+impl RouteTable {
+    pub(crate) fn new<'a, I: Iterator<Item = &'a Route>>(routes: I) -> Self {
+        let mut routes: Vec<RouteEntry> = routes.map(RouteEntry::from).collect();
+        routes.sort();
+        RouteTable { routes }
+    }
+
+    /// Returns the differences from `self` to `newer`, ordered by route.
+    ///
+    /// Routes are identified by their path, method, and format. A route in
+    /// both tables whose rank or name differs is [`RouteChange::Changed`].
+    /// When several routes share an identity, as with routes that forward to
+    /// one another, they are paired up in order of rank.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # #[macro_use] extern crate rocket;
+    /// use rocket::RouteChange;
+    ///
+    /// #[get("/")] fn index() { }
+    /// #[get("/", rank = 5)] fn fallback() { }
+    ///
+    /// let blue = rocket::ignite().mount("/", routes![index]).route_table();
+    /// let green = rocket::ignite().mount("/", routes![fallback]).route_table();
+    ///
+    /// match &blue.diff(&green)[..] {
+    ///     [RouteChange::Changed { from, to }] => {
+    ///         assert_eq!(from.name.as_deref(), Some("index"));
+    ///         assert_eq!(to.rank, 5);
+    ///     }
+    ///     changes => panic!("unexpected changes: {:?}", changes),
+    /// }
+    ///
+    /// assert!(blue.diff(&blue).is_empty());
+    /// ```
+    pub fn diff(&self, newer: &RouteTable) -> Vec<RouteChange> {
+        let mut groups = BTreeMap::new();
+        for route in &self.routes {
+            groups.entry(route.key()).or_insert((vec![], vec![])).0.push(route);
+        }
+
+        for route in &newer.routes {
+            groups.entry(route.key()).or_insert((vec![], vec![])).1.push(route);
+        }
+
+        let mut changes = vec![];
+        for (old, new) in groups.values() {
+            let paired = old.len().min(new.len());
+            for (from, to) in old.iter().zip(new.iter()) {
+                if from != to {
+                    let (from, to) = ((*from).clone(), (*to).clone());
+                    changes.push(RouteChange::Changed { from, to });
+                }
+            }
+
+            let removed = old[paired..].iter().map(|r| RouteChange::Removed((*r).clone()));
+            let added = new[paired..].iter().map(|r| RouteChange::Added((*r).clone()));
+            changes.extend(removed.chain(added));
+        }
+
+        changes
+    }
+}
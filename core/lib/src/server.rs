@@ -1,37 +1,113 @@
 use std::io;
+use std::pin::Pin;
 use std::sync::Arc;
+use std::any::Any;
+use std::panic::AssertUnwindSafe;
+use std::task::{Context, Poll};
+use std::time::Instant;
 
 use futures::stream::StreamExt;
-use futures::future::{Future, BoxFuture};
+use futures::future::{Future, BoxFuture, FutureExt};
 use tokio::sync::oneshot;
 use yansi::Paint;
 
-use crate::Rocket;
+use crate::{Rocket, Route, Ignite, Orbit};
 use crate::handler;
-use crate::request::{Request, FormItems};
+use crate::request::{Request, FormItems, TraceEvent, ConnectionInfo, RequestId};
 use crate::data::Data;
-use crate::response::{Body, Response};
+use crate::response::{Body, Response, ResponseParts};
 use crate::outcome::Outcome;
 use crate::error::{Error, ErrorKind};
-use crate::logger::PaintExt;
+use crate::logger::{PaintExt, Tagged};
+use crate::deferred::Deferred;
 use crate::ext::AsyncReadExt;
 
 use crate::http::{Method, Status, Header, hyper};
-use crate::http::private::{Listener, Connection, Incoming};
+use crate::http::{Listener, Connection};
+use crate::http::private::{Incoming, TlsInfo, Timed, Busy};
 use crate::http::uri::Origin;
+use crate::http::ext::IntoOwned;
 
 // A token returned to force the execution of one method before another.
 pub(crate) struct Token;
 
+// The body of a hyper response: the data written to a channel followed by the
+// trailers, if any, sent once the data has been written. Holds the connection's
+// `Busy` guard, if any, so it stays busy until hyper is done with the body.
+pub(crate) struct HyperBody {
+    data: hyper::Body,
+    trailers: Option<oneshot::Receiver<hyper::HeaderMap>>,
+    busy: Option<Busy>,
+}
+
+impl hyper::HttpBody for HyperBody {
+    type Data = hyper::Bytes;
+    type Error = hyper::Error;
+
+    fn poll_data(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Option<Result<Self::Data, Self::Error>>> {
+        Pin::new(&mut self.data).poll_data(cx)
+    }
+
+    fn poll_trailers(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Result<Option<hyper::HeaderMap>, Self::Error>> {
+        let trailers = match self.trailers.as_mut() {
+            Some(trailers) => Pin::new(trailers).poll(cx),
+            None => return Poll::Ready(Ok(None)),
+        };
+
+        match trailers {
+            Poll::Ready(trailers) => {
+                // The sender is dropped without sending if writing failed.
+                self.trailers = None;
+                Poll::Ready(Ok(trailers.ok()))
+            }
+            Poll::Pending => Poll::Pending,
+        }
+    }
+}
+
+// Converts a Rocket header into a hyper header field. Returns `None` if the
+// header's name or value can't be written out safely.
+fn hyper_header(header: &Header<'_>) -> Option<(hyper::HeaderName, hyper::HeaderValue)> {
+    let name = hyper::HeaderName::from_bytes(header.name.as_str().as_bytes()).ok()?;
+    let value = hyper::HeaderValue::from_str(header.value()).ok()?;
+    Some((name, value))
+}
+
+// Converts Rocket trailer fields into hyper trailer fields, dropping invalid
+// fields with a warning.
+fn hyper_trailers(trailers: crate::http::HeaderMap<'_>) -> hyper::HeaderMap {
+    let mut hyp_trailers = hyper::HeaderMap::new();
+    for header in trailers.iter() {
+        match hyper_header(&header) {
+            Some((name, value)) => { hyp_trailers.append(name, value); }
+            None => {
+                let name = header.name.as_str().escape_debug();
+                warn_!("Dropping invalid trailer field: {}", name);
+            }
+        }
+    }
+
+    hyp_trailers
+}
+
 // This function tries to hide all of the Hyper-ness from Rocket. It essentially
 // converts Hyper types into Rocket types, then calls the `dispatch` function,
 // which knows nothing about Hyper. Because responding depends on the
 // `HyperResponse` type, this function does the actual response processing.
 async fn hyper_service_fn(
-    rocket: Arc<Rocket>,
+    rocket: Arc<Rocket<Orbit>>,
     h_addr: std::net::SocketAddr,
+    tls_info: Option<Arc<TlsInfo>>,
+    connection: ConnectionInfo,
+    busy: Busy,
     hyp_req: hyper::Request<hyper::Body>,
-) -> Result<hyper::Response<hyper::Body>, io::Error> {
+) -> Result<hyper::Response<HyperBody>, io::Error> {
     // This future must return a hyper::Response, but the response body might
     // borrow from the request. Instead, write the body in another future that
     // sends the response metadata (and a body channel) prior.
@@ -43,11 +119,15 @@ async fn hyper_service_fn(
 
         // Convert the Hyper request into a Rocket request.
         let req_res = Request::from_hyp(
-            &rocket, h_parts.method, h_parts.headers, &h_parts.uri, h_addr
+            &rocket, h_parts.method, h_parts.version, h_parts.headers, &h_parts.uri, h_addr
         );
 
         let mut req = match req_res {
-            Ok(req) => req,
+            Ok(mut req) => {
+                req.set_tls_info(tls_info);
+                req.set_connection_info(connection);
+                req
+            }
             Err(e) => {
                 error!("Bad incoming request: {}", e);
                 // TODO: We don't have a request to pass in, so we just
@@ -63,26 +143,53 @@ async fn hyper_service_fn(
         // Retrieve the data from the hyper body.
         let mut data = Data::from_hyp(h_body).await;
 
+        // Tag the log lines emitted while dispatching with the request's ID.
+        let id = match rocket.config.request_id {
+            true => Some(RequestId::of(&req).shared()),
+            false => None
+        };
+
         // Dispatch the request to get a response, then write that response out.
-        let token = rocket.preprocess_request(&mut req, &mut data).await;
-        let r = rocket.dispatch(token, &mut req, data).await;
-        rocket.send_response(r, tx).await;
+        let dispatch = async move {
+            let token = rocket.preprocess_request(&mut req, &mut data).await;
+            let mut r = rocket.dispatch(token, &mut req, data).await;
+            let on_complete = r.take_on_complete();
+            rocket.send_response(r, tx).await;
+
+            // Deliver the published events and run the deferred work now that
+            // the response is sent.
+            if !rocket.events.is_empty() {
+                tokio::spawn(rocket.events.deliver(&req));
+            }
+
+            if let Some(deferred) = Deferred::run(&req, on_complete) {
+                tokio::spawn(deferred);
+            }
+        };
+
+        match id {
+            Some(id) => Tagged::new(id, Box::pin(dispatch)).await,
+            None => dispatch.await
+        }
     });
 
-    // Receive the response written to `tx` by the task above.
-    rx.await.map_err(|e| io::Error::new(io::ErrorKind::Other, e))
+    // Receive the response written to `tx` by the task above. Keep the
+    // connection's read timeout suspended until the entire body is written,
+    // however long it takes: streams may legitimately go quiet for a while.
+    let response = rx.await.map_err(|e| io::Error::new(io::ErrorKind::Other, e))?;
+    Ok(response.map(|body| HyperBody { busy: Some(busy), ..body }))
 }
 
-impl Rocket {
+impl Rocket<Orbit> {
     /// Wrapper around `make_response` to log a success or failure.
     #[inline]
     async fn send_response(
         &self,
         response: Response<'_>,
-        tx: oneshot::Sender<hyper::Response<hyper::Body>>,
+        tx: oneshot::Sender<hyper::Response<HyperBody>>,
     ) {
         match self.make_response(response, tx).await {
-            Ok(()) => info_!("{}", Paint::green("Response succeeded.")),
+            Ok(()) => dispatch_log!(info_: "{}", Paint::green("Response succeeded.")),
             Err(e) => error_!("Failed to write response: {:?}.", e),
         }
     }
@@ -92,19 +199,33 @@ impl Rocket {
     async fn make_response(
         &self,
         mut response: Response<'_>,
-        tx: oneshot::Sender<hyper::Response<hyper::Body>>,
+        tx: oneshot::Sender<hyper::Response<HyperBody>>,
     ) -> io::Result<()> {
         let mut hyp_res = hyper::Response::builder()
             .status(response.status().code);
 
+        // Never let a malformed header, say one with a CR/LF in its value,
+        // reach the wire. Drop it instead of failing the entire response.
         for header in response.headers().iter() {
-            let name = header.name.as_str();
-            let value = header.value.as_bytes();
-            hyp_res = hyp_res.header(name, value);
+            match hyper_header(&header) {
+                Some((name, value)) => hyp_res = hyp_res.header(name, value),
+                None => {
+                    let name = header.name.as_str().escape_debug();
+                    warn_!("Dropping invalid header field: {}", name);
+                }
+            }
         }
 
-        let send_response = move |res: hyper::ResponseBuilder, body| -> io::Result<()> {
-            let response = res.body(body)
+        let (trailers, trailers_rx) = match response.take_trailers() {
+            Some(trailers) => {
+                let (trailers_tx, trailers_rx) = oneshot::channel();
+                (Some((trailers, trailers_tx)), Some(trailers_rx))
+            }
+            None => (None, None),
+        };
+
+        let send_response = move |res: hyper::ResponseBuilder, data| -> io::Result<()> {
+            let response = res.body(HyperBody { data, trailers: trailers_rx, busy: None })
                 .map_err(|e| io::Error::new(io::ErrorKind::Other, e))?;
 
             tx.send(response).map_err(|_| {
@@ -139,9 +260,50 @@ impl Rocket {
             }
         };
 
+        if let Some((trailers, trailers_tx)) = trailers {
+            let _ = trailers_tx.send(hyper_trailers(trailers.await));
+        }
+
         Ok(())
     }
 
+    /// Decodes `data` if `req` has a supported `Content-Encoding` and one of
+    /// the configured `decompress_formats`, removing the `Content-Encoding` and
+    /// `Content-Length` headers, which no longer describe the body.
+    #[cfg(feature = "decompression")]
+    fn decode_request_body(&self, req: &mut Request<'_>, data: &mut Data) {
+        use crate::data::Encoding;
+        use crate::http::ContentType;
+
+        if !self.config.decompress {
+            return;
+        }
+
+        let formats = &self.config.decompress_formats;
+        if !formats.is_empty() {
+            let content_type = match req.content_type() {
+                Some(content_type) => content_type,
+                None => return,
+            };
+
+            let selected = formats.iter()
+                .filter_map(|format| ContentType::parse_flexible(format))
+                .any(|format| format.top() == content_type.top()
+                    && format.sub() == content_type.sub());
+
+            if !selected {
+                return;
+            }
+        }
+
+        let encoding = req.headers().get_one("Content-Encoding").map(Encoding::parse);
+        if let Some(Some(encoding)) = encoding {
+            data.decode(encoding);
+            req._remove_header("Content-Encoding");
+            req._remove_header("Content-Length");
+        }
+    }
+
     /// Preprocess the request for Rocket things. Currently, this means:
     ///
     ///   * Rewriting the method in the request if _method form field exists.
@@ -153,6 +315,30 @@ impl Rocket {
         req: &mut Request<'_>,
         data: &mut Data
     ) -> Token {
+        // Dispatch starts here: time the request from now, not from when it
+        // was built, which may have been well before.
+        req.state.started = Instant::now();
+
+        // Strip the configured path prefix so fairings and routes see the path
+        // as if the application were served from the root.
+        if let Some(prefix) = self.config.path_prefix_segments() {
+            let stripped = req.uri().path()[1..].strip_prefix(prefix)
+                .filter(|rest| rest.is_empty() || rest.starts_with('/'))
+                .and_then(|rest| {
+                    req.uri().map_path(|_| format!("/{}", rest.trim_start_matches('/')))
+                })
+                .map(|uri| uri.into_owned());
+
+            if let Some(uri) = stripped {
+                req.set_uri(uri);
+            }
+        }
+
+        // Decode a compressed body so that fairings and data guards see, and
+        // limits apply to, the decoded data.
+        #[cfg(feature = "decompression")]
+        self.decode_request_body(req, data);
+
         // Check if this is a form and if the form contains the special _method
         // field which we use to reinterpret the request's method.
         let (min_len, max_len) = ("_method=get".len(), "_method=delete".len());
@@ -172,6 +358,11 @@ impl Rocket {
             }
         }
 
+        // Assign the request its ID before request fairings see it.
+        if self.config.request_id {
+            RequestId::of(req);
+        }
+
         // Run request fairings.
         self.fairings.handle_request(req, data).await;
 
@@ -185,13 +376,13 @@ impl Rocket {
         request: &'r Request<'s>,
         data: Data
     ) -> Response<'r> {
-        info!("{}:", request);
+        dispatch_log!(info: "{}:", request);
 
         // Remember if the request is `HEAD` for later body stripping.
         let was_head_request = request.method() == Method::Head;
 
         // Route the request and run the user's handlers.
-        let mut response = self.route_and_process(request, data).await;
+        let mut response = self.route_and_process(request, data, vec![]).await;
 
         // Add a default 'Server' header if it isn't already there.
         // TODO: If removing Hyper, write out `Date` header too.
@@ -199,38 +390,67 @@ impl Rocket {
             response.set_header(Header::new("Server", "Rocket"));
         }
 
+        // Echo the request's ID so clients can refer to the request.
+        if self.config.request_id {
+            let id = RequestId::of(request).to_string();
+            response.set_header(Header::new(RequestId::HEADER, id));
+        }
+
         // Run the response fairings.
         self.fairings.handle_response(request, &mut response).await;
 
-        // Strip the body if this is a `HEAD` request.
+        // Strip the body if this is a `HEAD` request. Otherwise, let the
+        // response body fairings transform it.
         if was_head_request {
             response.strip_body();
+        } else {
+            self.fairings.handle_response_body(request, &mut response);
         }
 
         response
     }
 
     /// Route the request and process the outcome to eventually get a response.
+    /// `attempted` holds the routes the request has already been forwarded
+    /// through, across internal re-dispatches.
     fn route_and_process<'s, 'r: 's>(
         &'s self,
         request: &'r Request<'s>,
-        data: Data
+        data: Data,
+        mut attempted: Vec<&'s Route>,
     ) -> impl Future<Output = Response<'r>> + Send + 's {
         async move {
-            let mut response = match self.route(request, data).await {
+            // A request failed by a request fairing isn't routed.
+            let outcome = match request.failure() {
+                Some(status) => {
+                    dispatch_log!(info_: "Request failed by a fairing: {}.", status);
+                    request._trace(|| {
+                        TraceEvent::Failed(status, "failed by a request fairing".into())
+                    });
+
+                    Outcome::Failure(status)
+                }
+                None => self.route(request, data, &mut attempted).await,
+            };
+
+            let mut response = match outcome {
                 Outcome::Success(response) => response,
                 Outcome::Forward(data) => {
                     // There was no matching route. Autohandle `HEAD` requests.
                     if request.method() == Method::Head {
-                        info_!("Autohandling {} request.", Paint::default("HEAD").bold());
+                        dispatch_log!(info_: "Autohandling {} request.",
+                            Paint::default("HEAD").bold());
 
                         // Dispatch the request again with Method `GET`.
                         request._set_method(Method::Get);
+                        request._trace(|| TraceEvent::Redispatched(Method::Get));
 
                         // Return early so we don't set cookies twice.
                         let try_next: BoxFuture<'_, _> =
-                            Box::pin(self.route_and_process(request, data));
+                            Box::pin(self.route_and_process(request, data, attempted));
                         return try_next.await;
+                    } else if let Some(response) = self.auto_options(request) {
+                        response
                     } else {
                         // No match was found and it can't be autohandled. 404.
                         self.handle_error(Status::NotFound, request).await
@@ -239,8 +459,13 @@ impl Rocket {
                 Outcome::Failure(status) => self.handle_error(status, request).await,
             };
 
-            // Set the cookies. Note that error responses will only include
-            // cookies set by the error handler. See `handle_error` for more.
+            // Apply the committed parts, then set the cookies. Note that error
+            // responses only include cookies set before the error if
+            // `error_cookies` is enabled. See `handle_error` for more.
+            if let Some(parts) = request.state.cache.try_get::<ResponseParts>() {
+                parts.apply(request.cookies(), &mut response);
+            }
+
             let delta_jar = request.cookies().take_delta_jar();
             for cookie in delta_jar.delta() {
                 response.adjoin_header(cookie);
@@ -250,41 +475,123 @@ impl Rocket {
         }
     }
 
+    /// Answers an unmatched `OPTIONS` request when `auto_options` is enabled.
+    /// The response is a `204` with an `Allow` header listing the methods of
+    /// the routes whose path matches the request. Returns `None` if the option
+    /// is disabled, the request isn't an `OPTIONS` request, or no route's path
+    /// matches the request.
+    fn auto_options<'r>(&self, request: &'r Request<'_>) -> Option<Response<'r>> {
+        if !self.config.auto_options || request.method() != Method::Options {
+            return None;
+        }
+
+        let mut methods = self.router.allowed_methods(request);
+        if methods.is_empty() {
+            return None;
+        }
+
+        // `HEAD` requests are autohandled by `GET` routes.
+        if methods.contains(&Method::Get) && !methods.contains(&Method::Head) {
+            methods.insert(1, Method::Head);
+        }
+
+        if !methods.contains(&Method::Options) {
+            methods.push(Method::Options);
+        }
+
+        dispatch_log!(info_: "Autohandling {} request.", Paint::default("OPTIONS").bold());
+        let allow = methods.iter().map(|m| m.as_str()).collect::<Vec<_>>().join(", ");
+        Some(Response::build()
+            .status(Status::NoContent)
+            .raw_header("Allow", allow)
+            .finalize())
+    }
+
     /// Tries to find a `Responder` for a given `request`. It does this by
     /// routing the request and calling the handler for each matching route
     /// until one of the handlers returns success or failure, or there are no
     /// additional routes to try (forward). The corresponding outcome for each
     /// condition is returned.
+    ///
+    /// Every route tried is recorded in `attempted`. If the request would be
+    /// forwarded through more than `forward_limit` routes in total, routing
+    /// stops and the request fails with a `500`.
     #[inline]
-    fn route<'s, 'r: 's>(
+    async fn route<'s, 'r: 's>(
         &'s self,
         request: &'r Request<'s>,
         mut data: Data,
-    ) -> impl Future<Output = handler::Outcome<'r>> + 's {
-        async move {
-            // Go through the list of matching routes until we fail or succeed.
-            let matches = self.router.route(request);
-            for route in matches {
-                // Retrieve and set the requests parameters.
-                info_!("Matched: {}", route);
-                request.set_route(route);
-
-                // Dispatch the request to the handler.
-                let outcome = route.handler.handle(request, data).await;
-
-                // Check if the request processing completed (Some) or if the
-                // request needs to be forwarded. If it does, continue the loop
-                // (None) to try again.
-                info_!("{} {}", Paint::default("Outcome:").bold(), outcome);
-                match outcome {
-                    o@Outcome::Success(_) | o@Outcome::Failure(_) => return o,
-                    Outcome::Forward(unused_data) => data = unused_data,
+        attempted: &mut Vec<&'s Route>,
+    ) -> handler::Outcome<'r> {
+        // Go through the list of matching routes until we fail or succeed.
+        let matches = self.router.route(request);
+        for route in matches {
+            if attempted.len() >= self.config.forward_limit {
+                error_!("Forward limit ({}) exceeded for {}.",
+                    self.config.forward_limit, request);
+                log_attempted(attempted);
+                request._trace(|| {
+                    let limit = self.config.forward_limit;
+                    TraceEvent::Failed(Status::InternalServerError,
+                        format!("forward limit ({}) exceeded", limit))
+                });
+
+                return Outcome::Failure(Status::InternalServerError);
+            }
+
+            attempted.push(route);
+            if self.config.trace {
+                route.counters.attempted();
+            }
+
+            // Retrieve and set the requests parameters.
+            dispatch_log!(info_: "Matched: {}", route);
+            request.set_route(route);
+            request._trace(|| TraceEvent::matched(route));
+
+            // Dispatch the request to the handler. A panic fails the request.
+            let handler = AssertUnwindSafe(route.handler.handle(request, data));
+            let outcome = match handler.catch_unwind().await {
+                Ok(outcome) => outcome,
+                Err(payload) => {
+                    let message = panic_message(&*payload);
+                    error_!("Handler {} panicked: {}", route, message);
+                    request._trace(|| TraceEvent::Panicked(message.into()));
+                    return Outcome::Failure(Status::InternalServerError);
                 }
+            };
+
+            // A guard cycle fails the request, whatever the handler made of it.
+            if let Some(cycle) = request.guard_cycle() {
+                error_!("Handler {} failed: request guard cycle.", route);
+                request._trace(|| {
+                    TraceEvent::Failed(Status::InternalServerError,
+                        format!("request guard cycle: {}", cycle))
+                });
+
+                return Outcome::Failure(Status::InternalServerError);
             }
 
-            error_!("No matching routes for {}.", request);
-            Outcome::Forward(data)
+            // Check if the request processing completed (Some) or if the
+            // request needs to be forwarded. If it does, continue the loop
+            // (None) to try again.
+            dispatch_log!(info_: "{} {}", Paint::default("Outcome:").bold(), outcome);
+            match outcome {
+                o@Outcome::Success(_) | o@Outcome::Failure(_) => {
+                    if self.config.trace {
+                        route.counters.won();
+                    }
+
+                    return o;
+                }
+                Outcome::Forward(unused_data) => data = unused_data,
+            }
         }
+
+        error_!("No matching routes for {}.", request);
+        log_attempted(attempted);
+        request._trace(|| TraceEvent::Unmatched);
+        Outcome::Forward(data)
     }
 
     // Finds the error catcher for the status `status` and executes it for the
@@ -300,15 +607,24 @@ impl Rocket {
         async move {
             warn_!("Responding with {} catcher.", Paint::red(&status));
 
-            // For now, we reset the delta state to prevent any modifications
-            // from earlier, unsuccessful paths from being reflected in error
-            // response. We may wish to relax this in the future.
-            req.cookies().reset_delta();
+            // Unless configured otherwise, reset the delta state to prevent
+            // modifications from earlier, unsuccessful paths from being
+            // reflected in the error response. Committed `ResponseParts` are
+            // unaffected.
+            if !self.config.error_cookies {
+                req.cookies().reset_delta();
+            }
 
             // Try to get the active catcher but fallback to user's 500 catcher.
             let code = Paint::red(status.code);
+            let ranged = self.range_catchers.iter()
+                .filter(|c| c.range.as_ref().map_or(false, |r| r.contains(&status.code)))
+                .min_by_key(|c| c.range.as_ref().map_or(0, |r| r.end - r.start));
+
             let response = if let Some(catcher) = self.catchers.get(&status.code) {
                 catcher.handler.handle(status, req).await
+            } else if let Some(catcher) = ranged {
+                catcher.handler.handle(status, req).await
             } else if let Some(ref default) =  self.default_catcher {
                 warn_!("No {} catcher found. Using default catcher.", code);
                 default.handler.handle(status, req).await
@@ -329,27 +645,29 @@ impl Rocket {
             }
         }
     }
+}
 
+impl Rocket<Ignite> {
     // TODO.async: Solidify the Listener APIs and make this function public
     pub(crate) async fn listen_on<L>(mut self, listener: L) -> Result<(), Error>
         where L: Listener + Send + Unpin + 'static,
               <L as Listener>::Connection: Send + Unpin + 'static,
     {
-        // We do this twice if `listen_on` was called through `launch()` but
-        // only once if `listen_on()` gets called directly.
-        self.prelaunch_check().await?;
-
         // Freeze managed state for synchronization-free accesses later.
         self.managed_state.freeze();
-
-        // Run the launch fairings.
-        self.fairings.pretty_print_counts();
-        self.fairings.handle_launch(&self);
+        self.scoped_state.freeze();
 
         // Determine the address and port we actually bound to.
-        self.config.port = listener.local_addr().map(|a| a.port()).unwrap_or(0);
-        let proto = self.config.tls.as_ref().map_or("http://", |_| "https://");
-        let full_addr = format!("{}:{}", self.config.address, self.config.port);
+        let local = listener.local_addr();
+        self.config.port = local.map(|a| a.port()).unwrap_or(0);
+
+        // We're now in orbit. Run the launch fairings.
+        let mut rocket = self.into_orbit();
+        rocket.fairings.pretty_print_counts();
+        rocket.fairings.handle_launch(&rocket);
+
+        let proto = rocket.config.tls.as_ref().map_or("http://", |_| "https://");
+        let full_addr = format!("{}:{}", rocket.config.address, rocket.config.port);
 
         launch_info!("{}{} {}{}",
                      Paint::emoji("🚀 "),
@@ -357,35 +675,90 @@ impl Rocket {
                      Paint::default(proto).bold().underline(),
                      Paint::default(&full_addr).bold().underline());
 
+        // Let a process that handed over its socket know we're now serving.
+        #[cfg(unix)]
+        crate::handover::notify_ready();
+
+        // HTTP/2 is negotiated via ALPN over TLS; it's otherwise opt-in.
+        let http1_only = !rocket.config.tls_enabled() && !rocket.config.h2c;
+
         // Determine keep-alives.
-        let http1_keepalive = self.config.keep_alive != 0;
-        let http2_keep_alive = match self.config.keep_alive {
+        let http1_keepalive = rocket.config.keep_alive != 0;
+        let http2_keep_alive = match rocket.config.keep_alive {
             0 => None,
             n => Some(std::time::Duration::from_secs(n as u64))
         };
 
-        // We need to get this before moving `self` into an `Arc`.
-        let mut shutdown_receiver = self.shutdown_receiver.take()
+        // We need to get this before moving `rocket` into an `Arc`.
+        let mut shutdown_receiver = rocket.shutdown_receiver.take()
             .expect("shutdown receiver has already been used");
 
-        let rocket = Arc::new(self);
-        let service = hyper::make_service_fn(move |conn: &<L as Listener>::Connection| {
+        let rocket = Arc::new(rocket);
+        let orbit = rocket.clone();
+        let mut next_id = 0;
+        let service = hyper::make_service_fn(move |conn: &Timed<<L as Listener>::Connection>| {
             let rocket = rocket.clone();
+            let activity = conn.activity();
             let remote = conn.remote_addr().unwrap_or_else(|| ([0, 0, 0, 0], 0).into());
+            let tls_info = conn.tls_info().map(Arc::new);
+            let (id, remote_addr) = (next_id, conn.remote_addr());
+            let mut requests = 0;
+            next_id += 1;
+
             async move {
                 Ok::<_, std::convert::Infallible>(hyper::service_fn(move |req| {
-                    hyper_service_fn(rocket.clone(), remote, req)
+                    let reuses = requests;
+                    requests += 1;
+
+                    let connection = ConnectionInfo { id, local, remote: remote_addr, reuses };
+                    let (tls_info, busy) = (tls_info.clone(), activity.busy());
+                    hyper_service_fn(rocket.clone(), remote, tls_info, connection, busy, req)
                 }))
             }
         });
 
+        // Determine the connection timeouts.
+        let timeout = |secs: u32| match secs {
+            0 => None,
+            n => Some(std::time::Duration::from_secs(n as u64))
+        };
+
+        let mut incoming = Incoming::from_listener(listener);
+        let config = &orbit.config;
+        incoming.set_timeouts(timeout(config.read_timeout), timeout(config.write_timeout));
+
         // NOTE: `hyper` uses `tokio::spawn()` as the default executor.
-        hyper::Server::builder(Incoming::from_listener(listener))
+        let result = hyper::Server::builder(incoming)
+            .http1_only(http1_only)
             .http1_keepalive(http1_keepalive)
             .http2_keep_alive_interval(http2_keep_alive)
             .serve(service)
             .with_graceful_shutdown(async move { shutdown_receiver.recv().await; })
             .await
-            .map_err(|e| Error::new(ErrorKind::Runtime(Box::new(e))))
+            .map_err(|e| Error::new(ErrorKind::Runtime(Box::new(e))));
+
+        // All requests have completed: terminate state, most recent first.
+        for terminate in orbit.terminators.iter().rev() {
+            terminate(&orbit.managed_state).await;
+        }
+
+        result
     }
 }
+
+/// Logs the routes in `attempted`, in the order they were tried, if any.
+fn log_attempted(attempted: &[&Route]) {
+    if !attempted.is_empty() {
+        warn_!("{}", Paint::default("Attempted routes:").bold());
+        for route in attempted {
+            warn_!("{}", route);
+        }
+    }
+}
+
+/// Returns the message of the panic with payload `payload`, if it has one.
+fn panic_message(payload: &(dyn Any + Send)) -> &str {
+    payload.downcast_ref::<&str>().copied()
+        .or_else(|| payload.downcast_ref::<String>().map(|s| s.as_str()))
+        .unwrap_or("<non-string panic payload>")
+}
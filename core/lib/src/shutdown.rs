@@ -1,5 +1,7 @@
 use crate::request::{FromRequest, Outcome, Request};
 use tokio::sync::mpsc;
+use futures::future::BoxFuture;
+use state::Container;
 
 /// A request guard to gracefully shutdown a Rocket server.
 ///
@@ -58,3 +60,52 @@ impl<'a, 'r> FromRequest<'a, 'r> for Shutdown {
         Outcome::Success(request.state.shutdown.clone())
     }
 }
+
+/// Trait implemented by managed state that must be released when the server
+/// shuts down.
+///
+/// State managed via [`Rocket::manage_terminable()`] is _terminated_ once a
+/// launched server has shut down and all pending requests have completed:
+/// its [`terminate()`](Terminator::terminate()) method is called and awaited.
+/// Terminators run one at a time, in the reverse of the order in which their
+/// state was registered, so that state registered later, which may depend on
+/// state registered earlier, is terminated first. This makes it possible to
+/// deterministically close database pools, stop background schedulers, or
+/// release file locks.
+///
+/// Terminators are not run for instances that are never launched, such as
+/// those used by local clients.
+///
+/// [`Rocket::manage_terminable()`]: crate::Rocket::manage_terminable()
+///
+/// # Example
+///
+/// ```rust,no_run
+/// # #[macro_use] extern crate rocket;
+/// use rocket::Terminator;
+///
+/// struct Scheduler;
+///
+/// #[rocket::async_trait]
+/// impl Terminator for Scheduler {
+///     async fn terminate(&self) {
+///         println!("Stopping the scheduler.");
+///     }
+/// }
+///
+/// #[launch]
+/// fn rocket() -> rocket::Rocket {
+///     rocket::ignite().manage_terminable(Scheduler)
+/// }
+/// ```
+#[crate::async_trait]
+pub trait Terminator: Send + Sync + 'static {
+    /// Releases the resources held by `self`. Called once the server has shut
+    /// down gracefully.
+    async fn terminate(&self);
+}
+
+// Terminates the managed state of type `T` in `state`.
+pub(crate) fn terminate<T: Terminator>(state: &Container) -> BoxFuture<'_, ()> {
+    state.try_get::<T>().expect("terminable state is managed").terminate()
+}
@@ -0,0 +1,151 @@
+use std::{fmt, io};
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::{Duration, SystemTime};
+
+use yansi::Paint;
+
+use crate::logger::PaintExt;
+use crate::config::TlsConfig;
+use crate::http::tls::CertResolver;
+
+/// A handle to the certificate served by a running TLS-enabled server.
+///
+/// Through a `TlsHandle`, the certificate chain and private key presented to
+/// new connections can be replaced while the server is running, without
+/// interrupting established connections. Replacement is atomic: a failed
+/// replacement leaves the current certificate in use.
+///
+/// A running server reloads its certificate on `SIGHUP` and, if configured to,
+/// when the certificate files change. See [`TlsConfig`] for details.
+///
+/// A `TlsHandle` is available via [`Rocket::tls_handle()`] once the server has
+/// been launched with TLS enabled, and thus in launch fairings.
+///
+/// [`Rocket::tls_handle()`]: crate::Rocket::tls_handle()
+///
+/// # Example
+///
+/// ```rust
+/// use rocket::fairing::AdHoc;
+///
+/// let fairing = AdHoc::on_launch("Certificate Check", |rocket| {
+///     if let Some(handle) = rocket.tls_handle() {
+///         if let Err(e) = handle.reload() {
+///             eprintln!("failed to reload TLS certificate: {}", e);
+///         }
+///     }
+/// });
+/// ```
+#[derive(Clone)]
+pub struct TlsHandle {
+    resolver: Arc<CertResolver>,
+    config: TlsConfig,
+}
+
+impl TlsHandle {
+    pub(crate) fn new(resolver: Arc<CertResolver>, config: TlsConfig) -> TlsHandle {
+        TlsHandle { resolver, config }
+    }
+
+    /// Re-reads the configured `certs` and `key` and begins serving them.
+    ///
+    /// This is useful when the certificate files have been replaced on disk,
+    /// for instance by an external renewal tool. If reading either fails or
+    /// either is malformed, an error is returned and the current certificate
+    /// remains in use.
+    pub fn reload(&self) -> io::Result<()> {
+        let (certs, key) = self.config.to_readers()?;
+        self.resolver.set(certs, key)
+    }
+
+    /// Begins serving the PEM-encoded certificate chain `certs` with the
+    /// PEM-encoded private key `key`. If either is malformed, an error is
+    /// returned and the current certificate remains in use.
+    pub fn set_certificate(&self, certs: &[u8], key: &[u8]) -> io::Result<()> {
+        self.resolver.set(certs, key)
+    }
+
+    /// Returns the TLS configuration the server was launched with.
+    pub fn config(&self) -> &TlsConfig {
+        &self.config
+    }
+
+    fn reload_and_log(&self, cause: &str) {
+        match self.reload() {
+            Ok(()) => info!("{}Reloaded TLS certificate ({}).", Paint::emoji("🔐 "), cause),
+            Err(e) => {
+                warn!("Failed to reload TLS certificate ({}).", cause);
+                warn_!("{}", e);
+                warn_!("The previous certificate remains in use.");
+            }
+        }
+    }
+
+    /// Spawns the tasks that reload the certificate on `SIGHUP` and, if
+    /// `watch` is enabled, when the certificate files change.
+    pub(crate) fn spawn_reloaders(&self) {
+        #[cfg(unix)] {
+            use tokio::signal::unix::{signal, SignalKind};
+
+            match signal(SignalKind::hangup()) {
+                Ok(mut hangup) => {
+                    let handle = self.clone();
+                    tokio::spawn(async move {
+                        while hangup.recv().await.is_some() {
+                            handle.reload_and_log("SIGHUP");
+                        }
+                    });
+                }
+                Err(e) => warn!("Failed to enable TLS certificate reload on SIGHUP: {}", e),
+            }
+        }
+
+        if self.config.watch {
+            let paths: Vec<PathBuf> = vec![self.config.certs(), self.config.key()]
+                .into_iter()
+                .filter_map(|v| v.left())
+                .collect();
+
+            if paths.is_empty() {
+                warn!("TLS `watch` is enabled but no certificate files are configured.");
+                return;
+            }
+
+            let handle = self.clone();
+            tokio::spawn(async move { handle.watch(paths).await });
+        }
+    }
+
+    /// Polls the modification times of `paths`, reloading once they've
+    /// changed and then remained unchanged for one interval. Waiting for the
+    /// files to settle avoids loading a new certificate with an old key.
+    async fn watch(self, paths: Vec<PathBuf>) {
+        const INTERVAL: Duration = Duration::from_secs(5);
+
+        fn mtimes(paths: &[PathBuf]) -> Vec<Option<SystemTime>> {
+            paths.iter()
+                .map(|p| std::fs::metadata(p).and_then(|m| m.modified()).ok())
+                .collect()
+        }
+
+        let (mut last, mut pending) = (mtimes(&paths), false);
+        loop {
+            tokio::time::delay_for(INTERVAL).await;
+            let current = mtimes(&paths);
+            if current != last {
+                last = current;
+                pending = true;
+            } else if pending {
+                pending = false;
+                self.reload_and_log("file change");
+            }
+        }
+    }
+}
+
+impl fmt::Debug for TlsHandle {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("TlsHandle").field("config", &self.config).finish()
+    }
+}
@@ -0,0 +1,44 @@
+use serde::Deserialize;
+
+use rocket::fairing::AdHoc;
+use rocket::error::ErrorKind;
+use rocket::figment::{Figment, providers::{Format, Toml}};
+
+#[derive(Debug, PartialEq, Deserialize)]
+struct Mailer {
+    host: String,
+    port: u16,
+}
+
+fn rocket_with(toml: &str) -> rocket::Rocket {
+    let figment: Figment = rocket::Config::figment().merge(Toml::string(toml));
+    rocket::custom(figment).attach(AdHoc::config_section::<Mailer>("app.mailer"))
+}
+
+#[rocket::async_test]
+async fn section_is_extracted_and_managed() {
+    let rocket = rocket_with(r#"
+        [app.mailer]
+        host = "smtp.example.com"
+        port = 587
+    "#);
+
+    let mailer = rocket.state::<Mailer>().unwrap();
+    assert_eq!(mailer, &Mailer { host: "smtp.example.com".into(), port: 587 });
+}
+
+#[rocket::async_test]
+async fn missing_or_invalid_section_aborts_launch() {
+    let invalid = r#"
+        [app.mailer]
+        host = "smtp.example.com"
+        port = "not a port"
+    "#;
+
+    for toml in &["[app]\nname = \"missing mailer\"", invalid] {
+        match rocket_with(toml).ignited().await {
+            Err(e) => assert!(matches!(e.kind(), ErrorKind::FailedFairings(..))),
+            Ok(_) => panic!("launched without a valid mailer section"),
+        }
+    }
+}
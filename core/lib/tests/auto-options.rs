@@ -0,0 +1,67 @@
+#[macro_use] extern crate rocket;
+
+use rocket::Config;
+use rocket::fairing::AdHoc;
+use rocket::http::{Header, Method, Status};
+use rocket::local::blocking::Client;
+
+#[get("/thing/<_id>")]
+fn get_thing(_id: usize) { }
+
+#[put("/thing/<_id>")]
+fn put_thing(_id: usize) { }
+
+#[delete("/thing/<_id>")]
+fn delete_thing(_id: usize) { }
+
+#[post("/other")]
+fn post_other() { }
+
+#[options("/custom")]
+fn custom() -> &'static str { "custom" }
+
+fn client(auto_options: bool) -> Client {
+    let config = Config { auto_options, ..Config::debug_default() };
+    let rocket = rocket::custom(config)
+        .mount("/", routes![get_thing, put_thing, delete_thing, post_other, custom])
+        .attach(AdHoc::on_response("CORS", |req, res| Box::pin(async move {
+            if req.method() == Method::Options && res.headers().contains("Allow") {
+                res.set_header(Header::new("Access-Control-Allow-Origin", "*"));
+            }
+        })));
+
+    Client::tracked(rocket).unwrap()
+}
+
+#[test]
+fn options_are_autohandled_when_enabled() {
+    let client = client(true);
+    let response = client.options("/thing/10").dispatch();
+    assert_eq!(response.status(), Status::NoContent);
+    assert_eq!(response.headers().get_one("Allow"), Some("GET, HEAD, PUT, DELETE, OPTIONS"));
+    assert_eq!(response.headers().get_one("Access-Control-Allow-Origin"), Some("*"));
+
+    let response = client.options("/other").dispatch();
+    assert_eq!(response.status(), Status::NoContent);
+    assert_eq!(response.headers().get_one("Allow"), Some("POST, OPTIONS"));
+
+    let response = client.options("/custom").dispatch();
+    assert_eq!(response.status(), Status::Ok);
+    assert_eq!(response.headers().get_one("Allow"), None);
+    assert_eq!(response.into_string().unwrap(), "custom");
+
+    let response = client.options("/nothing").dispatch();
+    assert_eq!(response.status(), Status::NotFound);
+
+    let response = client.options("/thing/not-a-number").dispatch();
+    assert_eq!(response.status(), Status::NoContent);
+}
+
+#[test]
+fn options_are_not_autohandled_by_default() {
+    let client = client(false);
+    let response = client.options("/thing/10").dispatch();
+    assert_eq!(response.status(), Status::NotFound);
+    assert_eq!(response.headers().get_one("Allow"), None);
+    assert_eq!(response.headers().get_one("Access-Control-Allow-Origin"), None);
+}
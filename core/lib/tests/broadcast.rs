@@ -0,0 +1,85 @@
+#[macro_use] extern crate rocket;
+
+use std::time::Duration;
+
+use rocket::State;
+use rocket::broadcast::{Channel, Lag, Subscription};
+use rocket::futures::stream::{self, Stream, StreamExt};
+use rocket::http::ContentType;
+use rocket::local::blocking::Client;
+use rocket::response::{Event, EventStream};
+
+#[post("/message", data = "<message>")]
+fn send(channel: State<'_, Channel<String>>, message: String) -> String {
+    channel.send(message).to_string()
+}
+
+#[get("/messages")]
+fn messages(channel: State<'_, Channel<String>>) -> EventStream<Subscription<String>> {
+    channel.subscribe()
+}
+
+#[get("/quiet")]
+fn quiet() -> EventStream<impl Stream<Item = Event>> {
+    EventStream::from(stream::pending::<Event>()).heartbeat(Duration::from_millis(10))
+}
+
+#[test]
+fn subscribers_receive_events() {
+    let rocket = rocket::ignite()
+        .manage(Channel::<String>::new(8))
+        .mount("/", routes![send, messages]);
+
+    let client = Client::tracked(rocket).unwrap();
+    let first = client.get("/messages").dispatch();
+    let second = client.get("/messages").dispatch();
+    assert_eq!(first.content_type(), Some(ContentType::EventStream));
+    assert_eq!(first.headers().get_one("Cache-Control"), Some("no-cache"));
+
+    let response = client.post("/message").body("hello\nworld").dispatch();
+    assert_eq!(response.into_string().unwrap(), "2");
+
+    drop(second);
+    let response = client.post("/message").body("bye").dispatch();
+    assert_eq!(response.into_string().unwrap(), "1");
+
+    client.rocket().state::<Channel<String>>().unwrap().close();
+    let body = first.into_string().unwrap();
+    assert_eq!(body, "data: hello\ndata: world\n\ndata: bye\n\n");
+}
+
+#[rocket::async_test]
+async fn lag_policies() {
+    let channel = Channel::new(2);
+    let subscription = channel.subscription();
+    (1..=3).for_each(|i| { channel.send(i); });
+    channel.close();
+    assert_eq!(subscription.missed(), 1);
+    assert_eq!(subscription.collect::<Vec<_>>().await, vec![2, 3]);
+
+    let channel = Channel::new(2).lag(Lag::DropNewest);
+    let subscription = channel.subscription();
+    (1..=3).for_each(|i| { channel.send(i); });
+    channel.close();
+    assert_eq!(subscription.collect::<Vec<_>>().await, vec![1, 2]);
+
+    let channel = Channel::new(2).lag(Lag::Disconnect);
+    let subscription = channel.subscription();
+    assert_eq!(channel.send(1), 1);
+    assert_eq!(channel.send(2), 1);
+    assert_eq!(channel.send(3), 0);
+    assert_eq!(channel.subscriber_count(), 0);
+    assert_eq!(subscription.collect::<Vec<_>>().await, vec![1, 2]);
+}
+
+#[rocket::async_test]
+async fn quiet_streams_send_heartbeats() {
+    use rocket::local::asynchronous::Client;
+    use rocket::tokio::io::AsyncReadExt;
+
+    let client = Client::tracked(rocket::ignite().mount("/", routes![quiet])).await.unwrap();
+    let mut response = client.get("/quiet").dispatch().await;
+    let mut heartbeats = [0; 8];
+    response.read_exact(&mut heartbeats).await.unwrap();
+    assert_eq!(&heartbeats, b": \n\n: \n\n");
+}
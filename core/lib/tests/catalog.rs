@@ -0,0 +1,96 @@
+#[macro_use] extern crate rocket;
+
+use rocket::Request;
+use rocket::request::{Form, FormError};
+
+#[derive(FromForm)]
+struct Signup {
+    name: String,
+}
+
+#[post("/", data = "<form>")]
+fn signup(req: &Request<'_>, form: Result<Form<Signup>, FormError<'_>>) -> String {
+    match form {
+        Ok(form) => form.into_inner().name,
+        Err(e) => e.message(req).into_owned(),
+    }
+}
+
+mod catalog_tests {
+    use super::*;
+    use rocket::Config;
+    use rocket::catalog::Catalog;
+    use rocket::http::{Accept, ContentType, Header};
+    use rocket::local::blocking::Client;
+
+    fn client() -> Client {
+        let catalog = Catalog::new()
+            .add("fr", "error.404.reason", "Introuvable")
+            .add("fr", "error.404.description", "La ressource <demandée> est introuvable.")
+            .add("fr", "form.missing", "Le champ « {field} » est obligatoire.");
+
+        let config = Config { diagnostics: false, trace: false, ..Config::debug_default() };
+        let rocket = rocket::custom(config).mount("/", routes![signup]).manage(catalog);
+        Client::tracked(rocket).unwrap()
+    }
+
+    #[test]
+    fn translated_default_catcher() {
+        let client = client();
+        let response = client.get("/missing")
+            .header(Header::new("Accept-Language", "de, fr-CA;q=0.8"))
+            .dispatch();
+
+        assert_eq!(response.headers().get_one("Vary"), Some("Accept, Accept-Language"));
+        let body = response.into_string().unwrap();
+
+        assert!(body.contains(r#"<html lang="fr">"#));
+        assert!(body.contains("<h1>404: Introuvable</h1>"));
+        assert!(body.contains("La ressource &lt;demandée&gt; est introuvable."));
+
+        let body = client.get("/missing")
+            .header(Header::new("Accept-Language", "fr"))
+            .header(Accept::JSON)
+            .dispatch()
+            .into_string()
+            .unwrap();
+
+        assert!(body.contains(r#""reason": "Introuvable""#));
+    }
+
+    #[test]
+    fn untranslated_default_catcher() {
+        let client = client();
+        let body = client.get("/missing")
+            .header(Header::new("Accept-Language", "de"))
+            .dispatch()
+            .into_string()
+            .unwrap();
+
+        assert!(body.contains(r#"<html lang="en">"#));
+        assert!(body.contains("<h1>404: Not Found</h1>"));
+    }
+
+    #[test]
+    fn translated_form_errors() {
+        let client = client();
+        let body = client.post("/")
+            .header(ContentType::Form)
+            .header(Header::new("Accept-Language", "fr"))
+            .body("")
+            .dispatch()
+            .into_string()
+            .unwrap();
+
+        assert_eq!(body, "Le champ « name » est obligatoire.");
+
+        let body = client.post("/")
+            .header(ContentType::Form)
+            .body("")
+            .dispatch()
+            .into_string()
+            .unwrap();
+
+        assert_eq!(body, "The field 'name' is missing.");
+    }
+}
@@ -0,0 +1,72 @@
+#[macro_use] extern crate rocket;
+
+use std::sync::Mutex;
+
+use rocket::Config;
+use rocket::fairing::AdHoc;
+use rocket::request::ConnectionInfo;
+use rocket::tokio::net::TcpStream;
+use rocket::tokio::io::{AsyncReadExt, AsyncWriteExt};
+use rocket::tokio::sync::oneshot;
+
+#[get("/")]
+fn connection(conn: ConnectionInfo) -> String {
+    assert!(conn.local_addr().is_some());
+    assert!(conn.remote_addr().is_some());
+    format!("{}/{}", conn.id(), conn.reuse_count())
+}
+
+#[get("/", rank = 2)]
+fn no_connection() -> &'static str {
+    "none"
+}
+
+// Sends the requests in `paths` over one connection, pipelined, and returns
+// the raw responses.
+async fn pipelined(port: u16, paths: &[&str]) -> String {
+    let mut stream = TcpStream::connect(("127.0.0.1", port)).await.unwrap();
+    for (i, path) in paths.iter().enumerate() {
+        let close = if i == paths.len() - 1 { "Connection: close\r\n" } else { "" };
+        let request = format!("GET {} HTTP/1.1\r\nHost: localhost\r\n{}\r\n", path, close);
+        stream.write_all(request.as_bytes()).await.unwrap();
+    }
+
+    let mut response = String::new();
+    stream.read_to_string(&mut response).await.unwrap();
+    response
+}
+
+#[rocket::async_test]
+async fn connection_info_identifies_connections_and_reuse() {
+    let (tx, rx) = oneshot::channel();
+    let tx = Mutex::new(Some(tx));
+    let config = Config { port: 0, ctrlc: false, ..Config::debug_default() };
+    let rocket = rocket::custom(config)
+        .mount("/", routes![connection, no_connection])
+        .attach(AdHoc::on_launch("Port", move |rocket| {
+            let port = rocket.config().port;
+            let _ = tx.lock().unwrap().take().unwrap().send((port, rocket.shutdown()));
+        }));
+
+    let server = rocket::tokio::spawn(rocket.launch());
+    let (port, shutdown) = rx.await.unwrap();
+
+    let response = pipelined(port, &["/", "/"]).await;
+    assert!(response.contains("\r\n\r\n0/0"));
+    assert!(response.ends_with("\r\n\r\n0/1"));
+
+    let response = pipelined(port, &["/"]).await;
+    assert!(response.ends_with("\r\n\r\n1/0"));
+
+    shutdown.shutdown();
+    server.await.unwrap().unwrap();
+}
+
+#[test]
+fn connection_info_forwards_for_local_clients() {
+    let client = rocket::local::blocking::Client::tracked(
+        rocket::ignite().mount("/", routes![connection, no_connection])
+    ).unwrap();
+
+    assert_eq!(client.get("/").dispatch().into_string().unwrap(), "none");
+}
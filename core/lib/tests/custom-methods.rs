@@ -0,0 +1,52 @@
+#[macro_use] extern crate rocket;
+
+use rocket::Config;
+use rocket::http::{Method, Status};
+
+#[route(PURGE, path = "/cache/<key>")]
+fn purge(key: String) -> String {
+    format!("purged {}", key)
+}
+
+#[get("/cache/<key>")]
+fn get(key: String) -> String {
+    key
+}
+
+#[route(PROPFIND, path = "/cache/<_key>")]
+fn propfind(_key: String) -> &'static str {
+    "<multistatus/>"
+}
+
+mod custom_methods_tests {
+    use super::*;
+    use rocket::local::blocking::Client;
+
+    fn client() -> Client {
+        let config = Config { auto_options: true, ..Config::debug_default() };
+        let rocket = rocket::custom(config).mount("/", routes![purge, get, propfind]);
+        Client::tracked(rocket).unwrap()
+    }
+
+    #[test]
+    fn routes_extension_methods() {
+        let client = client();
+        let response = client.req(Method::Custom("PURGE"), "/cache/home").dispatch();
+        assert_eq!(response.into_string().unwrap(), "purged home");
+
+        let response = client.req(Method::Custom("PROPFIND"), "/cache/home").dispatch();
+        assert_eq!(response.into_string().unwrap(), "<multistatus/>");
+
+        let response = client.req(Method::Custom("MKCOL"), "/cache/home").dispatch();
+        assert_eq!(response.status(), Status::NotFound);
+    }
+
+    #[test]
+    fn lists_extension_methods_in_allow() {
+        let client = client();
+        let response = client.options("/cache/home").dispatch();
+        assert_eq!(response.status(), Status::NoContent);
+        assert_eq!(response.headers().get_one("Allow"),
+            Some("GET, HEAD, PROPFIND, PURGE, OPTIONS"));
+    }
+}
@@ -0,0 +1,54 @@
+mod default_catcher_negotiation_tests {
+    use rocket::Config;
+    use rocket::http::{Accept, ContentType, Header, Status};
+    use rocket::local::blocking::{Client, LocalResponse};
+
+    fn client() -> Client {
+        let config = Config { diagnostics: false, trace: false, ..Config::debug_default() };
+        Client::tracked(rocket::custom(config)).unwrap()
+    }
+
+    fn assert_json(response: LocalResponse<'_>) {
+        assert_eq!(response.status(), Status::NotFound);
+        assert_eq!(response.content_type(), Some(ContentType::JSON));
+        assert_eq!(response.headers().get_one("Vary"), Some("Accept"));
+
+        let body = response.into_string().unwrap();
+        assert!(body.contains(r#""code": 404"#));
+        assert!(body.contains(r#""reason": "Not Found""#));
+    }
+
+    fn assert_html(response: LocalResponse<'_>) {
+        assert_eq!(response.status(), Status::NotFound);
+        assert_eq!(response.content_type(), Some(ContentType::HTML));
+        assert_eq!(response.headers().get_one("Vary"), Some("Accept"));
+        assert!(response.into_string().unwrap().contains("<h1>404: Not Found</h1>"));
+    }
+
+    #[test]
+    fn json_for_api_clients() {
+        let client = client();
+        assert_json(client.get("/").header(Accept::JSON).dispatch());
+        assert_json(client.get("/")
+            .header(Header::new("Accept", "application/problem+json"))
+            .dispatch());
+        assert_json(client.get("/")
+            .header(Header::new("Accept", "text/html;q=0.5, application/json"))
+            .dispatch());
+        assert_json(client.post("/").header(ContentType::JSON).dispatch());
+    }
+
+    #[test]
+    fn html_for_browsers() {
+        let client = client();
+        assert_html(client.get("/").dispatch());
+        assert_html(client.get("/").header(Accept::HTML).dispatch());
+        assert_html(client.get("/")
+            .header(Header::new("Accept", "text/html,application/xhtml+xml,*/*;q=0.8"))
+            .dispatch());
+        assert_html(client.get("/")
+            .header(Header::new("Accept", "application/json;q=0.5, text/html"))
+            .dispatch());
+        assert_html(client.post("/").header(ContentType::Form).dispatch());
+    }
+}
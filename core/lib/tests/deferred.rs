@@ -0,0 +1,59 @@
+#[macro_use] extern crate rocket;
+
+use std::sync::{Arc, Mutex};
+
+use rocket::{Request, Response};
+use rocket::deferred::Deferrer;
+use rocket::fairing::AdHoc;
+use rocket::local::blocking::Client;
+use rocket::response::{self, Responder};
+
+type Log = Arc<Mutex<Vec<String>>>;
+
+struct Logged(&'static str, Log);
+
+impl<'r> Responder<'r, 'static> for Logged {
+    fn respond_to(self, _: &'r Request<'_>) -> response::Result<'static> {
+        let Logged(body, log) = self;
+        Response::build()
+            .sized_body(body.len(), std::io::Cursor::new(body))
+            .on_complete(move || async move { log.lock().unwrap().push("response".into()); })
+            .ok()
+    }
+}
+
+#[get("/")]
+fn index(deferrer: Deferrer<'_>, log: rocket::State<'_, Log>) -> Logged {
+    let handler_log = log.inner().clone();
+    deferrer.defer(move || async move {
+        handler_log.lock().unwrap().push("handler".into());
+    });
+
+    deferrer.defer(|| async { Err::<(), _>("oh no") });
+    deferrer.defer(|| async { panic!("deferred panic") });
+    Logged("index", log.inner().clone())
+}
+
+#[test]
+fn deferred_work_runs_in_order() {
+    let log = Log::default();
+    let fairing_log = log.clone();
+    let rocket = rocket::ignite()
+        .manage(log.clone())
+        .mount("/", routes![index])
+        .attach(AdHoc::on_request("Defer", move |req, _| {
+            let log = fairing_log.clone();
+            Box::pin(async move {
+                req.defer(move || async move { log.lock().unwrap().push("fairing".into()); });
+            })
+        }));
+
+    let client = Client::tracked(rocket).unwrap();
+    let response = client.get("/").dispatch();
+    assert_eq!(response.into_string().unwrap(), "index");
+    assert_eq!(*log.lock().unwrap(), vec!["fairing", "handler", "response"]);
+
+    log.lock().unwrap().clear();
+    client.get("/unknown").dispatch();
+    assert_eq!(*log.lock().unwrap(), vec!["fairing"]);
+}
@@ -0,0 +1,59 @@
+#[macro_use] extern crate rocket;
+
+#[get("/<id>")]
+fn item(id: usize) -> String {
+    id.to_string()
+}
+
+#[get("/panic")]
+fn panics() -> &'static str {
+    panic!("oh no")
+}
+
+mod diagnostic_pages_tests {
+    use super::*;
+    use rocket::Config;
+    use rocket::http::Status;
+    use rocket::local::blocking::Client;
+
+    fn client(config: Config) -> Client {
+        let rocket = rocket::custom(config).mount("/", routes![item, panics]);
+        Client::tracked(rocket).unwrap()
+    }
+
+    #[test]
+    fn debug_shows_diagnostics() {
+        let client = client(Config::debug_default());
+        let response = client.get("/bob").dispatch();
+        assert_eq!(response.status(), Status::NotFound);
+
+        let body = response.into_string().unwrap();
+        assert!(body.contains("<h2>Matching Routes</h2>"));
+        assert!(body.contains("<li>GET /&lt;id&gt;</li>"));
+        assert!(body.contains("<li>forwarded: failed to parse parameter 'id'"));
+        assert!(body.contains("<th align=\"left\">forward limit</th><td>100</td>"));
+
+        let body = client.get("/a/b").dispatch().into_string().unwrap();
+        assert!(body.contains("No mounted route matches this request."));
+    }
+
+    #[test]
+    fn panics_are_reported() {
+        let client = client(Config::debug_default());
+        let response = client.get("/panic").dispatch();
+        assert_eq!(response.status(), Status::InternalServerError);
+
+        let body = response.into_string().unwrap();
+        assert!(body.contains("<li>panicked: oh no</li>"));
+        assert!(body.contains("RUST_BACKTRACE=1"));
+    }
+
+    #[test]
+    fn release_is_terse() {
+        let client = client(Config::release_default());
+        let body = client.get("/panic").dispatch().into_string().unwrap();
+        assert!(body.contains("500: Internal Server Error"));
+        assert!(!body.contains("panicked"));
+        assert!(!body.contains("Configuration"));
+    }
+}
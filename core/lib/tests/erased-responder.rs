@@ -0,0 +1,43 @@
+#[macro_use] extern crate rocket;
+
+use rocket::http::{ContentType, Status};
+use rocket::response::{ErasedResponder, content::{Html, Json}};
+
+#[get("/<kind>")]
+fn dynamic(kind: usize) -> Box<dyn ErasedResponder + Send> {
+    match kind {
+        0 => Box::new("plain"),
+        1 => Box::new(Html(String::from("<b>html</b>"))),
+        2 => Box::new(Some(Json("{}"))),
+        3 => Box::new(Box::new(Status::ImATeapot) as Box<dyn ErasedResponder + Send>),
+        _ => Box::new(Status::NotFound),
+    }
+}
+
+mod erased_responder_tests {
+    use super::*;
+    use rocket::local::blocking::Client;
+
+    #[test]
+    fn boxed_responders_respond() {
+        let client = Client::tracked(rocket::ignite().mount("/", routes![dynamic])).unwrap();
+
+        let response = client.get("/0").dispatch();
+        assert_eq!(response.content_type(), Some(ContentType::Plain));
+        assert_eq!(response.into_string().unwrap(), "plain");
+
+        let response = client.get("/1").dispatch();
+        assert_eq!(response.content_type(), Some(ContentType::HTML));
+        assert_eq!(response.into_string().unwrap(), "<b>html</b>");
+
+        let response = client.get("/2").dispatch();
+        assert_eq!(response.content_type(), Some(ContentType::JSON));
+        assert_eq!(response.into_string().unwrap(), "{}");
+
+        let response = client.get("/3").dispatch();
+        assert_eq!(response.status(), Status::ImATeapot);
+
+        let response = client.get("/4").dispatch();
+        assert_eq!(response.status(), Status::NotFound);
+    }
+}
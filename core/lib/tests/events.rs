@@ -0,0 +1,70 @@
+#[macro_use] extern crate rocket;
+
+use std::sync::{Arc, Mutex};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use rocket::events::Publisher;
+use rocket::fairing::AdHoc;
+use rocket::local::blocking::Client;
+
+struct Registered(String);
+
+struct Served(u16);
+
+#[post("/register", data = "<name>")]
+fn register(name: String, events: Publisher<'_>) -> &'static str {
+    events.publish(Registered(name));
+    "registered"
+}
+
+#[get("/")]
+fn index() -> &'static str {
+    "index"
+}
+
+#[test]
+fn events_reach_subscribers() {
+    let names = Arc::new(Mutex::new(vec![]));
+    let served = Arc::new(AtomicUsize::new(0));
+
+    let (sync_names, async_names) = (names.clone(), names.clone());
+    let counter = served.clone();
+    let rocket = rocket::ignite()
+        .mount("/", routes![register, index])
+        .subscribe(move |event: &Registered| sync_names.lock().unwrap().push(event.0.clone()))
+        .attach(AdHoc::on_attach("Subscriber", |rocket| async move {
+            Ok(rocket.subscribe_async(move |event: Arc<Registered>| {
+                let names = async_names.clone();
+                async move { names.lock().unwrap().push(format!("async {}", event.0)); }
+            }))
+        }))
+        .attach(AdHoc::on_response("Publisher", |req, res| Box::pin(async move {
+            Publisher::of(req).publish(Served(res.status().code));
+        })))
+        .subscribe(move |event: &Served| {
+            assert_eq!(event.0, 200);
+            counter.fetch_add(1, Ordering::SeqCst);
+        });
+
+    let client = Client::tracked(rocket).unwrap();
+    client.post("/register").body("bob").dispatch();
+    client.get("/").dispatch();
+
+    assert_eq!(*names.lock().unwrap(), vec!["bob".to_string(), "async bob".to_string()]);
+    assert_eq!(served.load(Ordering::SeqCst), 2);
+}
+
+#[test]
+fn panicking_subscriber_is_contained() {
+    let delivered = Arc::new(AtomicUsize::new(0));
+    let counter = delivered.clone();
+    let rocket = rocket::ignite()
+        .mount("/", routes![register])
+        .subscribe(|_: &Registered| panic!("subscriber failure"))
+        .subscribe(move |_: &Registered| { counter.fetch_add(1, Ordering::SeqCst); });
+
+    let client = Client::tracked(rocket).unwrap();
+    let response = client.post("/register").body("alice").dispatch();
+    assert_eq!(response.into_string().unwrap(), "registered");
+    assert_eq!(delivered.load(Ordering::SeqCst), 1);
+}
@@ -0,0 +1,38 @@
+#[macro_use] extern crate rocket;
+
+use std::io::Cursor;
+
+use rocket::fairing::AdHoc;
+use rocket::local::blocking::Client;
+use rocket::http::Status;
+use tokio::io::AsyncReadExt;
+
+#[get("/")]
+fn index() -> &'static str {
+    "Hello, world!"
+}
+
+fn rocket() -> rocket::Rocket {
+    rocket::ignite()
+        .mount("/", routes![index])
+        .attach(AdHoc::on_response_body("Truncate", |_, body| Box::pin(body.take(5))))
+        .attach(AdHoc::on_response_body("Append", |_, body| {
+            Box::pin(body.chain(Cursor::new(&b"<!-- footer -->"[..])))
+        }))
+}
+
+#[test]
+fn response_body_fairings_run_in_order() {
+    let client = Client::tracked(rocket()).unwrap();
+    let response = client.get("/").dispatch();
+    assert_eq!(response.status(), Status::Ok);
+    assert_eq!(response.into_string().unwrap(), "Hello<!-- footer -->");
+}
+
+#[test]
+fn response_body_fairings_skip_head() {
+    let client = Client::tracked(rocket()).unwrap();
+    let response = client.head("/").dispatch();
+    assert_eq!(response.status(), Status::Ok);
+    assert!(response.body().map_or(false, |body| body.is_sized()));
+}
@@ -0,0 +1,45 @@
+#[macro_use] extern crate rocket;
+
+#[get("/", rank = 1)]
+fn first() -> Option<&'static str> { None }
+
+#[get("/", rank = 2)]
+fn second() -> Option<&'static str> { None }
+
+#[get("/", rank = 3)]
+fn third() -> &'static str { "third" }
+
+mod forward_limit_tests {
+    use super::*;
+    use rocket::Config;
+    use rocket::http::Status;
+    use rocket::local::blocking::Client;
+
+    fn client(forward_limit: usize) -> Client {
+        let config = Config { forward_limit, ..Config::debug_default() };
+        let rocket = rocket::custom(config).mount("/", routes![first, second, third]);
+        Client::tracked(rocket).unwrap()
+    }
+
+    #[test]
+    fn forwards_within_limit() {
+        let response = client(3).get("/").dispatch();
+        assert_eq!(response.status(), Status::Ok);
+        assert_eq!(response.into_string().unwrap(), "third");
+    }
+
+    #[test]
+    fn forwards_past_limit_fail() {
+        let response = client(2).get("/").dispatch();
+        assert_eq!(response.status(), Status::InternalServerError);
+    }
+
+    #[test]
+    fn head_redispatch_counts_toward_limit() {
+        let response = client(3).head("/").dispatch();
+        assert_eq!(response.status(), Status::Ok);
+
+        let response = client(2).head("/").dispatch();
+        assert_eq!(response.status(), Status::InternalServerError);
+    }
+}
@@ -0,0 +1,129 @@
+#[macro_use] extern crate rocket;
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use rocket::State;
+use rocket::request::{self, Request, FromRequest};
+use rocket::outcome::{IntoOutcome, try_outcome};
+
+#[derive(Default)]
+struct Loads(AtomicUsize);
+
+#[derive(Clone)]
+struct Session(String);
+
+#[derive(Clone)]
+struct User { name: String, admin: bool }
+
+struct AdminUser(User);
+
+struct Cyclic;
+
+#[rocket::async_trait]
+impl<'a, 'r> FromRequest<'a, 'r> for Session {
+    type Error = ();
+
+    async fn from_request(req: &'a Request<'r>) -> request::Outcome<Self, ()> {
+        req.headers().get_one("Session").map(|s| Session(s.into())).or_forward(())
+    }
+}
+
+#[rocket::async_trait]
+impl<'a, 'r> FromRequest<'a, 'r> for User {
+    type Error = ();
+
+    async fn from_request(req: &'a Request<'r>) -> request::Outcome<Self, ()> {
+        let session = try_outcome!(req.guard::<Session>().await);
+        let loads = try_outcome!(req.guard::<State<'_, Loads>>().await);
+        loads.0.fetch_add(1, Ordering::SeqCst);
+        request::Outcome::Success(User { admin: session.0 == "root", name: session.0 })
+    }
+}
+
+#[rocket::async_trait]
+impl<'a, 'r> FromRequest<'a, 'r> for AdminUser {
+    type Error = ();
+
+    async fn from_request(req: &'a Request<'r>) -> request::Outcome<Self, ()> {
+        let user = try_outcome!(req.cached_guard::<User>().await);
+        if user.admin {
+            request::Outcome::Success(AdminUser(user))
+        } else {
+            request::Outcome::Forward(())
+        }
+    }
+}
+
+#[rocket::async_trait]
+impl<'a, 'r> FromRequest<'a, 'r> for Cyclic {
+    type Error = ();
+
+    async fn from_request(req: &'a Request<'r>) -> request::Outcome<Self, ()> {
+        req.guard::<Cyclic>().await
+    }
+}
+
+#[get("/admin", rank = 1)]
+async fn admin(req: &Request<'_>, admin: AdminUser) -> String {
+    // Already cached by the `AdminUser` guard: not loaded a second time.
+    let user = req.cached_guard::<User>().await.unwrap();
+    format!("admin {} {}", admin.0.name, user.name)
+}
+
+#[get("/admin", rank = 2)]
+fn not_admin() -> &'static str {
+    "not admin"
+}
+
+#[get("/cyclic")]
+fn cyclic(_cyclic: Cyclic) { }
+
+#[get("/cyclic/optional")]
+fn optional_cyclic(_cyclic: Option<Cyclic>) -> &'static str {
+    "handled"
+}
+
+mod guard_composition_tests {
+    use super::*;
+    use rocket::http::{Header, Status};
+    use rocket::local::blocking::Client;
+
+    fn client() -> Client {
+        let rocket = rocket::ignite()
+            .mount("/", routes![admin, not_admin, cyclic, optional_cyclic])
+            .manage(Loads::default());
+
+        Client::tracked(rocket).unwrap()
+    }
+
+    fn loads(client: &Client) -> usize {
+        client.rocket().state::<Loads>().unwrap().0.load(Ordering::SeqCst)
+    }
+
+    #[test]
+    fn composed_guards_are_cached() {
+        let client = client();
+        let response = client.get("/admin").header(Header::new("Session", "root")).dispatch();
+        assert_eq!(response.into_string().unwrap(), "admin root root");
+        assert_eq!(loads(&client), 1);
+
+        let response = client.get("/admin").header(Header::new("Session", "bob")).dispatch();
+        assert_eq!(response.into_string().unwrap(), "not admin");
+        assert_eq!(loads(&client), 2);
+
+        let response = client.get("/admin").dispatch();
+        assert_eq!(response.into_string().unwrap(), "not admin");
+        assert_eq!(loads(&client), 2);
+    }
+
+    #[test]
+    fn guard_cycles_are_detected() {
+        let client = client();
+        let response = client.get("/cyclic").dispatch();
+        assert_eq!(response.status(), Status::InternalServerError);
+
+        // The cycle fails the request even if the handler copes with it.
+        let response = client.get("/cyclic/optional").dispatch();
+        assert_eq!(response.status(), Status::InternalServerError);
+    }
+}
@@ -0,0 +1,41 @@
+#[macro_use] extern crate rocket;
+
+use rocket::{Request, Response};
+use rocket::http::{Header, Status, RawStr};
+use rocket::response::{self, Responder};
+
+struct Echo(String);
+
+impl<'r> Responder<'r, 'static> for Echo {
+    fn respond_to(self, _: &'r Request<'_>) -> response::Result<'static> {
+        let mut response = Response::new();
+        response.try_set_header(Header::new("X-Echo", self.0))
+            .map_err(|_| Status::BadRequest)?;
+
+        Ok(response)
+    }
+}
+
+#[get("/?<value>")]
+fn echo(value: &RawStr) -> Echo {
+    Echo(value.url_decode_lossy())
+}
+
+mod header_validation_tests {
+    use super::*;
+    use rocket::local::blocking::Client;
+
+    #[test]
+    fn user_input_cannot_inject_headers() {
+        let client = Client::tracked(rocket::ignite().mount("/", routes![echo])).unwrap();
+
+        let response = client.get("/?value=hello%20there").dispatch();
+        assert_eq!(response.status(), Status::Ok);
+        assert_eq!(response.headers().get_one("X-Echo"), Some("hello there"));
+
+        let response = client.get("/?value=a%0D%0ASet-Cookie:%20x=y").dispatch();
+        assert_eq!(response.status(), Status::BadRequest);
+        assert!(response.headers().get_one("X-Echo").is_none());
+        assert!(response.headers().get_one("Set-Cookie").is_none());
+    }
+}
@@ -0,0 +1,69 @@
+#[macro_use] extern crate rocket;
+
+use std::io;
+use std::net::SocketAddr;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::task::{Context, Poll};
+
+use rocket::Config;
+use rocket::http::Listener;
+use rocket::tokio::net::{TcpListener, TcpStream};
+use rocket::tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+#[get("/")]
+fn index() -> &'static str {
+    "listening"
+}
+
+/// A listener that counts the connections it accepts.
+struct Counting {
+    listener: TcpListener,
+    accepted: Arc<AtomicUsize>,
+}
+
+impl Listener for Counting {
+    type Connection = TcpStream;
+
+    fn local_addr(&self) -> Option<SocketAddr> {
+        self.listener.local_addr().ok()
+    }
+
+    fn poll_accept(&mut self, cx: &mut Context<'_>) -> Poll<io::Result<TcpStream>> {
+        self.listener.poll_accept(cx).map_ok(|(stream, _)| {
+            self.accepted.fetch_add(1, Ordering::SeqCst);
+            stream
+        })
+    }
+}
+
+async fn get(port: u16) -> String {
+    let mut stream = TcpStream::connect(("127.0.0.1", port)).await.unwrap();
+    let request = "GET / HTTP/1.1\r\nHost: localhost\r\nConnection: close\r\n\r\n";
+    stream.write_all(request.as_bytes()).await.unwrap();
+
+    let mut response = String::new();
+    stream.read_to_string(&mut response).await.unwrap();
+    response
+}
+
+#[rocket::async_test]
+async fn launch_on_serves_custom_listener() {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let port = listener.local_addr().unwrap().port();
+    let accepted = Arc::new(AtomicUsize::new(0));
+    let listener = Counting { listener, accepted: accepted.clone() };
+
+    // The configured port is ignored in favor of the listener's.
+    let config = Config { port: 1, ctrlc: false, ..Config::debug_default() };
+    let rocket = rocket::custom(config).mount("/", routes![index]).ignited().await.unwrap();
+    let shutdown = rocket.shutdown();
+    let server = rocket::tokio::spawn(rocket.launch_on(listener));
+
+    assert!(get(port).await.ends_with("\r\n\r\nlistening"));
+    assert!(get(port).await.ends_with("\r\n\r\nlistening"));
+    assert_eq!(accepted.load(Ordering::SeqCst), 2);
+
+    shutdown.shutdown();
+    server.await.unwrap().unwrap();
+}
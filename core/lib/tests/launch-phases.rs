@@ -0,0 +1,62 @@
+#[macro_use] extern crate rocket;
+
+use rocket::{Rocket, Orbit};
+use rocket::error::ErrorKind;
+use rocket::fairing::AdHoc;
+
+#[get("/")]
+fn index() -> &'static str { "index" }
+
+#[get("/")]
+fn other() -> &'static str { "other" }
+
+#[rocket::async_test]
+async fn ignited_rocket_is_inspectable() {
+    let rocket = rocket::ignite()
+        .mount("/", routes![index])
+        .manage(7usize)
+        .ignited()
+        .await
+        .expect("no collisions or failures");
+
+    assert_eq!(rocket.routes().count(), 1);
+    assert_eq!(rocket.state::<usize>(), Some(&7));
+}
+
+#[rocket::async_test]
+async fn ignition_fails_on_collisions() {
+    let result = rocket::ignite()
+        .mount("/", routes![index, other])
+        .ignited()
+        .await;
+
+    match result {
+        Err(e) => assert!(matches!(e.kind(), ErrorKind::Collision(..))),
+        Ok(_) => panic!("ignited with colliding routes"),
+    }
+}
+
+#[rocket::async_test]
+async fn ignition_fails_on_failed_attach_fairing() {
+    let result = rocket::ignite()
+        .attach(AdHoc::on_attach("Fail", |rocket| async { Err(rocket) }))
+        .ignited()
+        .await;
+
+    match result {
+        Err(e) => assert!(matches!(e.kind(), ErrorKind::FailedFairings(..))),
+        Ok(_) => panic!("ignited with a failed fairing"),
+    }
+}
+
+#[test]
+fn local_client_rocket_is_in_orbit() {
+    fn route_count(rocket: &Rocket<Orbit>) -> usize {
+        rocket.routes().count()
+    }
+
+    let rocket = rocket::ignite().mount("/", routes![index]);
+    let client = rocket::local::blocking::Client::tracked(rocket).unwrap();
+
+    assert_eq!(route_count(client.rocket()), 1);
+}
@@ -0,0 +1,73 @@
+#[macro_use] extern crate rocket;
+
+use std::time::Duration;
+
+use rocket::State;
+use rocket::http::Status;
+use rocket::local::asynchronous::Client;
+use rocket::response::{LongPoll, Update};
+
+type Counter = LongPoll<usize>;
+
+#[get("/count?<since>")]
+async fn count(counter: State<'_, Counter>, since: u64) -> Update<String> {
+    counter.wait(since, Duration::from_millis(250)).await.map(|n| n.to_string())
+}
+
+#[post("/count")]
+fn increment(counter: State<'_, Counter>) {
+    counter.update(|n| *n += 1);
+}
+
+async fn client() -> Client {
+    let rocket = rocket::ignite()
+        .manage(Counter::new(0))
+        .mount("/", routes![count, increment]);
+
+    Client::tracked(rocket).await.unwrap()
+}
+
+#[rocket::async_test]
+async fn returns_current_value_when_stale() {
+    let client = client().await;
+    let response = client.get("/count?since=0").dispatch().await;
+    assert_eq!(response.status(), Status::Ok);
+    assert_eq!(response.headers().get_one(Counter::HEADER), Some("1"));
+    assert_eq!(response.into_string().await.unwrap(), "0");
+}
+
+#[rocket::async_test]
+async fn returns_current_value_when_ahead() {
+    let client = client().await;
+    let response = client.get("/count?since=7").dispatch().await;
+    assert_eq!(response.status(), Status::Ok);
+    assert_eq!(response.headers().get_one(Counter::HEADER), Some("1"));
+    assert_eq!(response.into_string().await.unwrap(), "0");
+}
+
+#[rocket::async_test]
+async fn times_out_without_change() {
+    let client = client().await;
+    let response = client.get("/count?since=1").dispatch().await;
+    assert_eq!(response.status(), Status::NoContent);
+    assert_eq!(response.headers().get_one(Counter::HEADER), Some("1"));
+    assert!(response.into_string().await.is_none());
+}
+
+#[rocket::async_test]
+async fn wakes_on_change() {
+    let client = client().await;
+    let waiting = async {
+        let response = client.get("/count?since=1").dispatch().await;
+        assert_eq!(response.headers().get_one(Counter::HEADER), Some("2"));
+        response.into_string().await.unwrap()
+    };
+
+    let change = async {
+        rocket::tokio::time::delay_for(Duration::from_millis(50)).await;
+        client.post("/count").dispatch().await;
+    };
+
+    let (body, ()) = rocket::tokio::join!(waiting, change);
+    assert_eq!(body, "1");
+}
@@ -0,0 +1,108 @@
+use rocket::{Request, Data, Route, Catcher};
+use rocket::http::{Method, MediaType, Status};
+use rocket::handler::{Outcome, HandlerFuture};
+use rocket::catcher::ErrorHandlerFuture;
+use rocket::response::{Responder, status::Custom};
+
+fn echo<'r>(req: &'r Request, data: Data) -> HandlerFuture<'r> {
+    let id = match req.get_param_by_name::<usize>("id") {
+        Some(Ok(id)) => id,
+        _ => return Outcome::forward(data).pin(),
+    };
+
+    let name = req.route().and_then(|r| r.name).unwrap_or("unnamed");
+    Outcome::from(req, format!("{}: {}", name, id)).pin()
+}
+
+fn fallback<'r>(req: &'r Request, _: Data) -> HandlerFuture<'r> {
+    let path = req.get_segments_by_name::<std::path::PathBuf>("path")
+        .and_then(|r| r.ok())
+        .unwrap_or_default();
+
+    Outcome::from(req, format!("fallback: {}", path.display())).pin()
+}
+
+fn not_found<'r>(status: Status, req: &'r Request<'_>) -> ErrorHandlerFuture<'r> {
+    let res = Custom(status, format!("missing: {}", req.uri()));
+    Box::pin(async move { res.respond_to(req) })
+}
+
+mod manual_routes_tests {
+    use super::*;
+    use rocket::http::ContentType;
+    use rocket::local::blocking::Client;
+
+    fn client() -> Client {
+        let routes = vec![
+            Route::builder(Method::Post, "/<id>", echo)
+                .format(MediaType::JSON)
+                .name("json_echo")
+                .build(),
+            Route::builder(Method::Post, "/<id>", echo).rank(2).name("echo").build(),
+            Route::builder(Method::Post, "/<path..>", fallback).rank(3).build(),
+        ];
+
+        let rocket = rocket::ignite()
+            .mount("/", routes)
+            .register(vec![Catcher::new(404, not_found)]);
+
+        Client::tracked(rocket).unwrap()
+    }
+
+    #[test]
+    fn builder_sets_format_rank_and_name() {
+        let route = Route::builder(Method::Get, "/<id>", echo).build();
+        assert_eq!(route.rank, -1);
+        assert_eq!(route.format, None);
+        assert_eq!(route.name, None);
+
+        let route = Route::builder(Method::Post, "/<id>", echo)
+            .format(MediaType::JSON)
+            .rank(7)
+            .name("echo")
+            .build();
+
+        assert_eq!(route.rank, 7);
+        assert_eq!(route.format, Some(MediaType::JSON));
+        assert_eq!(route.name, Some("echo"));
+    }
+
+    #[test]
+    fn manual_routes_dispatch() {
+        let client = client();
+        let response = client.post("/10").header(ContentType::JSON).dispatch();
+        assert_eq!(response.into_string().unwrap(), "json_echo: 10");
+
+        let response = client.post("/10").dispatch();
+        assert_eq!(response.into_string().unwrap(), "echo: 10");
+
+        let response = client.post("/ten").dispatch();
+        assert_eq!(response.into_string().unwrap(), "fallback: ten");
+
+        let response = client.post("/a/b").dispatch();
+        assert_eq!(response.into_string().unwrap(), "fallback: a/b");
+    }
+
+    #[test]
+    fn manual_catchers_are_used() {
+        let response = client().get("/nothing").dispatch();
+        assert_eq!(response.status(), Status::NotFound);
+        assert_eq!(response.into_string().unwrap(), "missing: /nothing");
+    }
+
+    #[test]
+    fn closure_routes() {
+        use rocket::handler::from_fn;
+
+        let rocket = rocket::ignite().mount("/", vec![
+            Route::new(Method::Get, "/ping", from_fn(|_, _| "pong")),
+            Route::new(Method::Get, "/uri", from_fn(|req, _| req.uri().to_string())),
+            Route::new(Method::Get, "/teapot", from_fn(|_, _| Status::ImATeapot)),
+        ]);
+
+        let client = Client::tracked(rocket).unwrap();
+        assert_eq!(client.get("/ping").dispatch().into_string().unwrap(), "pong");
+        assert_eq!(client.get("/uri?a=b").dispatch().into_string().unwrap(), "/uri?a=b");
+        assert_eq!(client.get("/teapot").dispatch().status(), Status::ImATeapot);
+    }
+}
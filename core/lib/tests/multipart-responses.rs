@@ -0,0 +1,124 @@
+#[macro_use] extern crate rocket;
+
+use rocket::http::{ContentType, Header};
+use rocket::response::{ByteRanges, Multipart, Part};
+
+#[get("/mixed")]
+fn mixed() -> Multipart<'static> {
+    Multipart::mixed()
+        .part(Part::new(&b"{ \"id\": 1 }"[..]).content_type(ContentType::JSON))
+        .part(Part::new(&b"second"[..]).header(Header::new("Content-ID", "<two>")))
+}
+
+#[get("/bytes")]
+fn bytes() -> ByteRanges<&'static str> {
+    ByteRanges("0123456789")
+}
+
+mod multipart_response_tests {
+    use super::*;
+    use rocket::http::Status;
+    use rocket::local::blocking::{Client, LocalResponse};
+
+    fn client() -> Client {
+        Client::tracked(rocket::ignite().mount("/", routes![mixed, bytes])).unwrap()
+    }
+
+    fn boundary(response: &LocalResponse<'_>) -> String {
+        let content_type = response.content_type().unwrap();
+        content_type.params()
+            .find(|&(key, _)| key == "boundary")
+            .map(|(_, value)| value.to_string())
+            .unwrap()
+    }
+
+    fn range(client: &Client, range: &'static str) -> LocalResponse<'_> {
+        client.get("/bytes").header(Header::new("Range", range)).dispatch()
+    }
+
+    #[test]
+    fn mixed_parts_are_delimited() {
+        let client = client();
+        let response = client.get("/mixed").dispatch();
+        assert_eq!(response.content_type().unwrap().sub(), "mixed");
+
+        let b = boundary(&response);
+        assert_eq!(response.into_string().unwrap(), format!("--{b}\r\n\
+            Content-Type: application/json\r\n\r\n{{ \"id\": 1 }}\r\n\
+            --{b}\r\nContent-ID: <two>\r\n\r\nsecond\r\n--{b}--\r\n", b = b));
+    }
+
+    #[test]
+    fn boundaries_are_unique() {
+        let client = client();
+        let first = boundary(&client.get("/mixed").dispatch());
+        let second = boundary(&client.get("/mixed").dispatch());
+        assert_ne!(first, second);
+    }
+
+    #[test]
+    fn full_body_without_range() {
+        let client = client();
+        let response = client.get("/bytes").dispatch();
+        assert_eq!(response.status(), Status::Ok);
+        assert_eq!(response.headers().get_one("Accept-Ranges"), Some("bytes"));
+        assert_eq!(response.into_string().unwrap(), "0123456789");
+
+        let response = range(&client, "lines=1-2");
+        assert_eq!(response.status(), Status::Ok);
+        assert_eq!(response.into_string().unwrap(), "0123456789");
+    }
+
+    #[test]
+    fn single_range() {
+        let client = client();
+        let response = range(&client, "bytes=2-4");
+        assert_eq!(response.status(), Status::PartialContent);
+        assert_eq!(response.headers().get_one("Content-Range"), Some("bytes 2-4/10"));
+        assert_eq!(response.headers().get_one("Content-Length"), Some("3"));
+        assert_eq!(response.into_string().unwrap(), "234");
+
+        let response = range(&client, "bytes=-3");
+        assert_eq!(response.headers().get_one("Content-Range"), Some("bytes 7-9/10"));
+        assert_eq!(response.into_string().unwrap(), "789");
+    }
+
+    #[test]
+    fn multiple_ranges() {
+        let client = client();
+        let response = range(&client, "bytes=0-1, 8-");
+        assert_eq!(response.status(), Status::PartialContent);
+        assert_eq!(response.content_type().unwrap().sub(), "byteranges");
+
+        let b = boundary(&response);
+        let length: usize = response.headers().get_one("Content-Length").unwrap().parse().unwrap();
+        let body = response.into_string().unwrap();
+        assert_eq!(body.len(), length);
+        assert_eq!(body, format!("--{b}\r\n\
+            Content-Type: text/plain; charset=utf-8\r\nContent-Range: bytes 0-1/10\r\n\r\n01\r\n\
+            --{b}\r\n\
+            Content-Type: text/plain; charset=utf-8\r\nContent-Range: bytes 8-9/10\r\n\r\n89\r\n\
+            --{b}--\r\n", b = b));
+    }
+
+    #[test]
+    fn unsatisfiable_range() {
+        let client = client();
+        let response = range(&client, "bytes=10-20");
+        assert_eq!(response.status(), Status::RangeNotSatisfiable);
+        assert_eq!(response.headers().get_one("Content-Range"), Some("bytes */10"));
+        assert!(response.into_string().unwrap_or_default().is_empty());
+    }
+
+    #[test]
+    fn mismatched_if_range_sends_full_body() {
+        let client = client();
+        let response = client.get("/bytes")
+            .header(Header::new("Range", "bytes=0-1"))
+            .header(Header::new("If-Range", "\"some-etag\""))
+            .dispatch();
+
+        assert_eq!(response.status(), Status::Ok);
+        assert_eq!(response.into_string().unwrap(), "0123456789");
+    }
+}
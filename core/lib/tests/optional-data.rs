@@ -0,0 +1,59 @@
+#[macro_use] extern crate rocket;
+
+use rocket::data::{Data, ToByteUnit};
+
+#[patch("/optional", data = "<body>")]
+async fn optional(body: Option<Data>) -> String {
+    match body {
+        Some(body) => {
+            let body = body.open(1.kibibytes()).stream_to_string().await.unwrap();
+            format!("body: {}", body)
+        }
+        None => "no body".into(),
+    }
+}
+
+#[post("/data", data = "<data>")]
+fn data(data: Option<Data>) -> &'static str {
+    match data {
+        Some(data) if data.is_empty() => "unreachable",
+        Some(_) => "data",
+        None => "none",
+    }
+}
+
+#[put("/empty", data = "<data>")]
+async fn empty(mut data: Data) -> String {
+    let before = data.is_empty();
+    data.peek(512).await;
+    format!("{} {}", before, data.is_empty())
+}
+
+mod optional_data_tests {
+    use super::*;
+    use rocket::local::blocking::Client;
+
+    fn client() -> Client {
+        Client::tracked(rocket::ignite().mount("/", routes![optional, data, empty])).unwrap()
+    }
+
+    #[test]
+    fn option_is_none_without_body() {
+        let client = client();
+        assert_eq!(client.patch("/optional").dispatch().into_string().unwrap(), "no body");
+        assert_eq!(client.patch("/optional").body("").dispatch().into_string().unwrap(), "no body");
+
+        let response = client.patch("/optional").body("{}").dispatch();
+        assert_eq!(response.into_string().unwrap(), "body: {}");
+
+        assert_eq!(client.post("/data").dispatch().into_string().unwrap(), "none");
+        assert_eq!(client.post("/data").body("x").dispatch().into_string().unwrap(), "data");
+    }
+
+    #[test]
+    fn data_reports_emptiness() {
+        let client = client();
+        assert_eq!(client.put("/empty").dispatch().into_string().unwrap(), "true true");
+        assert_eq!(client.put("/empty").body("x").dispatch().into_string().unwrap(), "false false");
+    }
+}
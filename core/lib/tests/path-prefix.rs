@@ -0,0 +1,74 @@
+#[macro_use] extern crate rocket;
+
+use rocket::{Config, Request};
+use rocket::response::Redirect;
+use rocket::local::blocking::Client;
+
+#[get("/")]
+fn index() -> &'static str {
+    "index"
+}
+
+#[get("/hello/<name>")]
+fn hello(name: String) -> String {
+    format!("Hello, {}!", name)
+}
+
+#[get("/go")]
+fn go() -> Redirect {
+    Redirect::to(uri!(hello: "bob"))
+}
+
+#[get("/link")]
+fn link(request: &Request<'_>) -> String {
+    request.prefixed_uri(uri!(hello: "bob")).to_string()
+}
+
+fn client(path_prefix: Option<&str>) -> Client {
+    let config = Config { path_prefix: path_prefix.map(String::from), ..Config::debug_default() };
+    Client::tracked(rocket::custom(config).mount("/", routes![index, hello, go, link])).unwrap()
+}
+
+mod path_prefix_tests {
+    use super::*;
+    use rocket::http::Status;
+
+    #[test]
+    fn prefix_is_stripped() {
+        for prefix in &["/app", "/app/", "app"] {
+            let client = client(Some(*prefix));
+            let response = client.get("/app/hello/alice?x=1").dispatch();
+            assert_eq!(response.into_string().unwrap(), "Hello, alice!");
+
+            assert_eq!(client.get("/app").dispatch().into_string().unwrap(), "index");
+            assert_eq!(client.get("/app/").dispatch().into_string().unwrap(), "index");
+        }
+    }
+
+    #[test]
+    fn unprefixed_paths_are_untouched() {
+        let client = client(Some("/app"));
+        let response = client.get("/hello/alice").dispatch();
+        assert_eq!(response.into_string().unwrap(), "Hello, alice!");
+
+        let response = client.get("/application/hello/alice").dispatch();
+        assert_eq!(response.status(), Status::NotFound);
+    }
+
+    #[test]
+    fn prefix_is_added_to_redirects_and_links() {
+        let client = client(Some("/app"));
+        let response = client.get("/app/go").dispatch();
+        assert_eq!(response.headers().get_one("Location"), Some("/app/hello/bob"));
+
+        let response = client.get("/app/link").dispatch();
+        assert_eq!(response.into_string().unwrap(), "/app/hello/bob");
+
+        let client = super::client(None);
+        let response = client.get("/go").dispatch();
+        assert_eq!(response.headers().get_one("Location"), Some("/hello/bob"));
+
+        let response = client.get("/link").dispatch();
+        assert_eq!(response.into_string().unwrap(), "/hello/bob");
+    }
+}
@@ -0,0 +1,72 @@
+#[macro_use] extern crate rocket;
+
+use std::net::SocketAddr;
+use std::time::Duration;
+
+use rocket::Config;
+use rocket::fairing::AdHoc;
+use rocket::tokio::sync::oneshot;
+use rocket::tokio::time::timeout;
+use rocket::tokio::net::TcpStream;
+use rocket::tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+#[get("/")]
+fn remote(remote: SocketAddr) -> String {
+    remote.to_string()
+}
+
+async fn get(port: u16, header: &[u8]) -> String {
+    let mut stream = TcpStream::connect(("127.0.0.1", port)).await.unwrap();
+    let request = b"GET / HTTP/1.1\r\nHost: localhost\r\nConnection: close\r\n\r\n";
+    stream.write_all(&[header, &request[..]].concat()).await.unwrap();
+
+    let mut response = String::new();
+    let _ = stream.read_to_string(&mut response).await;
+    response
+}
+
+#[rocket::async_test]
+async fn proxy_protocol_sets_remote() {
+    let (tx, rx) = oneshot::channel();
+    let config = Config {
+        port: 0,
+        proxy_protocol: true,
+        ctrlc: false,
+        ..Config::debug_default()
+    };
+
+    let rocket = rocket::custom(config)
+        .mount("/", routes![remote])
+        .attach(AdHoc::on_launch("Port", move |rocket| {
+            tx.send(rocket.config().port).unwrap();
+        }));
+
+    let rocket = rocket.ignited().await.unwrap();
+    let shutdown = rocket.shutdown();
+    let server = rocket::tokio::spawn(rocket.launch());
+    let port = rx.await.unwrap();
+
+    // A connection that never sends its header doesn't hold up others.
+    let _idle = TcpStream::connect(("127.0.0.1", port)).await.unwrap();
+    let header = b"PROXY TCP4 192.0.2.1 198.51.100.1 56324 443\r\n";
+    let response = timeout(Duration::from_secs(2), get(port, header)).await
+        .expect("idle connection doesn't block accepts");
+    assert!(response.ends_with("\r\n\r\n192.0.2.1:56324"));
+
+    let mut v2 = b"\r\n\r\n\0\r\nQUIT\n\x21\x21\x00\x24".to_vec();
+    v2.extend_from_slice(&"2001:db8::1".parse::<std::net::Ipv6Addr>().unwrap().octets());
+    v2.extend_from_slice(&[0; 16]);
+    v2.extend_from_slice(&[0x12, 0x67, 0x01, 0xBB]);
+    let response = get(port, &v2).await;
+    assert!(response.ends_with("\r\n\r\n[2001:db8::1]:4711"));
+
+    // The proxy's own connections keep their address.
+    let response = get(port, b"PROXY UNKNOWN\r\n").await;
+    assert!(response.contains("\r\n\r\n127.0.0.1:"));
+
+    // Connections without a header are dropped.
+    assert!(get(port, b"").await.is_empty());
+
+    shutdown.shutdown();
+    server.await.unwrap().unwrap();
+}
@@ -0,0 +1,62 @@
+#[macro_use] extern crate rocket;
+
+use rocket::http::Status;
+
+#[get("/<code>")]
+fn fail(code: u16) -> Status {
+    Status::from_code(code).unwrap_or(Status::raw(code))
+}
+
+#[catch(404)]
+fn not_found() -> &'static str {
+    "not found"
+}
+
+#[catch(400..500)]
+fn client_error(status: Status) -> String {
+    format!("client error {}", status.code)
+}
+
+#[catch(420..=429)]
+fn narrow(status: Status) -> String {
+    format!("narrow {}", status.code)
+}
+
+#[catch(default)]
+fn default() -> &'static str {
+    "default"
+}
+
+mod range_catcher_tests {
+    use super::*;
+    use rocket::local::blocking::Client;
+
+    fn body_for(client: &Client, code: u16) -> String {
+        let response = client.get(format!("/{}", code)).dispatch();
+        assert_eq!(response.status().code, code);
+        response.into_string().unwrap()
+    }
+
+    #[test]
+    fn exact_then_narrowest_range_then_default() {
+        let catchers = catchers![not_found, client_error, narrow, default];
+        let client = Client::tracked(rocket::ignite()
+            .mount("/", routes![fail])
+            .register(catchers)).unwrap();
+
+        assert_eq!(body_for(&client, 404), "not found");
+        assert_eq!(body_for(&client, 403), "client error 403");
+        assert_eq!(body_for(&client, 499), "client error 499");
+        assert_eq!(body_for(&client, 422), "narrow 422");
+        assert_eq!(body_for(&client, 429), "narrow 429");
+        assert_eq!(body_for(&client, 500), "default");
+    }
+
+    #[test]
+    fn catchers_report_their_ranges() {
+        let catchers = catchers![client_error, narrow];
+        assert_eq!(catchers[0].code, None);
+        assert_eq!(catchers[0].range, Some(400..500));
+        assert_eq!(catchers[1].range, Some(420..430));
+    }
+}
@@ -0,0 +1,113 @@
+#![cfg(feature = "decompression")]
+
+#[macro_use] extern crate rocket;
+
+use rocket::Request;
+use rocket::data::{Data, ToByteUnit};
+
+/// `Hello, compressed world!`, gzipped.
+const GZIP: &[u8] = &[
+    0x1f, 0x8b, 0x08, 0x00, 0x00, 0x00, 0x00, 0x00, 0x02, 0x03, 0xf3, 0x48, 0xcd, 0xc9, 0xc9,
+    0xd7, 0x51, 0x48, 0xce, 0xcf, 0x2d, 0x28, 0x4a, 0x2d, 0x2e, 0x4e, 0x4d, 0x51, 0x28, 0xcf,
+    0x2f, 0xca, 0x49, 0x51, 0x04, 0x00, 0x05, 0xbd, 0x53, 0x6e, 0x18, 0x00, 0x00, 0x00
+];
+
+/// `Hello, deflated world!`, zlib-encoded.
+const DEFLATE: &[u8] = &[
+    0x78, 0x9c, 0xf3, 0x48, 0xcd, 0xc9, 0xc9, 0xd7, 0x51, 0x48, 0x49, 0x4d, 0xcb, 0x49, 0x2c,
+    0x49, 0x4d, 0x51, 0x28, 0xcf, 0x2f, 0xca, 0x49, 0x51, 0x04, 0x00, 0x5a, 0x87, 0x07, 0xe3
+];
+
+/// `Hello, brotli world!` in an uncompressed brotli meta-block.
+const BROTLI: &[u8] = &[
+    0x30, 0x01, 0x10, b'H', b'e', b'l', b'l', b'o', b',', b' ', b'b', b'r', b'o', b't', b'l',
+    b'i', b' ', b'w', b'o', b'r', b'l', b'd', b'!', 0x03
+];
+
+/// 64KiB of zeroes, gzipped into 96 bytes.
+const ZEROES: &[u8] = &[
+    0x1f, 0x8b, 0x08, 0x00, 0x00, 0x00, 0x00, 0x00, 0x02, 0x03, 0xed, 0xc1, 0x01, 0x01, 0x00,
+    0x00, 0x00, 0x80, 0x90, 0xfe, 0xaf, 0xee, 0x08, 0x0a, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+    0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+    0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+    0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+    0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x6a, 0xeb, 0x8e, 0x97, 0xd7, 0x00, 0x00, 0x01, 0x00
+];
+
+#[post("/echo", data = "<body>")]
+async fn echo(req: &Request<'_>, body: Data) -> String {
+    let encoding = req.headers().get_one("Content-Encoding").unwrap_or("none");
+    match body.open(1.kibibytes()).stream_to_vec().await {
+        Ok(bytes) => format!("{} {} {}", encoding, bytes.len(), String::from_utf8_lossy(&bytes)),
+        Err(e) => format!("error: {:?}", e.kind()),
+    }
+}
+
+mod request_decompression_tests {
+    use super::*;
+    use rocket::Config;
+    use rocket::http::Header;
+    use rocket::local::blocking::Client;
+
+    fn post(client: &Client, encoding: &'static str, body: &[u8]) -> String {
+        client.post("/echo")
+            .header(Header::new("Content-Encoding", encoding))
+            .body(body)
+            .dispatch()
+            .into_string()
+            .unwrap()
+    }
+
+    fn client(decompress: bool) -> Client {
+        let config = Config { decompress, ..Config::debug_default() };
+        Client::untracked(rocket::custom(config).mount("/", routes![echo])).unwrap()
+    }
+
+    #[test]
+    fn encoded_bodies_are_decoded() {
+        let client = client(true);
+        assert_eq!(post(&client, "gzip", GZIP), "none 24 Hello, compressed world!");
+        assert_eq!(post(&client, "deflate", DEFLATE), "none 22 Hello, deflated world!");
+        assert_eq!(post(&client, "br", BROTLI), "none 20 Hello, brotli world!");
+    }
+
+    #[test]
+    fn limits_apply_to_decoded_body() {
+        let client = client(true);
+        let response = post(&client, "gzip", ZEROES);
+        assert!(response.starts_with("none 1024 "));
+    }
+
+    #[test]
+    fn invalid_bodies_fail_to_read() {
+        let client = client(true);
+        assert_eq!(post(&client, "gzip", b"not gzip at all"), "error: InvalidData");
+        assert_eq!(post(&client, "gzip", &GZIP[..20]), "error: UnexpectedEof");
+    }
+
+    #[test]
+    fn decoding_can_be_disabled() {
+        let client = client(false);
+        let response = post(&client, "gzip", GZIP);
+        assert!(response.starts_with("gzip 44 "));
+    }
+
+    #[test]
+    fn decoding_can_be_limited_to_formats() {
+        use rocket::http::ContentType;
+
+        let decompress_formats = vec!["json".into()];
+        let config = Config { decompress_formats, ..Config::debug_default() };
+        let client = Client::untracked(rocket::custom(config).mount("/", routes![echo])).unwrap();
+        let post = |content_type: ContentType| client.post("/echo")
+            .header(content_type)
+            .header(Header::new("Content-Encoding", "gzip"))
+            .body(GZIP)
+            .dispatch()
+            .into_string()
+            .unwrap();
+
+        assert_eq!(post(ContentType::JSON), "none 24 Hello, compressed world!");
+        assert!(post(ContentType::Plain).starts_with("gzip 44 "));
+    }
+}
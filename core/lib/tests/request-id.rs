@@ -0,0 +1,47 @@
+#[macro_use] extern crate rocket;
+
+use rocket::Config;
+use rocket::http::Header;
+use rocket::local::blocking::Client;
+use rocket::request::RequestId;
+
+#[get("/")]
+fn id(id: &RequestId) -> String {
+    id.to_string()
+}
+
+fn client(request_id: bool) -> Client {
+    let config = Config { request_id, ..Config::debug_default() };
+    Client::tracked(rocket::custom(config).mount("/", routes![id])).unwrap()
+}
+
+#[test]
+fn request_id_is_echoed() {
+    let client = client(true);
+    let response = client.get("/").dispatch();
+    let header = response.headers().get_one(RequestId::HEADER).map(|s| s.to_string());
+    let body = response.into_string().unwrap();
+    assert_eq!(body.len(), 26);
+    assert_eq!(header, Some(body));
+}
+
+#[test]
+fn request_id_is_propagated() {
+    let client = client(true);
+    let response = client.get("/").header(Header::new("X-Request-Id", "abc-123")).dispatch();
+    assert_eq!(response.headers().get_one(RequestId::HEADER), Some("abc-123"));
+    assert_eq!(response.into_string().unwrap(), "abc-123");
+
+    let response = client.get("/").header(Header::new("X-Request-Id", "a b")).dispatch();
+    let id = response.into_string().unwrap();
+    assert_ne!(id, "a b");
+    assert_eq!(id.len(), 26);
+}
+
+#[test]
+fn request_id_disabled() {
+    let client = client(false);
+    let response = client.get("/").header(Header::new("X-Request-Id", "abc-123")).dispatch();
+    assert!(response.headers().get_one(RequestId::HEADER).is_none());
+    assert_eq!(response.into_string().unwrap(), "abc-123");
+}
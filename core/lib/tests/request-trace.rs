@@ -0,0 +1,83 @@
+#[macro_use] extern crate rocket;
+
+use rocket::Request;
+use rocket::request::{self, FromRequest};
+
+struct Admin;
+
+#[rocket::async_trait]
+impl<'a, 'r> FromRequest<'a, 'r> for Admin {
+    type Error = &'static str;
+
+    async fn from_request(_: &'a Request<'r>) -> request::Outcome<Self, Self::Error> {
+        request::Outcome::Forward(())
+    }
+}
+
+#[get("/<id>")]
+fn by_id(id: usize, _admin: Admin) -> String {
+    id.to_string()
+}
+
+#[get("/<name>", rank = 2)]
+fn by_name(name: String, _admin: Admin) -> String {
+    name
+}
+
+#[catch(404)]
+fn not_found(req: &Request<'_>) -> String {
+    req.trace().to_string()
+}
+
+mod request_trace_tests {
+    use super::*;
+    use rocket::Config;
+    use rocket::http::{Accept, Status};
+    use rocket::local::blocking::Client;
+
+    fn client(trace: bool) -> Client {
+        let config = Config { trace, diagnostics: false, ..Config::debug_default() };
+        let rocket = rocket::custom(config).mount("/", routes![by_id, by_name]);
+        Client::tracked(rocket).unwrap()
+    }
+
+    #[test]
+    fn trace_in_default_catcher() {
+        let client = client(true);
+        let response = client.get("/bob").dispatch();
+        assert_eq!(response.status(), Status::NotFound);
+
+        let body = response.into_string().unwrap();
+        assert!(body.contains("1. matched GET /&lt;id&gt;"));
+        assert!(body.contains("2. forwarded: failed to parse parameter 'id'"));
+        assert!(body.contains("3. matched GET /&lt;name&gt; [2]"));
+        assert!(body.contains("4. forwarded: request guard `request_trace::Admin` forwarded"));
+        assert!(body.contains("5. no further matching routes"));
+
+        let response = client.get("/bob").header(Accept::JSON).dispatch();
+        let body = response.into_string().unwrap();
+        assert!(body.contains(r#""trace": ["matched GET /<id>", "#));
+        assert!(body.contains(r#""no further matching routes"]"#));
+    }
+
+    #[test]
+    fn trace_disabled() {
+        let client = client(false);
+        let body = client.get("/bob").dispatch().into_string().unwrap();
+        assert!(!body.contains("matched"));
+        assert!(!body.contains("<pre"));
+    }
+
+    #[test]
+    fn trace_in_custom_catcher() {
+        let rocket = rocket::custom(Config { trace: true, ..Config::debug_default() })
+            .mount("/", routes![by_id])
+            .register(catchers![not_found]);
+
+        let client = Client::tracked(rocket).unwrap();
+        let body = client.get("/1").dispatch().into_string().unwrap();
+        assert_eq!(body, "1. matched GET /<id>\n\
+            2. forwarded: request guard `request_trace::Admin` forwarded: ()\n\
+            3. no further matching routes\n");
+    }
+}
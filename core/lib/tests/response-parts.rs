@@ -0,0 +1,72 @@
+#[macro_use] extern crate rocket;
+
+use rocket::{Config, Request};
+use rocket::http::{Cookie, CookieJar, Header, Status};
+use rocket::response::ResponseParts;
+
+#[get("/fail")]
+fn fail(jar: &CookieJar<'_>, parts: &ResponseParts) -> Status {
+    jar.add(Cookie::new("visited", "1"));
+    parts.set_header(Header::new("X-Committed", "handler"));
+    Status::Unauthorized
+}
+
+#[get("/ok")]
+fn ok(parts: &ResponseParts) -> &'static str {
+    parts.adjoin_header(Header::new("X-Committed", "ok"));
+    "ok"
+}
+
+#[catch(401)]
+fn unauthorized(req: &Request<'_>) -> &'static str {
+    req.cookies().add(Cookie::new("catcher", "1"));
+    req.response_parts().remove_cookie(Cookie::named("session"));
+    "unauthorized"
+}
+
+mod response_parts_tests {
+    use super::*;
+    use rocket::local::blocking::{Client, LocalResponse};
+
+    fn client(error_cookies: bool) -> Client {
+        let config = Config { error_cookies, ..Config::debug_default() };
+        let rocket = rocket::custom(config)
+            .mount("/", routes![fail, ok])
+            .register(catchers![unauthorized]);
+
+        Client::untracked(rocket).unwrap()
+    }
+
+    fn set_cookies(response: &LocalResponse<'_>) -> Vec<String> {
+        let mut cookies: Vec<_> = response.headers().get("Set-Cookie")
+            .map(|c| c.split(';').next().unwrap().to_string())
+            .collect();
+
+        cookies.sort();
+        cookies
+    }
+
+    #[test]
+    fn committed_parts_reach_error_responses() {
+        let client = client(false);
+        let response = client.get("/fail").cookie(Cookie::new("session", "abc")).dispatch();
+        assert_eq!(response.status(), Status::Unauthorized);
+        assert_eq!(response.headers().get_one("X-Committed"), Some("handler"));
+        assert_eq!(set_cookies(&response), vec!["catcher=1", "session="]);
+    }
+
+    #[test]
+    fn error_cookies_keeps_handler_cookies() {
+        let client = client(true);
+        let response = client.get("/fail").cookie(Cookie::new("session", "abc")).dispatch();
+        assert_eq!(set_cookies(&response), vec!["catcher=1", "session=", "visited=1"]);
+    }
+
+    #[test]
+    fn committed_parts_reach_successful_responses() {
+        let client = client(false);
+        let response = client.get("/ok").dispatch();
+        assert_eq!(response.headers().get_one("X-Committed"), Some("ok"));
+        assert_eq!(response.into_string().unwrap(), "ok");
+    }
+}
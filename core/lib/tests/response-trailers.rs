@@ -0,0 +1,78 @@
+#[macro_use] extern crate rocket;
+
+use std::io::Cursor;
+use std::sync::Mutex;
+
+use rocket::{Config, Request, Response};
+use rocket::fairing::AdHoc;
+use rocket::http::{Header, HeaderMap};
+use rocket::response::{self, Responder};
+use rocket::tokio::sync::oneshot;
+
+use hyper::body::HttpBody;
+
+struct Counted(&'static str);
+
+impl<'r> Responder<'r, 'static> for Counted {
+    fn respond_to(self, _: &'r Request<'_>) -> response::Result<'static> {
+        Response::build()
+            .sized_body(self.0.len(), Cursor::new(self.0))
+            .trailers(vec!["X-Length"], async move {
+                let mut trailers = HeaderMap::new();
+                trailers.add(Header::new("X-Length", self.0.len().to_string()));
+                trailers
+            })
+            .ok()
+    }
+}
+
+#[get("/")]
+fn counted() -> Counted {
+    Counted("hello")
+}
+
+#[rocket::async_test]
+async fn trailers_are_sent_over_h2c() {
+    let (tx, rx) = oneshot::channel();
+    let tx = Mutex::new(Some(tx));
+    let config = Config { port: 0, ctrlc: false, h2c: true, ..Config::debug_default() };
+    let rocket = rocket::custom(config)
+        .mount("/", routes![counted])
+        .attach(AdHoc::on_launch("Port", move |rocket| {
+            let port = rocket.config().port;
+            let _ = tx.lock().unwrap().take().unwrap().send((port, rocket.shutdown()));
+        }));
+
+    let server = rocket::tokio::spawn(rocket.launch());
+    let (port, shutdown) = rx.await.unwrap();
+
+    let client = hyper::Client::builder().http2_only(true).build_http::<hyper::Body>();
+    let uri = format!("http://127.0.0.1:{}/", port).parse().unwrap();
+    let mut response = client.get(uri).await.unwrap();
+    assert_eq!(response.headers()["trailer"], "X-Length");
+
+    let body = response.body_mut();
+    let mut data = vec![];
+    while let Some(chunk) = body.data().await {
+        data.extend_from_slice(&chunk.unwrap());
+    }
+
+    assert_eq!(data, b"hello");
+    let trailers = body.trailers().await.unwrap().expect("trailers");
+    assert_eq!(trailers["x-length"], "5");
+
+    shutdown.shutdown();
+    server.await.unwrap().unwrap();
+}
+
+#[test]
+fn head_responses_have_no_trailers() {
+    use rocket::local::blocking::Client;
+
+    let client = Client::tracked(rocket::ignite().mount("/", routes![counted])).unwrap();
+    let response = client.get("/").dispatch();
+    assert_eq!(response.headers().get_one("Trailer"), Some("X-Length"));
+
+    let response = client.head("/").dispatch();
+    assert!(response.headers().get_one("Trailer").is_none());
+}
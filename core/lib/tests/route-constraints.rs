@@ -0,0 +1,72 @@
+#[macro_use] extern crate rocket;
+
+#[get("/users/<id:int>")]
+fn user(id: i64) -> String {
+    format!("user {}", id)
+}
+
+#[get("/users/new")]
+fn new_user() -> &'static str {
+    "new user"
+}
+
+#[get("/users/<name>", rank = 2)]
+fn user_by_name(name: String) -> String {
+    format!("user named {}", name)
+}
+
+#[get("/posts/<slug:[a-z0-9-]+>")]
+fn post(slug: String) -> String {
+    format!("post {}", slug)
+}
+
+#[get("/orders/<id:uuid>/<rest..>")]
+fn order(id: String, rest: std::path::PathBuf) -> String {
+    format!("order {} {}", id, rest.display())
+}
+
+mod route_constraint_tests {
+    use super::*;
+    use rocket::http::Status;
+    use rocket::request::Rejection;
+    use rocket::local::blocking::Client;
+
+    fn client() -> Client {
+        let routes = routes![user, new_user, user_by_name, post, order];
+        Client::tracked(rocket::ignite().mount("/", routes)).unwrap()
+    }
+
+    #[test]
+    fn constraints_select_routes() {
+        let client = client();
+        let body = |uri: &'static str| client.get(uri).dispatch().into_string().unwrap();
+        assert_eq!(body("/users/12"), "user 12");
+        assert_eq!(body("/users/-3"), "user -3");
+        assert_eq!(body("/users/new"), "new user");
+        assert_eq!(body("/users/bob"), "user named bob");
+        assert_eq!(body("/users/12a"), "user named 12a");
+        assert_eq!(body("/posts/hello-world-2"), "post hello-world-2");
+        assert_eq!(body("/orders/67e55044-10b1-426f-9247-bb680e5fe0c8/items/1"),
+            "order 67e55044-10b1-426f-9247-bb680e5fe0c8 items/1");
+    }
+
+    #[test]
+    fn unsatisfied_constraints_dont_match() {
+        let client = client();
+        assert_eq!(client.get("/posts/Hello").dispatch().status(), Status::NotFound);
+        assert_eq!(client.get("/posts/a%20b").dispatch().status(), Status::NotFound);
+        assert_eq!(client.get("/orders/67e55044/items").dispatch().status(), Status::NotFound);
+
+        let request = client.get("/posts/Hello");
+        let checks = request.inner().explain_routing();
+        let post = checks.iter().find(|check| check.route.ends_with("(post)")).unwrap();
+        assert_eq!(post.rejection, Some(Rejection::Path));
+    }
+
+    #[test]
+    fn constraints_are_percent_decoded() {
+        let client = client();
+        let response = client.get("/users/%31%32").dispatch();
+        assert_eq!(response.into_string().unwrap(), "user 12");
+    }
+}
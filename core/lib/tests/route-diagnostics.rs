@@ -0,0 +1,96 @@
+#[macro_use] extern crate rocket;
+
+use rocket::{Config, RouteStats};
+use rocket::local::blocking::Client;
+use rocket::request::Rejection;
+use rocket::http::ContentType;
+
+#[get("/thing/<_id>", rank = 1)]
+fn small(_id: u8) -> &'static str {
+    "small"
+}
+
+#[get("/thing/<_id>", rank = 2)]
+fn any(_id: usize) -> &'static str {
+    "any"
+}
+
+#[get("/thing/static")]
+fn fixed() -> &'static str {
+    "static"
+}
+
+#[get("/thing?<q>&flag")]
+fn flagged(q: String) -> String {
+    q
+}
+
+#[post("/thing/<_id>", format = "json")]
+fn json(_id: usize) { }
+
+fn client(trace: bool) -> Client {
+    let config = Config { trace, ..Config::debug_default() };
+    let rocket = rocket::custom(config)
+        .mount("/", routes![small, any, fixed, flagged, json]);
+
+    Client::tracked(rocket).unwrap()
+}
+
+fn stats(client: &Client, name: &str) -> RouteStats {
+    client.rocket().routes()
+        .find(|route| route.name == Some(name))
+        .map(|route| route.stats())
+        .unwrap()
+}
+
+#[test]
+fn routes_count_attempts_and_wins() {
+    let client = client(true);
+    assert_eq!(client.get("/thing/2").dispatch().into_string().unwrap(), "small");
+    assert_eq!(client.get("/thing/300").dispatch().into_string().unwrap(), "any");
+    assert_eq!(client.get("/thing/500").dispatch().into_string().unwrap(), "any");
+
+    assert_eq!(stats(&client, "small"), RouteStats { attempts: 3, wins: 1 });
+    assert_eq!(stats(&client, "any"), RouteStats { attempts: 2, wins: 2 });
+    assert_eq!(stats(&client, "fixed"), RouteStats { attempts: 0, wins: 0 });
+}
+
+#[test]
+fn routes_are_not_counted_without_trace() {
+    let client = client(false);
+    client.get("/thing/2").dispatch();
+    assert_eq!(stats(&client, "small"), RouteStats::default());
+}
+
+#[test]
+fn routing_is_explained() {
+    let client = client(false);
+    let request = client.get("/thing/10");
+    let checks = request.inner().explain_routing();
+    let rejection = |route: &str| checks.iter()
+        .find(|check| check.route.ends_with(&format!("({})", route)))
+        .map(|check| check.rejection)
+        .unwrap();
+
+    assert_eq!(rejection("small"), None);
+    assert_eq!(rejection("any"), None);
+    assert_eq!(rejection("fixed"), Some(Rejection::Path));
+    assert_eq!(rejection("flagged"), Some(Rejection::Path));
+    assert_eq!(rejection("json"), Some(Rejection::Method));
+
+    // Matching routes come first, in the order they're tried.
+    assert!(checks[0].route.ends_with("(small)"));
+    assert!(checks[1].route.ends_with("(any)"));
+    assert!(checks[2..].iter().all(|check| check.rejection.is_some()));
+
+    let request = client.get("/thing?q=hi");
+    let checks = request.inner().explain_routing();
+    let flagged = checks.iter().find(|check| check.route.ends_with("(flagged)")).unwrap();
+    assert_eq!(flagged.rejection, Some(Rejection::Query));
+    assert_eq!(flagged.to_string(), "GET /thing?<q>&flag (flagged): query mismatch");
+
+    let request = client.post("/thing/1").header(ContentType::HTML);
+    let checks = request.inner().explain_routing();
+    let json = checks.iter().find(|check| check.route.ends_with("(json)")).unwrap();
+    assert_eq!(json.rejection, Some(Rejection::Format));
+}
@@ -0,0 +1,79 @@
+#[macro_use] extern crate rocket;
+
+use rocket::{Request, Route};
+use rocket::handler::{HandlerFuture, Outcome};
+use rocket::http::Method;
+
+fn has_api_key(req: &Request<'_>) -> bool {
+    req.headers().contains("X-Api-Key")
+}
+
+#[get("/", matcher = has_api_key, rank = 1)]
+fn keyed() -> &'static str {
+    "keyed"
+}
+
+#[get("/", rank = 2)]
+fn anonymous() -> &'static str {
+    "anonymous"
+}
+
+fn beta<'r>(req: &'r Request<'_>, _: rocket::Data) -> HandlerFuture<'r> {
+    Outcome::from(req, "beta").pin()
+}
+
+mod route_matcher_tests {
+    use super::*;
+    use rocket::http::Header;
+    use rocket::request::Rejection;
+    use rocket::local::blocking::Client;
+
+    fn client() -> Client {
+        let cohort = Route::ranked(0, Method::Get, "/", beta)
+            .with_matcher(|req| req.headers().get_one("X-Cohort") == Some("beta"));
+
+        let rocket = rocket::ignite()
+            .mount("/", routes![keyed, anonymous])
+            .mount("/", vec![cohort]);
+
+        Client::tracked(rocket).unwrap()
+    }
+
+    #[test]
+    fn matchers_select_routes() {
+        let client = client();
+        assert_eq!(client.get("/").dispatch().into_string().unwrap(), "anonymous");
+
+        let response = client.get("/").header(Header::new("X-Api-Key", "123")).dispatch();
+        assert_eq!(response.into_string().unwrap(), "keyed");
+
+        let response = client.get("/")
+            .header(Header::new("X-Api-Key", "123"))
+            .header(Header::new("X-Cohort", "beta"))
+            .dispatch();
+
+        assert_eq!(response.into_string().unwrap(), "beta");
+    }
+
+    #[test]
+    fn matcher_rejections_are_explained() {
+        let client = client();
+        let request = client.get("/");
+        let checks = request.inner().explain_routing();
+        let keyed = checks.iter().find(|check| check.route.ends_with("(keyed)")).unwrap();
+        assert_eq!(keyed.rejection, Some(Rejection::Matcher));
+
+        let anonymous = checks.iter().find(|check| check.route.ends_with("(anonymous)")).unwrap();
+        assert_eq!(anonymous.rejection, None);
+    }
+
+    #[test]
+    fn builder_sets_matcher() {
+        let route = Route::builder(Method::Get, "/", beta)
+            .matcher(|_| false)
+            .build();
+
+        assert!(route.matcher.is_some());
+        assert!(Route::new(Method::Get, "/", beta).matcher.is_none());
+    }
+}
@@ -0,0 +1,73 @@
+#[macro_use] extern crate rocket;
+
+use rocket::http::Status;
+
+#[get("/new/<id>")]
+fn new(id: usize) -> String {
+    id.to_string()
+}
+
+mod route_redirect_tests {
+    use super::*;
+    use rocket::error::ErrorKind;
+    use rocket::local::blocking::Client;
+
+    fn client() -> Client {
+        let rocket = rocket::ignite()
+            .mount("/", routes![new])
+            .redirect("/old/<id>", "/new/<id>", Status::PermanentRedirect)
+            .redirect("/docs/<v>/<page..>", "/v<v>/guide/<page..>?legacy=1",
+                Status::MovedPermanently);
+
+        Client::tracked(rocket).unwrap()
+    }
+
+    #[test]
+    fn redirects_substitute_segments() {
+        let client = client();
+        let response = client.get("/old/42").dispatch();
+        assert_eq!(response.status(), Status::PermanentRedirect);
+        assert_eq!(response.headers().get_one("Location"), Some("/new/42"));
+
+        let response = client.get("/docs/4/requests/forms").dispatch();
+        assert_eq!(response.status(), Status::MovedPermanently);
+        assert_eq!(response.headers().get_one("Location"),
+            Some("/v4/guide/requests/forms?legacy=1"));
+    }
+
+    #[test]
+    fn redirects_keep_query_without_target_query() {
+        let client = client();
+        let response = client.get("/old/7?page=2").dispatch();
+        assert_eq!(response.headers().get_one("Location"), Some("/new/7?page=2"));
+
+        let response = client.get("/docs/4/index?page=2").dispatch();
+        assert_eq!(response.headers().get_one("Location"), Some("/v4/guide/index?legacy=1"));
+    }
+
+    #[rocket::async_test]
+    async fn redirects_collide_with_routes() {
+        let result = rocket::ignite()
+            .mount("/", routes![new])
+            .redirect("/new/<id>", "/newer/<id>", Status::Found)
+            .ignited()
+            .await;
+
+        match result {
+            Err(e) => assert!(matches!(e.kind(), ErrorKind::Collision(..))),
+            Ok(_) => panic!("ignited with a colliding redirect"),
+        }
+    }
+
+    #[test]
+    #[should_panic]
+    fn unknown_target_segment_panics() {
+        let _ = rocket::ignite().redirect("/old/<id>", "/new/<name>", Status::Found);
+    }
+
+    #[test]
+    #[should_panic]
+    fn non_redirect_status_panics() {
+        let _ = rocket::ignite().redirect("/old", "/new", Status::Ok);
+    }
+}
@@ -0,0 +1,144 @@
+#[macro_use] extern crate rocket;
+
+use rocket::Config;
+use rocket::response::Redirect;
+
+#[get("/")]
+fn redirect() -> Redirect {
+    Redirect::to(uri!(target))
+}
+
+#[get("/target")]
+fn target() -> &'static str {
+    "target"
+}
+
+#[cfg(feature = "secrets")]
+#[get("/cookie")]
+fn cookie(jar: &rocket::http::CookieJar<'_>) {
+    jar.add_private(rocket::http::Cookie::new("name", "value"));
+}
+
+#[get("/public")]
+fn public(jar: &rocket::http::CookieJar<'_>) {
+    use rocket::http::{Cookie, SameSite};
+
+    jar.add(Cookie::new("default", "value"));
+    jar.add(Cookie::build("explicit", "value").secure(false).same_site(SameSite::Lax).finish());
+}
+
+mod secure_defaults_tests {
+    use super::*;
+    use rocket::local::blocking::Client;
+    use rocket::http::Header;
+
+    fn client(config: Config) -> Client {
+        #[cfg(feature = "secrets")] let routes = routes![redirect, target, cookie, public];
+        #[cfg(not(feature = "secrets"))] let routes = routes![redirect, target, public];
+        Client::tracked(rocket::custom(config).mount("/", routes)).unwrap()
+    }
+
+    #[test]
+    fn redirect_is_relative_without_trusted_proxy() {
+        let client = client(Config::debug_default());
+        let response = client.get("/")
+            .header(Header::new("X-Forwarded-Proto", "https"))
+            .header(Header::new("Host", "rocket.rs"))
+            .dispatch();
+
+        assert_eq!(response.headers().get_one("Location"), Some("/target"));
+    }
+
+    #[test]
+    fn redirect_is_absolute_with_trusted_proxy() {
+        let config = Config { trust_proxy_headers: true, ..Config::debug_default() };
+        let client = client(config);
+
+        let response = client.get("/")
+            .header(Header::new("X-Forwarded-Proto", "https, http"))
+            .header(Header::new("Host", "rocket.rs"))
+            .dispatch();
+
+        assert_eq!(response.headers().get_one("Location"), Some("https://rocket.rs/target"));
+
+        let response = client.get("/")
+            .header(Header::new("X-Forwarded-Proto", "https"))
+            .header(Header::new("X-Forwarded-Host", "example.com:8443"))
+            .header(Header::new("Host", "internal"))
+            .dispatch();
+
+        let location = response.headers().get_one("Location");
+        assert_eq!(location, Some("https://example.com:8443/target"));
+
+        let response = client.get("/")
+            .header(Header::new("X-Forwarded-Proto", "http"))
+            .header(Header::new("Host", "rocket.rs"))
+            .dispatch();
+
+        assert_eq!(response.headers().get_one("Location"), Some("/target"));
+
+        let response = client.get("/")
+            .header(Header::new("X-Forwarded-Proto", "https"))
+            .header(Header::new("Host", "evil.com/path"))
+            .dispatch();
+
+        assert_eq!(response.headers().get_one("Location"), Some("/target"));
+    }
+
+    #[test]
+    #[cfg(feature = "secrets")]
+    fn private_cookies_are_secure_when_configured() {
+        let config = Config { cookie_secure: true, ..Config::debug_default() };
+        let response = client(config).get("/cookie").dispatch();
+        let cookie = response.cookies().get("name").unwrap();
+        assert_eq!(cookie.secure(), Some(true));
+        assert_eq!(cookie.http_only(), Some(true));
+
+        let response = client(Config::debug_default()).get("/cookie").dispatch();
+        let cookie = response.cookies().get("name").unwrap();
+        assert_eq!(cookie.secure(), None);
+    }
+
+    #[test]
+    fn cookie_defaults_follow_config() {
+        use rocket::http::SameSite;
+
+        let config = Config {
+            cookie_secure: true,
+            cookie_same_site: SameSite::None,
+            ..Config::debug_default()
+        };
+
+        let response = client(config).get("/public").dispatch();
+        let cookie = response.cookies().get("default").unwrap();
+        assert_eq!(cookie.secure(), Some(true));
+        assert_eq!(cookie.same_site(), Some(SameSite::None));
+
+        let cookie = response.cookies().get("explicit").unwrap();
+        assert_eq!(cookie.secure(), Some(false));
+        assert_eq!(cookie.same_site(), Some(SameSite::Lax));
+
+        let response = client(Config::debug_default()).get("/public").dispatch();
+        let cookie = response.cookies().get("default").unwrap();
+        assert_eq!(cookie.secure(), None);
+        assert_eq!(cookie.same_site(), Some(SameSite::Strict));
+
+        let response = client(Config::release_default()).get("/public").dispatch();
+        let cookie = response.cookies().get("default").unwrap();
+        assert_eq!(cookie.secure(), None);
+    }
+
+    #[test]
+    fn same_site_none_cookies_are_secure() {
+        use rocket::http::SameSite;
+
+        let config = Config { cookie_same_site: SameSite::None, ..Config::debug_default() };
+        let response = client(config).get("/public").dispatch();
+        let cookie = response.cookies().get("default").unwrap();
+        assert_eq!(cookie.secure(), Some(true));
+        assert_eq!(cookie.same_site(), Some(SameSite::None));
+
+        let cookie = response.cookies().get("explicit").unwrap();
+        assert_eq!(cookie.secure(), Some(false));
+    }
+}
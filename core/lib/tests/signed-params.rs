@@ -0,0 +1,80 @@
+#[macro_use]
+#[cfg(feature = "secrets")]
+extern crate rocket;
+
+#[cfg(feature = "secrets")]
+mod signed_params_tests {
+    use rocket::config::SecretKey;
+    use rocket::request::Signed;
+    use rocket::local::blocking::Client;
+    use rocket::http::Status;
+
+    #[get("/unsubscribe/<id>")]
+    fn unsubscribe(id: Signed<u64>) -> String {
+        format!("unsubscribed {}", *id)
+    }
+
+    #[get("/maybe/<name>")]
+    fn maybe(name: Option<Signed<String>>) -> String {
+        name.map(|name| name.into_inner()).unwrap_or_else(|| "nobody".into())
+    }
+
+    #[get("/link/<id>")]
+    fn link(id: u64, key: &SecretKey) -> String {
+        uri!(unsubscribe: Signed::new(id, key)).to_string()
+    }
+
+    #[get("/name/<name>")]
+    fn name_link(name: String, key: &SecretKey) -> String {
+        uri!(maybe: Signed::new(name, key)).to_string()
+    }
+
+    fn client() -> Client {
+        let rocket = rocket::ignite().mount("/", routes![unsubscribe, maybe, link, name_link]);
+        Client::untracked(rocket).unwrap()
+    }
+
+    #[test]
+    fn signed_param_round_trips() {
+        let client = client();
+        let link = client.get("/link/42").dispatch().into_string().unwrap();
+        assert!(link.starts_with("/unsubscribe/42."));
+
+        // An unpadded, URL-safe base64 HMAC-SHA256.
+        let signature = &link["/unsubscribe/42.".len()..];
+        assert_eq!(signature.len(), 43);
+        assert!(signature.bytes().all(|b| b.is_ascii_alphanumeric() || b == b'-' || b == b'_'));
+
+        let response = client.get(link).dispatch();
+        assert_eq!(response.status(), Status::Ok);
+        assert_eq!(response.into_string().unwrap(), "unsubscribed 42");
+
+        let link = client.get("/name/Bob%20Smith").dispatch().into_string().unwrap();
+        let response = client.get(link).dispatch();
+        assert_eq!(response.into_string().unwrap(), "Bob Smith");
+    }
+
+    #[test]
+    fn tampered_param_is_forbidden() {
+        let client = client();
+        let link = client.get("/link/42").dispatch().into_string().unwrap();
+        let signature = &link["/unsubscribe/42.".len()..];
+
+        let response = client.get(format!("/unsubscribe/43.{}", signature)).dispatch();
+        assert_eq!(response.status(), Status::Forbidden);
+
+        let response = client.get("/unsubscribe/42").dispatch();
+        assert_eq!(response.status(), Status::Forbidden);
+
+        let response = client.get("/unsubscribe/42.bogus").dispatch();
+        assert_eq!(response.status(), Status::Forbidden);
+    }
+
+    #[test]
+    fn optional_signed_param_is_none_when_tampered() {
+        let client = client();
+        let response = client.get("/maybe/Bob.bogus").dispatch();
+        assert_eq!(response.status(), Status::Ok);
+        assert_eq!(response.into_string().unwrap(), "nobody");
+    }
+}
@@ -0,0 +1,52 @@
+#[macro_use] extern crate rocket;
+
+use std::io;
+use std::path::PathBuf;
+
+use rocket::response::TempFile;
+
+fn temp_path(name: &str) -> PathBuf {
+    std::env::temp_dir().join(format!("rocket-temp-file-{}-{}", std::process::id(), name))
+}
+
+#[get("/<name>")]
+async fn report(name: String) -> io::Result<TempFile> {
+    let path = temp_path(&name);
+    rocket::tokio::fs::write(&path, format!("report {}", name)).await?;
+    TempFile::open(path).await
+}
+
+mod temp_file_tests {
+    use super::*;
+    use rocket::local::blocking::Client;
+    use rocket::http::{ContentType, Status};
+
+    #[test]
+    fn temp_file_is_deleted_after_response() {
+        let client = Client::tracked(rocket::ignite().mount("/", routes![report])).unwrap();
+        let response = client.get("/a.txt").dispatch();
+        assert_eq!(response.status(), Status::Ok);
+        assert_eq!(response.content_type(), Some(ContentType::Plain));
+        assert_eq!(response.into_string().unwrap(), "report a.txt");
+        assert!(!temp_path("a.txt").exists());
+    }
+
+    #[test]
+    fn temp_file_is_deleted_when_unread() {
+        let client = Client::tracked(rocket::ignite().mount("/", routes![report])).unwrap();
+        let response = client.head("/b.txt").dispatch();
+        assert_eq!(response.status(), Status::Ok);
+        drop(response);
+        assert!(!temp_path("b.txt").exists());
+    }
+
+    #[rocket::async_test]
+    async fn temp_file_is_deleted_when_dropped() {
+        let path = temp_path("c.txt");
+        rocket::tokio::fs::write(&path, "unused").await.unwrap();
+        let file = TempFile::open(&path).await.unwrap();
+        assert_eq!(file.path(), path.as_path());
+        drop(file);
+        assert!(!path.exists());
+    }
+}
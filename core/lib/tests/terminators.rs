@@ -0,0 +1,47 @@
+use std::sync::{Arc, Mutex};
+
+use rocket::{Config, Terminator};
+use rocket::fairing::AdHoc;
+
+type Log = Arc<Mutex<Vec<&'static str>>>;
+
+struct Pool(Log);
+
+struct Scheduler(Log);
+
+#[rocket::async_trait]
+impl Terminator for Pool {
+    async fn terminate(&self) {
+        self.0.lock().unwrap().push("pool");
+    }
+}
+
+#[rocket::async_trait]
+impl Terminator for Scheduler {
+    async fn terminate(&self) {
+        self.0.lock().unwrap().push("scheduler");
+    }
+}
+
+#[rocket::async_test]
+async fn terminators_run_in_reverse_order_on_shutdown() {
+    let log = Log::default();
+    let config = Config { port: 0, ctrlc: false, ..Config::debug_default() };
+    let result = rocket::custom(config)
+        .manage_terminable(Pool(log.clone()))
+        .manage_terminable(Scheduler(log.clone()))
+        .attach(AdHoc::on_launch("Shutdown", |rocket| rocket.shutdown().shutdown()))
+        .launch()
+        .await;
+
+    assert!(result.is_ok());
+    assert_eq!(*log.lock().unwrap(), vec!["scheduler", "pool"]);
+}
+
+#[test]
+fn terminable_state_is_managed() {
+    let log = Log::default();
+    let rocket = rocket::ignite().manage_terminable(Pool(log.clone()));
+    assert!(rocket.state::<Pool>().is_some());
+    assert!(log.lock().unwrap().is_empty());
+}
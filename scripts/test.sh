@@ -82,10 +82,28 @@ if [ "$1" = "--contrib" ]; then
   FEATURES=(
     json
     msgpack
+    protobuf
+    csv_export
+    process
+    archive
+    feed
+    calendar
     tera_templates
     handlebars_templates
     serve
+    well_known
+    webdav
     helmet
+    response_cache
+    cors
+    csrf
+    csp
+    canary
+    audit
+    timing
+    schema
+    locale
+    acme
     diesel_postgres_pool
     diesel_sqlite_pool
     diesel_mysql_pool
@@ -112,6 +130,9 @@ elif [ "$1" = "--core" ]; then
   FEATURES=(
     secrets
     tls
+    decompression
+    quiet
+    tracing
   )
 
   pushd "${CORE_LIB_ROOT}" > /dev/null 2>&1